@@ -0,0 +1,529 @@
+//! Approval gates for pushes (vqx extension)
+//!
+//! `sync push --require-approval` does not rely on the person (or CI job)
+//! running it. It computes a hash of the push plan (target and the exact
+//! files to import) and either:
+//! - checks an `--approval-token` for that hash, printed by `vqx approve` on
+//!   another machine, or
+//! - writes an approval request to the approvals directory and polls until
+//!   someone else runs `vqx approve <hash>` (or `--reject`) against the same
+//!   directory, or `[approval] timeout_seconds` passes.
+//!
+//! Approvers sign their decisions with an Ed25519 private key
+//! (`[approval] signing_key` or `VQX_APPROVAL_KEY`, created with
+//! `vqx approve --generate-key`). The token is that signature, and the
+//! decision file carries it too. The pushing side only holds the public keys
+//! (`[approval.trusted_keys]`) and refuses to wait for an approval when it
+//! can read one of the matching private keys, so a push can never approve
+//! itself. Unsigned or forged decision files are ignored.
+//!
+//! The approvals directory is `<config dir>/approvals/` unless
+//! `[approval] dir` points elsewhere, e.g. to storage shared by CI and
+//! reviewers.
+
+use crate::config::{ApprovalConfig, Config};
+use crate::error::{Result, VqxError};
+use crate::profile::Profile;
+use chrono::{DateTime, Utc};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Subdirectory of the config directory holding approval files
+pub const APPROVAL_DIR: &str = "approvals";
+
+/// Environment variable with the path of the approver's private key
+pub const KEY_ENV: &str = "VQX_APPROVAL_KEY";
+
+/// Hex digits of a plan hash
+const HASH_LEN: usize = 16;
+
+/// Interval between checks while waiting for an approval
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash identifying a push: target server, namespace and file contents
+///
+/// `files` are relative to `dir`; any change to them or to the target gives
+/// a different hash, so an approval only covers what the approver saw.
+pub fn plan_hash(
+    profile_name: &str,
+    profile: &Profile,
+    dir: &Path,
+    files: &[PathBuf],
+) -> Result<String> {
+    let mut files: Vec<&PathBuf> = files.iter().collect();
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for part in [
+        profile_name,
+        &profile.url,
+        profile.namespace.as_deref().unwrap_or(""),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    for rel in files {
+        let content = fs::read(dir.join(rel)).map_err(|_| VqxError::FileReadFailed {
+            path: dir.join(rel).display().to_string(),
+        })?;
+        hasher.update(rel.to_string_lossy().replace('\\', "/").as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(&content));
+    }
+    Ok(hex(&hasher.finalize())[..HASH_LEN].to_string())
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// What an approver signs for a decision on a plan
+fn signed_message(hash: &str, approved: bool) -> Vec<u8> {
+    let verdict = if approved { "approve" } else { "reject" };
+    format!("vqx-approval-v1\0{}\0{}", verdict, hash).into_bytes()
+}
+
+/// An approver's Ed25519 private key
+pub struct SigningKey(Ed25519KeyPair);
+
+impl SigningKey {
+    /// A new key pair, as the content of a key file (hex PKCS#8)
+    pub fn generate() -> Result<String> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| VqxError::Other("Failed to generate an approval key".to_string()))?;
+        Ok(hex(pkcs8.as_ref()))
+    }
+
+    /// Parse the content of a key file
+    pub fn from_hex(content: &str) -> Result<Self> {
+        unhex(content.trim())
+            .and_then(|pkcs8| Ed25519KeyPair::from_pkcs8(&pkcs8).ok())
+            .map(Self)
+            .ok_or_else(|| VqxError::Other("Not a vqx approval key".to_string()))
+    }
+
+    /// The key from `[approval] signing_key` or [`KEY_ENV`], if either is set
+    pub fn load(config: &ApprovalConfig) -> Result<Option<Self>> {
+        let path = match config.signing_key {
+            Some(ref path) => PathBuf::from(path),
+            None => match std::env::var(KEY_ENV).ok().filter(|p| !p.is_empty()) {
+                Some(path) => PathBuf::from(path),
+                None => return Ok(None),
+            },
+        };
+        let content = fs::read_to_string(&path).map_err(|_| VqxError::FileReadFailed {
+            path: path.display().to_string(),
+        })?;
+        Self::from_hex(&content).map(Some)
+    }
+
+    /// Public key for `[approval.trusted_keys]` on the pushing side (hex)
+    pub fn public_key(&self) -> String {
+        hex(self.0.public_key().as_ref())
+    }
+
+    /// Signature of a decision on `hash` (hex); an approval's is its token
+    pub fn sign(&self, hash: &str, approved: bool) -> String {
+        hex(self.0.sign(&signed_message(hash, approved)).as_ref())
+    }
+}
+
+/// Name of the trusted key that made `signature` for a decision on `hash`
+pub fn verify(
+    trusted: &BTreeMap<String, String>,
+    hash: &str,
+    approved: bool,
+    signature: &str,
+) -> Option<String> {
+    let signature = unhex(signature.trim())?;
+    let message = signed_message(hash, approved);
+    trusted
+        .iter()
+        .find(|(_, key)| {
+            unhex(key.trim()).is_some_and(|key| {
+                UnparsedPublicKey::new(&ED25519, key)
+                    .verify(&message, &signature)
+                    .is_ok()
+            })
+        })
+        .map(|(name, _)| name.clone())
+}
+
+/// Name of the approver whose `--approval-token` approves the plan `hash`
+pub fn verify_token(trusted: &BTreeMap<String, String>, hash: &str, token: &str) -> Option<String> {
+    verify(trusted, hash, true, token)
+}
+
+/// Fail unless approvals for pushes from this machine must come from someone else
+///
+/// Approvals are checked against `[approval.trusted_keys]`. A machine that
+/// can read one of the matching private keys could approve its own pushes,
+/// so it may not push with `--require-approval`.
+pub fn check_pusher(config: &ApprovalConfig) -> Result<()> {
+    if config.trusted_keys.is_empty() {
+        return Err(VqxError::ApprovalRejected {
+            message: "no [approval.trusted_keys] are configured to verify approvals".to_string(),
+        });
+    }
+    if let Some(key) = SigningKey::load(config)? {
+        let public_key = key.public_key();
+        if let Some((name, _)) = config
+            .trusted_keys
+            .iter()
+            .find(|(_, k)| k.trim().eq_ignore_ascii_case(&public_key))
+        {
+            return Err(VqxError::ApprovalRejected {
+                message: format!(
+                    "this machine holds the approval key of '{}'; push from where no approver key is available",
+                    name
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Name of the user running vqx
+pub fn current_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+/// A push waiting for approval
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ApprovalRequest {
+    pub hash: String,
+    /// vqx command asking for approval (e.g. "sync push")
+    pub operation: String,
+    pub profile: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    pub directory: PathBuf,
+    /// Files to import, relative to the directory
+    pub files: Vec<String>,
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// Decision on a request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Decision {
+    pub hash: String,
+    pub approved: bool,
+    /// User who ran `vqx approve` (informational; the signature is what counts)
+    pub decided_by: String,
+    pub decided_at: DateTime<Utc>,
+    /// Ed25519 signature of the decision by the approver's key (hex)
+    #[serde(default)]
+    pub signature: String,
+}
+
+/// Approval requests and decisions in a directory
+pub struct ApprovalStore {
+    dir: PathBuf,
+    timeout: Duration,
+}
+
+impl ApprovalStore {
+    /// Store in the configured (or default) directory
+    pub fn new(config: &Config) -> Result<Self> {
+        let ApprovalConfig {
+            dir,
+            timeout_seconds,
+            ..
+        } = &config.approval;
+        let dir = match dir {
+            Some(dir) => PathBuf::from(dir),
+            None => Config::config_dir()?.join(APPROVAL_DIR),
+        };
+        Ok(Self::in_dir(dir, Duration::from_secs(*timeout_seconds)))
+    }
+
+    pub fn in_dir(dir: impl Into<PathBuf>, timeout: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            timeout,
+        }
+    }
+
+    fn request_path(&self, hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.request.json", hash))
+    }
+
+    fn decision_path(&self, hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.decision.json", hash))
+    }
+
+    fn write<T: Serialize>(&self, path: &Path, value: &T) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|_| VqxError::FileWriteFailed {
+            path: self.dir.display().to_string(),
+        })?;
+        fs::write(path, serde_json::to_string_pretty(value)? + "\n").map_err(|_| {
+            VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            }
+        })
+    }
+
+    /// Record a request; an earlier decision on the same plan is kept
+    pub fn request(&self, request: &ApprovalRequest) -> Result<()> {
+        self.write(&self.request_path(&request.hash), request)
+    }
+
+    /// All requests, oldest first
+    fn requests(&self) -> Vec<ApprovalRequest> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut requests: Vec<ApprovalRequest> = entries
+            .flatten()
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".request.json"))
+            .filter_map(|e| serde_json::from_str(&fs::read_to_string(e.path()).ok()?).ok())
+            .collect();
+        requests.sort_by_key(|r| r.requested_at);
+        requests
+    }
+
+    /// Requests without a decision, oldest first
+    pub fn pending(&self) -> Vec<ApprovalRequest> {
+        self.requests()
+            .into_iter()
+            .filter(|r| self.decision(&r.hash).is_none())
+            .collect()
+    }
+
+    /// The `limit` most recent requests with their decisions, newest first
+    pub fn recent(&self, limit: usize) -> Vec<(ApprovalRequest, Option<Decision>)> {
+        self.requests()
+            .into_iter()
+            .rev()
+            .take(limit)
+            .map(|r| {
+                let decision = self.decision(&r.hash);
+                (r, decision)
+            })
+            .collect()
+    }
+
+    /// The request for a hash or unique hash prefix
+    pub fn find(&self, hash: &str) -> Result<ApprovalRequest> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Err(VqxError::Other(format!("No approval request {}", hash)));
+        };
+        let matches: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .is_some_and(|n| n.starts_with(hash) && n.ends_with(".request.json"))
+            })
+            .collect();
+        match matches.as_slice() {
+            [path] => Ok(serde_json::from_str(&fs::read_to_string(path)?)?),
+            [] => Err(VqxError::Other(format!("No approval request {}", hash))),
+            _ => Err(VqxError::Other(format!(
+                "Approval hash {} is ambiguous; give more characters",
+                hash
+            ))),
+        }
+    }
+
+    pub fn decision(&self, hash: &str) -> Option<Decision> {
+        serde_json::from_str(&fs::read_to_string(self.decision_path(hash)).ok()?).ok()
+    }
+
+    /// Approve or reject a request as `user`, signed with `key`
+    pub fn decide(
+        &self,
+        request: &ApprovalRequest,
+        approved: bool,
+        user: &str,
+        key: &SigningKey,
+    ) -> Result<Decision> {
+        let decision = Decision {
+            hash: request.hash.clone(),
+            approved,
+            decided_by: user.to_string(),
+            decided_at: Utc::now(),
+            signature: key.sign(&request.hash, approved),
+        };
+        self.write(&self.decision_path(&request.hash), &decision)?;
+        Ok(decision)
+    }
+
+    /// Wait for a decision signed by one of the `trusted` keys
+    ///
+    /// Returns the name of the key that approved the plan.
+    pub async fn wait(&self, hash: &str, trusted: &BTreeMap<String, String>) -> Result<String> {
+        let deadline = Instant::now() + self.timeout;
+        let mut warned = false;
+        loop {
+            let decision = self.decision(hash);
+            let signer = decision
+                .as_ref()
+                .and_then(|d| verify(trusted, hash, d.approved, &d.signature));
+            match (decision, signer) {
+                (Some(d), Some(name)) if d.approved => return Ok(name),
+                (Some(_), Some(name)) => {
+                    return Err(VqxError::ApprovalRejected {
+                        message: format!("Plan {} was rejected by {}", hash, name),
+                    })
+                }
+                (Some(_), None) if !warned => {
+                    warn!(hash = %hash, "Ignoring a decision that is not signed by a trusted key");
+                    warned = true;
+                }
+                _ => {}
+            }
+            if Instant::now() >= deadline {
+                return Err(VqxError::ApprovalTimeout {
+                    hash: hash.to_string(),
+                    seconds: self.timeout.as_secs(),
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn trusted(name: &str, key: &SigningKey) -> BTreeMap<String, String> {
+        BTreeMap::from([(name.to_string(), key.public_key())])
+    }
+
+    #[test]
+    fn test_tokens() {
+        let key = SigningKey::from_hex(&SigningKey::generate().unwrap()).unwrap();
+        let trusted = trusted("alice", &key);
+        let token = key.sign("0123456789abcdef", true);
+        assert_eq!(
+            verify_token(&trusted, "0123456789abcdef", &token).as_deref(),
+            Some("alice")
+        );
+        // Read from a file or pasted with a trailing newline
+        assert!(verify_token(&trusted, "0123456789abcdef", &format!("{}\n", token)).is_some());
+        assert!(verify_token(&trusted, "fedcba9876543210", &token).is_none());
+        assert!(verify_token(&trusted, "0123456789abcdef", "abc").is_none());
+
+        // A rejection does not approve, and other keys are not trusted
+        let rejection = key.sign("0123456789abcdef", false);
+        assert!(verify_token(&trusted, "0123456789abcdef", &rejection).is_none());
+        let other = SigningKey::from_hex(&SigningKey::generate().unwrap()).unwrap();
+        let forged = other.sign("0123456789abcdef", true);
+        assert!(verify_token(&trusted, "0123456789abcdef", &forged).is_none());
+    }
+
+    #[test]
+    fn test_check_pusher() {
+        let dir = TempDir::new().unwrap();
+        let key_file = dir.path().join("approver.key");
+        fs::write(&key_file, SigningKey::generate().unwrap()).unwrap();
+        let key = SigningKey::from_hex(&fs::read_to_string(&key_file).unwrap()).unwrap();
+
+        let mut config = ApprovalConfig::default();
+        assert!(check_pusher(&config).is_err());
+        config.trusted_keys = trusted("alice", &key);
+        config.signing_key = Some("/nonexistent/key".to_string());
+        assert!(check_pusher(&config).is_err());
+
+        // The pushing side must not hold a trusted private key
+        config.signing_key = Some(key_file.display().to_string());
+        let err = check_pusher(&config).unwrap_err();
+        assert!(err.to_string().contains("'alice'"));
+
+        let other = dir.path().join("other.key");
+        fs::write(&other, SigningKey::generate().unwrap()).unwrap();
+        config.signing_key = Some(other.display().to_string());
+        assert!(check_pusher(&config).is_ok());
+    }
+
+    #[test]
+    fn test_plan_hash() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("types")).unwrap();
+        fs::write(dir.path().join("types/Order.json"), "{}").unwrap();
+        let files = vec![PathBuf::from("types/Order.json")];
+        let prod = Profile::new("https://prod.vantiq.com");
+
+        let hash = plan_hash("prod", &prod, dir.path(), &files).unwrap();
+        assert_eq!(hash.len(), HASH_LEN);
+        assert_eq!(hash, plan_hash("prod", &prod, dir.path(), &files).unwrap());
+
+        let other = Profile::new("https://prod.vantiq.com").with_namespace("orders");
+        assert_ne!(hash, plan_hash("prod", &other, dir.path(), &files).unwrap());
+        fs::write(dir.path().join("types/Order.json"), "{\"a\":1}").unwrap();
+        assert_ne!(hash, plan_hash("prod", &prod, dir.path(), &files).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_request_and_decide() {
+        let dir = TempDir::new().unwrap();
+        let store = ApprovalStore::in_dir(dir.path(), Duration::ZERO);
+        let request = ApprovalRequest {
+            hash: "0123456789abcdef".to_string(),
+            operation: "sync push".to_string(),
+            profile: "prod".to_string(),
+            url: "https://prod.vantiq.com".to_string(),
+            namespace: None,
+            directory: PathBuf::from("./export"),
+            files: vec!["types/Order.json".to_string()],
+            requested_by: "ci".to_string(),
+            requested_at: Utc::now(),
+        };
+        store.request(&request).unwrap();
+        assert_eq!(store.pending().len(), 1);
+        assert_eq!(store.find("0123").unwrap().profile, "prod");
+        let alice = SigningKey::from_hex(&SigningKey::generate().unwrap()).unwrap();
+        let trusted = trusted("alice", &alice);
+        assert!(matches!(
+            store.wait(&request.hash, &trusted).await,
+            Err(VqxError::ApprovalTimeout { .. })
+        ));
+
+        // A decision file written without a trusted key is ignored
+        let mallory = SigningKey::from_hex(&SigningKey::generate().unwrap()).unwrap();
+        store.decide(&request, true, "alice", &mallory).unwrap();
+        assert!(matches!(
+            store.wait(&request.hash, &trusted).await,
+            Err(VqxError::ApprovalTimeout { .. })
+        ));
+
+        store.decide(&request, true, "alice", &alice).unwrap();
+        assert!(store.pending().is_empty());
+        assert_eq!(store.wait(&request.hash, &trusted).await.unwrap(), "alice");
+        let recent = store.recent(5);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].1.as_ref().unwrap().decided_by, "alice");
+
+        store.decide(&request, false, "alice", &alice).unwrap();
+        assert!(matches!(
+            store.wait(&request.hash, &trusted).await,
+            Err(VqxError::ApprovalRejected { .. })
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+//! Backend selection (vqx extension)
+//!
+//! [`Executor`] runs the record and resource operations shared by both
+//! backends (list, find, select, insert, upsert, delete) through the REST
+//! API when the profile has `backend = "rest"`, and through the CLI
+//! otherwise. Operations the REST client does not cover, such as the
+//! checked insert variants, fall back to the CLI.
+
+use crate::error::Result;
+use crate::profile::{Backend, Profile, ProfileManager};
+#[cfg(feature = "rest-backend")]
+use crate::rest::RestClient;
+use crate::underlying::{CliOptions, ExecResult, UnderlyingCli};
+
+/// Resolve the profile selected with `--profile`, if any
+pub fn resolve_profile(profile_name: Option<&str>) -> Result<Option<Profile>> {
+    match profile_name {
+        Some(name) => Ok(Some(ProfileManager::new()?.get_resolved(name)?)),
+        None => Ok(None),
+    }
+}
+
+/// CLI options for an optional profile
+pub fn cli_options(profile: Option<&Profile>) -> CliOptions {
+    profile.map(CliOptions::from_profile).unwrap_or_default()
+}
+
+/// Runs operations through the profile's backend
+pub struct Executor {
+    cli: UnderlyingCli,
+    #[cfg(feature = "rest-backend")]
+    rest: Option<RestClient>,
+}
+
+impl Executor {
+    /// Create an executor; `profile` decides the backend
+    ///
+    /// Without a profile, or when the profile cannot be served over REST,
+    /// everything goes through `cli`.
+    pub fn new(cli: UnderlyingCli, profile: Option<&Profile>) -> Result<Self> {
+        let wants_rest = profile.is_some_and(|p| p.backend == Backend::Rest);
+
+        #[cfg(feature = "rest-backend")]
+        {
+            let rest = match profile {
+                Some(profile) if wants_rest => RestClient::from_profile(profile, cli.timeout())?,
+                _ => None,
+            };
+            Ok(Self { cli, rest })
+        }
+
+        #[cfg(not(feature = "rest-backend"))]
+        {
+            if wants_rest {
+                tracing::warn!("vqx was built without the rest-backend feature, using the CLI");
+            }
+            Ok(Self { cli })
+        }
+    }
+
+    /// The CLI, for operations only it supports
+    pub fn cli(&self) -> &UnderlyingCli {
+        &self.cli
+    }
+
+    #[cfg(feature = "rest-backend")]
+    fn rest(&self) -> Option<&RestClient> {
+        self.rest.as_ref()
+    }
+
+    /// Whether operations go through the REST API
+    #[cfg(test)]
+    fn is_rest(&self) -> bool {
+        #[cfg(feature = "rest-backend")]
+        {
+            self.rest.is_some()
+        }
+        #[cfg(not(feature = "rest-backend"))]
+        {
+            false
+        }
+    }
+
+    /// `list <resource>`
+    pub async fn list(&self, options: &CliOptions, resource: &str) -> Result<ExecResult> {
+        #[cfg(feature = "rest-backend")]
+        if let Some(rest) = self.rest() {
+            check_policy(options, "list", &[resource])?;
+            return rest.list(resource).await;
+        }
+        self.cli.list(options, resource).await
+    }
+
+    /// `find <resource> <id>`
+    pub async fn find(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        resource_id: &str,
+    ) -> Result<ExecResult> {
+        #[cfg(feature = "rest-backend")]
+        if let Some(rest) = self.rest() {
+            check_policy(options, "find", &[resource, resource_id])?;
+            return rest.find(resource, resource_id).await;
+        }
+        self.cli.find(options, resource, resource_id).await
+    }
+
+    /// `select`, with the same arguments as [`UnderlyingCli::select`]
+    ///
+    /// Over REST, `chunk_size` is ignored: the API returns all records, or
+    /// `limit` of them.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn select(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        resource_id: Option<&str>,
+        qual_file: Option<&str>,
+        props: Option<&str>,
+        chunk_size: Option<u32>,
+        limit: Option<usize>,
+    ) -> Result<ExecResult> {
+        #[cfg(feature = "rest-backend")]
+        if let Some(rest) = self.rest() {
+            check_policy(options, "select", &[resource])?;
+            if let Some(id) = resource_id {
+                return rest.find(resource, id).await;
+            }
+            let qual = qual_file.map(qual_json).transpose()?;
+            let props = props.map(props_json).transpose()?;
+            return rest
+                .select(resource, qual.as_deref(), props.as_deref(), limit)
+                .await;
+        }
+        self.cli
+            .select(
+                options,
+                resource,
+                resource_id,
+                qual_file,
+                props,
+                chunk_size,
+                limit,
+            )
+            .await
+    }
+
+    /// `insert` or `checkedInsert`; checked inserts always use the CLI
+    pub async fn insert(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        file: &str,
+        checked: bool,
+    ) -> Result<ExecResult> {
+        #[cfg(feature = "rest-backend")]
+        if let (Some(rest), false) = (self.rest(), checked) {
+            check_policy(options, "insert", &[resource, file])?;
+            return rest.write(resource, &read_file(file)?, false).await;
+        }
+        self.cli.insert(options, resource, file, checked).await
+    }
+
+    /// `upsert` or `checkedUpsert`; checked upserts always use the CLI
+    pub async fn upsert(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        file: &str,
+        checked: bool,
+    ) -> Result<ExecResult> {
+        #[cfg(feature = "rest-backend")]
+        if let (Some(rest), false) = (self.rest(), checked) {
+            check_policy(options, "upsert", &[resource, file])?;
+            return rest.write(resource, &read_file(file)?, true).await;
+        }
+        self.cli.upsert(options, resource, file, checked).await
+    }
+
+    /// `delete <resource> <id>`
+    pub async fn delete(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        resource_id: &str,
+    ) -> Result<ExecResult> {
+        #[cfg(feature = "rest-backend")]
+        if let Some(rest) = self.rest() {
+            check_policy(options, "delete", &[resource, resource_id])?;
+            return rest.delete(resource, resource_id).await;
+        }
+        self.cli.delete(options, resource, resource_id).await
+    }
+
+    /// `deleteMatching <resource> <query>`
+    pub async fn delete_matching(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        query: &str,
+    ) -> Result<ExecResult> {
+        #[cfg(feature = "rest-backend")]
+        if let Some(rest) = self.rest() {
+            check_policy(options, "deleteMatching", &[resource, query])?;
+            return rest.delete_matching(resource, query).await;
+        }
+        self.cli.delete_matching(options, resource, query).await
+    }
+}
+
+/// Check a REST call against `[policy]`, as the CLI does for its calls
+#[cfg(feature = "rest-backend")]
+fn check_policy(options: &CliOptions, command: &str, args: &[&str]) -> Result<()> {
+    let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+    crate::policy::check(options.profile.as_deref(), command, &args)
+}
+
+#[cfg(feature = "rest-backend")]
+fn read_file(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|_| crate::error::VqxError::FileReadFailed {
+        path: path.to_string(),
+    })
+}
+
+/// Query for the REST API
+///
+/// Like the CLI's `-qual`, the value is a file or the JSON query itself.
+#[cfg(feature = "rest-backend")]
+fn qual_json(qual: &str) -> Result<String> {
+    if std::path::Path::new(qual).is_file() {
+        return read_file(qual);
+    }
+    Ok(qual.to_string())
+}
+
+/// Property list as the JSON array the REST API expects
+///
+/// Like the CLI's `-props`, the value is a file (holding a JSON array) or
+/// a comma-separated list.
+#[cfg(feature = "rest-backend")]
+fn props_json(props: &str) -> Result<String> {
+    if std::path::Path::new(props).is_file() {
+        return read_file(props);
+    }
+    let names: Vec<&str> = props
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+    Ok(serde_json::to_string(&names)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_executor_defaults_to_cli() {
+        let cli = UnderlyingCli::new("vantiq".to_string());
+        assert!(!Executor::new(cli, None).unwrap().is_rest());
+
+        let profile = Profile::new("https://dev.vantiq.com").with_token("t");
+        let cli = UnderlyingCli::new("vantiq".to_string());
+        assert!(!Executor::new(cli, Some(&profile)).unwrap().is_rest());
+    }
+
+    #[cfg(feature = "rest-backend")]
+    #[test]
+    fn test_executor_rest() {
+        let mut profile = Profile::new("https://dev.vantiq.com").with_token("t");
+        profile.backend = Backend::Rest;
+        let cli = UnderlyingCli::new("vantiq".to_string());
+        assert!(Executor::new(cli, Some(&profile)).unwrap().is_rest());
+
+        assert_eq!(
+            props_json("name, ars_version").unwrap(),
+            r#"["name","ars_version"]"#
+        );
+    }
+}
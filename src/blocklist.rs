@@ -0,0 +1,148 @@
+//! Resource name block list for import and sync push (vqx extension)
+//!
+//! An import overwrites whatever the directory contains, including system
+//! resources that were exported along with the application. [`scan`] flags
+//! the resources whose names match the `[import]` prefixes in config.toml;
+//! import and sync push refuse to send them without `--force`, and list them
+//! before the confirmation when forced.
+
+use crate::config::ImportConfig;
+use crate::error::{Result, VqxError};
+use crate::manifest;
+use crate::theme::style;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// A resource matched by the block list
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct BlockedResource {
+    pub resource_type: String,
+    pub name: String,
+}
+
+impl BlockedResource {
+    /// `<resource_type>/<name>`
+    pub fn id(&self) -> String {
+        crate::report::resource_id(&self.resource_type, &self.name)
+    }
+}
+
+/// Blocked resources among the JSON and VAIL files below `dir`
+pub fn scan(dir: &Path, config: &ImportConfig) -> Vec<BlockedResource> {
+    let files: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(dir).ok().map(Path::to_path_buf))
+        .collect();
+    scan_files(files, config)
+}
+
+/// Blocked resources among files relative to an export directory
+///
+/// The resource type is the top-level directory and the name is the file
+/// stem; extracted code (`.vail`) and its definition count once.
+pub fn scan_files<P: AsRef<Path>>(
+    files: impl IntoIterator<Item = P>,
+    config: &ImportConfig,
+) -> Vec<BlockedResource> {
+    let mut blocked = BTreeSet::new();
+    for rel in files {
+        let rel = rel.as_ref();
+        let ext = rel.extension().and_then(|e| e.to_str());
+        if rel.components().count() < 2
+            || manifest::is_hidden_path(rel)
+            || !matches!(ext, Some("json") | Some("vail"))
+        {
+            continue;
+        }
+        let resource_type = rel
+            .components()
+            .next()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default();
+        let name = rel
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if config.is_blocked(&name) {
+            blocked.insert(BlockedResource {
+                resource_type,
+                name,
+            });
+        }
+    }
+    blocked.into_iter().collect()
+}
+
+/// Fail unless there is nothing blocked or `--force` was given
+pub fn check(blocked: &[BlockedResource], force: bool) -> Result<()> {
+    if blocked.is_empty() || force {
+        return Ok(());
+    }
+    Err(VqxError::ResourcesBlocked {
+        count: blocked.len(),
+        names: blocked
+            .iter()
+            .map(BlockedResource::id)
+            .collect::<Vec<_>>()
+            .join(", "),
+    })
+}
+
+/// List blocked resources included with `--force`
+pub fn display(blocked: &[BlockedResource]) {
+    if blocked.is_empty() {
+        return;
+    }
+    println!(
+        "{}",
+        style(format!(
+            "⚠  Including {} blocked resource(s) (--force):",
+            blocked.len()
+        ))
+        .yellow()
+    );
+    for resource in blocked {
+        println!("   {}", style(resource.id()).yellow());
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_files() {
+        let config = ImportConfig::default();
+        let files: Vec<PathBuf> = [
+            "types/Order.json",
+            "types/SystemUser.json",
+            "procedures/SystemUtil.json",
+            "procedures/SystemUtil.vail",
+            "_hidden/types/SystemHidden.json",
+            "README.md",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+
+        let blocked = scan_files(&files, &config);
+        let ids: Vec<String> = blocked.iter().map(BlockedResource::id).collect();
+        assert_eq!(ids, vec!["procedures/SystemUtil", "types/SystemUser"]);
+
+        assert!(check(&blocked, true).is_ok());
+        let err = check(&blocked, false).unwrap_err();
+        assert!(err.to_string().contains("types/SystemUser"));
+
+        let config = ImportConfig {
+            allowed_prefixes: vec!["Order".to_string()],
+            blocked_prefixes: vec![],
+        };
+        let blocked = scan_files(&files, &config);
+        assert_eq!(blocked.len(), 2);
+        assert!(check(&[], false).is_ok());
+    }
+}
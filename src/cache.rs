@@ -0,0 +1,266 @@
+//! Export cache (vqx extension)
+//!
+//! `diff` and `sync push` compare against a full metadata export of the
+//! server. The normalized export is kept per profile and namespace under
+//! `<data dir>/vqx/cache/exports/` and reused until it is older than
+//! `[cache] ttl_seconds`, so repeated diffs against an unchanged server do
+//! not export again. `--refresh` bypasses the cache, and commands that
+//! import into a namespace drop its entry. `sync push` never reads the
+//! cache when deciding what to push; it only stores its fresh export.
+//!
+//! An entry is only reused for the same server URL and normalization
+//! settings it was exported with.
+
+use crate::config::{Config, NormalizationConfig};
+use crate::error::{Result, VqxError};
+use crate::lock;
+use crate::profile::Profile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::TempDir;
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// Entry metadata file, next to the snapshot directory
+const ENTRY_FILE: &str = "entry.json";
+
+/// Subdirectory of an entry holding the normalized export
+const SNAPSHOT_DIR: &str = "snapshot";
+
+/// Directory holding cached exports
+pub fn cache_root() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("vqx")
+        .join("cache")
+        .join("exports")
+}
+
+/// Metadata of a cached export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    profile: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+    url: String,
+    exported_at: DateTime<Utc>,
+    /// Normalization settings the snapshot was written with
+    normalization: NormalizationConfig,
+}
+
+/// A cached, normalized export
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub exported_at: DateTime<Utc>,
+}
+
+impl Snapshot {
+    /// Age for display, e.g. `42s` or `3m`
+    pub fn age(&self) -> String {
+        let secs = (Utc::now() - self.exported_at).num_seconds().max(0);
+        if secs < 60 {
+            format!("{}s", secs)
+        } else {
+            format!("{}m", secs / 60)
+        }
+    }
+}
+
+/// Cached exports per profile and namespace
+pub struct ExportCache {
+    root: PathBuf,
+    ttl: Duration,
+}
+
+impl ExportCache {
+    /// Cache in the default location with the configured TTL
+    pub fn new(config: &Config) -> Self {
+        Self::in_dir(cache_root(), config.cache.ttl())
+    }
+
+    /// Cache in `root`; a zero `ttl` disables it
+    pub fn in_dir(root: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            root: root.into(),
+            ttl,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// A fresh snapshot for a profile, if there is one
+    pub fn lookup(
+        &self,
+        name: &str,
+        profile: &Profile,
+        normalization: &NormalizationConfig,
+    ) -> Option<Snapshot> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let dir = self.entry_dir(name, profile.namespace.as_deref());
+        let entry: Entry =
+            serde_json::from_str(&fs::read_to_string(dir.join(ENTRY_FILE)).ok()?).ok()?;
+
+        let age = (Utc::now() - entry.exported_at)
+            .to_std()
+            .unwrap_or_default();
+        let matches = entry.url == profile.url
+            && serde_json::to_value(&entry.normalization).ok()
+                == serde_json::to_value(normalization).ok();
+        if !matches || age > self.ttl {
+            debug!(profile = name, ?age, matches, "Cached export not usable");
+            return None;
+        }
+
+        let path = dir.join(SNAPSHOT_DIR);
+        path.is_dir().then_some(Snapshot {
+            path,
+            exported_at: entry.exported_at,
+        })
+    }
+
+    /// Store a normalized export, replacing the profile's previous entry
+    pub fn store(
+        &self,
+        name: &str,
+        profile: &Profile,
+        normalization: &NormalizationConfig,
+        export_dir: &Path,
+    ) -> Result<Snapshot> {
+        fs::create_dir_all(&self.root).map_err(|_| VqxError::FileWriteFailed {
+            path: self.root.display().to_string(),
+        })?;
+
+        // Fill a staging directory next to the entry and swap it in, so a
+        // concurrent lookup never sees a half-copied snapshot
+        let staging = TempDir::new_in(&self.root)?;
+        copy_tree(export_dir, &staging.path().join(SNAPSHOT_DIR))?;
+        let entry = Entry {
+            profile: name.to_string(),
+            namespace: profile.namespace.clone(),
+            url: profile.url.clone(),
+            exported_at: Utc::now(),
+            normalization: normalization.clone(),
+        };
+        fs::write(
+            staging.path().join(ENTRY_FILE),
+            serde_json::to_string_pretty(&entry)?,
+        )?;
+
+        let dir = self.entry_dir(name, profile.namespace.as_deref());
+        let _ = fs::remove_dir_all(&dir);
+        fs::rename(staging.keep(), &dir).map_err(|_| VqxError::FileWriteFailed {
+            path: dir.display().to_string(),
+        })?;
+        debug!(profile = name, path = %dir.display(), "Cached export");
+
+        Ok(Snapshot {
+            path: dir.join(SNAPSHOT_DIR),
+            exported_at: entry.exported_at,
+        })
+    }
+
+    /// Drop the entry for a profile and namespace (after changing the server)
+    pub fn invalidate(&self, name: &str, namespace: Option<&str>) {
+        let dir = self.entry_dir(name, namespace);
+        if dir.exists() {
+            if let Err(e) = fs::remove_dir_all(&dir) {
+                warn!(path = %dir.display(), error = %e, "Could not remove cached export");
+            }
+        }
+    }
+
+    fn entry_dir(&self, name: &str, namespace: Option<&str>) -> PathBuf {
+        self.root.join(lock::target_key(name, namespace))
+    }
+}
+
+/// Copy a directory tree
+fn copy_tree(from: &Path, to: &Path) -> Result<()> {
+    for entry in WalkDir::new(from).into_iter().filter_map(|e| e.ok()) {
+        let rel = entry.path().strip_prefix(from).unwrap_or(entry.path());
+        let dest = to.join(rel);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&dest)?;
+        } else {
+            fs::copy(entry.path(), &dest).map_err(|_| VqxError::FileWriteFailed {
+                path: dest.display().to_string(),
+            })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export_dir() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("types")).unwrap();
+        fs::write(dir.path().join("types/Order.json"), "{}").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_store_and_lookup() {
+        let root = TempDir::new().unwrap();
+        let cache = ExportCache::in_dir(root.path(), Duration::from_secs(300));
+        let profile = Profile::new("https://dev.vantiq.com").with_namespace("orders");
+        let normalization = NormalizationConfig::default();
+
+        assert!(cache.lookup("dev", &profile, &normalization).is_none());
+        cache
+            .store("dev", &profile, &normalization, export_dir().path())
+            .unwrap();
+
+        let snapshot = cache.lookup("dev", &profile, &normalization).unwrap();
+        assert!(snapshot.path.join("types/Order.json").is_file());
+        assert!(root.path().join("dev@orders").is_dir());
+
+        // Other namespace, server or normalization settings: no hit
+        let other = Profile::new("https://dev.vantiq.com");
+        assert!(cache.lookup("dev", &other, &normalization).is_none());
+        let other = Profile::new("https://prod.vantiq.com").with_namespace("orders");
+        assert!(cache.lookup("dev", &other, &normalization).is_none());
+        let extract = NormalizationConfig {
+            extract_code: true,
+            ..Default::default()
+        };
+        assert!(cache.lookup("dev", &profile, &extract).is_none());
+
+        cache.invalidate("dev", Some("orders"));
+        assert!(cache.lookup("dev", &profile, &normalization).is_none());
+    }
+
+    #[test]
+    fn test_ttl() {
+        let root = TempDir::new().unwrap();
+        let profile = Profile::new("https://dev.vantiq.com");
+        let normalization = NormalizationConfig::default();
+
+        let disabled = ExportCache::in_dir(root.path(), Duration::ZERO);
+        assert!(!disabled.is_enabled());
+        disabled
+            .store("dev", &profile, &normalization, export_dir().path())
+            .unwrap();
+        assert!(disabled.lookup("dev", &profile, &normalization).is_none());
+
+        // Backdate the entry past the TTL
+        let cache = ExportCache::in_dir(root.path(), Duration::from_secs(60));
+        let entry_path = root.path().join("dev").join(ENTRY_FILE);
+        let mut entry: Entry =
+            serde_json::from_str(&fs::read_to_string(&entry_path).unwrap()).unwrap();
+        assert!(cache.lookup("dev", &profile, &normalization).is_some());
+        entry.exported_at = Utc::now() - chrono::Duration::seconds(120);
+        fs::write(&entry_path, serde_json::to_string(&entry).unwrap()).unwrap();
+        assert!(cache.lookup("dev", &profile, &normalization).is_none());
+    }
+}
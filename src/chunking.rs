@@ -0,0 +1,338 @@
+//! Chunk size auto-tuning (`--chunk auto`, vqx extension)
+//!
+//! One chunk size rarely suits every type: tiny types fit into a single
+//! chunk, while types with large records time out at the default. With
+//! `--chunk auto`, export and import make one CLI call per resource type,
+//! starting with the size learned for that type (or `default_chunk_size`).
+//! The chunk and record counts in the streamed output, together with the
+//! elapsed time, give the time per chunk; the next size aims at
+//! `[chunking] target_seconds`. A failed call is retried with half the size.
+//!
+//! Learned sizes are kept per profile and command in
+//! `~/.local/share/vqx/chunks.json`.
+
+use crate::config::{ChunkingConfig, Config};
+use crate::error::{Result, VqxError};
+use crate::progress::{self, ProgressLine};
+use crate::theme::style;
+use crate::underlying::{
+    CliOptions, ExecResult, StallPolicy, StreamControl, StreamEvent, UnderlyingCli,
+};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Calls per type at most; each retry halves the chunk size
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Value of `--chunk`: a fixed size or `auto`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkSize {
+    Fixed(u32),
+    Auto,
+}
+
+impl ChunkSize {
+    /// The fixed size, if not `auto`
+    pub fn fixed(self) -> Option<u32> {
+        match self {
+            ChunkSize::Fixed(size) => Some(size),
+            ChunkSize::Auto => None,
+        }
+    }
+}
+
+impl FromStr for ChunkSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(ChunkSize::Auto);
+        }
+        match s.parse::<u32>() {
+            Ok(size) if size > 0 => Ok(ChunkSize::Fixed(size)),
+            _ => Err(format!("expected a positive number or 'auto', got '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for ChunkSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChunkSize::Fixed(size) => write!(f, "{}", size),
+            ChunkSize::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// Chunk size used for one resource type, and the one chosen for next time
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ChunkChoice {
+    pub resource: String,
+    /// Size of the last call
+    pub chunk: u32,
+    /// Chunks of the last call, as reported or estimated from record counts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunks: Option<u64>,
+    pub seconds: f64,
+    pub attempts: u32,
+    pub success: bool,
+    /// Size the next run starts with
+    pub next: u32,
+}
+
+/// Learned sizes: profile -> command -> resource type -> size
+type TunedSizes = BTreeMap<String, BTreeMap<String, BTreeMap<String, u32>>>;
+
+/// Chooses chunk sizes per resource type for one export or import
+pub struct ChunkTuner {
+    path: PathBuf,
+    stored: TunedSizes,
+    profile: String,
+    command: String,
+    initial: u32,
+    limits: ChunkingConfig,
+    policy: StallPolicy,
+    choices: Vec<ChunkChoice>,
+}
+
+impl ChunkTuner {
+    /// Tuner for `command` ("export" or "import") against a profile
+    pub fn new(config: &Config, profile: &str, command: &str) -> Self {
+        let path = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("vqx")
+            .join("chunks.json");
+        Self::at(path, config, profile, command)
+    }
+
+    fn at(path: PathBuf, config: &Config, profile: &str, command: &str) -> Self {
+        let stored = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            stored,
+            profile: profile.to_string(),
+            command: command.to_string(),
+            initial: config.default_chunk_size,
+            limits: config.chunking.clone(),
+            policy: config.stall_policy(),
+            choices: Vec::new(),
+        }
+    }
+
+    /// Size to start a resource type with
+    pub fn size_for(&self, resource: &str) -> u32 {
+        self.stored
+            .get(&self.profile)
+            .and_then(|commands| commands.get(&self.command))
+            .and_then(|sizes| sizes.get(resource))
+            .copied()
+            .map_or_else(|| self.clamp(self.initial), |size| self.clamp(size))
+    }
+
+    /// Size to use after a call with `chunk` took `elapsed` for `chunks` chunks
+    fn next_size(&self, chunk: u32, chunks: Option<u64>, elapsed: Duration, success: bool) -> u32 {
+        if !success {
+            return self.clamp(chunk / 2);
+        }
+        let chunks = chunks.unwrap_or(1).max(1);
+        let per_chunk = elapsed.as_secs_f64() / chunks as f64;
+        let target = self.limits.target_seconds.max(1) as f64;
+        // Everything fit into one quick chunk; a larger size changes nothing
+        if chunks == 1 && per_chunk <= target {
+            return self.clamp(chunk);
+        }
+        let factor = (target / per_chunk.max(0.001)).clamp(0.25, 4.0);
+        self.clamp(round_size(chunk as f64 * factor))
+    }
+
+    fn clamp(&self, size: u32) -> u32 {
+        let min = self.limits.min_size.max(1);
+        size.clamp(min, self.limits.max_size.max(min))
+    }
+
+    /// Run a CLI call for one resource type, retrying failures with smaller chunks
+    ///
+    /// `args` builds the arguments for a chunk size; `cleanup` removes the
+    /// output of a failed attempt before the retry.
+    pub async fn run(
+        &mut self,
+        resource: &str,
+        cli: &UnderlyingCli,
+        options: &CliOptions,
+        args: &dyn Fn(u32) -> Vec<OsString>,
+        on_line: &mut dyn FnMut(&str),
+        cleanup: &dyn Fn() -> Result<()>,
+    ) -> Result<ExecResult> {
+        let mut chunk = self.size_for(resource);
+        let mut attempts = 1;
+        loop {
+            let started = Instant::now();
+            let mut reported = None;
+            let mut records: Option<u64> = None;
+            let result = cli
+                .execute_streaming(options, &self.command, args(chunk), self.policy, |event| {
+                    if let StreamEvent::Line { text, .. } = event {
+                        match progress::parse_line(text) {
+                            Some(ProgressLine::Chunk { total, .. }) => reported = Some(total),
+                            Some(ProgressLine::Position { total, .. }) => records = Some(total),
+                            Some(ProgressLine::Processed { count }) => {
+                                *records.get_or_insert(0) += count
+                            }
+                            None => {}
+                        }
+                        on_line(text);
+                    }
+                    StreamControl::Continue
+                })
+                .await?;
+            let elapsed = started.elapsed();
+            let chunks = reported.or(records.map(|r| r.div_ceil(chunk as u64).max(1)));
+            let mut next = self.next_size(chunk, chunks, elapsed, result.success());
+            // Larger sizes failed in this run; do not go back to them
+            if attempts > 1 {
+                next = next.min(chunk);
+            }
+
+            if result.success() || attempts >= MAX_ATTEMPTS || next >= chunk {
+                self.stored
+                    .entry(self.profile.clone())
+                    .or_default()
+                    .entry(self.command.clone())
+                    .or_default()
+                    .insert(resource.to_string(), next);
+                self.choices.push(ChunkChoice {
+                    resource: resource.to_string(),
+                    chunk,
+                    chunks,
+                    seconds: (elapsed.as_secs_f64() * 10.0).round() / 10.0,
+                    attempts,
+                    success: result.success(),
+                    next,
+                });
+                return Ok(result);
+            }
+
+            info!(
+                resource,
+                chunk, next, "Call failed; retrying with a smaller chunk"
+            );
+            cleanup()?;
+            chunk = next;
+            attempts += 1;
+        }
+    }
+
+    /// Save the learned sizes and return the choices made
+    pub fn finish(self) -> Result<Vec<ChunkChoice>> {
+        let write_failed = || VqxError::FileWriteFailed {
+            path: self.path.display().to_string(),
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|_| write_failed())?;
+        }
+        fs::write(
+            &self.path,
+            serde_json::to_string_pretty(&self.stored)? + "\n",
+        )
+        .map_err(|_| write_failed())?;
+        Ok(self.choices)
+    }
+}
+
+/// Round to two significant digits, so learned sizes stay readable
+fn round_size(size: f64) -> u32 {
+    let size = size.max(1.0);
+    let unit = 10f64.powi((size.log10().floor() as i32 - 1).max(0));
+    ((size / unit).round() * unit) as u32
+}
+
+/// Print the chunk size chosen for each type
+pub fn display(choices: &[ChunkChoice]) {
+    if choices.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", style("Chunk sizes (--chunk auto):").bold());
+    let width = choices.iter().map(|c| c.resource.len()).max().unwrap_or(0);
+    for choice in choices {
+        let chunks = choice
+            .chunks
+            .map_or_else(String::new, |n| format!("{} chunk(s), ", n));
+        let retries = match choice.attempts {
+            1 => String::new(),
+            n => format!(", {} attempts", n),
+        };
+        let line = format!(
+            "  {:<width$}  {:>6} → next {:<6} ({}{:.1}s{})",
+            choice.resource,
+            choice.chunk,
+            choice.next,
+            chunks,
+            choice.seconds,
+            retries,
+            width = width
+        );
+        if choice.success {
+            println!("{}", line);
+        } else {
+            println!("{}", style(line).red());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_size_parse() {
+        assert_eq!("auto".parse::<ChunkSize>(), Ok(ChunkSize::Auto));
+        assert_eq!("500".parse::<ChunkSize>(), Ok(ChunkSize::Fixed(500)));
+        assert!("0".parse::<ChunkSize>().is_err());
+        assert!("big".parse::<ChunkSize>().is_err());
+        assert_eq!(ChunkSize::Fixed(500).to_string(), "500");
+    }
+
+    #[test]
+    fn test_next_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = Config::default();
+        config.chunking.target_seconds = 10;
+        let mut tuner = ChunkTuner::at(dir.path().join("chunks.json"), &config, "dev", "export");
+        let secs = Duration::from_secs;
+
+        // 4 chunks of 40s each: shrink to a quarter at most
+        assert_eq!(tuner.next_size(5000, Some(4), secs(160), true), 1300);
+        // 10 chunks of 1s each: grow fourfold at most
+        assert_eq!(tuner.next_size(5000, Some(10), secs(10), true), 20000);
+        // One quick chunk: keep
+        assert_eq!(tuner.next_size(5000, None, secs(3), true), 5000);
+        // Failures halve, within the limits
+        assert_eq!(tuner.next_size(5000, Some(3), secs(3), false), 2500);
+        assert_eq!(tuner.next_size(150, None, secs(3), false), 100);
+
+        tuner
+            .stored
+            .entry("dev".into())
+            .or_default()
+            .entry("export".into())
+            .or_default()
+            .insert("Order".into(), 800);
+        tuner.finish().unwrap();
+        let tuner = ChunkTuner::at(dir.path().join("chunks.json"), &config, "dev", "export");
+        assert_eq!(tuner.size_for("Order"), 800);
+        assert_eq!(tuner.size_for("Customer"), 5000);
+    }
+}
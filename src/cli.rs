@@ -4,6 +4,9 @@
 //! All subcommands are designed to wrap the underlying Vantiq CLI
 //! as documented in the CLI Reference Guide PDF.
 
+use crate::chunking::ChunkSize;
+use crate::profile::{Backend, CredentialPassing, Protection};
+use crate::underlying::CommandClass;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
@@ -30,6 +33,15 @@ pub struct Cli {
     #[arg(long, global = true, env = "VQX_CONFIG")]
     pub config: Option<PathBuf>,
 
+    /// Timeout for underlying CLI commands in seconds
+    /// Overrides `timeout_seconds` and `[timeouts]` in config
+    #[arg(long, global = true, env = "VQX_TIMEOUT")]
+    pub timeout: Option<u64>,
+
+    /// Write logs to this file (overrides `[logging] file` in config)
+    #[arg(long, global = true, env = "VQX_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
+
     /// Enable verbose output
     #[arg(short, long, global = true)]
     pub verbose: bool,
@@ -38,10 +50,71 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub quiet: bool,
 
+    /// Never prompt: fail with an error naming the flag to pass instead
+    /// (prompts are also skipped when stdin is not a terminal)
+    #[arg(
+        long,
+        global = true,
+        env = "VQX_NO_INPUT",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub no_input: bool,
+
+    /// Print long output directly instead of through a pager
+    #[arg(
+        long,
+        global = true,
+        env = "VQX_NO_PAGER",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub no_pager: bool,
+
+    /// Never draw progress spinners or bars (they are also off when stderr
+    /// is not a terminal)
+    #[arg(
+        long,
+        global = true,
+        env = "VQX_NO_PROGRESS",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub no_progress: bool,
+
+    /// Never run the underlying CLI or contact a server: local commands
+    /// work, commands that need the server fail at once
+    #[arg(
+        long,
+        global = true,
+        env = "VQX_OFFLINE",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub offline: bool,
+
+    /// Print per-step timing, total duration and resource counts when done
+    /// (also added to JSON reports)
+    #[arg(long, global = true)]
+    pub summary: bool,
+
+    /// Confirm a command against a profile with `protection = "confirm"`
+    /// without typing its name
+    #[arg(long, global = true, value_name = "PROFILE")]
+    pub confirm_profile: Option<String>,
+
+    /// Exit with code 2 when `diff` finds changes (for CI gating)
+    #[arg(long, global = true)]
+    pub strict: bool,
+
     /// Output format
     #[arg(long, global = true, value_enum, default_value = "text")]
     pub output: OutputFormat,
 
+    /// Stable line-oriented output for scripts (same as `--output porcelain`)
+    #[arg(long, global = true, conflicts_with = "output")]
+    pub porcelain: bool,
+
+    /// When to use colors (auto honors NO_COLOR and `[output] colors`)
+    #[arg(long, global = true, value_enum, default_value = "auto")]
+    pub color: ColorWhen,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -49,13 +122,48 @@ pub struct Cli {
 /// Output format for command results
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
 pub enum OutputFormat {
-    /// Human-readable text output
+    /// Human-readable text output (tables where applicable)
     #[default]
+    #[value(alias = "table")]
     Text,
     /// JSON output
     Json,
+    /// YAML output (same document as JSON)
+    Yaml,
+    /// Newline-delimited JSON: one object per line (rows, diff entries, checks)
+    Ndjson,
     /// CSV output (where applicable)
     Csv,
+    /// Stable line-oriented output for scripts (diff, sync, list)
+    Porcelain,
+}
+
+impl OutputFormat {
+    /// Whether the output is meant for programs (JSON, YAML, NDJSON)
+    ///
+    /// Machine-readable formats print nothing but the result: no progress,
+    /// headers or interactive prompts.
+    pub fn is_machine_readable(&self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Json
+                | OutputFormat::Yaml
+                | OutputFormat::Ndjson
+                | OutputFormat::Porcelain
+        )
+    }
+}
+
+/// When to color output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ColorWhen {
+    /// Colors on a terminal, unless NO_COLOR is set or colors are disabled in config
+    #[default]
+    Auto,
+    /// Always use colors
+    Always,
+    /// Never use colors
+    Never,
 }
 
 /// Available subcommands
@@ -64,6 +172,13 @@ pub enum Commands {
     // =========================================================================
     // Phase 1: Core utilities
     // =========================================================================
+    /// Set up vqx and a project directory for a new user
+    ///
+    /// Locates the Vantiq CLI, verifies Java, writes config.toml, runs
+    /// `profile init` when no profile exists, and scaffolds an export
+    /// directory, a .gitignore for secrets and a .vqx.toml project file
+    Init(InitArgs),
+
     /// Check environment and CLI prerequisites
     ///
     /// Verifies:
@@ -79,6 +194,48 @@ pub enum Commands {
     #[command(subcommand)]
     Profile(ProfileCommands),
 
+    /// Manage named credentials shared by several profiles
+    #[command(subcommand)]
+    Credential(CredentialCommands),
+
+    /// Log in with username/password and use a short-lived token (vqx extension)
+    ///
+    /// For Edge servers: exchanges the profile's username/password for an
+    /// access token, which later commands pass instead of -u/-p until it expires
+    Login(LoginArgs),
+
+    /// Discard the token of `vqx login` (vqx extension)
+    Logout(LogoutArgs),
+
+    /// Read, change and check config.toml
+    ///
+    /// Keys are dotted paths into the file, e.g. `logging.level` or
+    /// `normalization.excluded_fields`
+    #[command(subcommand)]
+    Config(ConfigCommands),
+
+    /// List resources as structured rows
+    ///
+    /// Wraps PDF's "list" command with column selection, sorting and
+    /// name filtering
+    List(ListArgs),
+
+    /// Select records from a resource
+    ///
+    /// Wraps PDF's "select" command with inline qualifiers and chunked
+    /// retrieval
+    Select(SelectArgs),
+
+    /// Insert records from a file or stdin
+    ///
+    /// Wraps PDF's "insert" command (or "checkedInsert" with --checked)
+    Insert(InsertArgs),
+
+    /// Upsert records from a file or stdin
+    ///
+    /// Wraps PDF's "upsert" command (or "checkedUpsert" with --checked)
+    Upsert(InsertArgs),
+
     // =========================================================================
     // Phase 2: Export/Import (to be implemented)
     // =========================================================================
@@ -92,6 +249,17 @@ pub enum Commands {
     /// Wraps PDF's "import" command with safety checks
     Import(ImportArgs),
 
+    /// Write one resource to a file
+    ///
+    /// Wraps PDF's "dump" command; the file is normalized like exports
+    Dump(DumpArgs),
+
+    /// Create or replace one resource from a file
+    ///
+    /// Wraps PDF's "load" command with schema validation and a confirmation
+    /// before an existing resource is overwritten
+    Load(LoadArgs),
+
     // =========================================================================
     // Phase 3: Diff/Sync (to be implemented)
     // =========================================================================
@@ -113,6 +281,16 @@ pub enum Commands {
     /// - Automatic backup
     SafeDelete(SafeDeleteArgs),
 
+    /// List, restore or empty items deleted with `safe-delete --trash`
+    #[command(subcommand)]
+    Trash(TrashCommands),
+
+    /// List snapshots, or import one back into the profile it was taken of
+    ///
+    /// Snapshots are taken before import, sync push, promote and rollback
+    /// change a protected profile.
+    Rollback(RollbackArgs),
+
     /// Promote resources between environments
     ///
     /// Workflow: export -> diff -> confirm -> import -> test
@@ -124,25 +302,255 @@ pub enum Commands {
     #[command(subcommand)]
     Run(RunCommands),
 
+    /// CI helpers: ephemeral sandboxes for integration tests
+    #[command(subcommand)]
+    Ci(CiCommands),
+
+    /// Deploy a deployment configuration
+    ///
+    /// Wraps PDF's "deploy" command; `deploy plan` shows what it would change
+    Deploy(DeployCommand),
+
+    /// Undeploy a deployment configuration (with confirmation)
+    ///
+    /// Wraps PDF's "undeploy" command
+    Undeploy(DeployArgs),
+
+    // =========================================================================
+    // Tooling
+    // =========================================================================
+    /// Print JSON Schemas for vqx output formats
+    #[command(subcommand)]
+    Schema(SchemaCommands),
+
+    /// Generate the command reference from the CLI definitions
+    #[command(subcommand)]
+    Docs(DocsCommands),
+
+    /// Show an annotated guide for a command, with the PDF options it maps
+    /// to, vqx extensions and examples
+    Explain(ExplainArgs),
+
+    /// Collect a sanitized support bundle for issue reports
+    Triage(TriageArgs),
+
+    /// Serve an HTTP API that runs the operations in `[serve.operations]`
+    ///
+    /// For ChatOps and CI: clients start operations and fetch their JSON
+    /// reports with a bearer token
+    Serve(ServeArgs),
+
+    /// Run vqx commands on a cron schedule
+    ///
+    /// Jobs are stored as `[[schedule]]` entries in config.toml and run by
+    /// `vqx schedule run`
+    #[command(subcommand)]
+    Schedule(ScheduleCommands),
+
+    /// Opt in to (or out of) anonymous usage statistics
+    ///
+    /// Records which commands are used and how they fail in a local buffer;
+    /// never arguments, profiles or credentials
+    #[command(subcommand)]
+    Telemetry(TelemetryCommands),
+
+    /// Normalize the JSON files of an export directory in place
+    ///
+    /// Applies the `[normalization]` settings used by export and sync pull
+    Normalize(NormalizeArgs),
+
+    /// Check exported resources against built-in schemas
+    ///
+    /// Reports missing required fields, wrong field types and invalid enum
+    /// values with file and line, before an import fails halfway through
+    Validate(ValidateArgs),
+
+    /// Scan exported resources for embedded secrets
+    ///
+    /// Detects token-like strings, literal passwords and high-entropy
+    /// values; exits 1 when anything outside the allowlist is found
+    Scan(ScanArgs),
+
+    /// Search resource names, code and configuration for a regex
+    ///
+    /// Searches an export directory (-d) or, with --profile, the profile's
+    /// namespace (exported first, or taken from the export cache)
+    Grep(GrepArgs),
+
+    /// Summarize a namespace: resource counts and sizes per type, largest
+    /// and recently modified resources
+    ///
+    /// Works on an export directory (-d) or, with --profile, the profile's
+    /// namespace (exported first, or taken from the export cache)
+    Stats(StatsArgs),
+
+    /// Show one resource, normalized and with highlighted code
+    ///
+    /// Fetches the resource from the profile's namespace, or reads it from
+    /// an export directory (-d)
+    Show(ShowArgs),
+
+    /// Open a resource in the Vantiq web IDE
+    ///
+    /// Builds the IDE URL from the profile's base URL (see `[ide]
+    /// url_template`) and opens it in the browser
+    Open(OpenArgs),
+
+    /// Apply patches written by `vqx diff --patch`
+    #[command(subcommand)]
+    Patch(PatchCommands),
+
+    /// Render or extract `{{vars.<name>}}` placeholders with profile values
+    ///
+    /// import and sync push render placeholders with the target profile's
+    /// `vars` automatically
+    #[command(subcommand)]
+    Template(TemplateCommands),
+
+    /// Create a resource definition in an export directory
+    ///
+    /// Writes normalized JSON (and a `.vail` stub with
+    /// `normalization.extract_code`) so resources can be authored locally
+    /// and pushed
+    New(NewArgs),
+
+    /// Rename a resource in an export directory and rewrite its references
+    ///
+    /// Renames the file, updates the `name` field and rewrites references in
+    /// procedures, rules, services and clients; push the directory to apply
+    /// the rename on the server
+    Rename(RenameArgs),
+
+    /// Approve or reject a push waiting in `sync push --require-approval`
+    ///
+    /// Without a hash, lists pending requests
+    Approve(ApproveArgs),
+
     // =========================================================================
     // Direct CLI access (external subcommand)
     // =========================================================================
     /// Any unrecognized command is passed directly to the underlying Vantiq CLI
     ///
     /// Examples:
-    ///   vqx list types
     ///   vqx find procedures MyProc
-    ///   vqx select types
+    ///   vqx help
     #[command(external_subcommand)]
     External(Vec<String>),
 }
 
+impl Commands {
+    /// Whether the command modifies resources or data on the server
+    ///
+    /// Tests and procedures may change data, CI sandboxes import resources,
+    /// and `serve`/`schedule run` start whatever operations they are given.
+    pub fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            Commands::Insert(_)
+                | Commands::Upsert(_)
+                | Commands::Import(_)
+                | Commands::Load(_)
+                | Commands::Sync(SyncCommands::Push(_) | SyncCommands::Watch(_))
+                | Commands::SafeDelete(_)
+                | Commands::Trash(TrashCommands::Restore(_))
+                | Commands::Promote(_)
+                | Commands::Deploy(DeployCommand { command: None, .. })
+                | Commands::Undeploy(_)
+                | Commands::Rollback(RollbackArgs { to: Some(_), .. })
+                | Commands::Run(_)
+                | Commands::Ci(_)
+                | Commands::Serve(_)
+                | Commands::Schedule(ScheduleCommands::Run(_))
+        ) || matches!(self, Commands::External(args)
+            if args.first().is_some_and(|c| CommandClass::of(c) != CommandClass::Read))
+    }
+
+    /// Whether the command only shows what it would do
+    pub fn is_dry_run(&self) -> bool {
+        match self {
+            Commands::Sync(SyncCommands::Push(args)) => args.dry_run,
+            Commands::SafeDelete(args) => args.dry_run,
+            Commands::Import(args) => args.dry_run,
+            Commands::Export(args) => args.dry_run,
+            Commands::Deploy(DeployCommand { args, .. }) | Commands::Undeploy(args) => args.dry_run,
+            _ => false,
+        }
+    }
+
+    /// Whether the command cannot do anything without the server
+    ///
+    /// Commands that only may contact it (`diff`, `grep` and `stats` with a
+    /// profile) are refused when they try, see [`crate::offline`].
+    pub fn needs_server(&self) -> bool {
+        match self {
+            Commands::List(_)
+            | Commands::Select(_)
+            | Commands::Insert(_)
+            | Commands::Upsert(_)
+            | Commands::Dump(_)
+            | Commands::Load(_)
+            | Commands::Sync(_)
+            | Commands::SafeDelete(_)
+            | Commands::Trash(TrashCommands::Restore(_))
+            | Commands::Promote(_)
+            | Commands::Run(_)
+            | Commands::Ci(_)
+            | Commands::Login(_)
+            | Commands::Deploy(_)
+            | Commands::Undeploy(_)
+            | Commands::Rollback(RollbackArgs { to: Some(_), .. })
+            | Commands::External(_) => true,
+            Commands::Doctor(args) => args.test_connection || args.benchmark,
+            // A data export dry run lists the namespace's types
+            Commands::Export(args) => {
+                !args.dry_run
+                    || matches!(args.export_type, ExportType::Data | ExportType::ProjectData)
+            }
+            Commands::Import(args) => !args.dry_run,
+            Commands::Show(args) => args.directory.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Name of the command as typed, for messages
+    pub fn operation(&self) -> String {
+        match self {
+            Commands::Doctor(_) => "doctor",
+            Commands::List(_) => "list",
+            Commands::Select(_) => "select",
+            Commands::Insert(_) => "insert",
+            Commands::Upsert(_) => "upsert",
+            Commands::Export(_) => "export",
+            Commands::Import(_) => "import",
+            Commands::Dump(_) => "dump",
+            Commands::Load(_) => "load",
+            Commands::Sync(SyncCommands::Pull(_)) => "sync pull",
+            Commands::Sync(SyncCommands::Push(_)) => "sync push",
+            Commands::Sync(SyncCommands::Watch(_)) => "sync watch",
+            Commands::SafeDelete(_) => "safe-delete",
+            Commands::Trash(_) => "trash restore",
+            Commands::Rollback(_) => "rollback",
+            Commands::Promote(_) => "promote",
+            Commands::Run(_) => "run",
+            Commands::Ci(CiCommands::Sandbox(_)) => "ci sandbox",
+            Commands::Deploy(_) => "deploy",
+            Commands::Undeploy(_) => "undeploy",
+            Commands::Show(_) => "show",
+            Commands::Open(_) => "open",
+            Commands::Login(_) => "login",
+            Commands::External(args) => return args.first().cloned().unwrap_or_default(),
+            _ => "command",
+        }
+        .to_string()
+    }
+}
+
 // =============================================================================
 // Phase 1: Doctor
 // =============================================================================
 
 /// Arguments for the doctor command
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Default)]
 pub struct DoctorArgs {
     /// Only check Java installation
     #[arg(long)]
@@ -155,6 +563,38 @@ pub struct DoctorArgs {
     /// Test connection to the server using a profile
     #[arg(long)]
     pub test_connection: bool,
+
+    /// Try to fix the problems found, asking before each change
+    #[arg(long)]
+    pub fix: bool,
+
+    /// Apply fixes without asking (with --fix)
+    #[arg(short, long, requires = "fix")]
+    pub yes: bool,
+
+    /// Time round trips (help, list types, select) against the selected
+    /// profile and report min/avg/max latency
+    #[arg(long)]
+    pub benchmark: bool,
+
+    /// Number of timed runs per operation (with --benchmark)
+    #[arg(long, default_value = "3", requires = "benchmark")]
+    pub iterations: u32,
+}
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    /// Project directory to scaffold
+    #[arg(default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Export directory inside the project
+    #[arg(long, default_value = "export")]
+    pub export_dir: PathBuf,
+
+    /// Accept every default without asking (the profile wizard is skipped)
+    #[arg(short, long)]
+    pub yes: bool,
 }
 
 // =============================================================================
@@ -171,7 +611,7 @@ pub enum ProfileCommands {
     Show(ProfileShowArgs),
 
     /// Create or update a profile
-    Set(ProfileSetArgs),
+    Set(Box<ProfileSetArgs>),
 
     /// Delete a profile
     Delete(ProfileDeleteArgs),
@@ -182,9 +622,30 @@ pub enum ProfileCommands {
     /// Import profiles from a file
     Import(ProfileImportArgs),
 
+    /// Import profiles from the Vantiq CLI's profile file (~/.vantiq/profile)
+    ///
+    /// Copies URL, credentials and namespace, or with --link keeps only a
+    /// reference so the CLI reads the credentials from its own file
+    ImportVantiq(ProfileImportVantiqArgs),
+
     /// Export profiles to a file
     Export(ProfileExportArgs),
 
+    /// Replace a profile's token, checking the new one first (vqx extension)
+    ///
+    /// The token is prompted for or read from stdin, verified against the
+    /// server and saved where the old one was (keyring or profiles.toml)
+    Rotate(ProfileRotateArgs),
+
+    /// Encrypt profiles.toml with a passphrase (vqx extension)
+    ///
+    /// Replaces profiles.toml with profiles.toml.enc; vqx unlocks it with
+    /// VQX_PROFILES_PASSPHRASE, the OS keyring (--remember) or a prompt
+    Encrypt(ProfileEncryptArgs),
+
+    /// Turn profiles.toml.enc back into a plaintext profiles.toml (vqx extension)
+    Decrypt,
+
     /// Interactively create a new profile
     Init(ProfileInitArgs),
 }
@@ -241,9 +702,60 @@ pub struct ProfileSetArgs {
     #[arg(long)]
     pub secure: bool,
 
+    /// Connect through an HTTP proxy
+    /// PDF: "proxyOptions" in the profile's clientOptions
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "no_proxy")]
+    pub proxy: Option<String>,
+
+    /// Username for the proxy
+    #[arg(long)]
+    pub proxy_username: Option<String>,
+
+    /// Password for the proxy (kept in the keyring with --secure)
+    #[arg(long)]
+    pub proxy_password: Option<String>,
+
+    /// Remove the proxy settings
+    #[arg(long)]
+    pub no_proxy: bool,
+
     /// Description for this profile
     #[arg(long)]
     pub description: Option<String>,
+
+    /// Use a named credential (see `vqx credential`) instead of the profile's own
+    #[arg(long)]
+    pub credential: Option<String>,
+
+    /// Warn when credentials are older than this many days
+    #[arg(long)]
+    pub max_credential_age_days: Option<u32>,
+
+    /// Refuse destructive operations while credentials are overdue
+    #[arg(long)]
+    pub block_stale_credentials: bool,
+
+    /// Talk to the server through the CLI or the REST API
+    #[arg(long, value_enum)]
+    pub backend: Option<Backend>,
+
+    /// Guardrail for commands that change the server: open, confirm
+    /// (type the profile name) or read-only (refuse them)
+    #[arg(long, value_enum)]
+    pub protection: Option<Protection>,
+
+    /// Pass the password/token to the CLI as options or in a temporary
+    /// profile file (auto: the file for protected profiles)
+    #[arg(long, value_enum)]
+    pub credential_passing: Option<CredentialPassing>,
+
+    /// Set a value for `{{vars.<NAME>}}` placeholders (repeatable)
+    #[arg(long = "var", value_name = "NAME=VALUE")]
+    pub vars: Vec<String>,
+
+    /// Remove a template variable (repeatable)
+    #[arg(long = "unset-var", value_name = "NAME")]
+    pub unset_vars: Vec<String>,
 }
 
 #[derive(Args, Debug)]
@@ -262,6 +774,20 @@ pub struct ProfileDefaultArgs {
     pub name: String,
 }
 
+#[derive(Args, Debug)]
+pub struct ProfileImportVantiqArgs {
+    /// Profile file to read (default: ~/.vantiq/profile)
+    pub path: Option<PathBuf>,
+
+    /// Pass `-s <name>` to the CLI instead of copying credentials
+    #[arg(long)]
+    pub link: bool,
+
+    /// Overwrite existing profiles
+    #[arg(long)]
+    pub overwrite: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct ProfileImportArgs {
     /// File to import from
@@ -277,124 +803,455 @@ pub struct ProfileExportArgs {
     /// File to export to
     pub file: PathBuf,
 
+    /// Profiles to export (default: all)
+    pub profiles: Vec<String>,
+
+    /// File format: vqx TOML, or the Vantiq CLI's ~/.vantiq/profile format
+    #[arg(long, value_enum, default_value = "toml")]
+    pub format: ProfileFileFormat,
+
     /// Include sensitive values
     #[arg(long)]
     pub include_secrets: bool,
 }
 
 #[derive(Args, Debug)]
-pub struct ProfileInitArgs {
-    /// Profile name to create
-    pub name: Option<String>,
-}
-
-// =============================================================================
-// Phase 2: Export/Import (placeholders)
-// =============================================================================
+pub struct ProfileRotateArgs {
+    /// Profile name
+    pub name: String,
 
-/// Arguments for export command
-/// Based on PDF "Export" section
-#[derive(Args, Debug)]
-pub struct ExportArgs {
-    /// What to export: metadata, data, project, projectdata, hidden
-    /// PDF: "export [data | metadata | project <projectName> | projectdata <projectName> | hidden]"
-    #[arg(value_enum, default_value = "metadata")]
-    pub export_type: ExportType,
+    /// Read the new token from stdin instead of prompting
+    #[arg(long)]
+    pub token_stdin: bool,
 
-    /// Project name (required for project/projectdata types)
+    /// Save the token without checking it against the server
     #[arg(long)]
-    pub project: Option<String>,
+    pub no_verify: bool,
 
-    /// Output directory
-    /// PDF: "-d <directoryName>"
-    #[arg(short = 'd', long)]
-    pub directory: Option<PathBuf>,
+    /// Delete the old access token on the server after saving, by its name
+    /// in the Vantiq IDE
+    #[arg(long, value_name = "TOKEN_NAME")]
+    pub revoke: Option<String>,
+}
 
-    /// Chunk size for large exports
-    /// PDF: "-chunk <integer>"
+#[derive(Args, Debug)]
+pub struct ProfileEncryptArgs {
+    /// Store the passphrase in the OS keyring for unlocking without a prompt
+    /// (also works on an already encrypted store)
     #[arg(long)]
-    pub chunk: Option<u32>,
+    pub remember: bool,
+}
 
-    /// Types to include
-    /// PDF: "-include <typeName(s)>"
-    #[arg(long)]
-    pub include: Vec<String>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ProfileFileFormat {
+    Toml,
+    Vantiq,
+}
 
-    /// Types to exclude
-    /// PDF: "-exclude <typeName(s)>"
-    #[arg(long)]
-    pub exclude: Vec<String>,
+#[derive(Args, Debug)]
+pub struct ProfileInitArgs {
+    /// Profile name to create
+    pub name: Option<String>,
+}
 
-    /// Export data until this timestamp
-    /// PDF: "-until <DateTime>" (ISO format or "NOW")
-    #[arg(long)]
-    pub until: Option<String>,
+/// Arguments for the login command
+#[derive(Args, Debug)]
+pub struct LoginArgs {
+    /// Profile to log in with (default: --profile or the default profile)
+    pub name: Option<String>,
 
-    /// Ignore errors during export
-    /// PDF: "-ignoreErrors"
+    /// Read the password from stdin instead of prompting, when the profile
+    /// does not store one
     #[arg(long)]
-    pub ignore_errors: bool,
+    pub password_stdin: bool,
+}
 
-    /// Normalize JSON output for git-friendly diffs (vqx extension)
-    /// Use --no-normalize to disable
-    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
-    pub normalize: bool,
+/// Arguments for the logout command
+#[derive(Args, Debug)]
+pub struct LogoutArgs {
+    /// Profile to log out (default: --profile or the default profile)
+    pub name: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
-pub enum ExportType {
-    Metadata,
-    Data,
-    Project,
-    ProjectData,
-    Hidden,
+/// Named credential subcommands
+#[derive(Subcommand, Debug)]
+pub enum CredentialCommands {
+    /// List named credentials and the profiles using them
+    List,
+
+    /// Create or update (rotate) a named credential
+    Set(CredentialSetArgs),
+
+    /// Delete a named credential
+    Delete(CredentialDeleteArgs),
 }
 
-/// Arguments for import command
-/// Based on PDF "Import" section
 #[derive(Args, Debug)]
-pub struct ImportArgs {
-    /// What to import: metadata or data
-    /// PDF: "import [data | metadata]"
-    #[arg(value_enum, default_value = "metadata")]
-    pub import_type: ImportType,
+pub struct CredentialSetArgs {
+    /// Credential name (e.g. svc-ci-token)
+    pub name: String,
 
-    /// Input directory
-    /// PDF: "-d <directoryName>"
-    #[arg(short = 'd', long)]
-    pub directory: Option<PathBuf>,
+    /// Username (Edge servers only, see `profile set`)
+    #[arg(short, long)]
+    pub username: Option<String>,
 
-    /// Chunk size for large imports
-    /// PDF: "-chunk <integer>"
-    #[arg(long)]
-    pub chunk: Option<u32>,
+    /// Password
+    #[arg(short, long)]
+    pub password: Option<String>,
+
+    /// Access token
+    #[arg(short, long)]
+    pub token: Option<String>,
 
-    /// Types to include
-    /// PDF: "-include <typeName>"
+    /// Role label (e.g. ci, deploy, readonly)
     #[arg(long)]
-    pub include: Vec<String>,
+    pub role: Option<String>,
 
-    /// Types to exclude
-    /// PDF: "-exclude <typeName>"
+    /// Store secrets in secure storage (keyring)
     #[arg(long)]
-    pub exclude: Vec<String>,
+    pub secure: bool,
 
-    /// Resource types to ignore
-    /// PDF: "-ignore <resourceType>"
+    /// Description for this credential
     #[arg(long)]
-    pub ignore: Vec<String>,
+    pub description: Option<String>,
+}
 
-    /// Skip confirmation prompt
+#[derive(Args, Debug)]
+pub struct CredentialDeleteArgs {
+    /// Credential name to delete
+    pub name: String,
+
+    /// Skip confirmation and delete even if profiles still use it
     #[arg(short, long)]
-    pub yes: bool,
+    pub force: bool,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
-pub enum ImportType {
+// =============================================================================
+// Phase 1: Config
+// =============================================================================
+
+/// Config subcommands
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print a setting (or all settings), defaults included
+    Get(ConfigGetArgs),
+
+    /// Change a setting in config.toml
+    ///
+    /// The value is read as TOML (numbers, true/false, ["a", "b"]) and
+    /// otherwise as a string. Examples:
+    ///   vqx config set logging.level debug
+    ///   vqx config set normalization.excluded_fields '["_id", "ars_*"]'
+    Set(ConfigSetArgs),
+
+    /// Open config.toml in $VISUAL or $EDITOR and check it afterwards
+    Edit,
+
+    /// Print the path of config.toml
+    Path,
+
+    /// Check config.toml for errors and unknown keys
+    Validate,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigGetArgs {
+    /// Dotted key, e.g. `retry.read.max_retries` (default: everything)
+    pub key: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigSetArgs {
+    /// Dotted key, e.g. `logging.level` or `policy."dev-*".deny`
+    pub key: String,
+
+    /// New value
+    #[arg(allow_hyphen_values = true)]
+    pub value: String,
+}
+
+// =============================================================================
+// Phase 1: List/Select/Insert
+// =============================================================================
+
+/// Arguments for list command
+/// Based on PDF "List" section
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    /// Resource type to list (e.g. types, procedures, rules)
+    /// PDF: "list <resource>"
+    pub resource: String,
+
+    /// Columns to include, comma separated (default: all)
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Column to sort by (default: name)
+    #[arg(long)]
+    pub sort: Option<String>,
+
+    /// Sort in descending order
+    #[arg(long)]
+    pub desc: bool,
+
+    /// Only include resources whose name matches this glob (e.g. "Test*")
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+/// Arguments for select command
+/// Based on PDF "Select" section
+#[derive(Args, Debug)]
+pub struct SelectArgs {
+    /// Resource or type to select from
+    /// PDF: "select <resource> [<resourceId>]"
+    pub resource: String,
+
+    /// Select a single instance by ID
+    pub id: Option<String>,
+
+    /// Inline query qualification as JSON (e.g. '{"status":"open"}')
+    #[arg(long = "where", value_name = "JSON", conflicts_with = "qual")]
+    pub where_clause: Option<String>,
+
+    /// File containing the query qualification
+    /// PDF: "-qual <fileName>"
+    #[arg(long)]
+    pub qual: Option<PathBuf>,
+
+    /// Properties to return, comma separated
+    /// PDF: "-props <propertyList>"
+    #[arg(long, value_delimiter = ',')]
+    pub props: Vec<String>,
+
+    /// Maximum number of records to fetch and output
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// Chunk size used to page through results
+    /// PDF: "-chunk <integer>"
+    #[arg(long)]
+    pub chunk: Option<u32>,
+
+    /// Emit one JSON record per line instead of a single array
+    #[arg(long)]
+    pub ndjson: bool,
+}
+
+/// Arguments for insert/upsert commands
+/// Based on PDF "Insert" and "Upsert" sections
+#[derive(Args, Debug)]
+pub struct InsertArgs {
+    /// Resource or type to write to
+    /// PDF: "insert <resource> <fileName>"
+    pub resource: String,
+
+    /// Input file: JSON array, single object or NDJSON (default: stdin, or "-")
+    pub file: Option<PathBuf>,
+
+    /// Number of records per CLI invocation (default: config default_chunk_size)
+    #[arg(long)]
+    pub chunk: Option<usize>,
+
+    /// Use the checked variant (checkedInsert/checkedUpsert)
+    #[arg(long)]
+    pub checked: bool,
+}
+
+// =============================================================================
+// Phase 2: Export/Import (placeholders)
+// =============================================================================
+
+/// Resource selection shared by commands (see [`crate::filter`])
+#[derive(Args, Debug, Clone, Default)]
+pub struct FilterArgs {
+    /// Only these resources (repeatable): a type (`types`), a resource
+    /// (`procedures/Orders.total`) or a glob (`procedures/Order*`)
+    /// PDF: "-include <typeName(s)>" for plain type names
+    #[arg(long, value_name = "PATTERN")]
+    pub include: Vec<String>,
+
+    /// Leave these resources out (repeatable), patterns as for --include
+    /// PDF: "-exclude <typeName(s)>" for plain type names
+    #[arg(long, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+}
+
+/// Arguments for export command
+/// Based on PDF "Export" section
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// What to export: metadata, data, project, projectdata, hidden
+    /// PDF: "export [data | metadata | project <projectName> | projectdata <projectName> | hidden]"
+    #[arg(value_enum, default_value = "metadata")]
+    pub export_type: ExportType,
+
+    /// Project name (required for project/projectdata types)
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// Output directory
+    /// PDF: "-d <directoryName>"
+    #[arg(short = 'd', long)]
+    pub directory: Option<PathBuf>,
+
+    /// Chunk size for large exports, or `auto` to tune it per type (vqx extension)
+    /// PDF: "-chunk <integer>"
+    #[arg(long, value_name = "SIZE|auto")]
+    pub chunk: Option<ChunkSize>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Export data until this timestamp
+    /// PDF: "-until <DateTime>" (ISO format or "NOW")
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Ignore errors during export
+    /// PDF: "-ignoreErrors"
+    #[arg(long)]
+    pub ignore_errors: bool,
+
+    /// Normalize JSON output for git-friendly diffs (vqx extension)
+    /// Use --no-normalize to disable
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub normalize: bool,
+
+    /// Acknowledge exporting hidden (system) resources (vqx extension)
+    /// Required for the `hidden` type; they are written to `_hidden/`
+    #[arg(long)]
+    pub include_hidden: bool,
+
+    /// Continue an export that failed, skipping the types it completed (vqx extension)
+    /// Completed types are excluded; data exports are limited to the start of the first attempt
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Show which resource types would be exported, without exporting (vqx extension)
+    /// Data exports list the namespace's types; nothing is written
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportType {
+    Metadata,
+    Data,
+    Project,
+    ProjectData,
+    Hidden,
+}
+
+/// Arguments for import command
+/// Based on PDF "Import" section
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// What to import: metadata or data
+    /// PDF: "import [data | metadata]"
+    #[arg(value_enum, default_value = "metadata")]
+    pub import_type: ImportType,
+
+    /// Input directory
+    /// PDF: "-d <directoryName>"
+    #[arg(short = 'd', long)]
+    pub directory: Option<PathBuf>,
+
+    /// Chunk size for large imports, or `auto` to tune it per type (vqx extension)
+    /// PDF: "-chunk <integer>"
+    #[arg(long, value_name = "SIZE|auto")]
+    pub chunk: Option<ChunkSize>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Resource types to ignore
+    /// PDF: "-ignore <resourceType>"
+    #[arg(long)]
+    pub ignore: Vec<String>,
+
+    /// Skip confirmation prompt
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Include resources matching the `[import]` blocked prefixes
+    #[arg(long)]
+    pub force: bool,
+
+    /// Import each resource type in its own CLI call and go on after failures (vqx extension)
+    /// `--continue-on-error=files` imports every file on its own; the report lists each one
+    #[arg(
+        long,
+        value_enum,
+        value_name = "UNIT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "types"
+    )]
+    pub continue_on_error: Option<ImportUnit>,
+
+    /// List what would be imported and check the directory, without importing (vqx extension)
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ImportType {
     Metadata,
     Data,
 }
 
+/// What `import --continue-on-error` imports in one CLI call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ImportUnit {
+    /// One resource type directory (one type of `data/`)
+    Types,
+    /// One file
+    Files,
+}
+
+/// Arguments for dump command
+#[derive(Args, Debug)]
+pub struct DumpArgs {
+    /// Resource type, e.g. `types` or `procedures`
+    pub resource: String,
+
+    /// Resource name
+    pub name: String,
+
+    /// File to write
+    pub file: PathBuf,
+
+    /// Normalize the file like `export` does (vqx extension)
+    /// Use --no-normalize to keep the CLI's output as is
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    pub normalize: bool,
+}
+
+/// Arguments for load command
+#[derive(Args, Debug)]
+pub struct LoadArgs {
+    /// Resource type, e.g. `types` or `procedures`
+    pub resource: String,
+
+    /// File holding the resource definition
+    pub file: PathBuf,
+
+    /// Skip the confirmation when the resource exists
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Load even if the file fails schema validation
+    #[arg(long)]
+    pub no_validate: bool,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
+}
+
 // =============================================================================
 // Phase 3: Diff/Sync (placeholders)
 // =============================================================================
@@ -408,13 +1265,56 @@ pub struct DiffArgs {
     /// Target: profile name or directory path
     pub target: String,
 
-    /// Only diff specific resource types
+    /// Only diff specific resource types (same as --include <type>)
     #[arg(long)]
     pub resource: Vec<String>,
 
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
     /// Show full diff output
     #[arg(long)]
     pub full: bool,
+
+    /// Only compare procedure/rule code; hide metadata changes
+    #[arg(long)]
+    pub code_only: bool,
+
+    /// Leave fields out of the comparison (repeatable): a glob over
+    /// `<type>/<name>.<dotted path>` like `sources/*.config.password`, or a
+    /// resource glob and JSON pointer glob like `'sources/* /config/password'`
+    #[arg(long, value_name = "RULE")]
+    pub ignore_path: Vec<String>,
+
+    /// Check type changes for breaking changes (removed properties, type
+    /// changes, new required fields, removed indexes); exit 1 if any
+    #[arg(long)]
+    pub breaking: bool,
+
+    /// Diff output format: text, json, yaml, ndjson, markdown, html, github,
+    /// gitlab, side-by-side, patch
+    ///
+    /// Defaults to `json` with `--output json` and `text` otherwise.
+    #[arg(long)]
+    pub format: Option<String>,
+
+    /// Export profiles again instead of using cached exports
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Also write a shareable report with full diffs (.html or .md)
+    #[arg(long, value_name = "FILE")]
+    pub report: Option<PathBuf>,
+
+    /// Emit GitHub Actions `::warning` annotations for removed resources
+    /// (and `--breaking` findings) on stderr
+    #[arg(long)]
+    pub annotations: bool,
+
+    /// Also write a unified diff of all changed files (for `vqx patch apply`,
+    /// `git apply` or `patch -p1`)
+    #[arg(long, value_name = "FILE")]
+    pub patch: Option<PathBuf>,
 }
 
 /// Sync subcommands
@@ -425,6 +1325,9 @@ pub enum SyncCommands {
 
     /// Push from local to remote (import with diff + confirm)
     Push(SyncPushArgs),
+
+    /// Watch a local directory and push changed files as they are saved
+    Watch(SyncWatchArgs),
 }
 
 #[derive(Args, Debug)]
@@ -436,6 +1339,25 @@ pub struct SyncPullArgs {
     /// Force overwrite local changes
     #[arg(short, long)]
     pub force: bool,
+
+    /// Only pull these resource types (comma-separated, e.g. types,procedures)
+    #[arg(long, value_delimiter = ',')]
+    pub resource: Vec<String>,
+
+    /// Only pull resources whose name matches this glob (e.g. 'Order*')
+    #[arg(long)]
+    pub name: Option<String>,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    /// Remove local files for resources deleted on the server
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Skip the prune confirmation
+    #[arg(short, long)]
+    pub yes: bool,
 }
 
 #[derive(Args, Debug)]
@@ -451,12 +1373,70 @@ pub struct SyncPushArgs {
     /// Dry run - show what would be pushed
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Import the whole directory instead of only changed resources
+    #[arg(long)]
+    pub all: bool,
+
+    /// Step through the changed resources, showing each diff, and choose
+    /// which ones to push
+    #[arg(short, long, conflicts_with_all = ["all", "dry_run"])]
+    pub interactive: bool,
+
+    /// Wait for a second person to run `vqx approve <hash>` before pushing
+    #[arg(long)]
+    pub require_approval: bool,
+
+    /// Approval token from `vqx approve` for this push (implies --require-approval)
+    #[arg(long, value_name = "TOKEN")]
+    pub approval_token: Option<String>,
+
+    /// Include resources matching the `[import]` blocked prefixes
+    #[arg(long)]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct SyncWatchArgs {
+    /// Local directory to watch
+    #[arg(short = 'd', long)]
+    pub directory: PathBuf,
+
+    /// Wait this long after the last change before pushing (milliseconds)
+    #[arg(long, default_value = "500")]
+    pub debounce_ms: u64,
+
+    /// Include resources matching the `[import]` blocked prefixes
+    #[arg(long)]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
 }
 
 // =============================================================================
 // Phase 4: Safe operations (placeholders)
 // =============================================================================
 
+/// Operation lock options for commands that write to a namespace (vqx extension)
+#[derive(Args, Debug, Clone, Default)]
+pub struct LockArgs {
+    /// Wait for another vqx operation on the same profile/namespace to finish
+    /// (`--wait` waits indefinitely, `--wait=<seconds>` gives up after that long)
+    #[arg(long, value_name = "SECONDS", num_args = 0..=1, require_equals = true)]
+    pub wait: Option<Option<u64>>,
+
+    /// Break an existing lock held by another vqx operation
+    #[arg(long)]
+    pub force_lock: bool,
+}
+
 /// Arguments for safe-delete command
 /// Wraps PDF's "delete" and "deleteMatching" with safety guards
 #[derive(Args, Debug)]
@@ -468,7 +1448,17 @@ pub struct SafeDeleteArgs {
     /// Resource ID or query
     /// If starts with '{', treated as deleteMatching query
     /// PDF: "deleteMatching <resource> <query>"
-    pub target: String,
+    #[arg(required_unless_present = "from_file")]
+    pub target: Option<String>,
+
+    /// Delete the IDs (or names) listed in a file instead (vqx extension)
+    /// One per line (`#` starts a comment) or a JSON array
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["target", "limit", "cascade"])]
+    pub from_file: Option<PathBuf>,
+
+    /// Deletions running at once with --from-file
+    #[arg(long, value_name = "N", default_value = "4")]
+    pub concurrency: usize,
 
     /// Dry run - only show what would be deleted
     #[arg(long)]
@@ -485,40 +1475,129 @@ pub struct SafeDeleteArgs {
     /// Force delete even if over limit
     #[arg(long)]
     pub force: bool,
-}
 
-/// Arguments for promote command
-#[derive(Args, Debug)]
-pub struct PromoteArgs {
-    /// Source profile
-    #[arg(long)]
-    pub from: String,
+    /// Delete at most this many records matching the query (vqx extension)
+    /// The selected records are deleted by _id (or name), not by the query
+    #[arg(long, value_name = "N")]
+    pub limit: Option<usize>,
 
-    /// Target profile
-    #[arg(long)]
-    pub to: String,
+    /// Number of matched records to preview before deleting
+    #[arg(long, value_name = "K", default_value = "10")]
+    pub sample: usize,
 
-    /// Skip diff display
-    #[arg(long)]
-    pub no_diff: bool,
+    /// Also delete resources that depend on the target (vqx extension)
+    /// Dependents are deleted first, each with its own backup
+    #[arg(long, conflicts_with = "ignore_dependents")]
+    pub cascade: bool,
 
-    /// Skip smoke tests after promotion
+    /// Skip the dependency check and delete only the target
     #[arg(long)]
-    pub no_test: bool,
+    pub ignore_dependents: bool,
 
-    /// Test suite to run after promotion
-    /// PDF: "run testsuite <testSuiteName>"
-    #[arg(long)]
-    pub testsuite: Option<String>,
+    /// Keep the deleted items in the trash instead of a backup
+    /// Restore them with `vqx trash restore <id>` until they expire
+    #[arg(long, conflicts_with = "no_backup")]
+    pub trash: bool,
 
-    /// Procedure to run after promotion
-    /// PDF: "run procedure <procedureName>"
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
+}
+
+/// Trash subcommands
+#[derive(Subcommand, Debug)]
+pub enum TrashCommands {
+    /// List trash entries
+    List,
+
+    /// Write the items of an entry back to the server and remove the entry
+    Restore(TrashRestoreArgs),
+
+    /// Remove expired entries (or all with --all)
+    Empty(TrashEmptyArgs),
+}
+
+/// Arguments for trash restore
+#[derive(Args, Debug)]
+pub struct TrashRestoreArgs {
+    /// Entry ID (or a unique prefix) from `vqx trash list`
+    pub id: String,
+
+    /// Skip confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
+}
+
+/// Arguments for trash empty
+#[derive(Args, Debug)]
+pub struct TrashEmptyArgs {
+    /// Also remove entries that have not expired yet
+    #[arg(long)]
+    pub all: bool,
+
+    /// Skip the confirmation for --all
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
+/// Arguments for rollback command
+#[derive(Args, Debug)]
+pub struct RollbackArgs {
+    /// Snapshot ID (or a unique prefix) to import; lists snapshots if omitted
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Skip confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
+}
+
+/// Arguments for promote command
+#[derive(Args, Debug)]
+pub struct PromoteArgs {
+    /// Source profile
+    #[arg(long)]
+    pub from: String,
+
+    /// Target profile
+    #[arg(long)]
+    pub to: String,
+
+    /// Skip diff display
+    #[arg(long)]
+    pub no_diff: bool,
+
+    /// Skip smoke tests after promotion
+    #[arg(long)]
+    pub no_test: bool,
+
+    /// Test suites to run after promotion (repeatable or comma-separated)
+    /// PDF: "run testsuite <testSuiteName>"
+    #[arg(long, value_delimiter = ',')]
+    pub testsuite: Vec<String>,
+
+    /// Procedure to run after promotion
+    /// PDF: "run procedure <procedureName>"
     #[arg(long)]
     pub procedure: Option<String>,
 
     /// Skip confirmation
     #[arg(short, long)]
     pub yes: bool,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
 }
 
 /// Run subcommands
@@ -531,13 +1610,120 @@ pub enum RunCommands {
 
     /// Run a test suite
     /// PDF: "run testsuite <testSuiteName>"
+    #[command(name = "testsuite", alias = "test-suite")]
     TestSuite(RunTestSuiteArgs),
 
+    /// Run the test suites of a suites file in parallel
+    ///
+    /// Runs each suite against its profiles with bounded concurrency,
+    /// per-suite timeouts and retries of suites with failed tests
+    #[command(name = "testsuites")]
+    TestSuites(RunTestSuitesArgs),
+
     /// Run a procedure
     /// PDF: "run procedure <procedureName>"
     Procedure(RunProcedureArgs),
 }
 
+/// CI subcommands (vqx extension)
+#[derive(Subcommand, Debug)]
+pub enum CiCommands {
+    /// Import an export into a throwaway namespace, run test suites, tear it down
+    ///
+    /// Creates a temporary namespace with the -s profile (username/password
+    /// of an organization admin), or uses --sandbox-profile. The sandbox is
+    /// torn down even when the import or the tests fail
+    Sandbox(CiSandboxArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CiSandboxArgs {
+    /// Export directory to import into the sandbox
+    #[arg(long, default_value = ".")]
+    pub from: PathBuf,
+
+    /// Test suite to run (repeatable)
+    #[arg(long = "testsuite", value_name = "NAME", required = true)]
+    pub testsuites: Vec<String>,
+
+    /// Write per-test results of all suites as JUnit XML to this file
+    #[arg(long, value_name = "FILE")]
+    pub report: Option<PathBuf>,
+
+    /// Use this profile's namespace instead of creating one; the imported
+    /// resources are deleted afterwards
+    #[arg(long, value_name = "PROFILE")]
+    pub sandbox_profile: Option<String>,
+
+    /// Prefix of the temporary namespace name
+    #[arg(long, default_value = "ci")]
+    pub prefix: String,
+
+    /// Leave the sandbox in place, e.g. to inspect a failure
+    #[arg(long)]
+    pub keep: bool,
+
+    #[command(flatten)]
+    pub lock: LockArgs,
+}
+
+/// Deploy command, or one of its subcommands
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct DeployCommand {
+    #[command(subcommand)]
+    pub command: Option<DeployCommands>,
+
+    #[command(flatten)]
+    pub args: DeployArgs,
+}
+
+/// Deploy subcommands (vqx extension)
+#[derive(Subcommand, Debug)]
+pub enum DeployCommands {
+    /// Show what deploying a configuration would change in a target environment
+    ///
+    /// Resolves the resources the configuration covers and diffs them
+    /// between this profile and the target profile
+    Plan(DeployPlanArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct DeployPlanArgs {
+    /// Deployment configuration name
+    pub name: String,
+
+    /// vqx profile of the environment the configuration deploys to
+    #[arg(long, value_name = "PROFILE")]
+    pub to: String,
+
+    /// Export again instead of using cached exports
+    #[arg(long)]
+    pub refresh: bool,
+
+    /// Show full diffs of changed resources
+    #[arg(long)]
+    pub full: bool,
+}
+
+/// Arguments for deploy/undeploy commands
+/// Based on PDF "Deploy" and "Undeploy" sections
+#[derive(Args, Debug)]
+pub struct DeployArgs {
+    /// Deployment configuration or deployment name
+    /// PDF: "deploy <configurationName> | <deploymentName>"
+    #[arg(required = true)]
+    pub name: Option<String>,
+
+    /// Only show the deployment configuration, do not change anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Skip confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct RunTestArgs {
     /// Test name
@@ -552,6 +1738,25 @@ pub struct RunTestSuiteArgs {
     /// Start from specific test
     #[arg(long)]
     pub start_from: Option<String>,
+
+    /// Write per-test results as JUnit XML to this file
+    #[arg(long, value_name = "FILE")]
+    pub report: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct RunTestSuitesArgs {
+    /// Suites file (TOML) listing the suites to run
+    #[arg(short, long)]
+    pub file: PathBuf,
+
+    /// Suites running at once (overrides `concurrency` in the file)
+    #[arg(long)]
+    pub concurrency: Option<usize>,
+
+    /// Write per-test results of all suites as JUnit XML to this file
+    #[arg(long, value_name = "FILE")]
+    pub report: Option<PathBuf>,
 }
 
 #[derive(Args, Debug)]
@@ -559,42 +1764,1273 @@ pub struct RunProcedureArgs {
     /// Procedure name
     pub name: String,
 
-    /// Parameters as name:value pairs
+    /// Parameters as name:value pairs, or name:=<json> for typed values
     /// PDF: "<p1Name>:<p1Value> ... <pNName>:<pNValue>"
     #[arg(trailing_var_arg = true)]
     pub params: Vec<String>,
+
+    /// JSON file with an object of parameter names to values
+    /// Command line parameters override values from the file
+    #[arg(long, value_name = "FILE")]
+    pub params_file: Option<PathBuf>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use clap::CommandFactory;
+// =============================================================================
+// Tooling: Schema
+// =============================================================================
 
-    #[test]
-    fn test_cli_parses() {
-        Cli::command().debug_assert();
-    }
+/// Schema subcommands
+#[derive(Subcommand, Debug)]
+pub enum SchemaCommands {
+    /// JSON Schemas of the reports printed with `--output json`
+    Reports(SchemaReportsArgs),
+}
 
-    #[test]
-    fn test_doctor_command() {
-        let cli = Cli::parse_from(["vqx", "doctor"]);
-        assert!(matches!(cli.command, Commands::Doctor(_)));
-    }
+#[derive(Args, Debug)]
+pub struct SchemaReportsArgs {
+    /// Only print the schema for this report (e.g. diff, sync, promote)
+    pub name: Option<String>,
 
-    #[test]
-    fn test_profile_list() {
-        let cli = Cli::parse_from(["vqx", "profile", "list"]);
-        assert!(matches!(
-            cli.command,
-            Commands::Profile(ProfileCommands::List)
-        ));
+    /// Write one `<name>.v<version>.schema.json` file per report to this directory
+    #[arg(long)]
+    pub out_dir: Option<PathBuf>,
+}
+
+// =============================================================================
+// Tooling: Docs
+// =============================================================================
+
+/// Docs subcommands
+#[derive(Subcommand, Debug)]
+pub enum DocsCommands {
+    /// Write man pages or a markdown command reference
+    Generate(DocsGenerateArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct DocsGenerateArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value = "man")]
+    pub format: DocsFormat,
+
+    /// Output directory
+    #[arg(long, default_value = "docs")]
+    pub out: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DocsFormat {
+    /// One roff man page per command (vqx.1, vqx-export.1, ...)
+    Man,
+    /// A single cli-reference.md
+    Markdown,
+}
+
+// =============================================================================
+// Tooling: Explain
+// =============================================================================
+
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+    /// Command to explain
+    #[arg(value_enum)]
+    pub command: ExplainTopic,
+}
+
+/// Commands with an annotated guide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExplainTopic {
+    Export,
+    Import,
+    Diff,
+    Sync,
+    Promote,
+    /// Commands passed through to the underlying CLI
+    Passthrough,
+}
+
+// =============================================================================
+// Tooling: Schedule
+// =============================================================================
+
+/// Schedule subcommands
+#[derive(Subcommand, Debug)]
+pub enum ScheduleCommands {
+    /// Add a job: a cron expression and the vqx command to run
+    ///
+    /// Example: vqx schedule add "0 2 * * *" sync pull -d ./repo --profile prod
+    Add(ScheduleAddArgs),
+
+    /// List jobs with their next run
+    List,
+
+    /// Remove a job
+    Remove(ScheduleRemoveArgs),
+
+    /// Run jobs as they come due, until stopped
+    Run(ScheduleRunArgs),
+
+    /// Print a systemd unit or Windows scheduled task that keeps
+    /// `vqx schedule run` running
+    Unit(ScheduleUnitArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ScheduleAddArgs {
+    /// Job ID (default: job-<n>)
+    #[arg(long)]
+    pub id: Option<String>,
+
+    /// Cron expression: minute hour day month weekday (e.g. "0 2 * * *"),
+    /// or @hourly, @daily, @weekly, @monthly
+    pub cron: String,
+
+    /// vqx command to run, without `vqx`
+    #[arg(
+        required = true,
+        num_args = 1..,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    pub command: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ScheduleRemoveArgs {
+    /// Job ID from `vqx schedule list`
+    pub id: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ScheduleRunArgs {
+    /// Only run these jobs (repeatable)
+    #[arg(long = "job", value_name = "ID")]
+    pub jobs: Vec<String>,
+
+    /// Serve Prometheus metrics of the runs on this address (e.g.
+    /// 127.0.0.1:9187)
+    #[arg(long, value_name = "ADDR")]
+    pub metrics: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ScheduleUnitArgs {
+    /// Service manager to write the definition for
+    #[arg(value_enum)]
+    pub kind: UnitKind,
+}
+
+/// Service definitions `vqx schedule unit` can write
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum UnitKind {
+    /// systemd service unit
+    Systemd,
+    /// Windows Task Scheduler task (XML for `schtasks /Create /XML`)
+    Windows,
+}
+
+// =============================================================================
+// Tooling: Serve
+// =============================================================================
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Address to listen on (default: `[serve] listen`, 127.0.0.1:8787)
+    #[arg(long, value_name = "ADDR")]
+    pub listen: Option<String>,
+}
+
+// =============================================================================
+// Tooling: Telemetry
+// =============================================================================
+
+/// Telemetry subcommands
+#[derive(Subcommand, Debug)]
+pub enum TelemetryCommands {
+    /// Start recording usage statistics
+    On,
+
+    /// Stop recording and delete the recorded statistics
+    Off,
+
+    /// Show whether telemetry is on and what has been recorded
+    Show,
+}
+
+// =============================================================================
+// Tooling: Triage
+// =============================================================================
+
+/// Arguments for the triage command
+#[derive(Args, Debug)]
+pub struct TriageArgs {
+    /// Archive path (default: ./vqx-triage-<timestamp>.tar.gz)
+    #[arg(short = 'o', long)]
+    pub out: Option<PathBuf>,
+
+    /// Also test the connection to the server (uses the default profile)
+    #[arg(long)]
+    pub test_connection: bool,
+
+    /// Number of trailing log file lines to include
+    #[arg(long, default_value = "500")]
+    pub log_lines: usize,
+
+    /// Include the log lines of a failed run (the operation ID printed with its error)
+    #[arg(long, value_name = "ID")]
+    pub operation: Option<String>,
+}
+
+// =============================================================================
+// Tooling: Normalize
+// =============================================================================
+
+/// Arguments for the normalize command
+#[derive(Args, Debug)]
+pub struct NormalizeArgs {
+    /// Export directory to normalize
+    #[arg(default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Show which files would change without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// With --dry-run, show the full diff of each file
+    #[arg(long, requires = "dry_run")]
+    pub full: bool,
+}
+
+// =============================================================================
+// Tooling: Validate
+// =============================================================================
+
+/// Arguments for the validate command
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Export directory to validate
+    #[arg(short = 'd', long, default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Also check the directory layout: known subdirectories, file names
+    /// matching the `name` inside and no orphaned `.vail` files
+    #[arg(long)]
+    pub layout: bool,
+}
+
+// =============================================================================
+// Tooling: Scan
+// =============================================================================
+
+/// Arguments for the grep command
+#[derive(Args, Debug)]
+pub struct GrepArgs {
+    /// Regular expression to search for
+    pub pattern: String,
+
+    /// Export directory to search (default: current directory, or the
+    /// namespace of --profile when given)
+    #[arg(short = 'd', long)]
+    pub directory: Option<PathBuf>,
+
+    /// Only search these resource types (comma-separated, e.g. procedures,rules)
+    #[arg(long, value_delimiter = ',')]
+    pub resource: Vec<String>,
+
+    /// Case-insensitive search
+    #[arg(short = 'i', long)]
+    pub ignore_case: bool,
+
+    /// Export the profile again instead of using the cached export
+    #[arg(long)]
+    pub refresh: bool,
+}
+
+/// Arguments for the stats command
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    /// Export directory to summarize (default: current directory, or the
+    /// namespace of --profile when given)
+    #[arg(short = 'd', long)]
+    pub directory: Option<PathBuf>,
+
+    /// Number of largest and recently modified resources to list
+    #[arg(long, default_value = "10")]
+    pub top: usize,
+
+    /// Export the profile again instead of using the cached export
+    #[arg(long)]
+    pub refresh: bool,
+}
+
+/// Arguments for the show command
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// Resource type, e.g. `types` or `procedures`
+    pub resource: String,
+
+    /// Resource name
+    pub name: String,
+
+    /// Read the resource from this export directory instead of the server
+    #[arg(short = 'd', long)]
+    pub directory: Option<PathBuf>,
+
+    /// Print the definition as stored, without normalization or highlighting
+    #[arg(long)]
+    pub raw: bool,
+}
+
+/// Arguments for the open command
+#[derive(Args, Debug)]
+pub struct OpenArgs {
+    /// Resource type, e.g. `types` or `procedures`
+    pub resource: String,
+
+    /// Resource name
+    pub name: String,
+
+    /// Print the URL instead of opening a browser
+    #[arg(long)]
+    pub print: bool,
+}
+
+/// Patch subcommands
+#[derive(Subcommand, Debug)]
+pub enum PatchCommands {
+    /// Apply a unified diff to an export directory
+    ///
+    /// Checks every file first: when a hunk does not match, nothing is
+    /// changed and the conflicts are reported (exit 1)
+    Apply(PatchApplyArgs),
+}
+
+/// Arguments for patch apply
+#[derive(Args, Debug)]
+pub struct PatchApplyArgs {
+    /// Patch file
+    pub patch: PathBuf,
+
+    /// Export directory to patch
+    #[arg(short = 'd', long, default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Check the patch without changing any file
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Template subcommands
+#[derive(Subcommand, Debug)]
+pub enum TemplateCommands {
+    /// Write a copy of an export with placeholders replaced by the
+    /// profile's values
+    Render(TemplateRenderArgs),
+
+    /// Replace the profile's values (and --var values) with placeholders
+    Extract(TemplateExtractArgs),
+}
+
+/// Arguments for template render
+#[derive(Args, Debug)]
+pub struct TemplateRenderArgs {
+    /// Export directory with placeholders
+    #[arg(short = 'd', long, default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Directory to write the rendered copy to
+    #[arg(long, value_name = "DIR")]
+    pub out_dir: PathBuf,
+}
+
+/// Arguments for template extract
+#[derive(Args, Debug)]
+pub struct TemplateExtractArgs {
+    /// Export directory to change in place
+    #[arg(short = 'd', long, default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Variable to extract in addition to the profile's `vars` (repeatable)
+    #[arg(long = "var", value_name = "NAME=VALUE")]
+    pub vars: Vec<String>,
+
+    /// Show what would be replaced without changing any file
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Resource kinds `vqx new` can create
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NewKind {
+    Type,
+    Procedure,
+    Rule,
+    Service,
+}
+
+/// Arguments for the new command
+#[derive(Args, Debug)]
+pub struct NewArgs {
+    /// Kind of resource
+    pub kind: NewKind,
+
+    /// Resource name (e.g. Order, Orders.total)
+    pub name: String,
+
+    /// Export directory (default: current directory, or `directory` in
+    /// .vqx.toml)
+    #[arg(short = 'd', long)]
+    pub directory: Option<PathBuf>,
+
+    /// Type property (repeatable; types only)
+    #[arg(short, long = "property", value_name = "NAME:TYPE")]
+    pub properties: Vec<String>,
+
+    /// Event path the rule fires on, e.g. /types/Order/insert (rules only)
+    #[arg(long, value_name = "PATH", required_if_eq("kind", "rule"))]
+    pub on: Option<String>,
+
+    /// Overwrite an existing definition
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Arguments for the rename command
+#[derive(Args, Debug)]
+pub struct RenameArgs {
+    /// Resource type directory (e.g. procedures, types)
+    pub resource_type: String,
+
+    /// Current resource name
+    pub old_name: String,
+
+    /// New resource name
+    pub new_name: String,
+
+    /// Export directory (default: current directory, or `directory` in
+    /// .vqx.toml)
+    #[arg(short = 'd', long)]
+    pub directory: Option<PathBuf>,
+
+    /// Show which files would change without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for the approve command
+#[derive(Args, Debug)]
+pub struct ApproveArgs {
+    /// Plan hash (or a unique prefix) printed by `sync push --require-approval`
+    pub hash: Option<String>,
+
+    /// Reject the push instead of approving it
+    #[arg(long)]
+    pub reject: bool,
+
+    /// Skip the confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// Create a new approver key in FILE and print its public key
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["hash", "reject"])]
+    pub generate_key: Option<PathBuf>,
+}
+
+/// Arguments for the scan command
+#[derive(Args, Debug)]
+pub struct ScanArgs {
+    /// Export directory to scan
+    #[arg(short = 'd', long, default_value = ".")]
+    pub directory: PathBuf,
+
+    /// Allowlist file (default: <directory>/.vqx-scan-allow if present)
+    #[arg(long)]
+    pub allowlist: Option<PathBuf>,
+
+    /// Also write findings as a SARIF 2.1.0 report for CI annotations
+    #[arg(long)]
+    pub sarif: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_parses() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_doctor_command() {
+        let cli = Cli::parse_from(["vqx", "doctor"]);
+        assert!(matches!(cli.command, Commands::Doctor(_)));
+    }
+
+    #[test]
+    fn test_global_timeout() {
+        let cli = Cli::parse_from(["vqx", "export", "data", "--timeout", "3600"]);
+        assert_eq!(cli.timeout, Some(3600));
+        assert!(matches!(cli.command, Commands::Export(_)));
+    }
+
+    #[test]
+    fn test_global_log_file() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "sync",
+            "pull",
+            "-d",
+            "./src",
+            "--log-file",
+            "/tmp/vqx.log",
+        ]);
+        assert_eq!(cli.log_file, Some(PathBuf::from("/tmp/vqx.log")));
+    }
+
+    #[test]
+    fn test_doctor_fix() {
+        let cli = Cli::parse_from(["vqx", "doctor", "--fix", "-y"]);
+        if let Commands::Doctor(args) = cli.command {
+            assert!(args.fix);
+            assert!(args.yes);
+        } else {
+            panic!("Expected Doctor command");
+        }
+        assert!(Cli::try_parse_from(["vqx", "doctor", "--yes"]).is_err());
+    }
+
+    #[test]
+    fn test_profile_import_vantiq() {
+        let cli = Cli::parse_from(["vqx", "profile", "import-vantiq", "--link"]);
+        if let Commands::Profile(ProfileCommands::ImportVantiq(args)) = cli.command {
+            assert!(args.path.is_none());
+            assert!(args.link);
+            assert!(!args.overwrite);
+        } else {
+            panic!("Expected Profile ImportVantiq command");
+        }
+    }
+
+    #[test]
+    fn test_profile_export_vantiq() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "profile",
+            "export",
+            "team.profile",
+            "dev",
+            "prod",
+            "--format",
+            "vantiq",
+        ]);
+        if let Commands::Profile(ProfileCommands::Export(args)) = cli.command {
+            assert_eq!(args.file, PathBuf::from("team.profile"));
+            assert_eq!(args.profiles, vec!["dev", "prod"]);
+            assert_eq!(args.format, ProfileFileFormat::Vantiq);
+            assert!(!args.include_secrets);
+        } else {
+            panic!("Expected Profile Export command");
+        }
+    }
+
+    #[test]
+    fn test_profile_rotate() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "profile",
+            "rotate",
+            "prod",
+            "--token-stdin",
+            "--revoke",
+            "ci-2025",
+        ]);
+        if let Commands::Profile(ProfileCommands::Rotate(args)) = cli.command {
+            assert_eq!(args.name, "prod");
+            assert!(args.token_stdin);
+            assert!(!args.no_verify);
+            assert_eq!(args.revoke.as_deref(), Some("ci-2025"));
+        } else {
+            panic!("Expected Profile Rotate command");
+        }
+    }
+
+    #[test]
+    fn test_profile_set_proxy() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "profile",
+            "set",
+            "corp",
+            "--proxy",
+            "proxy.corp:8080",
+            "--proxy-username",
+            "me",
+        ]);
+        if let Commands::Profile(ProfileCommands::Set(args)) = cli.command {
+            assert_eq!(args.proxy.as_deref(), Some("proxy.corp:8080"));
+            assert_eq!(args.proxy_username.as_deref(), Some("me"));
+            assert!(!args.no_proxy);
+        } else {
+            panic!("Expected Profile Set command");
+        }
+
+        let result = Cli::try_parse_from([
+            "vqx",
+            "profile",
+            "set",
+            "corp",
+            "--proxy",
+            "p:1",
+            "--no-proxy",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_login_command() {
+        let cli = Cli::parse_from(["vqx", "login", "edge", "--password-stdin"]);
+        if let Commands::Login(args) = cli.command {
+            assert_eq!(args.name.as_deref(), Some("edge"));
+            assert!(args.password_stdin);
+        } else {
+            panic!("Expected Login command");
+        }
+        assert!(Cli::parse_from(["vqx", "login"]).command.needs_server());
+        assert!(!Cli::parse_from(["vqx", "logout"]).command.needs_server());
+    }
+
+    #[test]
+    fn test_profile_encrypt() {
+        let cli = Cli::parse_from(["vqx", "profile", "encrypt", "--remember"]);
+        if let Commands::Profile(ProfileCommands::Encrypt(args)) = cli.command {
+            assert!(args.remember);
+        } else {
+            panic!("Expected Profile Encrypt command");
+        }
+        let cli = Cli::parse_from(["vqx", "profile", "decrypt"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Profile(ProfileCommands::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn test_profile_list() {
+        let cli = Cli::parse_from(["vqx", "profile", "list"]);
+        assert!(matches!(
+            cli.command,
+            Commands::Profile(ProfileCommands::List)
+        ));
+    }
+
+    #[test]
+    fn test_credential_set_command() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "credential",
+            "set",
+            "svc-ci-token",
+            "-t",
+            "abc",
+            "--role",
+            "ci",
+        ]);
+        if let Commands::Credential(CredentialCommands::Set(args)) = cli.command {
+            assert_eq!(args.name, "svc-ci-token");
+            assert_eq!(args.token.as_deref(), Some("abc"));
+            assert_eq!(args.role.as_deref(), Some("ci"));
+        } else {
+            panic!("Expected Credential Set command");
+        }
+    }
+
+    #[test]
+    fn test_is_destructive() {
+        let cli = Cli::parse_from(["vqx", "safe-delete", "types", "Order"]);
+        assert!(cli.command.is_destructive());
+        let cli = Cli::parse_from(["vqx", "sync", "pull", "-d", "./src"]);
+        assert!(!cli.command.is_destructive());
+
+        // Passthrough commands are classified by the underlying command
+        let cli = Cli::parse_from(["vqx", "deleteMatching", "types", "{}"]);
+        assert!(cli.command.is_destructive());
+        assert_eq!(cli.command.operation(), "deleteMatching");
+        let cli = Cli::parse_from(["vqx", "find", "types", "Order"]);
+        assert!(!cli.command.is_destructive());
+
+        let cli = Cli::parse_from(["vqx", "run", "procedure", "Orders.reset"]);
+        assert!(cli.command.is_destructive());
+        let cli = Cli::parse_from(["vqx", "schedule", "run"]);
+        assert!(cli.command.is_destructive());
+        let cli = Cli::parse_from(["vqx", "schedule", "list"]);
+        assert!(!cli.command.is_destructive());
+
+        let cli = Cli::parse_from(["vqx", "sync", "push", "-d", "./src", "--dry-run"]);
+        assert!(cli.command.is_dry_run());
+        assert_eq!(cli.command.operation(), "sync push");
+    }
+
+    #[test]
+    fn test_list_command() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "list",
+            "types",
+            "--columns",
+            "name,ars_version",
+            "--name",
+            "Test*",
+            "--output",
+            "table",
+        ]);
+        assert!(matches!(cli.output, OutputFormat::Text));
+        if let Commands::List(args) = cli.command {
+            assert_eq!(args.resource, "types");
+            assert_eq!(args.columns, vec!["name", "ars_version"]);
+            assert_eq!(args.name.as_deref(), Some("Test*"));
+        } else {
+            panic!("Expected List command");
+        }
+    }
+
+    #[test]
+    fn test_strict_flag() {
+        let cli = Cli::parse_from(["vqx", "diff", "dev", "prod", "--strict"]);
+        assert!(cli.strict);
+        let cli = Cli::parse_from(["vqx", "diff", "dev", "prod"]);
+        assert!(!cli.strict);
+    }
+
+    #[test]
+    fn test_machine_readable_output() {
+        let cli = Cli::parse_from(["vqx", "diff", "dev", "prod", "--porcelain"]);
+        assert!(cli.porcelain);
+        assert!(OutputFormat::Porcelain.is_machine_readable());
+        assert!(
+            Cli::try_parse_from(["vqx", "list", "types", "--porcelain", "--output", "json"])
+                .is_err()
+        );
+
+        let cli = Cli::parse_from(["vqx", "profile", "list", "--output", "ndjson"]);
+        assert!(matches!(cli.output, OutputFormat::Ndjson));
+        assert!(cli.output.is_machine_readable());
+
+        let cli = Cli::parse_from(["vqx", "doctor", "--output", "yaml"]);
+        assert!(matches!(cli.output, OutputFormat::Yaml));
+        assert!(cli.output.is_machine_readable());
+        assert!(!OutputFormat::Csv.is_machine_readable());
+    }
+
+    #[test]
+    fn test_select_command() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "select",
+            "Orders",
+            "--where",
+            r#"{"status":"open"}"#,
+            "--props",
+            "id,status",
+            "--limit",
+            "10",
+        ]);
+        if let Commands::Select(args) = cli.command {
+            assert_eq!(args.resource, "Orders");
+            assert_eq!(args.where_clause.as_deref(), Some(r#"{"status":"open"}"#));
+            assert_eq!(args.props, vec!["id", "status"]);
+            assert_eq!(args.limit, Some(10));
+        } else {
+            panic!("Expected Select command");
+        }
+
+        assert!(Cli::try_parse_from([
+            "vqx", "select", "Orders", "--where", "{}", "--qual", "q.json"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_upsert_command() {
+        let cli = Cli::parse_from(["vqx", "upsert", "Orders", "-", "--chunk", "500"]);
+        if let Commands::Upsert(args) = cli.command {
+            assert_eq!(args.resource, "Orders");
+            assert_eq!(args.file, Some(PathBuf::from("-")));
+            assert_eq!(args.chunk, Some(500));
+            assert!(!args.checked);
+        } else {
+            panic!("Expected Upsert command");
+        }
+    }
+
+    #[test]
+    fn test_rollback_command() {
+        let cli = Cli::parse_from(["vqx", "rollback", "--to", "20260101_120000_prod", "-y"]);
+        assert!(cli.command.is_destructive());
+        if let Commands::Rollback(args) = cli.command {
+            assert_eq!(args.to.as_deref(), Some("20260101_120000_prod"));
+            assert!(args.yes);
+        } else {
+            panic!("Expected Rollback command");
+        }
+
+        // Without --to the snapshots are only listed
+        let cli = Cli::parse_from(["vqx", "rollback"]);
+        assert!(!cli.command.is_destructive());
+        assert!(!cli.command.needs_server());
+    }
+
+    #[test]
+    fn test_undeploy_command() {
+        let cli = Cli::parse_from(["vqx", "undeploy", "prodConfig", "--dry-run"]);
+        if let Commands::Undeploy(args) = cli.command {
+            assert_eq!(args.name.as_deref(), Some("prodConfig"));
+            assert!(args.dry_run);
+            assert!(!args.yes);
+        } else {
+            panic!("Expected Undeploy command");
+        }
+    }
+
+    #[test]
+    fn test_deploy_plan_command() {
+        let cli = Cli::parse_from(["vqx", "deploy", "prodConfig", "--yes"]);
+        assert!(cli.command.is_destructive());
+
+        let cli = Cli::parse_from(["vqx", "deploy", "plan", "prodConfig", "--to", "prod"]);
+        assert!(!cli.command.is_destructive());
+        if let Commands::Deploy(DeployCommand {
+            command: Some(DeployCommands::Plan(args)),
+            ..
+        }) = cli.command
+        {
+            assert_eq!(args.name, "prodConfig");
+            assert_eq!(args.to, "prod");
+        } else {
+            panic!("Expected Deploy plan command");
+        }
+
+        assert!(Cli::try_parse_from(["vqx", "deploy"]).is_err());
+    }
+
+    #[test]
+    fn test_sync_pull_filters() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "sync",
+            "pull",
+            "-d",
+            "./src",
+            "--resource",
+            "types,procedures",
+            "--name",
+            "Order*",
+        ]);
+        if let Commands::Sync(SyncCommands::Pull(args)) = cli.command {
+            assert_eq!(args.resource, vec!["types", "procedures"]);
+            assert_eq!(args.name.as_deref(), Some("Order*"));
+        } else {
+            panic!("Expected Sync Pull command");
+        }
+    }
+
+    #[test]
+    fn test_diff_code_only() {
+        let cli = Cli::parse_from(["vqx", "diff", "dev", "./local", "--code-only"]);
+        if let Commands::Diff(args) = cli.command {
+            assert!(args.code_only);
+            assert!(!args.full);
+            assert!(args.format.is_none());
+            assert!(!args.breaking);
+        } else {
+            panic!("Expected Diff command");
+        }
+    }
+
+    #[test]
+    fn test_import_export_dry_run() {
+        let import = Cli::parse_from(["vqx", "import", "-d", "./export", "--dry-run"]);
+        assert!(import.command.is_dry_run());
+        assert!(!import.command.needs_server());
+
+        let metadata = Cli::parse_from(["vqx", "export", "--dry-run", "--include", "types"]);
+        assert!(metadata.command.is_dry_run());
+        assert!(!metadata.command.needs_server());
+        // The namespace's types are listed for data
+        let data = Cli::parse_from(["vqx", "export", "data", "--dry-run"]);
+        assert!(data.command.needs_server());
+        assert!(Cli::parse_from(["vqx", "export"]).command.needs_server());
+    }
+
+    #[test]
+    fn test_normalize_dry_run() {
+        let cli = Cli::parse_from(["vqx", "normalize", "./export", "--dry-run", "--full"]);
+        if let Commands::Normalize(args) = cli.command {
+            assert_eq!(args.directory, PathBuf::from("./export"));
+            assert!(args.dry_run);
+            assert!(args.full);
+        } else {
+            panic!("Expected Normalize command");
+        }
+
+        assert!(Cli::try_parse_from(["vqx", "normalize", "--full"]).is_err());
+    }
+
+    #[test]
+    fn test_run_testsuite_report() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "run",
+            "testsuite",
+            "OrderSuite",
+            "--report",
+            "junit.xml",
+        ]);
+        if let Commands::Run(RunCommands::TestSuite(args)) = cli.command {
+            assert_eq!(args.name, "OrderSuite");
+            assert_eq!(args.report, Some(PathBuf::from("junit.xml")));
+        } else {
+            panic!("Expected Run TestSuite command");
+        }
+    }
+
+    #[test]
+    fn test_run_testsuites_and_promote_suites() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "run",
+            "testsuites",
+            "-f",
+            "suites.toml",
+            "--concurrency",
+            "4",
+        ]);
+        if let Commands::Run(RunCommands::TestSuites(args)) = cli.command {
+            assert_eq!(args.file, PathBuf::from("suites.toml"));
+            assert_eq!(args.concurrency, Some(4));
+        } else {
+            panic!("Expected Run TestSuites command");
+        }
+
+        let cli = Cli::parse_from([
+            "vqx",
+            "promote",
+            "--from",
+            "dev",
+            "--to",
+            "prod",
+            "--testsuite",
+            "OrderSuite,BillingSuite",
+            "--testsuite",
+            "Smoke",
+        ]);
+        if let Commands::Promote(args) = cli.command {
+            assert_eq!(args.testsuite, vec!["OrderSuite", "BillingSuite", "Smoke"]);
+        } else {
+            panic!("Expected Promote command");
+        }
+    }
+
+    #[test]
+    fn test_run_procedure_params_file() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "run",
+            "procedure",
+            "Orders.total",
+            "--params-file",
+            "params.json",
+            "count:=5",
+            "label:five",
+        ]);
+        if let Commands::Run(RunCommands::Procedure(args)) = cli.command {
+            assert_eq!(args.params_file, Some(PathBuf::from("params.json")));
+            assert_eq!(args.params, vec!["count:=5", "label:five"]);
+        } else {
+            panic!("Expected Run Procedure command");
+        }
+    }
+
+    #[test]
+    fn test_diff_format() {
+        let cli = Cli::parse_from(["vqx", "diff", "dev", "prod", "--format", "patch"]);
+        if let Commands::Diff(args) = cli.command {
+            assert_eq!(args.format.as_deref(), Some("patch"));
+        } else {
+            panic!("Expected Diff command");
+        }
+    }
+
+    #[test]
+    fn test_safe_delete_cascade_flags() {
+        let cli = Cli::parse_from(["vqx", "safe-delete", "types", "Order", "--cascade"]);
+        if let Commands::SafeDelete(args) = cli.command {
+            assert!(args.cascade);
+            assert!(!args.ignore_dependents);
+        } else {
+            panic!("Expected SafeDelete command");
+        }
+
+        assert!(Cli::try_parse_from([
+            "vqx",
+            "safe-delete",
+            "types",
+            "Order",
+            "--cascade",
+            "--ignore-dependents",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_lock_args() {
+        let cli = Cli::parse_from(["vqx", "import", "metadata", "--wait"]);
+        if let Commands::Import(args) = cli.command {
+            assert_eq!(args.lock.wait, Some(None));
+            assert!(!args.lock.force_lock);
+        } else {
+            panic!("Expected Import command");
+        }
+
+        let cli = Cli::parse_from([
+            "vqx",
+            "safe-delete",
+            "types",
+            "Order",
+            "--wait=30",
+            "--force-lock",
+        ]);
+        if let Commands::SafeDelete(args) = cli.command {
+            assert_eq!(args.lock.wait, Some(Some(30)));
+            assert!(args.lock.force_lock);
+            assert_eq!(args.target.as_deref(), Some("Order"));
+        } else {
+            panic!("Expected SafeDelete command");
+        }
+
+        let cli = Cli::parse_from(["vqx", "promote", "--from", "dev", "--to", "prod"]);
+        if let Commands::Promote(args) = cli.command {
+            assert_eq!(args.lock.wait, None);
+        } else {
+            panic!("Expected Promote command");
+        }
+    }
+
+    #[test]
+    fn test_export_hidden_flag() {
+        let cli = Cli::parse_from(["vqx", "export", "hidden", "-d", "./export"]);
+        if let Commands::Export(args) = cli.command {
+            assert!(matches!(args.export_type, ExportType::Hidden));
+            assert!(!args.include_hidden);
+        } else {
+            panic!("Expected Export command");
+        }
+
+        let cli = Cli::parse_from(["vqx", "export", "hidden", "--include-hidden"]);
+        if let Commands::Export(args) = cli.command {
+            assert!(args.include_hidden);
+        } else {
+            panic!("Expected Export command");
+        }
+    }
+
+    #[test]
+    fn test_import_continue_on_error() {
+        let cli = Cli::parse_from(["vqx", "import", "--continue-on-error"]);
+        if let Commands::Import(args) = cli.command {
+            assert_eq!(args.continue_on_error, Some(ImportUnit::Types));
+        } else {
+            panic!("Expected Import command");
+        }
+
+        let cli = Cli::parse_from(["vqx", "import", "data", "--continue-on-error=files"]);
+        if let Commands::Import(args) = cli.command {
+            assert_eq!(args.continue_on_error, Some(ImportUnit::Files));
+        } else {
+            panic!("Expected Import command");
+        }
+    }
+
+    #[test]
+    fn test_show_command() {
+        let cli = Cli::parse_from(["vqx", "show", "procedures", "MyProc", "--raw"]);
+        assert!(cli.command.needs_server());
+        if let Commands::Show(args) = cli.command {
+            assert_eq!(args.resource, "procedures");
+            assert_eq!(args.name, "MyProc");
+            assert!(args.raw);
+            assert!(args.directory.is_none());
+        } else {
+            panic!("Expected Show command");
+        }
+
+        let cli = Cli::parse_from(["vqx", "show", "rules", "OnOrder", "-d", "./export"]);
+        assert!(!cli.command.needs_server());
+        assert_eq!(cli.command.operation(), "show");
+    }
+
+    #[test]
+    fn test_open_command() {
+        let cli = Cli::parse_from(["vqx", "open", "procedures", "MyProc", "--print"]);
+        assert!(!cli.command.needs_server());
+        if let Commands::Open(args) = cli.command {
+            assert_eq!(args.resource, "procedures");
+            assert_eq!(args.name, "MyProc");
+            assert!(args.print);
+        } else {
+            panic!("Expected Open command");
+        }
+    }
+
+    #[test]
+    fn test_ci_sandbox_command() {
+        let cli = Cli::parse_from([
+            "vqx",
+            "ci",
+            "sandbox",
+            "--from",
+            "./export",
+            "--testsuite",
+            "Smoke",
+            "--testsuite",
+            "Orders",
+            "--report",
+            "junit.xml",
+        ]);
+        assert!(cli.command.needs_server());
+        assert_eq!(cli.command.operation(), "ci sandbox");
+        let Commands::Ci(CiCommands::Sandbox(args)) = cli.command else {
+            panic!("Expected Ci Sandbox command");
+        };
+        assert_eq!(args.from, PathBuf::from("./export"));
+        assert_eq!(args.testsuites, vec!["Smoke", "Orders"]);
+        assert_eq!(args.prefix, "ci");
+        assert!(args.sandbox_profile.is_none());
+        assert!(!args.keep);
+
+        assert!(Cli::try_parse_from(["vqx", "ci", "sandbox"]).is_err());
+    }
+
+    #[test]
+    fn test_dump_load_commands() {
+        let cli = Cli::parse_from(["vqx", "dump", "types", "Order", "order.json"]);
+        assert!(!cli.command.is_destructive());
+        if let Commands::Dump(args) = cli.command {
+            assert_eq!(args.resource, "types");
+            assert_eq!(args.name, "Order");
+            assert_eq!(args.file, PathBuf::from("order.json"));
+            assert!(args.normalize);
+        } else {
+            panic!("Expected Dump command");
+        }
+
+        let cli = Cli::parse_from(["vqx", "load", "types", "order.json", "--yes"]);
+        assert!(cli.command.is_destructive());
+        if let Commands::Load(args) = cli.command {
+            assert_eq!(args.file, PathBuf::from("order.json"));
+            assert!(args.yes);
+            assert!(!args.no_validate);
+        } else {
+            panic!("Expected Load command");
+        }
+    }
+
+    #[test]
+    fn test_chunk_auto() {
+        let cli = Cli::parse_from(["vqx", "export", "data", "--chunk", "auto"]);
+        if let Commands::Export(args) = cli.command {
+            assert_eq!(args.chunk, Some(ChunkSize::Auto));
+        } else {
+            panic!("Expected Export command");
+        }
+
+        let cli = Cli::parse_from(["vqx", "import", "--chunk", "500"]);
+        if let Commands::Import(args) = cli.command {
+            assert_eq!(args.chunk, Some(ChunkSize::Fixed(500)));
+        } else {
+            panic!("Expected Import command");
+        }
+        assert!(Cli::try_parse_from(["vqx", "import", "--chunk", "lots"]).is_err());
+    }
+
+    #[test]
+    fn test_sync_watch_command() {
+        let cli = Cli::parse_from(["vqx", "sync", "watch", "-d", "./src"]);
+        if let Commands::Sync(SyncCommands::Watch(args)) = cli.command {
+            assert_eq!(args.directory, PathBuf::from("./src"));
+            assert_eq!(args.debounce_ms, 500);
+            assert!(!args.force);
+            assert!(args.lock.wait.is_none());
+        } else {
+            panic!("Expected Sync Watch command");
+        }
+    }
+
+    #[test]
+    fn test_schema_reports_command() {
+        let cli = Cli::parse_from(["vqx", "schema", "reports", "diff"]);
+        if let Commands::Schema(SchemaCommands::Reports(args)) = cli.command {
+            assert_eq!(args.name.as_deref(), Some("diff"));
+        } else {
+            panic!("Expected Schema command");
+        }
+    }
+
+    #[test]
+    fn test_docs_generate_command() {
+        let cli = Cli::parse_from(["vqx", "docs", "generate", "--format", "markdown"]);
+        if let Commands::Docs(DocsCommands::Generate(args)) = cli.command {
+            assert!(matches!(args.format, DocsFormat::Markdown));
+            assert_eq!(args.out, PathBuf::from("docs"));
+        } else {
+            panic!("Expected Docs command");
+        }
+    }
+
+    #[test]
+    fn test_needs_server() {
+        let cli = Cli::parse_from(["vqx", "--offline", "export"]);
+        assert!(cli.offline);
+        assert!(cli.command.needs_server());
+        assert_eq!(cli.command.operation(), "export");
+
+        let local = Cli::parse_from(["vqx", "diff", "./a", "./b"]);
+        assert!(!local.command.needs_server());
+        let cli = Cli::parse_from(["vqx", "doctor", "--test-connection"]);
+        assert!(cli.command.needs_server());
+        let cli = Cli::parse_from(["vqx", "find", "types", "Order"]);
+        assert!(cli.command.needs_server());
+    }
+
+    #[test]
+    fn test_explain_command() {
+        let cli = Cli::parse_from(["vqx", "explain", "sync"]);
+        if let Commands::Explain(args) = cli.command {
+            assert_eq!(args.command, ExplainTopic::Sync);
+        } else {
+            panic!("Expected Explain command");
+        }
+        assert!(Cli::try_parse_from(["vqx", "explain", "frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn test_triage_command() {
+        let cli = Cli::parse_from(["vqx", "triage", "-o", "bundle.tar.gz"]);
+        if let Commands::Triage(args) = cli.command {
+            assert_eq!(args.out, Some(PathBuf::from("bundle.tar.gz")));
+            assert!(!args.test_connection);
+            assert_eq!(args.log_lines, 500);
+        } else {
+            panic!("Expected Triage command");
+        }
     }
 
     #[test]
     fn test_external_command() {
-        let cli = Cli::parse_from(["vqx", "list", "types"]);
+        let cli = Cli::parse_from(["vqx", "find", "procedures", "MyProc"]);
         if let Commands::External(args) = cli.command {
-            assert_eq!(args, vec!["list", "types"]);
+            assert_eq!(args, vec!["find", "procedures", "MyProc"]);
         } else {
             panic!("Expected External command");
         }
@@ -0,0 +1,211 @@
+//! Approve command implementation
+//!
+//! Second half of `sync push --require-approval`: lists pending push plans
+//! and records an approval or rejection for one of them, signed with the
+//! approver's key (`[approval] signing_key` or `VQX_APPROVAL_KEY`). An
+//! approval also prints a token that can be passed to
+//! `sync push --approval-token` on a machine that does not share the
+//! approvals directory. See [`crate::approval`].
+
+use crate::approval::{self, ApprovalRequest, ApprovalStore, SigningKey};
+use crate::cli::{ApproveArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::prompt;
+use crate::report;
+use crate::secure_file;
+use crate::theme::style;
+use serde::Serialize;
+use std::path::Path;
+
+/// Result of approve
+#[derive(Debug, Serialize)]
+pub struct ApproveResult {
+    pub success: bool,
+    /// Requests waiting for a decision (without a hash)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pending: Vec<ApprovalRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request: Option<ApprovalRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved: Option<bool>,
+    /// Token for `sync push --approval-token`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// Public key of a key created with `--generate-key`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+/// Run approve command
+pub fn run(args: &ApproveArgs, config: &Config, output_format: OutputFormat) -> Result<bool> {
+    if let Some(ref path) = args.generate_key {
+        let result = ApproveResult {
+            success: true,
+            pending: vec![],
+            request: None,
+            approved: None,
+            token: None,
+            public_key: Some(generate_key(path)?),
+        };
+        if output_format.is_machine_readable() {
+            println!("{}", report::render(&result, output_format)?);
+        } else {
+            println!(
+                "{} Created approver key {}",
+                style("✓").green(),
+                path.display()
+            );
+            println!(
+                "  Public key: {}",
+                style(result.public_key.as_deref().unwrap_or("")).bold()
+            );
+            println!();
+            println!("  Add the public key to [approval.trusted_keys] on the pushing side,");
+            println!("  and keep the key file away from it.");
+            println!();
+        }
+        return Ok(true);
+    }
+
+    let store = ApprovalStore::new(config)?;
+
+    let Some(ref hash) = args.hash else {
+        let result = ApproveResult {
+            success: true,
+            pending: store.pending(),
+            request: None,
+            approved: None,
+            token: None,
+            public_key: None,
+        };
+        if output_format.is_machine_readable() {
+            println!("{}", report::render(&result, output_format)?);
+        } else {
+            display_pending(&result.pending);
+        }
+        return Ok(true);
+    };
+
+    let key = SigningKey::load(&config.approval)?.ok_or_else(|| {
+        VqxError::Other(format!(
+            "No approver key: set [approval] signing_key or {} (create one with `vqx approve --generate-key <FILE>`)",
+            approval::KEY_ENV
+        ))
+    })?;
+    let request = store.find(hash)?;
+    let approved = !args.reject;
+    if !output_format.is_machine_readable() {
+        display_request(&request);
+    }
+
+    if !args.yes {
+        if output_format.is_machine_readable() {
+            return Err(VqxError::DestructiveOperationNotConfirmed {
+                operation: "approve".to_string(),
+            });
+        }
+        let verb = if approved { "Approve" } else { "Reject" };
+        let confirmed = prompt::confirm(
+            format!("{} plan {}?", verb, request.hash),
+            "Approve",
+            "--yes",
+        )?;
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Approve".to_string(),
+            });
+        }
+    }
+
+    let decision = store.decide(&request, approved, &approval::current_user(), &key)?;
+    let token = approved.then(|| decision.signature.clone());
+
+    let result = ApproveResult {
+        success: true,
+        pending: vec![],
+        request: Some(request),
+        approved: Some(decision.approved),
+        token,
+        public_key: None,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        if approved {
+            println!("{} Approved plan {}", style("✓").green(), decision.hash);
+        } else {
+            println!("{} Rejected plan {}", style("✗").red(), decision.hash);
+        }
+        if let Some(ref token) = result.token {
+            println!("  Token: {}", style(token).bold());
+        }
+        println!();
+    }
+
+    Ok(result.success)
+}
+
+/// Write a new approver key to `path` and return its public key
+fn generate_key(path: &Path) -> Result<String> {
+    if path.exists() {
+        return Err(VqxError::Other(format!(
+            "{} already exists; refusing to overwrite an approver key",
+            path.display()
+        )));
+    }
+    let content = SigningKey::generate()?;
+    let public_key = SigningKey::from_hex(&content)?.public_key();
+    secure_file::write_private(path, &content).map_err(|_| VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    })?;
+    Ok(public_key)
+}
+
+/// Display pending requests
+fn display_pending(pending: &[ApprovalRequest]) {
+    println!();
+    println!("{}", style("Pending Approvals").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    if pending.is_empty() {
+        println!("  No pushes are waiting for approval");
+    }
+    for r in pending {
+        println!(
+            "  {}  {} → {} ({} file(s)) by {} at {}",
+            style(&r.hash).bold(),
+            r.operation,
+            style(&r.profile).green(),
+            r.files.len(),
+            r.requested_by,
+            r.requested_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+    }
+    println!();
+}
+
+/// Display one request before the decision
+fn display_request(request: &ApprovalRequest) {
+    println!();
+    println!("{}", style("Approval Request").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Plan:      {}", style(&request.hash).bold());
+    println!("  Operation: {}", request.operation);
+    println!("  Profile:   {}", style(&request.profile).green());
+    println!("  Server:    {}", request.url);
+    if let Some(ref namespace) = request.namespace {
+        println!("  Namespace: {}", namespace);
+    }
+    println!("  Directory: {}", request.directory.display());
+    println!(
+        "  Requested: by {} at {}",
+        request.requested_by,
+        request.requested_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!();
+    for file in &request.files {
+        println!("  {}", file);
+    }
+    println!();
+}
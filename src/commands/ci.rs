@@ -0,0 +1,519 @@
+//! CI command implementation (vqx extension)
+//!
+//! `ci sandbox` runs integration tests against a namespace that exists only
+//! for the run:
+//!
+//! 1. Create a temporary namespace (`<prefix>_<timestamp>_<suffix>`) with the
+//!    `-s` profile, which needs username/password credentials since tokens
+//!    are bound to one namespace, or take the namespace of
+//!    `--sandbox-profile`.
+//! 2. Import the export directory (metadata, then `data/` if present), with
+//!    `{{vars.*}}` rendered and extracted code embedded like `import` does.
+//! 3. Run the test suites one after another and write JUnit XML.
+//! 4. Tear down: delete the temporary namespace, or the imported resources
+//!    of the sandbox profile. This happens when the import or the tests
+//!    fail and on Ctrl-C too; only `--keep` skips it.
+
+use crate::cli::{CiSandboxArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::lock::OperationLock;
+use crate::normalizer;
+use crate::profile::ProfileManager;
+use crate::report;
+use crate::template;
+use crate::testreport::{self, JunitSuite, TestCase, TestSummary};
+use crate::theme::style;
+use crate::timing::RESOURCE_TYPES;
+use crate::underlying::{CliOptions, ExecResult, UnderlyingCli};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
+use tempfile::NamedTempFile;
+use tracing::info;
+use walkdir::WalkDir;
+
+/// One test suite run in the sandbox
+#[derive(Debug, Serialize)]
+pub struct SandboxSuite {
+    pub name: String,
+    pub success: bool,
+    pub duration_ms: u64,
+    pub summary: TestSummary,
+    pub tests: Vec<TestCase>,
+    /// The suite could not be run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// How the sandbox was removed
+#[derive(Debug, Serialize)]
+pub struct Teardown {
+    /// Left in place with `--keep`
+    pub kept: bool,
+    /// Resources deleted from a sandbox profile
+    pub deleted: usize,
+    pub errors: Vec<String>,
+}
+
+/// Result of ci sandbox
+#[derive(Debug, Serialize)]
+pub struct SandboxResult {
+    pub success: bool,
+    /// Profile whose credentials were used
+    pub profile: String,
+    pub namespace: Option<String>,
+    /// The namespace was created for this run
+    pub temporary: bool,
+    /// Import or interruption error; the suites did not run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub suites: Vec<SandboxSuite>,
+    pub summary: TestSummary,
+    pub teardown: Teardown,
+}
+
+/// Run ci sandbox
+pub async fn sandbox(
+    args: &CiSandboxArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<SandboxResult> {
+    let text = !output_format.is_machine_readable();
+    if !args.from.is_dir() {
+        return Err(VqxError::Other(format!(
+            "Not a directory: {}",
+            args.from.display()
+        )));
+    }
+
+    let manager = ProfileManager::new()?;
+    let profile_name = args
+        .sandbox_profile
+        .as_deref()
+        .or(profile_name)
+        .unwrap_or(&manager.store().default_profile)
+        .to_string();
+    let profile = manager.get_resolved(&profile_name)?;
+    if !profile.has_auth() {
+        return Err(VqxError::ProfileInvalid {
+            message: format!(
+                "Profile '{}' has no authentication configured",
+                profile_name
+            ),
+        });
+    }
+
+    let import_cli = cli_for(config, "import");
+    let admin_options = CliOptions::from_profile(&profile);
+
+    // The sandbox: a new namespace, or the sandbox profile's own
+    let temporary = args.sandbox_profile.is_none();
+    let (namespace, _lock) = if temporary {
+        if profile.password.is_none() {
+            return Err(VqxError::Other(format!(
+                "Creating a namespace needs username/password credentials, but profile '{}' \
+                 uses a token; use --sandbox-profile to test in an existing namespace",
+                profile_name
+            )));
+        }
+        let namespace = namespace_name(&args.prefix, Utc::now());
+        create_namespace(&import_cli, &admin_options, &namespace).await?;
+        if text {
+            println!(
+                "{} Created namespace {}",
+                style("✓").green(),
+                style(&namespace).bold()
+            );
+        }
+        (Some(namespace), None)
+    } else {
+        // Everything imported is deleted afterwards
+        if !profile.protection.is_open() {
+            return Err(VqxError::Other(format!(
+                "Sandbox profile '{}' is protected; ci sandbox deletes what it imports",
+                profile_name
+            )));
+        }
+        let lock = OperationLock::acquire(
+            "ci sandbox",
+            &profile_name,
+            &profile.url,
+            profile.namespace.as_deref(),
+            &args.lock,
+        )
+        .await?;
+        (profile.namespace.clone(), Some(lock))
+    };
+
+    let mut options = admin_options.clone();
+    if temporary {
+        options.namespace = namespace.clone();
+    }
+
+    let work = async {
+        seed(
+            args,
+            config,
+            &import_cli,
+            &options,
+            &profile_name,
+            &profile.vars,
+            text,
+        )
+        .await?;
+        Ok::<_, VqxError>(run_suites(args, config, &options, text).await)
+    };
+    let (error, suites) = tokio::select! {
+        outcome = work => match outcome {
+            Ok(suites) => (None, suites),
+            Err(e) => (Some(e.to_string()), Vec::new()),
+        },
+        _ = tokio::signal::ctrl_c() => (Some("Interrupted".to_string()), Vec::new()),
+    };
+
+    // Suites that did not run still show up in the report, as errors
+    let suites = if let Some(ref error) = error {
+        if text {
+            println!("{} {}", style("✗").red().bold(), style(error).red());
+        }
+        args.testsuites
+            .iter()
+            .map(|name| SandboxSuite {
+                name: name.clone(),
+                success: false,
+                duration_ms: 0,
+                summary: TestSummary::default(),
+                tests: Vec::new(),
+                error: Some(format!("Sandbox setup failed: {}", error)),
+            })
+            .collect()
+    } else {
+        suites
+    };
+
+    let teardown = if args.keep {
+        Teardown {
+            kept: true,
+            deleted: 0,
+            errors: Vec::new(),
+        }
+    } else if temporary {
+        let namespace = namespace.as_deref().unwrap_or_default();
+        let result = import_cli
+            .delete(&admin_options, "namespaces", namespace)
+            .await;
+        Teardown {
+            kept: false,
+            deleted: 0,
+            errors: failure(result, &format!("namespaces/{}", namespace))
+                .into_iter()
+                .collect(),
+        }
+    } else {
+        delete_imported(&import_cli, &options, &args.from).await
+    };
+
+    // Written after the teardown, so a bad path cannot leave the sandbox behind
+    if let Some(ref path) = args.report {
+        let junit: Vec<JunitSuite> = suites
+            .iter()
+            .map(|suite| JunitSuite {
+                name: &suite.name,
+                tests: &suite.tests,
+                error: suite.error.as_deref(),
+                elapsed_ms: suite.duration_ms,
+            })
+            .collect();
+        std::fs::write(path, testreport::to_junit_xml(&junit)).map_err(|_| {
+            VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            }
+        })?;
+    }
+
+    let tests: Vec<TestCase> = suites.iter().flat_map(|s| s.tests.clone()).collect();
+    let result = SandboxResult {
+        success: error.is_none() && suites.iter().all(|s| s.success) && teardown.errors.is_empty(),
+        profile: profile_name,
+        namespace,
+        temporary,
+        error,
+        summary: TestSummary::of(&tests),
+        suites,
+        teardown,
+    };
+
+    if text {
+        display_result(&result, args);
+    } else {
+        println!("{}", report::render(&result, output_format)?);
+    }
+    Ok(result)
+}
+
+fn cli_for(config: &Config, command: &str) -> UnderlyingCli {
+    UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for(command))
+        .with_retry_policy(config.retry_policy())
+}
+
+/// Name of a temporary namespace, unique per run
+fn namespace_name(prefix: &str, now: DateTime<Utc>) -> String {
+    let suffix = std::process::id() ^ now.timestamp_subsec_nanos();
+    format!(
+        "{}_{}_{:04x}",
+        prefix,
+        now.format("%Y%m%d%H%M%S"),
+        suffix & 0xffff
+    )
+}
+
+async fn create_namespace(cli: &UnderlyingCli, options: &CliOptions, name: &str) -> Result<()> {
+    let mut file = NamedTempFile::new()?;
+    file.write_all(serde_json::json!({ "name": name }).to_string().as_bytes())?;
+    let result = cli
+        .insert(options, "namespaces", &file.path().to_string_lossy(), false)
+        .await?;
+    if !result.success() {
+        return Err(VqxError::Other(format!(
+            "Could not create namespace {}: {}",
+            name,
+            result.stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Import the export directory into the sandbox
+async fn seed(
+    args: &CiSandboxArgs,
+    config: &Config,
+    cli: &UnderlyingCli,
+    options: &CliOptions,
+    profile_name: &str,
+    vars: &std::collections::BTreeMap<String, String>,
+    text: bool,
+) -> Result<()> {
+    let rendered = template::render_copy(&args.from, profile_name, vars)?;
+    let rendered_dir = rendered
+        .as_ref()
+        .map_or(args.from.clone(), |r| r.path().to_path_buf());
+    let staging = normalizer::stage_for_import(&rendered_dir)?;
+    let import_dir = staging
+        .as_ref()
+        .map_or(rendered_dir, |s| s.path().to_path_buf());
+
+    for import_type in ["metadata", "data"] {
+        if import_type == "data" && !import_dir.join("data").is_dir() {
+            continue;
+        }
+        info!(import_type, dir = %import_dir.display(), "Seeding sandbox");
+        let result = cli
+            .import(
+                options,
+                Some(import_type),
+                Some(&import_dir),
+                Some(config.default_chunk_size),
+                None,
+                None,
+                None,
+            )
+            .await?;
+        if !result.success() {
+            return Err(VqxError::Other(format!(
+                "Import of {} failed: {}",
+                import_type,
+                result.stderr.trim()
+            )));
+        }
+        if text {
+            println!("{} Imported {}", style("✓").green(), import_type);
+        }
+    }
+    Ok(())
+}
+
+async fn run_suites(
+    args: &CiSandboxArgs,
+    config: &Config,
+    options: &CliOptions,
+    text: bool,
+) -> Vec<SandboxSuite> {
+    let cli = cli_for(config, "run");
+    let mut suites = Vec::new();
+    for name in &args.testsuites {
+        let started = Instant::now();
+        let (output, error) = match cli.run_testsuite(options, name, None).await {
+            Ok(result) if result.success() => (result.stdout, None),
+            Ok(result) => {
+                let error = if result.stderr.trim().is_empty() {
+                    format!("CLI exited with code {}", result.code())
+                } else {
+                    result.stderr.trim().to_string()
+                };
+                (result.stdout, Some(error))
+            }
+            Err(e) => (String::new(), Some(e.to_string())),
+        };
+        let tests = testreport::parse_output(&output);
+        let summary = TestSummary::of(&tests);
+        let suite = SandboxSuite {
+            name: name.clone(),
+            success: error.is_none() && !summary.has_failures(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            summary,
+            tests,
+            // A non-zero exit with failed tests is a test failure, not an error
+            error: error.filter(|_| !summary.has_failures()),
+        };
+        if text {
+            let mark = if suite.success {
+                style("✓").green()
+            } else {
+                style("✗").red()
+            };
+            println!(
+                "{} {} ({} tests, {:.1}s)",
+                mark,
+                suite.name,
+                suite.summary.total(),
+                suite.duration_ms as f64 / 1000.0
+            );
+        }
+        suites.push(suite);
+    }
+    suites
+}
+
+/// Error message of a failed CLI call
+fn failure(result: Result<ExecResult>, what: &str) -> Option<String> {
+    match result {
+        Ok(result) if result.success() => None,
+        Ok(result) if result.stderr.trim().is_empty() => {
+            Some(format!("{}: CLI exited with code {}", what, result.code()))
+        }
+        Ok(result) => Some(format!("{}: {}", what, result.stderr.trim())),
+        Err(e) => Some(format!("{}: {}", what, e)),
+    }
+}
+
+/// Resources of an export directory as (type, name), dependents first
+fn imported_resources(dir: &Path) -> Vec<(String, String)> {
+    let mut resources = Vec::new();
+    // Rules and procedures refer to types and sources, so go backwards
+    for resource_type in RESOURCE_TYPES.iter().rev().filter(|t| **t != "data") {
+        let type_dir = dir.join(resource_type);
+        let mut names: Vec<String> = WalkDir::new(&type_dir)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+            .filter_map(|e| {
+                let name = std::fs::read_to_string(e.path())
+                    .ok()
+                    .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                    .and_then(|v| v.get("name").and_then(Value::as_str).map(String::from));
+                name.or_else(|| Some(e.path().file_stem()?.to_string_lossy().to_string()))
+            })
+            .collect();
+        names.sort();
+        resources.extend(names.into_iter().map(|n| (resource_type.to_string(), n)));
+    }
+    resources
+}
+
+/// Delete what the export directory put into a sandbox profile
+async fn delete_imported(cli: &UnderlyingCli, options: &CliOptions, dir: &Path) -> Teardown {
+    let mut teardown = Teardown {
+        kept: false,
+        deleted: 0,
+        errors: Vec::new(),
+    };
+    for (resource_type, name) in imported_resources(dir) {
+        let result = cli.delete(options, &resource_type, &name).await;
+        match failure(result, &format!("{}/{}", resource_type, name)) {
+            None => teardown.deleted += 1,
+            Some(error) => teardown.errors.push(error),
+        }
+    }
+    teardown
+}
+
+fn display_result(result: &SandboxResult, args: &CiSandboxArgs) {
+    println!();
+    let target = result.namespace.as_deref().unwrap_or(&result.profile);
+    if result.teardown.kept {
+        println!(
+            "{} Sandbox {} kept (--keep)",
+            style("!").yellow(),
+            style(target).bold()
+        );
+    } else if result.teardown.errors.is_empty() {
+        println!("{} Sandbox {} torn down", style("✓").green(), target);
+    } else {
+        println!(
+            "{} Sandbox {} not fully torn down:",
+            style("✗").red().bold(),
+            style(target).bold()
+        );
+        for error in &result.teardown.errors {
+            println!("    {}", style(error).red());
+        }
+    }
+
+    let summary = result.summary;
+    println!(
+        "  Tests: {} passed, {} failed, {} errors, {} skipped",
+        summary.passed, summary.failed, summary.errors, summary.skipped
+    );
+    if let Some(ref path) = args.report {
+        println!("  JUnit report: {}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_namespace_name() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap();
+        let name = namespace_name("ci", now);
+        assert!(name.starts_with("ci_20261016093000_"), "{}", name);
+        assert_eq!(name.len(), "ci_20261016093000_".len() + 4);
+    }
+
+    #[test]
+    fn test_imported_resources_order() {
+        let dir = tempfile::tempdir().unwrap();
+        for (resource_type, file, content) in [
+            ("types", "Order.json", r#"{"name": "Order"}"#),
+            ("procedures", "Create.json", r#"{"name": "Orders.Create"}"#),
+            ("rules", "OnOrder.json", "{}"),
+            ("data", "Order.json", "[]"),
+        ] {
+            std::fs::create_dir_all(dir.path().join(resource_type)).unwrap();
+            std::fs::write(dir.path().join(resource_type).join(file), content).unwrap();
+        }
+
+        let resources = imported_resources(dir.path());
+        let pairs: Vec<(&str, &str)> = resources
+            .iter()
+            .map(|(t, n)| (t.as_str(), n.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("rules", "OnOrder"),
+                ("procedures", "Orders.Create"),
+                ("types", "Order"),
+            ]
+        );
+    }
+}
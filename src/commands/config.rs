@@ -0,0 +1,529 @@
+//! Config command implementation
+//!
+//! Reads and changes config.toml (or the `--config` file) from the command
+//! line. Keys are dotted paths into the file (`logging.level`,
+//! `normalization.excluded_fields`); segments containing dots or other
+//! special characters are quoted (`policy."dev-*".deny`), and array elements
+//! are addressed by index (`schedule.0.cron`).
+//!
+//! `get` shows the effective value, defaults included. `set` only writes the
+//! keys present in the file and refuses values that would make it invalid.
+//! Like every command that writes config.toml, it does not keep comments.
+
+use crate::cli::{ConfigCommands, ConfigGetArgs, ConfigSetArgs, OutputFormat};
+use crate::config::{Config, NotifyKind};
+use crate::error::{Result, VqxError};
+use crate::ignore::IgnoreRules;
+use crate::policy::Policy;
+use crate::report;
+use crate::schedule::CronSchedule;
+use crate::theme::style;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"];
+
+/// Result of config get and config set
+#[derive(Debug, Serialize)]
+pub struct ConfigValueResult {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub value: serde_json::Value,
+}
+
+/// Result of config path
+#[derive(Debug, Serialize)]
+pub struct ConfigPathResult {
+    pub success: bool,
+    pub path: PathBuf,
+    pub exists: bool,
+}
+
+/// Result of config validate (and config edit)
+#[derive(Debug, Serialize)]
+pub struct ConfigValidateResult {
+    pub success: bool,
+    pub path: PathBuf,
+    pub exists: bool,
+    pub errors: Vec<String>,
+    /// Unknown keys, which are ignored
+    pub warnings: Vec<String>,
+}
+
+/// Run a config subcommand
+///
+/// `config` is the loaded configuration (with `--cli`/`--timeout` applied)
+/// and `config_path` the `--config` file, if given.
+pub fn run(
+    cmd: &ConfigCommands,
+    config: &Config,
+    config_path: Option<&Path>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => Config::config_file_path()?,
+    };
+    match cmd {
+        ConfigCommands::Get(args) => get(args, config, output_format),
+        ConfigCommands::Set(args) => set(args, &path, output_format),
+        ConfigCommands::Edit => edit(&path, output_format),
+        ConfigCommands::Path => {
+            let result = ConfigPathResult {
+                success: true,
+                exists: path.exists(),
+                path,
+            };
+            if output_format.is_machine_readable() {
+                println!("{}", report::render(&result, output_format)?);
+            } else {
+                println!("{}", result.path.display());
+            }
+            Ok(true)
+        }
+        ConfigCommands::Validate => {
+            let result = validate(&path);
+            report_validation(&result, output_format)?;
+            Ok(result.success)
+        }
+    }
+}
+
+fn get(args: &ConfigGetArgs, config: &Config, output_format: OutputFormat) -> Result<bool> {
+    let effective = to_toml(config)?;
+    let value = match args.key {
+        Some(ref key) => lookup(&effective, &parse_key(key)?)
+            .cloned()
+            .ok_or_else(|| VqxError::InvalidConfigKey {
+                key: key.clone(),
+                message: "no such setting".to_string(),
+            })?,
+        None => effective,
+    };
+
+    if output_format.is_machine_readable() {
+        let result = ConfigValueResult {
+            success: true,
+            key: args.key.clone(),
+            value: serde_json::to_value(&value)?,
+        };
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        print!("{}", display_value(&value));
+    }
+    Ok(true)
+}
+
+fn set(args: &ConfigSetArgs, path: &Path, output_format: OutputFormat) -> Result<bool> {
+    let segments = parse_key(&args.key)?;
+    let invalid = |message: String| VqxError::InvalidConfigKey {
+        key: args.key.clone(),
+        message,
+    };
+
+    let mut doc = read_table(path)?;
+    let current = to_toml(&parse_config(&doc).map_err(&invalid)?)?;
+    let value = parse_value(&args.value, lookup(&current, &segments));
+    insert(&mut doc, &segments, value.clone()).map_err(&invalid)?;
+
+    // The new value must deserialize, and to a setting that exists
+    let config = parse_config(&doc).map_err(&invalid)?;
+    if lookup(&to_toml(&config)?, &segments).is_none() {
+        return Err(invalid("no such setting".to_string()));
+    }
+    let errors = check_settings(&config);
+    if let Some(error) = errors.first() {
+        return Err(invalid(error.clone()));
+    }
+
+    write_table(path, &doc)?;
+
+    if output_format.is_machine_readable() {
+        let result = ConfigValueResult {
+            success: true,
+            key: Some(args.key.clone()),
+            value: serde_json::to_value(&value)?,
+        };
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        println!(
+            "{} {} = {}",
+            style("✓").green(),
+            style(&args.key).bold(),
+            value
+        );
+    }
+    Ok(true)
+}
+
+fn edit(path: &Path, output_format: OutputFormat) -> Result<bool> {
+    if !path.exists() {
+        // Start from the defaults so the available settings are visible
+        Config::default().save_to(path)?;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|e| VqxError::Other(format!("Cannot start editor '{}': {}", editor, e)))?;
+    if !status.success() {
+        return Err(VqxError::Other(format!(
+            "Editor '{}' exited with {}",
+            editor, status
+        )));
+    }
+
+    let result = validate(path);
+    report_validation(&result, output_format)?;
+    Ok(result.success)
+}
+
+/// Check a config file without loading it into vqx
+pub fn validate(path: &Path) -> ConfigValidateResult {
+    let mut result = ConfigValidateResult {
+        success: false,
+        path: path.to_path_buf(),
+        exists: path.exists(),
+        errors: Vec::new(),
+        warnings: Vec::new(),
+    };
+    if !result.exists {
+        result.success = true;
+        result
+            .warnings
+            .push("The file does not exist; defaults apply".to_string());
+        return result;
+    }
+
+    let doc = match read_table(path) {
+        Ok(doc) => doc,
+        Err(e) => {
+            result.errors.push(e.to_string());
+            return result;
+        }
+    };
+    let config = match parse_config(&doc) {
+        Ok(config) => config,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+    if let Ok(Value::Table(known)) = to_toml(&config) {
+        unknown_keys(&doc, &known, "", &mut result.warnings);
+    }
+    result.errors = check_settings(&config);
+    result.success = result.errors.is_empty();
+    result
+}
+
+/// Errors in settings that deserialize but cannot work
+fn check_settings(config: &Config) -> Vec<String> {
+    let mut errors = Vec::new();
+    if !LOG_LEVELS.contains(&config.logging.level.as_str()) {
+        errors.push(format!(
+            "logging.level '{}' is not one of {}",
+            config.logging.level,
+            LOG_LEVELS.join(", ")
+        ));
+    }
+    if let Err(e) = Policy::compile(&config.policy) {
+        errors.push(format!("policy: {}", e));
+    }
+    if let Err(e) = IgnoreRules::parse(&config.diff.ignore) {
+        errors.push(format!("diff.ignore: {}", e));
+    }
+    for job in &config.schedule {
+        if let Err(e) = job.cron.parse::<CronSchedule>() {
+            errors.push(format!("schedule '{}': {}", job.id, e));
+        }
+        if let Err(e) = super::schedule::check_command(&job.command) {
+            errors.push(format!("schedule '{}': {}", job.id, e));
+        }
+    }
+    for (name, operation) in &config.serve.operations {
+        if operation.command.is_empty() {
+            errors.push(format!("serve.operations.{}: command is empty", name));
+        }
+    }
+    for (i, channel) in config.notify.channels.iter().enumerate() {
+        if channel.kind != NotifyKind::Desktop && channel.url.is_none() {
+            errors.push(format!("notify.channels.{}: url is required", i));
+        }
+    }
+    errors
+}
+
+/// Keys of `doc` that are missing from `known` (dropped when parsing)
+fn unknown_keys(doc: &Table, known: &Table, prefix: &str, warnings: &mut Vec<String>) {
+    for (key, value) in doc {
+        let segment = if key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            key.clone()
+        } else {
+            format!("\"{}\"", key)
+        };
+        let path = if prefix.is_empty() {
+            segment
+        } else {
+            format!("{}.{}", prefix, segment)
+        };
+        match (value, known.get(key)) {
+            (Value::Table(doc), Some(Value::Table(known))) => {
+                unknown_keys(doc, known, &path, warnings)
+            }
+            (Value::Array(doc), Some(Value::Array(known))) => {
+                for (i, (doc, known)) in doc.iter().zip(known).enumerate() {
+                    if let (Value::Table(doc), Value::Table(known)) = (doc, known) {
+                        unknown_keys(doc, known, &format!("{}.{}", path, i), warnings);
+                    }
+                }
+            }
+            // Empty sections are left out when serializing
+            (Value::Table(t), None) if t.is_empty() => {}
+            (Value::Array(a), None) if a.is_empty() => {}
+            (_, None) => warnings.push(format!("Unknown key '{}' is ignored", path)),
+            _ => {}
+        }
+    }
+}
+
+fn report_validation(result: &ConfigValidateResult, output_format: OutputFormat) -> Result<()> {
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(result, output_format)?);
+        return Ok(());
+    }
+    for warning in &result.warnings {
+        println!("{} {}", style("!").yellow(), warning);
+    }
+    for error in &result.errors {
+        println!("{} {}", style("✗").red(), error);
+    }
+    if result.success {
+        println!("{} {} is valid", style("✓").green(), result.path.display());
+    } else {
+        println!(
+            "{} {} has {} error(s)",
+            style("✗").red().bold(),
+            result.path.display(),
+            result.errors.len()
+        );
+    }
+    Ok(())
+}
+
+/// Split a dotted key, honoring double-quoted segments
+fn parse_key(key: &str) -> Result<Vec<String>> {
+    let invalid = |message: &str| VqxError::InvalidConfigKey {
+        key: key.to_string(),
+        message: message.to_string(),
+    };
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut was_quoted = false;
+    for c in key.trim().chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                was_quoted = true;
+            }
+            '.' if !quoted => {
+                if current.is_empty() && !was_quoted {
+                    return Err(invalid("empty segment"));
+                }
+                segments.push(std::mem::take(&mut current));
+                was_quoted = false;
+            }
+            c => current.push(c),
+        }
+    }
+    if quoted {
+        return Err(invalid("unterminated quote"));
+    }
+    if current.is_empty() && !was_quoted {
+        return Err(invalid("empty segment"));
+    }
+    segments.push(current);
+    Ok(segments)
+}
+
+fn lookup<'a>(value: &'a Value, segments: &[String]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match value {
+            Value::Table(table) => table.get(segment),
+            Value::Array(array) => segment.parse::<usize>().ok().and_then(|i| array.get(i)),
+            _ => None,
+        })
+}
+
+/// Read a command-line value as TOML, falling back to a string
+///
+/// Settings that are strings always take the text as is, so that e.g.
+/// `cli_path 123` does not become a number.
+fn parse_value(raw: &str, current: Option<&Value>) -> Value {
+    if matches!(current, Some(Value::String(_))) {
+        return Value::String(raw.to_string());
+    }
+    toml::from_str::<Table>(&format!("value = {}", raw))
+        .ok()
+        .and_then(|mut table| table.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// Set `segments` in `doc`, creating tables along the way
+///
+/// An array of tables (`[[schedule]]`) is entered by the index that follows
+/// it.
+fn insert(doc: &mut Table, segments: &[String], value: Value) -> std::result::Result<(), String> {
+    let (last, parents) = segments.split_last().ok_or("empty key")?;
+    let mut table = doc;
+    let mut parents = parents.iter();
+    while let Some(segment) = parents.next() {
+        let entry = match table
+            .entry(segment.clone())
+            .or_insert_with(|| Value::Table(Table::new()))
+        {
+            Value::Array(array) => {
+                let index = parents
+                    .next()
+                    .and_then(|i| i.parse::<usize>().ok())
+                    .ok_or_else(|| {
+                        format!("'{}' is a list; give the index of an entry", segment)
+                    })?;
+                array
+                    .get_mut(index)
+                    .ok_or_else(|| format!("'{}' has no entry {}", segment, index))?
+            }
+            entry => entry,
+        };
+        table = match entry {
+            Value::Table(t) => t,
+            _ => return Err(format!("'{}' is not a section", segment)),
+        };
+    }
+    table.insert(last.clone(), value);
+    Ok(())
+}
+
+fn read_table(path: &Path) -> Result<Table> {
+    if !path.exists() {
+        return Ok(Table::new());
+    }
+    let content = fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+        path: path.display().to_string(),
+    })?;
+    Ok(toml::from_str(&content)?)
+}
+
+fn write_table(path: &Path, doc: &Table) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = toml::to_string_pretty(doc).map_err(|e| VqxError::InvalidToml {
+        message: e.to_string(),
+    })?;
+    fs::write(path, content).map_err(|_| VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    })
+}
+
+fn parse_config(doc: &Table) -> std::result::Result<Config, String> {
+    Value::Table(doc.clone())
+        .try_into()
+        .map_err(|e: toml::de::Error| e.message().to_string())
+}
+
+fn to_toml(config: &Config) -> Result<Value> {
+    Value::try_from(config).map_err(|e| VqxError::InvalidToml {
+        message: e.to_string(),
+    })
+}
+
+/// A value as `config get` prints it
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("{}\n", s),
+        Value::Table(table) => toml::to_string_pretty(table).unwrap_or_default(),
+        value => format!("{}\n", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(parse_key("logging.level").unwrap(), ["logging", "level"]);
+        assert_eq!(
+            parse_key("policy.\"dev-*.x\".deny").unwrap(),
+            ["policy", "dev-*.x", "deny"]
+        );
+        assert!(parse_key("logging..level").is_err());
+        assert!(parse_key("policy.\"dev").is_err());
+    }
+
+    #[test]
+    fn test_set_value() {
+        let mut doc: Table = toml::from_str("cli_path = \"vantiq\"\n").unwrap();
+        let current = to_toml(&parse_config(&doc).unwrap()).unwrap();
+
+        let key = parse_key("normalization.excluded_fields").unwrap();
+        let value = parse_value(r#"["_id", "ars_*"]"#, lookup(&current, &key));
+        insert(&mut doc, &key, value).unwrap();
+        let key = parse_key("cli_path").unwrap();
+        let value = parse_value("123", lookup(&current, &key));
+        insert(&mut doc, &key, value).unwrap();
+        let key = parse_key("timeout_seconds").unwrap();
+        let value = parse_value("30", lookup(&current, &key));
+        insert(&mut doc, &key, value).unwrap();
+        assert!(insert(&mut doc, &parse_key("cli_path.x").unwrap(), Value::from(1)).is_err());
+
+        let config = parse_config(&doc).unwrap();
+        assert_eq!(config.normalization.excluded_fields, ["_id", "ars_*"]);
+        assert_eq!(config.cli_path, "123");
+        assert_eq!(config.timeout_seconds, 30);
+
+        // Wrong type
+        let key = parse_key("timeout_seconds").unwrap();
+        insert(&mut doc, &key, parse_value("soon", None)).unwrap();
+        assert!(parse_config(&doc).is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let doc: Table = toml::from_str(
+            "timeout_secs = 5\n[logging]\nlevel = \"info\"\ncolour = true\n\
+             [policy.\"dev-*\"]\ndeny = []\nallwo = [\"list\"]\n",
+        )
+        .unwrap();
+        let Value::Table(known) = to_toml(&parse_config(&doc).unwrap()).unwrap() else {
+            panic!("config is a table");
+        };
+        let mut warnings = Vec::new();
+        unknown_keys(&doc, &known, "", &mut warnings);
+        assert_eq!(
+            warnings,
+            [
+                "Unknown key 'logging.colour' is ignored",
+                "Unknown key 'policy.\"dev-*\".allwo' is ignored",
+                "Unknown key 'timeout_secs' is ignored"
+            ]
+        );
+    }
+}
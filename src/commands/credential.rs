@@ -0,0 +1,295 @@
+//! Credential command implementation
+//!
+//! Manages named credentials that several profiles can share through
+//! `credential = "<name>"`. Rotating a shared token with `credential set`
+//! updates every profile that references it.
+//!
+//! Secrets follow the same rules as profiles: with `--secure` they go to the
+//! keyring, otherwise they are stored in the profiles file. A credential
+//! created with `--secure` keeps its secrets in the keyring when rotated.
+//!
+//! Also enforces credential age limits (`max_credential_age_days`) before
+//! commands run: overdue credentials produce a warning, and profiles with
+//! `block_stale_credentials` refuse destructive commands.
+
+use crate::cli::{CredentialCommands, CredentialDeleteArgs, CredentialSetArgs, OutputFormat};
+use crate::error::{Result, VqxError};
+use crate::profile::{Credential, ProfileManager, ProfileStore};
+use crate::prompt;
+use crate::report;
+use crate::theme::style;
+use chrono::Utc;
+
+/// Run credential subcommand
+pub async fn run(cmd: &CredentialCommands, output_format: OutputFormat) -> Result<()> {
+    match cmd {
+        CredentialCommands::List => list(output_format).await,
+        CredentialCommands::Set(args) => set(args).await,
+        CredentialCommands::Delete(args) => delete(args).await,
+    }
+}
+
+/// List named credentials
+async fn list(output_format: OutputFormat) -> Result<()> {
+    let manager = ProfileManager::new()?;
+    let store = manager.store();
+
+    match output_format {
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => {
+            let credentials: Vec<_> = store
+                .credentials
+                .iter()
+                .map(|(name, credential)| {
+                    serde_json::json!({
+                        "name": name,
+                        "role": credential.role,
+                        "auth_type": credential.auth_type(),
+                        "secure": credential.use_secure_storage,
+                        "profiles": store.profiles_using(name),
+                    })
+                })
+                .collect();
+            if matches!(output_format, OutputFormat::Ndjson) {
+                print!("{}", report::to_ndjson(&credentials)?);
+            } else {
+                println!("{}", report::serialize(&credentials, output_format)?);
+            }
+        }
+        OutputFormat::Csv => {
+            println!("name,role,auth_type,secure,profiles");
+            for (name, credential) in &store.credentials {
+                println!(
+                    "{},{},{},{},{}",
+                    name,
+                    credential.role.as_deref().unwrap_or(""),
+                    credential.auth_type(),
+                    credential.use_secure_storage,
+                    store.profiles_using(name).join(" ")
+                );
+            }
+        }
+        OutputFormat::Text => {
+            println!();
+            println!("{}", style("Named Credentials").bold().cyan());
+            println!("{}", style("─".repeat(40)).dim());
+
+            if store.credentials.is_empty() {
+                println!("{}", style("No credentials configured.").dim());
+                println!();
+                println!(
+                    "Run '{}' to create one.",
+                    style("vqx credential set <name> --token <token>").green()
+                );
+            }
+
+            for (name, credential) in &store.credentials {
+                let role = credential
+                    .role
+                    .as_ref()
+                    .map(|r| format!(" [{}]", r))
+                    .unwrap_or_default();
+                println!(
+                    "  • {}{} ({})",
+                    style(name).bold(),
+                    style(role).yellow(),
+                    credential.auth_type()
+                );
+
+                let profiles = store.profiles_using(name);
+                if profiles.is_empty() {
+                    println!("      {}", style("not used by any profile").dim());
+                } else {
+                    println!("      {} {}", style("used by:").dim(), profiles.join(", "));
+                }
+            }
+            println!();
+        }
+    }
+
+    Ok(())
+}
+
+/// Create or update a named credential
+async fn set(args: &CredentialSetArgs) -> Result<()> {
+    let mut manager = ProfileManager::new()?;
+
+    let mut credential = manager
+        .store()
+        .credentials
+        .get(&args.name)
+        .cloned()
+        .unwrap_or_default();
+    let existed = manager.store().credentials.contains_key(&args.name);
+
+    if let Some(ref role) = args.role {
+        credential.role = Some(role.clone());
+    }
+    if let Some(ref username) = args.username {
+        credential.username = Some(username.clone());
+    }
+    if args.password.is_some() || args.token.is_some() {
+        credential.touch(Utc::now());
+    }
+    let secure = keeps_in_keyring(&credential, args.secure);
+    if let Some(ref password) = args.password {
+        if secure {
+            manager.set_credential_secret(&args.name, "password", password)?;
+            credential.use_secure_storage = true;
+            credential.password = None;
+        } else {
+            credential.password = Some(password.clone());
+        }
+    }
+    if let Some(ref token) = args.token {
+        if secure {
+            manager.set_credential_secret(&args.name, "token", token)?;
+            credential.use_secure_storage = true;
+            credential.token = None;
+        } else {
+            credential.token = Some(token.clone());
+        }
+    }
+    if let Some(ref desc) = args.description {
+        credential.description = Some(desc.clone());
+    }
+
+    manager.store_mut().set_credential(&args.name, credential);
+    manager.save()?;
+
+    println!(
+        "{} Credential '{}' {}.",
+        style("✓").green(),
+        style(&args.name).bold(),
+        if existed { "updated" } else { "saved" }
+    );
+
+    let profiles = manager.store().profiles_using(&args.name);
+    if existed && !profiles.is_empty() {
+        println!("  {} Applies to: {}", style("→").dim(), profiles.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Whether `credential set` stores secrets in the keyring
+///
+/// A credential whose secrets are in the keyring keeps them there: a new
+/// secret written to the file would be shadowed by the old keyring entry.
+fn keeps_in_keyring(credential: &Credential, secure: bool) -> bool {
+    secure || credential.use_secure_storage
+}
+
+/// Delete a named credential
+async fn delete(args: &CredentialDeleteArgs) -> Result<()> {
+    let mut manager = ProfileManager::new()?;
+    manager.store().get_credential(&args.name)?;
+
+    let profiles: Vec<String> = manager
+        .store()
+        .profiles_using(&args.name)
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    if !profiles.is_empty() && !args.force {
+        return Err(VqxError::Other(format!(
+            "Credential '{}' is used by profile(s): {}. Detach them first or use --force.",
+            args.name,
+            profiles.join(", ")
+        )));
+    }
+
+    if !args.force {
+        let confirmed = prompt::confirm(
+            format!("Delete credential '{}'?", args.name),
+            "Credential deletion",
+            "--force",
+        )?;
+
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Credential deletion".to_string(),
+            });
+        }
+    }
+
+    manager.delete_credential_secret(&args.name, "password")?;
+    manager.delete_credential_secret(&args.name, "token")?;
+
+    manager.store_mut().remove_credential(&args.name);
+    manager.save()?;
+
+    println!(
+        "{} Credential '{}' deleted.",
+        style("✓").green(),
+        style(&args.name).bold()
+    );
+    if !profiles.is_empty() {
+        println!(
+            "{}",
+            style(format!(
+                "⚠ Profiles still referencing it will fail to resolve: {}",
+                profiles.join(", ")
+            ))
+            .yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// Check the age of a profile's credentials before running a command
+///
+/// Uses the default profile when none is given. Missing profiles are ignored
+/// here; the command itself reports them.
+pub fn check_credential_age(profile_name: Option<&str>, destructive: bool) -> Result<()> {
+    let Ok(store) = ProfileStore::load() else {
+        return Ok(());
+    };
+    let name = profile_name.unwrap_or(&store.default_profile);
+    let Ok(Some(age)) = store.credential_age(name, Utc::now()) else {
+        return Ok(());
+    };
+
+    if !age.is_overdue() {
+        return Ok(());
+    }
+
+    if destructive && age.blocking {
+        return Err(VqxError::StaleCredential {
+            profile: name.to_string(),
+            age_days: age.age_days,
+            max_days: age.max_days,
+        });
+    }
+
+    eprintln!(
+        "{} Credentials for profile '{}' are {} days old (limit: {} days). Rotate them with '{}' (or '{}' for shared credentials).",
+        style("⚠").yellow(),
+        name,
+        age.age_days,
+        age.max_days,
+        style(format!("vqx profile set {}", name)).green(),
+        style("vqx credential set").green()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_keeps_keyring_credentials_in_keyring() {
+        let mut credential = Credential::default();
+        assert!(!keeps_in_keyring(&credential, false));
+        assert!(keeps_in_keyring(&credential, true));
+
+        // Created with --secure, rotated without it
+        credential.use_secure_storage = true;
+        assert!(keeps_in_keyring(&credential, false));
+    }
+}
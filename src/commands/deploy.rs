@@ -0,0 +1,513 @@
+//! Deploy/Undeploy command implementation
+//!
+//! Wraps the underlying CLI's deploy and undeploy commands with profile
+//! resolution, a dry-run preview and confirmation for undeploy.
+//!
+//! Based on: CLI Reference Guide PDF - "Deploy" and "Undeploy" sections
+//!
+//! PDF: "deploy <configurationName> | <deploymentName>"
+//! PDF: "undeploy <configurationName> | <deploymentName>"
+//!
+//! Dry-run looks the configuration up in the `deployconfigs` resource and
+//! prints it without changing anything on the server.
+//!
+//! `deploy plan` (vqx extension) resolves the resources a configuration
+//! covers and diffs them between the profile and a target profile, so a
+//! deployment can be reviewed before it is made, like `sync push --dry-run`.
+
+use crate::cli::{DeployArgs, DeployPlanArgs, OutputFormat};
+use crate::commands::diff::{self, DiffResult, RESOURCE_TYPES};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::profile::ProfileManager;
+use crate::progress;
+use crate::prompt;
+use crate::render::{self, RenderOptions};
+use crate::report;
+use crate::theme::style;
+use crate::underlying::{CliOptions, UnderlyingCli};
+use indicatif::{ProgressBar, ProgressStyle};
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+/// Resource holding deployment configurations
+const DEPLOY_CONFIG_RESOURCE: &str = "deployconfigs";
+
+/// Deploy or undeploy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployAction {
+    Deploy,
+    Undeploy,
+}
+
+impl DeployAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            DeployAction::Deploy => "deploy",
+            DeployAction::Undeploy => "undeploy",
+        }
+    }
+}
+
+/// Result of a deploy/undeploy operation
+#[derive(Debug, Serialize)]
+pub struct DeployResult {
+    pub success: bool,
+    pub operation: String,
+    pub name: String,
+    pub dry_run: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// What deploying a configuration would change
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DeployPlan {
+    pub success: bool,
+    pub name: String,
+    pub source_profile: String,
+    pub target_profile: String,
+    /// Projects the configuration refers to
+    pub projects: Vec<String>,
+    /// Resources the configuration covers (`<type>/<name>`)
+    pub resources: Vec<String>,
+    /// Covered resources that do not exist in the source profile
+    pub missing: Vec<String>,
+    /// Covered resources that are the same in both profiles
+    pub unchanged: usize,
+    /// `added`: created in the target, `modified`: changed in the target
+    pub changes: DiffResult,
+}
+
+/// Run deploy or undeploy
+pub async fn run(
+    action: DeployAction,
+    args: &DeployArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+    verbose: bool,
+) -> Result<DeployResult> {
+    // Required by clap unless a subcommand is given
+    let name = args.name.as_deref().unwrap_or_default();
+    info!(
+        operation = action.as_str(),
+        name = %name,
+        dry_run = args.dry_run,
+        "Running deploy"
+    );
+
+    let cli = UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for(action.as_str()))
+        .with_retry_policy(config.retry_policy());
+
+    let (options, target) = build_cli_options(profile_name)?;
+
+    if verbose && !output_format.is_machine_readable() {
+        println!();
+        println!("{}", style(action.as_str()).bold().cyan());
+        println!("{}", style("─".repeat(40)).dim());
+        println!("Configuration: {}", style(name).yellow());
+        println!("Target: {}", style(&target).dim());
+        if args.dry_run {
+            println!("Mode: {}", style("DRY RUN").yellow().bold());
+        }
+        println!();
+    }
+
+    // Dry run: show the configuration that would be (un)deployed
+    if args.dry_run {
+        let configuration = find_configuration(&cli, &options, name).await?;
+        let result = DeployResult {
+            success: configuration.is_some(),
+            operation: action.as_str().to_string(),
+            name: name.to_string(),
+            dry_run: true,
+            error: if configuration.is_none() {
+                Some(format!("Deployment configuration '{}' not found", name))
+            } else {
+                None
+            },
+            configuration,
+            output: None,
+        };
+        display_result(&result, output_format)?;
+        return Ok(result);
+    }
+
+    // Undeploy removes running resources from the target: confirm first
+    if action == DeployAction::Undeploy && !args.yes {
+        let confirmed = prompt::confirm(
+            format!("Undeploy '{}' from {}?", name, target),
+            "Undeploy",
+            "--yes",
+        )?;
+
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Undeploy".to_string(),
+            });
+        }
+    }
+
+    let exec_result = match action {
+        DeployAction::Deploy => cli.deploy(&options, name).await?,
+        DeployAction::Undeploy => cli.undeploy(&options, name).await?,
+    };
+
+    let result = DeployResult {
+        success: exec_result.success(),
+        operation: action.as_str().to_string(),
+        name: name.to_string(),
+        dry_run: false,
+        configuration: None,
+        output: Some(exec_result.stdout.clone()).filter(|s| !s.trim().is_empty()),
+        error: if exec_result.success() {
+            None
+        } else {
+            Some(exec_result.stderr.clone())
+        },
+    };
+
+    display_result(&result, output_format)?;
+    Ok(result)
+}
+
+/// Show what deploying a configuration to `--to` would change
+pub async fn plan(
+    args: &DeployPlanArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<DeployPlan> {
+    let manager = ProfileManager::new()?;
+    let source_profile = profile_name
+        .unwrap_or(&manager.store().default_profile)
+        .to_string();
+    info!(name = %args.name, from = %source_profile, to = %args.to, "Planning deployment");
+
+    let cli = UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for("deploy"))
+        .with_retry_policy(config.retry_policy());
+    let options = CliOptions::from_profile(&manager.get_resolved(&source_profile)?);
+    let configuration = find_configuration(&cli, &options, &args.name)
+        .await?
+        .ok_or_else(|| {
+            VqxError::Other(format!(
+                "Deployment configuration '{}' not found in profile '{}'",
+                args.name, source_profile
+            ))
+        })?;
+
+    let progress = (!output_format.is_machine_readable() && progress::enabled()).then(|| {
+        let pb = ProgressBar::new_spinner();
+        pb.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} {msg}")
+                .unwrap(),
+        );
+        pb.enable_steady_tick(Duration::from_millis(100));
+        pb.set_message("Exporting both profiles...");
+        pb
+    });
+    let ((source_dir, _source_temp), (target_dir, _target_temp)) = tokio::try_join!(
+        diff::export_profile(&source_profile, config, args.refresh, None),
+        diff::export_profile(&args.to, config, args.refresh, None),
+    )?;
+    if let Some(pb) = progress {
+        pb.finish_and_clear();
+    }
+
+    let (projects, resources): (Vec<String>, Vec<String>) =
+        covered_resources(&configuration, &source_dir)
+            .into_iter()
+            .partition(|id| id.starts_with("projects/"));
+    let (present, missing): (Vec<String>, Vec<String>) = resources
+        .iter()
+        .cloned()
+        .partition(|id| source_dir.join(format!("{}.json", id)).is_file());
+
+    let present: BTreeSet<String> = present.into_iter().collect();
+
+    // The target is the state before the deployment, the source the state after
+    let changes = diff::compare_resources(
+        &target_dir,
+        &source_dir,
+        &present,
+        args.full,
+        &args.to,
+        &source_profile,
+    )?;
+
+    let plan = DeployPlan {
+        success: changes.success,
+        name: args.name.clone(),
+        source_profile,
+        target_profile: args.to.clone(),
+        projects,
+        unchanged: present.len() - changes.total_changes(),
+        resources,
+        missing,
+        changes,
+    };
+
+    display_plan(&plan, output_format, args.full)?;
+    Ok(plan)
+}
+
+/// Resources a deployment configuration covers, as `<type>/<name>`
+///
+/// Every string of the form `/<type>/<name>` (a resource reference) counts,
+/// as do `project` and `projectName` values. Projects are expanded with the
+/// references in their definition in `export_dir`.
+fn covered_resources(configuration: &Value, export_dir: &Path) -> BTreeSet<String> {
+    let mut ids = BTreeSet::new();
+    let mut pending = vec![configuration.clone()];
+    while let Some(value) = pending.pop() {
+        let mut references = Vec::new();
+        collect_references(&value, &mut references);
+        for (resource_type, name) in references {
+            let id = report::resource_id(&resource_type, &name);
+            if ids.insert(id.clone()) && resource_type == "projects" {
+                let project = fs::read_to_string(export_dir.join(format!("{}.json", id)))
+                    .ok()
+                    .and_then(|s| serde_json::from_str(&s).ok());
+                pending.extend(project);
+            }
+        }
+    }
+    ids
+}
+
+fn collect_references(value: &Value, references: &mut Vec<(String, String)>) {
+    match value {
+        Value::String(s) => references.extend(parse_reference(s)),
+        Value::Array(items) => {
+            for item in items {
+                collect_references(item, references);
+            }
+        }
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("project" | "projectName", Value::String(name)) => references.push(
+                        parse_reference(name)
+                            .unwrap_or_else(|| ("projects".to_string(), name.clone())),
+                    ),
+                    _ => collect_references(value, references),
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `/<type>/<name>` for a known resource type
+fn parse_reference(s: &str) -> Option<(String, String)> {
+    let (resource_type, name) = s.strip_prefix('/')?.split_once('/')?;
+    (RESOURCE_TYPES.contains(&resource_type) && !name.is_empty() && !name.contains('/'))
+        .then(|| (resource_type.to_string(), name.to_string()))
+}
+
+/// Display a deployment plan
+fn display_plan(plan: &DeployPlan, output_format: OutputFormat, full: bool) -> Result<()> {
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(plan, output_format)?);
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", style("Deploy Plan").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Configuration: {}", style(&plan.name).yellow());
+    println!("  From:          {}", style(&plan.source_profile).cyan());
+    println!("  To:            {}", style(&plan.target_profile).yellow());
+    if !plan.projects.is_empty() {
+        println!("  Projects:      {}", plan.projects.join(", "));
+    }
+    println!(
+        "  Resources:     {} covered, {} unchanged",
+        plan.resources.len(),
+        plan.unchanged
+    );
+
+    print!(
+        "{}",
+        render::diff_renderer("text")?.render(&plan.changes, RenderOptions { full })?
+    );
+
+    if !plan.missing.is_empty() {
+        println!(
+            "{}",
+            style(format!(
+                "⚠  {} covered resource(s) not found in '{}':",
+                plan.missing.len(),
+                plan.source_profile
+            ))
+            .yellow()
+        );
+        for id in &plan.missing {
+            println!("   {}", style(id).yellow());
+        }
+        println!();
+    }
+    if plan.changes.has_changes() {
+        println!(
+            "Run `vqx -s {} deploy {}` to deploy.",
+            plan.source_profile, plan.name
+        );
+        println!();
+    }
+    Ok(())
+}
+
+/// Look up a deployment configuration, returning None if it does not exist
+async fn find_configuration(
+    cli: &UnderlyingCli,
+    options: &CliOptions,
+    name: &str,
+) -> Result<Option<Value>> {
+    let exec_result = cli.find(options, DEPLOY_CONFIG_RESOURCE, name).await?;
+    let stdout = exec_result.stdout.trim();
+
+    if !exec_result.success() || stdout.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(parse_configuration(stdout)?))
+}
+
+/// Parse `find` output; a one-element array is unwrapped
+fn parse_configuration(stdout: &str) -> Result<Value> {
+    let value: Value = serde_json::from_str(stdout).map_err(|e| VqxError::InvalidJson {
+        message: format!("Failed to parse deployment configuration: {}", e),
+    })?;
+
+    Ok(match value {
+        Value::Array(mut items) if items.len() == 1 => items.remove(0),
+        other => other,
+    })
+}
+
+/// Build CLI options from profile, along with a description of the target
+fn build_cli_options(profile_name: Option<&str>) -> Result<(CliOptions, String)> {
+    if let Some(name) = profile_name {
+        let manager = ProfileManager::new()?;
+        let profile = manager.get_resolved(name)?;
+        Ok((
+            CliOptions::from_profile(&profile),
+            format!("{} ({})", name, profile.url),
+        ))
+    } else {
+        Ok((CliOptions::default(), "default CLI profile".to_string()))
+    }
+}
+
+/// Display the result
+fn display_result(result: &DeployResult, output_format: OutputFormat) -> Result<()> {
+    match output_format {
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => {
+            println!("{}", report::render(result, output_format)?);
+        }
+        OutputFormat::Text | OutputFormat::Csv => {
+            if let Some(ref configuration) = result.configuration {
+                println!("{}", serde_json::to_string_pretty(configuration)?);
+                println!();
+                println!(
+                    "{} Dry run complete. Nothing was {}ed.",
+                    style("✓").green(),
+                    result.operation
+                );
+                return Ok(());
+            }
+
+            if let Some(ref output) = result.output {
+                print!("{}", output);
+            }
+
+            if result.success {
+                println!(
+                    "{} {} '{}' completed successfully",
+                    style("✓").green().bold(),
+                    result.operation,
+                    result.name
+                );
+            } else {
+                println!(
+                    "{} {} '{}' failed",
+                    style("✗").red().bold(),
+                    result.operation,
+                    result.name
+                );
+                if let Some(ref err) = result.error {
+                    if !err.is_empty() {
+                        eprintln!("{}", style(err).red());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_configuration() {
+        let config = parse_configuration(r#"[{"name": "prod", "target": "edge"}]"#).unwrap();
+        assert_eq!(config["name"], "prod");
+
+        let config = parse_configuration(r#"{"name": "prod"}"#).unwrap();
+        assert_eq!(config["name"], "prod");
+
+        assert!(parse_configuration("not json").is_err());
+    }
+
+    #[test]
+    fn test_covered_resources() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("projects")).unwrap();
+        fs::write(
+            dir.path().join("projects/Orders.json"),
+            r#"{"name": "Orders", "resources": [
+                {"resourceReference": "/types/Order"},
+                {"resourceReference": "/procedures/Orders.process"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let configuration = serde_json::json!({
+            "name": "prodConfig",
+            "project": "Orders",
+            "resources": ["/rules/OnOrder", "/unknown/X", "not/a/reference"]
+        });
+        let ids: Vec<String> = covered_resources(&configuration, dir.path())
+            .into_iter()
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "procedures/Orders.process",
+                "projects/Orders",
+                "rules/OnOrder",
+                "types/Order"
+            ]
+        );
+    }
+}
@@ -11,25 +11,47 @@
 //! - Added resources (exist in target but not source)
 //! - Removed resources (exist in source but not target)
 //! - Modified resources (exist in both but differ)
+//!
+//! Procedures and rules are compared as VAIL code: the code body is diffed
+//! line by line (ignoring line endings and trailing whitespace) and the rest
+//! of the definition separately. `--code-only` drops the metadata part.
+//!
+//! Profile exports are reused from the export cache ([`crate::cache`]) while
+//! they are fresh; `--refresh` exports again.
 
+use crate::cache::ExportCache;
 use crate::cli::{DiffArgs, OutputFormat};
+use crate::compat::{self, CompatIssue, Severity};
 use crate::config::Config;
 use crate::error::{Result, VqxError};
-use crate::normalizer::ResourceNormalizer;
+use crate::filter::ResourceFilter;
+use crate::i18n;
+use crate::ignore::IgnoreRules;
+use crate::normalizer::{self, ResourceNormalizer};
+use crate::offline;
+use crate::pager;
 use crate::profile::ProfileManager;
+use crate::progress;
+use crate::render::{self, RenderOptions};
+use crate::report;
+use crate::theme::style;
 use crate::underlying::{CliOptions, UnderlyingCli};
-use console::style;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use similar::{ChangeTag, TextDiff};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tempfile::TempDir;
+use tracing::{info, warn};
 
 /// Represents a difference between two resources
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ResourceDiff {
+    /// Stable identifier (`<resource_type>/<name>`)
+    pub id: String,
     /// Resource type (e.g., "types", "procedures")
     pub resource_type: String,
     /// Resource name
@@ -40,14 +62,42 @@ pub struct ResourceDiff {
     pub diff_text: Option<String>,
 }
 
+impl ResourceDiff {
+    pub fn new(
+        resource_type: &str,
+        name: &str,
+        change: ChangeKind,
+        diff_text: Option<String>,
+    ) -> Self {
+        Self {
+            id: report::resource_id(resource_type, name),
+            resource_type: resource_type.to_string(),
+            name: name.to_string(),
+            change,
+            diff_text,
+        }
+    }
+}
+
 /// Kind of change detected
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum ChangeKind {
     Added,
     Removed,
     Modified,
 }
 
+impl ChangeKind {
+    /// Status letter of `--output porcelain`, as in `git status`
+    pub fn code(&self) -> char {
+        match self {
+            ChangeKind::Added => 'A',
+            ChangeKind::Removed => 'D',
+            ChangeKind::Modified => 'M',
+        }
+    }
+}
+
 impl std::fmt::Display for ChangeKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -59,7 +109,7 @@ impl std::fmt::Display for ChangeKind {
 }
 
 /// Result of diff operation
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct DiffResult {
     pub success: bool,
     pub source: String,
@@ -68,6 +118,8 @@ pub struct DiffResult {
     pub removed: Vec<ResourceDiff>,
     pub modified: Vec<ResourceDiff>,
     pub errors: Vec<String>,
+    /// Compatibility findings for types; `null` unless run with `--breaking`
+    pub breaking: Option<Vec<CompatIssue>>,
 }
 
 impl DiffResult {
@@ -78,6 +130,14 @@ impl DiffResult {
     pub fn has_changes(&self) -> bool {
         self.total_changes() > 0
     }
+
+    /// Whether `--breaking` found changes of `breaking` severity
+    pub fn has_breaking_changes(&self) -> bool {
+        self.breaking
+            .iter()
+            .flatten()
+            .any(|issue| issue.severity == Severity::Breaking)
+    }
 }
 
 /// Source type for diff comparison
@@ -113,149 +173,324 @@ pub async fn run(
 ) -> Result<DiffResult> {
     let source = DiffSource::parse(&args.source);
     let target = DiffSource::parse(&args.target);
+    for side in [&source, &target] {
+        if let DiffSource::Profile(name) = side {
+            // Fail before printing anything; only directories work offline
+            offline::check(format!("Exporting profile '{}'", name))?;
+        }
+    }
+
+    let renderer = match (&args.format, output_format) {
+        (Some(name), _) => render::diff_renderer(name)?,
+        (None, OutputFormat::Json) => render::diff_renderer("json")?,
+        (None, OutputFormat::Yaml) => render::diff_renderer("yaml")?,
+        (None, OutputFormat::Ndjson) => render::diff_renderer("ndjson")?,
+        (None, OutputFormat::Porcelain) => render::diff_renderer("porcelain")?,
+        (None, _) => render::diff_renderer("text")?,
+    };
+    let report_renderer = args
+        .report
+        .as_deref()
+        .map(render::report_renderer)
+        .transpose()?;
+    let interactive = renderer.is_terminal();
+    let full_diff = args.full || renderer.requires_full_diff();
+    let ignore_rules: Vec<&String> = config.diff.ignore.iter().chain(&args.ignore_path).collect();
+    let ignore = IgnoreRules::parse(&ignore_rules)?;
+    let filter = ResourceFilter::from_args(&args.filter, &args.resource, None)?;
 
     // Display diff info
-    if !matches!(output_format, OutputFormat::Json) {
+    if interactive {
         println!();
-        println!("{}", style("Diff").bold().cyan());
+        println!("{}", style(i18n::t("diff.title")).bold().cyan());
         println!("{}", style("─".repeat(50)).dim());
-        println!("  Source: {}", source.description());
-        println!("  Target: {}", target.description());
-        if !args.resource.is_empty() {
-            println!("  Filter: {}", args.resource.join(", "));
+        println!("  {} {}", i18n::label("label.source"), source.description());
+        println!("  {} {}", i18n::label("label.target"), target.description());
+        if !filter.is_empty() {
+            println!("  {} {}", i18n::label("label.filter"), filter);
+        }
+        if args.code_only {
+            println!(
+                "  {} {}",
+                i18n::label("label.mode"),
+                i18n::t("diff.code_only")
+            );
+        }
+        if !ignore.is_empty() {
+            println!(
+                "  {} {}",
+                i18n::label("label.ignore"),
+                i18n::tf("diff.rules", &[("count", &ignore.len())])
+            );
         }
         println!();
     }
 
-    // Progress bar
-    let progress = if !matches!(output_format, OutputFormat::Json) {
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
-                .unwrap(),
-        );
-        pb.enable_steady_tick(Duration::from_millis(100));
-        Some(pb)
-    } else {
-        None
+    // One progress line per profile; both exports run concurrently
+    let multi = (interactive && progress::enabled()).then(MultiProgress::new);
+    let line = |source: &DiffSource| match (&multi, source) {
+        (Some(multi), DiffSource::Profile(_)) => Some(multi.add(spinner())),
+        _ => None,
     };
+    let (source_pb, target_pb) = (line(&source), line(&target));
 
-    // Get source directory (export if profile)
-    let (source_dir, _source_temp) =
-        get_directory_for_source(&source, config, progress.as_ref()).await?;
-
-    // Get target directory (export if profile)
-    let (target_dir, _target_temp) =
-        get_directory_for_source(&target, config, progress.as_ref()).await?;
+    let ((source_dir, _source_temp), (target_dir, _target_temp)) = tokio::try_join!(
+        get_directory_for_source(&source, config, args.refresh, source_pb.as_ref()),
+        get_directory_for_source(&target, config, args.refresh, target_pb.as_ref()),
+    )?;
+    for pb in source_pb.iter().chain(target_pb.iter()) {
+        pb.finish_and_clear();
+    }
 
+    let progress = interactive.then(spinner);
     if let Some(ref pb) = progress {
         pb.set_message("Comparing resources...");
     }
 
     // Perform diff
-    let result = compare_directories(
+    let options = CompareOptions {
+        filter: &filter,
+        full: full_diff,
+        code_only: args.code_only,
+        ignore: &ignore,
+        only: None,
+    };
+    let mut result = compare_directories(
         &source_dir,
         &target_dir,
-        &args.resource,
-        args.full,
+        &options,
         &args.source,
         &args.target,
     )?;
 
+    if args.breaking {
+        result.breaking = Some(breaking_changes(&source_dir, &target_dir, &result)?);
+    }
+
+    // The report always has full diffs, whatever is printed
+    if let (Some(path), Some(report_renderer)) = (&args.report, report_renderer) {
+        let full = if full_diff {
+            None
+        } else {
+            let mut full = compare_directories(
+                &source_dir,
+                &target_dir,
+                &CompareOptions {
+                    full: true,
+                    ..options
+                },
+                &args.source,
+                &args.target,
+            )?;
+            full.breaking = result.breaking.clone();
+            Some(full)
+        };
+        let report = report_renderer.render(
+            full.as_ref().unwrap_or(&result),
+            RenderOptions { full: true },
+        )?;
+        std::fs::write(path, report).map_err(|_| VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        })?;
+        if let Some(ref pb) = progress {
+            pb.println(format!("  Report written to {}", path.display()));
+        }
+    }
+
+    // File-level patch of the normalized trees, for `vqx patch apply`
+    if let Some(ref path) = args.patch {
+        let patch = crate::patch::tree_patch(&source_dir, &target_dir, &filter)?;
+        std::fs::write(path, patch).map_err(|_| VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        })?;
+        if let Some(ref pb) = progress {
+            pb.println(format!("  Patch written to {}", path.display()));
+        }
+    }
+
     if let Some(ref pb) = progress {
         pb.finish_and_clear();
     }
 
     // Display results
-    if !matches!(output_format, OutputFormat::Json) {
-        display_diff_results(&result, args.full);
+    let rendered = renderer.render(&result, RenderOptions { full: full_diff })?;
+    if interactive {
+        pager::page(&rendered);
     } else {
-        println!("{}", serde_json::to_string_pretty(&result)?);
+        print!("{}", rendered);
+    }
+
+    // On stderr, so stdout stays usable as the comment body
+    if args.annotations {
+        eprint!("{}", render::github_annotations(&result));
     }
 
     Ok(result)
 }
 
+/// Spinner for one progress line
+fn spinner() -> ProgressBar {
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    pb.enable_steady_tick(Duration::from_millis(100));
+    pb
+}
+
 /// Get a directory for a diff source, exporting if necessary
 async fn get_directory_for_source(
     source: &DiffSource,
     config: &Config,
+    refresh: bool,
     progress: Option<&ProgressBar>,
 ) -> Result<(PathBuf, Option<TempDir>)> {
     match source {
         DiffSource::Directory(path) => Ok((path.clone(), None)),
-        DiffSource::Profile(name) => {
+        DiffSource::Profile(name) => export_profile(name, config, refresh, progress).await,
+    }
+}
+
+/// Export a profile's metadata into a normalized directory
+///
+/// The export comes from the export cache unless `refresh` is set; fresh
+/// exports go to a temp dir (returned to keep it alive) and are cached.
+pub async fn export_profile(
+    name: &str,
+    config: &Config,
+    refresh: bool,
+    progress: Option<&ProgressBar>,
+) -> Result<(PathBuf, Option<TempDir>)> {
+    offline::check(format!("Exporting profile '{}'", name))?;
+
+    // Load profile
+    let manager = ProfileManager::new()?;
+    let profile = manager.get_resolved(name)?;
+
+    let cache = ExportCache::new(config);
+    if !refresh {
+        if let Some(snapshot) = cache.lookup(name, &profile, &config.normalization) {
+            info!(profile = %name, age = %snapshot.age(), "Using cached export");
             if let Some(pb) = progress {
-                pb.set_message(format!("Exporting from profile '{}'...", name));
+                pb.println(format!(
+                    "  Using cached export of '{}' ({} old, --refresh to export again)",
+                    name,
+                    snapshot.age()
+                ));
             }
+            return Ok((snapshot.path, None));
+        }
+    }
 
-            // Create temp directory
-            let temp_dir = TempDir::new().map_err(|e| VqxError::Other(e.to_string()))?;
-            let export_path = temp_dir.path().to_path_buf();
+    if let Some(pb) = progress {
+        pb.set_message(format!("Exporting from profile '{}'...", name));
+    }
 
-            // Load profile
-            let manager = ProfileManager::new()?;
-            let profile = manager.get_resolved(name)?;
+    // Create temp directory
+    let temp_dir = TempDir::new().map_err(|e| VqxError::Other(e.to_string()))?;
+    let export_path = temp_dir.path().to_path_buf();
 
-            if !profile.has_auth() {
-                return Err(VqxError::ProfileInvalid {
-                    message: format!("Profile '{}' has no authentication configured", name),
-                });
-            }
+    if !profile.has_auth() {
+        return Err(VqxError::ProfileInvalid {
+            message: format!("Profile '{}' has no authentication configured", name),
+        });
+    }
 
-            // Export to temp directory
-            let cli = UnderlyingCli::new(config.cli_path.clone())
-                .with_timeout(config.timeout())
-                .with_retries(config.max_retries, config.retry_delay_ms);
-
-            let options = CliOptions::from_profile(&profile);
-
-            let result = cli
-                .export(
-                    &options,
-                    Some("metadata"),
-                    Some(export_path.to_str().unwrap()),
-                    Some(config.default_chunk_size),
-                    None,
-                    None,
-                    None,
-                    false,
-                )
-                .await?;
-
-            if !result.success() {
-                return Err(VqxError::CliExecutionFailed {
-                    code: result.code(),
-                    message: result.stderr,
-                });
-            }
+    // Export to temp directory
+    let cli = UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for("diff"))
+        .with_retry_policy(config.retry_policy());
+
+    let options = CliOptions::from_profile(&profile);
+
+    let result = cli
+        .export(
+            &options,
+            Some("metadata"),
+            Some(&export_path),
+            Some(config.default_chunk_size),
+            None,
+            None,
+            None,
+            false,
+        )
+        .await?;
+
+    if !result.success() {
+        return Err(VqxError::CliExecutionFailed {
+            code: result.code(),
+            message: result.stderr,
+        });
+    }
 
-            // Normalize exported files
-            let normalizer = ResourceNormalizer::new(config.normalization.clone());
-            normalizer.normalize_export_directory(&export_path)?;
+    // Normalize exported files
+    let normalizer = ResourceNormalizer::new(config.normalization.clone());
+    normalizer.normalize_export_directory(&export_path)?;
 
-            Ok((export_path, Some(temp_dir)))
+    if cache.is_enabled() {
+        if let Err(e) = cache.store(name, &profile, &config.normalization, &export_path) {
+            warn!(profile = %name, error = %e, "Could not cache export");
         }
     }
+
+    Ok((export_path, Some(temp_dir)))
+}
+
+/// Compare some resources of two normalized export directories
+///
+/// Added resources are the ones only in `target_dir`. Used by `deploy plan`
+/// for the resources a deployment configuration covers.
+pub(crate) fn compare_resources(
+    source_dir: &Path,
+    target_dir: &Path,
+    ids: &BTreeSet<String>,
+    full: bool,
+    source_name: &str,
+    target_name: &str,
+) -> Result<DiffResult> {
+    let options = CompareOptions {
+        filter: &ResourceFilter::default(),
+        full,
+        code_only: false,
+        ignore: &IgnoreRules::default(),
+        only: Some(ids),
+    };
+    compare_directories(source_dir, target_dir, &options, source_name, target_name)
+}
+
+/// What to compare, and how
+#[derive(Clone, Copy)]
+struct CompareOptions<'a> {
+    /// Resources to compare (`--include`/`--exclude`)
+    filter: &'a ResourceFilter,
+    /// Full diff listings instead of `+N -M` summaries
+    full: bool,
+    code_only: bool,
+    ignore: &'a IgnoreRules,
+    /// Only these resources (`<type>/<name>`), if given
+    only: Option<&'a BTreeSet<String>>,
 }
 
-/// Compare two directories
 fn compare_directories(
     source_dir: &Path,
     target_dir: &Path,
-    filter_types: &[String],
-    full_diff: bool,
+    options: &CompareOptions,
     source_name: &str,
     target_name: &str,
 ) -> Result<DiffResult> {
+    let _step = crate::summary::step("diff");
     let mut added = Vec::new();
     let mut removed = Vec::new();
     let mut modified = Vec::new();
     let mut errors = Vec::new();
 
     // Get resource types to compare
-    let resource_types = get_resource_types(source_dir, target_dir, filter_types);
+    let mut resource_types = get_resource_types(source_dir, target_dir, options.filter);
+    if options.code_only {
+        resource_types.retain(|t| CODE_RESOURCE_TYPES.contains(&t.as_str()));
+    }
 
     for resource_type in resource_types {
         let source_type_dir = source_dir.join(&resource_type);
@@ -265,27 +500,33 @@ fn compare_directories(
         let source_files = get_json_files(&source_type_dir);
         let target_files = get_json_files(&target_type_dir);
 
-        let source_names: HashSet<_> = source_files.keys().collect();
-        let target_names: HashSet<_> = target_files.keys().collect();
+        let selected = |name: &&String| {
+            options.filter.matches(&resource_type, name)
+                && options
+                    .only
+                    .is_none_or(|ids| ids.contains(&report::resource_id(&resource_type, name)))
+        };
+        let source_names: HashSet<_> = source_files.keys().filter(selected).collect();
+        let target_names: HashSet<_> = target_files.keys().filter(selected).collect();
 
         // Find added (in target but not source)
         for name in target_names.difference(&source_names) {
-            added.push(ResourceDiff {
-                resource_type: resource_type.clone(),
-                name: (*name).clone(),
-                change: ChangeKind::Added,
-                diff_text: None,
-            });
+            added.push(ResourceDiff::new(
+                &resource_type,
+                name,
+                ChangeKind::Added,
+                None,
+            ));
         }
 
         // Find removed (in source but not target)
         for name in source_names.difference(&target_names) {
-            removed.push(ResourceDiff {
-                resource_type: resource_type.clone(),
-                name: (*name).clone(),
-                change: ChangeKind::Removed,
-                diff_text: None,
-            });
+            removed.push(ResourceDiff::new(
+                &resource_type,
+                name,
+                ChangeKind::Removed,
+                None,
+            ));
         }
 
         // Find modified (in both but different)
@@ -293,14 +534,14 @@ fn compare_directories(
             let source_path = &source_files[*name];
             let target_path = &target_files[*name];
 
-            match compare_files(source_path, target_path, full_diff) {
+            match compare_files(source_path, target_path, &resource_type, name, options) {
                 Ok(Some(diff_text)) => {
-                    modified.push(ResourceDiff {
-                        resource_type: resource_type.clone(),
-                        name: (*name).clone(),
-                        change: ChangeKind::Modified,
-                        diff_text: Some(diff_text),
-                    });
+                    modified.push(ResourceDiff::new(
+                        &resource_type,
+                        name,
+                        ChangeKind::Modified,
+                        Some(diff_text),
+                    ));
                 }
                 Ok(None) => {
                     // Files are identical
@@ -313,9 +554,10 @@ fn compare_directories(
     }
 
     // Sort results for consistent output
-    added.sort_by(|a, b| (&a.resource_type, &a.name).cmp(&(&b.resource_type, &b.name)));
-    removed.sort_by(|a, b| (&a.resource_type, &a.name).cmp(&(&b.resource_type, &b.name)));
-    modified.sort_by(|a, b| (&a.resource_type, &a.name).cmp(&(&b.resource_type, &b.name)));
+    added.sort_by(|a, b| a.id.cmp(&b.id));
+    removed.sort_by(|a, b| a.id.cmp(&b.id));
+    modified.sort_by(|a, b| a.id.cmp(&b.id));
+    errors.sort();
 
     Ok(DiffResult {
         success: errors.is_empty(),
@@ -325,41 +567,81 @@ fn compare_directories(
         removed,
         modified,
         errors,
+        breaking: None,
     })
 }
 
+/// Check removed and modified types for breaking changes
+fn breaking_changes(
+    source_dir: &Path,
+    target_dir: &Path,
+    result: &DiffResult,
+) -> Result<Vec<CompatIssue>> {
+    let mut issues = Vec::new();
+
+    for diff in result.removed.iter().filter(|d| d.resource_type == "types") {
+        issues.push(compat::removed_type(&diff.id));
+    }
+
+    for diff in result
+        .modified
+        .iter()
+        .filter(|d| d.resource_type == "types")
+    {
+        let file = format!("{}.json", diff.name);
+        let source: Value = serde_json::from_str(&normalizer::read_embedded(
+            &source_dir.join("types").join(&file),
+        )?)?;
+        let target: Value = serde_json::from_str(&normalizer::read_embedded(
+            &target_dir.join("types").join(&file),
+        )?)?;
+        issues.extend(compat::compare_types(&diff.id, &source, &target));
+    }
+
+    // Stable sort keeps each type's findings in property order
+    issues.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(issues)
+}
+
+/// Resource types whose definitions carry VAIL code
+const CODE_RESOURCE_TYPES: &[&str] = &["procedures", "rules"];
+
+/// Known resource type directories (PDF "Export" section)
+pub(crate) const RESOURCE_TYPES: &[&str] = &[
+    "types",
+    "procedures",
+    "rules",
+    "sources",
+    "services",
+    "topics",
+    "collaborationtypes",
+    "aicomponents",
+    "catalogs",
+    "clients",
+    "configurations",
+    "debugconfigs",
+    "deployconfigs",
+    "environments",
+    "projects",
+    "scheduledevents",
+    "subscriptions",
+    "systemmodels",
+];
+
 /// Get resource types from both directories
-fn get_resource_types(source_dir: &Path, target_dir: &Path, filter: &[String]) -> Vec<String> {
+fn get_resource_types(
+    source_dir: &Path,
+    target_dir: &Path,
+    filter: &ResourceFilter,
+) -> Vec<String> {
     let mut types = HashSet::new();
 
-    // Known resource type directories
-    let known_types = [
-        "types",
-        "procedures",
-        "rules",
-        "sources",
-        "services",
-        "topics",
-        "collaborationtypes",
-        "aicomponents",
-        "catalogs",
-        "clients",
-        "configurations",
-        "debugconfigs",
-        "deployconfigs",
-        "environments",
-        "projects",
-        "scheduledevents",
-        "subscriptions",
-        "systemmodels",
-    ];
-
     for dir in [source_dir, target_dir] {
         if let Ok(entries) = std::fs::read_dir(dir) {
             for entry in entries.flatten() {
                 if entry.path().is_dir() {
                     if let Some(name) = entry.file_name().to_str() {
-                        if known_types.contains(&name) {
+                        if RESOURCE_TYPES.contains(&name) {
                             types.insert(name.to_string());
                         }
                     }
@@ -368,15 +650,10 @@ fn get_resource_types(source_dir: &Path, target_dir: &Path, filter: &[String]) -
         }
     }
 
-    // Filter if specified
-    let mut result: Vec<_> = if filter.is_empty() {
-        types.into_iter().collect()
-    } else {
-        types
-            .into_iter()
-            .filter(|t| filter.iter().any(|f| t.contains(f)))
-            .collect()
-    };
+    let mut result: Vec<_> = types
+        .into_iter()
+        .filter(|t| filter.matches_type(t))
+        .collect();
 
     result.sort();
     result
@@ -401,20 +678,131 @@ fn get_json_files(dir: &Path) -> HashMap<String, PathBuf> {
 }
 
 /// Compare two JSON files
-fn compare_files(source: &Path, target: &Path, full_diff: bool) -> Result<Option<String>> {
-    let source_content = std::fs::read_to_string(source).map_err(|_| VqxError::FileReadFailed {
-        path: source.display().to_string(),
-    })?;
-    let target_content = std::fs::read_to_string(target).map_err(|_| VqxError::FileReadFailed {
-        path: target.display().to_string(),
-    })?;
+fn compare_files(
+    source: &Path,
+    target: &Path,
+    resource_type: &str,
+    name: &str,
+    options: &CompareOptions,
+) -> Result<Option<String>> {
+    let (full_diff, code_only) = (options.full, options.code_only);
+
+    // Extracted procedure/rule code is compared as part of its definition
+    let mut source_content = normalizer::read_embedded(source)?;
+    let mut target_content = normalizer::read_embedded(target)?;
+
+    if !options.ignore.is_empty() {
+        if let (Ok(mut source_value), Ok(mut target_value)) = (
+            serde_json::from_str::<Value>(&source_content),
+            serde_json::from_str::<Value>(&target_content),
+        ) {
+            let id = report::resource_id(resource_type, name);
+            // Both sides are re-serialized, so formatting stays comparable
+            if options.ignore.strip(&id, &mut source_value)
+                | options.ignore.strip(&id, &mut target_value)
+            {
+                source_content = serde_json::to_string_pretty(&source_value)? + "\n";
+                target_content = serde_json::to_string_pretty(&target_value)? + "\n";
+            }
+        }
+    }
+
+    if CODE_RESOURCE_TYPES.contains(&resource_type) {
+        if let (Ok(source_value), Ok(target_value)) = (
+            serde_json::from_str::<Value>(&source_content),
+            serde_json::from_str::<Value>(&target_content),
+        ) {
+            return Ok(compare_code_resources(
+                &source_value,
+                &target_value,
+                full_diff,
+                code_only,
+            ));
+        }
+    }
 
     if source_content == target_content {
         return Ok(None);
     }
 
-    // Generate unified diff
-    let diff = TextDiff::from_lines(&source_content, &target_content);
+    Ok(Some(text_diff(&source_content, &target_content, full_diff)))
+}
+
+/// Compare procedure/rule definitions as code plus metadata
+fn compare_code_resources(
+    source: &Value,
+    target: &Value,
+    full_diff: bool,
+    code_only: bool,
+) -> Option<String> {
+    let (source_code, source_meta) = split_code(source);
+    let (target_code, target_meta) = split_code(target);
+
+    let code_changed = source_code != target_code;
+    let meta_changed = !code_only && source_meta != target_meta;
+    if !code_changed && !meta_changed {
+        return None;
+    }
+
+    let mut parts = Vec::new();
+    if full_diff {
+        if code_changed {
+            let diff = TextDiff::from_lines(&source_code, &target_code);
+            parts.push(format!("# code\n{}", diff.unified_diff().context_radius(3)));
+        }
+        if meta_changed {
+            parts.push(format!(
+                "# metadata\n{}",
+                text_diff(&source_meta, &target_meta, true)
+            ));
+        }
+        Some(parts.join(""))
+    } else {
+        if code_changed {
+            parts.push(format!(
+                "code {}",
+                text_diff(&source_code, &target_code, false)
+            ));
+        }
+        if meta_changed {
+            parts.push(format!(
+                "metadata {}",
+                text_diff(&source_meta, &target_meta, false)
+            ));
+        }
+        Some(parts.join(", "))
+    }
+}
+
+/// Split a definition into its normalized VAIL code and the remaining JSON
+fn split_code(value: &Value) -> (String, String) {
+    let mut metadata = value.clone();
+    let code = normalizer::code_field(value)
+        .and_then(|field| metadata.as_object_mut()?.remove(field))
+        .and_then(|code| code.as_str().map(normalize_vail))
+        .unwrap_or_default();
+    let metadata = serde_json::to_string_pretty(&metadata).unwrap_or_default();
+    (code, metadata)
+}
+
+/// Normalize VAIL whitespace that carries no meaning
+///
+/// Line endings and trailing whitespace are ignored, as are blank lines at
+/// the end. Indentation and whitespace inside lines are kept since they may
+/// be part of string literals.
+fn normalize_vail(code: &str) -> String {
+    let mut lines: Vec<&str> = code.lines().map(|l| l.trim_end()).collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    let mut normalized = lines.join("\n");
+    normalized.push('\n');
+    normalized
+}
+
+/// Line diff of two texts: full listing or a `+N -M` summary
+fn text_diff(source_content: &str, target_content: &str, full_diff: bool) -> String {
+    let diff = TextDiff::from_lines(source_content, target_content);
 
     let mut diff_text = String::new();
 
@@ -444,111 +832,64 @@ fn compare_files(source: &Path, target: &Path, full_diff: bool) -> Result<Option
         diff_text = format!("+{} -{}", additions, deletions);
     }
 
-    Ok(Some(diff_text))
+    diff_text
 }
 
-/// Display diff results to terminal
-fn display_diff_results(result: &DiffResult, full_diff: bool) {
+/// Show the annotated guide for the diff command (`vqx explain diff`)
+pub fn display_help() {
+    println!();
+    println!("{}", style("Diff Command").bold().cyan());
+    println!("{}", style("─".repeat(60)).dim());
+    println!();
+    println!("Compare resources between two profiles, two directories, or both.");
     println!();
-    println!("{}", style("─".repeat(50)).dim());
-
-    if !result.has_changes() {
-        println!("{} No differences found", style("✓").green().bold());
-        println!();
-        return;
-    }
-
     println!(
-        "{} Found {} change(s)",
-        style("!").yellow().bold(),
-        result.total_changes()
+        "{}",
+        style("PDF Reference: none (vqx extension built on export)").bold()
     );
     println!();
-
-    // Added
-    if !result.added.is_empty() {
-        println!(
-            "{} {} added:",
-            style("+").green().bold(),
-            result.added.len()
-        );
-        for diff in &result.added {
-            println!(
-                "    {} {}/{}",
-                style("+").green(),
-                diff.resource_type,
-                diff.name
-            );
-        }
-        println!();
-    }
-
-    // Removed
-    if !result.removed.is_empty() {
-        println!(
-            "{} {} removed:",
-            style("-").red().bold(),
-            result.removed.len()
-        );
-        for diff in &result.removed {
-            println!(
-                "    {} {}/{}",
-                style("-").red(),
-                diff.resource_type,
-                diff.name
-            );
-        }
-        println!();
-    }
-
-    // Modified
-    if !result.modified.is_empty() {
-        println!(
-            "{} {} modified:",
-            style("~").yellow().bold(),
-            result.modified.len()
-        );
-        for diff in &result.modified {
-            println!(
-                "    {} {}/{}",
-                style("~").yellow(),
-                diff.resource_type,
-                diff.name
-            );
-            if full_diff {
-                if let Some(ref text) = diff.diff_text {
-                    for line in text.lines() {
-                        let colored_line = if line.starts_with('+') {
-                            style(line).green().to_string()
-                        } else if line.starts_with('-') {
-                            style(line).red().to_string()
-                        } else {
-                            line.to_string()
-                        };
-                        println!("        {}", colored_line);
-                    }
-                }
-            } else if let Some(ref text) = diff.diff_text {
-                println!("        {}", style(text).dim());
-            }
-        }
-        println!();
-    }
-
-    // Errors
-    if !result.errors.is_empty() {
-        println!(
-            "{} {} error(s):",
-            style("⚠").red().bold(),
-            result.errors.len()
-        );
-        for error in &result.errors {
-            println!("    {}", style(error).red());
-        }
-        println!();
+    println!("{}", style("Sources:").bold());
+    println!("  <profile>    Exported with 'vantiq export metadata' (cached)");
+    println!("  <directory>  An existing export directory");
+    println!();
+    println!("{}", style("Options:").bold());
+    println!("  --resource <type>      Only compare these resource types");
+    println!("  --full                 Show the full diff of each modified resource");
+    println!("  --code-only            Only compare procedure/rule code");
+    println!("  --ignore-path <rule>   Leave fields out (e.g. 'sources/*.config.password')");
+    println!("  --breaking             Report breaking type changes; exit 1 if any");
+    println!("  --format <fmt>         Output format (see Formats)");
+    println!("  --refresh              Export profiles again instead of using the cache");
+    println!();
+    println!("{}", style("Formats:").bold());
+    for renderer in render::diff_renderers() {
+        println!("  {:<22} {}", renderer.name(), renderer.description());
     }
-
-    println!("{}", style("─".repeat(50)).dim());
+    println!();
+    println!("{}", style("Reports:").bold());
+    println!("  --report <file>        Write a shareable report (.html or .md)");
+    println!("  --patch <file>         Write a unified diff for 'vqx patch apply'");
+    println!("  --annotations          Emit GitHub Actions annotations on stderr");
+    println!();
+    println!("{}", style("Examples:").bold());
+    println!();
+    println!("  # What differs between dev and prod");
+    println!("  {} vqx diff dev prod", style("$").dim());
+    println!();
+    println!("  # Local changes not yet on the server");
+    println!("  {} vqx diff dev ./export --full", style("$").dim());
+    println!();
+    println!("  # Gate a release on breaking type changes");
+    println!(
+        "  {} vqx diff prod ./export --resource types --breaking",
+        style("$").dim()
+    );
+    println!();
+    println!("{}", style("Note:").dim());
+    println!(
+        "{}",
+        style("  Procedures and rules are compared as VAIL code, line by line.").dim()
+    );
     println!();
 }
 
@@ -570,6 +911,78 @@ mod tests {
         assert!(matches!(source, DiffSource::Profile(_)));
     }
 
+    #[test]
+    fn test_code_diff_ignores_insignificant_whitespace() {
+        let source =
+            serde_json::json!({"name": "OnOrder", "ruleText": "RULE OnOrder\r\nlog.info(1)  \r\n"});
+        let target =
+            serde_json::json!({"name": "OnOrder", "ruleText": "RULE OnOrder\nlog.info(1)\n\n"});
+
+        assert!(compare_code_resources(&source, &target, true, false).is_none());
+    }
+
+    #[test]
+    fn test_code_diff_is_line_based() {
+        let source = serde_json::json!({"name": "Orders.total", "ruleText": "PROCEDURE Orders.total()\nreturn 1\n"});
+        let target = serde_json::json!({"name": "Orders.total", "ruleText": "PROCEDURE Orders.total()\nreturn 2\n"});
+
+        let full = compare_code_resources(&source, &target, true, false).unwrap();
+        assert!(full.contains("-return 1"));
+        assert!(full.contains("+return 2"));
+        assert!(!full.contains("# metadata"));
+
+        let summary = compare_code_resources(&source, &target, false, false).unwrap();
+        assert_eq!(summary, "code +1 -1");
+    }
+
+    #[test]
+    fn test_code_only_hides_metadata_changes() {
+        let source =
+            serde_json::json!({"name": "OnOrder", "active": true, "ruleText": "RULE OnOrder"});
+        let target =
+            serde_json::json!({"name": "OnOrder", "active": false, "ruleText": "RULE OnOrder"});
+
+        assert!(compare_code_resources(&source, &target, false, true).is_none());
+        assert_eq!(
+            compare_code_resources(&source, &target, false, false).unwrap(),
+            "metadata +1 -1"
+        );
+    }
+
+    #[test]
+    fn test_ignore_rules_hide_environment_values() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        for (dir, password, interval) in [(&source, "dev", 60), (&target, "prod", 60)] {
+            std::fs::create_dir_all(dir.path().join("sources")).unwrap();
+            std::fs::write(
+                dir.path().join("sources/Crm.json"),
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "name": "Crm",
+                    "config": {"password": password, "pollingInterval": interval}
+                }))
+                .unwrap(),
+            )
+            .unwrap();
+        }
+
+        let compare = |rules: &[&str]| {
+            let ignore = IgnoreRules::parse(rules).unwrap();
+            let options = CompareOptions {
+                filter: &ResourceFilter::default(),
+                full: false,
+                code_only: false,
+                ignore: &ignore,
+                only: None,
+            };
+            compare_directories(source.path(), target.path(), &options, "a", "b").unwrap()
+        };
+        assert_eq!(compare(&[]).modified.len(), 1);
+        assert!(!compare(&["sources/*.config.password"]).has_changes());
+        assert!(!compare(&["sources/Crm /config/password"]).has_changes());
+        assert_eq!(compare(&["topics/*.config.password"]).modified.len(), 1);
+    }
+
     #[test]
     fn test_change_kind_display() {
         assert_eq!(format!("{}", ChangeKind::Added), "added");
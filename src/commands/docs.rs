@@ -0,0 +1,242 @@
+//! Docs command implementation
+//!
+//! Generates the command reference from the clap definitions, so the man
+//! pages and markdown reference never drift from the actual flags.
+//!
+//! - `man`: one roff page per command (`vqx.1`, `vqx-sync-push.1`, ...)
+//! - `markdown`: a single `cli-reference.md` with a section per command
+
+use crate::cli::{Cli, DocsCommands, DocsFormat, DocsGenerateArgs};
+use crate::error::{Result, VqxError};
+use crate::theme::style;
+use clap::{Arg, ArgAction, Command, CommandFactory};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// File name of the markdown reference
+pub const MARKDOWN_FILE: &str = "cli-reference.md";
+
+/// Run a docs subcommand
+pub fn run(cmd: &DocsCommands) -> Result<()> {
+    match cmd {
+        DocsCommands::Generate(args) => run_generate(args),
+    }
+}
+
+/// Write the reference in the requested format
+fn run_generate(args: &DocsGenerateArgs) -> Result<()> {
+    let written = generate(args.format, &args.out)?;
+    for path in &written {
+        println!("{} {}", style("✓").green(), path.display());
+    }
+    Ok(())
+}
+
+/// Generate the reference into `out`, returning the files written
+pub fn generate(format: DocsFormat, out: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(out).map_err(|_| VqxError::FileWriteFailed {
+        path: out.display().to_string(),
+    })?;
+
+    let mut root = Cli::command();
+    root.build();
+
+    let mut commands = Vec::new();
+    collect(&root, Vec::new(), &mut commands);
+
+    match format {
+        DocsFormat::Man => commands
+            .iter()
+            .map(|(path, cmd)| {
+                let name = path.join("-");
+                let mut buf = Vec::new();
+                clap_mangen::Man::new(Command::clone(cmd).name(name.clone()))
+                    .render(&mut buf)
+                    .map_err(|e| VqxError::Other(format!("Failed to render man page: {}", e)))?;
+                write_file(&out.join(format!("{}.1", name)), &buf)
+            })
+            .collect(),
+        DocsFormat::Markdown => {
+            let content = render_markdown(&commands);
+            Ok(vec![write_file(
+                &out.join(MARKDOWN_FILE),
+                content.as_bytes(),
+            )?])
+        }
+    }
+}
+
+/// Walk the command tree depth-first, skipping `help` and hidden commands
+fn collect<'a>(cmd: &'a Command, mut path: Vec<String>, acc: &mut Vec<(Vec<String>, &'a Command)>) {
+    path.push(cmd.get_name().to_string());
+    acc.push((path.clone(), cmd));
+    for sub in cmd.get_subcommands() {
+        if sub.get_name() == "help" || sub.is_hide_set() {
+            continue;
+        }
+        collect(sub, path.clone(), acc);
+    }
+}
+
+fn render_markdown(commands: &[(Vec<String>, &Command)]) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# vqx command reference\n");
+    let _ = writeln!(
+        md,
+        "Generated by `vqx docs generate --format markdown`. Do not edit by hand.\n"
+    );
+
+    for (path, cmd) in commands {
+        let _ = writeln!(md, "## {}\n", path.join(" "));
+
+        if let Some(about) = cmd.get_long_about().or_else(|| cmd.get_about()) {
+            let _ = writeln!(md, "{}\n", about);
+        }
+
+        let usage = Command::clone(cmd).render_usage().to_string();
+        let usage = usage.trim_start_matches("Usage:").trim();
+        let _ = writeln!(md, "```text\n{}\n```\n", usage);
+
+        // Global options are documented once, on the root command
+        let is_root = path.len() == 1;
+        let args: Vec<&Arg> = cmd
+            .get_arguments()
+            .filter(|a| !a.is_hide_set() && !a.is_positional())
+            .filter(|a| is_root || !a.is_global_set())
+            .filter(|a| !matches!(a.get_id().as_str(), "help" | "version"))
+            .collect();
+        if !args.is_empty() {
+            let _ = writeln!(md, "| Option | Description |");
+            let _ = writeln!(md, "|--------|-------------|");
+            for arg in args {
+                let _ = writeln!(md, "| {} | {} |", option_name(arg), option_help(arg));
+            }
+            md.push('\n');
+        }
+
+        let subcommands: Vec<&Command> = cmd
+            .get_subcommands()
+            .filter(|s| s.get_name() != "help" && !s.is_hide_set())
+            .collect();
+        if !subcommands.is_empty() {
+            let _ = writeln!(md, "| Subcommand | Description |");
+            let _ = writeln!(md, "|------------|-------------|");
+            for sub in subcommands {
+                let about = sub.get_about().map(|a| a.to_string()).unwrap_or_default();
+                let _ = writeln!(md, "| `{}` | {} |", sub.get_name(), escape(&about));
+            }
+            md.push('\n');
+        }
+    }
+
+    md
+}
+
+/// e.g. "`-o, --output <OUTPUT>`"
+fn option_name(arg: &Arg) -> String {
+    let mut name = String::new();
+    if let Some(short) = arg.get_short() {
+        let _ = write!(name, "-{}", short);
+    }
+    if let Some(long) = arg.get_long() {
+        if !name.is_empty() {
+            name.push_str(", ");
+        }
+        let _ = write!(name, "--{}", long);
+    }
+    if matches!(arg.get_action(), ArgAction::Set | ArgAction::Append) {
+        let value = arg
+            .get_value_names()
+            .and_then(|names| names.first())
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| arg.get_id().as_str().to_uppercase());
+        let _ = write!(name, " <{}>", value);
+    }
+    format!("`{}`", name)
+}
+
+/// Help text with possible values, default and environment variable
+fn option_help(arg: &Arg) -> String {
+    let mut help = arg
+        .get_long_help()
+        .or_else(|| arg.get_help())
+        .map(|h| h.to_string())
+        .unwrap_or_default();
+
+    let possible: Vec<String> = arg
+        .get_possible_values()
+        .iter()
+        .filter(|v| !v.is_hide_set())
+        .map(|v| format!("`{}`", v.get_name()))
+        .collect();
+    if !possible.is_empty() && arg.get_action().takes_values() {
+        let _ = write!(help, " (values: {})", possible.join(", "));
+    }
+
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|v| v.to_string_lossy().into_owned())
+        .collect();
+    if !defaults.is_empty() && arg.get_action().takes_values() {
+        let _ = write!(help, " (default: `{}`)", defaults.join(","));
+    }
+
+    if let Some(env) = arg.get_env() {
+        let _ = write!(help, " (env: `{}`)", env.to_string_lossy());
+    }
+
+    escape(help.trim())
+}
+
+/// Keep a table cell on one line
+fn escape(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace('|', "\\|")
+}
+
+fn write_file(path: &Path, content: &[u8]) -> Result<PathBuf> {
+    std::fs::write(path, content).map_err(|_| VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    })?;
+    Ok(path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_generate_man() {
+        let dir = TempDir::new().unwrap();
+        let written = generate(DocsFormat::Man, dir.path()).unwrap();
+
+        assert!(dir.path().join("vqx.1").exists());
+        assert!(dir.path().join("vqx-export.1").exists());
+        assert!(dir.path().join("vqx-sync-push.1").exists());
+        assert!(!dir.path().join("vqx-help.1").exists());
+        assert!(written.len() > 10);
+    }
+
+    #[test]
+    fn test_generate_markdown() {
+        let dir = TempDir::new().unwrap();
+        generate(DocsFormat::Markdown, dir.path()).unwrap();
+
+        let md = std::fs::read_to_string(dir.path().join(MARKDOWN_FILE)).unwrap();
+        assert!(md.contains("## vqx export"));
+        assert!(md.contains("## vqx sync push"));
+        assert!(md.contains("`--include-hidden`"));
+        assert!(md.contains("(env: `VQX_TIMEOUT`)"));
+        // Global options only appear in the root section
+        assert_eq!(md.matches("(env: `VQX_TIMEOUT`)").count(), 1);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a | b\n  c"), "a \\| b c");
+    }
+}
@@ -4,32 +4,145 @@
 //!
 //! Based on: CLI Reference Guide PDF
 //! - "Prerequisites" section: "The Vantiq CLI is a Java (Groovy) application
-//!    and requires an installation of Java 11."
+//!   and requires an installation of Java 11."
 //! - "Installation" section: CLI binary location
 
-use crate::cli::DoctorArgs;
+use crate::backend::Executor;
+use crate::cli::{DoctorArgs, OutputFormat, ProfileCommands, ProfileInitArgs};
+use crate::commands::{profile, safe_delete};
 use crate::config::Config;
-use crate::error::Result;
-use crate::profile::ProfileManager;
+use crate::error::{Result, VqxError};
+use crate::offline;
+use crate::profile::{ProfileManager, ProfileStore};
+use crate::prompt;
+use crate::report;
+use crate::theme::style;
 use crate::underlying::{CliOptions, UnderlyingCli};
-use console::{style, Emoji};
+use clap::ValueEnum;
+use console::Emoji;
 use regex::Regex;
+use serde::Serialize;
+use std::fs;
+use std::net::ToSocketAddrs;
+use std::path::PathBuf;
 use std::process::Command;
+use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
 // Emojis for status display
 static CHECK: Emoji<'_, '_> = Emoji("✅ ", "[OK] ");
 static CROSS: Emoji<'_, '_> = Emoji("❌ ", "[FAIL] ");
-static WARN: Emoji<'_, '_> = Emoji("⚠️  ", "[WARN] ");
-static INFO: Emoji<'_, '_> = Emoji("ℹ️  ", "[INFO] ");
+
+/// Limit for DNS lookups and the release check
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(5);
+
+const LATEST_RELEASE_API: &str = "https://api.github.com/repos/shun-sonohara/vqx/releases/latest";
+const RELEASES_URL: &str = "https://github.com/shun-sonohara/vqx/releases";
 
 /// Result of a single check
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct CheckResult {
     pub name: String,
     pub passed: bool,
     pub message: String,
     pub details: Option<String>,
+    /// Outcome of `--fix` for this check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+    /// Timings measured by `--benchmark`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<Latency>,
+    /// What `--fix` can do about this check
+    #[serde(skip)]
+    pub remedy: Option<Remedy>,
+}
+
+/// Round-trip latency of one benchmarked operation, in milliseconds
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Latency {
+    pub runs: usize,
+    pub min_ms: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+}
+
+impl Latency {
+    fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let min = samples.iter().min()?;
+        let max = samples.iter().max()?;
+        let total: Duration = samples.iter().sum();
+        Some(Self {
+            runs: samples.len(),
+            min_ms: min.as_millis() as u64,
+            avg_ms: (total / samples.len() as u32).as_millis() as u64,
+            max_ms: max.as_millis() as u64,
+        })
+    }
+}
+
+impl std::fmt::Display for Latency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "min {} ms / avg {} ms / max {} ms ({} runs)",
+            self.min_ms, self.avg_ms, self.max_ms, self.runs
+        )
+    }
+}
+
+/// A change `vqx doctor --fix` can make
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Remedy {
+    /// Create the config directory
+    CreateConfigDir(PathBuf),
+    /// Write a config.toml with the default settings
+    ScaffoldConfig(PathBuf),
+    /// Explain how to install the Vantiq CLI (cannot be automated)
+    InstallCli,
+    /// Run the `vqx profile init` wizard
+    InitProfile,
+}
+
+impl Remedy {
+    fn describe(&self) -> String {
+        match self {
+            Remedy::CreateConfigDir(dir) => format!("Create {}", dir.display()),
+            Remedy::ScaffoldConfig(path) => {
+                format!("Write {} with default settings", path.display())
+            }
+            Remedy::InstallCli => "Install the Vantiq CLI".to_string(),
+            Remedy::InitProfile => "Create a profile with `vqx profile init`".to_string(),
+        }
+    }
+
+    async fn apply(&self, config: &Config) -> Result<String> {
+        match self {
+            Remedy::CreateConfigDir(dir) => {
+                fs::create_dir_all(dir).map_err(|_| VqxError::FileWriteFailed {
+                    path: dir.display().to_string(),
+                })?;
+                Ok(format!("Created {}", dir.display()))
+            }
+            Remedy::ScaffoldConfig(path) => {
+                // Never overwrite a config written since the check ran
+                if !path.exists() {
+                    Config::default().save_to(path)?;
+                }
+                Ok(format!("Wrote {}", path.display()))
+            }
+            Remedy::InstallCli => Ok(format!(
+                "Download the CLI from Help -> Developer Resources in the Vantiq UI, unzip it \
+                 and add its bin directory to PATH, or point `cli_path` in config.toml \
+                 (or --cli / VQX_CLI_PATH) at '{}'",
+                config.cli_path
+            )),
+            Remedy::InitProfile => {
+                let init = ProfileCommands::Init(ProfileInitArgs { name: None });
+                profile::run(&init, config, OutputFormat::Text).await?;
+                Ok("Created a profile".to_string())
+            }
+        }
+    }
 }
 
 impl CheckResult {
@@ -39,6 +152,9 @@ impl CheckResult {
             passed: true,
             message: message.into(),
             details: None,
+            fix: None,
+            latency: None,
+            remedy: None,
         }
     }
 
@@ -48,6 +164,9 @@ impl CheckResult {
             passed: false,
             message: message.into(),
             details: None,
+            fix: None,
+            latency: None,
+            remedy: None,
         }
     }
 
@@ -55,10 +174,22 @@ impl CheckResult {
         self.details = Some(details.into());
         self
     }
+
+    fn with_remedy(mut self, remedy: Remedy) -> Self {
+        self.remedy = Some(remedy);
+        self
+    }
 }
 
 /// Run the doctor command
-pub async fn run(args: &DoctorArgs, config: &Config) -> Result<Vec<CheckResult>> {
+///
+/// `profile_name` selects the profile for `--benchmark` (default profile
+/// when `None`).
+pub async fn run(
+    args: &DoctorArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+) -> Result<Vec<CheckResult>> {
     let mut results = Vec::new();
 
     if !args.cli_only {
@@ -72,7 +203,43 @@ pub async fn run(args: &DoctorArgs, config: &Config) -> Result<Vec<CheckResult>>
         results.push(check_cli(&config.cli_path).await);
 
         // Check CLI help command works
-        results.push(check_cli_help(&config.cli_path).await);
+        if offline::enabled() {
+            results.push(CheckResult::ok("CLI Help", "Skipped (--offline)"));
+        } else {
+            results.push(check_cli_help(&config.cli_path).await);
+        }
+    }
+
+    if !args.java_only && !args.cli_only {
+        results.extend(check_config());
+        results.extend(check_profiles().await);
+        results.push(check_write_access(
+            "Config Directory Access",
+            Config::config_dir().ok(),
+        ));
+        results.push(check_write_access(
+            "Backup Directory Access",
+            Some(safe_delete::backup_root()),
+        ));
+        if let Some(result) = check_git() {
+            results.push(result);
+        }
+        if offline::enabled() {
+            results.push(CheckResult::ok(
+                "vqx Version",
+                format!(
+                    "{} (latest release not checked: --offline)",
+                    env!("CARGO_PKG_VERSION")
+                ),
+            ));
+        } else {
+            results.push(check_version().await);
+        }
+    }
+
+    if args.fix {
+        // Only with --fix: probing the keychain may prompt for access
+        results.push(check_secure_storage());
     }
 
     if args.test_connection {
@@ -80,12 +247,74 @@ pub async fn run(args: &DoctorArgs, config: &Config) -> Result<Vec<CheckResult>>
         results.push(check_connection(&config.cli_path).await);
     }
 
+    if args.benchmark {
+        results.extend(benchmark(config, profile_name, args.iterations.max(1)).await);
+    }
+
     Ok(results)
 }
 
+/// Apply the remedies found by [`run`] (`--fix`), recording each outcome
+///
+/// Every change is confirmed unless `--yes` is given. Without a terminal
+/// (or with machine-readable output) only `--yes` applies changes, and the
+/// profile wizard is skipped.
+pub async fn fix(
+    results: &mut [CheckResult],
+    args: &DoctorArgs,
+    config: &Config,
+    interactive: bool,
+) -> Result<()> {
+    let interactive = interactive && prompt::is_interactive();
+
+    for result in results.iter_mut() {
+        let remedy = match result.remedy.clone() {
+            Some(remedy) => remedy,
+            None => continue,
+        };
+
+        let skipped = if remedy == Remedy::InstallCli {
+            // Nothing to confirm: only instructions
+            None
+        } else if remedy == Remedy::InitProfile && !interactive {
+            Some("Skipped: run `vqx profile init` in a terminal")
+        } else if args.yes {
+            None
+        } else if !interactive {
+            Some("Skipped: run with --yes to apply")
+        } else {
+            let confirmed = prompt::ask_yes_no(
+                format!("{}?", remedy.describe()),
+                true,
+                "Doctor --fix",
+                "pass --yes to apply",
+            )?;
+            (!confirmed).then_some("Skipped")
+        };
+
+        if let Some(reason) = skipped {
+            result.fix = Some(reason.to_string());
+            continue;
+        }
+
+        match remedy.apply(config).await {
+            Ok(message) => {
+                result.fix = Some(message);
+                if remedy != Remedy::InstallCli {
+                    result.passed = true;
+                    result.remedy = None;
+                }
+            }
+            Err(e) => result.fix = Some(format!("Failed: {}", e)),
+        }
+    }
+
+    Ok(())
+}
+
 /// Check Java installation
 /// PDF: "Prerequisites" - "requires an installation of Java 11"
-async fn check_java() -> CheckResult {
+pub(crate) async fn check_java() -> CheckResult {
     info!("Checking Java installation...");
 
     // Try to run java -version
@@ -116,9 +345,9 @@ async fn check_java() -> CheckResult {
                             "Java",
                             format!("Java {} found (>= 11 required)", version_str),
                         )
-                        .with_details(format!(
+                        .with_details(
                             "PDF Reference: Prerequisites section states 'requires an installation of Java 11'"
-                        ))
+                        )
                     } else {
                         CheckResult::fail(
                             "Java",
@@ -127,10 +356,10 @@ async fn check_java() -> CheckResult {
                                 version_str
                             ),
                         )
-                        .with_details(format!(
+                        .with_details(
                             "PDF Reference: Prerequisites section - 'The Vantiq CLI is a Java (Groovy) application and requires an installation of Java 11.'\n\
                              Please install Java 11 or later from https://adoptium.net/"
-                        ))
+                        )
                     }
                 } else {
                     CheckResult::fail(
@@ -183,6 +412,7 @@ async fn check_cli(cli_path: &str) -> CheckResult {
             "Vantiq CLI",
             format!("CLI not found: {}", cli_path),
         )
+        .with_remedy(Remedy::InstallCli)
         .with_details(format!(
             "PDF Reference: Installation section\n\
              - Download from: Help -> Developer Resources in Vantiq UI\n\
@@ -194,6 +424,356 @@ async fn check_cli(cli_path: &str) -> CheckResult {
     }
 }
 
+/// Check the config directory and config.toml
+///
+/// Both are optional (defaults apply), so missing ones pass with a remedy.
+fn check_config() -> Vec<CheckResult> {
+    let paths = || -> Result<(PathBuf, PathBuf)> {
+        Ok((Config::config_dir()?, Config::config_file_path()?))
+    };
+    let (dir, path) = match paths() {
+        Ok(paths) => paths,
+        Err(e) => {
+            return vec![CheckResult::fail(
+                "Config",
+                format!("Could not determine config directory: {}", e),
+            )]
+        }
+    };
+
+    let mut results = Vec::new();
+    if dir.is_dir() {
+        results.push(CheckResult::ok(
+            "Config Directory",
+            dir.display().to_string(),
+        ));
+    } else {
+        results.push(
+            CheckResult::ok(
+                "Config Directory",
+                format!("Not created yet: {}", dir.display()),
+            )
+            .with_remedy(Remedy::CreateConfigDir(dir)),
+        );
+    }
+
+    if path.is_file() {
+        // `Config::load` falls back to defaults, so a broken file goes unnoticed elsewhere
+        results.push(match Config::load_from(&path) {
+            Ok(config) => {
+                let issues = config_issues(&config);
+                if issues.is_empty() {
+                    CheckResult::ok("Config File", format!("Found at: {}", path.display()))
+                } else {
+                    CheckResult::fail(
+                        "Config File",
+                        format!("{} invalid value(s) in {}", issues.len(), path.display()),
+                    )
+                    .with_details(issues.join("\n"))
+                }
+            }
+            Err(e) => CheckResult::fail(
+                "Config File",
+                format!("Failed to parse: {}", path.display()),
+            )
+            .with_details(e.to_string()),
+        });
+    } else {
+        results.push(
+            CheckResult::ok("Config File", "No config.toml, using defaults")
+                .with_remedy(Remedy::ScaffoldConfig(path)),
+        );
+    }
+    results
+}
+
+/// Values that parse but cannot work
+fn config_issues(config: &Config) -> Vec<String> {
+    let mut issues = Vec::new();
+    if config.cli_path.trim().is_empty() {
+        issues.push("cli_path is empty".to_string());
+    }
+    if config.timeout_seconds == 0 {
+        issues.push("timeout_seconds must be greater than 0".to_string());
+    }
+    for (command, seconds) in &config.timeouts {
+        if *seconds == 0 {
+            issues.push(format!("timeouts.{} must be greater than 0", command));
+        }
+    }
+    if config.default_chunk_size == 0 {
+        issues.push("default_chunk_size must be greater than 0".to_string());
+    }
+    if !(0.0..=1.0).contains(&config.retry.jitter) {
+        issues.push(format!(
+            "retry.jitter must be between 0.0 and 1.0 (got {})",
+            config.retry.jitter
+        ));
+    }
+    if !matches!(
+        config.logging.level.to_lowercase().as_str(),
+        "trace" | "debug" | "info" | "warn" | "error"
+    ) {
+        issues.push(format!(
+            "logging.level '{}' is not one of trace, debug, info, warn, error",
+            config.logging.level
+        ));
+    }
+    if OutputFormat::from_str(&config.output.default_format, true).is_err() {
+        issues.push(format!(
+            "output.default_format '{}' is not a known format",
+            config.output.default_format
+        ));
+    }
+    issues
+}
+
+/// Check that profiles are configured, and that each one can be used
+async fn check_profiles() -> Vec<CheckResult> {
+    let store = match ProfileStore::load() {
+        Ok(store) => store,
+        Err(e) => {
+            return vec![CheckResult::fail(
+                "Profiles",
+                format!("Could not load profiles: {}", e),
+            )]
+        }
+    };
+
+    if store.profiles.is_empty() {
+        return vec![
+            CheckResult::ok("Profiles", "No profiles configured").with_remedy(Remedy::InitProfile)
+        ];
+    }
+
+    let mut results = vec![CheckResult::ok(
+        "Profiles",
+        format!(
+            "{} profile(s), default: {}",
+            store.profiles.len(),
+            store.default_profile
+        ),
+    )];
+
+    let mut names: Vec<&String> = store.profiles.keys().collect();
+    names.sort();
+    for name in names {
+        results.push(check_profile(&store, name).await);
+    }
+    results
+}
+
+/// Check one profile: credentials present, options valid, host resolvable
+///
+/// Secrets in the keychain are not read, so this never prompts.
+async fn check_profile(store: &ProfileStore, name: &str) -> CheckResult {
+    let check_name = format!("Profile '{}'", name);
+    let profile = &store.profiles[name];
+
+    let has_auth = match profile.credential {
+        Some(ref credential) => match store.credentials.get(credential) {
+            Some(c) => c.use_secure_storage || c.token.is_some() || c.password.is_some(),
+            None => {
+                return CheckResult::fail(
+                    check_name,
+                    format!("Credential '{}' does not exist", credential),
+                )
+            }
+        },
+        None => profile.use_secure_storage || profile.has_auth(),
+    };
+    if !has_auth {
+        return CheckResult::fail(check_name, "No password or token configured").with_details(
+            format!("Set one with: vqx profile set {} --token <TOKEN>", name),
+        );
+    }
+
+    if let Err(e) = profile.validate() {
+        return CheckResult::fail(check_name, e.to_string());
+    }
+
+    let host = match url_host(&profile.url) {
+        Some(host) => host,
+        None => return CheckResult::fail(check_name, format!("Invalid URL: {}", profile.url)),
+    };
+    let lookup = tokio::task::spawn_blocking({
+        let host = host.clone();
+        move || {
+            host.to_socket_addrs()
+                .map(|mut addrs| addrs.next().is_some())
+        }
+    });
+    match tokio::time::timeout(NETWORK_TIMEOUT, lookup).await {
+        Ok(Ok(Ok(true))) => CheckResult::ok(check_name, profile.url.clone()),
+        Ok(Ok(Ok(false))) | Ok(Ok(Err(_))) => {
+            CheckResult::fail(check_name, format!("Cannot resolve host: {}", host))
+                .with_details(profile.url.clone())
+        }
+        _ => {
+            CheckResult::fail(check_name, "DNS lookup timed out").with_details(profile.url.clone())
+        }
+    }
+}
+
+/// `host:port` of a URL, with the port defaulted from the scheme
+fn url_host(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?;
+    if authority.is_empty() {
+        return None;
+    }
+
+    // Bracketed IPv6 literal, e.g. [::1]:8080
+    let has_port = match authority.rfind(']') {
+        Some(end) => authority[end..].contains(':'),
+        None => authority.contains(':'),
+    };
+    if has_port {
+        return Some(authority.to_string());
+    }
+    let port = match scheme.to_lowercase().as_str() {
+        "https" => 443,
+        "http" => 80,
+        _ => return None,
+    };
+    Some(format!("{}:{}", authority, port))
+}
+
+/// Check that files can be created in `dir` (or the nearest existing parent)
+fn check_write_access(name: &str, dir: Option<PathBuf>) -> CheckResult {
+    let dir = match dir {
+        Some(dir) => dir,
+        None => return CheckResult::fail(name, "Could not determine directory"),
+    };
+
+    // A missing directory is created on first use, so test where that will happen
+    let existing = dir.ancestors().find(|p| p.is_dir());
+    let writable = existing.map(|p| tempfile::NamedTempFile::new_in(p).is_ok());
+    match writable {
+        Some(true) => CheckResult::ok(name, format!("Writable: {}", dir.display())),
+        _ => CheckResult::fail(name, format!("Not writable: {}", dir.display())),
+    }
+}
+
+/// Check git when working inside a git repository (exports and sync dirs
+/// are usually versioned)
+fn check_git() -> Option<CheckResult> {
+    let cwd = std::env::current_dir().ok()?;
+    if !cwd.ancestors().any(|p| p.join(".git").exists()) {
+        return None;
+    }
+
+    Some(match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => CheckResult::ok(
+            "Git",
+            String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        ),
+        _ => CheckResult::fail("Git", "git not found in PATH")
+            .with_details(format!("{} is a git repository", cwd.display())),
+    })
+}
+
+/// Compare this build with the latest GitHub release
+///
+/// Never fails: being offline or behind is reported, not an error.
+async fn check_version() -> CheckResult {
+    let current = env!("CARGO_PKG_VERSION");
+    let latest = latest_release().await;
+    debug!(latest = ?latest, "Latest vqx release");
+
+    match latest {
+        Some(latest) if parse_version(&latest) > parse_version(current) => CheckResult::ok(
+            "vqx Version",
+            format!("{} (update available: {})", current, latest),
+        )
+        .with_details(RELEASES_URL),
+        Some(_) => CheckResult::ok("vqx Version", format!("{} (latest)", current)),
+        None => CheckResult::ok(
+            "vqx Version",
+            format!("{} (could not check latest release)", current),
+        ),
+    }
+}
+
+/// Tag of the latest release, via curl so no HTTP client is linked in
+async fn latest_release() -> Option<String> {
+    let output = tokio::process::Command::new("curl")
+        .args(["-fsSL", "-H", "Accept: application/vnd.github+json"])
+        .arg(LATEST_RELEASE_API)
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(NETWORK_TIMEOUT, output)
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let release: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    release
+        .get("tag_name")?
+        .as_str()
+        .map(|tag| tag.trim_start_matches('v').to_string())
+}
+
+/// "v1.2.3" -> (1, 2, 3); pre-release suffixes are ignored
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version
+        .trim_start_matches('v')
+        .split(['.', '-', '+'])
+        .map(|p| p.parse::<u64>());
+    Some((
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+        parts.next()?.ok()?,
+    ))
+}
+
+/// Check that secrets can be stored in the OS keychain
+///
+/// Only fails if a profile or credential relies on secure storage.
+fn check_secure_storage() -> CheckResult {
+    const PROBE_OWNER: &str = "doctor";
+    const PROBE_KEY: &str = "vqx-doctor-probe";
+
+    let manager = match ProfileManager::new() {
+        Ok(manager) => manager,
+        Err(e) => {
+            return CheckResult::fail("Secure Storage", format!("Could not load profiles: {}", e))
+        }
+    };
+
+    let probe = manager
+        .set_secret(PROBE_OWNER, PROBE_KEY, "ok")
+        .and_then(|_| manager.get_secret(PROBE_OWNER, PROBE_KEY));
+    let _ = manager.delete_secret(PROBE_OWNER, PROBE_KEY);
+
+    let error = match probe {
+        Ok(Some(value)) if value == "ok" => {
+            return CheckResult::ok("Secure Storage", "OS keychain is available")
+        }
+        Ok(_) => "OS keychain is not available".to_string(),
+        Err(e) => format!("OS keychain is not available: {}", e),
+    };
+
+    let store = manager.store();
+    let in_use = store.profiles.values().any(|p| p.use_secure_storage)
+        || store.credentials.values().any(|c| c.use_secure_storage);
+    let result = if in_use {
+        CheckResult::fail("Secure Storage", error)
+    } else {
+        CheckResult::ok(
+            "Secure Storage",
+            format!("{} (not used by any profile)", error),
+        )
+    };
+    result.with_details(
+        "Without a keychain, store secrets in profiles.toml (omit --secure) \
+         or unlock the keychain / start a Secret Service provider",
+    )
+}
+
 /// Check CLI help command works
 /// PDF: "Help" - "The help command displays a short summary of the commands available"
 async fn check_cli_help(cli_path: &str) -> CheckResult {
@@ -227,6 +807,11 @@ async fn check_cli_help(cli_path: &str) -> CheckResult {
 
 /// Check connection to Vantiq server
 /// Uses the default profile or prompts for credentials
+/// Procedure run to check that credentials are accepted; any authenticated
+/// user can call it
+/// PDF: Example shows "vantiq -s personal execute Utils.getNamespaceAndProfiles"
+pub(crate) const CONNECTION_CHECK_PROCEDURE: &str = "Utils.getNamespaceAndProfiles";
+
 async fn check_connection(cli_path: &str) -> CheckResult {
     info!("Checking connection to Vantiq server...");
 
@@ -264,10 +849,8 @@ async fn check_connection(cli_path: &str) -> CheckResult {
     let cli = UnderlyingCli::new(cli_path.to_string());
     let options = CliOptions::from_profile(&profile);
 
-    // Try to run a simple command that requires authentication
-    // PDF: Example shows "vantiq -s personal execute Utils.getNamespaceAndProfiles"
     match cli
-        .run_procedure(&options, "Utils.getNamespaceAndProfiles", &[])
+        .run_procedure(&options, CONNECTION_CHECK_PROCEDURE, &[])
         .await
     {
         Ok(result) => {
@@ -299,6 +882,123 @@ async fn check_connection(cli_path: &str) -> CheckResult {
     }
 }
 
+/// Time round trips against a profile (`--benchmark`)
+///
+/// `help` needs no server, so it measures JVM startup alone; the time the
+/// other operations take beyond it is spent on the network and server.
+async fn benchmark(
+    config: &Config,
+    profile_name: Option<&str>,
+    iterations: u32,
+) -> Vec<CheckResult> {
+    info!(iterations, "Benchmarking round trips...");
+
+    let manager = match ProfileManager::new() {
+        Ok(manager) => manager,
+        Err(e) => {
+            return vec![CheckResult::fail(
+                "Benchmark",
+                format!("Could not load profiles: {}", e),
+            )]
+        }
+    };
+    let profile = match profile_name {
+        Some(name) => manager.get_resolved(name),
+        None => manager.get_default_resolved(),
+    };
+    let profile = match profile {
+        Ok(profile) => profile,
+        Err(e) => return vec![CheckResult::fail("Benchmark", e.to_string())],
+    };
+
+    let options = CliOptions::from_profile(&profile);
+    let cli = match Executor::new(UnderlyingCli::new(config.cli_path.clone()), Some(&profile)) {
+        Ok(cli) => cli,
+        Err(e) => return vec![CheckResult::fail("Benchmark", e.to_string())],
+    };
+
+    let mut results = Vec::new();
+    let mut baseline = None;
+    for operation in ["help", "list types", "select types"] {
+        let name = format!("Benchmark: {}", operation);
+        let mut samples = Vec::new();
+        let mut failure = None;
+
+        for _ in 0..iterations {
+            let start = Instant::now();
+            let result = match operation {
+                "help" => cli.cli().help().await,
+                "list types" => cli.list(&options, "types").await,
+                _ => {
+                    cli.select(&options, "types", None, None, Some("name"), None, None)
+                        .await
+                }
+            };
+            match result {
+                Ok(result) if result.success() => samples.push(start.elapsed()),
+                Ok(result) => {
+                    failure = Some(format!("Exited with code {}", result.code()));
+                    break;
+                }
+                Err(e) => {
+                    failure = Some(e.to_string());
+                    break;
+                }
+            }
+        }
+
+        if let Some(failure) = failure {
+            results.push(CheckResult::fail(name, failure));
+            continue;
+        }
+        let Some(latency) = Latency::from_samples(&samples) else {
+            continue;
+        };
+
+        let mut result = CheckResult::ok(name, latency.to_string());
+        match baseline {
+            None => {
+                result = result.with_details("No server round trip: JVM startup only");
+                baseline = Some(latency.avg_ms);
+            }
+            Some(startup) => {
+                result = result.with_details(format!(
+                    "~{} ms beyond JVM startup (network and server) against {}",
+                    latency.avg_ms.saturating_sub(startup),
+                    profile.url
+                ));
+            }
+        }
+        result.latency = Some(latency);
+        results.push(result);
+    }
+    results
+}
+
+/// Doctor report for `--output json` and `--output yaml`
+#[derive(Debug, Serialize)]
+pub struct DoctorResult<'a> {
+    pub success: bool,
+    pub checks: &'a [CheckResult],
+}
+
+/// Print check results in a machine-readable format
+///
+/// NDJSON prints one check per line.
+pub fn print_report(results: &[CheckResult], output_format: OutputFormat) -> Result<()> {
+    if matches!(output_format, OutputFormat::Ndjson) {
+        print!("{}", report::to_ndjson(results)?);
+        return Ok(());
+    }
+
+    let result = DoctorResult {
+        success: results.iter().all(|r| r.passed),
+        checks: results,
+    };
+    println!("{}", report::render(&result, output_format)?);
+    Ok(())
+}
+
 /// Display check results to the user
 pub fn display_results(results: &[CheckResult], verbose: bool) {
     println!();
@@ -326,6 +1026,19 @@ pub fn display_results(results: &[CheckResult], verbose: bool) {
                 }
             }
         }
+
+        if let Some(ref fix) = result.fix {
+            println!("    {} {}", style("Fix:").cyan(), fix);
+        } else if let Some(ref remedy) = result.remedy {
+            println!(
+                "    {}",
+                style(format!(
+                    "Fix available: {} (vqx doctor --fix)",
+                    remedy.describe()
+                ))
+                .dim()
+            );
+        }
         println!();
     }
 
@@ -347,6 +1060,64 @@ pub fn display_results(results: &[CheckResult], verbose: bool) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_doctor_report() {
+        let checks = vec![
+            CheckResult::ok("Java", "Java 11 found"),
+            CheckResult::fail("CLI", "vantiq not found"),
+        ];
+        let result = DoctorResult {
+            success: false,
+            checks: &checks,
+        };
+
+        let yaml = report::render(&result, OutputFormat::Yaml).unwrap();
+        assert!(yaml.contains("success: false"));
+        assert!(yaml.contains("- name: Java"));
+        assert_eq!(report::to_ndjson(&checks).unwrap().lines().count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fix() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config_dir = dir.path().join("vqx");
+        let config_file = config_dir.join("config.toml");
+        let mut checks = vec![
+            CheckResult::ok("Config Directory", "Not created yet")
+                .with_remedy(Remedy::CreateConfigDir(config_dir.clone())),
+            CheckResult::ok("Config File", "No config.toml")
+                .with_remedy(Remedy::ScaffoldConfig(config_file.clone())),
+            CheckResult::ok("Profiles", "No profiles configured").with_remedy(Remedy::InitProfile),
+            CheckResult::fail("Vantiq CLI", "CLI not found").with_remedy(Remedy::InstallCli),
+        ];
+        let args = DoctorArgs {
+            fix: true,
+            yes: true,
+            ..Default::default()
+        };
+
+        fix(&mut checks, &args, &Config::default(), false)
+            .await
+            .unwrap();
+
+        assert!(config_dir.is_dir());
+        assert!(config_file.is_file());
+        assert!(checks[0].remedy.is_none());
+        // The profile wizard needs a terminal
+        assert!(checks[2].fix.as_deref().unwrap().starts_with("Skipped"));
+        // Installing the CLI is only explained
+        assert!(!checks[3].passed);
+        assert!(checks[3]
+            .fix
+            .as_deref()
+            .unwrap()
+            .contains("Developer Resources"));
+
+        let json = serde_json::to_value(&checks[0]).unwrap();
+        assert!(json["fix"].as_str().unwrap().starts_with("Created"));
+        assert!(json.get("remedy").is_none());
+    }
+
     #[test]
     fn test_parse_java_version_modern() {
         assert_eq!(parse_java_major_version("11.0.12"), Some(11));
@@ -360,6 +1131,68 @@ mod tests {
         assert_eq!(parse_java_major_version("1.7.0"), Some(7));
     }
 
+    #[test]
+    fn test_config_issues() {
+        assert!(config_issues(&Config::default()).is_empty());
+
+        let mut config = Config {
+            timeout_seconds: 0,
+            ..Default::default()
+        };
+        config.logging.level = "verbose".to_string();
+        config.output.default_format = "xml".to_string();
+        let issues = config_issues(&config);
+        assert_eq!(issues.len(), 3);
+        assert!(issues[0].starts_with("timeout_seconds"));
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(
+            url_host("https://dev.vantiq.com"),
+            Some("dev.vantiq.com:443".to_string())
+        );
+        assert_eq!(
+            url_host("http://user@localhost:8080/ui?x=1"),
+            Some("localhost:8080".to_string())
+        );
+        assert_eq!(url_host("http://[::1]/"), Some("[::1]:80".to_string()));
+        assert_eq!(url_host("dev.vantiq.com"), None);
+        assert_eq!(url_host("ftp://dev.vantiq.com"), None);
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v0.1.3"), Some((0, 1, 3)));
+        assert_eq!(parse_version("1.2.0-rc.1"), Some((1, 2, 0)));
+        assert!(parse_version("0.2.0") > parse_version("0.1.10"));
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    #[test]
+    fn test_latency() {
+        let samples = [
+            Duration::from_millis(1200),
+            Duration::from_millis(900),
+            Duration::from_millis(1500),
+        ];
+        let latency = Latency::from_samples(&samples).unwrap();
+        assert_eq!(
+            latency,
+            Latency {
+                runs: 3,
+                min_ms: 900,
+                avg_ms: 1200,
+                max_ms: 1500,
+            }
+        );
+        assert_eq!(
+            latency.to_string(),
+            "min 900 ms / avg 1200 ms / max 1500 ms (3 runs)"
+        );
+        assert!(Latency::from_samples(&[]).is_none());
+    }
+
     #[test]
     fn test_check_result_ok() {
         let result = CheckResult::ok("Test", "All good");
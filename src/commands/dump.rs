@@ -0,0 +1,279 @@
+//! Dump/Load command implementation
+//!
+//! Single-resource counterparts of export and import.
+//!
+//! Based on: CLI Reference Guide PDF - "Dump" and "Load" sections
+//!
+//! PDF: "dump <resource> <resourceId> <fileName>" / "load <resource> <fileName>"
+//!
+//! `dump` normalizes the written file like `export` (including code
+//! extraction), so a dumped resource can live next to exported ones. `load`
+//! embeds extracted code again, validates the definition against the
+//! built-in schema and asks before it replaces a resource that exists.
+
+use crate::cache::ExportCache;
+use crate::cli::{DumpArgs, LoadArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::lock::OperationLock;
+use crate::normalizer::{self, PlannedWrite, ResourceNormalizer};
+use crate::profile::{Profile, ProfileManager};
+use crate::prompt;
+use crate::report;
+use crate::theme::style;
+use crate::underlying::{CliOptions, UnderlyingCli};
+use crate::validation::{self, Violation};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Write;
+use std::path::PathBuf;
+use tempfile::NamedTempFile;
+
+/// Result of dump
+#[derive(Debug, Serialize)]
+pub struct DumpResult {
+    pub success: bool,
+    pub resource: String,
+    pub name: String,
+    pub file: PathBuf,
+    pub normalized: bool,
+}
+
+/// Result of load
+#[derive(Debug, Serialize)]
+pub struct LoadResult {
+    pub success: bool,
+    pub resource: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub file: PathBuf,
+    /// The resource existed and was replaced
+    pub replaced: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run the dump command
+pub async fn dump(
+    args: &DumpArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<DumpResult> {
+    let (_, profile) = resolve_profile(profile_name)?;
+    let cli = underlying_cli(config, "dump");
+    let options = CliOptions::from_profile(&profile);
+
+    if let Some(dir) = args.file.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|_| VqxError::FileWriteFailed {
+            path: dir.display().to_string(),
+        })?;
+    }
+
+    let result = cli
+        .dump(
+            &options,
+            &args.resource,
+            &args.name,
+            &args.file.to_string_lossy(),
+        )
+        .await?;
+    if !result.success() {
+        return Err(VqxError::CliExecutionFailed {
+            code: result.code(),
+            message: result.stderr.trim().to_string(),
+        });
+    }
+
+    if args.normalize {
+        let content =
+            std::fs::read_to_string(&args.file).map_err(|_| VqxError::FileReadFailed {
+                path: args.file.display().to_string(),
+            })?;
+        let value: Value = serde_json::from_str(&content).map_err(|e| VqxError::InvalidJson {
+            message: format!("{}: {}", args.file.display(), e),
+        })?;
+        ResourceNormalizer::new(config.normalization.clone())
+            .plan_resource(&args.file, &args.resource, &value)?
+            .iter()
+            .try_for_each(PlannedWrite::apply)?;
+    }
+
+    let result = DumpResult {
+        success: true,
+        resource: args.resource.clone(),
+        name: args.name.clone(),
+        file: args.file.clone(),
+        normalized: args.normalize,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        println!(
+            "{} Dumped {}/{} to {}{}",
+            style("✓").green(),
+            result.resource,
+            style(&result.name).bold(),
+            result.file.display(),
+            if result.normalized {
+                style(" (normalized)").dim().to_string()
+            } else {
+                String::new()
+            }
+        );
+    }
+    Ok(result)
+}
+
+/// Run the load command
+pub async fn load(
+    args: &LoadArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<LoadResult> {
+    // Code extracted by dump or export goes back into the definition
+    let raw = std::fs::read_to_string(&args.file).map_err(|_| VqxError::FileReadFailed {
+        path: args.file.display().to_string(),
+    })?;
+    let content = normalizer::read_embedded(&args.file)?;
+    let value: Value = serde_json::from_str(&content).map_err(|e| VqxError::InvalidJson {
+        message: format!("{}: {}", args.file.display(), e),
+    })?;
+
+    if !args.no_validate {
+        let violations = validation::validate_str(&content, &args.resource);
+        if !violations.is_empty() {
+            if !output_format.is_machine_readable() {
+                display_violations(&args.file, &violations);
+            }
+            return Err(VqxError::Other(format!(
+                "{} has {} schema violation(s); fix them or pass --no-validate",
+                args.file.display(),
+                violations.len()
+            )));
+        }
+    }
+
+    let (profile_name, profile) = resolve_profile(profile_name)?;
+    let cli = underlying_cli(config, "load");
+    let options = CliOptions::from_profile(&profile);
+
+    // Ask before replacing a resource that exists
+    let name = value.get("name").and_then(Value::as_str).map(String::from);
+    let replaced = match name {
+        Some(ref name) => {
+            let found = cli.find(&options, &args.resource, name).await?;
+            found.success() && !found.stdout.trim().is_empty()
+        }
+        None => false,
+    };
+    if replaced && !args.yes {
+        if output_format.is_machine_readable() {
+            return Err(VqxError::DestructiveOperationNotConfirmed {
+                operation: "load".to_string(),
+            });
+        }
+        let confirmed = prompt::confirm(
+            format!(
+                "{}/{} exists on {} ({}). Replace it?",
+                args.resource,
+                name.as_deref().unwrap_or_default(),
+                profile.url,
+                profile_name
+            ),
+            "Load",
+            "--yes",
+        )?;
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Load".to_string(),
+            });
+        }
+    }
+
+    let _lock = OperationLock::acquire(
+        "load",
+        &profile_name,
+        &profile.url,
+        profile.namespace.as_deref(),
+        &args.lock,
+    )
+    .await?;
+    ExportCache::new(config).invalidate(&profile_name, profile.namespace.as_deref());
+
+    // The CLI reads a file, so a definition with embedded code needs a copy
+    let embedded = if content == raw {
+        None
+    } else {
+        let mut file = NamedTempFile::new()?;
+        file.write_all(content.as_bytes())?;
+        Some(file)
+    };
+    let file = embedded
+        .as_ref()
+        .map_or(args.file.as_path(), NamedTempFile::path);
+    let exec_result = cli
+        .load(&options, &args.resource, &file.to_string_lossy())
+        .await?;
+
+    let result = LoadResult {
+        success: exec_result.success(),
+        resource: args.resource.clone(),
+        name,
+        file: args.file.clone(),
+        replaced,
+        error: (!exec_result.success()).then(|| exec_result.stderr.trim().to_string()),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else if let Some(ref error) = result.error {
+        println!("{} Load failed", style("✗").red().bold());
+        eprintln!("{}", style(error).red());
+    } else {
+        println!(
+            "{} {} {}/{} from {}",
+            style("✓").green(),
+            if result.replaced {
+                "Replaced"
+            } else {
+                "Loaded"
+            },
+            result.resource,
+            style(result.name.as_deref().unwrap_or("?")).bold(),
+            result.file.display()
+        );
+    }
+    Ok(result)
+}
+
+/// The named (or default) profile
+pub(crate) fn resolve_profile(profile_name: Option<&str>) -> Result<(String, Profile)> {
+    let manager = ProfileManager::new()?;
+    let name = profile_name
+        .unwrap_or(&manager.store().default_profile)
+        .to_string();
+    let profile = manager.get_resolved(&name)?;
+    Ok((name, profile))
+}
+
+pub(crate) fn underlying_cli(config: &Config, command: &str) -> UnderlyingCli {
+    UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for(command))
+        .with_retry_policy(config.retry_policy())
+}
+
+fn display_violations(file: &std::path::Path, violations: &[Violation]) {
+    println!("{} {}", style("✗").red(), file.display());
+    for violation in violations {
+        println!(
+            "    {}:{} {} {}",
+            file.display(),
+            violation.line,
+            style(&violation.path).dim(),
+            violation.message
+        );
+    }
+}
@@ -0,0 +1,21 @@
+//! Explain command implementation
+//!
+//! Shows the annotated guide of a command: what it does, which options of
+//! the underlying Vantiq CLI (as documented in the PDF) it maps to, the vqx
+//! extensions on top, and examples. `--help` lists the flags; the guides
+//! explain how they fit together.
+
+use crate::cli::{ExplainArgs, ExplainTopic};
+use crate::commands::{diff, export, external, import, promote, sync};
+
+/// Run the explain command
+pub fn run(args: &ExplainArgs) {
+    match args.command {
+        ExplainTopic::Export => export::display_help(),
+        ExplainTopic::Import => import::display_help(),
+        ExplainTopic::Diff => diff::display_help(),
+        ExplainTopic::Sync => sync::display_help(),
+        ExplainTopic::Promote => promote::display_help(),
+        ExplainTopic::Passthrough => external::display_help(),
+    }
+}
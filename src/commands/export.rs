@@ -16,6 +16,21 @@
 //! - projectdata <projectName>: export the data within a project
 //! - hidden: (undocumented in PDF excerpt)
 //!
+//! When `[masking]` is configured, `data` and `projectdata` exports are
+//! staged in a temporary directory and only masked copies are written to
+//! the output directory (see [`crate::masking`]).
+//!
+//! Hidden resources are gated behind `--include-hidden`, written to the
+//! `_hidden/` subdirectory and recorded in the export manifest so they are
+//! not imported, diffed or promoted by accident.
+//!
+//! Every export keeps a checkpoint in the manifest until it succeeds. The
+//! types it completed are found from the files: everything written since
+//! the start except the newest type, which was being written when the
+//! export failed. `--resume` excludes the completed types, and limits data
+//! exports to the start of the first attempt (`-until`), so the resumed
+//! types fit the ones already exported.
+//!
 //! Options (PDF):
 //! - -d <directoryName>: output directory
 //! - -chunk <integer>: chunk size for large exports
@@ -24,22 +39,35 @@
 //! - -until <DateTime>: limit to instances before timestamp
 //! - -ignoreErrors: continue on errors
 
+use crate::chunking::{self, ChunkSize, ChunkTuner};
 use crate::cli::{ExportArgs, ExportType, OutputFormat};
+use crate::commands::list::parse_list_output;
 use crate::config::Config;
 use crate::error::{Result, VqxError};
+use crate::filter::ResourceFilter;
+use crate::i18n;
+use crate::manifest::{self, ExportCheckpoint, HiddenResources, Manifest};
+use crate::masking::{self, MaskingReport};
 use crate::normalizer::ResourceNormalizer;
-use crate::profile::ProfileManager;
-use crate::underlying::{CliOptions, UnderlyingCli};
-use console::style;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::path::PathBuf;
-use std::time::Duration;
+use crate::profile::{Profile, ProfileManager};
+use crate::progress::TransferProgress;
+use crate::report;
+use crate::theme::style;
+use crate::timing::{self, TimingTracker};
+use crate::underlying::{
+    exit_status, CliOptions, ExecResult, StreamControl, StreamEvent, UnderlyingCli,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+use tempfile::TempDir;
+use walkdir::WalkDir;
 
 /// Export operation result
 #[derive(Debug)]
 pub struct ExportResult {
     pub success: bool,
-    pub directory: PathBuf,
     pub files_exported: Option<usize>,
     pub files_normalized: Option<usize>,
     pub errors: Vec<String>,
@@ -53,12 +81,29 @@ pub async fn run(
     output_format: OutputFormat,
     verbose: bool,
 ) -> Result<ExportResult> {
+    let hidden = matches!(args.export_type, ExportType::Hidden);
+    if hidden && !args.include_hidden {
+        return Err(VqxError::Other(
+            "Exporting hidden resources requires --include-hidden. They are written to \
+             _hidden/ and excluded from import, diff and promote."
+                .to_string(),
+        ));
+    }
+
+    if args.chunk == Some(ChunkSize::Auto)
+        && !matches!(args.export_type, ExportType::Metadata | ExportType::Data)
+    {
+        return Err(VqxError::Other(
+            "--chunk auto works with metadata and data exports".to_string(),
+        ));
+    }
+
     // Load profile
     let manager = ProfileManager::new()?;
     let profile_name = profile_name.unwrap_or(&manager.store().default_profile);
     let profile = manager.get_resolved(profile_name)?;
 
-    if !profile.has_auth() {
+    if !profile.has_auth() && !args.dry_run {
         return Err(VqxError::ProfileInvalid {
             message: format!(
                 "Profile '{}' has no authentication configured",
@@ -68,7 +113,27 @@ pub async fn run(
     }
 
     // Determine output directory
-    let output_dir = args.directory.clone().unwrap_or_else(|| PathBuf::from("."));
+    let export_root = args.directory.clone().unwrap_or_else(|| PathBuf::from("."));
+    let output_dir = if hidden {
+        export_root.join(manifest::HIDDEN_DIR)
+    } else {
+        export_root.clone()
+    };
+
+    // --dry-run: the types the filters select, before anything is written
+    if args.dry_run {
+        let filter = ResourceFilter::from_args(&args.filter, &[], None)?;
+        return dry_run(
+            args,
+            config,
+            profile_name,
+            &profile,
+            &filter,
+            &output_dir,
+            output_format,
+        )
+        .await;
+    }
 
     // Create output directory if it doesn't exist
     if !output_dir.exists() {
@@ -77,48 +142,79 @@ pub async fn run(
         })?;
     }
 
+    // Filters the underlying CLI does not understand are applied to the files
+    let filter = ResourceFilter::from_args(&args.filter, &[], None)?;
+    let cli_types = filter.cli_types();
+    let filter_locally = cli_types.is_none();
+
+    // Stage data exports to be masked, so unmasked data never reaches the
+    // output directory, and filtered exports, so other files are left alone
+    let data_export = matches!(args.export_type, ExportType::Data | ExportType::ProjectData);
+    let mask = data_export && config.masking.is_enabled();
+    if mask {
+        masking::check(&config.masking)?;
+    }
+    let staging = (mask || filter_locally)
+        .then(TempDir::new)
+        .transpose()
+        .map_err(|e| VqxError::Other(format!("Failed to create staging directory: {}", e)))?;
+    let cli_dir = staging
+        .as_ref()
+        .map_or(output_dir.as_path(), |staging| staging.path());
+
     // Display export info
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
-        println!("{}", style("Export").bold().cyan());
+        println!("{}", style(i18n::t("export.title")).bold().cyan());
         println!("{}", style("─".repeat(50)).dim());
-        println!("  Profile:   {}", style(profile_name).green());
-        println!("  Server:    {}", profile.url);
         println!(
-            "  Type:      {}",
+            "  {} {}",
+            i18n::label("label.profile"),
+            style(profile_name).green()
+        );
+        println!("  {} {}", i18n::label("label.server"), profile.url);
+        println!(
+            "  {} {}",
+            i18n::label("label.type"),
             format_export_type(&args.export_type, &args.project)
         );
-        println!("  Directory: {}", output_dir.display());
+        println!(
+            "  {} {}",
+            i18n::label("label.directory"),
+            output_dir.display()
+        );
         if let Some(chunk) = args.chunk {
-            println!("  Chunk:     {}", chunk);
+            println!("  {} {}", i18n::label("label.chunk"), chunk);
         }
         if args.normalize {
-            println!("  Normalize: {}", style("enabled").green());
+            println!(
+                "  {} {}",
+                i18n::label("label.normalize"),
+                style(i18n::t("enabled")).green()
+            );
+        }
+        if mask {
+            println!(
+                "  {} {}",
+                i18n::label("label.masking"),
+                style(i18n::t("enabled")).green()
+            );
         }
         println!();
     }
 
     // Build CLI
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("export"))
+        .with_retry_policy(config.retry_policy());
 
     let options = CliOptions::from_profile(&profile);
 
-    // Progress bar for export
-    let progress = if !matches!(output_format, OutputFormat::Json) {
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
-                .unwrap(),
-        );
-        pb.set_message("Exporting from Vantiq...");
-        pb.enable_steady_tick(Duration::from_millis(100));
-        Some(pb)
-    } else {
-        None
-    };
+    // Progress bar for export, driven by the CLI's chunk output
+    let mut progress = TransferProgress::new(
+        i18n::t("export.progress"),
+        !output_format.is_machine_readable(),
+    );
 
     // Build export type string for CLI
     // PDF: "export [data | metadata | project <projectName> | projectdata <projectName> | hidden]"
@@ -146,98 +242,251 @@ pub async fn run(
         ExportType::Hidden => "hidden".to_string(),
     };
 
+    // Checkpoint until the export succeeds; with --resume, continue the
+    // failed export recorded there
+    let previous = Manifest::load(&export_root)?
+        .checkpoint
+        .filter(|_| args.resume);
+    if args.resume && previous.is_none() && !output_format.is_machine_readable() {
+        println!(
+            "{}",
+            style(format!(
+                "No failed export to resume in {}; exporting everything",
+                export_root.display()
+            ))
+            .dim()
+        );
+    }
+    let mut checkpoint =
+        start_checkpoint(args, &export_type_str, previous, &output_dir, data_export)?;
+    let resuming = checkpoint.attempts > 1;
+    save_checkpoint(&export_root, Some(&checkpoint))?;
+
     // Execute export
     // PDF: "vantiq export [type] [-d <directory>] [-chunk <size>] [-include <type>] [-exclude <type>] [-until <DateTime>] [-ignoreErrors]"
-    let include_refs: Vec<&str> = args.include.iter().map(|s| s.as_str()).collect();
-    let exclude_refs: Vec<&str> = args.exclude.iter().map(|s| s.as_str()).collect();
-
-    let result = cli
-        .export(
-            &options,
-            Some(&export_type_str),
-            Some(output_dir.to_str().unwrap()),
-            args.chunk.or(Some(config.default_chunk_size)),
-            if include_refs.is_empty() {
-                None
-            } else {
-                Some(&include_refs)
-            },
-            if exclude_refs.is_empty() {
-                None
+    let (mut include, mut exclude): (Vec<&str>, Vec<&str>) = cli_types.unwrap_or_default();
+    let completed: Vec<&str> = checkpoint.completed.iter().map(String::as_str).collect();
+    if include.is_empty() {
+        exclude.extend(&completed);
+    } else {
+        include.retain(|t| !completed.contains(t));
+        if include.is_empty() {
+            save_checkpoint(&export_root, None)?;
+            return Err(VqxError::Other(
+                "Every included type was exported completely already; nothing to resume"
+                    .to_string(),
+            ));
+        }
+    }
+    let until = args.until.clone().or_else(|| {
+        (resuming && data_export).then(|| {
+            checkpoint
+                .started_at
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+        })
+    });
+    if resuming && !output_format.is_machine_readable() {
+        println!(
+            "{} Resuming (attempt {}): skipping {} completed type(s){}",
+            style("→").cyan(),
+            checkpoint.attempts,
+            completed.len(),
+            if data_export {
+                format!(", data until {}", until.as_deref().unwrap_or("?"))
             } else {
-                Some(&exclude_refs)
+                String::new()
+            }
+        );
+        println!();
+    }
+
+    let include = (!include.is_empty()).then_some(include);
+    let exclude = (!exclude.is_empty()).then_some(exclude);
+    let mut tracker = TimingTracker::new();
+    let mut chunking = Vec::new();
+    let result = if args.chunk == Some(ChunkSize::Auto) {
+        // One call per type, each with its own chunk size
+        let types: Vec<String> = match include {
+            Some(include) => include.iter().map(|t| t.to_string()).collect(),
+            None => export_types(&cli, &options, config, data_export)
+                .await?
+                .into_iter()
+                .filter(|t| !exclude.as_ref().is_some_and(|e| e.contains(&t.as_str())))
+                .collect(),
+        };
+        let mut tuner = ChunkTuner::new(config, profile_name, "export");
+        let mut result = None;
+        let mut retries = Vec::new();
+        for (i, name) in types.iter().enumerate() {
+            progress.set_phase(format!("Exporting {} ({}/{})...", name, i + 1, types.len()));
+            let started = Instant::now();
+            let type_result = tuner
+                .run(
+                    name,
+                    &cli,
+                    &options,
+                    &|chunk| {
+                        UnderlyingCli::export_args(
+                            Some(&export_type_str),
+                            Some(cli_dir),
+                            Some(chunk),
+                            Some(&[name.as_str()]),
+                            None,
+                            until.as_deref(),
+                            args.ignore_errors,
+                        )
+                    },
+                    &mut |line| progress.observe(line),
+                    &|| remove_portion(cli_dir, data_export, name),
+                )
+                .await?;
+            tracker.add(name, started.elapsed());
+            retries.extend(type_result.retries.iter().cloned());
+            let failed = !type_result.success();
+            result = Some(type_result);
+            if failed {
+                break;
+            }
+        }
+        chunking = tuner.finish()?;
+        // Report the retries of every call, not only the last one
+        match result {
+            Some(result) => ExecResult { retries, ..result },
+            None => ExecResult {
+                status: exit_status(0),
+                stdout: String::new(),
+                stderr: String::new(),
+                retries,
             },
-            args.until.as_deref(),
+        }
+    } else {
+        let export_args = UnderlyingCli::export_args(
+            Some(&export_type_str),
+            Some(cli_dir),
+            args.chunk
+                .and_then(ChunkSize::fixed)
+                .or(Some(config.default_chunk_size)),
+            include.as_deref(),
+            exclude.as_deref(),
+            until.as_deref(),
             args.ignore_errors,
-        )
-        .await?;
+        );
 
-    if let Some(ref pb) = progress {
-        pb.finish_and_clear();
-    }
+        // Stream output to attribute elapsed time to each resource type and
+        // report chunk progress
+        cli.execute_streaming(
+            &options,
+            "export",
+            export_args,
+            config.stall_policy(),
+            |event| {
+                if let StreamEvent::Line { text, .. } = event {
+                    tracker.observe(text, Instant::now());
+                    progress.observe(text);
+                }
+                StreamControl::Continue
+            },
+        )
+        .await?
+    };
+    let timings = tracker.finish(Instant::now());
+    progress.finish_and_clear();
 
     if !result.success() {
-        if !matches!(output_format, OutputFormat::Json) {
+        // Keep the complete types; the one in progress is exported again
+        let written = scan_progress(
+            staging.as_ref().map_or(output_dir.as_path(), |s| s.path()),
+            data_export,
+            checkpoint.started_at,
+        );
+        if let Some(ref staging) = staging {
+            if let Some(ref name) = written.in_progress {
+                remove_portion(staging.path(), data_export, name)?;
+            }
+            finish_staging(staging.path(), &output_dir, mask, &filter, config)?;
+        }
+        let mut all_completed: BTreeSet<String> = checkpoint.completed.drain(..).collect();
+        all_completed.extend(written.completed);
+        checkpoint.completed = all_completed.into_iter().collect();
+        checkpoint.in_progress = written.in_progress;
+        checkpoint.chunks_written = written.chunks_written;
+        save_checkpoint(&export_root, Some(&checkpoint))?;
+
+        if !output_format.is_machine_readable() {
             println!(
-                "{} Export failed with exit code {}",
+                "{} {}",
                 style("✗").red(),
-                result.code()
+                i18n::tf("export.failed", &[("code", &result.code())])
             );
             if !result.stderr.is_empty() {
                 println!("{}", style(&result.stderr).red());
             }
+            display_checkpoint(&checkpoint);
+            chunking::display(&chunking);
         }
 
         return Ok(ExportResult {
             success: false,
-            directory: output_dir,
             files_exported: None,
             files_normalized: None,
             errors: vec![result.stderr],
         });
     }
 
+    let masking = match staging {
+        Some(staging) => finish_staging(staging.path(), &output_dir, mask, &filter, config)?,
+        None => None,
+    };
+    if let Some(ref report) = masking {
+        if !output_format.is_machine_readable() {
+            display_masking(report);
+        }
+    }
+    save_checkpoint(&export_root, None)?;
+
     // Count exported files
     let files_exported = count_json_files(&output_dir);
 
-    if !matches!(output_format, OutputFormat::Json) {
+    if hidden {
+        record_hidden_export(&export_root, &output_dir, files_exported)?;
+    }
+
+    if !output_format.is_machine_readable() {
         println!(
-            "{} Exported {} files to {}",
+            "{} {}",
             style("✓").green(),
-            files_exported,
-            output_dir.display()
+            i18n::tf(
+                "export.exported",
+                &[("count", &files_exported), ("dir", &output_dir.display())]
+            )
         );
     }
 
     // Normalize if requested
     let files_normalized = if args.normalize {
-        if let Some(ref pb) = progress {
-            pb.set_message("Normalizing JSON files...");
-            pb.enable_steady_tick(Duration::from_millis(100));
-        } else if !matches!(output_format, OutputFormat::Json) {
+        if progress.bar().is_some() {
+            progress.set_phase(i18n::t("export.normalizing_progress"));
+        } else if !output_format.is_machine_readable() {
             println!();
-            println!("{}", style("Normalizing...").dim());
+            println!("{}", style(i18n::t("export.normalizing")).dim());
         }
 
         let normalizer = ResourceNormalizer::new(config.normalization.clone());
         let stats = normalizer.normalize_export_directory(&output_dir)?;
+        progress.finish_and_clear();
 
-        if let Some(ref pb) = progress {
-            pb.finish_and_clear();
-        }
-
-        if !matches!(output_format, OutputFormat::Json) {
+        if !output_format.is_machine_readable() {
             println!(
-                "{} Normalized {} files",
+                "{} {}",
                 style("✓").green(),
-                stats.files_processed
+                i18n::tf("export.normalized", &[("count", &stats.files_processed)])
             );
 
             if stats.errors > 0 {
                 println!(
-                    "{} {} files had errors during normalization",
+                    "{} {}",
                     style("⚠").yellow(),
-                    stats.errors
+                    i18n::tf("export.normalize_errors", &[("count", &stats.errors)])
                 );
                 for (file, err) in &stats.error_files {
                     println!("    {} {}: {}", style("•").dim(), file, err);
@@ -251,10 +500,16 @@ pub async fn run(
     };
 
     // Output summary
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
         println!("{}", style("─".repeat(50)).dim());
-        println!("{} Export complete", style("✓").green().bold());
+        println!(
+            "{} {}",
+            style("✓").green().bold(),
+            i18n::t("export.complete")
+        );
+        timing::display_breakdown(&timings);
+        chunking::display(&chunking);
 
         // Show PDF reference for directory structure
         if verbose {
@@ -276,28 +531,428 @@ pub async fn run(
     }
 
     // JSON output
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_machine_readable() {
         let json_result = serde_json::json!({
             "success": true,
             "directory": output_dir.display().to_string(),
             "files_exported": files_exported,
             "files_normalized": files_normalized,
+            "masking": masking,
             "profile": profile_name,
             "server": profile.url,
             "export_type": format_export_type(&args.export_type, &args.project),
+            "timings": timings,
+            "chunking": chunking,
+            "retries": result.retries,
         });
-        println!("{}", serde_json::to_string_pretty(&json_result)?);
+        println!("{}", report::render(&json_result, output_format)?);
     }
 
     Ok(ExportResult {
         success: true,
-        directory: output_dir,
         files_exported: Some(files_exported),
         files_normalized,
         errors: vec![],
     })
 }
 
+/// Write a staged export to the output directory, masked or filtered
+fn finish_staging(
+    staging: &Path,
+    output_dir: &Path,
+    mask: bool,
+    filter: &ResourceFilter,
+    config: &Config,
+) -> Result<Option<MaskingReport>> {
+    if mask {
+        filter.prune(staging)?;
+        Ok(Some(masking::mask_directory(
+            &config.masking,
+            staging,
+            output_dir,
+        )?))
+    } else {
+        filter.copy_selected(staging, output_dir)?;
+        Ok(None)
+    }
+}
+
+/// Checkpoint of this attempt
+///
+/// A resumed export continues `previous`, which must have been started
+/// with the same type and options.
+fn start_checkpoint(
+    args: &ExportArgs,
+    export_type: &str,
+    previous: Option<ExportCheckpoint>,
+    output_dir: &Path,
+    data_export: bool,
+) -> Result<ExportCheckpoint> {
+    let fresh = ExportCheckpoint {
+        export_type: export_type.to_string(),
+        include: args.filter.include.clone(),
+        exclude: args.filter.exclude.clone(),
+        until: args.until.clone(),
+        started_at: Utc::now(),
+        attempts: 1,
+        completed: vec![],
+        in_progress: None,
+        chunks_written: 0,
+    };
+    let Some(previous) = previous else {
+        return Ok(fresh);
+    };
+    if previous.export_type != fresh.export_type
+        || previous.include != fresh.include
+        || previous.exclude != fresh.exclude
+        || previous.until != fresh.until
+    {
+        return Err(VqxError::Other(format!(
+            "The failed export in {} was '{}' with other options; repeat them, or drop --resume to start over",
+            output_dir.display(),
+            previous.export_type
+        )));
+    }
+
+    // An attempt that was killed did not record what it completed
+    let written = scan_progress(output_dir, data_export, previous.started_at);
+    let completed: BTreeSet<String> = previous
+        .completed
+        .into_iter()
+        .chain(written.completed)
+        .collect();
+    Ok(ExportCheckpoint {
+        started_at: previous.started_at,
+        attempts: previous.attempts + 1,
+        completed: completed.into_iter().collect(),
+        ..fresh
+    })
+}
+
+/// Store the checkpoint in the manifest of the export root, or remove it
+fn save_checkpoint(export_root: &Path, checkpoint: Option<&ExportCheckpoint>) -> Result<()> {
+    let mut manifest = Manifest::load(export_root)?;
+    manifest.checkpoint = checkpoint.cloned();
+    manifest.save(export_root)
+}
+
+/// Types an export wrote since it started
+#[derive(Debug, Default, PartialEq)]
+struct WrittenTypes {
+    /// Written completely, by name
+    completed: Vec<String>,
+    /// Written last, and maybe not completely
+    in_progress: Option<String>,
+    /// Files (chunks) of `in_progress`
+    chunks_written: usize,
+}
+
+/// Find the types written to an export directory since `since`
+///
+/// Data exports write `data/<Type>.json` or `data/<Type>/<chunk>.json`;
+/// other exports one directory per resource type. Types are written one
+/// after another, so the one with the newest file is the one in progress.
+fn scan_progress(dir: &Path, data_export: bool, since: DateTime<Utc>) -> WrittenTypes {
+    let root = if data_export {
+        dir.join(masking::DATA_DIR)
+    } else {
+        dir.to_path_buf()
+    };
+    let since = SystemTime::from(since);
+
+    let mut written: Vec<(SystemTime, String, usize)> = Vec::new();
+    for entry in std::fs::read_dir(&root).into_iter().flatten().flatten() {
+        let path = entry.path();
+        let name = if path.is_dir() {
+            entry.file_name().to_str().map(String::from)
+        } else if data_export && path.extension().is_some_and(|e| e == "json") {
+            path.file_stem().and_then(|s| s.to_str()).map(String::from)
+        } else {
+            None
+        };
+        let Some(name) = name.filter(|n| !n.starts_with(['.', '_'])) else {
+            continue;
+        };
+        let modified: Vec<SystemTime> = WalkDir::new(&path)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.metadata().ok()?.modified().ok())
+            .collect();
+        if let Some(newest) = modified.iter().max().filter(|t| **t >= since) {
+            written.push((*newest, name, modified.len()));
+        }
+    }
+    written.sort();
+
+    let in_progress = written.pop();
+    WrittenTypes {
+        completed: written.into_iter().map(|(_, name, _)| name).collect(),
+        chunks_written: in_progress.as_ref().map_or(0, |(_, _, files)| *files),
+        in_progress: in_progress.map(|(_, name, _)| name),
+    }
+}
+
+/// Show the types an export would write (`--dry-run`)
+///
+/// Metadata exports go through the resource type directories; data exports
+/// list the namespace's types, the only server call. Filters of plain names
+/// select data by type, like the CLI's `-include`; other patterns select
+/// `data/<Type>` files.
+async fn dry_run(
+    args: &ExportArgs,
+    config: &Config,
+    profile_name: &str,
+    profile: &Profile,
+    filter: &ResourceFilter,
+    output_dir: &Path,
+    output_format: OutputFormat,
+) -> Result<ExportResult> {
+    if matches!(
+        args.export_type,
+        ExportType::Project | ExportType::ProjectData
+    ) && args.project.is_none()
+    {
+        return Err(VqxError::Other(
+            "Project name required for project export".to_string(),
+        ));
+    }
+
+    let data_export = matches!(args.export_type, ExportType::Data | ExportType::ProjectData);
+    let cli = UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for("list"))
+        .with_retry_policy(config.retry_policy());
+    let candidates = export_types(
+        &cli,
+        &CliOptions::from_profile(profile),
+        config,
+        data_export,
+    )
+    .await?;
+
+    let plain = filter.cli_types().is_some();
+    let by_type = !data_export || plain;
+    let resources: Vec<(&str, Option<&str>)> = candidates
+        .iter()
+        .map(|t| {
+            if by_type {
+                (t.as_str(), None)
+            } else {
+                (masking::DATA_DIR, Some(t.as_str()))
+            }
+        })
+        .collect();
+    let types: Vec<&str> = candidates
+        .iter()
+        .map(String::as_str)
+        .filter(|t| {
+            if by_type {
+                filter.matches_type(t)
+            } else {
+                filter.matches(masking::DATA_DIR, t)
+            }
+        })
+        .collect();
+
+    let (includes, excludes) = filter.unmatched(&resources);
+    let mut errors: Vec<String> = includes
+        .iter()
+        .map(|p| format!("--include {} matches no type to export", p))
+        .collect();
+    let warnings: Vec<String> = excludes
+        .iter()
+        .map(|p| format!("--exclude {} matches no type to export", p))
+        .collect();
+    if types.is_empty() && includes.is_empty() {
+        errors.push("Nothing would be exported".to_string());
+    }
+    let success = errors.is_empty();
+
+    if output_format.is_machine_readable() {
+        let json_result = serde_json::json!({
+            "success": success,
+            "dry_run": true,
+            "directory": output_dir.display().to_string(),
+            "profile": profile_name,
+            "export_type": format_export_type(&args.export_type, &args.project),
+            "types": types,
+            "warnings": warnings,
+            "errors": errors,
+        });
+        println!("{}", report::render(&json_result, output_format)?);
+    } else {
+        println!();
+        println!(
+            "{} {}",
+            style(i18n::t("export.title")).bold().cyan(),
+            style("(dry run)").yellow()
+        );
+        println!("{}", style("─".repeat(50)).dim());
+        println!(
+            "  {} {}",
+            i18n::label("label.profile"),
+            style(profile_name).green()
+        );
+        println!(
+            "  {} {}",
+            i18n::label("label.type"),
+            format_export_type(&args.export_type, &args.project)
+        );
+        println!(
+            "  {} {}",
+            i18n::label("label.directory"),
+            output_dir.display()
+        );
+        if !filter.is_empty() {
+            println!("  {} {}", i18n::label("label.filter"), filter);
+        }
+        println!();
+        for resource_type in &types {
+            println!("  {}", resource_type);
+        }
+        for warning in &warnings {
+            println!("{} {}", style("⚠").yellow(), warning);
+        }
+        for error in &errors {
+            println!("{} {}", style("✗").red(), error);
+        }
+        println!();
+        if success {
+            println!(
+                "{} Dry run complete. {} type(s) would be exported; nothing was written.",
+                style("✓").green(),
+                types.len()
+            );
+        } else {
+            println!(
+                "{} Dry run found {} problem(s); nothing was exported.",
+                style("✗").red(),
+                errors.len()
+            );
+        }
+    }
+
+    Ok(ExportResult {
+        success,
+        files_exported: None,
+        files_normalized: None,
+        errors,
+    })
+}
+
+/// Types a `--chunk auto` export goes through one by one: the resource type
+/// directories for metadata, the user types of the namespace for data
+async fn export_types(
+    cli: &UnderlyingCli,
+    options: &CliOptions,
+    config: &Config,
+    data_export: bool,
+) -> Result<Vec<String>> {
+    if !data_export {
+        return Ok(timing::RESOURCE_TYPES
+            .iter()
+            .filter(|t| **t != masking::DATA_DIR)
+            .map(|t| t.to_string())
+            .collect());
+    }
+    let result = cli.list(options, "types").await?;
+    if !result.success() {
+        return Err(VqxError::Other(format!(
+            "Could not list the types to export: {}",
+            result.stderr.trim()
+        )));
+    }
+    // System types (the `[import]` blocked prefixes) hold no user data
+    Ok(parse_list_output(&result.stdout)
+        .into_iter()
+        .filter_map(|row| row.get("name")?.as_str().map(String::from))
+        .filter(|name| !config.import.is_blocked(name))
+        .collect())
+}
+
+/// Remove a type written by a data or metadata export
+fn remove_portion(dir: &Path, data_export: bool, name: &str) -> Result<()> {
+    let paths = if data_export {
+        let data = dir.join(masking::DATA_DIR);
+        vec![data.join(format!("{}.json", name)), data.join(name)]
+    } else {
+        vec![dir.join(name)]
+    };
+    for path in paths {
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else if path.is_file() {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Show what a failed export completed, and how to resume it
+fn display_checkpoint(checkpoint: &ExportCheckpoint) {
+    if checkpoint.completed.is_empty() {
+        return;
+    }
+    println!(
+        "  {} type(s) exported completely: {}",
+        checkpoint.completed.len(),
+        checkpoint.completed.join(", ")
+    );
+    if let Some(ref name) = checkpoint.in_progress {
+        println!(
+            "  {} was in progress ({} file(s) written) and is exported again",
+            name, checkpoint.chunks_written
+        );
+    }
+    println!(
+        "{}",
+        style("  Run the same export with --resume to skip the completed types").dim()
+    );
+}
+
+/// Record a hidden export in the manifest of the export root
+fn record_hidden_export(export_root: &Path, hidden_dir: &Path, files: usize) -> Result<()> {
+    let mut resource_types: Vec<String> = std::fs::read_dir(hidden_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .collect();
+    resource_types.sort();
+
+    let mut manifest = Manifest::load(export_root)?;
+    manifest.hidden = Some(HiddenResources {
+        directory: manifest::HIDDEN_DIR.to_string(),
+        exported_at: chrono::Utc::now(),
+        resource_types,
+        files,
+    });
+    manifest.save(export_root)
+}
+
+/// Display masked value counts per type and field
+fn display_masking(report: &MaskingReport) {
+    println!(
+        "{} Masked {} values in {} files ({} records checked)",
+        style("✓").green(),
+        report.total(),
+        report.files_masked,
+        report.records
+    );
+    for (type_name, fields) in &report.fields {
+        for (field, count) in fields {
+            println!(
+                "    {} {}.{}: {}",
+                style("•").dim(),
+                type_name,
+                field,
+                count
+            );
+        }
+    }
+}
+
 /// Format export type for display
 fn format_export_type(export_type: &ExportType, project: &Option<String>) -> String {
     match export_type {
@@ -342,6 +997,7 @@ pub fn display_help() {
     println!("  data         Export data in user defined types and documents");
     println!("  project      Export resource definitions within a project");
     println!("  projectdata  Export data within a project");
+    println!("  hidden       Export hidden (system) resources into _hidden/");
     println!();
     println!("{}", style("Options (from PDF):").bold());
     println!("  -d <dir>           Output directory");
@@ -354,6 +1010,10 @@ pub fn display_help() {
     println!("{}", style("vqx Extensions:").bold());
     println!("  --normalize        Normalize JSON for git-friendly diffs (default: true)");
     println!("  --no-normalize     Disable JSON normalization");
+    println!("  --include-hidden   Acknowledge exporting hidden resources");
+    println!("  --include/--exclude <type>/<name>, with globs, are applied by vqx");
+    println!("  --resume           Continue a failed export, skipping completed types");
+    println!("  --chunk auto       One call per type, tuning its chunk size from past runs");
     println!();
     println!("{}", style("Examples:").bold());
     println!();
@@ -393,4 +1053,44 @@ mod tests {
             "project Test"
         );
     }
+
+    #[test]
+    fn test_scan_progress() {
+        let dir = TempDir::new().unwrap();
+        let since = Utc::now() - chrono::Duration::minutes(10);
+        let write = |rel: &str, minutes_ago: i64| {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, "[]").unwrap();
+            let at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(at.into())
+                .unwrap();
+        };
+        write("data/Customer.json", 8);
+        write("data/Order/1.json", 5);
+        write("data/Order/2.json", 2);
+        write("data/Invoice/1.json", 4);
+        write("data/Old.json", 60);
+
+        let written = scan_progress(dir.path(), true, since);
+        assert_eq!(written.completed, vec!["Customer", "Invoice"]);
+        assert_eq!(written.in_progress.as_deref(), Some("Order"));
+        assert_eq!(written.chunks_written, 2);
+
+        remove_portion(dir.path(), true, "Order").unwrap();
+        assert!(!dir.path().join("data/Order").exists());
+        let written = scan_progress(dir.path(), true, since);
+        assert_eq!(written.in_progress.as_deref(), Some("Invoice"));
+
+        // Metadata exports: one directory per resource type
+        write("types/Order.json", 3);
+        write("_hidden/types/X.json", 1);
+        let written = scan_progress(dir.path(), false, since);
+        assert_eq!(written.completed, vec!["data"]);
+        assert_eq!(written.in_progress.as_deref(), Some("types"));
+    }
 }
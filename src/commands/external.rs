@@ -2,15 +2,16 @@
 //!
 //! Passes unrecognized commands directly to the underlying Vantiq CLI.
 //! This allows users to run any CLI command through vqx:
-//!   vqx list types
 //!   vqx find procedures MyProc
-//!   vqx --profile dev select types
+//!   vqx --profile dev find types MyType
 
 use crate::config::Config;
 use crate::error::Result;
+use crate::pager;
+use crate::policy;
 use crate::profile::ProfileManager;
+use crate::theme::style;
 use crate::underlying::{CliOptions, UnderlyingCli};
-use console::style;
 use tracing::info;
 
 /// Run an external CLI command
@@ -27,47 +28,25 @@ pub async fn run(
     );
 
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("external"))
+        .with_retry_policy(config.retry_policy());
 
     // Build arguments with profile credentials if specified
     let mut full_args: Vec<String> = Vec::new();
+    // Temporary profile file for client options, kept until the CLI exits
+    let mut _profile_file = None;
 
     if let Some(profile_name) = profile_name {
-        // Load profile and add connection options
+        // Load profile and add connection options first
         let manager = ProfileManager::new()?;
         let profile = manager.get_resolved(profile_name)?;
-        let _options = CliOptions::from_profile(&profile);
-
-        // Add connection options first
-        full_args.push("-b".to_string());
-        full_args.push(profile.url.clone());
-
-        if let Some(ref username) = profile.username {
-            full_args.push("-u".to_string());
-            full_args.push(username.clone());
-        }
-        if let Some(ref password) = profile.password {
-            full_args.push("-p".to_string());
-            full_args.push(password.clone());
-        }
-
-        // Token only if no password
-        if profile.password.is_none() {
-            if let Some(ref token) = profile.token {
-                full_args.push("-t".to_string());
-                full_args.push(token.clone());
-            }
-        }
-
-        if let Some(ref ns) = profile.namespace {
-            full_args.push("-n".to_string());
-            full_args.push(ns.clone());
-        }
+        let (options, file) = CliOptions::from_profile(&profile).with_profile_file()?;
+        full_args = options.to_args();
+        _profile_file = file;
+    }
 
-        if profile.trust_ssl {
-            full_args.push("-trust".to_string());
-        }
+    if let Some((command, command_args)) = args.split_first() {
+        policy::check(profile_name, command, command_args)?;
     }
 
     // Add user-provided arguments
@@ -90,7 +69,7 @@ pub async fn run(
 
     // Print output
     if !result.stdout.is_empty() {
-        print!("{}", result.stdout);
+        pager::page(&result.stdout);
     }
     if !result.stderr.is_empty() {
         eprint!("{}", result.stderr);
@@ -142,6 +121,48 @@ fn mask_sensitive_args(args: &[String]) -> Vec<String> {
     masked
 }
 
+/// Show the annotated guide for passthrough commands
+/// (`vqx explain passthrough`)
+pub fn display_help() {
+    println!();
+    println!("{}", style("Passthrough Commands").bold().cyan());
+    println!("{}", style("─".repeat(60)).dim());
+    println!();
+    println!("Any command vqx does not know is passed to the underlying Vantiq CLI.");
+    println!();
+    println!("{}", style("PDF Reference: all commands").bold());
+    println!();
+    println!(
+        "{}",
+        style("Connection Options (added from the vqx profile):").bold()
+    );
+    println!("  -b <url>       Server URL (PDF: '-b <baseURL>')");
+    println!("  -u / -p        Username and password (PDF: '-u <username> -p <password>')");
+    println!("  -t <token>     Access token, when there is no password (PDF: '-t <token>')");
+    println!("  -n <ns>        Namespace (PDF: '-n <namespace>')");
+    println!("  -trust         Trust SSL certificates (PDF: '-trust')");
+    println!();
+    println!("{}", style("vqx Extensions:").bold());
+    println!("  --profile, -s  Profile whose connection options are added");
+    println!("  --verbose, -v  Show the command line (passwords masked) and exit code");
+    println!("  [policy.*]     config.toml rules can refuse commands per profile");
+    println!();
+    println!("{}", style("Examples:").bold());
+    println!();
+    println!("  # Find a procedure (PDF: 'vantiq find procedures <name>')");
+    println!("  {} vqx -s dev find procedures MyProc", style("$").dim());
+    println!();
+    println!("  # Show the help of the underlying CLI");
+    println!("  {} vqx help", style("$").dim());
+    println!();
+    println!("{}", style("Note:").dim());
+    println!(
+        "{}",
+        style("  The exit code and output of the underlying CLI are passed through as is.").dim()
+    );
+    println!();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
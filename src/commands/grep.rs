@@ -0,0 +1,418 @@
+//! Grep command implementation
+//!
+//! Searches an export directory, or a profile's namespace, for a regex.
+//! JSON files are searched value by value, so a match reports the JSON
+//! pointer of the field it is in (e.g. `/ruleText`) and, for multi-line
+//! values such as procedure and rule code, the line within the value.
+//! `.vail` files written by `extract_code` are searched line by line.
+//!
+//! Profiles are exported (or taken from the export cache) first; see
+//! [`crate::commands::diff::export_profile`].
+
+use crate::cli::{GrepArgs, OutputFormat};
+use crate::commands::diff;
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::pager;
+use crate::progress;
+use crate::report;
+use crate::theme::style;
+use indicatif::{ProgressBar, ProgressStyle};
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+/// Longest line shown for a match; longer lines are cut around the match
+const MAX_LINE_CHARS: usize = 160;
+
+/// One matching line
+#[derive(Debug, Clone, Serialize)]
+pub struct GrepMatch {
+    /// Path relative to the searched directory
+    pub file: String,
+    /// Stable identifier (`<resource_type>/<name>`); the file for top-level files
+    pub resource: String,
+    /// JSON pointer of the matching value; `null` for `.vail` files
+    pub field: Option<String>,
+    /// Line within the file (`.vail`) or within a multi-line value
+    pub line: Option<usize>,
+    pub text: String,
+}
+
+/// Result of grep operation
+#[derive(Debug, Serialize)]
+pub struct GrepResult {
+    /// Whether anything matched
+    pub success: bool,
+    pub pattern: String,
+    /// Directory or `profile: <name>`
+    pub source: String,
+    pub files_searched: usize,
+    pub matches: Vec<GrepMatch>,
+}
+
+/// Run grep command
+pub async fn run(
+    args: &GrepArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<GrepResult> {
+    let pattern = RegexBuilder::new(&args.pattern)
+        .case_insensitive(args.ignore_case)
+        .build()
+        .map_err(|e| VqxError::Other(format!("Invalid pattern '{}': {}", args.pattern, e)))?;
+
+    let (dir, source, _temp) = resolve_source(
+        args.directory.as_deref(),
+        profile_name,
+        config,
+        args.refresh,
+        output_format,
+    )
+    .await?;
+
+    let files = searchable_files(&dir, &args.resource);
+    let mut matches = Vec::new();
+    for path in &files {
+        let content = std::fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+            path: path.display().to_string(),
+        })?;
+        matches.extend(search_file(&dir, path, &content, &pattern));
+    }
+
+    let result = GrepResult {
+        success: !matches.is_empty(),
+        pattern: args.pattern.clone(),
+        source,
+        files_searched: files.len(),
+        matches,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display_result(&result, &pattern);
+    }
+
+    Ok(result)
+}
+
+/// The directory to work on: `directory`, or else the profile's export
+///
+/// `-d` wins over a profile, which may come from `VQX_PROFILE`; with
+/// neither, the current directory is used. Returns the directory, a
+/// description for reports, and the temp dir holding a fresh export.
+pub async fn resolve_source(
+    directory: Option<&Path>,
+    profile_name: Option<&str>,
+    config: &Config,
+    refresh: bool,
+    output_format: OutputFormat,
+) -> Result<(PathBuf, String, Option<TempDir>)> {
+    match (directory, profile_name) {
+        (None, Some(name)) => {
+            let progress =
+                (!output_format.is_machine_readable() && progress::enabled()).then(|| {
+                    let pb = ProgressBar::new_spinner();
+                    pb.set_style(
+                        ProgressStyle::default_spinner()
+                            .template("{spinner:.green} {msg}")
+                            .unwrap(),
+                    );
+                    pb.enable_steady_tick(Duration::from_millis(100));
+                    pb
+                });
+            let (dir, temp) =
+                diff::export_profile(name, config, refresh, progress.as_ref()).await?;
+            if let Some(pb) = progress {
+                pb.finish_and_clear();
+            }
+            Ok((dir, format!("profile: {}", name), temp))
+        }
+        (directory, _) => {
+            let dir = directory.map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+            if !dir.is_dir() {
+                return Err(VqxError::Other(format!(
+                    "Not a directory: {}",
+                    dir.display()
+                )));
+            }
+            let source = dir.display().to_string();
+            Ok((dir, source, None))
+        }
+    }
+}
+
+/// JSON and VAIL files below a directory, optionally only in some
+/// resource type directories, in a stable order
+pub(crate) fn searchable_files(dir: &Path, resources: &[String]) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json" || e == "vail"))
+        .filter(|p| {
+            resources.is_empty()
+                || resources
+                    .iter()
+                    .any(|r| p.strip_prefix(dir).is_ok_and(|rel| rel.starts_with(r)))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Search one file
+fn search_file(dir: &Path, path: &Path, content: &str, pattern: &Regex) -> Vec<GrepMatch> {
+    let relative = path.strip_prefix(dir).unwrap_or(path);
+    let file = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let resource_type = match relative.components().count() {
+        0 | 1 => String::new(),
+        _ => relative
+            .components()
+            .next()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    };
+
+    let is_json = path.extension().is_some_and(|e| e == "json");
+    let json = is_json
+        .then(|| serde_json::from_str::<Value>(content).ok())
+        .flatten();
+    let name = json
+        .as_ref()
+        .and_then(|v| v.get("name"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .or_else(|| {
+            // `Orders.total.vail` belongs to the procedure `Orders.total`
+            path.file_stem().map(|s| s.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+    let resource = if resource_type.is_empty() {
+        file.clone()
+    } else {
+        report::resource_id(&resource_type, &name)
+    };
+
+    let mut found = Vec::new();
+    let mut push = |field: Option<String>, line: Option<usize>, text: &str| {
+        found.push(GrepMatch {
+            file: file.clone(),
+            resource: resource.clone(),
+            field,
+            line,
+            text: excerpt(text, pattern),
+        });
+    };
+
+    match json {
+        Some(json) => {
+            let mut values = Vec::new();
+            string_values(&json, String::new(), &mut values);
+            for (pointer, value) in values {
+                if value.contains('\n') {
+                    for (i, line) in value.lines().enumerate() {
+                        if pattern.is_match(line) {
+                            push(Some(pointer.clone()), Some(i + 1), line);
+                        }
+                    }
+                } else if pattern.is_match(&value) {
+                    push(Some(pointer), None, &value);
+                }
+            }
+        }
+        // VAIL code, or JSON that does not parse
+        None => {
+            for (i, line) in content.lines().enumerate() {
+                if pattern.is_match(line) {
+                    push(None, Some(i + 1), line);
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Collect string and scalar values with their JSON pointers
+fn string_values(value: &Value, pointer: String, out: &mut Vec<(String, String)>) {
+    let escape = |key: &str| key.replace('~', "~0").replace('/', "~1");
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map {
+                string_values(v, format!("{}/{}", pointer, escape(key)), out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                string_values(v, format!("{}/{}", pointer, i), out);
+            }
+        }
+        Value::String(s) => out.push((pointer, s.clone())),
+        Value::Null => {}
+        other => out.push((pointer, other.to_string())),
+    }
+}
+
+/// Trimmed line, cut around the first match when it is long
+fn excerpt(line: &str, pattern: &Regex) -> String {
+    let line = line.trim();
+    if line.chars().count() <= MAX_LINE_CHARS {
+        return line.to_string();
+    }
+    let start = pattern.find(line).map_or(0, |m| m.start());
+    let prefix = line[..start].chars().count();
+    let skip = prefix.saturating_sub(MAX_LINE_CHARS / 4);
+    let cut: String = line.chars().skip(skip).take(MAX_LINE_CHARS).collect();
+    format!(
+        "{}{}{}",
+        if skip > 0 { "…" } else { "" },
+        cut,
+        if skip + MAX_LINE_CHARS < line.chars().count() {
+            "…"
+        } else {
+            ""
+        }
+    )
+}
+
+/// Display the grep result to the terminal
+fn display_result(result: &GrepResult, pattern: &Regex) {
+    let mut out = String::new();
+    let mut current_file = None;
+    for m in &result.matches {
+        if current_file != Some(&m.file) {
+            if current_file.is_some() {
+                out.push('\n');
+            }
+            let _ = writeln!(
+                out,
+                "{}  {}",
+                style(&m.file).magenta().bold(),
+                style(&m.resource).dim()
+            );
+            current_file = Some(&m.file);
+        }
+
+        let location = match (&m.field, m.line) {
+            (Some(field), Some(line)) => format!("{}:{}", field, line),
+            (Some(field), None) => field.clone(),
+            (None, Some(line)) => line.to_string(),
+            (None, None) => String::new(),
+        };
+        let text = pattern.replace_all(&m.text, |caps: &regex::Captures| {
+            style(&caps[0]).red().bold().to_string()
+        });
+        let _ = writeln!(out, "  {}: {}", style(location).green(), text);
+    }
+
+    if result.matches.is_empty() {
+        let _ = writeln!(
+            out,
+            "{} No matches in {} files ({})",
+            style("○").dim(),
+            result.files_searched,
+            result.source
+        );
+    } else {
+        out.push('\n');
+        let _ = writeln!(
+            out,
+            "{} match(es) in {} of {} files ({})",
+            result.matches.len(),
+            result
+                .matches
+                .iter()
+                .map(|m| &m.file)
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            result.files_searched,
+            result.source
+        );
+    }
+    pager::page(&out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_json_fields_and_code() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("procedures/Orders.total.json");
+        let content = r#"{
+            "name": "Orders.total",
+            "ruleText": "PROCEDURE Orders.total()\nvar x = 1\nSELECT FROM Orders",
+            "properties": {"a/b": "Orders"}
+        }"#;
+        let pattern = Regex::new("Orders").unwrap();
+        let found = search_file(dir.path(), &path, content, &pattern);
+
+        let locations: Vec<_> = found.iter().map(|m| (m.field.as_deref(), m.line)).collect();
+        assert_eq!(
+            locations,
+            vec![
+                (Some("/name"), None),
+                (Some("/properties/a~1b"), None),
+                (Some("/ruleText"), Some(1)),
+                (Some("/ruleText"), Some(3)),
+            ]
+        );
+        assert!(found
+            .iter()
+            .all(|m| m.resource == "procedures/Orders.total"));
+        assert_eq!(found[3].text, "SELECT FROM Orders");
+    }
+
+    #[test]
+    fn test_search_vail_and_filter() {
+        let dir = TempDir::new().unwrap();
+        for (rel, content) in [
+            (
+                "procedures/Orders.total.vail",
+                "PROCEDURE Orders.total()\nreturn 1",
+            ),
+            ("types/Order.json", "{}"),
+            ("export.json", "{}"),
+        ] {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        assert_eq!(searchable_files(dir.path(), &[]).len(), 3);
+        let files = searchable_files(dir.path(), &["procedures".to_string()]);
+        assert_eq!(files.len(), 1);
+
+        let content = std::fs::read_to_string(&files[0]).unwrap();
+        let pattern = Regex::new("(?i)return").unwrap();
+        let found = search_file(dir.path(), &files[0], &content, &pattern);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].resource, "procedures/Orders.total");
+        assert_eq!(found[0].field, None);
+        assert_eq!(found[0].line, Some(2));
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let pattern = Regex::new("needle").unwrap();
+        assert_eq!(excerpt("  short needle  ", &pattern), "short needle");
+
+        let long = format!("{}needle{}", "a".repeat(300), "b".repeat(300));
+        let cut = excerpt(&long, &pattern);
+        assert!(cut.starts_with('…') && cut.ends_with('…'));
+        assert!(cut.contains("needle"));
+        assert_eq!(cut.chars().count(), MAX_LINE_CHARS + 2);
+    }
+}
@@ -17,27 +17,86 @@
 //! - -include <typeName>: types to include
 //! - -exclude <typeName>: types to exclude
 //! - -ignore <resourceType>: resource types to ignore
+//!
+//! The CLI imports a directory all or nothing. With `--continue-on-error`
+//! vqx imports each resource type directory (or each file) in a CLI call of
+//! its own, goes on after failures and reports the outcome of every one.
 
-use crate::cli::{ImportArgs, ImportType, OutputFormat};
+use crate::blocklist::{self, BlockedResource};
+use crate::cache::ExportCache;
+use crate::chunking::{self, ChunkSize, ChunkTuner};
+use crate::cli::{ImportArgs, ImportType, ImportUnit, OutputFormat};
 use crate::config::Config;
 use crate::error::{Result, VqxError};
+use crate::filter::ResourceFilter;
+use crate::i18n;
+use crate::lock::OperationLock;
+use crate::manifest;
+use crate::masking;
+use crate::normalizer;
 use crate::profile::ProfileManager;
-use crate::underlying::{CliOptions, UnderlyingCli};
-use console::style;
-use dialoguer::Confirm;
-use indicatif::{ProgressBar, ProgressStyle};
-use std::path::PathBuf;
-use std::time::Duration;
+use crate::progress::TransferProgress;
+use crate::prompt;
+use crate::report;
+use crate::snapshot;
+use crate::template;
+use crate::theme::style;
+use crate::timing::{self, TimingTracker};
+use crate::underlying::{CliOptions, StreamControl, StreamEvent, UnderlyingCli};
+use indicatif::ProgressBar;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tracing::warn;
+use walkdir::WalkDir;
+
+/// Directories the import reads (PDF "Import" section)
+const IMPORT_DIRS: &[&str] = &[
+    "types",
+    "procedures",
+    "rules",
+    "sources",
+    "services",
+    "topics",
+    "collaborationtypes",
+    "aicomponents",
+    "catalogs",
+    "clients",
+    "configurations",
+    "debugconfigs",
+    "deployconfigs",
+    "environments",
+    "projects",
+    "scheduledevents",
+    "subscriptions",
+    "systemmodels",
+    "data",
+    "documents",
+];
 
 /// Import operation result
 #[derive(Debug)]
 pub struct ImportResult {
     pub success: bool,
-    pub directory: PathBuf,
     pub resources_imported: Option<usize>,
     pub errors: Vec<String>,
 }
 
+/// Outcome of importing one resource type directory or file
+#[derive(Debug, Serialize)]
+pub struct ImportOutcome {
+    /// Path relative to the import directory, e.g. `types` or `types/Order.json`
+    pub resource: String,
+    pub success: bool,
+    pub files: usize,
+    pub seconds: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// Run import command
 pub async fn run(
     args: &ImportArgs,
@@ -51,7 +110,7 @@ pub async fn run(
     let profile_name = profile_name.unwrap_or(&manager.store().default_profile);
     let profile = manager.get_resolved(profile_name)?;
 
-    if !profile.has_auth() {
+    if !profile.has_auth() && !args.dry_run {
         return Err(VqxError::ProfileInvalid {
             message: format!(
                 "Profile '{}' has no authentication configured",
@@ -77,29 +136,91 @@ pub async fn run(
         )));
     }
 
+    // Import with `{{vars.*}}` placeholders rendered for this profile
+    let rendered = template::render_copy(&input_dir, profile_name, &profile.vars)?;
+    let rendered_dir = rendered
+        .as_ref()
+        .map_or(input_dir.clone(), |r| r.path().to_path_buf());
+
+    // Filters the underlying CLI does not understand select the files to import
+    let filter = ResourceFilter::from_args(&args.filter, &[], None)?;
+    let cli_types = filter.cli_types();
+
+    // --dry-run: what would be imported, without the server
+    if args.dry_run {
+        let plan = plan_import(
+            &rendered_dir,
+            args.import_type,
+            &filter,
+            cli_types.is_some(),
+            &args.ignore,
+            config,
+            args.force,
+        );
+        return display_plan(&plan, args, profile_name, &input_dir, output_format);
+    }
+
+    // Keep other vqx operations out of this namespace until the import is done
+    let _lock = OperationLock::acquire(
+        "import",
+        profile_name,
+        &profile.url,
+        profile.namespace.as_deref(),
+        &args.lock,
+    )
+    .await?;
+
+    let filtered = match cli_types {
+        Some(_) => None,
+        None => filter.stage(&rendered_dir)?,
+    };
+    let source_dir = filtered
+        .as_ref()
+        .map_or(rendered_dir, |f| f.path().to_path_buf());
+
     // Count files to import
-    let file_count = count_import_files(&input_dir);
+    let file_count = count_import_files(&source_dir);
 
     // Display import info and warning
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
-        println!("{}", style("Import").bold().cyan());
+        println!("{}", style(i18n::t("import.title")).bold().cyan());
         println!("{}", style("─".repeat(50)).dim());
-        println!("  Profile:   {}", style(profile_name).green());
-        println!("  Server:    {}", profile.url);
-        println!("  Type:      {}", format_import_type(&args.import_type));
-        println!("  Directory: {}", input_dir.display());
-        println!("  Files:     ~{}", file_count);
+        println!(
+            "  {} {}",
+            i18n::label("label.profile"),
+            style(profile_name).green()
+        );
+        println!("  {} {}", i18n::label("label.server"), profile.url);
+        println!(
+            "  {} {}",
+            i18n::label("label.type"),
+            format_import_type(&args.import_type)
+        );
+        println!(
+            "  {} {}",
+            i18n::label("label.directory"),
+            input_dir.display()
+        );
+        println!("  {} ~{}", i18n::label("label.files"), file_count);
         if let Some(chunk) = args.chunk {
-            println!("  Chunk:     {}", chunk);
+            println!("  {} {}", i18n::label("label.chunk"), chunk);
+        }
+        if manifest::has_hidden(&input_dir) {
+            println!(
+                "  {} {}",
+                i18n::label("label.hidden"),
+                style(i18n::tf(
+                    "hidden.not_imported",
+                    &[("dir", &manifest::HIDDEN_DIR)]
+                ))
+                .dim()
+            );
         }
         println!();
 
         // Warning about destructive nature
-        println!(
-            "{}",
-            style("⚠  Warning: Import may overwrite existing resources!").yellow()
-        );
+        println!("{}", style(i18n::t("import.warning")).yellow());
         println!(
             "{}",
             style("   PDF: 'The import command reads all artifact definitions stored in a").dim()
@@ -111,49 +232,72 @@ pub async fn run(
         println!();
     }
 
+    // System and other blocked resources only with --force
+    let blocked: Vec<BlockedResource> = blocklist::scan(&source_dir, &config.import)
+        .into_iter()
+        .filter(|r| !args.ignore.contains(&r.resource_type))
+        .collect();
+    blocklist::check(&blocked, args.force)?;
+    if !output_format.is_machine_readable() {
+        blocklist::display(&blocked);
+    }
+
     // Confirmation required unless --yes is specified
-    if !args.yes && !matches!(output_format, OutputFormat::Json) {
-        let confirmed = Confirm::new()
-            .with_prompt(format!(
-                "Import ~{} files to {} ({})?",
-                file_count, profile.url, profile_name
-            ))
-            .default(false)
-            .interact()
-            .map_err(|e| VqxError::Other(e.to_string()))?;
+    if !args.yes && !output_format.is_machine_readable() {
+        let confirmed = prompt::confirm(
+            i18n::tf(
+                "import.confirm",
+                &[
+                    ("count", &file_count),
+                    ("url", &profile.url),
+                    ("profile", &profile_name),
+                ],
+            ),
+            "Import",
+            "--yes",
+        )?;
 
         if !confirmed {
-            println!("Import cancelled.");
-            return Ok(ImportResult {
-                success: false,
-                directory: input_dir,
-                resources_imported: None,
-                errors: vec!["Cancelled by user".to_string()],
+            return Err(VqxError::Cancelled {
+                operation: "Import".to_string(),
             });
         }
     }
 
+    // Cached exports of this namespace are outdated once the import starts
+    if matches!(args.import_type, ImportType::Metadata) {
+        ExportCache::new(config).invalidate(profile_name, profile.namespace.as_deref());
+    }
+
     // Build CLI
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("import"))
+        .with_retry_policy(config.retry_policy());
 
     let options = CliOptions::from_profile(&profile);
 
-    // Progress bar
-    let progress = if !matches!(output_format, OutputFormat::Json) {
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
-                .unwrap(),
-        );
-        pb.set_message("Importing to Vantiq...");
-        pb.enable_steady_tick(Duration::from_millis(100));
-        Some(pb)
-    } else {
-        None
+    // Snapshot a protected profile so the import can be rolled back
+    let snapshot = match args.import_type {
+        ImportType::Metadata => {
+            snapshot::take(
+                config,
+                &cli,
+                profile_name,
+                &profile,
+                "import",
+                !output_format.is_machine_readable(),
+            )
+            .await?
+        }
+        ImportType::Data => None,
     };
+    let snapshot_id = snapshot.map(|s| s.id);
+
+    // Progress bar
+    let mut progress = TransferProgress::new(
+        i18n::t("import.progress"),
+        !output_format.is_machine_readable(),
+    );
 
     // Build import type string
     // PDF: "import [data | metadata]"
@@ -164,16 +308,23 @@ pub async fn run(
 
     // Execute import
     // PDF: "vantiq import [type] [-d <directory>] [-chunk <size>] [-include <type>] [-exclude <type>] [-ignore <resourceType>]"
-    let include_refs: Vec<&str> = args.include.iter().map(|s| s.as_str()).collect();
-    let exclude_refs: Vec<&str> = args.exclude.iter().map(|s| s.as_str()).collect();
+    let (include_refs, exclude_refs) = cli_types.unwrap_or_default();
     let ignore_refs: Vec<&str> = args.ignore.iter().map(|s| s.as_str()).collect();
 
-    let result = cli
-        .import(
-            &options,
+    // Re-embed procedure/rule code extracted by the normalizer (extract_code)
+    let staging = normalizer::stage_for_import(&source_dir)?;
+    let import_dir = staging
+        .as_ref()
+        .map(|s| s.path().to_path_buf())
+        .unwrap_or_else(|| source_dir.clone());
+
+    let import_args = |dir: &Path, chunk: Option<u32>| {
+        UnderlyingCli::import_args(
             Some(import_type_str),
-            Some(input_dir.to_str().unwrap()),
-            args.chunk.or(Some(config.default_chunk_size)),
+            Some(dir),
+            chunk
+                .or(args.chunk.and_then(ChunkSize::fixed))
+                .or(Some(config.default_chunk_size)),
             if include_refs.is_empty() {
                 None
             } else {
@@ -190,18 +341,102 @@ pub async fn run(
                 Some(&ignore_refs)
             },
         )
+    };
+
+    // --chunk auto imports each type on its own, stopping at the first
+    // failure unless --continue-on-error is given
+    let mut tuner = (args.chunk == Some(ChunkSize::Auto))
+        .then(|| ChunkTuner::new(config, profile_name, "import"));
+    if args.continue_on_error.is_some() || tuner.is_some() {
+        let outcomes = import_each(
+            &cli,
+            &options,
+            config,
+            &import_dir,
+            args.continue_on_error.unwrap_or(ImportUnit::Types),
+            args.continue_on_error.is_none(),
+            &import_args,
+            tuner.as_mut(),
+            &mut progress,
+        )
         .await?;
+        progress.finish_and_clear();
+        let chunking = match tuner {
+            Some(tuner) => tuner.finish()?,
+            None => vec![],
+        };
+
+        let failed: Vec<&ImportOutcome> = outcomes.iter().filter(|o| !o.success).collect();
+        let imported: usize = outcomes.iter().filter(|o| o.success).map(|o| o.files).sum();
+        if output_format.is_machine_readable() {
+            let json_result = serde_json::json!({
+                "success": failed.is_empty(),
+                "directory": input_dir.display().to_string(),
+                "files_in_directory": file_count,
+                "files_imported": imported,
+                "failed": failed.len(),
+                "snapshot": snapshot_id,
+                "profile": profile_name,
+                "server": profile.url,
+                "import_type": format_import_type(&args.import_type),
+                "outcomes": outcomes,
+                "chunking": chunking,
+            });
+            println!("{}", report::render(&json_result, output_format)?);
+        } else {
+            display_outcomes(&outcomes);
+            chunking::display(&chunking);
+        }
 
-    if let Some(ref pb) = progress {
-        pb.finish_and_clear();
+        return Ok(ImportResult {
+            success: failed.is_empty(),
+            resources_imported: Some(imported),
+            errors: failed
+                .iter()
+                .map(|o| format!("{}: {}", o.resource, o.error.as_deref().unwrap_or("")))
+                .collect(),
+        });
     }
 
+    // Stream output so a silent (possibly hung) import is surfaced instead
+    // of sitting behind the spinner until the global timeout
+    let interactive = prompt::is_interactive() && !output_format.is_machine_readable();
+    let mut tracker = TimingTracker::new();
+    let result = cli
+        .execute_streaming(
+            &options,
+            "import",
+            import_args(&import_dir, None),
+            config.stall_policy(),
+            |event| match event {
+                StreamEvent::Line { text, .. } => {
+                    tracker.observe(text, Instant::now());
+                    progress.observe(text);
+                    StreamControl::Continue
+                }
+                StreamEvent::Heartbeat { elapsed, idle } => {
+                    progress.set_status(format!(
+                        "Importing to Vantiq... ({} elapsed, no output for {})",
+                        format_duration(elapsed),
+                        format_duration(idle)
+                    ));
+                    StreamControl::Continue
+                }
+                StreamEvent::Stall { elapsed, idle } => {
+                    handle_stall(progress.bar(), interactive, elapsed, idle)
+                }
+            },
+        )
+        .await?;
+    let timings = tracker.finish(Instant::now());
+    progress.finish_and_clear();
+
     if !result.success() {
-        if !matches!(output_format, OutputFormat::Json) {
+        if !output_format.is_machine_readable() {
             println!(
-                "{} Import failed with exit code {}",
+                "{} {}",
                 style("✗").red(),
-                result.code()
+                i18n::tf("import.failed", &[("code", &result.code())])
             );
             if !result.stderr.is_empty() {
                 println!("{}", style(&result.stderr).red());
@@ -210,17 +445,21 @@ pub async fn run(
 
         return Ok(ImportResult {
             success: false,
-            directory: input_dir,
             resources_imported: None,
             errors: vec![result.stderr],
         });
     }
 
     // Output summary
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
         println!("{}", style("─".repeat(50)).dim());
-        println!("{} Import complete", style("✓").green().bold());
+        println!(
+            "{} {}",
+            style("✓").green().bold(),
+            i18n::t("import.complete")
+        );
+        timing::display_breakdown(&timings);
 
         if !result.stdout.is_empty() && verbose {
             println!();
@@ -250,26 +489,490 @@ pub async fn run(
     }
 
     // JSON output
-    if matches!(output_format, OutputFormat::Json) {
+    if output_format.is_machine_readable() {
         let json_result = serde_json::json!({
             "success": true,
             "directory": input_dir.display().to_string(),
             "files_in_directory": file_count,
+            "snapshot": snapshot_id,
             "profile": profile_name,
             "server": profile.url,
             "import_type": format_import_type(&args.import_type),
+            "timings": timings,
         });
-        println!("{}", serde_json::to_string_pretty(&json_result)?);
+        println!("{}", report::render(&json_result, output_format)?);
     }
 
     Ok(ImportResult {
         success: true,
-        directory: input_dir,
         resources_imported: Some(file_count),
         errors: vec![],
     })
 }
 
+/// What an import would do (`--dry-run`)
+#[derive(Debug, Default, Serialize)]
+struct ImportPlan {
+    /// Selected resources by type directory; data by user type
+    resources: BTreeMap<String, BTreeSet<String>>,
+    files: usize,
+    blocked: Vec<BlockedResource>,
+    warnings: Vec<String>,
+    /// Problems that would make the import fail or import nothing
+    errors: Vec<String>,
+}
+
+/// Check an import directory and list the resources an import selects
+fn plan_import(
+    dir: &Path,
+    import_type: ImportType,
+    filter: &ResourceFilter,
+    plain: bool,
+    ignore: &[String],
+    config: &Config,
+    force: bool,
+) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    // The data import reads user type data and documents, the metadata
+    // import everything else
+    let dirs: Vec<&str> = IMPORT_DIRS
+        .iter()
+        .copied()
+        .filter(|d| match import_type {
+            ImportType::Metadata => *d != masking::DATA_DIR,
+            ImportType::Data => *d == masking::DATA_DIR || *d == "documents",
+        })
+        .collect();
+
+    let mut top: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| !name.starts_with(['.', '_']))
+        .collect();
+    top.sort();
+    for name in top.iter().filter(|n| !IMPORT_DIRS.contains(&n.as_str())) {
+        plan.warnings.push(format!(
+            "{}/ is not a resource directory and is not imported",
+            name
+        ));
+    }
+    let present: Vec<&str> = dirs
+        .iter()
+        .copied()
+        .filter(|d| top.iter().any(|t| t == d))
+        .collect();
+    if present.is_empty() {
+        plan.errors.push(format!(
+            "No resource directories in {} (expected {})",
+            dir.display(),
+            dirs.iter()
+                .map(|d| format!("{}/", d))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+        return plan;
+    }
+
+    // Every resource of the directory, and the selected files
+    let mut all: Vec<(String, Option<String>)> = Vec::new();
+    let mut selected: Vec<PathBuf> = Vec::new();
+    for resource_type in present.iter().filter(|d| !ignore.iter().any(|i| i == *d)) {
+        for entry in WalkDir::new(dir.join(resource_type))
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+        {
+            let Ok(rel) = entry.path().strip_prefix(dir) else {
+                continue;
+            };
+            if rel
+                .file_name()
+                .is_some_and(|n| n.to_string_lossy().starts_with('.'))
+            {
+                continue;
+            }
+            let (key, name, stem) = resource_of(rel);
+            // Plain names of a data import are user types (`-include <typeName>`)
+            let is_data = key == masking::DATA_DIR && matches!(import_type, ImportType::Data);
+            let chosen = if is_data && plain {
+                all.push((name.clone(), None));
+                filter.matches_type(&name)
+            } else {
+                all.push((key.clone(), Some(stem)));
+                filter.matches_path(rel)
+            };
+            if chosen {
+                plan.resources.entry(key).or_default().insert(name);
+                selected.push(rel.to_path_buf());
+            }
+        }
+    }
+    plan.files = selected.len();
+
+    let all: Vec<(&str, Option<&str>)> = all
+        .iter()
+        .map(|(t, n)| (t.as_str(), n.as_deref()))
+        .collect();
+    let (includes, excludes) = filter.unmatched(&all);
+    plan.errors.extend(
+        includes
+            .iter()
+            .map(|p| format!("--include {} matches nothing in {}", p, dir.display())),
+    );
+    plan.warnings.extend(
+        excludes
+            .iter()
+            .map(|p| format!("--exclude {} matches nothing in {}", p, dir.display())),
+    );
+    if plan.files == 0 && includes.is_empty() {
+        plan.errors.push("Nothing would be imported".to_string());
+    }
+
+    plan.blocked = blocklist::scan_files(&selected, &config.import);
+    if let Err(e) = blocklist::check(&plan.blocked, force) {
+        plan.errors.push(e.to_string());
+    }
+    plan
+}
+
+/// Type directory, listed name and name stem of a file to import: data
+/// files are listed by user type (`data/<Type>.json` or
+/// `data/<Type>/<chunk>.json`), other files by their path in the type
+/// directory
+fn resource_of(rel: &Path) -> (String, String, String) {
+    let mut parts = rel.iter().map(|p| p.to_string_lossy().to_string());
+    let key = parts.next().unwrap_or_default();
+    let rest: Vec<String> = parts.collect();
+    let stem = rel
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let path = rest.join("/");
+    let name = if key == masking::DATA_DIR {
+        let first = rest.first().map(String::as_str).unwrap_or_default();
+        first.strip_suffix(".json").unwrap_or(first).to_string()
+    } else {
+        path.strip_suffix(".json")
+            .or_else(|| path.strip_suffix(".vail"))
+            .unwrap_or(&path)
+            .to_string()
+    };
+    (key, name, stem)
+}
+
+fn display_plan(
+    plan: &ImportPlan,
+    args: &ImportArgs,
+    profile_name: &str,
+    input_dir: &Path,
+    output_format: OutputFormat,
+) -> Result<ImportResult> {
+    let success = plan.errors.is_empty();
+    if output_format.is_machine_readable() {
+        let json_result = serde_json::json!({
+            "success": success,
+            "dry_run": true,
+            "directory": input_dir.display().to_string(),
+            "profile": profile_name,
+            "import_type": format_import_type(&args.import_type),
+            "files": plan.files,
+            "resources": plan.resources,
+            "blocked": plan.blocked,
+            "warnings": plan.warnings,
+            "errors": plan.errors,
+        });
+        println!("{}", report::render(&json_result, output_format)?);
+    } else {
+        println!();
+        println!(
+            "{} {}",
+            style(i18n::t("import.title")).bold().cyan(),
+            style("(dry run)").yellow()
+        );
+        println!("{}", style("─".repeat(50)).dim());
+        println!(
+            "  {} {}",
+            i18n::label("label.profile"),
+            style(profile_name).green()
+        );
+        println!(
+            "  {} {}",
+            i18n::label("label.type"),
+            format_import_type(&args.import_type)
+        );
+        println!(
+            "  {} {}",
+            i18n::label("label.directory"),
+            input_dir.display()
+        );
+        println!();
+        for (resource_type, names) in &plan.resources {
+            println!(
+                "  {} {}",
+                style(format!("{}/", resource_type)).bold(),
+                style(format!("({})", names.len())).dim()
+            );
+            for name in names {
+                println!("    {}", name);
+            }
+        }
+        blocklist::display(&plan.blocked);
+        for warning in &plan.warnings {
+            println!("{} {}", style("⚠").yellow(), warning);
+        }
+        for error in &plan.errors {
+            println!("{} {}", style("✗").red(), error);
+        }
+        println!();
+        if success {
+            println!(
+                "{} Dry run complete. {} file(s) would be imported; nothing was imported.",
+                style("✓").green(),
+                plan.files
+            );
+        } else {
+            println!(
+                "{} Dry run found {} problem(s); nothing was imported.",
+                style("✗").red(),
+                plan.errors.len()
+            );
+        }
+    }
+
+    Ok(ImportResult {
+        success,
+        resources_imported: None,
+        errors: plan.errors.clone(),
+    })
+}
+
+/// Import the units of a directory one CLI call at a time
+///
+/// A failed call is recorded and the next unit imported, unless
+/// `stop_on_error`; errors that stop vqx itself (such as a missing CLI)
+/// always end the loop. With a tuner, each unit gets its own chunk size.
+#[allow(clippy::too_many_arguments)]
+async fn import_each(
+    cli: &UnderlyingCli,
+    options: &CliOptions,
+    config: &Config,
+    import_dir: &Path,
+    unit: ImportUnit,
+    stop_on_error: bool,
+    import_args: &dyn Fn(&Path, Option<u32>) -> Vec<OsString>,
+    mut tuner: Option<&mut ChunkTuner>,
+    progress: &mut TransferProgress,
+) -> Result<Vec<ImportOutcome>> {
+    let units = import_units(import_dir, unit);
+    let mut outcomes = Vec::with_capacity(units.len());
+
+    for (i, rel) in units.iter().enumerate() {
+        let resource = rel.to_string_lossy().replace('\\', "/");
+        progress.set_phase(format!(
+            "Importing {} ({}/{})...",
+            resource,
+            i + 1,
+            units.len()
+        ));
+
+        let staging = TempDir::new()?;
+        let source = import_dir.join(rel);
+        let dest = staging.path().join(rel);
+        if source.is_dir() {
+            normalizer::copy_directory(&source, &dest)?;
+        } else {
+            std::fs::create_dir_all(dest.parent().unwrap_or(staging.path()))?;
+            std::fs::copy(&source, &dest).map_err(|_| VqxError::FileReadFailed {
+                path: source.display().to_string(),
+            })?;
+        }
+
+        let started = Instant::now();
+        let result = match tuner.as_deref_mut() {
+            Some(tuner) => {
+                let name = resource.strip_suffix(".json").unwrap_or(&resource);
+                tuner
+                    .run(
+                        name,
+                        cli,
+                        options,
+                        &|chunk| import_args(staging.path(), Some(chunk)),
+                        &mut |line| progress.observe(line),
+                        &|| Ok(()),
+                    )
+                    .await
+            }
+            None => {
+                progress
+                    .run(
+                        cli,
+                        options,
+                        "import",
+                        import_args(staging.path(), None),
+                        config.stall_policy(),
+                    )
+                    .await
+            }
+        };
+        let error = match result {
+            Ok(result) if result.success() => None,
+            Ok(result) => Some(
+                result
+                    .stderr
+                    .lines()
+                    .rev()
+                    .find(|l| !l.trim().is_empty())
+                    .map(|l| l.trim().to_string())
+                    .unwrap_or_else(|| format!("exit code {}", result.code())),
+            ),
+            Err(e @ (VqxError::CliNotFound { .. } | VqxError::JavaNotFound)) => return Err(e),
+            Err(e) => Some(e.to_string()),
+        };
+        outcomes.push(ImportOutcome {
+            resource,
+            success: error.is_none(),
+            files: count_files_recursive(&source.to_path_buf()).max(1),
+            seconds: (started.elapsed().as_secs_f64() * 10.0).round() / 10.0,
+            error,
+        });
+        if stop_on_error && outcomes.last().is_some_and(|o| !o.success) {
+            break;
+        }
+    }
+    Ok(outcomes)
+}
+
+/// Resource type directories (each type of `data/`), or their JSON files,
+/// relative to the import directory
+fn import_units(dir: &Path, unit: ImportUnit) -> Vec<PathBuf> {
+    let mut units = Vec::new();
+    for name in IMPORT_DIRS {
+        let path = dir.join(name);
+        if !path.is_dir() {
+            continue;
+        }
+        match unit {
+            ImportUnit::Types if *name == crate::masking::DATA_DIR => {
+                let mut types: Vec<PathBuf> = std::fs::read_dir(&path)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|e| PathBuf::from(name).join(e.file_name()))
+                    .collect();
+                types.sort();
+                units.extend(types);
+            }
+            ImportUnit::Types => units.push(PathBuf::from(name)),
+            ImportUnit::Files => units.extend(
+                WalkDir::new(&path)
+                    .sort_by_file_name()
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file())
+                    .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+                    .filter_map(|e| e.path().strip_prefix(dir).ok().map(Path::to_path_buf)),
+            ),
+        }
+    }
+    units
+}
+
+/// Show the outcome of every CLI call and a summary
+fn display_outcomes(outcomes: &[ImportOutcome]) {
+    println!();
+    for outcome in outcomes {
+        match outcome.error {
+            None => println!(
+                "  {} {} {}",
+                style("✓").green(),
+                outcome.resource,
+                style(format!("({:.1}s)", outcome.seconds)).dim()
+            ),
+            Some(ref error) => println!(
+                "  {} {}: {}",
+                style("✗").red(),
+                outcome.resource,
+                style(error).red()
+            ),
+        }
+    }
+
+    let failed = outcomes.iter().filter(|o| !o.success).count();
+    println!();
+    println!("{}", style("─".repeat(50)).dim());
+    if failed == 0 {
+        println!(
+            "{} {}",
+            style("✓").green().bold(),
+            i18n::t("import.complete")
+        );
+    } else {
+        println!(
+            "{} {} of {} imported, {} failed",
+            style("✗").red().bold(),
+            outcomes.len() - failed,
+            outcomes.len(),
+            failed
+        );
+    }
+    println!();
+}
+
+/// Warn about a stalled import and, when interactive, offer to abort it
+fn handle_stall(
+    progress: Option<&ProgressBar>,
+    interactive: bool,
+    elapsed: Duration,
+    idle: Duration,
+) -> StreamControl {
+    let message = format!(
+        "Import has produced no output for {} ({} elapsed)",
+        format_duration(idle),
+        format_duration(elapsed)
+    );
+
+    if !interactive {
+        warn!("{}", message);
+        return StreamControl::Continue;
+    }
+
+    let ask = || {
+        eprintln!("{} {}", style("⚠").yellow(), message);
+        prompt::ask_yes_no(
+            "Abort the import?",
+            false,
+            "Import",
+            "wait for it to finish",
+        )
+        .unwrap_or(false)
+    };
+    let abort = match progress {
+        Some(pb) => pb.suspend(ask),
+        None => ask(),
+    };
+
+    if abort {
+        StreamControl::Abort
+    } else {
+        StreamControl::Continue
+    }
+}
+
+/// Format a duration as e.g. "45s", "2m 10s" or "1h 03m"
+fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
 /// Format import type for display
 fn format_import_type(import_type: &ImportType) -> String {
     match import_type {
@@ -282,31 +985,7 @@ fn format_import_type(import_type: &ImportType) -> String {
 fn count_import_files(dir: &PathBuf) -> usize {
     let mut count = 0;
 
-    // Known import directories from PDF
-    let import_dirs = [
-        "types",
-        "procedures",
-        "rules",
-        "sources",
-        "services",
-        "topics",
-        "collaborationtypes",
-        "aicomponents",
-        "catalogs",
-        "clients",
-        "configurations",
-        "debugconfigs",
-        "deployconfigs",
-        "environments",
-        "projects",
-        "scheduledevents",
-        "subscriptions",
-        "systemmodels",
-        "data",
-        "documents",
-    ];
-
-    for subdir in &import_dirs {
+    for subdir in IMPORT_DIRS {
         let path = dir.join(subdir);
         if path.is_dir() {
             count += count_files_recursive(&path);
@@ -369,9 +1048,14 @@ pub fn display_help() {
     println!("  --include <type>   Include specific types (PDF: '-include <typeName>')");
     println!("  --exclude <type>   Exclude specific types (PDF: '-exclude <typeName>')");
     println!("  --ignore <res>     Ignore resource types (PDF: '-ignore <resourceType>')");
+    println!("  --include/--exclude <type>/<name>, with globs, are applied by vqx");
     println!();
     println!("{}", style("Safety Options:").bold());
     println!("  --yes, -y          Skip confirmation prompt");
+    println!("  --force            Include resources with blocked name prefixes");
+    println!("  --continue-on-error[=files]  Import type by type (or file by file), report each");
+    println!("  --chunk auto       One call per type, tuning its chunk size from past runs");
+    println!("  --wait[=<seconds>] Wait for other vqx operations on the namespace");
     println!();
     println!("{}", style("Examples:").bold());
     println!();
@@ -415,6 +1099,111 @@ pub fn display_help() {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_import_units() {
+        let dir = TempDir::new().unwrap();
+        for rel in [
+            "types/Order.json",
+            "procedures/Orders.total.json",
+            "data/Order.json",
+            "data/Customer/1.json",
+            "_hidden/types/ArsType.json",
+            "export.json",
+        ] {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "{}").unwrap();
+        }
+
+        let names = |unit| -> Vec<String> {
+            import_units(dir.path(), unit)
+                .iter()
+                .map(|p| p.to_string_lossy().replace('\\', "/"))
+                .collect()
+        };
+        assert_eq!(
+            names(ImportUnit::Types),
+            ["types", "procedures", "data/Customer", "data/Order.json"]
+        );
+        assert_eq!(
+            names(ImportUnit::Files),
+            [
+                "types/Order.json",
+                "procedures/Orders.total.json",
+                "data/Customer/1.json",
+                "data/Order.json"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_plan_import() {
+        let dir = TempDir::new().unwrap();
+        for rel in [
+            "types/Order.json",
+            "procedures/Orders.total.json",
+            "procedures/Orders.total.vail",
+            "procedures/Billing.json",
+            "data/Order.json",
+            "data/Customer/1.json",
+            "backup/types/Order.json",
+        ] {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "{}").unwrap();
+        }
+        let config = Config::default();
+        let plan_with = |import_type, include: &[&str], exclude: &[&str]| {
+            let filter = ResourceFilter::new(include, exclude).unwrap();
+            let plain = filter.cli_types().is_some();
+            plan_import(dir.path(), import_type, &filter, plain, &[], &config, false)
+        };
+
+        let plan = plan_with(ImportType::Metadata, &["procedures/Order*", "rules"], &[]);
+        assert_eq!(plan.files, 2);
+        let names: Vec<&String> = plan.resources["procedures"].iter().collect();
+        assert_eq!(names, ["Orders.total"]);
+        assert!(!plan.resources.contains_key("data"));
+        assert_eq!(
+            plan.errors,
+            [format!(
+                "--include rules matches nothing in {}",
+                dir.path().display()
+            )]
+        );
+        assert!(plan.warnings[0].starts_with("backup/"));
+
+        // Plain names of a data import are user types
+        let plan = plan_with(ImportType::Data, &["Customer"], &["Invoice"]);
+        assert!(plan.errors.is_empty());
+        let names: Vec<&String> = plan.resources["data"].iter().collect();
+        assert_eq!(names, ["Customer"]);
+        assert!(plan
+            .warnings
+            .iter()
+            .any(|w| w.starts_with("--exclude Invoice")));
+
+        let empty = TempDir::new().unwrap();
+        let filter = ResourceFilter::default();
+        let plan = plan_import(
+            empty.path(),
+            ImportType::Metadata,
+            &filter,
+            true,
+            &[],
+            &config,
+            false,
+        );
+        assert!(plan.errors[0].starts_with("No resource directories"));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(130)), "2m 10s");
+        assert_eq!(format_duration(Duration::from_secs(3780)), "1h 03m");
+    }
+
     #[test]
     fn test_format_import_type() {
         assert_eq!(format_import_type(&ImportType::Metadata), "metadata");
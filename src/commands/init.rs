@@ -0,0 +1,406 @@
+//! Init command implementation
+//!
+//! First-run setup (vqx extension). Walks through what a new user otherwise
+//! has to piece together from `doctor`, `config` and `profile init`:
+//!
+//! 1. Locate the Vantiq CLI (asking for its path when it is not in PATH)
+//! 2. Verify Java 11+ (PDF: "Prerequisites" section)
+//! 3. Write config.toml with the CLI path
+//! 4. Run the `profile init` wizard when no profile exists
+//! 5. Scaffold the project: an export directory, a .gitignore that keeps
+//!    credentials out of git, and a `.vqx.toml` project file (see
+//!    [`crate::project`])
+//!
+//! Every step is safe to repeat: existing files are kept, and a failed
+//! step does not stop the ones after it.
+
+use crate::cli::{InitArgs, OutputFormat, ProfileCommands, ProfileInitArgs};
+use crate::commands::{doctor, profile};
+use crate::config::Config;
+use crate::error::Result;
+use crate::profile::ProfileManager;
+use crate::project::{self, ProjectConfig, PROJECT_FILE};
+use crate::prompt;
+use crate::report;
+use crate::theme::style;
+use crate::underlying::UnderlyingCli;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Files the Vantiq CLI and vqx keep credentials in
+const GITIGNORE_ENTRIES: &[&str] = &["server.config", "profiles.toml", ".env", "*.token"];
+
+/// Outcome of one step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    /// The step changed something
+    Done,
+    /// Nothing to do
+    Ok,
+    /// Needs the user; `message` says what to run
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InitStep {
+    pub name: String,
+    pub status: StepStatus,
+    pub message: String,
+}
+
+impl InitStep {
+    fn new(name: &str, status: StepStatus, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of the init command
+#[derive(Debug, Serialize)]
+pub struct InitResult {
+    pub success: bool,
+    pub directory: PathBuf,
+    pub steps: Vec<InitStep>,
+}
+
+/// Run the init command
+///
+/// `config_path` is the `--config` file, if given; `profile` names the
+/// profile the wizard creates and the project file selects.
+pub async fn run(
+    args: &InitArgs,
+    config: &Config,
+    config_path: Option<&Path>,
+    profile: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let interactive = !args.yes && !output_format.is_machine_readable() && prompt::is_interactive();
+    if !output_format.is_machine_readable() {
+        println!();
+        println!("{}", style("vqx Init").bold().cyan());
+        println!("{}", style("─".repeat(40)).dim());
+        println!();
+    }
+
+    let mut steps = Vec::new();
+
+    let (step, cli_path) = locate_cli(&config.cli_path, interactive)?;
+    steps.push(step);
+
+    let java = doctor::check_java().await;
+    steps.push(InitStep::new(
+        "Java",
+        if java.passed {
+            StepStatus::Ok
+        } else {
+            StepStatus::Failed
+        },
+        java.message,
+    ));
+
+    let config_path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => Config::config_file_path()?,
+    };
+    steps.push(write_config(&config_path, cli_path.as_deref()));
+    steps.push(init_profile(config, profile, interactive).await?);
+
+    let export_dir = args.directory.join(&args.export_dir);
+    steps.push(match fs::create_dir_all(&export_dir) {
+        Ok(()) => InitStep::new(
+            "Export directory",
+            StepStatus::Ok,
+            format!("{}", export_dir.display()),
+        ),
+        Err(e) => InitStep::new(
+            "Export directory",
+            StepStatus::Failed,
+            format!("Could not create {}: {}", export_dir.display(), e),
+        ),
+    });
+    steps.push(write_gitignore(&args.directory.join(".gitignore")));
+    steps.push(write_project_file(
+        &args.directory.join(PROJECT_FILE),
+        &ProjectConfig {
+            profile: profile.map(String::from),
+            directory: Some(args.export_dir.clone()),
+        },
+    ));
+
+    let result = InitResult {
+        success: steps.iter().all(|s| s.status != StepStatus::Failed),
+        directory: args.directory.clone(),
+        steps,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display(&result, &args.export_dir);
+    }
+    Ok(result.success)
+}
+
+/// Find the CLI at `cli_path`, or ask for its location
+///
+/// Returns the path to store in config.toml, if one was found.
+fn locate_cli(cli_path: &str, interactive: bool) -> Result<(InitStep, Option<String>)> {
+    const NAME: &str = "Vantiq CLI";
+    if let Ok(found) = UnderlyingCli::new(cli_path.to_string()).check_cli_exists() {
+        return Ok((
+            InitStep::new(NAME, StepStatus::Ok, format!("Found at: {}", found)),
+            Some(cli_path.to_string()),
+        ));
+    }
+    if !interactive {
+        return Ok((
+            InitStep::new(
+                NAME,
+                StepStatus::Failed,
+                format!(
+                    "CLI not found: {}. Download it from Help -> Developer Resources in the \
+                     Vantiq UI, then rerun `vqx init --cli <path>`",
+                    cli_path
+                ),
+            ),
+            None,
+        ));
+    }
+
+    println!(
+        "The Vantiq CLI ('{}') is not in PATH. Download it from Help -> Developer Resources",
+        cli_path
+    );
+    println!("in the Vantiq UI and unzip it; its bin directory holds 'vantiq' ('vantiq.bat' on Windows).");
+    loop {
+        let answer = prompt::input(
+            "Path to the Vantiq CLI (empty to skip)",
+            None,
+            true,
+            "Locating the Vantiq CLI",
+            "set `cli_path` with `vqx config set cli_path <path>`",
+        )?;
+        let answer = answer.trim();
+        if answer.is_empty() {
+            return Ok((
+                InitStep::new(
+                    NAME,
+                    StepStatus::Skipped,
+                    "Not located: set `cli_path` with `vqx config set cli_path <path>`",
+                ),
+                None,
+            ));
+        }
+        match UnderlyingCli::new(answer.to_string()).check_cli_exists() {
+            Ok(found) => {
+                return Ok((
+                    InitStep::new(NAME, StepStatus::Done, format!("Found at: {}", found)),
+                    Some(found),
+                ))
+            }
+            Err(_) => println!("{} Not an executable: {}", style("✗").red(), answer),
+        }
+    }
+}
+
+/// Write config.toml, or record a newly located CLI in an existing one
+fn write_config(path: &Path, cli_path: Option<&str>) -> InitStep {
+    const NAME: &str = "Config";
+    let existed = path.exists();
+    let mut config = match Config::load_from(path) {
+        Ok(config) => config,
+        Err(e) => {
+            return InitStep::new(
+                NAME,
+                StepStatus::Failed,
+                format!("{}: {} (see `vqx config validate`)", path.display(), e),
+            )
+        }
+    };
+    let changed = match cli_path {
+        Some(cli_path) if config.cli_path != cli_path => {
+            config.cli_path = cli_path.to_string();
+            true
+        }
+        _ => false,
+    };
+    if existed && !changed {
+        return InitStep::new(NAME, StepStatus::Ok, format!("{}", path.display()));
+    }
+    match config.save_to(path) {
+        Ok(()) if existed => InitStep::new(
+            NAME,
+            StepStatus::Done,
+            format!("Set cli_path in {}", path.display()),
+        ),
+        Ok(()) => InitStep::new(NAME, StepStatus::Done, format!("Wrote {}", path.display())),
+        Err(e) => InitStep::new(NAME, StepStatus::Failed, e.to_string()),
+    }
+}
+
+/// Run the profile wizard unless a profile exists
+async fn init_profile(
+    config: &Config,
+    profile: Option<&str>,
+    interactive: bool,
+) -> Result<InitStep> {
+    const NAME: &str = "Profile";
+    let manager = ProfileManager::new()?;
+    let count = manager.store().list_names().len();
+    if count > 0 {
+        return Ok(InitStep::new(
+            NAME,
+            StepStatus::Ok,
+            format!("{} profile(s) configured", count),
+        ));
+    }
+    if !interactive {
+        return Ok(InitStep::new(
+            NAME,
+            StepStatus::Skipped,
+            "No profile yet: run `vqx profile init` in a terminal",
+        ));
+    }
+
+    let init = ProfileCommands::Init(ProfileInitArgs {
+        name: profile.map(String::from),
+    });
+    Ok(
+        match profile::run(&init, config, OutputFormat::Text).await {
+            Ok(()) => InitStep::new(NAME, StepStatus::Done, "Created a profile"),
+            Err(e) => InitStep::new(NAME, StepStatus::Failed, e.to_string()),
+        },
+    )
+}
+
+/// Add the credential files missing from `.gitignore`
+fn write_gitignore(path: &Path) -> InitStep {
+    const NAME: &str = ".gitignore";
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let missing = missing_entries(&existing);
+    if missing.is_empty() {
+        return InitStep::new(NAME, StepStatus::Ok, "Already ignores credential files");
+    }
+
+    let mut content = existing.clone();
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    content.push_str("# vqx: never commit credentials\n");
+    for entry in &missing {
+        content.push_str(entry);
+        content.push('\n');
+    }
+    match fs::write(path, content) {
+        Ok(()) => InitStep::new(
+            NAME,
+            StepStatus::Done,
+            format!("Ignored {} in {}", missing.join(", "), path.display()),
+        ),
+        Err(e) => InitStep::new(
+            NAME,
+            StepStatus::Failed,
+            format!("Could not write {}: {}", path.display(), e),
+        ),
+    }
+}
+
+fn missing_entries(gitignore: &str) -> Vec<&'static str> {
+    GITIGNORE_ENTRIES
+        .iter()
+        .filter(|entry| !gitignore.lines().any(|line| line.trim() == **entry))
+        .copied()
+        .collect()
+}
+
+/// Write `.vqx.toml` unless the project already has one
+fn write_project_file(path: &Path, config: &ProjectConfig) -> InitStep {
+    const NAME: &str = "Project file";
+    if path.exists() {
+        return InitStep::new(NAME, StepStatus::Ok, format!("{}", path.display()));
+    }
+    match fs::write(path, project::render(config)) {
+        Ok(()) => InitStep::new(NAME, StepStatus::Done, format!("Wrote {}", path.display())),
+        Err(e) => InitStep::new(
+            NAME,
+            StepStatus::Failed,
+            format!("Could not write {}: {}", path.display(), e),
+        ),
+    }
+}
+
+fn display(result: &InitResult, export_dir: &Path) {
+    println!();
+    for step in &result.steps {
+        let (mark, message) = match step.status {
+            StepStatus::Done => (style("✓").green(), style(&step.message).green()),
+            StepStatus::Ok => (style("✓").green(), style(&step.message)),
+            StepStatus::Skipped => (style("–").yellow(), style(&step.message).yellow()),
+            StepStatus::Failed => (style("✗").red(), style(&step.message).red()),
+        };
+        println!("  {} {:<18} {}", mark, step.name, message);
+    }
+    println!();
+
+    if result.success {
+        println!("{}", style("Next steps:").bold());
+        println!("  vqx doctor --test-connection   check the connection with your profile");
+        println!(
+            "  vqx export                     export the namespace into {}",
+            export_dir.display()
+        );
+    } else {
+        println!(
+            "{} Fix the failed steps above and run `vqx init` again",
+            style("✗").red()
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_gitignore_appends_missing_entries() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(".gitignore");
+        fs::write(&path, "target/\n.env\n").unwrap();
+
+        let step = write_gitignore(&path);
+        assert_eq!(step.status, StepStatus::Done);
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("target/\n.env\n\n# vqx: never commit credentials\n"));
+        assert_eq!(content.matches(".env").count(), 1);
+        assert!(missing_entries(&content).is_empty());
+
+        // Running again changes nothing
+        assert_eq!(write_gitignore(&path).status, StepStatus::Ok);
+    }
+
+    #[test]
+    fn test_write_config_records_cli_path() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+
+        assert_eq!(write_config(&path, None).status, StepStatus::Done);
+        assert_eq!(write_config(&path, None).status, StepStatus::Ok);
+        let step = write_config(&path, Some("/opt/vantiq/bin/vantiq"));
+        assert_eq!(step.status, StepStatus::Done);
+        assert_eq!(
+            Config::load_from(&path).unwrap().cli_path,
+            "/opt/vantiq/bin/vantiq"
+        );
+    }
+}
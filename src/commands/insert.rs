@@ -0,0 +1,315 @@
+//! Insert/Upsert command implementation
+//!
+//! Bulk-loads records through the underlying CLI's insert and upsert
+//! commands (and their checked variants).
+//!
+//! Based on: CLI Reference Guide PDF - "Insert" and "Upsert" sections
+//!
+//! PDF: "insert <resource> <fileName>" / "upsert <resource> <fileName>"
+//!
+//! Records are read from a file or stdin as a JSON array, a single object
+//! or NDJSON, split into batches of `--chunk` records, and each batch is
+//! written to a temp file and passed to the CLI. A failed batch does not
+//! stop the remaining batches; failures are reported per batch.
+
+use crate::backend::{self, Executor};
+use crate::cli::{InsertArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::progress;
+use crate::report;
+use crate::theme::style;
+use crate::underlying::UnderlyingCli;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{Read, Write};
+use tempfile::NamedTempFile;
+use tracing::{info, warn};
+
+/// Write mode for bulk loading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    Insert,
+    Upsert,
+}
+
+impl WriteMode {
+    fn command_name(self, checked: bool) -> &'static str {
+        match (self, checked) {
+            (WriteMode::Insert, false) => "insert",
+            (WriteMode::Insert, true) => "checkedInsert",
+            (WriteMode::Upsert, false) => "upsert",
+            (WriteMode::Upsert, true) => "checkedUpsert",
+        }
+    }
+}
+
+/// Failure of a single batch
+#[derive(Debug, Serialize)]
+pub struct BatchFailure {
+    /// 1-based batch number
+    pub batch: usize,
+    /// Index of the first record in the batch (0-based)
+    pub first_record: usize,
+    pub records: usize,
+    pub error: String,
+}
+
+/// Result of an insert/upsert operation
+#[derive(Debug, Serialize)]
+pub struct InsertResult {
+    pub success: bool,
+    pub operation: String,
+    pub resource: String,
+    pub records_total: usize,
+    pub records_written: usize,
+    pub batches: usize,
+    pub failures: Vec<BatchFailure>,
+}
+
+/// Run insert or upsert
+pub async fn run(
+    mode: WriteMode,
+    args: &InsertArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+    verbose: bool,
+) -> Result<InsertResult> {
+    let operation = mode.command_name(args.checked);
+    info!(operation, resource = %args.resource, "Writing records");
+
+    let input = read_input(args.file.as_deref())?;
+    let records = parse_records(&input)?;
+    let chunk = args
+        .chunk
+        .unwrap_or(config.default_chunk_size as usize)
+        .max(1);
+
+    let profile = backend::resolve_profile(profile_name)?;
+    let options = backend::cli_options(profile.as_ref());
+    let cli = Executor::new(
+        UnderlyingCli::new(config.cli_path.clone())
+            .with_timeout(config.timeout_for(mode.command_name(false)))
+            .with_retry_policy(config.retry_policy()),
+        profile.as_ref(),
+    )?;
+
+    if verbose && !output_format.is_machine_readable() {
+        println!();
+        println!("{}", style(operation).bold().cyan());
+        println!("{}", style("─".repeat(50)).dim());
+        println!("  Resource: {}", style(&args.resource).yellow());
+        println!("  Records:  {}", records.len());
+        println!("  Chunk:    {}", chunk);
+        println!();
+    }
+
+    let progress = if !output_format.is_machine_readable() && progress::enabled() {
+        let pb = ProgressBar::new(records.len() as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} records ({msg})")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        Some(pb)
+    } else {
+        None
+    };
+
+    let mut failures = Vec::new();
+    let mut records_written = 0;
+    let batches: Vec<&[Value]> = records.chunks(chunk).collect();
+
+    for (i, batch) in batches.iter().enumerate() {
+        if let Some(ref pb) = progress {
+            pb.set_message(format!("batch {}/{}", i + 1, batches.len()));
+        }
+
+        let batch_file = write_batch_file(batch)?;
+        let path = batch_file.path().display().to_string();
+        let exec_result = match mode {
+            WriteMode::Insert => {
+                cli.insert(&options, &args.resource, &path, args.checked)
+                    .await
+            }
+            WriteMode::Upsert => {
+                cli.upsert(&options, &args.resource, &path, args.checked)
+                    .await
+            }
+        };
+
+        let error = match exec_result {
+            Ok(r) if r.success() => None,
+            Ok(r) => Some(if r.stderr.trim().is_empty() {
+                format!("exit code {}", r.code())
+            } else {
+                r.stderr.trim().to_string()
+            }),
+            Err(e) => Some(e.to_string()),
+        };
+
+        match error {
+            None => records_written += batch.len(),
+            Some(error) => {
+                warn!(batch = i + 1, %error, "Batch failed");
+                failures.push(BatchFailure {
+                    batch: i + 1,
+                    first_record: i * chunk,
+                    records: batch.len(),
+                    error,
+                });
+            }
+        }
+
+        if let Some(ref pb) = progress {
+            pb.inc(batch.len() as u64);
+        }
+    }
+
+    if let Some(ref pb) = progress {
+        pb.finish_and_clear();
+    }
+
+    let result = InsertResult {
+        success: failures.is_empty(),
+        operation: operation.to_string(),
+        resource: args.resource.clone(),
+        records_total: records.len(),
+        records_written,
+        batches: batches.len(),
+        failures,
+    };
+
+    display_result(&result, output_format)?;
+    Ok(result)
+}
+
+/// Read input from a file, or stdin when no file (or "-") is given
+fn read_input(file: Option<&std::path::Path>) -> Result<String> {
+    match file {
+        Some(path) if path.as_os_str() != "-" => {
+            std::fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+                path: path.display().to_string(),
+            })
+        }
+        _ => {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            Ok(input)
+        }
+    }
+}
+
+/// Parse records from a JSON array, a single object, or NDJSON
+fn parse_records(input: &str) -> Result<Vec<Value>> {
+    let mut records = Vec::new();
+
+    for (i, value) in serde_json::Deserializer::from_str(input)
+        .into_iter::<Value>()
+        .enumerate()
+    {
+        let value = value.map_err(|e| VqxError::InvalidJson {
+            message: format!("record {}: {}", i + 1, e),
+        })?;
+        match value {
+            Value::Array(items) => records.extend(items),
+            other => records.push(other),
+        }
+    }
+
+    if let Some(pos) = records.iter().position(|r| !r.is_object()) {
+        return Err(VqxError::InvalidJson {
+            message: format!("record {} is not a JSON object", pos + 1),
+        });
+    }
+
+    Ok(records)
+}
+
+/// Write a batch of records to a temp file as a JSON array
+fn write_batch_file(batch: &[Value]) -> Result<NamedTempFile> {
+    let mut file = tempfile::Builder::new().suffix(".json").tempfile()?;
+    serde_json::to_writer(&mut file, batch)?;
+    file.flush()?;
+    Ok(file)
+}
+
+/// Display the result
+fn display_result(result: &InsertResult, output_format: OutputFormat) -> Result<()> {
+    match output_format {
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => {
+            println!("{}", report::render(result, output_format)?);
+        }
+        OutputFormat::Text | OutputFormat::Csv => {
+            for failure in &result.failures {
+                eprintln!(
+                    "{} Batch {} (records {}-{}) failed: {}",
+                    style("✗").red(),
+                    failure.batch,
+                    failure.first_record + 1,
+                    failure.first_record + failure.records,
+                    failure.error
+                );
+            }
+
+            if result.success {
+                println!(
+                    "{} {} {} record(s) into {} in {} batch(es)",
+                    style("✓").green().bold(),
+                    result.operation,
+                    result.records_written,
+                    result.resource,
+                    result.batches
+                );
+            } else {
+                println!(
+                    "{} {}: {}/{} record(s) written, {} of {} batch(es) failed",
+                    style("✗").red().bold(),
+                    result.operation,
+                    result.records_written,
+                    result.records_total,
+                    result.failures.len(),
+                    result.batches
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_records_formats() {
+        assert_eq!(parse_records(r#"[{"a": 1}, {"a": 2}]"#).unwrap().len(), 2);
+        assert_eq!(
+            parse_records("{\"a\": 1}\n{\"a\": 2}\n\n{\"a\": 3}\n")
+                .unwrap()
+                .len(),
+            3
+        );
+        assert_eq!(parse_records(r#"{"a": 1}"#).unwrap().len(), 1);
+        assert!(parse_records("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_records_rejects_non_objects() {
+        assert!(parse_records("[1, 2]").is_err());
+        assert!(parse_records("{\"a\": 1}\n{oops").is_err());
+    }
+
+    #[test]
+    fn test_command_name() {
+        assert_eq!(WriteMode::Insert.command_name(false), "insert");
+        assert_eq!(WriteMode::Upsert.command_name(true), "checkedUpsert");
+    }
+}
@@ -0,0 +1,407 @@
+//! List command implementation
+//!
+//! Wraps the underlying CLI's list command and turns its output into
+//! structured rows that can be filtered, sorted and rendered as a table,
+//! JSON or CSV.
+//!
+//! Based on: CLI Reference Guide PDF - "List" section
+//!
+//! PDF: "The list command lists all instances of a given resource"
+//!
+//! The underlying CLI prints either a JSON document or one resource per
+//! line depending on the resource type; both forms are accepted here.
+
+use crate::backend::{self, Executor};
+use crate::cli::{ListArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::report;
+use crate::theme::style;
+use crate::underlying::UnderlyingCli;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use tracing::info;
+
+/// Column used for name filtering and default sorting
+const NAME_COLUMN: &str = "name";
+
+/// Result of a list operation
+#[derive(Debug, Serialize)]
+pub struct ListResult {
+    pub success: bool,
+    pub resource: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run the list command
+pub async fn run(
+    args: &ListArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+    _verbose: bool,
+) -> Result<ListResult> {
+    info!(resource = %args.resource, "Listing resources");
+
+    let profile = backend::resolve_profile(profile_name)?;
+    let options = backend::cli_options(profile.as_ref());
+    let cli = Executor::new(
+        UnderlyingCli::new(config.cli_path.clone())
+            .with_timeout(config.timeout_for("list"))
+            .with_retry_policy(config.retry_policy()),
+        profile.as_ref(),
+    )?;
+    let exec_result = cli.list(&options, &args.resource).await?;
+
+    if !exec_result.success() {
+        let result = ListResult {
+            success: false,
+            resource: args.resource.clone(),
+            columns: vec![],
+            rows: vec![],
+            error: Some(exec_result.stderr.clone()),
+        };
+        display_result(&result, output_format);
+        return Ok(result);
+    }
+
+    let mut rows = parse_list_output(&exec_result.stdout);
+
+    if let Some(ref pattern) = args.name {
+        let matcher = glob_to_regex(pattern)?;
+        rows.retain(|row| {
+            row.get(NAME_COLUMN)
+                .map(|v| matcher.is_match(&value_to_string(v)))
+                .unwrap_or(false)
+        });
+    }
+
+    let sort_key = args.sort.as_deref().unwrap_or(NAME_COLUMN);
+    sort_rows(&mut rows, sort_key, args.desc);
+
+    let columns = if args.columns.is_empty() {
+        infer_columns(&rows)
+    } else {
+        args.columns.clone()
+    };
+    let rows = project_rows(rows, &columns);
+
+    let result = ListResult {
+        success: true,
+        resource: args.resource.clone(),
+        columns,
+        rows,
+        error: None,
+    };
+
+    display_result(&result, output_format);
+    Ok(result)
+}
+
+/// Parse the underlying CLI output into rows
+///
+/// JSON arrays of objects are used as-is, arrays of strings and plain text
+/// lines become single-column rows keyed by `name`.
+pub(crate) fn parse_list_output(stdout: &str) -> Vec<Map<String, Value>> {
+    if let Ok(value) = serde_json::from_str::<Value>(stdout.trim()) {
+        let items = match value {
+            Value::Array(items) => items,
+            other => vec![other],
+        };
+        return items.into_iter().map(value_to_row).collect();
+    }
+
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut row = Map::new();
+            row.insert(NAME_COLUMN.to_string(), Value::String(line.to_string()));
+            row
+        })
+        .collect()
+}
+
+/// Convert a JSON value into a row
+fn value_to_row(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        other => {
+            let mut row = Map::new();
+            row.insert(NAME_COLUMN.to_string(), other);
+            row
+        }
+    }
+}
+
+/// Determine the columns to show when none were requested
+///
+/// `name` comes first, remaining keys follow in alphabetical order.
+pub(crate) fn infer_columns(rows: &[Map<String, Value>]) -> Vec<String> {
+    let mut keys: Vec<String> = rows
+        .iter()
+        .flat_map(|row| row.keys().cloned())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    if let Some(pos) = keys.iter().position(|k| k == NAME_COLUMN) {
+        let name = keys.remove(pos);
+        keys.insert(0, name);
+    }
+
+    if keys.is_empty() {
+        keys.push(NAME_COLUMN.to_string());
+    }
+
+    keys
+}
+
+/// Keep only the selected columns in each row (missing values become null)
+fn project_rows(rows: Vec<Map<String, Value>>, columns: &[String]) -> Vec<Map<String, Value>> {
+    rows.into_iter()
+        .map(|row| {
+            columns
+                .iter()
+                .map(|c| (c.clone(), row.get(c).cloned().unwrap_or(Value::Null)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Sort rows by a column; numbers compare numerically, everything else as text
+fn sort_rows(rows: &mut [Map<String, Value>], key: &str, descending: bool) {
+    rows.sort_by(|a, b| {
+        let ordering = compare_values(a.get(key), b.get(key));
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn compare_values(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(Value::Number(x)), Some(Value::Number(y))) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(x), Some(y)) => value_to_string(x).cmp(&value_to_string(y)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Render a JSON value as a plain cell string
+pub(crate) fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Compile a shell-style glob (`*`, `?`) into an anchored regex
+pub(crate) fn glob_to_regex(pattern: &str) -> Result<Regex> {
+    let mut re = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            other => re.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+    re.push('$');
+
+    Regex::new(&re).map_err(|e| VqxError::Other(format!("Invalid pattern '{}': {}", pattern, e)))
+}
+
+/// Quote a CSV field when needed (RFC 4180)
+pub(crate) fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escape tabs, line breaks and backslashes (`\t`, `\n`, `\r`, `\\`) so a
+/// porcelain row stays on one line
+fn porcelain_escape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Display the list result
+fn display_result(result: &ListResult, output_format: OutputFormat) {
+    if !result.success {
+        match output_format {
+            OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Ndjson => {
+                if let Ok(out) = report::serialize(result, output_format) {
+                    println!("{}", out);
+                }
+            }
+            OutputFormat::Text | OutputFormat::Csv | OutputFormat::Porcelain => {
+                eprintln!(
+                    "{} Failed to list {}",
+                    style("✗").red().bold(),
+                    result.resource
+                );
+                if let Some(ref err) = result.error {
+                    if !err.is_empty() {
+                        eprintln!("{}", style(err).red());
+                    }
+                }
+            }
+        }
+        return;
+    }
+
+    match output_format {
+        OutputFormat::Json | OutputFormat::Yaml => {
+            if let Ok(out) = report::serialize(&result.rows, output_format) {
+                println!("{}", out);
+            }
+        }
+        OutputFormat::Ndjson => {
+            if let Ok(out) = report::to_ndjson(&result.rows) {
+                print!("{}", out);
+            }
+        }
+        OutputFormat::Porcelain => {
+            for row in &result.rows {
+                let fields: Vec<String> = result
+                    .columns
+                    .iter()
+                    .map(|c| porcelain_escape(&row.get(c).map(value_to_string).unwrap_or_default()))
+                    .collect();
+                println!("{}", fields.join("\t"));
+            }
+        }
+        OutputFormat::Csv => {
+            let header: Vec<String> = result.columns.iter().map(|c| csv_escape(c)).collect();
+            println!("{}", header.join(","));
+            for row in &result.rows {
+                let fields: Vec<String> = result
+                    .columns
+                    .iter()
+                    .map(|c| csv_escape(&row.get(c).map(value_to_string).unwrap_or_default()))
+                    .collect();
+                println!("{}", fields.join(","));
+            }
+        }
+        OutputFormat::Text => {
+            let cells: Vec<Vec<String>> = result
+                .rows
+                .iter()
+                .map(|row| {
+                    result
+                        .columns
+                        .iter()
+                        .map(|c| row.get(c).map(value_to_string).unwrap_or_default())
+                        .collect()
+                })
+                .collect();
+
+            let widths: Vec<usize> = result
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    cells
+                        .iter()
+                        .map(|r| r[i].chars().count())
+                        .chain(std::iter::once(c.chars().count()))
+                        .max()
+                        .unwrap_or(0)
+                })
+                .collect();
+
+            let header: Vec<String> = result
+                .columns
+                .iter()
+                .zip(&widths)
+                .map(|(c, w)| format!("{:<w$}", c, w = *w))
+                .collect();
+            println!("{}", style(header.join("  ").trim_end()).bold());
+            println!(
+                "{}",
+                style(
+                    "─".repeat(widths.iter().sum::<usize>() + 2 * widths.len().saturating_sub(1))
+                )
+                .dim()
+            );
+            for row in &cells {
+                let line: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(v, w)| format!("{:<w$}", v, w = *w))
+                    .collect();
+                println!("{}", line.join("  ").trim_end());
+            }
+            println!();
+            println!(
+                "{}",
+                style(format!("{} {}", result.rows.len(), result.resource)).dim()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_output_lines() {
+        let rows = parse_list_output("TypeB\n\nTypeA\n");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["name"], "TypeB");
+    }
+
+    #[test]
+    fn test_parse_list_output_json() {
+        let rows = parse_list_output(r#"[{"name": "p1", "ars_version": 2}, "p2"]"#);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["ars_version"], 2);
+        assert_eq!(rows[1]["name"], "p2");
+        assert_eq!(infer_columns(&rows), vec!["name", "ars_version"]);
+    }
+
+    #[test]
+    fn test_sort_and_filter() {
+        let mut rows = parse_list_output("beta\nalpha\nAlphaX\n");
+        sort_rows(&mut rows, "name", true);
+        assert_eq!(rows[0]["name"], "beta");
+
+        let matcher = glob_to_regex("al*").unwrap();
+        assert!(matcher.is_match("alpha"));
+        assert!(!matcher.is_match("AlphaX"));
+        assert!(glob_to_regex("a.?").unwrap().is_match("a.b"));
+        assert!(!glob_to_regex("a.?").unwrap().is_match("abb"));
+    }
+
+    #[test]
+    fn test_csv_escape() {
+        assert_eq!(csv_escape("plain"), "plain");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,194 @@
+//! Login command implementation (vqx extension)
+//!
+//! Edge servers take username/password, which the CLI otherwise receives as
+//! `-p` on every invocation. `vqx login` exchanges them once for an access
+//! token through the REST API (`rest-backend` feature) and stores it with
+//! its expiry next to the profile's other secrets: in the keyring for
+//! `--secure` profiles, otherwise in profiles.toml. Until it expires,
+//! [`ProfileManager::get_resolved`] passes the token instead of the
+//! username/password; afterwards the stored credentials are used again.
+
+use crate::cli::{LoginArgs, LogoutArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::profile::{Profile, ProfileManager};
+use crate::prompt;
+use crate::report;
+use crate::theme::style;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Result of login
+#[derive(Debug, Serialize)]
+pub struct LoginResult {
+    pub success: bool,
+    pub profile: String,
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Result of logout
+#[derive(Debug, Serialize)]
+pub struct LogoutResult {
+    pub success: bool,
+    pub profile: String,
+    /// A session was discarded
+    pub logged_out: bool,
+}
+
+/// Run the login command
+pub async fn login(
+    args: &LoginArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<LoginResult> {
+    let mut manager = ProfileManager::new()?;
+    let name = args
+        .name
+        .as_deref()
+        .or(profile_name)
+        .unwrap_or(&manager.store().default_profile)
+        .to_string();
+    let mut credentials = manager.get_resolved_stored(&name)?;
+
+    if credentials.vantiq_profile.is_some() {
+        return Err(VqxError::Other(format!(
+            "Profile '{}' is linked to a Vantiq CLI profile, which handles authentication",
+            name
+        )));
+    }
+    if credentials.namespace.is_some() {
+        // PDF: the namespace option can only be used with username/password
+        return Err(VqxError::Other(format!(
+            "Profile '{}' selects a namespace, which needs username/password on every call",
+            name
+        )));
+    }
+    let Some(username) = credentials.username.clone() else {
+        return Err(VqxError::Other(format!(
+            "Profile '{}' has no username; login is for username/password profiles (Edge servers)",
+            name
+        )));
+    };
+    if credentials.password.is_none() {
+        credentials.password = Some(read_password(args, &username)?);
+    }
+
+    let (token, expires_at) = authenticate(&credentials, config).await?;
+
+    let mut profile = manager.store().get(&name)?.clone();
+    profile.session_expires_at = Some(expires_at);
+    if profile.use_secure_storage {
+        manager.set_secret(&name, "session_token", &token)?;
+        profile.session_token = None;
+    } else {
+        profile.session_token = Some(token);
+    }
+    manager.store_mut().set(&name, profile);
+    manager.save()?;
+
+    let result = LoginResult {
+        success: true,
+        profile: name,
+        url: credentials.url,
+        expires_at,
+    };
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        println!(
+            "{} Logged in to {} as {}; the token is used until {}.",
+            style("✓").green(),
+            result.url,
+            style(&username).bold(),
+            result
+                .expires_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+        );
+    }
+    Ok(result)
+}
+
+/// Run the logout command
+pub fn logout(
+    args: &LogoutArgs,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<LogoutResult> {
+    let mut manager = ProfileManager::new()?;
+    let name = args
+        .name
+        .as_deref()
+        .or(profile_name)
+        .unwrap_or(&manager.store().default_profile)
+        .to_string();
+
+    let mut profile = manager.store().get(&name)?.clone();
+    let logged_out = profile.session_expires_at.is_some();
+    if logged_out {
+        if profile.use_secure_storage {
+            manager.delete_secret(&name, "session_token")?;
+        }
+        profile.session_token = None;
+        profile.session_expires_at = None;
+        manager.store_mut().set(&name, profile);
+        manager.save()?;
+    }
+
+    let result = LogoutResult {
+        success: true,
+        profile: name,
+        logged_out,
+    };
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else if logged_out {
+        println!(
+            "{} Logged out of '{}'.",
+            style("✓").green(),
+            style(&result.profile).bold()
+        );
+    } else {
+        println!("'{}' is not logged in.", result.profile);
+    }
+    Ok(result)
+}
+
+fn read_password(args: &LoginArgs, username: &str) -> Result<String> {
+    let password = if args.password_stdin {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line).map_err(|e| {
+            VqxError::Other(format!("Failed to read the password from stdin: {}", e))
+        })?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        prompt::password(
+            format!("Password for {}", username),
+            "Login",
+            "pipe the password with --password-stdin",
+        )?
+    };
+    if password.is_empty() {
+        return Err(VqxError::Other("The password is empty".to_string()));
+    }
+    Ok(password)
+}
+
+/// Access token and expiry for the profile's username/password
+#[cfg(feature = "rest-backend")]
+async fn authenticate(profile: &Profile, config: &Config) -> Result<(String, DateTime<Utc>)> {
+    let client = crate::rest::RestClient::from_profile(profile, config.timeout_for("login"))?
+        .ok_or_else(|| VqxError::Other("The profile cannot be used over REST".to_string()))?;
+    let session = client.authenticate().await?;
+    Ok((session.token, session.expires_at))
+}
+
+#[cfg(not(feature = "rest-backend"))]
+async fn authenticate(_profile: &Profile, _config: &Config) -> Result<(String, DateTime<Utc>)> {
+    Err(VqxError::Other(
+        "vqx login talks to the REST API; rebuild with `cargo install --path . --features rest-backend`"
+            .to_string(),
+    ))
+}
@@ -3,11 +3,19 @@
 //! Each submodule implements a vqx subcommand.
 
 // Phase 1: Core utilities
+pub mod config;
+pub mod credential;
 pub mod doctor;
 pub mod external;
+pub mod init;
+pub mod insert;
+pub mod list;
+pub mod login;
 pub mod profile;
+pub mod select;
 
 // Phase 2: Export/Import
+pub mod dump;
 pub mod export;
 pub mod import;
 
@@ -16,6 +24,31 @@ pub mod diff;
 pub mod sync;
 
 // Phase 4: Safe operations
+pub mod approve;
+pub mod ci;
+pub mod deploy;
 pub mod promote;
+pub mod rollback;
 pub mod run;
 pub mod safe_delete;
+pub mod trash;
+
+// Tooling
+pub mod docs;
+pub mod explain;
+pub mod grep;
+pub mod new;
+pub mod normalize;
+pub mod open;
+pub mod patch;
+pub mod rename;
+pub mod scan;
+pub mod schedule;
+pub mod schema;
+pub mod serve;
+pub mod show;
+pub mod stats;
+pub mod telemetry;
+pub mod template;
+pub mod triage;
+pub mod validate;
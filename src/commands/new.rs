@@ -0,0 +1,245 @@
+//! New command implementation
+//!
+//! Writes the definition of a new type, procedure, rule or service into an
+//! export directory, normalized the way `export` writes it (with
+//! `normalization.extract_code`, procedure and rule code goes into a
+//! `.vail` stub). The resource can then be edited locally and sent with
+//! `import` or `sync push` instead of being created in the IDE and pulled.
+
+use crate::cli::{NewArgs, NewKind, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::normalizer::ResourceNormalizer;
+use crate::report;
+use crate::theme::style;
+use crate::validation::PROPERTY_TYPES;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Result of the new command
+#[derive(Debug, Serialize)]
+pub struct NewResult {
+    pub success: bool,
+    pub resource_type: &'static str,
+    pub name: String,
+    pub files: Vec<PathBuf>,
+}
+
+/// Export directory of each kind
+fn resource_type(kind: NewKind) -> &'static str {
+    match kind {
+        NewKind::Type => "types",
+        NewKind::Procedure => "procedures",
+        NewKind::Rule => "rules",
+        NewKind::Service => "services",
+    }
+}
+
+/// Run the new command
+pub fn run(args: &NewArgs, config: &Config, output_format: OutputFormat) -> Result<NewResult> {
+    check_name(&args.name)?;
+    if !args.properties.is_empty() && args.kind != NewKind::Type {
+        return Err(VqxError::Other(
+            "--property only applies to types".to_string(),
+        ));
+    }
+    if args.on.is_some() && args.kind != NewKind::Rule {
+        return Err(VqxError::Other("--on only applies to rules".to_string()));
+    }
+
+    let value = definition(args)?;
+    let resource_type = resource_type(args.kind);
+    let directory = args.directory.clone().unwrap_or_else(|| PathBuf::from("."));
+    let dir = directory.join(resource_type);
+    let path = dir.join(format!("{}.json", args.name));
+
+    let normalizer = ResourceNormalizer::new(config.normalization.clone());
+    let writes = normalizer.plan_resource(&path, resource_type, &value)?;
+    if !args.force {
+        if let Some(existing) = writes.iter().find(|w| w.before.is_some()) {
+            return Err(VqxError::Other(format!(
+                "{} already exists (pass --force to overwrite)",
+                existing.path.display()
+            )));
+        }
+    }
+
+    fs::create_dir_all(&dir).map_err(|_| VqxError::FileWriteFailed {
+        path: dir.display().to_string(),
+    })?;
+    for write in &writes {
+        write.apply()?;
+    }
+
+    let result = NewResult {
+        success: true,
+        resource_type,
+        name: args.name.clone(),
+        files: writes.into_iter().map(|w| w.path).collect(),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display(&result, &directory);
+    }
+    Ok(result)
+}
+
+/// Names are identifiers, optionally qualified with dots (`Orders.total`)
+pub(crate) fn check_name(name: &str) -> Result<()> {
+    let re = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*(\.[A-Za-z_][A-Za-z0-9_]*)*$").unwrap();
+    if re.is_match(name) {
+        Ok(())
+    } else {
+        Err(VqxError::Other(format!(
+            "Invalid resource name '{}': use letters, digits and _ (dots separate a package or service)",
+            name
+        )))
+    }
+}
+
+/// Definition of the new resource
+fn definition(args: &NewArgs) -> Result<Value> {
+    let name = &args.name;
+    Ok(match args.kind {
+        NewKind::Type => {
+            let mut properties = Map::new();
+            for property in &args.properties {
+                let (prop, kind) = parse_property(property)?;
+                properties.insert(prop.to_string(), json!({ "type": kind }));
+            }
+            json!({ "name": name, "properties": properties })
+        }
+        NewKind::Procedure => json!({
+            "name": name,
+            "ruleText": format!("PROCEDURE {}()\n\n// TODO: implement\n", name),
+        }),
+        NewKind::Rule => json!({
+            "name": name,
+            "active": true,
+            "ruleText": format!(
+                "RULE {}\nWHEN EVENT OCCURS ON \"{}\" AS event\n\n// TODO: implement\n",
+                name,
+                args.on.as_deref().unwrap_or_default()
+            ),
+        }),
+        NewKind::Service => json!({ "name": name, "interface": [] }),
+    })
+}
+
+/// Parse `NAME:TYPE`, matching the type case-insensitively
+fn parse_property(property: &str) -> Result<(&str, &'static str)> {
+    let invalid =
+        |message: String| VqxError::Other(format!("--property {}: {}", property, message));
+    let (name, kind) = property
+        .split_once(':')
+        .ok_or_else(|| invalid("expected NAME:TYPE".to_string()))?;
+    check_name(name)?;
+    let kind = PROPERTY_TYPES
+        .iter()
+        .find(|t| t.eq_ignore_ascii_case(kind.trim()))
+        .ok_or_else(|| invalid(format!("type must be one of {}", PROPERTY_TYPES.join(", "))))?;
+    Ok((name, kind))
+}
+
+fn display(result: &NewResult, directory: &Path) {
+    println!(
+        "{} Created {} {}",
+        style("✓").green(),
+        result.resource_type.trim_end_matches('s'),
+        style(&result.name).bold()
+    );
+    for file in &result.files {
+        println!("  {}", file.display());
+    }
+    println!(
+        "{}",
+        style(format!(
+            "Send it with `vqx import -d {}` or `vqx sync push -d {}`",
+            directory.display(),
+            directory.display()
+        ))
+        .dim()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    fn new_args(command: &[&str]) -> NewArgs {
+        match crate::cli::Cli::parse_from(command).command {
+            crate::cli::Commands::New(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_new_type() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path().to_str().unwrap();
+        let args = new_args(&[
+            "vqx",
+            "new",
+            "type",
+            "Order",
+            "-d",
+            d,
+            "-p",
+            "total:real",
+            "-p",
+            "id:String",
+        ]);
+        let result = run(&args, &Config::default(), OutputFormat::Json).unwrap();
+        assert_eq!(result.files, vec![dir.path().join("types/Order.json")]);
+
+        let value: Value =
+            serde_json::from_str(&fs::read_to_string(&result.files[0]).unwrap()).unwrap();
+        assert_eq!(value["properties"]["total"]["type"], "Real");
+        assert_eq!(value["properties"]["id"]["type"], "String");
+
+        // Never overwritten without --force
+        let err = run(&args, &Config::default(), OutputFormat::Json).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+
+    #[test]
+    fn test_new_rule_with_extracted_code() {
+        let dir = TempDir::new().unwrap();
+        let d = dir.path().to_str().unwrap();
+        let mut config = Config::default();
+        config.normalization.extract_code = true;
+        let args = new_args(&[
+            "vqx",
+            "new",
+            "rule",
+            "OnOrder",
+            "-d",
+            d,
+            "--on",
+            "/types/Order/insert",
+        ]);
+        let result = run(&args, &config, OutputFormat::Json).unwrap();
+        assert_eq!(result.files.len(), 2);
+
+        let code = fs::read_to_string(dir.path().join("rules/OnOrder.vail")).unwrap();
+        assert!(code.starts_with("RULE OnOrder\nWHEN EVENT OCCURS ON \"/types/Order/insert\""));
+        let json = fs::read_to_string(dir.path().join("rules/OnOrder.json")).unwrap();
+        assert!(json.contains("\"$vail\": \"OnOrder.vail\""));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(check_name("Orders.total").is_ok());
+        assert!(check_name("1Order").is_err());
+        assert!(check_name("../Order").is_err());
+        assert!(parse_property("total:Float").is_err());
+        assert!(parse_property("total").is_err());
+    }
+}
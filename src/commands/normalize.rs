@@ -0,0 +1,167 @@
+//! Normalize command implementation
+//!
+//! Re-normalizes an export directory in place with the `[normalization]`
+//! settings, e.g. after changing `excluded_fields` or hand-editing files.
+//!
+//! With `--dry-run` nothing is written: the command lists the files that
+//! would change (and their diffs with `--full`) so it is safe to run in a
+//! working tree with uncommitted changes.
+
+use crate::cli::{NormalizeArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::normalizer::{NormalizationStats, PlannedWrite, ResourceNormalizer};
+use crate::report;
+use crate::theme::style;
+use similar::TextDiff;
+use std::path::{Path, PathBuf};
+
+/// Normalize operation result
+#[derive(Debug)]
+pub struct NormalizeResult {
+    pub success: bool,
+    pub directory: PathBuf,
+    pub files_processed: usize,
+    /// Files that were (or, with --dry-run, would be) changed
+    pub changed: Vec<PathBuf>,
+    pub errors: Vec<String>,
+}
+
+/// Run normalize command
+pub fn run(
+    args: &NormalizeArgs,
+    config: &Config,
+    output_format: OutputFormat,
+) -> Result<NormalizeResult> {
+    let dir = &args.directory;
+    if !dir.is_dir() {
+        return Err(VqxError::Other(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+
+    let normalizer = ResourceNormalizer::new(config.normalization.clone());
+
+    // The preview also tells a real run which files it changed
+    let (preview_stats, planned) = normalizer.preview_export_directory(dir)?;
+    let stats = if args.dry_run {
+        preview_stats
+    } else {
+        normalizer.normalize_export_directory(dir)?
+    };
+
+    let result = NormalizeResult {
+        success: stats.errors == 0,
+        directory: dir.clone(),
+        files_processed: stats.files_processed,
+        changed: planned.iter().map(|w| w.path.clone()).collect(),
+        errors: error_messages(&stats),
+    };
+
+    if output_format.is_machine_readable() {
+        let json_result = serde_json::json!({
+            "success": result.success,
+            "directory": dir.display().to_string(),
+            "dry_run": args.dry_run,
+            "files_processed": result.files_processed,
+            "changed": result
+                .changed
+                .iter()
+                .map(|p| relative(dir, p))
+                .collect::<Vec<_>>(),
+            "errors": result.errors,
+        });
+        println!("{}", report::render(&json_result, output_format)?);
+    } else {
+        display_result(args, &result, &planned);
+    }
+
+    Ok(result)
+}
+
+fn error_messages(stats: &NormalizationStats) -> Vec<String> {
+    stats
+        .error_files
+        .iter()
+        .map(|(file, err)| format!("{}: {}", file, err))
+        .collect()
+}
+
+/// Path of a file relative to the normalized directory
+fn relative(dir: &Path, path: &Path) -> String {
+    path.strip_prefix(dir).unwrap_or(path).display().to_string()
+}
+
+/// Display the normalize result to the terminal
+fn display_result(args: &NormalizeArgs, result: &NormalizeResult, planned: &[PlannedWrite]) {
+    println!();
+    println!("{}", style("Normalize").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Directory: {}", result.directory.display());
+    if args.dry_run {
+        println!(
+            "  Mode:      {}",
+            style("dry run (nothing is written)").yellow()
+        );
+    }
+    println!();
+
+    for write in planned {
+        let marker = if write.before.is_some() {
+            style("~").yellow()
+        } else {
+            style("+").green()
+        };
+        println!(
+            "    {} {}",
+            marker,
+            relative(&result.directory, &write.path)
+        );
+
+        if args.full {
+            let before = write.before.as_deref().unwrap_or("");
+            let diff = TextDiff::from_lines(before, write.after.as_str());
+            for line in diff.unified_diff().context_radius(3).to_string().lines() {
+                let colored_line = if line.starts_with('+') {
+                    style(line).green().to_string()
+                } else if line.starts_with('-') {
+                    style(line).red().to_string()
+                } else if line.starts_with("@@") {
+                    style(line).cyan().to_string()
+                } else {
+                    line.to_string()
+                };
+                println!("        {}", colored_line);
+            }
+        }
+    }
+    if !planned.is_empty() {
+        println!();
+    }
+
+    let verb = if args.dry_run {
+        "would change"
+    } else {
+        "changed"
+    };
+    println!(
+        "{} {} of {} files {}",
+        style("✓").green(),
+        result.changed.len(),
+        result.files_processed,
+        verb
+    );
+
+    if !result.errors.is_empty() {
+        println!(
+            "{} {} files had errors during normalization",
+            style("⚠").yellow(),
+            result.errors.len()
+        );
+        for error in &result.errors {
+            println!("    {} {}", style("•").dim(), error);
+        }
+    }
+    println!();
+}
@@ -0,0 +1,170 @@
+//! Open command implementation
+//!
+//! Opens a resource in the Vantiq web IDE. The URL is built from the
+//! profile's base URL with `[ide] url_template`; the profile's credentials
+//! are not needed. The browser is `$BROWSER` when set, otherwise the
+//! platform's opener (`open`, `start` or `xdg-open`). Without a display, or
+//! with `--print`, the URL is printed instead.
+
+use crate::cli::{OpenArgs, OutputFormat};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::profile::{Profile, ProfileManager};
+use crate::report;
+use crate::theme::style;
+use serde::Serialize;
+use std::process::{Command, Stdio};
+
+/// Result of open
+#[derive(Debug, Serialize)]
+pub struct OpenResult {
+    pub success: bool,
+    pub profile: String,
+    pub url: String,
+    /// A browser was started
+    pub opened: bool,
+}
+
+/// Run the open command
+pub fn run(
+    args: &OpenArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<OpenResult> {
+    let manager = ProfileManager::new()?;
+    let profile_name = profile_name
+        .unwrap_or(&manager.store().default_profile)
+        .to_string();
+    let profile = manager.store().get(&profile_name)?;
+    let url = ide_url(
+        &config.ide.url_template,
+        profile,
+        &args.resource,
+        &args.name,
+    );
+
+    let opened = !args.print && !output_format.is_machine_readable() && has_display();
+    if opened {
+        launch(&url)?;
+    }
+
+    let result = OpenResult {
+        success: true,
+        profile: profile_name,
+        url,
+        opened,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else if result.opened {
+        println!(
+            "{} Opened {}/{} in the browser: {}",
+            style("✓").green(),
+            args.resource,
+            style(&args.name).bold(),
+            result.url
+        );
+    } else {
+        println!("{}", result.url);
+    }
+    Ok(result)
+}
+
+/// IDE URL of a resource
+fn ide_url(template: &str, profile: &Profile, resource: &str, name: &str) -> String {
+    template
+        .replace("{url}", profile.url.trim_end_matches('/'))
+        .replace(
+            "{namespace}",
+            &encode(profile.namespace.as_deref().unwrap_or_default()),
+        )
+        .replace("{resource}", &encode(resource))
+        .replace("{name}", &encode(name))
+}
+
+/// Percent-encode everything but unreserved characters
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Whether a browser can be shown
+fn has_display() -> bool {
+    if std::env::var_os("BROWSER").is_some() || cfg!(any(windows, target_os = "macos")) {
+        return true;
+    }
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|v| std::env::var_os(v).is_some_and(|v| !v.is_empty()))
+}
+
+fn launch(url: &str) -> Result<()> {
+    let browser = std::env::var("BROWSER")
+        .ok()
+        .filter(|b| !b.trim().is_empty());
+    let mut command = match browser {
+        Some(ref browser) => {
+            let mut words = browser.split_whitespace();
+            let mut command = Command::new(words.next().unwrap_or_default());
+            command.args(words);
+            command
+        }
+        None if cfg!(windows) => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        None if cfg!(target_os = "macos") => Command::new("open"),
+        None => Command::new("xdg-open"),
+    };
+    let program = command.get_program().to_string_lossy().to_string();
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+        .map_err(|e| {
+            VqxError::Other(format!(
+                "Cannot start browser '{}': {}; use --print to show the URL",
+                program, e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::IdeConfig;
+
+    #[test]
+    fn test_ide_url() {
+        let mut profile = Profile::new("https://dev.vantiq.com/");
+        profile.namespace = Some("my ns".to_string());
+
+        let template = IdeConfig::default().url_template;
+        assert_eq!(
+            ide_url(&template, &profile, "procedures", "Order.Create"),
+            "https://dev.vantiq.com/ui/ide/index.html#/procedures/Order.Create"
+        );
+        assert_eq!(
+            ide_url(
+                "{url}/ide?ns={namespace}#{resource}/{name}",
+                &profile,
+                "types",
+                "A&B"
+            ),
+            "https://dev.vantiq.com/ide?ns=my%20ns#types/A%26B"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Patch command implementation
+//!
+//! Applies unified diffs written by `vqx diff --patch` to an export
+//! directory, so changes can be reviewed and exchanged as plain patch files
+//! without a shared git repository. See [`crate::patch`] for the format and
+//! conflict detection.
+
+use crate::cli::{OutputFormat, PatchApplyArgs, PatchCommands};
+use crate::error::{Result, VqxError};
+use crate::patch::{self, Conflict};
+use crate::report;
+use crate::theme::style;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// A file the patch changes
+#[derive(Debug, Serialize)]
+pub struct PatchedFile {
+    /// Path relative to the patched directory
+    pub file: String,
+    /// `created`, `modified` or `deleted`
+    pub action: &'static str,
+}
+
+/// A file the patch cannot be applied to
+#[derive(Debug, Serialize)]
+pub struct PatchConflict {
+    pub file: String,
+    pub message: String,
+}
+
+impl From<Conflict> for PatchConflict {
+    fn from(c: Conflict) -> Self {
+        Self {
+            file: c.path,
+            message: c.message,
+        }
+    }
+}
+
+/// Result of patch apply
+#[derive(Debug, Serialize)]
+pub struct PatchResult {
+    pub success: bool,
+    pub patch: PathBuf,
+    pub directory: PathBuf,
+    pub dry_run: bool,
+    /// Files changed (with --dry-run: that would be changed)
+    pub files: Vec<PatchedFile>,
+    /// Files already in the patched state
+    pub already_applied: usize,
+    pub conflicts: Vec<PatchConflict>,
+}
+
+/// Run patch subcommand
+pub fn run(cmd: &PatchCommands, output_format: OutputFormat) -> Result<PatchResult> {
+    match cmd {
+        PatchCommands::Apply(args) => apply(args, output_format),
+    }
+}
+
+/// Apply a patch file to a directory
+fn apply(args: &PatchApplyArgs, output_format: OutputFormat) -> Result<PatchResult> {
+    let dir = &args.directory;
+    if !dir.is_dir() {
+        return Err(VqxError::Other(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+    let content = std::fs::read_to_string(&args.patch).map_err(|_| VqxError::FileReadFailed {
+        path: args.patch.display().to_string(),
+    })?;
+
+    let files = patch::parse(&content)?;
+    let (changes, conflicts) = match patch::apply(dir, &files) {
+        Ok(changes) => (changes, Vec::new()),
+        Err(conflicts) => (Default::default(), conflicts),
+    };
+
+    let patched: Vec<PatchedFile> = files
+        .iter()
+        .filter(|f| conflicts.is_empty() && changes.contains_key(&f.path))
+        .map(|f| PatchedFile {
+            file: f.path.clone(),
+            action: if f.created {
+                "created"
+            } else if f.deleted {
+                "deleted"
+            } else {
+                "modified"
+            },
+        })
+        .collect();
+
+    if conflicts.is_empty() && !args.dry_run {
+        patch::write_changes(dir, &changes)?;
+    }
+
+    let result = PatchResult {
+        success: conflicts.is_empty(),
+        patch: args.patch.clone(),
+        directory: dir.clone(),
+        dry_run: args.dry_run,
+        already_applied: if conflicts.is_empty() {
+            files.len() - patched.len()
+        } else {
+            0
+        },
+        files: patched,
+        conflicts: conflicts.into_iter().map(PatchConflict::from).collect(),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display_result(&result);
+    }
+
+    Ok(result)
+}
+
+/// Display the patch result to the terminal
+fn display_result(result: &PatchResult) {
+    println!();
+    println!("{}", style("Patch").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Patch:     {}", result.patch.display());
+    println!("  Directory: {}", result.directory.display());
+    println!();
+
+    if !result.success {
+        for c in &result.conflicts {
+            println!("{} {}: {}", style("✗").red(), c.file, c.message);
+        }
+        println!();
+        println!(
+            "{} {} conflict(s); no files were changed",
+            style("✗").red(),
+            result.conflicts.len()
+        );
+        println!();
+        return;
+    }
+
+    for f in &result.files {
+        let marker = match f.action {
+            "created" => style("+").green(),
+            "deleted" => style("-").red(),
+            _ => style("~").yellow(),
+        };
+        println!("  {} {}", marker, f.file);
+    }
+    if !result.files.is_empty() {
+        println!();
+    }
+    if result.already_applied > 0 {
+        println!(
+            "  {}",
+            style(format!(
+                "{} file(s) already up to date",
+                result.already_applied
+            ))
+            .dim()
+        );
+    }
+
+    let verb = if result.dry_run {
+        "would be changed (dry run)"
+    } else {
+        "changed"
+    };
+    println!(
+        "{} {} file(s) {}",
+        style("✓").green(),
+        result.files.len(),
+        verb
+    );
+    println!();
+}
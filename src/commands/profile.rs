@@ -8,19 +8,37 @@
 //! - "Command Line Options" section: -s, -b, -u, -p, -t, -n, -trust
 
 use crate::cli::{
-    OutputFormat, ProfileCommands, ProfileDefaultArgs, ProfileDeleteArgs, ProfileExportArgs,
-    ProfileImportArgs, ProfileInitArgs, ProfileSetArgs, ProfileShowArgs,
+    OutputFormat, ProfileCommands, ProfileDefaultArgs, ProfileDeleteArgs, ProfileEncryptArgs,
+    ProfileExportArgs, ProfileFileFormat, ProfileImportArgs, ProfileImportVantiqArgs,
+    ProfileInitArgs, ProfileRotateArgs, ProfileSetArgs, ProfileShowArgs,
 };
+use crate::commands::doctor::CONNECTION_CHECK_PROCEDURE;
+use crate::commands::dump::underlying_cli;
+use crate::commands::list::csv_escape;
+use crate::config::Config;
 use crate::error::{Result, VqxError};
 use crate::profile::{
-    Profile, ProfileManager, ProfileStore, DEFAULT_PROFILE_NAME, DEFAULT_VANTIQ_URL,
+    ClientOptions, Profile, ProfileManager, ProfileStore, Protection, ProxyOptions,
+    DEFAULT_PROFILE_NAME, DEFAULT_VANTIQ_URL,
 };
-use console::style;
-use dialoguer::{Confirm, Input, Password, Select};
+use crate::prompt;
+use crate::report;
+use crate::secure_file;
+use crate::template;
+use crate::theme::style;
+use crate::underlying::CliOptions;
+use crate::vantiq_profile;
+use chrono::Utc;
+use sha2::{Digest, Sha256};
 use std::fs;
+use tracing::info;
 
 /// Run profile subcommand
-pub async fn run(cmd: &ProfileCommands, output_format: OutputFormat) -> Result<()> {
+pub async fn run(
+    cmd: &ProfileCommands,
+    config: &Config,
+    output_format: OutputFormat,
+) -> Result<()> {
     match cmd {
         ProfileCommands::List => list(output_format).await,
         ProfileCommands::Show(args) => show(args, output_format).await,
@@ -28,7 +46,11 @@ pub async fn run(cmd: &ProfileCommands, output_format: OutputFormat) -> Result<(
         ProfileCommands::Delete(args) => delete(args).await,
         ProfileCommands::Default(args) => set_default(args).await,
         ProfileCommands::Import(args) => import(args).await,
+        ProfileCommands::ImportVantiq(args) => import_vantiq(args).await,
         ProfileCommands::Export(args) => export(args).await,
+        ProfileCommands::Rotate(args) => rotate(args, config).await,
+        ProfileCommands::Encrypt(args) => encrypt(args).await,
+        ProfileCommands::Decrypt => decrypt().await,
         ProfileCommands::Init(args) => init(args).await,
     }
 }
@@ -41,12 +63,19 @@ async fn list(output_format: OutputFormat) -> Result<()> {
     let default_name = &store.default_profile;
 
     match output_format {
-        OutputFormat::Json => {
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Porcelain => {
             let json = serde_json::json!({
                 "default": default_name,
                 "profiles": names,
             });
-            println!("{}", serde_json::to_string_pretty(&json)?);
+            println!("{}", report::serialize(&json, output_format)?);
+        }
+        OutputFormat::Ndjson => {
+            let rows: Vec<_> = names
+                .iter()
+                .map(|name| serde_json::json!({ "name": name, "default": name == default_name }))
+                .collect();
+            print!("{}", report::to_ndjson(&rows)?);
         }
         OutputFormat::Csv => {
             println!("name,is_default");
@@ -96,8 +125,11 @@ async fn show(args: &ProfileShowArgs, output_format: OutputFormat) -> Result<()>
     };
 
     match output_format {
-        OutputFormat::Json => {
-            println!("{}", serde_json::to_string_pretty(&display_profile)?);
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => {
+            println!("{}", report::serialize(&display_profile, output_format)?);
         }
         OutputFormat::Csv => {
             println!("field,value");
@@ -114,7 +146,42 @@ async fn show(args: &ProfileShowArgs, output_format: OutputFormat) -> Result<()>
             if let Some(ref n) = display_profile.namespace {
                 println!("namespace,{}", n);
             }
+            if let Some(ref c) = display_profile.credential {
+                println!("credential,{}", c);
+            }
+            if let Some(ref linked) = display_profile.vantiq_profile {
+                println!("vantiq_profile,{}", linked);
+            }
             println!("trust_ssl,{}", display_profile.trust_ssl);
+            if let Some(proxy) = display_profile
+                .client_options
+                .as_ref()
+                .and_then(|c| c.proxy.as_ref())
+            {
+                println!("proxy,{}:{}", proxy.host, proxy.port);
+            }
+            if !display_profile.backend.is_cli() {
+                println!("backend,rest");
+            }
+            if !display_profile.protection.is_open() {
+                println!("protection,{}", protection_name(display_profile.protection));
+            }
+            if !display_profile.credential_passing.is_auto() {
+                println!(
+                    "credential_passing,{}",
+                    if display_profile
+                        .credential_passing
+                        .uses_file(display_profile.protection)
+                    {
+                        "file"
+                    } else {
+                        "args"
+                    }
+                );
+            }
+            for (name, value) in &display_profile.vars {
+                println!("vars.{},{}", name, csv_escape(value));
+            }
         }
         OutputFormat::Text => {
             println!();
@@ -138,11 +205,77 @@ async fn show(args: &ProfileShowArgs, output_format: OutputFormat) -> Result<()>
             if let Some(ref n) = display_profile.namespace {
                 println!("  Namespace:  {}", n);
             }
+            if let Some(ref c) = display_profile.credential {
+                println!("  Credential: {}", c);
+            }
+            if let Some(ref linked) = display_profile.vantiq_profile {
+                match display_profile.vantiq_profile_file {
+                    Some(ref file) => println!("  CLI:        -s {} -f {}", linked, file),
+                    None => println!("  CLI:        -s {}", linked),
+                }
+            }
             println!("  Trust SSL:  {}", display_profile.trust_ssl);
+            if let Some(proxy) = display_profile
+                .client_options
+                .as_ref()
+                .and_then(|c| c.proxy.as_ref())
+            {
+                match proxy.username {
+                    Some(ref user) => {
+                        println!("  Proxy:      {}@{}:{}", user, proxy.host, proxy.port)
+                    }
+                    None => println!("  Proxy:      {}:{}", proxy.host, proxy.port),
+                }
+            }
+            if !display_profile.backend.is_cli() {
+                println!("  Backend:    REST API");
+            }
+            if !display_profile.protection.is_open() {
+                println!(
+                    "  Protection: {}",
+                    style(protection_name(display_profile.protection)).yellow()
+                );
+            }
+            if display_profile
+                .credential_passing
+                .uses_file(display_profile.protection)
+            {
+                println!("  Secrets:    passed in a temporary profile file");
+            }
+            if let Some(rotated) = display_profile.credential_rotated_at {
+                println!("  Rotated:    {}", rotated.format("%Y-%m-%d"));
+            }
+            if let Some(expires_at) = display_profile.session_expires_at {
+                let until = expires_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M");
+                if display_profile.has_session(Utc::now()) {
+                    println!("  Session:    logged in until {}", until);
+                } else {
+                    println!(
+                        "  Session:    {}",
+                        style(format!("expired {}", until)).dim()
+                    );
+                }
+            }
+            if let Some(age) = manager.store().credential_age(&args.name, Utc::now())? {
+                let line = format!("{} days (limit: {} days)", age.age_days, age.max_days);
+                if age.is_overdue() {
+                    println!("  Age:        {}", style(line + " - overdue").yellow());
+                } else {
+                    println!("  Age:        {}", line);
+                }
+            }
 
             if let Some(ref desc) = display_profile.description {
                 println!("  Note:       {}", desc);
             }
+            if !display_profile.vars.is_empty() {
+                println!("  Vars:");
+                for (name, value) in &display_profile.vars {
+                    println!("    {} = {}", name, value);
+                }
+            }
 
             println!();
             println!(
@@ -177,6 +310,133 @@ async fn show(args: &ProfileShowArgs, output_format: OutputFormat) -> Result<()>
     Ok(())
 }
 
+fn protection_name(protection: Protection) -> &'static str {
+    match protection {
+        Protection::Open => "open",
+        Protection::Confirm => "confirm",
+        Protection::ReadOnly => "read-only",
+    }
+}
+
+/// Enforce a profile's `protection` before a command that changes the server
+///
+/// Uses the default profile when none is given. Missing profiles are ignored
+/// here; the command itself reports them. `confirmed` is the value of
+/// `--confirm-profile`.
+pub fn check_protection(
+    profile_name: Option<&str>,
+    operation: &str,
+    confirmed: Option<&str>,
+) -> Result<()> {
+    let Ok(store) = ProfileStore::load() else {
+        return Ok(());
+    };
+    let name = profile_name.unwrap_or(&store.default_profile);
+    let Ok(profile) = store.get(name) else {
+        return Ok(());
+    };
+
+    match profile.protection {
+        Protection::Open => Ok(()),
+        Protection::ReadOnly => Err(VqxError::ProfileProtected {
+            profile: name.to_string(),
+            operation: operation.to_string(),
+        }),
+        Protection::Confirm if confirmed == Some(name) => Ok(()),
+        Protection::Confirm => {
+            let alternative = format!("pass --confirm-profile {} to confirm", name);
+            prompt::require_input(operation, &alternative)?;
+            eprintln!(
+                "{} Profile '{}' is protected: {} changes {}",
+                style("⚠").yellow(),
+                style(name).bold(),
+                operation,
+                profile.url
+            );
+            let typed = prompt::input(
+                format!("Type the profile name ({}) to continue", name),
+                None,
+                true,
+                operation,
+                &alternative,
+            )?;
+            if typed.trim() == name {
+                Ok(())
+            } else {
+                Err(VqxError::Cancelled {
+                    operation: operation.to_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Apply the proxy options of `profile set`
+fn set_proxy(manager: &ProfileManager, args: &ProfileSetArgs, profile: &mut Profile) -> Result<()> {
+    if args.no_proxy {
+        if let Some(ref mut options) = profile.client_options {
+            options.proxy = None;
+        }
+        // Default options need no profile file
+        if profile.client_options == Some(ClientOptions::default()) {
+            profile.client_options = None;
+        }
+        if profile.use_secure_storage {
+            manager.delete_secret(&args.name, "proxy_password")?;
+        }
+        return Ok(());
+    }
+
+    let changes_proxy =
+        args.proxy.is_some() || args.proxy_username.is_some() || args.proxy_password.is_some();
+    if !changes_proxy {
+        return Ok(());
+    }
+    let options = profile.client_options.get_or_insert_with(Default::default);
+    if let Some(ref address) = args.proxy {
+        let (host, port) = parse_proxy(address)?;
+        let proxy = options.proxy.get_or_insert_with(ProxyOptions::default);
+        proxy.host = host;
+        proxy.port = port;
+    }
+    let Some(ref mut proxy) = options.proxy else {
+        return Err(VqxError::Other(
+            "Set the proxy address with --proxy HOST:PORT first".to_string(),
+        ));
+    };
+    if let Some(ref username) = args.proxy_username {
+        proxy.username = Some(username.clone());
+    }
+    if let Some(ref password) = args.proxy_password {
+        if args.secure {
+            manager.set_secret(&args.name, "proxy_password", password)?;
+            proxy.password = None; // Don't store in file
+        } else {
+            proxy.password = Some(password.clone());
+        }
+    }
+    if args.secure && args.proxy_password.is_some() {
+        profile.use_secure_storage = true;
+    }
+    Ok(())
+}
+
+/// `host:port` of a proxy, with an optional `http://` prefix
+fn parse_proxy(address: &str) -> Result<(String, u16)> {
+    let address = address
+        .strip_prefix("http://")
+        .or_else(|| address.strip_prefix("https://"))
+        .unwrap_or(address)
+        .trim_end_matches('/');
+    let invalid = || VqxError::Other(format!("Invalid proxy '{}', expected HOST:PORT", address));
+    let (host, port) = address.rsplit_once(':').ok_or_else(invalid)?;
+    let port = port.parse().map_err(|_| invalid())?;
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    Ok((host.to_string(), port))
+}
+
 /// Create or update a profile
 async fn set(args: &ProfileSetArgs) -> Result<()> {
     let mut manager = ProfileManager::new()?;
@@ -191,6 +451,9 @@ async fn set(args: &ProfileSetArgs) -> Result<()> {
     if let Some(ref username) = args.username {
         profile.username = Some(username.clone());
     }
+    if args.password.is_some() || args.token.is_some() {
+        profile.touch_credential(Utc::now());
+    }
     if let Some(ref password) = args.password {
         if args.secure {
             // Store in secure storage
@@ -213,12 +476,39 @@ async fn set(args: &ProfileSetArgs) -> Result<()> {
     if let Some(ref namespace) = args.namespace {
         profile.namespace = Some(namespace.clone());
     }
+    set_proxy(&manager, args, &mut profile)?;
     if args.trust_ssl {
         profile.trust_ssl = true;
     }
     if let Some(ref desc) = args.description {
         profile.description = Some(desc.clone());
     }
+    if let Some(ref credential) = args.credential {
+        manager.store().get_credential(credential)?;
+        profile.credential = Some(credential.clone());
+    }
+    if let Some(days) = args.max_credential_age_days {
+        profile.max_credential_age_days = Some(days);
+    }
+    if args.block_stale_credentials {
+        profile.block_stale_credentials = true;
+    }
+    if let Some(backend) = args.backend {
+        profile.backend = backend;
+    }
+    if let Some(protection) = args.protection {
+        profile.protection = protection;
+    }
+    if let Some(passing) = args.credential_passing {
+        profile.credential_passing = passing;
+    }
+    for assignment in &args.vars {
+        let (name, value) = template::parse_assignment(assignment)?;
+        profile.vars.insert(name, value);
+    }
+    for name in &args.unset_vars {
+        profile.vars.remove(name);
+    }
 
     // Validate
     profile.validate()?;
@@ -249,21 +539,23 @@ async fn delete(args: &ProfileDeleteArgs) -> Result<()> {
 
     // Confirm deletion
     if !args.force {
-        let confirmed = Confirm::new()
-            .with_prompt(format!("Delete profile '{}'?", args.name))
-            .default(false)
-            .interact()
-            .map_err(|e| VqxError::Other(e.to_string()))?;
+        let confirmed = prompt::confirm(
+            format!("Delete profile '{}'?", args.name),
+            "Profile deletion",
+            "--force",
+        )?;
 
         if !confirmed {
-            println!("Cancelled.");
-            return Ok(());
+            return Err(VqxError::Cancelled {
+                operation: "Profile deletion".to_string(),
+            });
         }
     }
 
     // Delete secrets from secure storage
     manager.delete_secret(&args.name, "password")?;
     manager.delete_secret(&args.name, "token")?;
+    manager.delete_secret(&args.name, "proxy_password")?;
 
     // Delete from store
     manager.store_mut().remove(&args.name);
@@ -317,6 +609,18 @@ async fn import(args: &ProfileImportArgs) -> Result<()> {
         count += 1;
     }
 
+    for (name, credential) in imported_store.credentials {
+        if manager.store().credentials.contains_key(&name) && !args.overwrite {
+            println!(
+                "{} Skipping credential '{}' (already exists, use --overwrite to replace)",
+                style("⚠").yellow(),
+                name
+            );
+            continue;
+        }
+        manager.store_mut().set_credential(&name, credential);
+    }
+
     manager.save()?;
 
     println!(
@@ -329,25 +633,182 @@ async fn import(args: &ProfileImportArgs) -> Result<()> {
     Ok(())
 }
 
+/// Import profiles from the CLI's profile file
+async fn import_vantiq(args: &ProfileImportVantiqArgs) -> Result<()> {
+    let path = match args.path {
+        Some(ref path) => path.clone(),
+        None => vantiq_profile::default_path().ok_or_else(|| {
+            VqxError::Other("Cannot find the home directory; give the path".to_string())
+        })?,
+    };
+    let content = fs::read_to_string(&path).map_err(|_| VqxError::FileReadFailed {
+        path: path.display().to_string(),
+    })?;
+    let profiles = vantiq_profile::parse(&content)
+        .map_err(|e| VqxError::Other(format!("{}: {}", path.display(), e)))?;
+
+    // Linked profiles name a non-default file explicitly
+    let profile_file = (args.path.is_some()
+        && Some(&path) != vantiq_profile::default_path().as_ref())
+    .then(|| fs::canonicalize(&path).unwrap_or_else(|_| path.clone()))
+    .map(|p| p.display().to_string());
+
+    let mut manager = ProfileManager::new()?;
+    let now = Utc::now();
+    let mut count = 0;
+    for vantiq in &profiles {
+        for key in &vantiq.unknown_keys {
+            println!(
+                "{} '{}': ignoring unknown key '{}'",
+                style("⚠").yellow(),
+                vantiq.name,
+                key
+            );
+        }
+        if manager.store().exists(&vantiq.name) && !args.overwrite {
+            println!(
+                "{} Skipping '{}' (already exists, use --overwrite to replace)",
+                style("⚠").yellow(),
+                vantiq.name
+            );
+            continue;
+        }
+
+        let profile = if args.link {
+            // The CLI reads URL, credentials and namespace itself; the URL is kept for display
+            Profile {
+                url: vantiq
+                    .url
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_VANTIQ_URL.to_string()),
+                vantiq_profile: Some(vantiq.name.clone()),
+                vantiq_profile_file: profile_file.clone(),
+                ..Default::default()
+            }
+        } else {
+            let mut profile = vantiq.to_profile();
+            if profile.password.is_some() || profile.token.is_some() {
+                profile.touch_credential(now);
+            }
+            profile
+        };
+        println!(
+            "{} {} ({})",
+            style("✓").green(),
+            style(&vantiq.name).bold(),
+            if args.link {
+                format!("linked: -s {}", vantiq.name)
+            } else {
+                profile.auth_type().to_string()
+            }
+        );
+        manager.store_mut().set(&vantiq.name, profile);
+        count += 1;
+    }
+
+    manager.save()?;
+
+    println!(
+        "{} Imported {} profile(s) from '{}'.",
+        style("✓").green(),
+        count,
+        path.display()
+    );
+    if !args.link && count > 0 {
+        println!(
+            "  Re-run with --overwrite after changing '{}' to update them, or use --link.",
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
 /// Export profiles to file
 async fn export(args: &ProfileExportArgs) -> Result<()> {
     let manager = ProfileManager::new()?;
     let store = manager.store();
+    for name in &args.profiles {
+        store.get(name)?;
+    }
+    let selected = |name: &str| args.profiles.is_empty() || args.profiles.iter().any(|p| p == name);
 
-    // Mask secrets unless explicitly included
-    let export_store = if args.include_secrets {
-        store.clone()
-    } else {
-        let mut masked_store = ProfileStore::new();
-        masked_store.default_profile = store.default_profile.clone();
-        for (name, profile) in &store.profiles {
-            masked_store.set(name.clone(), profile.masked());
+    let content = match args.format {
+        ProfileFileFormat::Toml => {
+            // Mask secrets unless explicitly included
+            let mut export_store = if args.include_secrets {
+                store.clone()
+            } else {
+                store.masked()
+            };
+            export_store.profiles.retain(|name, _| selected(name));
+            // Login sessions belong to this machine
+            for profile in export_store.profiles.values_mut() {
+                profile.session_token = None;
+                profile.session_expires_at = None;
+            }
+            let used: Vec<String> = export_store
+                .profiles
+                .values()
+                .filter_map(|p| p.credential.clone())
+                .collect();
+            export_store
+                .credentials
+                .retain(|name, _| args.profiles.is_empty() || used.contains(name));
+            export_store.to_toml()?
+        }
+        ProfileFileFormat::Vantiq => {
+            let mut names: Vec<&String> = store.profiles.keys().filter(|n| selected(n)).collect();
+            names.sort();
+
+            let mut profiles = Vec::new();
+            for name in names {
+                if let Some(ref linked) = store.profiles[name].vantiq_profile {
+                    println!(
+                        "{} Skipping '{}' (linked to CLI profile '{}')",
+                        style("⚠").yellow(),
+                        name,
+                        linked
+                    );
+                    continue;
+                }
+                if !vantiq_profile::is_valid_name(name) {
+                    println!(
+                        "{} Skipping '{}' (CLI profile names use letters, digits and '_')",
+                        style("⚠").yellow(),
+                        name
+                    );
+                    continue;
+                }
+                // Without secrets, an unreadable keyring does not stop the export
+                let mut profile = match manager.get_resolved_stored(name) {
+                    Ok(profile) => profile,
+                    Err(e) if args.include_secrets => return Err(e),
+                    Err(_) => store.profiles[name].clone(),
+                };
+                if !args.include_secrets {
+                    profile.password = None;
+                    profile.token = None;
+                    if let Some(proxy) = profile
+                        .client_options
+                        .as_mut()
+                        .and_then(|c| c.proxy.as_mut())
+                    {
+                        proxy.password = None;
+                    }
+                }
+                profiles.push(vantiq_profile::VantiqProfile::from_profile(name, &profile));
+            }
+            vantiq_profile::render(&profiles)
         }
-        masked_store
     };
 
-    let content = export_store.to_toml()?;
-    fs::write(&args.file, content).map_err(|_| VqxError::FileWriteFailed {
+    let written = if args.include_secrets {
+        secure_file::write_private(&args.file, content)
+    } else {
+        fs::write(&args.file, content)
+    };
+    written.map_err(|_| VqxError::FileWriteFailed {
         path: args.file.display().to_string(),
     })?;
 
@@ -358,17 +819,273 @@ async fn export(args: &ProfileExportArgs) -> Result<()> {
     );
 
     if !args.include_secrets {
+        let note = match args.format {
+            ProfileFileFormat::Toml => "Secrets were masked.",
+            ProfileFileFormat::Vantiq => "Secrets were left out.",
+        };
+        println!(
+            "{}",
+            style(format!(
+                "Note: {} Use --include-secrets to export credentials.",
+                note
+            ))
+            .dim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Replace a profile's token after checking the new one against the server
+async fn rotate(args: &ProfileRotateArgs, config: &Config) -> Result<()> {
+    let mut manager = ProfileManager::new()?;
+    let mut profile = manager.store().get(&args.name)?.clone();
+    if profile.vantiq_profile.is_some() {
+        return Err(VqxError::Other(format!(
+            "Profile '{}' is linked to a Vantiq CLI profile; update the token in its profile file",
+            args.name
+        )));
+    }
+    if let Some(ref credential) = profile.credential {
+        return Err(VqxError::Other(format!(
+            "Profile '{}' uses the named credential '{}'; rotate it with `vqx credential set {} --token ...`",
+            args.name, credential, credential
+        )));
+    }
+    if profile.token.is_none() && profile.username.is_some() {
+        return Err(VqxError::Other(format!(
+            "Profile '{}' uses username/password; change it with `vqx profile set {} --password ...`",
+            args.name, args.name
+        )));
+    }
+
+    let token = if args.token_stdin {
+        let mut line = String::new();
+        std::io::stdin()
+            .read_line(&mut line)
+            .map_err(|e| VqxError::Other(format!("Failed to read the token from stdin: {}", e)))?;
+        line.trim().to_string()
+    } else {
+        prompt::password(
+            format!("New token for '{}'", args.name),
+            "Token rotation",
+            "pipe the new token with --token-stdin",
+        )?
+    };
+    if token.is_empty() {
+        return Err(VqxError::Other("The new token is empty".to_string()));
+    }
+
+    let resolved = manager.get_resolved_stored(&args.name)?;
+    let old_token = resolved.token.clone();
+    if old_token.as_deref() == Some(token.as_str()) {
+        return Err(VqxError::Other(
+            "The new token is the one already saved".to_string(),
+        ));
+    }
+
+    // Nothing is saved unless the server accepts the new token
+    let candidate = Profile {
+        token: Some(token.clone()),
+        ..resolved
+    };
+    if !args.no_verify {
+        let result = underlying_cli(config, "run")
+            .run_procedure(
+                &CliOptions::from_profile(&candidate),
+                CONNECTION_CHECK_PROCEDURE,
+                &[],
+            )
+            .await
+            .map_err(|e| {
+                VqxError::Other(format!(
+                    "Could not verify the new token, nothing was changed: {}",
+                    e
+                ))
+            })?;
+        if !result.success() {
+            return Err(VqxError::Other(format!(
+                "{} rejected the new token, nothing was changed: {}",
+                candidate.url,
+                result.stderr.trim()
+            )));
+        }
+        println!(
+            "{} The new token is accepted by {}",
+            style("✓").green(),
+            candidate.url
+        );
+    }
+
+    profile.touch_credential(Utc::now());
+    if profile.use_secure_storage {
+        manager.set_secret(&args.name, "token", &token)?;
+        manager.store_mut().set(&args.name, profile);
+        if let Err(e) = manager.save() {
+            // Keep the keyring and profiles.toml in step
+            match old_token {
+                Some(ref old) => manager.set_secret(&args.name, "token", old)?,
+                None => manager.delete_secret(&args.name, "token")?,
+            }
+            return Err(e);
+        }
+    } else {
+        profile.token = Some(token.clone());
+        manager.store_mut().set(&args.name, profile);
+        manager.save()?;
+    }
+
+    info!(
+        profile = %args.name,
+        old = %old_token.as_deref().map(fingerprint).unwrap_or_default(),
+        new = %fingerprint(&token),
+        "Rotated profile token"
+    );
+    println!(
+        "{} Rotated the token of '{}' ({} → {}).",
+        style("✓").green(),
+        style(&args.name).bold(),
+        old_token
+            .as_deref()
+            .map(fingerprint)
+            .unwrap_or_else(|| "none".to_string()),
+        fingerprint(&token)
+    );
+
+    if let Some(ref token_name) = args.revoke {
+        let result = underlying_cli(config, "delete")
+            .delete(&CliOptions::from_profile(&candidate), "tokens", token_name)
+            .await;
+        match result {
+            Ok(result) if result.success() => {
+                info!(profile = %args.name, token = %token_name, "Revoked old access token");
+                println!(
+                    "{} Revoked the old access token '{}'.",
+                    style("✓").green(),
+                    token_name
+                );
+            }
+            Ok(result) => println!(
+                "{} Could not revoke '{}': {}. Delete it in the Vantiq IDE.",
+                style("⚠").yellow(),
+                token_name,
+                result.stderr.trim()
+            ),
+            Err(e) => println!(
+                "{} Could not revoke '{}': {}. Delete it in the Vantiq IDE.",
+                style("⚠").yellow(),
+                token_name,
+                e
+            ),
+        }
+    }
+    Ok(())
+}
+
+/// Short, non-reversible identifier of a token for logs
+fn fingerprint(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    format!(
+        "sha256:{}",
+        digest[..4]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    )
+}
+
+/// Replace profiles.toml with a passphrase-encrypted profiles.toml.enc
+async fn encrypt(args: &ProfileEncryptArgs) -> Result<()> {
+    secure_file::ensure_available()?;
+    let path = ProfileStore::profiles_file_path()?;
+    let encrypted = secure_file::encrypted_path(&path);
+
+    if encrypted.exists() {
+        if !args.remember {
+            return Err(VqxError::Other(format!(
+                "Profiles are already encrypted ({}); use --remember to store the passphrase",
+                encrypted.display()
+            )));
+        }
+        // Loading checks the passphrase
+        ProfileStore::load_from(&path)?;
+        secure_file::remember(&secure_file::passphrase(&encrypted)?)?;
+        println!(
+            "{} Stored the passphrase in the OS keyring.",
+            style("✓").green()
+        );
+        return Ok(());
+    }
+
+    let store = ProfileStore::load_from(&path)?;
+    let passphrase = secure_file::new_passphrase()?;
+    let content = secure_file::encrypt(&store.to_toml()?, &passphrase)?;
+    secure_file::write_private(&encrypted, content).map_err(|_| VqxError::FileWriteFailed {
+        path: encrypted.display().to_string(),
+    })?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|_| VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        })?;
+    }
+    if args.remember {
+        secure_file::remember(&passphrase)?;
+    }
+
+    println!(
+        "{} Encrypted profiles to '{}'.",
+        style("✓").green(),
+        encrypted.display()
+    );
+    if !args.remember {
         println!(
             "{}",
-            style("Note: Secrets were masked. Use --include-secrets to export credentials.").dim()
+            style(format!(
+                "Note: vqx asks for the passphrase once per run; set {} or use --remember to unlock without a prompt.",
+                secure_file::PASSPHRASE_ENV
+            ))
+            .dim()
         );
     }
+    Ok(())
+}
+
+/// Replace profiles.toml.enc with a plaintext profiles.toml
+async fn decrypt() -> Result<()> {
+    let path = ProfileStore::profiles_file_path()?;
+    let encrypted = secure_file::encrypted_path(&path);
+    if !encrypted.exists() {
+        return Err(VqxError::Other(format!(
+            "Profiles are not encrypted ({} does not exist)",
+            encrypted.display()
+        )));
+    }
 
+    let store = ProfileStore::load_from(&path)?;
+    secure_file::write_private(&path, store.to_toml()?).map_err(|_| VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    })?;
+    fs::remove_file(&encrypted).map_err(|_| VqxError::FileWriteFailed {
+        path: encrypted.display().to_string(),
+    })?;
+    secure_file::forget()?;
+
+    println!(
+        "{} Decrypted profiles to '{}'.",
+        style("✓").green(),
+        path.display()
+    );
     Ok(())
 }
 
 /// Interactive profile creation
 async fn init(args: &ProfileInitArgs) -> Result<()> {
+    let (op, alt) = (
+        "Profile setup",
+        "create the profile with `vqx profile set <name> --url <url> --token <token>`",
+    );
+    prompt::require_input(op, alt)?;
+
     println!();
     println!("{}", style("vqx Profile Setup").bold().cyan());
     println!("{}", style("─".repeat(40)).dim());
@@ -386,18 +1103,16 @@ async fn init(args: &ProfileInitArgs) -> Result<()> {
         .clone()
         .unwrap_or_else(|| DEFAULT_PROFILE_NAME.to_string());
 
-    let name: String = Input::new()
-        .with_prompt("Profile name")
-        .default(default_name)
-        .interact_text()
-        .map_err(|e| VqxError::Other(e.to_string()))?;
+    let name = prompt::input("Profile name", Some(default_name), false, op, alt)?;
 
     // Server URL
-    let url: String = Input::new()
-        .with_prompt("Vantiq server URL")
-        .default(DEFAULT_VANTIQ_URL.to_string())
-        .interact_text()
-        .map_err(|e| VqxError::Other(e.to_string()))?;
+    let url = prompt::input(
+        "Vantiq server URL",
+        Some(DEFAULT_VANTIQ_URL.to_string()),
+        false,
+        op,
+        alt,
+    )?;
 
     // Authentication type
     println!();
@@ -420,35 +1135,20 @@ async fn init(args: &ProfileInitArgs) -> Result<()> {
         "Username/Password (Edge servers only)",
     ];
 
-    let auth_choice = Select::new()
-        .with_prompt("Authentication method")
-        .items(&auth_options)
-        .default(0)
-        .interact()
-        .map_err(|e| VqxError::Other(e.to_string()))?;
+    let auth_choice = prompt::select("Authentication method", &auth_options, op, alt)?;
 
     let mut profile = Profile::new(&url);
 
     match auth_choice {
         0 => {
             // Token authentication
-            let token: String = Password::new()
-                .with_prompt("Access token (long-lived token from Vantiq)")
-                .interact()
-                .map_err(|e| VqxError::Other(e.to_string()))?;
+            let token = prompt::password("Access token (long-lived token from Vantiq)", op, alt)?;
             profile.token = Some(token);
         }
         1 => {
             // Username/password
-            let username: String = Input::new()
-                .with_prompt("Username")
-                .interact_text()
-                .map_err(|e| VqxError::Other(e.to_string()))?;
-
-            let password: String = Password::new()
-                .with_prompt("Password")
-                .interact()
-                .map_err(|e| VqxError::Other(e.to_string()))?;
+            let username = prompt::input("Username", None, false, op, alt)?;
+            let password = prompt::password("Password", op, alt)?;
 
             profile.username = Some(username);
             profile.password = Some(password);
@@ -461,17 +1161,10 @@ async fn init(args: &ProfileInitArgs) -> Result<()> {
                     .dim()
             );
 
-            let use_namespace = Confirm::new()
-                .with_prompt("Specify a target namespace?")
-                .default(false)
-                .interact()
-                .map_err(|e| VqxError::Other(e.to_string()))?;
+            let use_namespace = prompt::ask_yes_no("Specify a target namespace?", false, op, alt)?;
 
             if use_namespace {
-                let namespace: String = Input::new()
-                    .with_prompt("Namespace")
-                    .interact_text()
-                    .map_err(|e| VqxError::Other(e.to_string()))?;
+                let namespace = prompt::input("Namespace", None, false, op, alt)?;
                 profile.namespace = Some(namespace);
             }
         }
@@ -479,36 +1172,38 @@ async fn init(args: &ProfileInitArgs) -> Result<()> {
     }
 
     // Trust SSL
-    let trust_ssl = Confirm::new()
-        .with_prompt("Trust SSL certificates? (PDF: '-trust' flag)")
-        .default(false)
-        .interact()
-        .map_err(|e| VqxError::Other(e.to_string()))?;
+    let trust_ssl = prompt::ask_yes_no(
+        "Trust SSL certificates? (PDF: '-trust' flag)",
+        false,
+        op,
+        alt,
+    )?;
 
     profile.trust_ssl = trust_ssl;
 
     // Store securely
     #[cfg(feature = "keyring-storage")]
-    let use_secure = Confirm::new()
-        .with_prompt("Store credentials in secure storage (keyring)?")
-        .default(true)
-        .interact()
-        .map_err(|e| VqxError::Other(e.to_string()))?;
+    let use_secure = prompt::ask_yes_no(
+        "Store credentials in secure storage (keyring)?",
+        true,
+        op,
+        alt,
+    )?;
 
     #[cfg(not(feature = "keyring-storage"))]
     let use_secure = false;
 
     // Description
-    let description: String = Input::new()
-        .with_prompt("Description (optional)")
-        .allow_empty(true)
-        .interact_text()
-        .map_err(|e| VqxError::Other(e.to_string()))?;
+    let description = prompt::input("Description (optional)", None, true, op, alt)?;
 
     if !description.is_empty() {
         profile.description = Some(description);
     }
 
+    if profile.has_auth() {
+        profile.touch_credential(Utc::now());
+    }
+
     // Validate
     profile.validate()?;
 
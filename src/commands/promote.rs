@@ -2,14 +2,25 @@
 //!
 //! Promotes resources from one Vantiq environment to another.
 //! Workflow: export from source -> diff (optional) -> confirm -> import to target -> test (optional)
+//!
+//! Several test suites run in parallel against the target, like
+//! `run testsuites`.
 
 use crate::cli::{OutputFormat, PromoteArgs};
+use crate::commands::run::display_suite_run;
 use crate::config::Config;
 use crate::error::{Result, VqxError};
+use crate::filter::ResourceFilter;
+use crate::lock::OperationLock;
 use crate::profile::ProfileManager;
+use crate::progress::TransferProgress;
+use crate::prompt;
+use crate::report;
+use crate::snapshot;
+use crate::suites::{self, Runner, SuiteRun, SuitesFile};
+use crate::theme::style;
 use crate::underlying::{CliOptions, UnderlyingCli};
-use console::style;
-use dialoguer::Confirm;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::path::PathBuf;
 use tempfile::TempDir;
@@ -17,13 +28,16 @@ use tracing::info;
 use walkdir::WalkDir;
 
 /// Result of a promote operation
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct PromoteResult {
     pub success: bool,
     pub source_profile: String,
     pub target_profile: String,
     pub exported: bool,
     pub imported: bool,
+    /// Snapshot of the target taken before the import (`vqx rollback --to`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub test_result: Option<TestResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -31,13 +45,16 @@ pub struct PromoteResult {
 }
 
 /// Test execution result
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct TestResult {
     pub success: bool,
     pub test_type: String,
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<String>,
+    /// Per-suite results when test suites were run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suites: Option<Vec<SuiteRun>>,
 }
 
 /// Run the promote command
@@ -54,6 +71,8 @@ pub async fn run(
         "Running promote"
     );
 
+    let filter = ResourceFilter::from_args(&args.filter, &[], None)?;
+
     // Validate profiles exist
     let manager = ProfileManager::new()?;
     let source_profile = manager.get_resolved(&args.from)?;
@@ -70,12 +89,22 @@ pub async fn run(
         });
     }
 
+    // Keep other vqx operations out of the target namespace until promotion is done
+    let _lock = OperationLock::acquire(
+        "promote",
+        &args.to,
+        &target_profile.url,
+        target_profile.namespace.as_deref(),
+        &args.lock,
+    )
+    .await?;
+
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("promote"))
+        .with_retry_policy(config.retry_policy());
 
     // Display promotion info
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
         println!("{}", style("Promote").bold().magenta());
         println!("{}", style("─".repeat(50)).dim());
@@ -89,12 +118,18 @@ pub async fn run(
             style(&args.to).yellow(),
             style(&target_profile.url).dim()
         );
-        if let Some(ref ts) = args.testsuite {
-            println!("  Test:   testsuite '{}'", style(ts).green());
+        if !args.testsuite.is_empty() {
+            println!(
+                "  Test:   testsuite '{}'",
+                style(args.testsuite.join("', '")).green()
+            );
         }
         if let Some(ref proc) = args.procedure {
             println!("  Test:   procedure '{}'", style(proc).green());
         }
+        if !filter.is_empty() {
+            println!("  Filter: {}", filter);
+        }
         println!();
     }
 
@@ -106,18 +141,27 @@ pub async fn run(
     // Step 1: Export from source
     println!("{} Exporting from source...", style("→").cyan());
     let source_options = CliOptions::from_profile(&source_profile);
-    let export_result = cli
-        .export(
+    let interactive = !output_format.is_machine_readable();
+    let mut progress = TransferProgress::new("Exporting from source...", interactive);
+    let export_args = UnderlyingCli::export_args(
+        Some("metadata"),
+        Some(&export_path),
+        None,
+        None,
+        None,
+        None,
+        false,
+    );
+    let export_result = progress
+        .run(
+            &cli,
             &source_options,
-            Some("metadata"),
-            Some(export_path.to_str().unwrap()),
-            None,
-            None,
-            None,
-            None,
-            false,
+            "export",
+            export_args,
+            config.stall_policy(),
         )
         .await?;
+    progress.finish_and_clear();
 
     if !export_result.success() {
         return Ok(PromoteResult {
@@ -126,11 +170,15 @@ pub async fn run(
             target_profile: args.to.clone(),
             exported: false,
             imported: false,
+            snapshot: None,
             test_result: None,
             error: Some(format!("Export failed: {}", export_result.stderr)),
         });
     }
 
+    // Only the selected resources are compared and imported
+    filter.prune(&export_path)?;
+
     // Count exported files
     let file_count = count_json_files(&export_path);
     println!(
@@ -150,20 +198,30 @@ pub async fn run(
         let target_export_path = target_temp.path();
 
         let target_options = CliOptions::from_profile(&target_profile);
-        let target_export_result = cli
-            .export(
+        let mut progress = TransferProgress::new("Exporting from target...", interactive);
+        let target_export_args = UnderlyingCli::export_args(
+            Some("metadata"),
+            Some(target_export_path),
+            None,
+            None,
+            None,
+            None,
+            false,
+        );
+        let target_export_result = progress
+            .run(
+                &cli,
                 &target_options,
-                Some("metadata"),
-                Some(target_export_path.to_str().unwrap()),
-                None,
-                None,
-                None,
-                None,
-                false,
+                "export",
+                target_export_args,
+                config.stall_policy(),
             )
             .await?;
+        progress.finish_and_clear();
 
         if target_export_result.success() {
+            filter.prune(target_export_path)?;
+
             // Show simple diff summary
             let source_files = list_json_files(&export_path);
             let target_files = list_json_files(&target_export_path.to_path_buf());
@@ -210,45 +268,46 @@ pub async fn run(
     // Step 3: Confirmation
     if !args.yes {
         println!();
-        let prompt = format!(
+        let question = format!(
             "Promote {} resources from '{}' to '{}'?",
             file_count, args.from, args.to
         );
-        let confirmed = Confirm::new()
-            .with_prompt(prompt)
-            .default(false)
-            .interact()
-            .map_err(|e| VqxError::Other(format!("Confirmation failed: {}", e)))?;
+        let confirmed = prompt::confirm(question, "Promotion", "--yes")?;
 
         if !confirmed {
-            println!("{} Operation cancelled.", style("✗").yellow());
-            return Ok(PromoteResult {
-                success: false,
-                source_profile: args.from.clone(),
-                target_profile: args.to.clone(),
-                exported: true,
-                imported: false,
-                test_result: None,
-                error: Some("Operation cancelled by user".to_string()),
+            return Err(VqxError::Cancelled {
+                operation: "Promotion".to_string(),
             });
         }
     }
 
-    // Step 4: Import to target
+    // Step 4: Import to target (snapshotting a protected target first)
+    let snapshot = snapshot::take(
+        config,
+        &cli,
+        &args.to,
+        &target_profile,
+        "promote",
+        interactive,
+    )
+    .await?
+    .map(|s| s.id);
     println!();
     println!("{} Importing to target...", style("→").cyan());
     let target_options = CliOptions::from_profile(&target_profile);
-    let import_result = cli
-        .import(
+    let mut progress = TransferProgress::new("Importing to target...", interactive);
+    let import_args =
+        UnderlyingCli::import_args(Some("metadata"), Some(&export_path), None, None, None, None);
+    let import_result = progress
+        .run(
+            &cli,
             &target_options,
-            Some("metadata"),
-            Some(export_path.to_str().unwrap()),
-            None,
-            None,
-            None,
-            None,
+            "import",
+            import_args,
+            config.stall_policy(),
         )
         .await?;
+    progress.finish_and_clear();
 
     if !import_result.success() {
         return Ok(PromoteResult {
@@ -257,6 +316,7 @@ pub async fn run(
             target_profile: args.to.clone(),
             exported: true,
             imported: false,
+            snapshot,
             test_result: None,
             error: Some(format!("Import failed: {}", import_result.stderr)),
         });
@@ -266,30 +326,39 @@ pub async fn run(
 
     // Step 5: Run tests (if specified)
     let test_result = if !args.no_test {
-        if let Some(ref testsuite) = args.testsuite {
+        if !args.testsuite.is_empty() {
             println!();
             println!(
-                "{} Running test suite '{}'...",
+                "{} Running test suites '{}'...",
                 style("→").cyan(),
-                testsuite
+                args.testsuite.join("', '")
             );
-            let result = cli.run_testsuite(&target_options, testsuite, None).await?;
-
-            let success = result.success();
+            let jobs = SuitesFile::from_names(&args.testsuite)
+                .jobs(Some(&args.to), config.timeout_for("promote"))
+                .into_iter()
+                .map(|job| (job, target_options.clone()))
+                .collect();
+            let runner = Runner {
+                cli_path: config.cli_path.clone(),
+                retry: config.retry_policy(),
+            };
+            let runs = runner
+                .run_all(jobs, suites::DEFAULT_CONCURRENCY, display_suite_run)
+                .await;
+
+            let success = runs.iter().all(SuiteRun::passed);
             if success {
-                println!("{} Test suite passed", style("✓").green());
+                println!("{} Test suites passed", style("✓").green());
             } else {
-                println!("{} Test suite failed", style("✗").red());
-                if !result.stderr.is_empty() {
-                    eprintln!("{}", style(&result.stderr).red());
-                }
+                println!("{} Test suites failed", style("✗").red());
             }
 
             Some(TestResult {
                 success,
                 test_type: "testsuite".to_string(),
-                name: testsuite.clone(),
-                output: Some(result.stdout),
+                name: args.testsuite.join(","),
+                output: None,
+                suites: Some(runs),
             })
         } else if let Some(ref procedure) = args.procedure {
             println!();
@@ -308,6 +377,7 @@ pub async fn run(
                 test_type: "procedure".to_string(),
                 name: procedure.clone(),
                 output: Some(result.stdout),
+                suites: None,
             })
         } else {
             None
@@ -325,6 +395,7 @@ pub async fn run(
         target_profile: args.to.clone(),
         exported: true,
         imported: true,
+        snapshot,
         test_result,
         error: None,
     };
@@ -378,8 +449,11 @@ fn list_json_files(dir: &PathBuf) -> Vec<String> {
 /// Display the result
 fn display_result(result: &PromoteResult, output_format: OutputFormat) {
     match output_format {
-        OutputFormat::Json => {
-            if let Ok(json) = serde_json::to_string_pretty(result) {
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => {
+            if let Ok(json) = report::render(result, output_format) {
                 println!("{}", json);
             }
         }
@@ -409,6 +483,68 @@ fn display_result(result: &PromoteResult, output_format: OutputFormat) {
     }
 }
 
+/// Show the annotated guide for the promote command (`vqx explain promote`)
+pub fn display_help() {
+    println!();
+    println!("{}", style("Promote Command").bold().cyan());
+    println!("{}", style("─".repeat(60)).dim());
+    println!();
+    println!("Move resources from one environment to another, then test them.");
+    println!();
+    println!(
+        "{}",
+        style("⚠  Warning: This is a potentially destructive operation!").yellow()
+    );
+    println!("   Resources in the target namespace are overwritten.");
+    println!();
+    println!(
+        "{}",
+        style("PDF Reference: Export, Import and Run sections").bold()
+    );
+    println!();
+    println!("{}", style("Workflow:").bold());
+    println!("  1. Export metadata from the source (PDF: 'export metadata')");
+    println!("  2. Show the diff against the target");
+    println!("  3. Confirm");
+    println!("  4. Import into the target (PDF: 'import metadata')");
+    println!("  5. Run test suites or a procedure (PDF: 'run testsuite', 'run procedure')");
+    println!();
+    println!("{}", style("Options:").bold());
+    println!("  --from <profile>        Source profile");
+    println!("  --to <profile>          Target profile");
+    println!("  --testsuite <name>      Test suites to run after promotion");
+    println!("  --procedure <name>      Procedure to run after promotion");
+    println!("  --no-diff               Skip the diff display");
+    println!("  --no-test               Skip the tests");
+    println!("  --include <pattern>     Only promote these resources (type, type/name, globs)");
+    println!("  --exclude <pattern>     Leave these resources out");
+    println!();
+    println!("{}", style("Safety Options:").bold());
+    println!("  --yes, -y               Skip confirmation prompt");
+    println!("  --wait[=<seconds>]      Wait for other vqx operations on the target");
+    println!();
+    println!("{}", style("Examples:").bold());
+    println!();
+    println!("  # Promote dev to staging and run a test suite");
+    println!(
+        "  {} vqx promote --from dev --to staging --testsuite Smoke",
+        style("$").dim()
+    );
+    println!();
+    println!("  # Unattended promotion in CI");
+    println!(
+        "  {} vqx promote --from staging --to prod --testsuite Smoke --yes",
+        style("$").dim()
+    );
+    println!();
+    println!("{}", style("Note:").dim());
+    println!(
+        "{}",
+        style("  Several test suites run in parallel, like 'run testsuites'.").dim()
+    );
+    println!();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
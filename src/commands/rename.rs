@@ -0,0 +1,318 @@
+//! Rename command implementation
+//!
+//! Renames a resource in an export directory: the definition file (and its
+//! extracted `.vail` file) gets the new name, and every reference to the old
+//! name in procedures, rules, services and clients is rewritten, including
+//! the `name` field and the `PROCEDURE`/`RULE` header of the resource
+//! itself. A reference is the whole name: renaming `Order` leaves `Orders`
+//! and `Billing.Order` alone.
+//!
+//! Only local files change. `sync push` or `import` then creates the
+//! resource under its new name; the old one stays on the server until it is
+//! deleted.
+
+use crate::cli::{OutputFormat, RenameArgs};
+use crate::commands::new::check_name;
+use crate::error::{Result, VqxError};
+use crate::report;
+use crate::theme::style;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directories whose resources refer to others by name
+const REFERRING_TYPES: &[&str] = &["procedures", "rules", "services", "clients"];
+
+/// A renamed file
+#[derive(Debug, Serialize)]
+pub struct RenamedFile {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// A file whose references were rewritten
+#[derive(Debug, Serialize)]
+pub struct TouchedFile {
+    pub path: PathBuf,
+    pub references: usize,
+}
+
+/// Result of the rename command
+#[derive(Debug, Serialize)]
+pub struct RenameResult {
+    pub success: bool,
+    pub resource_type: String,
+    pub old_name: String,
+    pub new_name: String,
+    pub directory: PathBuf,
+    pub dry_run: bool,
+    pub renamed: Vec<RenamedFile>,
+    pub touched: Vec<TouchedFile>,
+}
+
+/// Run the rename command
+pub fn run(args: &RenameArgs, output_format: OutputFormat) -> Result<RenameResult> {
+    check_name(&args.new_name)?;
+    let directory = args.directory.clone().unwrap_or_else(|| PathBuf::from("."));
+    let type_dir = directory.join(&args.resource_type);
+    let definition = type_dir.join(format!("{}.json", args.old_name));
+    if !definition.is_file() {
+        return Err(VqxError::Other(format!(
+            "No {} named '{}' in {}",
+            args.resource_type,
+            args.old_name,
+            directory.display()
+        )));
+    }
+
+    let mut renamed = vec![RenamedFile {
+        from: definition.clone(),
+        to: type_dir.join(format!("{}.json", args.new_name)),
+    }];
+    let code = type_dir.join(format!("{}.vail", args.old_name));
+    if code.is_file() {
+        renamed.push(RenamedFile {
+            from: code,
+            to: type_dir.join(format!("{}.vail", args.new_name)),
+        });
+    }
+    if let Some(existing) = renamed.iter().find(|r| r.to.exists()) {
+        return Err(VqxError::Other(format!(
+            "{} already exists",
+            existing.to.display()
+        )));
+    }
+
+    // Compute every change before writing anything
+    let mut rewrites = Vec::new();
+    for path in referring_files(&directory, &definition) {
+        let content = fs::read_to_string(&path).map_err(|_| VqxError::FileReadFailed {
+            path: path.display().to_string(),
+        })?;
+        let (mut updated, mut references) =
+            replace_references(&content, &args.old_name, &args.new_name);
+        if path == definition {
+            // The `{"$vail": "<old>.vail"}` reference of extracted code
+            let old_ref = format!("\"{}.vail\"", args.old_name);
+            references += updated.matches(&old_ref).count();
+            updated = updated.replace(&old_ref, &format!("\"{}.vail\"", args.new_name));
+        }
+        if references > 0 {
+            rewrites.push((path, updated, references));
+        }
+    }
+
+    if !args.dry_run {
+        for (path, content, _) in &rewrites {
+            fs::write(path, content).map_err(|_| VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            })?;
+        }
+        for file in &renamed {
+            fs::rename(&file.from, &file.to).map_err(|_| VqxError::FileWriteFailed {
+                path: file.to.display().to_string(),
+            })?;
+        }
+    }
+
+    let result = RenameResult {
+        success: true,
+        resource_type: args.resource_type.clone(),
+        old_name: args.old_name.clone(),
+        new_name: args.new_name.clone(),
+        directory,
+        dry_run: args.dry_run,
+        renamed,
+        touched: rewrites
+            .into_iter()
+            .map(|(path, _, references)| TouchedFile { path, references })
+            .collect(),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display(&result);
+    }
+    Ok(result)
+}
+
+/// JSON and `.vail` files that may refer to the resource, plus its own
+/// definition
+fn referring_files(directory: &Path, definition: &Path) -> Vec<PathBuf> {
+    let mut files = vec![definition.to_path_buf()];
+    for resource_type in REFERRING_TYPES {
+        let dir = directory.join(resource_type);
+        for entry in WalkDir::new(&dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            let path = entry.path();
+            let is_resource = path
+                .extension()
+                .is_some_and(|ext| ext == "json" || ext == "vail");
+            if entry.file_type().is_file() && is_resource && path != definition {
+                files.push(path.to_path_buf());
+            }
+        }
+    }
+    files
+}
+
+/// Replace whole-name occurrences of `old` with `new`
+///
+/// An occurrence must not be part of a longer name: the characters around
+/// it may not be letters, digits, `_` or `.`. A JSON escape such as `\n`
+/// before it counts as whitespace. Returns the new text and the number of
+/// replacements.
+fn replace_references(text: &str, old: &str, new: &str) -> (String, usize) {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '.';
+    let mut out = String::with_capacity(text.len());
+    let mut count = 0;
+    let mut start = 0;
+    for (pos, _) in text.match_indices(old) {
+        let preceding = &text[..pos];
+        let before = preceding.chars().last().filter(|_| {
+            !["\\n", "\\r", "\\t"]
+                .iter()
+                .any(|escape| preceding.ends_with(escape))
+        });
+        let after = text[pos + old.len()..].chars().next();
+        if before.is_some_and(is_name_char) || after.is_some_and(is_name_char) {
+            continue;
+        }
+        out.push_str(&text[start..pos]);
+        out.push_str(new);
+        start = pos + old.len();
+        count += 1;
+    }
+    out.push_str(&text[start..]);
+    (out, count)
+}
+
+fn display(result: &RenameResult) {
+    let verb = if result.dry_run {
+        "Would rename"
+    } else {
+        "Renamed"
+    };
+    println!(
+        "{} {} {} {} -> {}",
+        style("✓").green(),
+        verb,
+        result.resource_type,
+        style(&result.old_name).bold(),
+        style(&result.new_name).bold()
+    );
+    for file in &result.renamed {
+        println!("  {} -> {}", file.from.display(), file.to.display());
+    }
+    if !result.touched.is_empty() {
+        println!();
+        println!(
+            "{}",
+            style(if result.dry_run {
+                "References to rewrite:"
+            } else {
+                "References rewritten:"
+            })
+            .bold()
+        );
+        for file in &result.touched {
+            println!("  {} ({})", file.path.display(), file.references);
+        }
+    }
+    if !result.dry_run {
+        println!();
+        println!(
+            "{}",
+            style(format!(
+                "Push the directory to apply the rename; '{}' stays on the server until it is deleted",
+                result.old_name
+            ))
+            .dim()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_replace_references() {
+        let (text, count) = replace_references(
+            "SELECT FROM Order; Orders.total(); Billing.Order; \"/types/Order/insert\"",
+            "Order",
+            "PurchaseOrder",
+        );
+        assert_eq!(
+            text,
+            "SELECT FROM PurchaseOrder; Orders.total(); Billing.Order; \"/types/PurchaseOrder/insert\""
+        );
+        assert_eq!(count, 2);
+        assert_eq!(
+            replace_references("Orders.total(x)", "Orders.total", "Orders.sum"),
+            ("Orders.sum(x)".to_string(), 1)
+        );
+        // After an escaped newline in a JSON string
+        assert_eq!(
+            replace_references(r#""RULE A\nOrders.total()""#, "Orders.total", "Orders.sum"),
+            (r#""RULE A\nOrders.sum()""#.to_string(), 1)
+        );
+    }
+
+    #[test]
+    fn test_rename_procedure() {
+        let dir = TempDir::new().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write(
+            "procedures/Orders.total.json",
+            r#"{"name": "Orders.total", "ruleText": {"$vail": "Orders.total.vail"}}"#,
+        );
+        write("procedures/Orders.total.vail", "PROCEDURE Orders.total()\n");
+        write(
+            "rules/OnOrder.json",
+            r#"{"name": "OnOrder", "ruleText": "RULE OnOrder\nOrders.total()"}"#,
+        );
+        write("types/Order.json", r#"{"name": "Order"}"#);
+
+        let args = RenameArgs {
+            resource_type: "procedures".to_string(),
+            old_name: "Orders.total".to_string(),
+            new_name: "Orders.sum".to_string(),
+            directory: Some(dir.path().to_path_buf()),
+            dry_run: false,
+        };
+        let result = run(&args, OutputFormat::Json).unwrap();
+        assert_eq!(result.renamed.len(), 2);
+        assert_eq!(result.touched.len(), 3);
+
+        let read = |path: &str| fs::read_to_string(dir.path().join(path)).unwrap();
+        assert_eq!(
+            read("procedures/Orders.sum.json"),
+            r#"{"name": "Orders.sum", "ruleText": {"$vail": "Orders.sum.vail"}}"#
+        );
+        assert_eq!(
+            read("procedures/Orders.sum.vail"),
+            "PROCEDURE Orders.sum()\n"
+        );
+        assert!(read("rules/OnOrder.json").contains("Orders.sum()"));
+        assert!(!dir.path().join("procedures/Orders.total.json").exists());
+
+        // The new name is taken now
+        let args = RenameArgs {
+            old_name: "Orders.sum".to_string(),
+            new_name: "Orders.sum".to_string(),
+            ..args
+        };
+        assert!(run(&args, OutputFormat::Json).is_err());
+    }
+}
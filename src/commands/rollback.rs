@@ -0,0 +1,216 @@
+//! Rollback command implementation
+//!
+//! Without `--to`, lists the snapshots taken before imports into protected
+//! profiles. With `--to`, imports the metadata of a snapshot back into the
+//! profile it was taken of (or `--profile`). Resources created after the
+//! snapshot are not deleted. See [`crate::snapshot`].
+
+use crate::cache::ExportCache;
+use crate::cli::{OutputFormat, RollbackArgs};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::lock::OperationLock;
+use crate::profile::ProfileManager;
+use crate::progress::TransferProgress;
+use crate::prompt;
+use crate::report;
+use crate::snapshot::{self, Snapshot, SnapshotStore};
+use crate::theme::style;
+use crate::underlying::{CliOptions, UnderlyingCli};
+use serde::Serialize;
+
+/// Result of rollback without --to
+#[derive(Debug, Serialize)]
+pub struct SnapshotListResult {
+    pub success: bool,
+    pub snapshots: Vec<Snapshot>,
+}
+
+/// Result of rollback --to
+#[derive(Debug, Serialize)]
+pub struct RollbackResult {
+    pub success: bool,
+    pub profile: String,
+    pub snapshot: Snapshot,
+    /// Snapshot of the state the rollback replaced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run the rollback command
+pub async fn run(
+    args: &RollbackArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    match args.to {
+        Some(ref id) => rollback(id, args, config, profile_name, output_format).await,
+        None => list(config, profile_name, output_format),
+    }
+}
+
+fn list(config: &Config, profile_name: Option<&str>, output_format: OutputFormat) -> Result<bool> {
+    let result = SnapshotListResult {
+        success: true,
+        snapshots: SnapshotStore::new(config)
+            .list()
+            .into_iter()
+            .filter(|s| profile_name.is_none_or(|p| s.profile == p))
+            .collect(),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+        return Ok(true);
+    }
+
+    println!();
+    println!("{}", style("Snapshots").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    if result.snapshots.is_empty() {
+        println!("  No snapshots");
+    }
+    for s in &result.snapshots {
+        println!(
+            "  {}  {} ({} files) before {} by {} {}",
+            style(&s.id).bold(),
+            style(&s.profile).green(),
+            s.files,
+            s.operation,
+            s.created_by,
+            style(s.created_at.format("%Y-%m-%d %H:%M").to_string()).dim()
+        );
+    }
+    println!();
+    Ok(true)
+}
+
+async fn rollback(
+    id: &str,
+    args: &RollbackArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let store = SnapshotStore::new(config);
+    let snapshot = store.find(id)?;
+    let interactive = !output_format.is_machine_readable();
+
+    // Back into the profile the snapshot was taken of, unless --profile says otherwise
+    let manager = ProfileManager::new()?;
+    let profile_name = profile_name.unwrap_or(&snapshot.profile).to_string();
+    let profile = manager.get_resolved(&profile_name)?;
+
+    if interactive {
+        println!();
+        println!(
+            "{} {} of {} ({} files), taken before {} by {} at {}",
+            style("Snapshot").bold(),
+            style(&snapshot.id).cyan(),
+            style(&snapshot.profile).green(),
+            snapshot.files,
+            snapshot.operation,
+            snapshot.created_by,
+            snapshot.created_at.format("%Y-%m-%d %H:%M:%S")
+        );
+        println!(
+            "  {}",
+            style("Resources created after the snapshot are kept").dim()
+        );
+    }
+
+    if !args.yes {
+        if !interactive {
+            return Err(VqxError::DestructiveOperationNotConfirmed {
+                operation: "rollback".to_string(),
+            });
+        }
+        let confirmed = prompt::confirm(
+            format!(
+                "Import snapshot {} into {} ({})?",
+                snapshot.id, profile.url, profile_name
+            ),
+            "Rollback",
+            "--yes",
+        )?;
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Rollback".to_string(),
+            });
+        }
+    }
+
+    let _lock = OperationLock::acquire(
+        "rollback",
+        &profile_name,
+        &profile.url,
+        profile.namespace.as_deref(),
+        &args.lock,
+    )
+    .await?;
+
+    let cli = UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for("import"))
+        .with_retry_policy(config.retry_policy());
+
+    // The rollback itself can be undone, too
+    let replaced = snapshot::take(
+        config,
+        &cli,
+        &profile_name,
+        &profile,
+        "rollback",
+        interactive,
+    )
+    .await?
+    .map(|s| s.id);
+
+    ExportCache::new(config).invalidate(&profile_name, profile.namespace.as_deref());
+    let mut progress = TransferProgress::new("Importing snapshot...", interactive);
+    let import_args = UnderlyingCli::import_args(
+        Some("metadata"),
+        Some(&store.metadata_dir(&snapshot.id)),
+        Some(config.default_chunk_size),
+        None,
+        None,
+        None,
+    );
+    let import_result = progress
+        .run(
+            &cli,
+            &CliOptions::from_profile(&profile),
+            "import",
+            import_args,
+            config.stall_policy(),
+        )
+        .await?;
+    progress.finish_and_clear();
+
+    let result = RollbackResult {
+        success: import_result.success(),
+        profile: profile_name,
+        snapshot,
+        replaced,
+        error: (!import_result.success()).then(|| import_result.stderr.trim().to_string()),
+    };
+
+    if !interactive {
+        println!("{}", report::render(&result, output_format)?);
+    } else if let Some(ref error) = result.error {
+        println!("{} Rollback failed", style("✗").red().bold());
+        eprintln!("{}", style(error).red());
+    } else {
+        println!(
+            "{} Rolled {} back to {}",
+            style("✓").green().bold(),
+            style(&result.profile).green(),
+            result.snapshot.id
+        );
+        println!();
+    }
+
+    Ok(result.success)
+}
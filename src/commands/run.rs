@@ -2,16 +2,37 @@
 //!
 //! Provides commands to run tests, test suites, and procedures on Vantiq.
 //! Based on CLI Reference Guide "Run" section.
+//!
+//! Test suite output is parsed into per-test results (see
+//! [`crate::testreport`]) for a summary table and optional JUnit XML. The
+//! exit code tells test failures ([`EXIT_TEST_FAILURES`]) apart from suites
+//! that could not be run ([`EXIT_EXECUTION_ERROR`]).
+//!
+//! `run testsuites` runs the suites of a suites file in parallel (see
+//! [`crate::suites`]) and aggregates their results.
 
-use crate::cli::{OutputFormat, RunCommands, RunProcedureArgs, RunTestArgs, RunTestSuiteArgs};
+use crate::cli::{
+    OutputFormat, RunCommands, RunProcedureArgs, RunTestArgs, RunTestSuiteArgs, RunTestSuitesArgs,
+};
 use crate::config::Config;
-use crate::error::Result;
+use crate::error::{Result, VqxError};
 use crate::profile::ProfileManager;
+use crate::report;
+use crate::suites::{self, Runner, SuiteRun, SuiteStatus, SuitesFile, SuitesSummary};
+use crate::testreport::{self, JunitSuite, TestCase, TestStatus, TestSummary};
+use crate::theme::style;
 use crate::underlying::{CliOptions, UnderlyingCli};
-use console::style;
 use serde::Serialize;
+use serde_json::Value;
+use std::time::Instant;
 use tracing::info;
 
+/// Exit code when the command ran but tests failed
+pub const EXIT_TEST_FAILURES: i32 = 1;
+
+/// Exit code when a test suite could not be run
+pub const EXIT_EXECUTION_ERROR: i32 = 2;
+
 /// Result of a run operation
 #[derive(Debug, Serialize)]
 pub struct RunResult {
@@ -21,6 +42,26 @@ pub struct RunResult {
     pub output: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Per-test results of a test suite
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<Vec<TestCase>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<TestSummary>,
+    /// Suite results of `run testsuites`, sorted by suite and profile
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suites: Option<Vec<SuiteRun>>,
+}
+
+impl RunResult {
+    /// Process exit code for this result
+    pub fn exit_code(&self) -> i32 {
+        match self.summary {
+            _ if self.success => 0,
+            Some(summary) if summary.has_failures() => EXIT_TEST_FAILURES,
+            Some(_) => EXIT_EXECUTION_ERROR,
+            None => 1,
+        }
+    }
 }
 
 /// Run a test, test suite, or procedure
@@ -38,6 +79,9 @@ pub async fn run(
         RunCommands::TestSuite(args) => {
             run_testsuite(args, config, profile_name, output_format, verbose).await
         }
+        RunCommands::TestSuites(args) => {
+            run_testsuites(args, config, profile_name, output_format, verbose).await
+        }
         RunCommands::Procedure(args) => {
             run_procedure(args, config, profile_name, output_format, verbose).await
         }
@@ -55,8 +99,8 @@ async fn run_test(
     info!(test = %args.name, "Running test");
 
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("run"))
+        .with_retry_policy(config.retry_policy());
 
     let options = build_cli_options(profile_name)?;
 
@@ -80,6 +124,9 @@ async fn run_test(
         } else {
             Some(exec_result.stderr.clone())
         },
+        tests: None,
+        summary: None,
+        suites: None,
     };
 
     display_result(&result, output_format, verbose);
@@ -101,8 +148,8 @@ async fn run_testsuite(
     );
 
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("run"))
+        .with_retry_policy(config.retry_policy());
 
     let options = build_cli_options(profile_name)?;
 
@@ -117,26 +164,211 @@ async fn run_testsuite(
         println!();
     }
 
-    let exec_result = cli
+    let started = Instant::now();
+    let (output, error) = match cli
         .run_testsuite(&options, &args.name, args.start_from.as_deref())
-        .await?;
+        .await
+    {
+        Ok(exec_result) if exec_result.success() => (exec_result.stdout, None),
+        Ok(exec_result) => {
+            let error = if exec_result.stderr.trim().is_empty() {
+                format!("CLI exited with code {}", exec_result.code())
+            } else {
+                exec_result.stderr.trim().to_string()
+            };
+            (exec_result.stdout, Some(error))
+        }
+        // Spawn failures and timeouts are execution errors, not test failures
+        Err(e) => (String::new(), Some(e.to_string())),
+    };
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let tests = testreport::parse_output(&output);
+    let summary = TestSummary::of(&tests);
+
+    // A non-zero exit with failed tests is a test failure; without, the suite did not run
+    let execution_error = error.as_deref().filter(|_| !summary.has_failures());
+    if let Some(ref path) = args.report {
+        let xml = testreport::to_junit_xml(&[JunitSuite {
+            name: &args.name,
+            tests: &tests,
+            error: execution_error,
+            elapsed_ms,
+        }]);
+        std::fs::write(path, xml).map_err(|_| VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        })?;
+    }
 
     let result = RunResult {
-        success: exec_result.success(),
+        success: error.is_none() && !summary.has_failures(),
         command_type: "testsuite".to_string(),
         name: args.name.clone(),
-        output: exec_result.stdout.clone(),
-        error: if exec_result.success() {
-            None
-        } else {
-            Some(exec_result.stderr.clone())
-        },
+        output,
+        error,
+        tests: Some(tests),
+        summary: Some(summary),
+        suites: None,
     };
 
     display_result(&result, output_format, verbose);
+    if !output_format.is_machine_readable() {
+        if let Some(ref path) = args.report {
+            println!("  JUnit report: {}", path.display());
+        }
+    }
     Ok(result)
 }
 
+/// Run the test suites of a suites file
+async fn run_testsuites(
+    args: &RunTestSuitesArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+    verbose: bool,
+) -> Result<RunResult> {
+    let file = SuitesFile::load(&args.file)?;
+    let concurrency = args
+        .concurrency
+        .or(file.concurrency)
+        .unwrap_or(suites::DEFAULT_CONCURRENCY);
+    let jobs = file.jobs(profile_name, config.timeout_for("run"));
+    info!(
+        file = %args.file.display(),
+        jobs = jobs.len(),
+        concurrency,
+        "Running test suites"
+    );
+
+    // Resolve connections up front so a bad profile fails before any suite runs
+    let jobs = jobs
+        .into_iter()
+        .map(|job| {
+            let options = build_cli_options(job.profile.as_deref())?;
+            Ok((job, options))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let text = !output_format.is_machine_readable();
+    if text {
+        println!();
+        println!("{}", style("Running Test Suites").bold().cyan());
+        println!("{}", style("─".repeat(40)).dim());
+        println!("File:        {}", args.file.display());
+        println!("Suites:      {}", jobs.len());
+        println!("Concurrency: {}", concurrency);
+        println!();
+    }
+
+    let runner = Runner {
+        cli_path: config.cli_path.clone(),
+        retry: config.retry_policy(),
+    };
+    let runs = runner
+        .run_all(jobs, concurrency, |run| {
+            if text {
+                display_suite_run(run);
+            }
+        })
+        .await;
+
+    if let Some(ref path) = args.report {
+        let labels: Vec<String> = runs.iter().map(SuiteRun::label).collect();
+        let junit: Vec<JunitSuite> = runs
+            .iter()
+            .zip(&labels)
+            .map(|(run, label)| JunitSuite {
+                name: label,
+                tests: &run.tests,
+                error: run.error.as_deref(),
+                elapsed_ms: run.duration_ms,
+            })
+            .collect();
+        std::fs::write(path, testreport::to_junit_xml(&junit)).map_err(|_| {
+            VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            }
+        })?;
+    }
+
+    let summary = SuitesSummary::of(&runs);
+    let result = RunResult {
+        success: runs.iter().all(SuiteRun::passed),
+        command_type: "testsuites".to_string(),
+        name: args.file.display().to_string(),
+        output: String::new(),
+        error: None,
+        tests: None,
+        summary: Some(summary.tests),
+        suites: Some(runs),
+    };
+
+    display_result(&result, output_format, verbose);
+    if text {
+        display_suites_summary(&summary);
+        if let Some(ref path) = args.report {
+            println!("  JUnit report: {}", path.display());
+        }
+    }
+    Ok(result)
+}
+
+/// Print one line for a finished suite
+pub fn display_suite_run(run: &SuiteRun) {
+    let mark = match run.status {
+        SuiteStatus::Passed => style("✓").green(),
+        SuiteStatus::Flaky => style("⚠").yellow(),
+        SuiteStatus::Failed | SuiteStatus::Error => style("✗").red(),
+    };
+    let mut details = vec![
+        format!("{} tests", run.summary.total()),
+        format!("{:.1}s", run.duration_ms as f64 / 1000.0),
+    ];
+    if run.attempts > 1 {
+        details.push(format!("{} attempts", run.attempts));
+    }
+    println!(
+        "{} {} {} ({})",
+        mark,
+        run.label(),
+        run.status,
+        details.join(", ")
+    );
+    if !run.flaky_tests.is_empty() {
+        println!("    flaky: {}", run.flaky_tests.join(", "));
+    }
+    for test in run
+        .tests
+        .iter()
+        .filter(|t| matches!(t.status, TestStatus::Failed | TestStatus::Error))
+    {
+        println!(
+            "    {} {}",
+            style(&test.name).red(),
+            test.message.as_deref().unwrap_or_default()
+        );
+    }
+    if let Some(ref error) = run.error {
+        println!("    {}", style(error).red());
+    }
+}
+
+/// Print suite and test totals of `run testsuites`
+fn display_suites_summary(summary: &SuitesSummary) {
+    println!(
+        "  Suites: {} passed, {} flaky, {} failed, {} errors",
+        style(summary.passed).green(),
+        style(summary.flaky).yellow(),
+        style(summary.failed).red(),
+        style(summary.errors).red()
+    );
+    println!(
+        "  Tests:  {} passed, {} failed, {} errors, {} skipped",
+        summary.tests.passed, summary.tests.failed, summary.tests.errors, summary.tests.skipped
+    );
+}
+
 /// Run a procedure
 async fn run_procedure(
     args: &RunProcedureArgs,
@@ -152,23 +384,26 @@ async fn run_procedure(
     );
 
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("run"))
+        .with_retry_policy(config.retry_policy());
 
     let options = build_cli_options(profile_name)?;
 
-    // Parse parameters from "name:value" format
-    let params: Vec<(&str, &str)> = args
-        .params
+    let file_params = match args.params_file {
+        Some(ref path) => {
+            let content = std::fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+                path: path.display().to_string(),
+            })?;
+            serde_json::from_str(&content).map_err(|e| {
+                VqxError::Other(format!("Invalid params file {}: {}", path.display(), e))
+            })?
+        }
+        None => Value::Null,
+    };
+    let owned_params = procedure_params(&file_params, &args.params)?;
+    let params: Vec<(&str, &str)> = owned_params
         .iter()
-        .filter_map(|p| {
-            let parts: Vec<&str> = p.splitn(2, ':').collect();
-            if parts.len() == 2 {
-                Some((parts[0], parts[1]))
-            } else {
-                None
-            }
-        })
+        .map(|(name, value)| (name.as_str(), value.as_str()))
         .collect();
 
     if verbose {
@@ -197,12 +432,67 @@ async fn run_procedure(
         } else {
             Some(exec_result.stderr.clone())
         },
+        tests: None,
+        summary: None,
+        suites: None,
     };
 
     display_result(&result, output_format, verbose);
     Ok(result)
 }
 
+/// Encode a typed value for the CLI: strings as-is, everything else as compact JSON
+fn encode_param(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Merge parameters from a params file and the command line
+///
+/// `file` is a JSON object of parameter names to values. Arguments are
+/// `name:value` (a string, passed as-is) or `name:=<json>` (a typed literal
+/// such as `count:=5`, `flag:=true` or `obj:={"a":1}`); they override file
+/// parameters of the same name.
+fn procedure_params(file: &Value, args: &[String]) -> Result<Vec<(String, String)>> {
+    let mut params: Vec<(String, String)> = match file {
+        Value::Null => Vec::new(),
+        Value::Object(map) => map
+            .iter()
+            .map(|(name, value)| (name.clone(), encode_param(value)))
+            .collect(),
+        _ => {
+            return Err(VqxError::Other(
+                "Params file must contain a JSON object".to_string(),
+            ))
+        }
+    };
+
+    for arg in args {
+        let (name, value) = if let Some((name, literal)) = arg.split_once(":=") {
+            let value: Value = serde_json::from_str(literal).map_err(|e| {
+                VqxError::Other(format!("Invalid typed value for '{}': {}", name, e))
+            })?;
+            (name, encode_param(&value))
+        } else if let Some((name, value)) = arg.split_once(':') {
+            (name, value.to_string())
+        } else {
+            return Err(VqxError::Other(format!(
+                "Invalid parameter '{}'; expected name:value or name:=<json>",
+                arg
+            )));
+        };
+
+        match params.iter_mut().find(|(n, _)| n == name) {
+            Some(existing) => existing.1 = value,
+            None => params.push((name.to_string(), value)),
+        }
+    }
+
+    Ok(params)
+}
+
 /// Build CLI options from profile
 fn build_cli_options(profile_name: Option<&str>) -> Result<CliOptions> {
     if let Some(name) = profile_name {
@@ -217,8 +507,11 @@ fn build_cli_options(profile_name: Option<&str>) -> Result<CliOptions> {
 /// Display the run result
 fn display_result(result: &RunResult, output_format: OutputFormat, verbose: bool) {
     match output_format {
-        OutputFormat::Json => {
-            if let Ok(json) = serde_json::to_string_pretty(result) {
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => {
+            if let Ok(json) = report::render(result, output_format) {
                 println!("{}", json);
             }
         }
@@ -228,6 +521,10 @@ fn display_result(result: &RunResult, output_format: OutputFormat, verbose: bool
                 print!("{}", result.output);
             }
 
+            if let Some(ref tests) = result.tests {
+                display_tests(tests);
+            }
+
             if verbose {
                 println!();
                 println!("{}", style("─".repeat(40)).dim());
@@ -258,13 +555,132 @@ fn display_result(result: &RunResult, output_format: OutputFormat, verbose: bool
     }
 }
 
+/// Display per-test results as a table with a summary line
+fn display_tests(tests: &[TestCase]) {
+    if tests.is_empty() {
+        return;
+    }
+
+    let name_width = tests
+        .iter()
+        .map(|t| t.name.chars().count())
+        .chain(std::iter::once("Test".len()))
+        .max()
+        .unwrap_or(0);
+
+    println!();
+    println!(
+        "{}",
+        style(format!(
+            "{:<name_width$}  {:<7}  {:>8}  Message",
+            "Test", "Status", "Time"
+        ))
+        .bold()
+    );
+    println!("{}", style("─".repeat(name_width + 30)).dim());
+    for test in tests {
+        let status = format!("{:<7}", test.status.to_string());
+        let status = match test.status {
+            TestStatus::Passed => style(status).green(),
+            TestStatus::Failed | TestStatus::Error => style(status).red(),
+            TestStatus::Skipped => style(status).yellow(),
+        };
+        let time = test
+            .duration_ms
+            .map(|ms| format!("{:.3}s", ms as f64 / 1000.0))
+            .unwrap_or_default();
+        println!(
+            "{}",
+            format!(
+                "{:<name_width$}  {}  {:>8}  {}",
+                test.name,
+                status,
+                time,
+                test.message.as_deref().unwrap_or_default()
+            )
+            .trim_end()
+        );
+    }
+
+    let summary = TestSummary::of(tests);
+    println!();
+    println!(
+        "{} tests: {} passed, {} failed, {} errors, {} skipped",
+        summary.total(),
+        style(summary.passed).green(),
+        style(summary.failed).red(),
+        style(summary.errors).red(),
+        style(summary.skipped).yellow()
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    fn suite_result(success: bool, summary: TestSummary) -> RunResult {
+        RunResult {
+            success,
+            command_type: "testsuite".to_string(),
+            name: "OrderSuite".to_string(),
+            output: String::new(),
+            error: None,
+            tests: Some(Vec::new()),
+            summary: Some(summary),
+            suites: None,
+        }
+    }
+
+    #[test]
+    fn test_procedure_params() {
+        let file = json!({"count": 1, "label": "five", "tags": ["a", "b"]});
+        let args = [
+            "count:=5".to_string(),
+            "flag:=true".to_string(),
+            r#"obj:={"a": 1}"#.to_string(),
+            "url:http://example.com:8080".to_string(),
+        ];
+        let params = procedure_params(&file, &args).unwrap();
+        assert_eq!(
+            params,
+            vec![
+                ("count".to_string(), "5".to_string()),
+                ("label".to_string(), "five".to_string()),
+                ("tags".to_string(), r#"["a","b"]"#.to_string()),
+                ("flag".to_string(), "true".to_string()),
+                ("obj".to_string(), r#"{"a":1}"#.to_string()),
+                ("url".to_string(), "http://example.com:8080".to_string()),
+            ]
+        );
+
+        assert!(procedure_params(&Value::Null, &["count:=five".to_string()]).is_err());
+        assert!(procedure_params(&Value::Null, &["noseparator".to_string()]).is_err());
+        assert!(procedure_params(&json!([1]), &[]).is_err());
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let passed = TestSummary {
+            passed: 2,
+            ..Default::default()
+        };
+        let failed = TestSummary {
+            passed: 1,
+            failed: 1,
+            ..Default::default()
+        };
+        assert_eq!(suite_result(true, passed).exit_code(), 0);
+        assert_eq!(suite_result(false, failed).exit_code(), EXIT_TEST_FAILURES);
+        assert_eq!(
+            suite_result(false, TestSummary::default()).exit_code(),
+            EXIT_EXECUTION_ERROR
+        );
+    }
 
     #[test]
     fn test_parse_params() {
-        let params = vec!["name:value".to_string(), "foo:bar".to_string()];
+        let params = ["name:value".to_string(), "foo:bar".to_string()];
         let parsed: Vec<(&str, &str)> = params
             .iter()
             .filter_map(|p| {
@@ -284,7 +700,7 @@ mod tests {
 
     #[test]
     fn test_parse_params_with_colon_in_value() {
-        let params = vec!["url:http://example.com:8080".to_string()];
+        let params = ["url:http://example.com:8080".to_string()];
         let parsed: Vec<(&str, &str)> = params
             .iter()
             .filter_map(|p| {
@@ -2,22 +2,45 @@
 //!
 //! Provides safe deletion of Vantiq resources with:
 //! - Dry-run mode to preview what would be deleted
-//! - Automatic backup before deletion
+//! - Automatic backup before deletion, or a trash entry (`--trash`) that
+//!   `vqx trash restore` can write back
 //! - Confirmation prompts
-//! - Limits to prevent accidental mass deletion
+//! - Limits to prevent accidental mass deletion, and `--limit` to delete
+//!   only the first N records matching a query
+//! - A preview of the matched records, and their IDs kept with the backup
+//! - Bulk deletes of the IDs listed in a file (`--from-file`), with bounded
+//!   concurrency and an outcome per ID
+//! - Dependency-aware cascades: resources referencing the target are found
+//!   in a metadata export and can be deleted first, each with its own backup
+//! - `--include`/`--exclude` patterns: records and dependents that are not
+//!   selected are never deleted
 
+use crate::backend::{self, Executor};
 use crate::cli::{OutputFormat, SafeDeleteArgs};
+use crate::commands::diff::RESOURCE_TYPES;
 use crate::config::Config;
+use crate::deps::{DependencyGraph, ResourceRef};
 use crate::error::{Result, VqxError};
-use crate::profile::ProfileManager;
-use crate::underlying::{CliOptions, UnderlyingCli};
+use crate::filter::ResourceFilter;
+use crate::lock::OperationLock;
+use crate::progress;
+use crate::prompt;
+use crate::report;
+use crate::theme::style;
+use crate::trash::{TrashEntry, TrashStore, TrashedDefinition};
+use crate::underlying::{CliOptions, ExecResult, UnderlyingCli};
 use chrono::Local;
-use console::style;
-use dialoguer::Confirm;
+use indicatif::{ProgressBar, ProgressStyle};
 use serde::Serialize;
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tracing::{info, warn};
 
 /// Default limit for deleteMatching to prevent accidental mass deletion
@@ -32,8 +55,29 @@ pub struct SafeDeleteResult {
     pub target: String,
     pub items_found: usize,
     pub items_deleted: usize,
+    /// Records matching the query before `--limit` was applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items_matched: Option<usize>,
+    /// Dependent resources deleted before the target, in order
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cascade: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub backup_path: Option<PathBuf>,
+    /// Trash entry holding the deleted items (`--trash`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_id: Option<String>,
+    /// Outcome per ID (`--from-file`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<ItemOutcome>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of deleting one ID of a `--from-file` list
+#[derive(Debug, Clone, Serialize)]
+pub struct ItemOutcome {
+    pub id: String,
+    pub success: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
@@ -46,21 +90,40 @@ pub async fn run(
     output_format: OutputFormat,
     verbose: bool,
 ) -> Result<SafeDeleteResult> {
+    let filter = ResourceFilter::from_args(&args.filter, &[], None)?;
+    if let Some(ref file) = args.from_file {
+        return run_bulk(args, &filter, file, config, profile_name, output_format).await;
+    }
+    let Some(target) = args.target.as_deref() else {
+        return Err(VqxError::Other(
+            "Give a resource ID or query, or --from-file".to_string(),
+        ));
+    };
+
     info!(
         resource = %args.resource,
-        target = %args.target,
+        target = %target,
         dry_run = args.dry_run,
         "Running safe-delete"
     );
 
-    let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
-
-    let options = build_cli_options(profile_name)?;
+    let profile = backend::resolve_profile(profile_name)?;
+    let options = backend::cli_options(profile.as_ref());
+    let cli = Executor::new(
+        UnderlyingCli::new(config.cli_path.clone())
+            .with_timeout(config.timeout_for("safe-delete"))
+            .with_retry_policy(config.retry_policy()),
+        profile.as_ref(),
+    )?;
 
     // Determine if this is a single delete or deleteMatching
-    let is_matching = args.target.starts_with('{');
+    let is_matching = target.starts_with('{');
+    if !is_matching && !filter.matches(&args.resource, target) {
+        return Err(VqxError::Other(format!(
+            "{}/{} is not selected by --include/--exclude",
+            args.resource, target
+        )));
+    }
 
     if verbose {
         println!();
@@ -72,7 +135,7 @@ pub async fn run(
             if is_matching {
                 style("(query)").dim().to_string()
             } else {
-                style(&args.target).cyan().to_string()
+                style(target).cyan().to_string()
             }
         );
         if args.dry_run {
@@ -81,19 +144,67 @@ pub async fn run(
         println!();
     }
 
-    // Step 1: Find what would be deleted
-    let items = find_items(&cli, &options, &args.resource, &args.target, is_matching).await?;
+    if args.limit.is_some() && !is_matching {
+        return Err(VqxError::Other(
+            "--limit only applies to queries (deleteMatching)".to_string(),
+        ));
+    }
+
+    // Step 1: Find what would be deleted. With --limit, the select stops one
+    // record past the limit to tell whether more matched; --include/--exclude
+    // pick records by name here, so they need every match
+    let bounded = args.limit.filter(|_| filter.is_empty());
+    let mut items = find_items(
+        &cli,
+        &options,
+        &args.resource,
+        target,
+        is_matching,
+        bounded.map(|limit| limit + 1),
+    )
+    .await?;
+    if is_matching && !filter.is_empty() {
+        items.retain(|item| record_name(item).is_some_and(|n| filter.matches(&args.resource, &n)));
+    }
+
+    // Keys of every matched record, for the count and the backup
+    let key = record_key(&items).unwrap_or("_id");
+    let matched_keys: Vec<Value> = match args.limit {
+        Some(limit) if bounded.is_some() && items.len() > limit => {
+            find_keys(&cli, &options, &args.resource, target, key).await?
+        }
+        _ => items
+            .iter()
+            .filter_map(|item| item.get(key).cloned())
+            .collect(),
+    };
+    let items_matched = args.limit.map(|_| matched_keys.len());
+    if let Some(limit) = args.limit {
+        items.truncate(limit);
+    }
     let items_count = items.len();
 
+    // With --limit or a filter, delete exactly the selected records instead
+    // of the query
+    let delete_query = if (args.limit.is_some() || !filter.is_empty()) && items_count > 0 {
+        Some(id_query(&items)?)
+    } else {
+        None
+    };
+
     if items_count == 0 {
         let result = SafeDeleteResult {
             success: true,
             dry_run: args.dry_run,
             resource_type: args.resource.clone(),
-            target: args.target.clone(),
+            target: target.to_string(),
             items_found: 0,
             items_deleted: 0,
+            items_matched: None,
+            cascade: vec![],
             backup_path: None,
+            trash_id: None,
+            items: vec![],
             error: None,
         };
         display_result(&result, output_format, verbose);
@@ -101,9 +212,9 @@ pub async fn run(
     }
 
     // Step 2: Check limits for deleteMatching
-    if is_matching && items_count > DEFAULT_DELETE_LIMIT && !args.force {
+    if is_matching && args.limit.is_none() && items_count > DEFAULT_DELETE_LIMIT && !args.force {
         let error_msg = format!(
-            "Found {} items to delete, which exceeds the limit of {}. Use --force to override.",
+            "Found {} items to delete, which exceeds the limit of {}. Use --force to override, or --limit to delete only the first N.",
             items_count, DEFAULT_DELETE_LIMIT
         );
         warn!("{}", error_msg);
@@ -111,16 +222,48 @@ pub async fn run(
             success: false,
             dry_run: args.dry_run,
             resource_type: args.resource.clone(),
-            target: args.target.clone(),
+            target: target.to_string(),
             items_found: items_count,
             items_deleted: 0,
+            items_matched: None,
+            cascade: vec![],
             backup_path: None,
+            trash_id: None,
+            items: vec![],
             error: Some(error_msg),
         });
     }
 
+    // Step 3: Find dependents (single deletes of metadata resources only)
+    let check_dependents =
+        !is_matching && !args.ignore_dependents && RESOURCE_TYPES.contains(&args.resource.as_str());
+    let (cascade, definitions) = if check_dependents {
+        if verbose {
+            println!("{} Checking dependents...", style("→").cyan());
+        }
+        find_dependents(cli.cli(), &options, config, &args.resource, target).await?
+    } else {
+        (vec![], DependencyGraph::default())
+    };
+    let cascade_names: Vec<String> = cascade.iter().map(|r| r.to_string()).collect();
+    let unselected: Vec<String> = cascade
+        .iter()
+        .filter(|r| !filter.matches(&r.resource_type, &r.name))
+        .map(|r| r.to_string())
+        .collect();
+    if args.cascade && !unselected.is_empty() {
+        return Err(VqxError::Other(format!(
+            "Dependents of {}/{} are not selected by --include/--exclude: {}. Nothing was deleted",
+            args.resource,
+            target,
+            unselected.join(", ")
+        )));
+    }
+
     // Display items to be deleted
-    if verbose || args.dry_run {
+    if is_matching && !output_format.is_machine_readable() {
+        display_sample(&items, items_matched, args.sample);
+    } else if verbose || args.dry_run || !cascade.is_empty() {
         println!(
             "{} Found {} item(s) to delete:",
             style("→").cyan(),
@@ -133,6 +276,22 @@ pub async fn run(
                 println!("  - {}", style(id).dim());
             }
         }
+        if !cascade.is_empty() {
+            println!();
+            println!(
+                "{} {} resource(s) depend on it. Cascade plan (in order):",
+                style("⚠").yellow(),
+                cascade.len()
+            );
+            for (i, resource) in cascade.iter().enumerate() {
+                println!("  {}. {}", i + 1, style(resource).yellow());
+            }
+            println!(
+                "  {}. {}",
+                cascade.len() + 1,
+                style(format!("{}/{}", args.resource, target)).red()
+            );
+        }
         println!();
     }
 
@@ -146,47 +305,141 @@ pub async fn run(
             success: true,
             dry_run: true,
             resource_type: args.resource.clone(),
-            target: args.target.clone(),
+            target: target.to_string(),
             items_found: items_count,
             items_deleted: 0,
+            items_matched,
+            cascade: cascade_names,
             backup_path: None,
+            trash_id: None,
+            items: vec![],
             error: None,
         });
     }
 
-    // Step 3: Confirmation
-    if !args.yes {
-        let prompt = format!(
-            "Are you sure you want to delete {} {}(s)?",
-            items_count, args.resource
-        );
-        let confirmed = Confirm::new()
-            .with_prompt(prompt)
-            .default(false)
-            .interact()
-            .map_err(|e| VqxError::Other(format!("Confirmation failed: {}", e)))?;
+    // Keep other vqx operations out of this namespace until the delete is done
+    let _lock = OperationLock::acquire(
+        "safe-delete",
+        profile_name.unwrap_or("default"),
+        options
+            .base_url
+            .as_deref()
+            .unwrap_or(profile_name.unwrap_or("default")),
+        options.namespace.as_deref(),
+        &args.lock,
+    )
+    .await?;
+
+    // Step 4: Confirmation
+    let cancelled = |error: String| SafeDeleteResult {
+        success: false,
+        dry_run: false,
+        resource_type: args.resource.clone(),
+        target: target.to_string(),
+        items_found: items_count,
+        items_deleted: 0,
+        items_matched,
+        cascade: vec![],
+        backup_path: None,
+        trash_id: None,
+        items: vec![],
+        error: Some(error),
+    };
+
+    if !cascade.is_empty() && !args.cascade {
+        // Never cascade without an explicit answer
+        if args.yes || output_format.is_machine_readable() {
+            let result = cancelled(format!(
+                "{} resource(s) depend on {}/{}: {}. Use --cascade to delete them too, \
+                 or --ignore-dependents to delete only the target.",
+                cascade.len(),
+                args.resource,
+                target,
+                cascade_names.join(", ")
+            ));
+            display_result(&result, output_format, verbose);
+            return Ok(result);
+        }
+
+        prompt::require_input("Deletion", "pass --cascade or --ignore-dependents")?;
+        let confirmed = prompt::confirm(
+            format!(
+                "Delete {} dependent resource(s) and then {}/{}? (No aborts)",
+                cascade.len(),
+                args.resource,
+                target
+            ),
+            "Deletion",
+            "--yes",
+        )?;
 
         if !confirmed {
-            println!("{} Operation cancelled.", style("✗").yellow());
-            return Ok(SafeDeleteResult {
-                success: false,
-                dry_run: false,
-                resource_type: args.resource.clone(),
-                target: args.target.clone(),
-                items_found: items_count,
-                items_deleted: 0,
-                backup_path: None,
-                error: Some("Operation cancelled by user".to_string()),
+            return Err(VqxError::Cancelled {
+                operation: "Deletion".to_string(),
+            });
+        }
+    } else if !args.yes {
+        let question = if cascade.is_empty() {
+            format!(
+                "Are you sure you want to delete {} {}(s)?",
+                items_count, args.resource
+            )
+        } else {
+            format!(
+                "Are you sure you want to delete {}/{} and {} dependent resource(s)?",
+                args.resource,
+                target,
+                cascade.len()
+            )
+        };
+        let confirmed = prompt::confirm(question, "Deletion", "--yes")?;
+
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Deletion".to_string(),
             });
         }
     }
 
-    // Step 4: Create backup
-    let backup_path = if !args.no_backup {
-        Some(create_backup(&args.resource, &items)?)
+    // Step 5: Move the items to the trash, or back them up
+    let trash = if args.trash {
+        let store = TrashStore::new(config);
+        Some(move_to_trash(
+            &store,
+            profile_name,
+            &args.resource,
+            target,
+            &cascade,
+            &definitions,
+            &items,
+        )?)
     } else {
         None
     };
+    let backup_path = if trash.is_some() || args.no_backup {
+        None
+    } else if cascade.is_empty() {
+        let path = create_backup(&args.resource, &items)?;
+        if is_matching {
+            write_matched_ids(
+                &path,
+                &args.resource,
+                target,
+                args.limit,
+                key,
+                &matched_keys,
+                &items,
+            )?;
+        }
+        Some(path)
+    } else {
+        Some(create_cascade_backup(
+            &definitions,
+            &cascade,
+            &args.resource,
+            &items,
+        )?)
+    };
 
     if let Some(ref path) = backup_path {
         println!(
@@ -195,22 +448,67 @@ pub async fn run(
             style(path.display()).dim()
         );
     }
+    if let Some(ref entry) = trash {
+        println!(
+            "{} Moved to trash: {} (restore with `vqx trash restore {}` until {})",
+            style("✓").green(),
+            style(&entry.id).bold(),
+            entry.id,
+            entry.expires_at.format("%Y-%m-%d")
+        );
+    }
+    let trash_id = trash.map(|entry| entry.id);
 
-    // Step 5: Execute deletion
-    let deleted_count = if is_matching {
-        delete_matching(&cli, &options, &args.resource, &args.target).await?
+    // Step 6: Execute deletion, dependents first
+    let mut deleted_count = 0;
+    for resource in &cascade {
+        if let Err(e) = delete_single(&cli, &options, &resource.resource_type, &resource.name).await
+        {
+            let result = SafeDeleteResult {
+                success: false,
+                dry_run: false,
+                resource_type: args.resource.clone(),
+                target: target.to_string(),
+                items_found: items_count,
+                items_deleted: deleted_count,
+                items_matched,
+                cascade: cascade_names,
+                backup_path,
+                trash_id: trash_id.clone(),
+                items: vec![],
+                error: Some(format!(
+                    "Failed to delete {}: {}. The target and remaining dependents were kept.",
+                    resource, e
+                )),
+            };
+            display_result(&result, output_format, verbose);
+            return Ok(result);
+        }
+        if verbose {
+            println!("  {} Deleted {}", style("✓").green(), resource);
+        }
+        deleted_count += 1;
+    }
+
+    deleted_count += if is_matching {
+        let query = delete_query.as_deref().unwrap_or(target);
+        delete_matching(&cli, &options, &args.resource, query).await?
     } else {
-        delete_single(&cli, &options, &args.resource, &args.target).await?
+        delete_single(&cli, &options, &args.resource, target).await?
     };
 
     let result = SafeDeleteResult {
         success: true,
         dry_run: false,
         resource_type: args.resource.clone(),
-        target: args.target.clone(),
+        target: target.to_string(),
         items_found: items_count,
         items_deleted: deleted_count,
+        items_matched,
+        cascade: cascade_names,
         backup_path,
+        trash_id,
+        items: vec![],
         error: None,
     };
 
@@ -218,25 +516,431 @@ pub async fn run(
     Ok(result)
 }
 
+/// Delete the IDs listed in `--from-file`
+///
+/// The items are looked up first (for the backup or trash entry; IDs that
+/// cannot be found are reported as failed), then deleted with at most
+/// `--concurrency` deletions at once. Every ID gets an outcome.
+async fn run_bulk(
+    args: &SafeDeleteArgs,
+    filter: &ResourceFilter,
+    file: &Path,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<SafeDeleteResult> {
+    let mut ids = read_id_file(file)?;
+    ids.retain(|id| filter.matches(&args.resource, id));
+    info!(
+        resource = %args.resource,
+        file = %file.display(),
+        ids = ids.len(),
+        dry_run = args.dry_run,
+        "Running safe-delete from file"
+    );
+
+    let profile = backend::resolve_profile(profile_name)?;
+    let options = backend::cli_options(profile.as_ref());
+    let cli = Arc::new(Executor::new(
+        UnderlyingCli::new(config.cli_path.clone())
+            .with_timeout(config.timeout_for("safe-delete"))
+            .with_retry_policy(config.retry_policy()),
+        profile.as_ref(),
+    )?);
+
+    let target = file.display().to_string();
+    let mut result = SafeDeleteResult {
+        success: true,
+        dry_run: args.dry_run,
+        resource_type: args.resource.clone(),
+        target: target.clone(),
+        items_found: ids.len(),
+        items_deleted: 0,
+        items_matched: None,
+        cascade: vec![],
+        backup_path: None,
+        trash_id: None,
+        items: vec![],
+        error: None,
+    };
+    let interactive = !output_format.is_machine_readable();
+
+    if ids.len() > DEFAULT_DELETE_LIMIT && !args.force {
+        let error_msg = format!(
+            "{} lists {} items to delete, which exceeds the limit of {}. Use --force to override.",
+            target,
+            ids.len(),
+            DEFAULT_DELETE_LIMIT
+        );
+        warn!("{}", error_msg);
+        result.success = false;
+        result.error = Some(error_msg);
+        display_result(&result, output_format, false);
+        return Ok(result);
+    }
+
+    if interactive && !ids.is_empty() {
+        println!(
+            "{} {} {}(s) listed in {}:",
+            style("→").cyan(),
+            ids.len(),
+            args.resource,
+            target
+        );
+        for id in ids.iter().take(args.sample) {
+            println!("  - {}", style(id).yellow());
+        }
+        if ids.len() > args.sample {
+            println!(
+                "  {}",
+                style(format!("... and {} more", ids.len() - args.sample)).dim()
+            );
+        }
+        println!();
+    }
+
+    if args.dry_run || ids.is_empty() {
+        display_result(&result, output_format, false);
+        return Ok(result);
+    }
+
+    let _lock = OperationLock::acquire(
+        "safe-delete",
+        profile_name.unwrap_or("default"),
+        options
+            .base_url
+            .as_deref()
+            .unwrap_or(profile_name.unwrap_or("default")),
+        options.namespace.as_deref(),
+        &args.lock,
+    )
+    .await?;
+
+    if !args.yes {
+        let confirmed = prompt::confirm(
+            format!(
+                "Are you sure you want to delete {} {}(s)?",
+                ids.len(),
+                args.resource
+            ),
+            "Deletion",
+            "--yes",
+        )?;
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Deletion".to_string(),
+            });
+        }
+    }
+
+    // Look the items up for the backup; missing IDs are not deleted
+    let mut outcomes: Vec<ItemOutcome> = Vec::new();
+    let mut to_delete = ids.clone();
+    if !args.no_backup {
+        let progress = bulk_progress(interactive, ids.len(), "looking up");
+        let found = for_each_id(&ids, args.concurrency, progress.as_ref(), |id| {
+            let (cli, options, resource) =
+                (Arc::clone(&cli), options.clone(), args.resource.clone());
+            async move {
+                match find_items(&cli, &options, &resource, &id, false, None).await {
+                    Ok(items) if items.is_empty() => Err("not found".to_string()),
+                    Ok(mut items) => Ok(items.remove(0)),
+                    Err(e) => Err(e.to_string().trim().to_string()),
+                }
+            }
+        })
+        .await;
+        if let Some(pb) = progress {
+            pb.finish_and_clear();
+        }
+
+        let mut items = Vec::new();
+        to_delete.clear();
+        for (id, lookup) in ids.iter().zip(found) {
+            match lookup {
+                Ok(item) => {
+                    items.push(item);
+                    to_delete.push(id.clone());
+                }
+                Err(error) => outcomes.push(ItemOutcome {
+                    id: id.clone(),
+                    success: false,
+                    error: Some(error),
+                }),
+            }
+        }
+
+        if args.trash {
+            let store = TrashStore::new(config);
+            let entry = move_to_trash(
+                &store,
+                profile_name,
+                &args.resource,
+                &target,
+                &[],
+                &DependencyGraph::default(),
+                &items,
+            )?;
+            if interactive {
+                println!(
+                    "{} Moved to trash: {} (restore with `vqx trash restore {}` until {})",
+                    style("✓").green(),
+                    style(&entry.id).bold(),
+                    entry.id,
+                    entry.expires_at.format("%Y-%m-%d")
+                );
+            }
+            result.trash_id = Some(entry.id);
+        } else if !items.is_empty() {
+            let path = create_backup(&args.resource, &items)?;
+            if interactive {
+                println!(
+                    "{} Backup saved to: {}",
+                    style("✓").green(),
+                    style(path.display()).dim()
+                );
+            }
+            result.backup_path = Some(path);
+        }
+    }
+
+    let progress = bulk_progress(interactive, to_delete.len(), "deleting");
+    let deleted = for_each_id(&to_delete, args.concurrency, progress.as_ref(), |id| {
+        let (cli, options, resource) = (Arc::clone(&cli), options.clone(), args.resource.clone());
+        async move {
+            delete_single(&cli, &options, &resource, &id)
+                .await
+                .map_err(|e| e.to_string().trim().to_string())
+        }
+    })
+    .await;
+    if let Some(pb) = progress {
+        pb.finish_and_clear();
+    }
+    for (id, deletion) in to_delete.iter().zip(deleted) {
+        outcomes.push(ItemOutcome {
+            id: id.clone(),
+            success: deletion.is_ok(),
+            error: deletion.err(),
+        });
+    }
+
+    // Report in file order
+    let order: HashMap<&str, usize> = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| (id.as_str(), i))
+        .collect();
+    outcomes.sort_by_key(|o| order.get(o.id.as_str()).copied());
+
+    let failed = outcomes.iter().filter(|o| !o.success).count();
+    result.items_deleted = outcomes.len() - failed;
+    result.success = failed == 0;
+    if failed > 0 {
+        result.error = Some(format!("{} of {} deletion(s) failed", failed, ids.len()));
+    }
+    result.items = outcomes;
+
+    display_result(&result, output_format, false);
+    Ok(result)
+}
+
+/// IDs from a `--from-file` file, without duplicates
+fn read_id_file(path: &Path) -> Result<Vec<String>> {
+    let content = fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+        path: path.display().to_string(),
+    })?;
+    parse_ids(&content)
+}
+
+/// A JSON array (of IDs, or of records with `_id` or `name`), or one ID per
+/// line with blank lines and `#` comments skipped
+fn parse_ids(content: &str) -> Result<Vec<String>> {
+    let ids: Vec<String> = if content.trim_start().starts_with('[') {
+        let values: Vec<Value> =
+            serde_json::from_str(content).map_err(|e| VqxError::InvalidJson {
+                message: format!("--from-file: {}", e),
+            })?;
+        values
+            .iter()
+            .map(|v| match v {
+                Value::String(s) => Ok(s.clone()),
+                Value::Object(_) => ["_id", "name"]
+                    .iter()
+                    .find_map(|k| v.get(k).and_then(|id| id.as_str()))
+                    .map(str::to_string)
+                    .ok_or_else(|| VqxError::InvalidJson {
+                        message: format!("--from-file: record without _id or name: {}", v),
+                    }),
+                other => Ok(other.to_string()),
+            })
+            .collect::<Result<_>>()?
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(str::to_string)
+            .collect()
+    };
+
+    let mut seen = HashSet::new();
+    Ok(ids
+        .into_iter()
+        .filter(|id| seen.insert(id.clone()))
+        .collect())
+}
+
+/// Progress bar for a bulk phase, when output is for a person
+fn bulk_progress(interactive: bool, len: usize, phase: &str) -> Option<ProgressBar> {
+    (interactive && progress::enabled()).then(|| {
+        let pb = ProgressBar::new(len as u64);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} ({msg})")
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        pb.set_message(phase.to_string());
+        pb
+    })
+}
+
+/// Run `op` for each ID with at most `concurrency` at once
+///
+/// Results are in ID order; a task that panicked counts as failed.
+async fn for_each_id<T, F, Fut>(
+    ids: &[String],
+    concurrency: usize,
+    progress: Option<&ProgressBar>,
+    op: F,
+) -> Vec<std::result::Result<T, String>>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = std::result::Result<T, String>> + Send + 'static,
+    T: Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+    let mut set = JoinSet::new();
+    for (i, id) in ids.iter().enumerate() {
+        let semaphore = Arc::clone(&semaphore);
+        let task = op(id.clone());
+        set.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (i, task.await)
+        });
+    }
+
+    let mut results: Vec<Option<std::result::Result<T, String>>> =
+        (0..ids.len()).map(|_| None).collect();
+    while let Some(joined) = set.join_next().await {
+        if let Ok((i, result)) = joined {
+            results[i] = Some(result);
+        }
+        if let Some(pb) = progress {
+            pb.inc(1);
+        }
+    }
+    results
+        .into_iter()
+        .map(|r| r.unwrap_or_else(|| Err("task failed".to_string())))
+        .collect()
+}
+
+/// Find resources depending on the target, in deletion order
+///
+/// Exports metadata to a temp directory and builds the dependency graph from
+/// it. Also returns the graph so dependents can be backed up.
+async fn find_dependents(
+    cli: &UnderlyingCli,
+    options: &CliOptions,
+    config: &Config,
+    resource: &str,
+    target: &str,
+) -> Result<(Vec<ResourceRef>, DependencyGraph)> {
+    let temp_dir = TempDir::new()?;
+    let export_path = temp_dir.path();
+
+    let result = cli
+        .export(
+            options,
+            Some("metadata"),
+            Some(export_path),
+            Some(config.default_chunk_size),
+            None,
+            None,
+            None,
+            false,
+        )
+        .await?;
+
+    if !result.success() {
+        return Err(VqxError::CliExecutionFailed {
+            code: result.code(),
+            message: format!("Dependency check export failed: {}", result.stderr),
+        });
+    }
+
+    let graph = DependencyGraph::load(temp_dir.path())?;
+    let cascade = graph.cascade(&ResourceRef::new(resource, target));
+    Ok((cascade, graph))
+}
+
 /// Find items that match the target
+///
+/// A query selects at most `limit` records, in a single chunk.
 async fn find_items(
-    cli: &UnderlyingCli,
+    cli: &Executor,
     options: &CliOptions,
     resource: &str,
     target: &str,
     is_matching: bool,
+    limit: Option<usize>,
 ) -> Result<Vec<Value>> {
     let exec_result = if is_matching {
         // Use select with query
-        let mut args = vec![resource.to_string()];
-        args.push("-qual".to_string());
-        args.push(target.to_string());
-        cli.execute(options, "select", args).await?
+        let chunk_size = limit.map(|limit| u32::try_from(limit).unwrap_or(u32::MAX));
+        cli.select(
+            options,
+            resource,
+            None,
+            Some(target),
+            None,
+            chunk_size,
+            limit,
+        )
+        .await?
     } else {
         // Find single item
-        cli.execute(options, "find", [resource, target]).await?
+        cli.find(options, resource, target).await?
     };
+    let mut items = parse_items(exec_result)?;
+    // The chunk read last may hold more records than the limit
+    if let Some(limit) = limit {
+        items.truncate(limit);
+    }
+    Ok(items)
+}
 
+/// `key` of every record matching a query, selecting only that property
+async fn find_keys(
+    cli: &Executor,
+    options: &CliOptions,
+    resource: &str,
+    query: &str,
+    key: &str,
+) -> Result<Vec<Value>> {
+    let exec_result = cli
+        .select(options, resource, None, Some(query), Some(key), None, None)
+        .await?;
+    Ok(parse_items(exec_result)?
+        .into_iter()
+        .filter_map(|item| item.get(key).cloned())
+        .collect())
+}
+
+/// Records in the output of find or select; none when nothing was found
+fn parse_items(exec_result: ExecResult) -> Result<Vec<Value>> {
     if !exec_result.success() {
         // If not found, return empty
         if exec_result.stderr.contains("not found")
@@ -251,56 +955,279 @@ async fn find_items(
         });
     }
 
-    // Parse JSON output
-    let stdout = exec_result.stdout.trim();
-    if stdout.is_empty() {
-        return Ok(vec![]);
+    // Parse JSON output; a select may print several chunks
+    let mut items = Vec::new();
+    for value in serde_json::Deserializer::from_str(exec_result.stdout.trim()).into_iter::<Value>()
+    {
+        match value.map_err(|e| VqxError::Other(format!("Failed to parse response: {}", e)))? {
+            Value::Array(arr) => items.extend(arr),
+            value @ Value::Object(_) => items.push(value),
+            _ => {}
+        }
     }
+    Ok(items)
+}
 
-    let parsed: Value = serde_json::from_str(stdout)
-        .map_err(|e| VqxError::Other(format!("Failed to parse response: {}", e)))?;
+/// Field identifying a record: `_id` for type data, `name` for resources
+fn record_key(items: &[Value]) -> Option<&'static str> {
+    ["_id", "name"]
+        .into_iter()
+        .find(|key| items.iter().all(|item| item.get(key).is_some()))
+}
 
-    match parsed {
-        Value::Array(arr) => Ok(arr),
-        Value::Object(_) => Ok(vec![parsed]),
-        _ => Ok(vec![]),
+/// Name of a record for `--include`/`--exclude`: its `name`, or else its `_id`
+fn record_name(item: &Value) -> Option<String> {
+    ["name", "_id"]
+        .into_iter()
+        .find_map(|key| item.get(key))
+        .map(|value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+}
+
+/// Query matching exactly the given records
+fn id_query(items: &[Value]) -> Result<String> {
+    let key = record_key(items).ok_or_else(|| {
+        VqxError::Other(
+            "--limit and --include/--exclude need records with an _id or name to delete them one by one".to_string(),
+        )
+    })?;
+    let ids: Vec<&Value> = items.iter().filter_map(|item| item.get(key)).collect();
+    Ok(serde_json::json!({ key: { "$in": ids } }).to_string())
+}
+
+/// Top-level scalar fields shown in the preview, key fields first
+fn key_fields(item: &Value) -> Vec<(String, String)> {
+    let Some(object) = item.as_object() else {
+        return vec![];
+    };
+    let keys = ["_id", "name"]
+        .into_iter()
+        .filter(|k| object.contains_key(*k))
+        .map(str::to_string);
+    let others = object
+        .keys()
+        .filter(|k| !k.starts_with('_') && !k.starts_with("ars_") && *k != "name")
+        .filter(|k| !object[*k].is_object() && !object[*k].is_array())
+        .take(3)
+        .cloned();
+    keys.chain(others)
+        .map(|k| {
+            let value = match &object[&k] {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (k, value)
+        })
+        .collect()
+}
+
+/// Show the first `sample` matched records
+fn display_sample(items: &[Value], items_matched: Option<usize>, sample: usize) {
+    match items_matched {
+        Some(matched) if matched > items.len() => println!(
+            "{} Found {} matching item(s), deleting the first {} (--limit):",
+            style("→").cyan(),
+            matched,
+            items.len()
+        ),
+        _ => println!(
+            "{} Found {} item(s) to delete:",
+            style("→").cyan(),
+            items.len()
+        ),
+    }
+    for item in items.iter().take(sample) {
+        let fields: Vec<String> = key_fields(item)
+            .into_iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect();
+        println!("  - {}", style(fields.join(", ")).yellow());
     }
+    if items.len() > sample {
+        println!(
+            "  {}",
+            style(format!("... and {} more", items.len() - sample)).dim()
+        );
+    }
+    println!();
+}
+
+/// Record which records a query delete selected, next to its backup
+///
+/// Written as `<backup>.ids.json` so the exact set deleted can be audited:
+/// `ids` are the records deleted, `matched_ids` every record the query
+/// matched, including those `--limit` left out.
+fn write_matched_ids(
+    backup_path: &Path,
+    resource: &str,
+    query: &str,
+    limit: Option<usize>,
+    key: &str,
+    matched_keys: &[Value],
+    items: &[Value],
+) -> Result<()> {
+    let ids: Vec<&Value> = items.iter().filter_map(|item| item.get(key)).collect();
+    let record = serde_json::json!({
+        "resource": resource,
+        "query": query,
+        "limit": limit,
+        "matched": matched_keys.len(),
+        "key": key,
+        "ids": ids,
+        "matched_ids": matched_keys,
+    });
+
+    let path = backup_path.with_extension("ids.json");
+    let data = serde_json::to_string_pretty(&record).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not serialize the records: {}", e),
+    })?;
+    fs::write(&path, data).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not write the backup: {}", e),
+    })?;
+
+    info!(path = %path.display(), ids = ids.len(), "Matched IDs saved");
+    Ok(())
+}
+
+/// Keep everything about to be deleted in a new trash entry
+///
+/// Definitions of metadata resources (and cascade dependents) are kept in
+/// the export layout; type data records as they are.
+fn move_to_trash(
+    store: &TrashStore,
+    profile_name: Option<&str>,
+    resource: &str,
+    target: &str,
+    cascade: &[ResourceRef],
+    definitions: &DependencyGraph,
+    items: &[Value],
+) -> Result<TrashEntry> {
+    let mut trashed: Vec<TrashedDefinition<'_>> = cascade
+        .iter()
+        .filter_map(|d| {
+            definitions.get(d).map(|definition| TrashedDefinition {
+                resource_type: &d.resource_type,
+                name: &d.name,
+                definition,
+            })
+        })
+        .collect();
+
+    let records = if RESOURCE_TYPES.contains(&resource) {
+        trashed.extend(items.iter().map(|item| {
+            TrashedDefinition {
+                resource_type: resource,
+                name: item
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unnamed"),
+                definition: item,
+            }
+        }));
+        &[][..]
+    } else {
+        items
+    };
+
+    store.put(profile_name, resource, target, &trashed, records)
+}
+
+/// Directory holding safe-delete backups
+pub(crate) fn backup_root() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("vqx")
+        .join("backups")
 }
 
 /// Create a backup of items before deletion
 fn create_backup(resource: &str, items: &[Value]) -> Result<PathBuf> {
     let timestamp = Local::now().format("%Y%m%d_%H%M%S");
-    let backup_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("vqx")
-        .join("backups");
+    let backup_dir = backup_root();
 
-    fs::create_dir_all(&backup_dir)
-        .map_err(|e| VqxError::Other(format!("Failed to create backup directory: {}", e)))?;
+    fs::create_dir_all(&backup_dir).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not create the backup directory: {}", e),
+    })?;
 
     let filename = format!("{}_{}.json", resource, timestamp);
     let backup_path = backup_dir.join(filename);
 
-    let backup_data = serde_json::to_string_pretty(items)
-        .map_err(|e| VqxError::Other(format!("Failed to serialize backup: {}", e)))?;
+    let backup_data = serde_json::to_string_pretty(items).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not serialize the records: {}", e),
+    })?;
 
-    fs::write(&backup_path, backup_data)
-        .map_err(|e| VqxError::Other(format!("Failed to write backup: {}", e)))?;
+    fs::write(&backup_path, backup_data).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not write the backup: {}", e),
+    })?;
 
     info!(path = %backup_path.display(), "Backup created");
     Ok(backup_path)
 }
 
+/// Back up every resource of a cascade, one file per resource
+///
+/// Files use the export layout (`<type>/<name>.json`) so the backup can be
+/// restored with `vqx import -d <backup>`.
+fn create_cascade_backup(
+    definitions: &DependencyGraph,
+    cascade: &[ResourceRef],
+    resource: &str,
+    items: &[Value],
+) -> Result<PathBuf> {
+    let timestamp = Local::now().format("%Y%m%d_%H%M%S");
+    let backup_dir = backup_root().join(format!("cascade_{}_{}", resource, timestamp));
+
+    for dependent in cascade {
+        if let Some(definition) = definitions.get(dependent) {
+            write_backup_item(
+                &backup_dir,
+                &dependent.resource_type,
+                &dependent.name,
+                definition,
+            )?;
+        }
+    }
+    for item in items {
+        let name = item
+            .get("name")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unnamed");
+        write_backup_item(&backup_dir, resource, name, item)?;
+    }
+
+    info!(path = %backup_dir.display(), items = cascade.len() + items.len(), "Cascade backup created");
+    Ok(backup_dir)
+}
+
+fn write_backup_item(
+    backup_dir: &Path,
+    resource_type: &str,
+    name: &str,
+    item: &Value,
+) -> Result<()> {
+    let dir = backup_dir.join(resource_type);
+    fs::create_dir_all(&dir).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not create the backup directory: {}", e),
+    })?;
+
+    let data = serde_json::to_string_pretty(item).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not serialize the records: {}", e),
+    })?;
+    fs::write(dir.join(format!("{}.json", name)), data).map_err(|e| VqxError::BackupFailed {
+        message: format!("could not write the backup: {}", e),
+    })
+}
+
 /// Delete a single item
 async fn delete_single(
-    cli: &UnderlyingCli,
+    cli: &Executor,
     options: &CliOptions,
     resource: &str,
     resource_id: &str,
 ) -> Result<usize> {
-    let exec_result = cli
-        .execute(options, "delete", [resource, resource_id])
-        .await?;
+    let exec_result = cli.delete(options, resource, resource_id).await?;
 
     if exec_result.success() {
         Ok(1)
@@ -314,14 +1241,12 @@ async fn delete_single(
 
 /// Delete items matching a query
 async fn delete_matching(
-    cli: &UnderlyingCli,
+    cli: &Executor,
     options: &CliOptions,
     resource: &str,
     query: &str,
 ) -> Result<usize> {
-    let exec_result = cli
-        .execute(options, "deleteMatching", [resource, query])
-        .await?;
+    let exec_result = cli.delete_matching(options, resource, query).await?;
 
     if exec_result.success() {
         // Try to parse the count from output
@@ -346,26 +1271,11 @@ async fn delete_matching(
     }
 }
 
-/// Build CLI options from profile
-fn build_cli_options(profile_name: Option<&str>) -> Result<CliOptions> {
-    if let Some(name) = profile_name {
-        let manager = ProfileManager::new()?;
-        let profile = manager.get_resolved(name)?;
-        Ok(CliOptions::from_profile(&profile))
-    } else {
-        Ok(CliOptions::default())
-    }
-}
-
 /// Display the result
 fn display_result(result: &SafeDeleteResult, output_format: OutputFormat, verbose: bool) {
-    match output_format {
-        OutputFormat::Json => {
-            if let Ok(json) = serde_json::to_string_pretty(result) {
-                println!("{}", json);
-            }
-        }
-        OutputFormat::Text | OutputFormat::Csv => {
+    match machine_report(result, output_format) {
+        Some(report) => println!("{}", report),
+        None => {
             if verbose {
                 println!();
                 println!("{}", style("─".repeat(40)).dim());
@@ -389,6 +1299,14 @@ fn display_result(result: &SafeDeleteResult, output_format: OutputFormat, verbos
                 }
             } else {
                 println!("{} Delete failed", style("✗").red().bold());
+                for item in result.items.iter().filter(|i| !i.success) {
+                    println!(
+                        "  {} {}: {}",
+                        style("✗").red(),
+                        item.id,
+                        item.error.as_deref().unwrap_or("failed")
+                    );
+                }
                 if let Some(ref err) = result.error {
                     eprintln!("{}", style(err).red());
                 }
@@ -397,13 +1315,121 @@ fn display_result(result: &SafeDeleteResult, output_format: OutputFormat, verbos
     }
 }
 
+/// Report printed for machine-readable output formats
+fn machine_report(result: &SafeDeleteResult, output_format: OutputFormat) -> Option<String> {
+    match output_format {
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => report::render(result, output_format).ok(),
+        OutputFormat::Text | OutputFormat::Csv => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_is_matching_query() {
         assert!("{\"name\": \"test\"}".starts_with('{'));
         assert!(!"MyResource".starts_with('{'));
     }
+
+    #[tokio::test]
+    async fn test_bulk_dry_run_report() {
+        use crate::cli::{Cli, Commands};
+        use clap::Parser;
+
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("ids.txt");
+        fs::write(&file, "a1\na2\n").unwrap();
+        let cli = Cli::parse_from([
+            "vqx",
+            "safe-delete",
+            "Orders",
+            "--from-file",
+            &file.display().to_string(),
+            "--dry-run",
+        ]);
+        let Commands::SafeDelete(args) = cli.command else {
+            panic!("not a safe-delete command");
+        };
+
+        let result = run(&args, &Config::default(), None, OutputFormat::Json, false)
+            .await
+            .unwrap();
+        assert!(result.success && result.dry_run);
+        assert_eq!(result.items_found, 2);
+
+        let report: Value =
+            serde_json::from_str(&machine_report(&result, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(report["report_version"], 1);
+        assert_eq!(report["dry_run"], true);
+        assert_eq!(report["items_found"], 2);
+        assert!(machine_report(&result, OutputFormat::Text).is_none());
+    }
+
+    #[test]
+    fn test_parse_items_chunks() {
+        let output = |stdout: &str| ExecResult {
+            status: crate::underlying::exit_status(0),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            retries: vec![],
+        };
+        let items = parse_items(output("[{\"_id\": \"a1\"}]\n[{\"_id\": \"a2\"}]\n")).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(parse_items(output("{\"name\": \"T1\"}")).unwrap().len(), 1);
+        assert!(parse_items(output("  ")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_write_matched_ids() {
+        let dir = TempDir::new().unwrap();
+        let backup = dir.path().join("Orders_20240101.json");
+        let items = vec![json!({"_id": "a1"}), json!({"_id": "a2"})];
+        let matched = vec![json!("a1"), json!("a2"), json!("a3")];
+        write_matched_ids(&backup, "Orders", "{}", Some(2), "_id", &matched, &items).unwrap();
+
+        let record: Value =
+            serde_json::from_str(&fs::read_to_string(backup.with_extension("ids.json")).unwrap())
+                .unwrap();
+        assert_eq!(record["matched"], 3);
+        assert_eq!(record["ids"], json!(["a1", "a2"]));
+        assert_eq!(record["matched_ids"], json!(["a1", "a2", "a3"]));
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        let ids = parse_ids("# stale orders\na1\n\n  a2  \na1\n").unwrap();
+        assert_eq!(ids, vec!["a1", "a2"]);
+
+        let ids = parse_ids(r#"["a1", {"_id": "a2"}, {"name": "T1"}, 7]"#).unwrap();
+        assert_eq!(ids, vec!["a1", "a2", "T1", "7"]);
+        assert!(parse_ids(r#"[{"status": "old"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_id_query() {
+        let items = vec![
+            json!({"_id": "a1", "name": "x", "status": "old"}),
+            json!({"_id": "a2", "status": "old"}),
+        ];
+        assert_eq!(id_query(&items).unwrap(), r#"{"_id":{"$in":["a1","a2"]}}"#);
+        assert_eq!(
+            id_query(&[json!({"name": "T1"})]).unwrap(),
+            r#"{"name":{"$in":["T1"]}}"#
+        );
+        assert!(id_query(&[json!({"status": "old"})]).is_err());
+        assert_eq!(record_name(&items[0]).as_deref(), Some("x"));
+        assert_eq!(record_name(&items[1]).as_deref(), Some("a2"));
+        assert_eq!(record_name(&json!({"status": "old"})), None);
+
+        let fields = key_fields(&items[0]);
+        assert_eq!(fields[0], ("_id".to_string(), "a1".to_string()));
+        assert_eq!(fields[1], ("name".to_string(), "x".to_string()));
+        assert_eq!(fields[2], ("status".to_string(), "old".to_string()));
+    }
 }
@@ -0,0 +1,310 @@
+//! Scan command implementation
+//!
+//! Looks for embedded credentials in an export directory before it is
+//! committed or shared, using the detectors in [`crate::secrets`].
+//!
+//! Known false positives are listed in an allowlist file, one rule per line:
+//!
+//! ```text
+//! # <file glob> [<JSON pointer glob>]
+//! sources/Legacy*.json
+//! sources/Crm.json /config/signing
+//! ```
+//!
+//! By default `<directory>/.vqx-scan-allow` is used when it exists.
+
+use crate::cli::{OutputFormat, ScanArgs};
+use crate::commands::list::glob_to_regex;
+use crate::error::{Result, VqxError};
+use crate::report;
+use crate::secrets::{self, Finding};
+use crate::theme::style;
+use regex::Regex;
+use serde::Serialize;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Default allowlist file name inside the scanned directory
+pub const ALLOWLIST_FILE: &str = ".vqx-scan-allow";
+
+/// One allowlist rule
+#[derive(Debug)]
+struct AllowRule {
+    file: Regex,
+    pointer: Option<Regex>,
+}
+
+impl AllowRule {
+    fn allows(&self, file: &str, finding: &Finding) -> bool {
+        self.file.is_match(file)
+            && self
+                .pointer
+                .as_ref()
+                .is_none_or(|p| p.is_match(&finding.pointer))
+    }
+}
+
+/// Parse an allowlist file
+fn parse_allowlist(content: &str) -> Result<Vec<AllowRule>> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.split_whitespace();
+            let file = glob_to_regex(parts.next().unwrap_or_default())?;
+            let pointer = parts.next().map(glob_to_regex).transpose()?;
+            Ok(AllowRule { file, pointer })
+        })
+        .collect()
+}
+
+/// A finding in one file
+#[derive(Debug, Serialize)]
+pub struct FileFinding {
+    /// Path relative to the scanned directory
+    pub file: String,
+    #[serde(flatten)]
+    pub finding: Finding,
+}
+
+/// Scan operation result
+#[derive(Debug, Serialize)]
+pub struct ScanResult {
+    pub success: bool,
+    pub directory: PathBuf,
+    pub files_scanned: usize,
+    /// Findings suppressed by the allowlist
+    pub allowed: usize,
+    pub findings: Vec<FileFinding>,
+}
+
+/// Run scan command
+pub fn run(args: &ScanArgs, output_format: OutputFormat) -> Result<ScanResult> {
+    let dir = &args.directory;
+    if !dir.is_dir() {
+        return Err(VqxError::Other(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+
+    let allowlist_path = args
+        .allowlist
+        .clone()
+        .or_else(|| Some(dir.join(ALLOWLIST_FILE)).filter(|p| p.is_file()));
+    let allowlist = match allowlist_path {
+        Some(ref path) => {
+            let content = std::fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+                path: path.display().to_string(),
+            })?;
+            parse_allowlist(&content)?
+        }
+        None => Vec::new(),
+    };
+
+    let mut files_scanned = 0;
+    let mut allowed = 0;
+    let mut findings = Vec::new();
+
+    for path in scannable_files(dir) {
+        let content = std::fs::read_to_string(&path).map_err(|_| VqxError::FileReadFailed {
+            path: path.display().to_string(),
+        })?;
+        files_scanned += 1;
+
+        let file = relative_path(dir, &path);
+        let found = if path.extension().is_some_and(|e| e == "json") {
+            secrets::scan_json(&content)
+        } else {
+            secrets::scan_text(&content)
+        };
+        for finding in found {
+            if allowlist.iter().any(|rule| rule.allows(&file, &finding)) {
+                allowed += 1;
+            } else {
+                findings.push(FileFinding {
+                    file: file.clone(),
+                    finding,
+                });
+            }
+        }
+    }
+
+    let result = ScanResult {
+        success: findings.is_empty(),
+        directory: dir.clone(),
+        files_scanned,
+        allowed,
+        findings,
+    };
+
+    if let Some(ref sarif_path) = args.sarif {
+        let sarif = serde_json::to_string_pretty(&to_sarif(&result))?;
+        std::fs::write(sarif_path, sarif + "\n").map_err(|_| VqxError::FileWriteFailed {
+            path: sarif_path.display().to_string(),
+        })?;
+    }
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display_result(&result, args.sarif.as_deref());
+    }
+
+    Ok(result)
+}
+
+/// JSON and VAIL files below a directory, in a stable order
+fn scannable_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json" || e == "vail"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Path relative to the scanned directory, with `/` separators
+fn relative_path(dir: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(dir).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Convert a scan result to SARIF 2.1.0 for CI annotations
+fn to_sarif(result: &ScanResult) -> serde_json::Value {
+    let rules: Vec<_> = secrets::rules()
+        .into_iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "shortDescription": {"text": rule.description},
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = result
+        .findings
+        .iter()
+        .map(|f| {
+            json!({
+                "ruleId": f.finding.rule,
+                "level": "error",
+                "message": {"text": format!(
+                    "Possible secret ({}) at {}: {}",
+                    f.finding.rule,
+                    if f.finding.pointer.is_empty() { "-" } else { f.finding.pointer.as_str() },
+                    f.finding.preview
+                )},
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {"uri": f.file},
+                        "region": {"startLine": f.finding.line},
+                    }
+                }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "vqx",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Display the scan result to the terminal
+fn display_result(result: &ScanResult, sarif_path: Option<&Path>) {
+    println!();
+    println!("{}", style("Scan").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Directory: {}", result.directory.display());
+    println!();
+
+    for f in &result.findings {
+        println!(
+            "{} {}:{} {} {} {}",
+            style("✗").red(),
+            f.file,
+            f.finding.line,
+            style(f.finding.rule).yellow(),
+            style(&f.finding.pointer).dim(),
+            f.finding.preview
+        );
+    }
+    if !result.findings.is_empty() {
+        println!();
+    }
+
+    if result.success {
+        println!(
+            "{} No secrets found in {} files",
+            style("✓").green(),
+            result.files_scanned
+        );
+    } else {
+        println!(
+            "{} {} possible secret(s) in {} files",
+            style("✗").red().bold(),
+            result.findings.len(),
+            result.files_scanned
+        );
+    }
+    if result.allowed > 0 {
+        println!(
+            "  {} finding(s) suppressed by the allowlist",
+            result.allowed
+        );
+    }
+    if let Some(path) = sarif_path {
+        println!("  SARIF report: {}", path.display());
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist() {
+        let rules = parse_allowlist(
+            "# known test fixtures\nsources/Legacy*.json\n\nsources/Crm.json /config/sign*\n",
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 2);
+
+        let finding = Finding {
+            rule: "high-entropy",
+            pointer: "/config/signing".to_string(),
+            line: 1,
+            preview: "Zx8Q****".to_string(),
+        };
+        assert!(rules
+            .iter()
+            .any(|r| r.allows("sources/Legacy1.json", &finding)));
+        assert!(rules.iter().any(|r| r.allows("sources/Crm.json", &finding)));
+
+        let other = Finding {
+            pointer: "/config/password".to_string(),
+            ..finding
+        };
+        assert!(!rules.iter().any(|r| r.allows("sources/Crm.json", &other)));
+    }
+}
@@ -0,0 +1,535 @@
+//! Schedule command implementation
+//!
+//! Manages the `[[schedule]]` jobs in config.toml and runs them. `run` is a
+//! long-lived scheduler: it starts each job as a child vqx process (with
+//! `--no-input`, so a job that would prompt fails instead of hanging) when
+//! its cron expression fires, one job at a time. A job that is still running
+//! when it is due again skips that run. With `--metrics` the scheduler also
+//! serves Prometheus metrics of the runs (see [`crate::metrics`]). `unit`
+//! prints a service definition that keeps the scheduler running. See
+//! [`crate::schedule`].
+
+use crate::cli::{
+    Cli, Commands, OutputFormat, ScheduleAddArgs, ScheduleCommands, ScheduleRunArgs, UnitKind,
+};
+use crate::config::{Config, ScheduledJob};
+use crate::error::{Result, VqxError};
+use crate::metrics::Metrics;
+use crate::report;
+use crate::schedule::CronSchedule;
+use crate::serve;
+use crate::theme::style;
+use chrono::{DateTime, Local, Utc};
+use clap::Parser;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+/// Longest sleep between checks, so clock changes and suspends are noticed
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// A job as listed by `schedule list`
+#[derive(Debug, Serialize)]
+pub struct JobInfo {
+    #[serde(flatten)]
+    pub job: ScheduledJob,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<DateTime<Local>>,
+}
+
+/// Result of schedule list
+#[derive(Debug, Serialize)]
+pub struct ScheduleListResult {
+    pub success: bool,
+    pub jobs: Vec<JobInfo>,
+}
+
+/// Outcome of one scheduled run
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRun {
+    pub id: String,
+    pub started_at: DateTime<Local>,
+    pub duration_ms: u64,
+    pub success: bool,
+    /// Exit code of the job, if it exited normally
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run a schedule subcommand; returns whether it succeeded
+///
+/// `config_path` is the `--config` file, if given; jobs are added to and
+/// removed from that file (or the default config.toml).
+pub async fn run(
+    cmd: &ScheduleCommands,
+    config: &Config,
+    config_path: Option<&Path>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    match cmd {
+        ScheduleCommands::Add(args) => add(args, config_path, output_format),
+        ScheduleCommands::List => list(config, output_format),
+        ScheduleCommands::Remove(args) => remove(&args.id, config_path, output_format),
+        ScheduleCommands::Run(args) => run_jobs(args, config, config_path, output_format).await,
+        ScheduleCommands::Unit(args) => {
+            let exe = current_exe()?;
+            print!(
+                "{}",
+                match args.kind {
+                    UnitKind::Systemd => systemd_unit(&exe, config_path),
+                    UnitKind::Windows => windows_task(&exe, config_path),
+                }
+            );
+            Ok(true)
+        }
+    }
+}
+
+fn add(
+    args: &ScheduleAddArgs,
+    config_path: Option<&Path>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let schedule: CronSchedule = args.cron.parse()?;
+    check_command(&args.command)?;
+
+    let path = file_path(config_path)?;
+    let mut config = Config::load_from(&path)?;
+    let id = match args.id {
+        Some(ref id) if config.schedule.iter().any(|j| &j.id == id) => {
+            return Err(VqxError::Other(format!("Job '{}' already exists", id)));
+        }
+        Some(ref id) => id.clone(),
+        None => (1..)
+            .map(|n| format!("job-{}", n))
+            .find(|id| !config.schedule.iter().any(|j| &j.id == id))
+            .unwrap_or_default(),
+    };
+    let job = ScheduledJob {
+        id,
+        cron: schedule.to_string(),
+        command: args.command.clone(),
+        dir: std::env::current_dir()
+            .ok()
+            .map(|d| d.display().to_string()),
+    };
+    config.schedule.push(job.clone());
+    config.save_to(&path)?;
+
+    let info = JobInfo {
+        next_run: schedule.next_after(Local::now()),
+        job,
+    };
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&info, output_format)?);
+    } else {
+        println!(
+            "{} Added {}: vqx {}",
+            style("✓").green(),
+            style(&info.job.id).bold(),
+            info.job.command.join(" ")
+        );
+        if let Some(next) = info.next_run {
+            println!("  Next run: {}", next.format("%Y-%m-%d %H:%M"));
+        }
+        println!("  Start the scheduler with `vqx schedule run`");
+    }
+    Ok(true)
+}
+
+/// Fail unless the job's arguments form a vqx command that can be scheduled
+pub(crate) fn check_command(command: &[String]) -> Result<()> {
+    let cli = Cli::try_parse_from(std::iter::once("vqx").chain(command.iter().map(String::as_str)))
+        .map_err(|e| {
+            VqxError::Other(format!(
+                "Invalid job command `vqx {}`: {}",
+                command.join(" "),
+                e.render().to_string().lines().next().unwrap_or_default()
+            ))
+        })?;
+    if matches!(cli.command, Commands::Schedule(_)) {
+        return Err(VqxError::Other(
+            "A job cannot run `vqx schedule` itself".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn list(config: &Config, output_format: OutputFormat) -> Result<bool> {
+    let now = Local::now();
+    let jobs: Vec<JobInfo> = config
+        .schedule
+        .iter()
+        .map(|job| JobInfo {
+            next_run: job
+                .cron
+                .parse::<CronSchedule>()
+                .ok()
+                .and_then(|s| s.next_after(now)),
+            job: job.clone(),
+        })
+        .collect();
+
+    if output_format.is_machine_readable() {
+        let result = ScheduleListResult {
+            success: true,
+            jobs,
+        };
+        println!("{}", report::render(&result, output_format)?);
+        return Ok(true);
+    }
+
+    println!();
+    println!("{}", style("Scheduled Jobs").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    if jobs.is_empty() {
+        println!("  No jobs; add one with `vqx schedule add`");
+    }
+    for info in &jobs {
+        let next = match info.next_run {
+            Some(next) => style(format!("next {}", next.format("%Y-%m-%d %H:%M"))).dim(),
+            None => style("invalid cron expression".to_string()).red(),
+        };
+        println!(
+            "  {}  {}  vqx {}  {}",
+            style(&info.job.id).bold(),
+            style(&info.job.cron).yellow(),
+            info.job.command.join(" "),
+            next
+        );
+        if let Some(ref dir) = info.job.dir {
+            println!("      in {}", style(dir).dim());
+        }
+    }
+    println!();
+    Ok(true)
+}
+
+fn remove(id: &str, config_path: Option<&Path>, output_format: OutputFormat) -> Result<bool> {
+    let path = file_path(config_path)?;
+    let mut config = Config::load_from(&path)?;
+    let before = config.schedule.len();
+    config.schedule.retain(|j| j.id != id);
+    if config.schedule.len() == before {
+        return Err(VqxError::Other(format!("No scheduled job '{}'", id)));
+    }
+    config.save_to(&path)?;
+
+    if output_format.is_machine_readable() {
+        println!(
+            "{}",
+            report::render(
+                &serde_json::json!({ "success": true, "removed": id }),
+                output_format
+            )?
+        );
+    } else {
+        println!("{} Removed {}", style("✓").green(), style(id).bold());
+    }
+    Ok(true)
+}
+
+async fn run_jobs(
+    args: &ScheduleRunArgs,
+    config: &Config,
+    config_path: Option<&Path>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let mut jobs = Vec::new();
+    for job in &config.schedule {
+        if args.jobs.is_empty() || args.jobs.contains(&job.id) {
+            let schedule: CronSchedule = job.cron.parse()?;
+            jobs.push((job, schedule));
+        }
+    }
+    if let Some(id) = args
+        .jobs
+        .iter()
+        .find(|id| !config.schedule.iter().any(|j| &&j.id == id))
+    {
+        return Err(VqxError::Other(format!("No scheduled job '{}'", id)));
+    }
+    if jobs.is_empty() {
+        return Err(VqxError::Other(
+            "No jobs to run; add one with `vqx schedule add`".to_string(),
+        ));
+    }
+
+    let exe = current_exe()?;
+    let metrics = Arc::new(Metrics::new(jobs.iter().map(|(job, _)| job.id.as_str())));
+    // Aborted when the scheduler returns
+    let _metrics_server = match args.metrics {
+        Some(ref listen) => Some(start_metrics(listen, &metrics).await?),
+        None => None,
+    };
+
+    let interactive = !output_format.is_machine_readable();
+    if interactive {
+        println!(
+            "{} Running {} job(s) (Ctrl-C to stop)",
+            style("●").green(),
+            jobs.len()
+        );
+    }
+    info!(jobs = jobs.len(), "Scheduler started");
+
+    let mut next: Vec<Option<DateTime<Local>>> = jobs
+        .iter()
+        .map(|(_, schedule)| schedule.next_after(Local::now()))
+        .collect();
+    loop {
+        let Some(due) = next.iter().flatten().min().copied() else {
+            warn!("No job will run again");
+            return Ok(true);
+        };
+
+        let wait = (due - Local::now()).to_std().unwrap_or_default();
+        if !wait.is_zero() {
+            tokio::select! {
+                _ = tokio::time::sleep(wait.min(MAX_SLEEP)) => continue,
+                _ = tokio::signal::ctrl_c() => {
+                    info!("Scheduler stopped");
+                    return Ok(true);
+                }
+            }
+        }
+
+        for (i, (job, schedule)) in jobs.iter().enumerate() {
+            if next[i].is_some_and(|t| t <= due) {
+                let run = run_job(&exe, job, config_path).await;
+                metrics.record(
+                    &run.id,
+                    run.success,
+                    Duration::from_millis(run.duration_ms),
+                    Utc::now(),
+                );
+                report_run(&run, output_format)?;
+                // Runs missed while this one was going are skipped
+                next[i] = schedule.next_after(due.max(Local::now()));
+            }
+        }
+    }
+}
+
+/// Serve metrics on `listen` in the background
+async fn start_metrics(listen: &str, metrics: &Arc<Metrics>) -> Result<AbortOnDrop> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .map_err(|e| VqxError::Other(format!("Cannot listen on {}: {}", listen, e)))?;
+    let addr = listener.local_addr()?;
+    info!(listen = %addr, "Serving metrics");
+    let metrics = Arc::clone(metrics);
+    Ok(AbortOnDrop(tokio::spawn(async move {
+        if let Err(e) = serve::serve_metrics(listener, metrics).await {
+            warn!(error = %e, "Metrics server stopped");
+        }
+    })))
+}
+
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Run one job as a child vqx process and wait for it
+async fn run_job(exe: &Path, job: &ScheduledJob, config_path: Option<&Path>) -> JobRun {
+    let started_at = Local::now();
+    let start = Instant::now();
+    info!(job = %job.id, command = %job.command.join(" "), "Starting scheduled job");
+
+    let mut command = tokio::process::Command::new(exe);
+    if let Some(path) = config_path {
+        command.arg("--config").arg(path);
+    }
+    command.arg("--no-input").args(&job.command);
+    if let Some(ref dir) = job.dir {
+        command.current_dir(dir);
+    }
+    let status = command.status().await;
+
+    let mut run = JobRun {
+        id: job.id.clone(),
+        started_at,
+        duration_ms: start.elapsed().as_millis() as u64,
+        success: false,
+        exit_code: None,
+        error: None,
+    };
+    match status {
+        Ok(status) => {
+            run.success = status.success();
+            run.exit_code = status.code();
+        }
+        Err(e) => run.error = Some(format!("could not start {}: {}", exe.display(), e)),
+    }
+    if run.success {
+        info!(job = %run.id, duration_ms = run.duration_ms, "Scheduled job finished");
+    } else {
+        warn!(job = %run.id, exit_code = ?run.exit_code, error = ?run.error, "Scheduled job failed");
+    }
+    run
+}
+
+fn report_run(run: &JobRun, output_format: OutputFormat) -> Result<()> {
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(run, output_format)?);
+        return Ok(());
+    }
+    let took = Duration::from_millis(run.duration_ms).as_secs();
+    if run.success {
+        println!(
+            "{} {} finished in {}s",
+            style("✓").green(),
+            style(&run.id).bold(),
+            took
+        );
+    } else {
+        let reason = match (&run.error, run.exit_code) {
+            (Some(error), _) => error.clone(),
+            (None, Some(code)) => format!("exit code {}", code),
+            (None, None) => "terminated by a signal".to_string(),
+        };
+        println!(
+            "{} {} failed after {}s ({})",
+            style("✗").red(),
+            style(&run.id).bold(),
+            took,
+            reason
+        );
+    }
+    Ok(())
+}
+
+fn file_path(config_path: Option<&Path>) -> Result<PathBuf> {
+    match config_path {
+        Some(path) => Ok(path.to_path_buf()),
+        None => Config::config_file_path(),
+    }
+}
+
+fn current_exe() -> Result<PathBuf> {
+    std::env::current_exe()
+        .map_err(|e| VqxError::Other(format!("Cannot locate the vqx executable: {}", e)))
+}
+
+/// `[--config <path>] schedule run`, quoted for a service definition
+fn scheduler_args(config_path: Option<&Path>) -> String {
+    let mut args = Vec::new();
+    if let Some(path) = config_path {
+        args.push("--config".to_string());
+        args.push(path.display().to_string());
+    }
+    args.extend(["schedule".to_string(), "run".to_string()]);
+    args.iter()
+        .map(|a| {
+            if a.contains(char::is_whitespace) {
+                format!("\"{}\"", a)
+            } else {
+                a.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// systemd unit running the scheduler as a user service
+fn systemd_unit(exe: &Path, config_path: Option<&Path>) -> String {
+    format!(
+        "# Save as ~/.config/systemd/user/vqx-schedule.service, then run\n\
+         #   systemctl --user enable --now vqx-schedule\n\
+         [Unit]\n\
+         Description=vqx scheduled jobs\n\
+         Wants=network-online.target\n\
+         After=network-online.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart=\"{}\" {}\n\
+         Restart=on-failure\n\
+         RestartSec=30\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        exe.display(),
+        scheduler_args(config_path)
+    )
+}
+
+/// Task Scheduler task starting the scheduler at logon
+fn windows_task(exe: &Path, config_path: Option<&Path>) -> String {
+    format!(
+        r#"<?xml version="1.0"?>
+<!-- Save as vqx-schedule.xml, then run
+       schtasks /Create /TN vqx-schedule /XML vqx-schedule.xml -->
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo>
+    <Description>vqx scheduled jobs</Description>
+  </RegistrationInfo>
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+    </LogonTrigger>
+  </Triggers>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <RestartOnFailure>
+      <Interval>PT1M</Interval>
+      <Count>999</Count>
+    </RestartOnFailure>
+  </Settings>
+  <Actions Context="Author">
+    <Exec>
+      <Command>{}</Command>
+      <Arguments>{}</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        xml_escape(&exe.display().to_string()),
+        xml_escape(&scheduler_args(config_path))
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_command() {
+        let args = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert!(check_command(&args("sync pull -d ./repo --profile prod")).is_ok());
+        assert!(check_command(&args("sync pul -d ./repo")).is_err());
+        assert!(check_command(&args("schedule run")).is_err());
+    }
+
+    #[test]
+    fn test_service_definitions() {
+        let exe = Path::new("/usr/local/bin/vqx");
+        let unit = systemd_unit(exe, Some(Path::new("/etc/vqx/my config.toml")));
+        assert!(unit.contains(
+            "ExecStart=\"/usr/local/bin/vqx\" --config \"/etc/vqx/my config.toml\" schedule run"
+        ));
+
+        let task = windows_task(Path::new(r"C:\Tools\vqx.exe"), None);
+        assert!(task.contains(r"<Command>C:\Tools\vqx.exe</Command>"));
+        assert!(task.contains("<Arguments>schedule run</Arguments>"));
+    }
+}
@@ -0,0 +1,64 @@
+//! Schema command implementation
+//!
+//! Publishes the JSON Schemas of vqx's machine-readable reports so CI
+//! consumers and dashboards can validate or code-generate against them.
+//!
+//! Schemas are generated from the report types themselves, so they always
+//! match the `report_version` of the running binary.
+
+use crate::cli::{SchemaCommands, SchemaReportsArgs};
+use crate::error::{Result, VqxError};
+use crate::report::{self, REPORT_VERSION};
+use crate::theme::style;
+use serde_json::{Map, Value};
+
+/// Run a schema subcommand
+pub fn run(cmd: &SchemaCommands) -> Result<()> {
+    match cmd {
+        SchemaCommands::Reports(args) => run_reports(args),
+    }
+}
+
+/// Print or write the report schemas
+fn run_reports(args: &SchemaReportsArgs) -> Result<()> {
+    let mut schemas = report::report_schemas();
+
+    if let Some(ref name) = args.name {
+        schemas.retain(|(n, _)| n == name);
+        if schemas.is_empty() {
+            let available: Vec<_> = report::report_schemas()
+                .into_iter()
+                .map(|(n, _)| n)
+                .collect();
+            return Err(VqxError::Other(format!(
+                "Unknown report '{}'. Available: {}",
+                name,
+                available.join(", ")
+            )));
+        }
+    }
+
+    if let Some(ref dir) = args.out_dir {
+        std::fs::create_dir_all(dir).map_err(|_| VqxError::FileWriteFailed {
+            path: dir.display().to_string(),
+        })?;
+
+        for (name, schema) in &schemas {
+            let path = dir.join(format!("{}.v{}.schema.json", name, REPORT_VERSION));
+            let content = serde_json::to_string_pretty(schema)?;
+            std::fs::write(&path, content + "\n").map_err(|_| VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            })?;
+            println!("{} {}", style("✓").green(), path.display());
+        }
+        return Ok(());
+    }
+
+    let bundle: Map<String, Value> = schemas
+        .into_iter()
+        .map(|(name, schema)| Ok((name.to_string(), serde_json::to_value(schema)?)))
+        .collect::<Result<_>>()?;
+    println!("{}", serde_json::to_string_pretty(&bundle)?);
+
+    Ok(())
+}
@@ -0,0 +1,250 @@
+//! Select command implementation
+//!
+//! Wraps the underlying CLI's select command with inline qualifiers,
+//! property selection and chunked retrieval.
+//!
+//! Based on: CLI Reference Guide PDF - "Select" section
+//!
+//! PDF: "The select command is a convenience to allow you to retrieve data
+//! from the Vantiq database"
+//!
+//! Options (PDF):
+//! - -qual <fileName>: file containing a JSON query qualification
+//! - -props <fileName> | <propertyList>: properties to return
+//! - -chunk <integer>: retrieve results in chunks of the given size
+//!
+//! When chunking, the underlying CLI prints one JSON document per chunk.
+//! vqx concatenates them into a single JSON array (or an NDJSON stream).
+
+use crate::backend::{self, Executor};
+use crate::cli::{OutputFormat, SelectArgs};
+use crate::commands::list::{csv_escape, infer_columns, value_to_string};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::report;
+use crate::theme::style;
+use crate::underlying::UnderlyingCli;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io::Write;
+use tempfile::NamedTempFile;
+use tracing::{debug, info};
+
+/// Result of a select operation
+#[derive(Debug, Serialize)]
+pub struct SelectResult {
+    pub success: bool,
+    pub resource: String,
+    pub records: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Run the select command
+pub async fn run(
+    args: &SelectArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+    _verbose: bool,
+) -> Result<SelectResult> {
+    info!(resource = %args.resource, "Selecting records");
+
+    let profile = backend::resolve_profile(profile_name)?;
+    let options = backend::cli_options(profile.as_ref());
+    let cli = Executor::new(
+        UnderlyingCli::new(config.cli_path.clone())
+            .with_timeout(config.timeout_for("select"))
+            .with_retry_policy(config.retry_policy()),
+        profile.as_ref(),
+    )?;
+
+    // Inline qualifiers are written to a temp file for "-qual <fileName>".
+    // The file must outlive the CLI invocation.
+    let qual_temp = match args.where_clause {
+        Some(ref clause) => Some(write_qual_file(clause)?),
+        None => None,
+    };
+    let qual_path = match (&qual_temp, &args.qual) {
+        (Some(temp), _) => Some(temp.path().display().to_string()),
+        (None, Some(path)) => Some(path.display().to_string()),
+        (None, None) => None,
+    };
+
+    let props = if args.props.is_empty() {
+        None
+    } else {
+        Some(args.props.join(","))
+    };
+
+    let exec_result = cli
+        .select(
+            &options,
+            &args.resource,
+            args.id.as_deref(),
+            qual_path.as_deref(),
+            props.as_deref(),
+            args.chunk.or(Some(config.default_chunk_size)),
+            args.limit,
+        )
+        .await?;
+
+    if !exec_result.success() {
+        let result = SelectResult {
+            success: false,
+            resource: args.resource.clone(),
+            records: vec![],
+            error: Some(exec_result.stderr.clone()),
+        };
+        display_failure(&result, output_format, args.ndjson, exec_result.code())?;
+        return Ok(result);
+    }
+
+    // The last chunk read may hold more records than the limit
+    let mut records = parse_select_output(&exec_result.stdout)?;
+    if let Some(limit) = args.limit {
+        records.truncate(limit);
+    }
+    debug!(count = records.len(), "Parsed select output");
+
+    let result = SelectResult {
+        success: true,
+        resource: args.resource.clone(),
+        records,
+        error: None,
+    };
+
+    display_result(&result, output_format, args.ndjson)?;
+    Ok(result)
+}
+
+/// Validate an inline qualifier and write it to a temp file
+fn write_qual_file(clause: &str) -> Result<NamedTempFile> {
+    let value: Value = serde_json::from_str(clause).map_err(|e| VqxError::InvalidJson {
+        message: format!("--where: {}", e),
+    })?;
+    if !value.is_object() {
+        return Err(VqxError::InvalidJson {
+            message: "--where must be a JSON object".to_string(),
+        });
+    }
+
+    let mut file = NamedTempFile::new()?;
+    file.write_all(value.to_string().as_bytes())?;
+    file.flush()?;
+    Ok(file)
+}
+
+/// Parse the select output, concatenating all chunks into one list
+///
+/// Each chunk is a JSON array (or a single object); chunks may be separated
+/// by arbitrary whitespace.
+fn parse_select_output(stdout: &str) -> Result<Vec<Value>> {
+    let mut records = Vec::new();
+
+    for value in serde_json::Deserializer::from_str(stdout).into_iter::<Value>() {
+        match value? {
+            Value::Array(items) => records.extend(items),
+            other => records.push(other),
+        }
+    }
+
+    Ok(records)
+}
+
+/// Report a failed select: a result document for machine-readable output,
+/// otherwise the exit code and the CLI's error output on stderr
+fn display_failure(
+    result: &SelectResult,
+    output_format: OutputFormat,
+    ndjson: bool,
+    code: i32,
+) -> Result<()> {
+    match output_format {
+        _ if ndjson => println!("{}", report::serialize(result, OutputFormat::Ndjson)?),
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Ndjson => {
+            println!("{}", report::serialize(result, output_format)?);
+        }
+        OutputFormat::Text | OutputFormat::Csv | OutputFormat::Porcelain => {
+            eprintln!(
+                "{} Select on {} failed with exit code {}",
+                style("✗").red(),
+                result.resource,
+                code
+            );
+            if let Some(ref error) = result.error {
+                if !error.is_empty() {
+                    eprintln!("{}", style(error).red());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Display the selected records
+fn display_result(result: &SelectResult, output_format: OutputFormat, ndjson: bool) -> Result<()> {
+    if ndjson || matches!(output_format, OutputFormat::Ndjson) {
+        for record in &result.records {
+            println!("{}", serde_json::to_string(record)?);
+        }
+        return Ok(());
+    }
+
+    match output_format {
+        OutputFormat::Csv => {
+            let rows: Vec<Map<String, Value>> = result
+                .records
+                .iter()
+                .filter_map(|r| r.as_object().cloned())
+                .collect();
+            let columns = infer_columns(&rows);
+
+            let header: Vec<String> = columns.iter().map(|c| csv_escape(c)).collect();
+            println!("{}", header.join(","));
+            for row in &rows {
+                let fields: Vec<String> = columns
+                    .iter()
+                    .map(|c| csv_escape(&row.get(c).map(value_to_string).unwrap_or_default()))
+                    .collect();
+                println!("{}", fields.join(","));
+            }
+        }
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Text
+        | OutputFormat::Porcelain => {
+            println!("{}", report::serialize(&result.records, output_format)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_select_output_chunks() {
+        let stdout = "[{\"id\": 1}, {\"id\": 2}]\n[{\"id\": 3}]\n";
+        let records = parse_select_output(stdout).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2]["id"], 3);
+
+        assert!(parse_select_output("").unwrap().is_empty());
+        assert_eq!(parse_select_output("{\"id\": 1}").unwrap().len(), 1);
+        assert!(parse_select_output("not json").is_err());
+    }
+
+    #[test]
+    fn test_write_qual_file() {
+        let file = write_qual_file(r#"{"status": "open"}"#).unwrap();
+        let content = std::fs::read_to_string(file.path()).unwrap();
+        assert_eq!(content, r#"{"status":"open"}"#);
+
+        assert!(write_qual_file("[1, 2]").is_err());
+        assert!(write_qual_file("{bad").is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Serve command implementation
+//!
+//! Starts the HTTP trigger server in the foreground; see [`crate::serve`]
+//! for the API.
+
+use crate::cli::{OutputFormat, ServeArgs};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::serve::{self, Server};
+use crate::theme::style;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+/// Serve until Ctrl-C
+///
+/// `config_path` is the `--config` file, if given; operations run with it.
+pub async fn run(
+    args: &ServeArgs,
+    config: &Config,
+    config_path: Option<&Path>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let settings = &config.serve;
+    let token = std::env::var(&settings.token_env)
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| {
+            VqxError::Other(format!(
+                "Set {} to the bearer token clients must send",
+                settings.token_env
+            ))
+        })?;
+    if settings.operations.is_empty() {
+        warn!("No [serve.operations] configured; only /health and /runs will answer");
+    }
+
+    let mut global_args = Vec::new();
+    if let Some(path) = config_path {
+        global_args.push("--config".to_string());
+        global_args.push(path.display().to_string());
+    }
+    let exe = std::env::current_exe()
+        .map_err(|e| VqxError::Other(format!("Cannot locate the vqx executable: {}", e)))?;
+
+    let listen = args.listen.as_deref().unwrap_or(&settings.listen);
+    let listener = TcpListener::bind(listen)
+        .await
+        .map_err(|e| VqxError::Other(format!("Cannot listen on {}: {}", listen, e)))?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        warn!(listen = %addr, "vqx serve is reachable from other hosts; put it behind TLS");
+    }
+
+    if !output_format.is_machine_readable() {
+        println!(
+            "{} vqx serve listening on http://{} with {} operation(s) (Ctrl-C to stop)",
+            style("●").green(),
+            addr,
+            settings.operations.len()
+        );
+    }
+    info!(listen = %addr, operations = settings.operations.len(), "vqx serve listening");
+
+    let server = Server::new(token, settings.operations.clone(), exe, global_args);
+    serve::serve(listener, Arc::new(server)).await?;
+    Ok(true)
+}
@@ -0,0 +1,173 @@
+//! Show command implementation
+//!
+//! Prints one resource: fetched with `find` from the profile's namespace, or
+//! read from an export directory (`-d`, with extracted code embedded again).
+//! The definition is normalized like `export` writes it, and embedded VAIL
+//! code (`ruleText`, `text`) is printed after it, highlighted, instead of as
+//! one long JSON string. `--raw` prints the definition as stored.
+
+use crate::cli::{OutputFormat, ShowArgs};
+use crate::commands::dump::{resolve_profile, underlying_cli};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::highlight;
+use crate::normalizer::{self, ResourceNormalizer};
+use crate::pager;
+use crate::report;
+use crate::theme::style;
+use crate::underlying::CliOptions;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Result of show
+#[derive(Debug, Serialize)]
+pub struct ShowResult {
+    pub success: bool,
+    pub resource: String,
+    pub name: String,
+    /// File or `profile: <name>`
+    pub source: String,
+    pub definition: Value,
+}
+
+/// Run the show command
+pub async fn run(
+    args: &ShowArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<ShowResult> {
+    let (text, source) = match args.directory {
+        Some(ref dir) => {
+            let path = dir.join(&args.resource).join(format!("{}.json", args.name));
+            if !path.is_file() {
+                return Err(VqxError::Other(format!(
+                    "{}/{} not found in {}",
+                    args.resource,
+                    args.name,
+                    dir.display()
+                )));
+            }
+            let text = if args.raw {
+                std::fs::read_to_string(&path).map_err(|_| VqxError::FileReadFailed {
+                    path: path.display().to_string(),
+                })?
+            } else {
+                normalizer::read_embedded(&path)?
+            };
+            (text, path.display().to_string())
+        }
+        None => {
+            let (profile_name, profile) = resolve_profile(profile_name)?;
+            let result = underlying_cli(config, "find")
+                .find(
+                    &CliOptions::from_profile(&profile),
+                    &args.resource,
+                    &args.name,
+                )
+                .await?;
+            if !result.success() {
+                return Err(VqxError::CliExecutionFailed {
+                    code: result.code(),
+                    message: result.stderr.trim().to_string(),
+                });
+            }
+            (result.stdout, format!("profile: {}", profile_name))
+        }
+    };
+
+    let value = parse_definition(&text)?.ok_or_else(|| {
+        VqxError::Other(format!(
+            "{}/{} not found ({})",
+            args.resource, args.name, source
+        ))
+    })?;
+    let definition = if args.raw {
+        value
+    } else {
+        ResourceNormalizer::new(config.normalization.clone())
+            .normalize_resource(&args.resource, &value)
+    };
+
+    let result = ShowResult {
+        success: true,
+        resource: args.resource.clone(),
+        name: args.name.clone(),
+        source,
+        definition,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else if args.raw {
+        print!("{}", text);
+        if !text.ends_with('\n') {
+            println!();
+        }
+    } else {
+        pager::page(&render(&result));
+    }
+    Ok(result)
+}
+
+/// The resource in `find` output or a file; `None` for an empty result
+fn parse_definition(text: &str) -> Result<Option<Value>> {
+    if text.trim().is_empty() {
+        return Ok(None);
+    }
+    let value: Value = serde_json::from_str(text).map_err(|e| VqxError::InvalidJson {
+        message: format!("Failed to parse resource definition: {}", e),
+    })?;
+    Ok(match value {
+        Value::Array(mut items) if items.len() == 1 => Some(items.remove(0)),
+        Value::Array(items) if items.is_empty() => None,
+        Value::Null => None,
+        other => Some(other),
+    })
+}
+
+/// Definition as highlighted JSON, followed by its code
+fn render(result: &ShowResult) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{} {}",
+        style(format!("{}/{}", result.resource, result.name)).bold(),
+        style(format!("({})", result.source)).dim()
+    );
+    out.push('\n');
+
+    let mut definition = result.definition.clone();
+    let code = normalizer::code_field(&definition).and_then(|field| {
+        let code = definition.as_object_mut()?.remove(field)?;
+        Some((field, code.as_str()?.to_string()))
+    });
+    let _ = writeln!(out, "{}", highlight::json(&definition));
+
+    if let Some((field, code)) = code {
+        out.push('\n');
+        let _ = writeln!(out, "{}", style(format!("── {} ──", field)).dim());
+        out.push_str(&highlight::vail(&code));
+        if !code.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_definition() {
+        let found = parse_definition(r#"[{"name": "MyProc"}]"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found["name"], "MyProc");
+        assert!(parse_definition("[]").unwrap().is_none());
+        assert!(parse_definition("  \n").unwrap().is_none());
+        assert!(parse_definition("not json").is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! Stats command implementation
+//!
+//! Summarizes an export directory, or a profile's namespace: resource
+//! counts and sizes per type, the largest resources and, when the export
+//! still has `ars_modifiedAt` (it is excluded by the default
+//! normalization), the most recently modified resources.
+//!
+//! Sizes are those of the exported files; a procedure with its code in a
+//! `.vail` file counts both files.
+
+use crate::cli::{OutputFormat, StatsArgs};
+use crate::commands::grep;
+use crate::commands::list::csv_escape;
+use crate::config::Config;
+use crate::error::Result;
+use crate::report;
+use crate::theme::style;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Count and size of one resource type
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeStats {
+    pub resource_type: String,
+    pub count: usize,
+    pub bytes: u64,
+}
+
+/// Size of one resource
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceSize {
+    /// Stable identifier (`<resource_type>/<name>`)
+    pub resource: String,
+    pub bytes: u64,
+}
+
+/// Last modification of one resource
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentChange {
+    pub resource: String,
+    pub modified_at: DateTime<Utc>,
+    pub modified_by: Option<String>,
+}
+
+/// Result of stats operation
+#[derive(Debug, Serialize)]
+pub struct StatsResult {
+    pub success: bool,
+    /// Directory or `profile: <name>`
+    pub source: String,
+    pub resources: usize,
+    pub total_bytes: u64,
+    pub types: Vec<TypeStats>,
+    /// Largest resources first
+    pub largest: Vec<ResourceSize>,
+    /// Most recently modified first; empty without `ars_modifiedAt`
+    pub recently_modified: Vec<RecentChange>,
+}
+
+/// Run stats command
+pub async fn run(
+    args: &StatsArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<StatsResult> {
+    let (dir, source, _temp) = grep::resolve_source(
+        args.directory.as_deref(),
+        profile_name,
+        config,
+        args.refresh,
+        output_format,
+    )
+    .await?;
+
+    let result = collect(&dir, source, args.top)?;
+
+    match output_format {
+        OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Porcelain => {
+            println!("{}", report::render(&result, output_format)?);
+        }
+        OutputFormat::Ndjson => print!("{}", report::to_ndjson(&result.types)?),
+        OutputFormat::Csv => {
+            println!("resource_type,count,bytes");
+            for t in &result.types {
+                println!("{},{},{}", csv_escape(&t.resource_type), t.count, t.bytes);
+            }
+        }
+        OutputFormat::Text => display_result(&result),
+    }
+
+    Ok(result)
+}
+
+/// Gather the statistics of an export directory
+///
+/// Only files inside a resource type directory are counted; `top` limits
+/// the largest and recently modified lists.
+fn collect(dir: &Path, source: String, top: usize) -> Result<StatsResult> {
+    // Per resource: bytes and last modification
+    let mut resources: BTreeMap<(String, String), (u64, Option<RecentChange>)> = BTreeMap::new();
+
+    for path in grep::searchable_files(dir, &[]) {
+        let relative = path.strip_prefix(dir).unwrap_or(&path);
+        let mut components = relative.components();
+        let (Some(resource_type), Some(_)) = (components.next(), components.next()) else {
+            continue;
+        };
+        let resource_type = resource_type.as_os_str().to_string_lossy().into_owned();
+        // `Orders.total.json` and `Orders.total.vail` are the same procedure
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let id = report::resource_id(&resource_type, &name);
+
+        let bytes = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let entry = resources.entry((resource_type, name)).or_default();
+        entry.0 += bytes;
+
+        if path.extension().is_some_and(|e| e == "json") {
+            let json = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|c| serde_json::from_str::<Value>(&c).ok());
+            if let Some(change) = json.and_then(|j| last_change(&id, &j)) {
+                entry.1 = Some(change);
+            }
+        }
+    }
+
+    let mut types: BTreeMap<&str, TypeStats> = BTreeMap::new();
+    for ((resource_type, _), (bytes, _)) in &resources {
+        let stats = types
+            .entry(resource_type.as_str())
+            .or_insert_with(|| TypeStats {
+                resource_type: resource_type.clone(),
+                count: 0,
+                bytes: 0,
+            });
+        stats.count += 1;
+        stats.bytes += bytes;
+    }
+
+    let mut largest: Vec<ResourceSize> = resources
+        .iter()
+        .map(|((resource_type, name), (bytes, _))| ResourceSize {
+            resource: report::resource_id(resource_type, name),
+            bytes: *bytes,
+        })
+        .collect();
+    largest.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.resource.cmp(&b.resource)));
+    largest.truncate(top);
+
+    let mut recently_modified: Vec<RecentChange> =
+        resources.values().filter_map(|(_, c)| c.clone()).collect();
+    recently_modified.sort_by_key(|c| std::cmp::Reverse(c.modified_at));
+    recently_modified.truncate(top);
+
+    Ok(StatsResult {
+        success: true,
+        source,
+        resources: resources.len(),
+        total_bytes: resources.values().map(|(bytes, _)| bytes).sum(),
+        types: types.into_values().collect(),
+        largest,
+        recently_modified,
+    })
+}
+
+/// Last modification recorded in a resource definition
+fn last_change(resource: &str, json: &Value) -> Option<RecentChange> {
+    let modified_at = json.get("ars_modifiedAt")?.as_str()?;
+    let modified_at = DateTime::parse_from_rfc3339(modified_at).ok()?;
+    Some(RecentChange {
+        resource: resource.to_string(),
+        modified_at: modified_at.with_timezone(&Utc),
+        modified_by: json
+            .get("ars_modifiedBy")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    })
+}
+
+/// Human-readable size, e.g. `4.2 KB`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Display the stats to the terminal
+fn display_result(result: &StatsResult) {
+    println!();
+    println!("{}", style("Stats").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Source:    {}", result.source);
+    println!(
+        "  Resources: {} ({})",
+        result.resources,
+        format_size(result.total_bytes)
+    );
+    println!();
+
+    if result.types.is_empty() {
+        println!("{}", style("No resources found.").dim());
+        println!();
+        return;
+    }
+
+    let width = result
+        .types
+        .iter()
+        .map(|t| t.resource_type.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    println!(
+        "  {:<width$}  {:>7}  {:>10}",
+        style("Type").bold(),
+        style("Count").bold(),
+        style("Size").bold(),
+        width = width
+    );
+    for t in &result.types {
+        println!(
+            "  {:<width$}  {:>7}  {:>10}",
+            t.resource_type,
+            t.count,
+            format_size(t.bytes),
+            width = width
+        );
+    }
+    println!();
+
+    println!("{}", style("Largest resources").bold());
+    for r in &result.largest {
+        println!("  {:>10}  {}", format_size(r.bytes), r.resource);
+    }
+    println!();
+
+    if !result.recently_modified.is_empty() {
+        println!("{}", style("Recently modified").bold());
+        for c in &result.recently_modified {
+            println!(
+                "  {}  {}{}",
+                c.modified_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M"),
+                c.resource,
+                c.modified_by
+                    .as_ref()
+                    .map(|by| style(format!(" ({})", by)).dim().to_string())
+                    .unwrap_or_default()
+            );
+        }
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_collect() {
+        let dir = TempDir::new().unwrap();
+        for (rel, content) in [
+            ("export.json", "{}"),
+            (
+                "types/Order.json",
+                r#"{"name":"Order","ars_modifiedAt":"2024-01-15T09:30:00.000Z","ars_modifiedBy":"alice"}"#,
+            ),
+            ("types/Customer.json", r#"{"name":"Customer"}"#),
+            ("procedures/Orders.total.json", r#"{"name":"Orders.total"}"#),
+            ("procedures/Orders.total.vail", "PROCEDURE Orders.total()\n"),
+        ] {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let stats = collect(dir.path(), "test".to_string(), 2).unwrap();
+        assert_eq!(stats.resources, 3);
+        let counts: Vec<_> = stats
+            .types
+            .iter()
+            .map(|t| (t.resource_type.as_str(), t.count))
+            .collect();
+        assert_eq!(counts, vec![("procedures", 1), ("types", 2)]);
+        assert_eq!(
+            stats.total_bytes,
+            stats.types.iter().map(|t| t.bytes).sum::<u64>()
+        );
+
+        // The procedure's JSON and code are counted together
+        assert_eq!(stats.largest.len(), 2);
+        assert_eq!(stats.largest[0].resource, "types/Order");
+        assert_eq!(stats.largest[1].resource, "procedures/Orders.total");
+        assert_eq!(stats.largest[1].bytes, 23 + 25);
+
+        assert_eq!(stats.recently_modified.len(), 1);
+        assert_eq!(stats.recently_modified[0].resource, "types/Order");
+        assert_eq!(
+            stats.recently_modified[0].modified_by.as_deref(),
+            Some("alice")
+        );
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(4300), "4.2 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -3,33 +3,55 @@
 //! Provides bidirectional synchronization between local directories and Vantiq servers.
 //!
 //! Subcommands:
-//! - `sync pull`: Export from remote to local directory
+//! - `sync pull`: Export from remote to local directory (optionally scoped by
+//!   resource type and name)
 //! - `sync push`: Import from local to remote with diff preview and confirmation
+//! - `sync watch`: Push changed files as they are saved (live-reload style)
 //!
 //! The sync command builds on export/import but adds:
 //! - Automatic diff preview before push
-//! - Confirmation prompts
+//! - Pushing only added/modified resources (unless `--all`), or the ones
+//!   picked one by one (`--interactive`)
+//! - Confirmation prompts, or approval by a second person
+//!   (`--require-approval`, see [`crate::approval`])
 //! - Backup creation
 //! - JSON normalization
 
-use crate::cli::{OutputFormat, SyncCommands, SyncPullArgs, SyncPushArgs};
-use crate::commands::diff::{self, DiffResult};
+use crate::approval::{self, ApprovalRequest, ApprovalStore};
+use crate::blocklist;
+use crate::cache::ExportCache;
+use crate::cli::{OutputFormat, SyncCommands, SyncPullArgs, SyncPushArgs, SyncWatchArgs};
+use crate::commands::diff::{self, ChangeKind, DiffResult, ResourceDiff};
 use crate::config::Config;
 use crate::error::{Result, VqxError};
-use crate::normalizer::ResourceNormalizer;
-use crate::profile::ProfileManager;
-use crate::underlying::{CliOptions, UnderlyingCli};
-use console::style;
-use dialoguer::Confirm;
-use indicatif::{ProgressBar, ProgressStyle};
+use crate::filter::ResourceFilter;
+use crate::i18n;
+use crate::lock::OperationLock;
+use crate::manifest;
+use crate::normalizer::{self, ResourceNormalizer};
+use crate::profile::{Profile, ProfileManager};
+use crate::progress::TransferProgress;
+use crate::prompt;
+use crate::render;
+use crate::report;
+use crate::snapshot::{self, SnapshotStore};
+use crate::template;
+use crate::theme::style;
+use crate::underlying::{CliOptions, RetryAttempt, UnderlyingCli};
+use notify::{RecursiveMode, Watcher};
+use schemars::JsonSchema;
 use serde::Serialize;
-use std::path::PathBuf;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tempfile::TempDir;
-use tracing::warn;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
 
 /// Result of sync operation
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, JsonSchema)]
 pub struct SyncResult {
     pub success: bool,
     pub operation: String,
@@ -38,10 +60,12 @@ pub struct SyncResult {
     pub changes: Option<SyncChanges>,
     pub backup_path: Option<PathBuf>,
     pub errors: Vec<String>,
+    /// Transient CLI failures that were retried
+    pub retries: Vec<RetryAttempt>,
 }
 
 /// Summary of changes for sync operation
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct SyncChanges {
     pub added: usize,
     pub removed: usize,
@@ -73,6 +97,9 @@ pub async fn run(
         SyncCommands::Push(args) => {
             run_push(args, config, profile_name, output_format, verbose).await
         }
+        SyncCommands::Watch(args) => {
+            run_watch(args, config, profile_name, output_format, verbose).await
+        }
     }
 }
 
@@ -101,13 +128,21 @@ async fn run_pull(
     let output_dir = &args.directory;
 
     // Display sync pull info
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
-        println!("{}", style("Sync Pull").bold().cyan());
+        println!("{}", style(i18n::t("sync.pull.title")).bold().cyan());
         println!("{}", style("─".repeat(50)).dim());
-        println!("  Profile:   {}", style(profile_name).green());
-        println!("  Server:    {}", profile.url);
-        println!("  Directory: {}", output_dir.display());
+        println!(
+            "  {} {}",
+            i18n::label("label.profile"),
+            style(profile_name).green()
+        );
+        println!("  {} {}", i18n::label("label.server"), profile.url);
+        println!(
+            "  {} {}",
+            i18n::label("label.directory"),
+            output_dir.display()
+        );
         println!();
     }
 
@@ -120,29 +155,16 @@ async fn run_pull(
 
     // Warn about overwriting if directory has content
     if has_content && !args.force {
-        if !matches!(output_format, OutputFormat::Json) {
-            println!(
-                "{}",
-                style("⚠  Directory already contains files. They may be overwritten.").yellow()
-            );
+        if !output_format.is_machine_readable() {
+            println!("{}", style(i18n::t("sync.pull.not_empty")).yellow());
             println!();
         }
 
-        let confirmed = Confirm::new()
-            .with_prompt("Continue with sync pull?")
-            .default(false)
-            .interact()
-            .map_err(|e| VqxError::Other(e.to_string()))?;
+        let confirmed = prompt::confirm(i18n::t("sync.pull.confirm"), "Sync pull", "--force")?;
 
         if !confirmed {
-            return Ok(SyncResult {
-                success: false,
-                operation: "pull".to_string(),
-                directory: output_dir.clone(),
-                files_processed: None,
-                changes: None,
-                backup_path: None,
-                errors: vec!["Cancelled by user".to_string()],
+            return Err(VqxError::Cancelled {
+                operation: "Sync pull".to_string(),
             });
         }
     }
@@ -154,51 +176,57 @@ async fn run_pull(
         })?;
     }
 
+    // Porcelain output lists what the pull changed locally
+    let before = matches!(output_format, OutputFormat::Porcelain).then(|| snapshot(output_dir));
+
     // Progress bar
-    let progress = if !matches!(output_format, OutputFormat::Json) {
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
-                .unwrap(),
-        );
-        pb.set_message("Pulling from Vantiq...");
-        pb.enable_steady_tick(Duration::from_millis(100));
-        Some(pb)
-    } else {
-        None
-    };
+    let mut progress = TransferProgress::new(
+        i18n::t("sync.pull.progress"),
+        !output_format.is_machine_readable(),
+    );
 
     // Build CLI and export
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("sync"))
+        .with_retry_policy(config.retry_policy());
 
     let options = CliOptions::from_profile(&profile);
 
-    let result = cli
-        .export(
-            &options,
-            Some("metadata"),
-            Some(output_dir.to_str().unwrap()),
-            Some(config.default_chunk_size),
-            None,
-            None,
-            None,
-            false,
-        )
+    // Scoped and pruning pulls export to a temp dir first so the fresh
+    // export can be compared with the local tree
+    let filter = ResourceFilter::from_args(&args.filter, &args.resource, args.name.as_deref())?;
+    let scoped = !filter.is_empty();
+    let export_temp = if scoped || args.prune {
+        Some(TempDir::new()?)
+    } else {
+        None
+    };
+    let export_dir = export_temp
+        .as_ref()
+        .map(|t| t.path().to_path_buf())
+        .unwrap_or_else(|| output_dir.clone());
+
+    let export_args = UnderlyingCli::export_args(
+        Some("metadata"),
+        Some(&export_dir),
+        Some(config.default_chunk_size),
+        None,
+        None,
+        None,
+        false,
+    );
+    let result = progress
+        .run(&cli, &options, "export", export_args, config.stall_policy())
         .await?;
 
     if !result.success() {
-        if let Some(ref pb) = progress {
-            pb.finish_and_clear();
-        }
+        progress.finish_and_clear();
 
-        if !matches!(output_format, OutputFormat::Json) {
+        if !output_format.is_machine_readable() {
             println!(
-                "{} Sync pull failed with exit code {}",
+                "{} {}",
                 style("✗").red(),
-                result.code()
+                i18n::tf("sync.pull.failed", &[("code", &result.code())])
             );
         }
 
@@ -210,53 +238,87 @@ async fn run_pull(
             changes: None,
             backup_path: None,
             errors: vec![result.stderr],
+            retries: result.retries,
         });
     }
 
     // Normalize exported files
-    if let Some(ref pb) = progress {
-        pb.set_message("Normalizing JSON files...");
-    }
+    progress.set_phase("Normalizing JSON files...");
 
     let normalizer = ResourceNormalizer::new(config.normalization.clone());
-    let stats = normalizer.normalize_export_directory(output_dir)?;
+    let stats = normalizer.normalize_export_directory(&export_dir)?;
+    progress.finish_and_clear();
 
-    if let Some(ref pb) = progress {
-        pb.finish_and_clear();
-    }
+    let (files_processed, files_removed) = if export_temp.is_some() {
+        let plan = plan_pull(&export_dir, output_dir, &filter);
+
+        // A scoped pull always replaces its scope; --prune asks first
+        let remove_stale = if args.prune && !plan.stale.is_empty() {
+            confirm_prune(&plan.stale, args.yes, output_format)?
+        } else {
+            scoped
+        };
+
+        apply_pull_plan(&export_dir, output_dir, &plan, remove_stale)?
+    } else {
+        (stats.files_processed, 0)
+    };
 
     // Output success
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
         println!("{}", style("─".repeat(50)).dim());
-        println!("{} Sync pull complete", style("✓").green().bold());
-        println!("  Files: {}", stats.files_processed);
-        println!("  Directory: {}", output_dir.display());
+        println!(
+            "{} {}",
+            style("✓").green().bold(),
+            i18n::t("sync.pull.complete")
+        );
+        println!("  {} {}", i18n::label("label.files"), files_processed);
+        if scoped || args.prune {
+            println!(
+                "  {} {}",
+                i18n::label("label.removed"),
+                i18n::tf("sync.pull.removed", &[("count", &files_removed)])
+            );
+        }
+        println!(
+            "  {} {}",
+            i18n::label("label.directory"),
+            output_dir.display()
+        );
         println!();
     }
 
-    // JSON output
-    if matches!(output_format, OutputFormat::Json) {
+    if let Some(before) = before {
+        let after = snapshot(output_dir);
+        let changes = snapshot_changes(&before, &after);
+        print!(
+            "{}",
+            render::porcelain(changes.iter().map(|(change, id)| (*change, id.as_str())))
+        );
+    } else if output_format.is_machine_readable() {
         let json_result = SyncResult {
             success: true,
             operation: "pull".to_string(),
             directory: output_dir.clone(),
-            files_processed: Some(stats.files_processed),
+            files_processed: Some(files_processed),
             changes: None,
             backup_path: None,
             errors: vec![],
+            retries: result.retries.clone(),
         };
-        println!("{}", serde_json::to_string_pretty(&json_result)?);
+        println!("{}", report::render(&json_result, output_format)?);
     }
 
     Ok(SyncResult {
         success: true,
         operation: "pull".to_string(),
         directory: output_dir.clone(),
-        files_processed: Some(stats.files_processed),
+        files_processed: Some(files_processed),
         changes: None,
         backup_path: None,
         errors: vec![],
+        retries: result.retries,
     })
 }
 
@@ -268,6 +330,8 @@ async fn run_push(
     output_format: OutputFormat,
     _verbose: bool,
 ) -> Result<SyncResult> {
+    let filter = ResourceFilter::from_args(&args.filter, &[], None)?;
+
     // Load profile
     let manager = ProfileManager::new()?;
     let profile_name = profile_name.unwrap_or(&manager.store().default_profile);
@@ -284,6 +348,15 @@ async fn run_push(
 
     let input_dir = &args.directory;
 
+    if args.interactive {
+        if output_format.is_machine_readable() {
+            return Err(VqxError::Other(
+                "--interactive needs text output".to_string(),
+            ));
+        }
+        prompt::require_input("Interactive push", "push without --interactive")?;
+    }
+
     // Verify directory exists
     if !input_dir.exists() {
         return Err(VqxError::FileReadFailed {
@@ -299,103 +372,141 @@ async fn run_push(
     }
 
     // Display sync push info
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
-        println!("{}", style("Sync Push").bold().cyan());
+        println!("{}", style(i18n::t("sync.push.title")).bold().cyan());
         println!("{}", style("─".repeat(50)).dim());
-        println!("  Profile:   {}", style(profile_name).green());
-        println!("  Server:    {}", profile.url);
-        println!("  Directory: {}", input_dir.display());
-        println!();
-    }
-
-    // Progress bar
-    let progress = if !matches!(output_format, OutputFormat::Json) {
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
-                .unwrap(),
+        println!(
+            "  {} {}",
+            i18n::label("label.profile"),
+            style(profile_name).green()
         );
-        pb.enable_steady_tick(Duration::from_millis(100));
-        Some(pb)
-    } else {
-        None
-    };
-
-    // First, export current state from server to temp dir for diff
-    if let Some(ref pb) = progress {
-        pb.set_message("Fetching current server state for comparison...");
+        println!("  {} {}", i18n::label("label.server"), profile.url);
+        println!(
+            "  {} {}",
+            i18n::label("label.directory"),
+            input_dir.display()
+        );
+        println!();
     }
 
-    let temp_dir = TempDir::new().map_err(|e| VqxError::Other(e.to_string()))?;
-    let temp_path = temp_dir.path().to_path_buf();
+    // Compare and push with `{{vars.*}}` placeholders rendered for this profile
+    let rendered = template::render_copy(input_dir, profile_name, &profile.vars)?;
+    let push_dir = rendered
+        .as_ref()
+        .map_or(input_dir.clone(), |r| r.path().to_path_buf());
 
     let cli = UnderlyingCli::new(config.cli_path.clone())
-        .with_timeout(config.timeout())
-        .with_retries(config.max_retries, config.retry_delay_ms);
+        .with_timeout(config.timeout_for("sync"))
+        .with_retry_policy(config.retry_policy());
 
     let options = CliOptions::from_profile(&profile);
 
-    // Export current server state
-    let export_result = cli
-        .export(
-            &options,
-            Some("metadata"),
-            Some(temp_path.to_str().unwrap()),
-            Some(config.default_chunk_size),
-            None,
-            None,
-            None,
-            false,
+    // Keep other vqx operations out of this namespace from the export of its
+    // state until the push is done, so the push is decided on what it changes
+    let _lock = if args.dry_run {
+        None
+    } else {
+        Some(
+            OperationLock::acquire(
+                "sync push",
+                profile_name,
+                &profile.url,
+                profile.namespace.as_deref(),
+                &args.lock,
+            )
+            .await?,
         )
+    };
+
+    // First, export the current server state into a temp dir. What gets
+    // pushed is decided against this export, never the export cache: a
+    // stale cache would hide server changes made since it was stored
+    let cache = ExportCache::new(config);
+    let temp_dir = TempDir::new().map_err(|e| VqxError::Other(e.to_string()))?;
+    let server_dir = temp_dir.path().to_path_buf();
+    let mut progress = TransferProgress::new(
+        "Fetching current server state for comparison...",
+        !output_format.is_machine_readable(),
+    );
+
+    // Export current server state
+    let export_args = UnderlyingCli::export_args(
+        Some("metadata"),
+        Some(&server_dir),
+        Some(config.default_chunk_size),
+        None,
+        None,
+        None,
+        false,
+    );
+    let export_result = progress
+        .run(&cli, &options, "export", export_args, config.stall_policy())
         .await?;
 
-    if !export_result.success() {
-        if let Some(ref pb) = progress {
-            pb.finish_and_clear();
-        }
+    let server_state_known = export_result.success();
+    if !server_state_known {
+        progress.finish_and_clear();
 
         // If export fails (e.g., empty namespace), continue without diff
         warn!("Could not export current server state for diff comparison");
     } else {
-        // Normalize exported files
+        // Normalize exported files; the fresh export also refreshes the
+        // cache that read-only diffs use
         let normalizer = ResourceNormalizer::new(config.normalization.clone());
-        let _ = normalizer.normalize_export_directory(&temp_path);
+        if normalizer.normalize_export_directory(&server_dir).is_ok() && cache.is_enabled() {
+            if let Err(e) = cache.store(profile_name, &profile, &config.normalization, &server_dir)
+            {
+                warn!(error = %e, "Could not cache export");
+            }
+        }
     }
 
     // Perform diff
-    if let Some(ref pb) = progress {
-        pb.set_message("Comparing changes...");
-    }
+    progress.set_phase(i18n::t("sync.push.comparing"));
 
     let diff_result = diff::run(
         &crate::cli::DiffArgs {
-            source: temp_path.to_str().unwrap().to_string(),
-            target: input_dir.to_str().unwrap().to_string(),
+            source: server_dir.display().to_string(),
+            target: push_dir.display().to_string(),
             resource: vec![],
-            full: false,
+            filter: args.filter.clone(),
+            // Reviewing resources one by one shows their full diffs
+            full: args.interactive,
+            code_only: false,
+            ignore_path: vec![],
+            breaking: false,
+            format: None,
+            refresh: false,
+            report: None,
+            annotations: false,
+            patch: None,
         },
         config,
         OutputFormat::Text, // Don't output diff as JSON here
         false,
     )
     .await;
-
-    if let Some(ref pb) = progress {
-        pb.finish_and_clear();
-    }
+    progress.finish_and_clear();
 
     // Show diff summary
     let changes = if let Ok(ref diff) = diff_result {
-        if !matches!(output_format, OutputFormat::Json) && diff.has_changes() {
+        if !output_format.is_machine_readable() && diff.has_changes() {
             println!();
-            println!("{}", style("Changes to push:").bold());
+            println!("{}", style(i18n::t("sync.push.changes")).bold());
             println!(
-                "  {} added, {} removed, {} modified",
-                style(format!("+{}", diff.added.len())).green(),
-                style(format!("-{}", diff.removed.len())).red(),
-                style(format!("~{}", diff.modified.len())).yellow()
+                "  {}",
+                i18n::tf(
+                    "sync.push.summary",
+                    &[
+                        ("added", &style(format!("+{}", diff.added.len())).green()),
+                        ("removed", &style(format!("-{}", diff.removed.len())).red()),
+                        (
+                            "modified",
+                            &style(format!("~{}", diff.modified.len())).yellow()
+                        ),
+                    ]
+                )
             );
             println!();
         }
@@ -406,10 +517,13 @@ async fn run_push(
 
     // Dry run mode
     if args.dry_run {
-        if !matches!(output_format, OutputFormat::Json) {
-            println!("{}", style("Dry run - no changes made").dim());
+        if !output_format.is_machine_readable() {
+            println!("{}", style(i18n::t("sync.push.dry_run")).dim());
             println!();
         }
+        if let (OutputFormat::Porcelain, Ok(diff)) = (output_format, &diff_result) {
+            print!("{}", push_porcelain(diff));
+        }
 
         return Ok(SyncResult {
             success: true,
@@ -419,77 +533,164 @@ async fn run_push(
             changes,
             backup_path: None,
             errors: vec![],
+            retries: export_result.retries,
         });
     }
 
+    // Only push added/modified resources unless --all was given or the
+    // server state could not be compared
+    let partial = match diff_result {
+        Ok(ref diff) if args.interactive && server_state_known => {
+            Some(review_changes(&push_dir, diff)?)
+        }
+        Ok(ref diff) if !args.all && server_state_known => Some(changed_files(&push_dir, diff)),
+        _ if args.interactive => return Err(VqxError::Other(
+            "--interactive needs the server state to compare with, but it could not be exported"
+                .to_string(),
+        )),
+        // Without a diff, --include/--exclude still limit what is pushed
+        _ if !filter.is_empty() => Some(
+            push_files(&push_dir)
+                .into_iter()
+                .filter(|rel| filter.matches_path(rel))
+                .collect(),
+        ),
+        _ => None,
+    };
+
+    if matches!(partial, Some(ref files) if files.is_empty()) {
+        if !output_format.is_machine_readable() {
+            println!(
+                "{} {}",
+                style("✓").green().bold(),
+                i18n::t("sync.push.nothing")
+            );
+        }
+        let result = SyncResult {
+            success: true,
+            operation: "push".to_string(),
+            directory: input_dir.clone(),
+            files_processed: Some(0),
+            changes,
+            backup_path: None,
+            errors: vec![],
+            retries: export_result.retries,
+        };
+        if output_format.is_machine_readable() && !matches!(output_format, OutputFormat::Porcelain)
+        {
+            println!("{}", report::render(&result, output_format)?);
+        }
+        return Ok(result);
+    }
+
+    // System and other blocked resources only with --force
+    let blocked = match partial {
+        Some(ref files) => blocklist::scan_files(files, &config.import),
+        None => blocklist::scan(&push_dir, &config.import),
+    };
+    blocklist::check(&blocked, args.force)?;
+    if !output_format.is_machine_readable() {
+        blocklist::display(&blocked);
+    }
+
+    // Approval by a second person replaces the local confirmation
+    let require_approval = args.require_approval || args.approval_token.is_some();
+    if require_approval {
+        let files: Vec<PathBuf> = match partial {
+            Some(ref files) => files.iter().cloned().collect(),
+            None => push_files(&push_dir),
+        };
+        await_approval(
+            args,
+            config,
+            profile_name,
+            &profile,
+            input_dir,
+            &push_dir,
+            &files,
+            output_format,
+        )
+        .await?;
+    }
+
     // Confirmation
-    if !args.yes && !matches!(output_format, OutputFormat::Json) {
-        println!(
-            "{}",
-            style("⚠  Warning: This will modify resources on the server!").yellow()
-        );
+    if !args.yes && !require_approval && !output_format.is_machine_readable() {
+        prompt::require_input("Sync push", "pass --yes to confirm")?;
+        println!("{}", style(i18n::t("sync.push.warning")).yellow());
         println!();
 
-        let confirmed = Confirm::new()
-            .with_prompt(format!(
-                "Push changes to {} ({})?",
-                profile.url, profile_name
-            ))
-            .default(false)
-            .interact()
-            .map_err(|e| VqxError::Other(e.to_string()))?;
+        let confirmed = prompt::confirm(
+            i18n::tf(
+                "sync.push.confirm",
+                &[("url", &profile.url), ("profile", &profile_name)],
+            ),
+            "Sync push",
+            "--yes",
+        )?;
 
         if !confirmed {
-            return Ok(SyncResult {
-                success: false,
-                operation: "push".to_string(),
-                directory: input_dir.clone(),
-                files_processed: None,
-                changes,
-                backup_path: None,
-                errors: vec!["Cancelled by user".to_string()],
+            return Err(VqxError::Cancelled {
+                operation: "Sync push".to_string(),
             });
         }
     }
 
-    // Progress for import
-    let progress = if !matches!(output_format, OutputFormat::Json) {
-        let pb = ProgressBar::new_spinner();
-        pb.set_style(
-            ProgressStyle::default_spinner()
-                .template("{spinner:.green} {msg}")
-                .unwrap(),
-        );
-        pb.set_message("Pushing to Vantiq...");
-        pb.enable_steady_tick(Duration::from_millis(100));
-        Some(pb)
-    } else {
-        None
+    let staging = match partial {
+        Some(ref files) => Some(stage_files(&push_dir, files)?),
+        None => normalizer::stage_for_import(&push_dir)?,
     };
+    let import_dir = staging
+        .as_ref()
+        .map(|s| s.path().to_path_buf())
+        .unwrap_or_else(|| push_dir.clone());
+
+    // Snapshot a protected profile so the push can be rolled back
+    let backup_path = snapshot::take(
+        config,
+        &cli,
+        profile_name,
+        &profile,
+        "sync push",
+        !output_format.is_machine_readable(),
+    )
+    .await?
+    .map(|s| SnapshotStore::new(config).path(&s.id));
+
+    // Progress for import
+    let mut progress = TransferProgress::new(
+        i18n::t("sync.push.progress"),
+        !output_format.is_machine_readable(),
+    );
+
+    // The cached server state is outdated from here on, even if the import fails
+    cache.invalidate(profile_name, profile.namespace.as_deref());
 
     // Execute import
-    let import_result = cli
-        .import(
-            &options,
-            Some("metadata"),
-            Some(input_dir.to_str().unwrap()),
-            Some(config.default_chunk_size),
-            None,
-            None,
-            None,
-        )
+    let import_args = UnderlyingCli::import_args(
+        Some("metadata"),
+        Some(&import_dir),
+        Some(config.default_chunk_size),
+        None,
+        None,
+        None,
+    );
+    let import_result = progress
+        .run(&cli, &options, "import", import_args, config.stall_policy())
         .await?;
-
-    if let Some(ref pb) = progress {
-        pb.finish_and_clear();
-    }
+    progress.finish_and_clear();
+    let retries: Vec<RetryAttempt> = export_result
+        .retries
+        .iter()
+        .chain(&import_result.retries)
+        .cloned()
+        .collect();
 
     if !import_result.success() {
-        if !matches!(output_format, OutputFormat::Json) {
+        if !output_format.is_machine_readable() {
             println!(
-                "{} Sync push failed with exit code {}",
+                "{} {}",
                 style("✗").red(),
-                import_result.code()
+                i18n::tf("sync.push.failed", &[("code", &import_result.code())])
             );
             if !import_result.stderr.is_empty() {
                 println!("{}", style(&import_result.stderr).red());
@@ -502,36 +703,48 @@ async fn run_push(
             directory: input_dir.clone(),
             files_processed: None,
             changes,
-            backup_path: None,
+            backup_path,
             errors: vec![import_result.stderr],
+            retries,
         });
     }
 
     // Count files
-    let files_count = count_files(input_dir);
+    let files_count = match partial {
+        Some(ref files) => files.len(),
+        None => count_files(input_dir),
+    };
 
     // Output success
-    if !matches!(output_format, OutputFormat::Json) {
+    if !output_format.is_machine_readable() {
         println!();
         println!("{}", style("─".repeat(50)).dim());
-        println!("{} Sync push complete", style("✓").green().bold());
-        println!("  Files: {}", files_count);
-        println!("  Server: {}", profile.url);
+        println!(
+            "{} {}",
+            style("✓").green().bold(),
+            i18n::t("sync.push.complete")
+        );
+        println!("  {} {}", i18n::label("label.files"), files_count);
+        println!("  {} {}", i18n::label("label.server"), profile.url);
         println!();
     }
 
-    // JSON output
-    if matches!(output_format, OutputFormat::Json) {
+    if let OutputFormat::Porcelain = output_format {
+        if let Ok(ref diff) = diff_result {
+            print!("{}", push_porcelain(diff));
+        }
+    } else if output_format.is_machine_readable() {
         let json_result = SyncResult {
             success: true,
             operation: "push".to_string(),
             directory: input_dir.clone(),
             files_processed: Some(files_count),
             changes: changes.clone(),
-            backup_path: None,
+            backup_path: backup_path.clone(),
             errors: vec![],
+            retries: retries.clone(),
         };
-        println!("{}", serde_json::to_string_pretty(&json_result)?);
+        println!("{}", report::render(&json_result, output_format)?);
     }
 
     Ok(SyncResult {
@@ -540,11 +753,709 @@ async fn run_push(
         directory: input_dir.clone(),
         files_processed: Some(files_count),
         changes,
-        backup_path: None,
+        backup_path,
         errors: vec![],
+        retries,
     })
 }
 
+/// Run sync watch (push changed files on save until interrupted)
+///
+/// Changes are debounced: once a file changes, vqx waits until no further
+/// changes arrive for `--debounce-ms` and then pushes everything that changed
+/// in one import. Only the changed resources are staged into a temp
+/// directory with the same `<resourceType>/<file>` layout, so unrelated
+/// resources are not re-imported; a definition and its `.vail` code are
+/// staged together when either changes. Deleted files are not pushed.
+async fn run_watch(
+    args: &SyncWatchArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+    _verbose: bool,
+) -> Result<SyncResult> {
+    // Load profile
+    let manager = ProfileManager::new()?;
+    let profile_name = profile_name.unwrap_or(&manager.store().default_profile);
+    let profile = manager.get_resolved(profile_name)?;
+
+    if !profile.has_auth() {
+        return Err(VqxError::ProfileInvalid {
+            message: format!(
+                "Profile '{}' has no authentication configured",
+                profile_name
+            ),
+        });
+    }
+
+    // Watch pushes skip the snapshot and confirmation of a protected profile
+    if !profile.protection.is_open() {
+        return Err(VqxError::Other(format!(
+            "Profile '{}' is protected; sync watch cannot snapshot or confirm each push, use `vqx sync push` instead",
+            profile_name
+        )));
+    }
+
+    let watch_dir = &args.directory;
+    if !watch_dir.is_dir() {
+        return Err(VqxError::Other(format!(
+            "Not a directory: {}",
+            watch_dir.display()
+        )));
+    }
+    let watch_dir = watch_dir
+        .canonicalize()
+        .map_err(|e| VqxError::Other(e.to_string()))?;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            if event.kind.is_create() || event.kind.is_modify() {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+        }
+    })
+    .map_err(|e| VqxError::Other(format!("Failed to start file watcher: {}", e)))?;
+    watcher
+        .watch(&watch_dir, RecursiveMode::Recursive)
+        .map_err(|e| VqxError::Other(format!("Failed to watch directory: {}", e)))?;
+
+    let json = output_format.is_machine_readable();
+    if !json {
+        println!();
+        println!("{}", style("Sync Watch").bold().cyan());
+        println!("{}", style("─".repeat(50)).dim());
+        println!("  Profile:   {}", style(profile_name).green());
+        println!("  Server:    {}", profile.url);
+        println!("  Directory: {}", watch_dir.display());
+        println!();
+        println!(
+            "{}",
+            style("Watching for changes (Ctrl-C to stop)...").dim()
+        );
+    }
+
+    let cli = UnderlyingCli::new(config.cli_path.clone())
+        .with_timeout(config.timeout_for("sync"))
+        .with_retry_policy(config.retry_policy());
+    let options = CliOptions::from_profile(&profile);
+    let debounce = Duration::from_millis(args.debounce_ms);
+    let cache = ExportCache::new(config);
+
+    let mut files_pushed = 0;
+    let mut errors = Vec::new();
+
+    // Stop on Ctrl-C (after the push in progress) or when the watcher closes
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        let first = tokio::select! {
+            event = rx.recv() => match event {
+                Some(path) => path,
+                None => break,
+            },
+            _ = &mut ctrl_c => break,
+        };
+        let mut changed = BTreeSet::new();
+        changed.extend(watched_resource_file(&watch_dir, &first));
+        while let Ok(Some(path)) = tokio::time::timeout(debounce, rx.recv()).await {
+            changed.extend(watched_resource_file(&watch_dir, &path));
+        }
+
+        // Skip files that were removed again before the debounce settled
+        changed.retain(|rel| watch_dir.join(rel).is_file());
+        if changed.is_empty() {
+            continue;
+        }
+        debug!(files = changed.len(), "Pushing changed files");
+
+        // Same block list and lock as sync push, checked for every batch
+        let import_result = async {
+            blocklist::check(&blocklist::scan_files(&changed, &config.import), args.force)?;
+            let _lock = OperationLock::acquire(
+                "sync watch",
+                profile_name,
+                &profile.url,
+                profile.namespace.as_deref(),
+                &args.lock,
+            )
+            .await?;
+            let staging = stage_files(&watch_dir, &with_resource_files(&watch_dir, &changed))?;
+            cache.invalidate(profile_name, profile.namespace.as_deref());
+            template::render_directory(staging.path(), profile_name, &profile.vars)?;
+            cli.import(
+                &options,
+                Some("metadata"),
+                Some(staging.path()),
+                Some(config.default_chunk_size),
+                None,
+                None,
+                None,
+            )
+            .await
+        }
+        .await;
+
+        let time = chrono::Local::now().format("%H:%M:%S").to_string();
+        let error = match import_result {
+            Ok(r) if r.success() => None,
+            Ok(r) => Some(if r.stderr.trim().is_empty() {
+                format!("exit code {}", r.code())
+            } else {
+                r.stderr.trim().to_string()
+            }),
+            Err(e) => Some(e.to_string()),
+        };
+
+        match error {
+            None => {
+                files_pushed += changed.len();
+                if !json {
+                    for rel in &changed {
+                        println!(
+                            "{} {} {}",
+                            style(&time).dim(),
+                            style("✓").green(),
+                            rel.display()
+                        );
+                    }
+                }
+            }
+            Some(error) => {
+                warn!(%error, "Push failed");
+                if !json {
+                    println!(
+                        "{} {} Push of {} file(s) failed: {}",
+                        style(&time).dim(),
+                        style("✗").red(),
+                        changed.len(),
+                        error
+                    );
+                }
+                errors.push(error);
+            }
+        }
+    }
+
+    let result = SyncResult {
+        success: errors.is_empty(),
+        operation: "watch".to_string(),
+        directory: args.directory.clone(),
+        files_processed: Some(files_pushed),
+        changes: None,
+        backup_path: None,
+        errors,
+        retries: vec![],
+    };
+    if json {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        let failed = style(result.errors.len());
+        let failed = if result.errors.is_empty() {
+            failed.dim()
+        } else {
+            failed.red()
+        };
+        println!();
+        println!(
+            "Stopped watching: {} file(s) pushed, {} failed push(es)",
+            style(files_pushed).green(),
+            failed
+        );
+    }
+    Ok(result)
+}
+
+/// How a fresh export is applied to the local tree (paths are relative)
+#[derive(Debug, Default, PartialEq)]
+struct PullPlan {
+    /// Files to copy from the export
+    copy: Vec<PathBuf>,
+    /// Local files in scope that are no longer on the server
+    stale: Vec<PathBuf>,
+}
+
+/// Show local files deleted on the server and ask whether to remove them
+fn confirm_prune(stale: &[PathBuf], yes: bool, output_format: OutputFormat) -> Result<bool> {
+    let json = output_format.is_machine_readable();
+    if !json {
+        println!();
+        println!(
+            "{} {} local file(s) no longer exist on the server:",
+            style("⚠").yellow(),
+            stale.len()
+        );
+        for rel in stale {
+            println!("  {} {}", style("-").red(), rel.display());
+        }
+        println!();
+    }
+
+    if yes {
+        return Ok(true);
+    }
+    if json {
+        // No prompt in JSON mode; keep files unless --yes
+        return Ok(false);
+    }
+
+    prompt::confirm("Delete these files?", "Pruning stale files", "--yes")
+}
+
+/// Work out which files a pull copies and which local files are stale
+///
+/// Only resources selected by `filter` are in scope. Files outside the
+/// resource type directories are never stale. Without a filter, every file
+/// of the export is copied.
+fn plan_pull(export_dir: &Path, output_dir: &Path, filter: &ResourceFilter) -> PullPlan {
+    let exported = std::fs::read_dir(export_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().to_str().map(String::from));
+    let types: BTreeSet<String> = diff::RESOURCE_TYPES
+        .iter()
+        .map(|t| t.to_string())
+        .chain(exported)
+        .filter(|t| filter.matches_type(t))
+        .collect();
+
+    let files_under = |root: &Path, dir: &Path| -> BTreeSet<PathBuf> {
+        walkdir::WalkDir::new(dir)
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.path().strip_prefix(root).ok().map(Path::to_path_buf))
+            .filter(|rel| filter.matches_path(rel))
+            .collect()
+    };
+
+    let mut plan = PullPlan::default();
+    let unfiltered = filter.is_empty();
+    if unfiltered {
+        plan.copy = files_under(export_dir, export_dir).into_iter().collect();
+    }
+
+    for resource_type in &types {
+        let fresh = files_under(export_dir, &export_dir.join(resource_type));
+        let local = files_under(output_dir, &output_dir.join(resource_type));
+
+        plan.stale.extend(local.difference(&fresh).cloned());
+        if !unfiltered {
+            plan.copy.extend(fresh);
+        }
+    }
+
+    plan
+}
+
+/// Copy the planned files into the local tree, removing stale files if asked
+///
+/// Returns the number of files copied and removed.
+fn apply_pull_plan(
+    export_dir: &Path,
+    output_dir: &Path,
+    plan: &PullPlan,
+    remove_stale: bool,
+) -> Result<(usize, usize)> {
+    for rel in &plan.copy {
+        let dest = output_dir.join(rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent).map_err(|_| VqxError::FileWriteFailed {
+                path: parent.display().to_string(),
+            })?;
+        }
+        std::fs::copy(export_dir.join(rel), &dest).map_err(|_| VqxError::FileWriteFailed {
+            path: dest.display().to_string(),
+        })?;
+    }
+
+    let mut removed = 0;
+    if remove_stale {
+        for rel in &plan.stale {
+            std::fs::remove_file(output_dir.join(rel))?;
+            removed += 1;
+        }
+    }
+
+    Ok((plan.copy.len(), removed))
+}
+
+/// Files backing the added and modified resources of a push diff
+///
+/// The diff names resources by file stem, so every file with that stem in
+/// the resource type directory is included (e.g. `Foo.json` and `Foo.vail`).
+fn changed_files(input_dir: &Path, diff: &DiffResult) -> BTreeSet<PathBuf> {
+    diff.added
+        .iter()
+        .chain(diff.modified.iter())
+        .flat_map(|change| resource_files(input_dir, change))
+        .collect()
+}
+
+/// Files of one resource, relative to `input_dir`
+fn resource_files(input_dir: &Path, change: &ResourceDiff) -> Vec<PathBuf> {
+    stem_files(input_dir, Path::new(&change.resource_type), &change.name)
+}
+
+/// Files of `dir` (relative to `root`) named `<stem>.<ext>`, relative to `root`
+fn stem_files(root: &Path, dir: &Path, stem: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(root.join(dir)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| {
+            let path = entry.path();
+            path.is_file() && path.file_stem().and_then(|s| s.to_str()) == Some(stem)
+        })
+        .map(|entry| dir.join(entry.file_name()))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Changed files with the other files of their resources
+///
+/// A definition and its extracted `.vail` code are imported together: a
+/// bare `.vail` file is not a resource, and a definition referring to one
+/// cannot be staged without it.
+fn with_resource_files(root: &Path, changed: &BTreeSet<PathBuf>) -> BTreeSet<PathBuf> {
+    changed
+        .iter()
+        .flat_map(|rel| {
+            let stem = rel.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            let files = stem_files(root, rel.parent().unwrap_or(Path::new("")), stem);
+            if files.is_empty() {
+                vec![rel.clone()]
+            } else {
+                files
+            }
+        })
+        .collect()
+}
+
+/// Porcelain lines of the resources a push sends (added and modified)
+fn push_porcelain(diff: &DiffResult) -> String {
+    render::porcelain(
+        diff.added
+            .iter()
+            .chain(&diff.modified)
+            .map(|d| (d.change, d.id.as_str())),
+    )
+}
+
+/// Content hash of every resource (`<type>/<name>`) in an export directory
+///
+/// All files with the resource's stem count, so a change to an extracted
+/// `.vail` file modifies its resource.
+fn snapshot(dir: &Path) -> BTreeMap<String, u64> {
+    let mut hashers: BTreeMap<String, DefaultHasher> = BTreeMap::new();
+    for entry in walkdir::WalkDir::new(dir)
+        .min_depth(2)
+        .max_depth(2)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+    {
+        let path = entry.path();
+        let (Some(resource_type), Some(name)) = (
+            path.parent()
+                .and_then(|p| p.file_name())
+                .and_then(|s| s.to_str()),
+            path.file_stem().and_then(|s| s.to_str()),
+        ) else {
+            continue;
+        };
+        let Ok(content) = std::fs::read(path) else {
+            continue;
+        };
+        let hasher = hashers
+            .entry(report::resource_id(resource_type, name))
+            .or_default();
+        entry.file_name().hash(hasher);
+        content.hash(hasher);
+    }
+    hashers
+        .into_iter()
+        .map(|(id, hasher)| (id, hasher.finish()))
+        .collect()
+}
+
+/// Changes between two snapshots of the same directory
+fn snapshot_changes(
+    before: &BTreeMap<String, u64>,
+    after: &BTreeMap<String, u64>,
+) -> Vec<(ChangeKind, String)> {
+    let mut changes = Vec::new();
+    for (id, hash) in after {
+        match before.get(id) {
+            None => changes.push((ChangeKind::Added, id.clone())),
+            Some(old) if old != hash => changes.push((ChangeKind::Modified, id.clone())),
+            Some(_) => {}
+        }
+    }
+    for id in before.keys().filter(|id| !after.contains_key(*id)) {
+        changes.push((ChangeKind::Removed, id.clone()));
+    }
+    changes
+}
+
+/// Answer for one resource in `sync push --interactive`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Review {
+    Push,
+    Skip,
+    PushRest,
+    SkipRest,
+    Quit,
+}
+
+const REVIEW_CHOICES: &[(Review, &str)] = &[
+    (Review::Push, "Push this resource"),
+    (Review::Skip, "Skip this resource"),
+    (Review::PushRest, "Push this and all remaining resources"),
+    (Review::SkipRest, "Skip this and all remaining resources"),
+    (Review::Quit, "Quit without pushing anything"),
+];
+
+/// Step through the added and modified resources, like `git add -p`
+///
+/// Returns the files of the resources chosen for the push.
+fn review_changes(push_dir: &Path, diff: &DiffResult) -> Result<BTreeSet<PathBuf>> {
+    let changes: Vec<&ResourceDiff> = diff.added.iter().chain(diff.modified.iter()).collect();
+    let mut answers = Vec::with_capacity(changes.len());
+    let mut rest = None;
+
+    for (i, change) in changes.iter().enumerate() {
+        let answer = match rest {
+            Some(answer) => answer,
+            None => {
+                show_change(push_dir, change, i + 1, changes.len());
+                let choice = prompt::select(
+                    format!("{}/{}", change.resource_type, change.name),
+                    &REVIEW_CHOICES
+                        .iter()
+                        .map(|(_, label)| *label)
+                        .collect::<Vec<_>>(),
+                    "Interactive push",
+                    "drop --interactive",
+                )?;
+                REVIEW_CHOICES[choice].0
+            }
+        };
+        match answer {
+            Review::PushRest => rest = Some(Review::Push),
+            Review::SkipRest => rest = Some(Review::Skip),
+            Review::Quit => {
+                return Err(VqxError::Cancelled {
+                    operation: "Sync push".to_string(),
+                })
+            }
+            _ => {}
+        }
+        answers.push(answer);
+    }
+
+    let selected = staged_files(push_dir, &changes, &answers);
+    println!(
+        "  {} of {} resource(s) selected",
+        answers
+            .iter()
+            .filter(|a| matches!(a, Review::Push | Review::PushRest))
+            .count(),
+        changes.len()
+    );
+    println!();
+    Ok(selected)
+}
+
+/// Files of the resources answered with push
+fn staged_files(
+    push_dir: &Path,
+    changes: &[&ResourceDiff],
+    answers: &[Review],
+) -> BTreeSet<PathBuf> {
+    changes
+        .iter()
+        .zip(answers)
+        .filter(|(_, answer)| matches!(answer, Review::Push | Review::PushRest))
+        .flat_map(|(change, _)| resource_files(push_dir, change))
+        .collect()
+}
+
+/// Print the diff of one resource; added resources show their definition
+fn show_change(push_dir: &Path, change: &ResourceDiff, index: usize, total: usize) {
+    let added = change.change == ChangeKind::Added;
+    println!(
+        "{} {}/{} {}",
+        style(format!("[{}/{}]", index, total)).dim(),
+        change.resource_type,
+        style(&change.name).bold(),
+        if added {
+            style("(new)").green()
+        } else {
+            style("(modified)").yellow()
+        }
+    );
+    let text = match change.diff_text {
+        Some(ref text) if !added => text.clone(),
+        _ => resource_files(push_dir, change)
+            .iter()
+            .filter_map(|rel| std::fs::read_to_string(push_dir.join(rel)).ok())
+            .flat_map(|content| {
+                content
+                    .lines()
+                    .map(|line| format!("+{}\n", line))
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+    };
+    for line in text.lines() {
+        let line = if line.starts_with('+') {
+            style(line).green().to_string()
+        } else if line.starts_with('-') {
+            style(line).red().to_string()
+        } else {
+            line.to_string()
+        };
+        println!("    {}", line);
+    }
+    println!();
+}
+
+/// Map a watcher event path to a pushable resource file relative to `root`
+///
+/// Accepts `.json`/`.vail` files inside a resource type directory; hidden
+/// files, editor temp files and hidden resources (`_hidden/`) are ignored.
+fn watched_resource_file(root: &Path, path: &Path) -> Option<PathBuf> {
+    let rel = path.strip_prefix(root).ok()?;
+    if rel.components().count() < 2 || manifest::is_hidden_path(rel) {
+        return None;
+    }
+
+    let hidden = rel
+        .components()
+        .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+    let ext = rel.extension().and_then(|e| e.to_str());
+    if hidden || !matches!(ext, Some("json") | Some("vail")) {
+        return None;
+    }
+
+    Some(rel.to_path_buf())
+}
+
+/// Copy changed files into a temp directory keeping their relative layout
+///
+/// Extracted VAIL code is re-embedded in the staged definitions.
+fn stage_files(root: &Path, files: &BTreeSet<PathBuf>) -> Result<TempDir> {
+    let staging = TempDir::new()?;
+    for rel in files {
+        let dest = staging.path().join(rel);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(root.join(rel), &dest).map_err(|_| VqxError::FileReadFailed {
+            path: root.join(rel).display().to_string(),
+        })?;
+    }
+    normalizer::embed_code_directory(staging.path())?;
+    Ok(staging)
+}
+
+/// JSON and VAIL files below `dir`, relative to it
+fn push_files(dir: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            matches!(
+                e.path().extension().and_then(|x| x.to_str()),
+                Some("json") | Some("vail")
+            )
+        })
+        .filter_map(|e| e.path().strip_prefix(dir).ok().map(Path::to_path_buf))
+        .collect()
+}
+
+/// Wait until the push plan is approved out of band
+///
+/// With `--approval-token`, the token must be valid for the plan hash.
+/// Otherwise an approval request is written and polled until someone else
+/// runs `vqx approve <hash>`.
+#[allow(clippy::too_many_arguments)]
+async fn await_approval(
+    args: &SyncPushArgs,
+    config: &Config,
+    profile_name: &str,
+    profile: &Profile,
+    input_dir: &Path,
+    push_dir: &Path,
+    files: &[PathBuf],
+    output_format: OutputFormat,
+) -> Result<()> {
+    approval::check_pusher(&config.approval)?;
+    let hash = approval::plan_hash(profile_name, profile, push_dir, files)?;
+
+    if let Some(ref token) = args.approval_token {
+        let Some(approver) = approval::verify_token(&config.approval.trusted_keys, &hash, token)
+        else {
+            return Err(VqxError::ApprovalRejected {
+                message: format!("the approval token is not valid for plan {}", hash),
+            });
+        };
+        debug!(hash = %hash, approver = %approver, "Approval token verified");
+        return Ok(());
+    }
+
+    let store = ApprovalStore::new(config)?;
+    let request = ApprovalRequest {
+        hash: hash.clone(),
+        operation: "sync push".to_string(),
+        profile: profile_name.to_string(),
+        url: profile.url.clone(),
+        namespace: profile.namespace.clone(),
+        directory: input_dir.to_path_buf(),
+        files: files
+            .iter()
+            .map(|f| f.to_string_lossy().replace('\\', "/"))
+            .collect(),
+        requested_by: approval::current_user(),
+        requested_at: chrono::Utc::now(),
+    };
+    store.request(&request)?;
+
+    // Keep stdout clean for machine-readable output
+    let message = format!(
+        "Waiting for approval of plan {} ({} file(s)); run `vqx approve {}` with an approver key",
+        hash,
+        files.len(),
+        hash
+    );
+    if output_format.is_machine_readable() {
+        eprintln!("{}", message);
+    } else {
+        println!("{} {}", style("⏳").yellow(), message);
+        println!();
+    }
+
+    let approver = store.wait(&hash, &config.approval.trusted_keys).await?;
+    if !output_format.is_machine_readable() {
+        println!(
+            "{} Approved by {}",
+            style("✓").green(),
+            style(&approver).bold()
+        );
+        println!();
+    }
+    Ok(())
+}
+
 /// Count files in directory recursively
 fn count_files(dir: &PathBuf) -> usize {
     let mut count = 0;
@@ -566,6 +1477,75 @@ fn count_files(dir: &PathBuf) -> usize {
     count
 }
 
+/// Show the annotated guide for the sync command (`vqx explain sync`)
+pub fn display_help() {
+    println!();
+    println!("{}", style("Sync Command").bold().cyan());
+    println!("{}", style("─".repeat(60)).dim());
+    println!();
+    println!("Keep a local directory and a Vantiq namespace in step.");
+    println!();
+    println!(
+        "{}",
+        style("⚠  Warning: 'sync push' changes resources on the server!").yellow()
+    );
+    println!("   A diff is shown and confirmed before anything is imported.");
+    println!();
+    println!(
+        "{}",
+        style("PDF Reference: Export and Import sections").bold()
+    );
+    println!();
+    println!("{}", style("Subcommands:").bold());
+    println!("  pull    Export metadata into the directory (PDF: 'export metadata')");
+    println!("  push    Import the changed resources (PDF: 'import metadata')");
+    println!("  watch   Push files as they are saved");
+    println!();
+    println!("{}", style("Pull Options:").bold());
+    println!("  -d <dir>             Local directory (PDF: '-d <directoryName>')");
+    println!("  --resource <types>   Only pull these resource types");
+    println!("  --name <glob>        Only pull resources whose name matches");
+    println!("  --prune              Remove local files deleted on the server");
+    println!("  --force              Overwrite local changes");
+    println!();
+    println!("{}", style("Push Options:").bold());
+    println!("  -d <dir>             Local directory (PDF: '-d <directoryName>')");
+    println!("  --dry-run            Only show what would be pushed");
+    println!("  --all                Import the whole directory, not only changes");
+    println!("  --interactive, -i    Choose the resources to push one by one");
+    println!();
+    println!("{}", style("Safety Options:").bold());
+    println!("  --yes, -y            Skip confirmation prompt");
+    println!("  --require-approval   Wait for a second person to run 'vqx approve'");
+    println!("  --wait[=<seconds>]   Wait for other vqx operations on the namespace");
+    println!();
+    println!("{}", style("Examples:").bold());
+    println!();
+    println!("  # Pull the procedures and types of dev");
+    println!(
+        "  {} vqx -s dev sync pull -d ./export --resource procedures,types",
+        style("$").dim()
+    );
+    println!();
+    println!("  # Preview, then push local changes");
+    println!(
+        "  {} vqx -s dev sync push -d ./export --dry-run",
+        style("$").dim()
+    );
+    println!("  {} vqx -s dev sync push -d ./export", style("$").dim());
+    println!();
+    println!("  # Push on save while developing");
+    println!("  {} vqx -s dev sync watch -d ./export", style("$").dim());
+    println!();
+    println!("{}", style("Note:").dim());
+    println!(
+        "{}",
+        style("  Push never deletes resources on the server; use 'vqx safe-delete' for that.")
+            .dim()
+    );
+    println!();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -580,6 +1560,7 @@ mod tests {
             removed: vec![],
             modified: vec![],
             errors: vec![],
+            breaking: None,
         };
 
         let changes = SyncChanges::from(&diff_result);
@@ -587,4 +1568,246 @@ mod tests {
         assert_eq!(changes.removed, 0);
         assert_eq!(changes.modified, 0);
     }
+
+    #[test]
+    fn test_watched_resource_file() {
+        let root = Path::new("/work/src");
+        assert_eq!(
+            watched_resource_file(root, Path::new("/work/src/procedures/Foo.vail")),
+            Some(PathBuf::from("procedures/Foo.vail"))
+        );
+        assert_eq!(
+            watched_resource_file(root, Path::new("/work/src/types/Order.json")),
+            Some(PathBuf::from("types/Order.json"))
+        );
+        assert_eq!(
+            watched_resource_file(root, Path::new("/work/src/README.json")),
+            None
+        );
+        assert_eq!(
+            watched_resource_file(root, Path::new("/work/src/types/.Order.json.swp")),
+            None
+        );
+        assert_eq!(
+            watched_resource_file(root, Path::new("/work/src/types/Order.json~")),
+            None
+        );
+        assert_eq!(
+            watched_resource_file(root, Path::new("/elsewhere/types/Order.json")),
+            None
+        );
+        assert_eq!(
+            watched_resource_file(root, Path::new("/work/src/_hidden/types/Sys.json")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_changed_files_from_diff() {
+        use crate::commands::diff::ResourceDiff;
+
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join("procedures")).unwrap();
+        for file in ["Foo.json", "Foo.vail", "Bar.json", "FooBar.json"] {
+            std::fs::write(root.path().join("procedures").join(file), "{}").unwrap();
+        }
+
+        let diff_result = DiffResult {
+            success: true,
+            source: "server".to_string(),
+            target: "local".to_string(),
+            added: vec![],
+            removed: vec![ResourceDiff::new(
+                "procedures",
+                "Gone",
+                ChangeKind::Removed,
+                None,
+            )],
+            modified: vec![ResourceDiff::new(
+                "procedures",
+                "Foo",
+                ChangeKind::Modified,
+                None,
+            )],
+            errors: vec![],
+            breaking: None,
+        };
+
+        let files: Vec<_> = changed_files(root.path(), &diff_result)
+            .into_iter()
+            .collect();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from("procedures/Foo.json"),
+                PathBuf::from("procedures/Foo.vail")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_staged_files_follow_answers() {
+        use crate::commands::diff::ResourceDiff;
+
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join("types")).unwrap();
+        for file in ["A.json", "B.json", "C.json"] {
+            std::fs::write(root.path().join("types").join(file), "{}").unwrap();
+        }
+        let changes: Vec<ResourceDiff> = ["A", "B", "C"]
+            .iter()
+            .map(|name| ResourceDiff::new("types", name, ChangeKind::Modified, None))
+            .collect();
+        let changes: Vec<&ResourceDiff> = changes.iter().collect();
+
+        let files: Vec<_> = staged_files(
+            root.path(),
+            &changes,
+            &[Review::Push, Review::Skip, Review::PushRest],
+        )
+        .into_iter()
+        .collect();
+        assert_eq!(
+            files,
+            vec![PathBuf::from("types/A.json"), PathBuf::from("types/C.json")]
+        );
+    }
+
+    fn write_file(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_scoped_pull_plan() {
+        let export = TempDir::new().unwrap();
+        let local = TempDir::new().unwrap();
+
+        write_file(export.path(), "types/Order.json", "new");
+        write_file(export.path(), "types/Customer.json", "server");
+        write_file(export.path(), "rules/OrderRule.vail", "server");
+        write_file(local.path(), "types/Order.json", "old");
+        write_file(local.path(), "types/OrderLine.json", "deleted on server");
+        write_file(local.path(), "types/Customer.json", "local");
+        write_file(local.path(), "rules/OrderRule.vail", "local");
+
+        let filter = ResourceFilter::new(&["types/Order*"], &[]).unwrap();
+        let plan = plan_pull(export.path(), local.path(), &filter);
+        assert_eq!(plan.copy, vec![PathBuf::from("types/Order.json")]);
+        assert_eq!(plan.stale, vec![PathBuf::from("types/OrderLine.json")]);
+
+        let (copied, removed) = apply_pull_plan(export.path(), local.path(), &plan, true).unwrap();
+        assert_eq!((copied, removed), (1, 1));
+        let read = |rel: &str| std::fs::read_to_string(local.path().join(rel)).unwrap();
+        assert_eq!(read("types/Order.json"), "new");
+        assert!(!local.path().join("types/OrderLine.json").exists());
+        // Outside the scope: untouched
+        assert_eq!(read("types/Customer.json"), "local");
+        assert_eq!(read("rules/OrderRule.vail"), "local");
+    }
+
+    #[test]
+    fn test_prune_plan_only_touches_resource_dirs() {
+        let export = TempDir::new().unwrap();
+        let local = TempDir::new().unwrap();
+
+        write_file(export.path(), "types/Order.json", "{}");
+        write_file(export.path(), "export.json", "{}");
+        write_file(local.path(), "types/Order.json", "{}");
+        write_file(local.path(), "procedures/Gone.vail", "");
+        write_file(local.path(), "README.md", "");
+        write_file(local.path(), ".git/config", "");
+
+        let plan = plan_pull(export.path(), local.path(), &ResourceFilter::default());
+        assert_eq!(
+            plan.copy,
+            vec![
+                PathBuf::from("export.json"),
+                PathBuf::from("types/Order.json")
+            ]
+        );
+        assert_eq!(plan.stale, vec![PathBuf::from("procedures/Gone.vail")]);
+
+        // Declined prune keeps stale files
+        let (_, removed) = apply_pull_plan(export.path(), local.path(), &plan, false).unwrap();
+        assert_eq!(removed, 0);
+        assert!(local.path().join("procedures/Gone.vail").exists());
+    }
+
+    #[test]
+    fn test_stage_files_keeps_layout() {
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join("rules")).unwrap();
+        std::fs::write(root.path().join("rules/OnOrder.vail"), "RULE OnOrder").unwrap();
+        std::fs::write(root.path().join("rules/Other.vail"), "RULE Other").unwrap();
+
+        let files = BTreeSet::from([PathBuf::from("rules/OnOrder.vail")]);
+        let staging = stage_files(root.path(), &files).unwrap();
+
+        assert!(staging.path().join("rules/OnOrder.vail").is_file());
+        assert!(!staging.path().join("rules/Other.vail").exists());
+    }
+
+    #[test]
+    fn test_watch_stages_whole_resources() {
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join("procedures")).unwrap();
+        std::fs::write(
+            root.path().join("procedures/Foo.json"),
+            r#"{"name": "Foo", "ruleText": {"$vail": "Foo.vail"}}"#,
+        )
+        .unwrap();
+        std::fs::write(root.path().join("procedures/Foo.vail"), "PROCEDURE Foo()").unwrap();
+        std::fs::write(
+            root.path().join("procedures/Bar.json"),
+            r#"{"name": "Bar"}"#,
+        )
+        .unwrap();
+
+        // Only the code changed, or only the definition
+        for changed in ["procedures/Foo.vail", "procedures/Foo.json"] {
+            let changed = BTreeSet::from([PathBuf::from(changed)]);
+            let files = with_resource_files(root.path(), &changed);
+            assert_eq!(
+                files,
+                BTreeSet::from([
+                    PathBuf::from("procedures/Foo.json"),
+                    PathBuf::from("procedures/Foo.vail")
+                ])
+            );
+
+            let staging = stage_files(root.path(), &files).unwrap();
+            let staged =
+                std::fs::read_to_string(staging.path().join("procedures/Foo.json")).unwrap();
+            assert!(staged.contains("PROCEDURE Foo()"));
+            assert!(!staging.path().join("procedures/Foo.vail").exists());
+            assert!(!staging.path().join("procedures/Bar.json").exists());
+        }
+    }
+
+    #[test]
+    fn test_snapshot_changes() {
+        let root = TempDir::new().unwrap();
+        let write = |path: &str, content: &str| {
+            let path = root.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("types/Order.json", "{}");
+        write("rules/OnOrder.json", "{}");
+        write("rules/OnOrder.vail", "RULE OnOrder");
+        write("types/Gone.json", "{}");
+        let before = snapshot(root.path());
+
+        write("rules/OnOrder.vail", "RULE OnOrder\n// changed");
+        write("procedures/New.json", "{}");
+        std::fs::remove_file(root.path().join("types/Gone.json")).unwrap();
+        let changes = snapshot_changes(&before, &snapshot(root.path()));
+
+        assert_eq!(
+            render::porcelain(changes.iter().map(|(c, id)| (*c, id.as_str()))),
+            "A procedures/New\nM rules/OnOrder\nD types/Gone\n"
+        );
+    }
 }
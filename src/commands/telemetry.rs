@@ -0,0 +1,131 @@
+//! Telemetry command implementation
+//!
+//! Turns the opt-in usage statistics on and off in config.toml and shows
+//! what has been recorded. Turning telemetry off also deletes the buffer.
+//! See [`crate::telemetry`].
+
+use crate::cli::{OutputFormat, TelemetryCommands};
+use crate::config::Config;
+use crate::error::Result;
+use crate::report;
+use crate::telemetry::{self, Summary};
+use crate::theme::style;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Result of the telemetry commands
+#[derive(Debug, Serialize)]
+pub struct TelemetryResult {
+    pub success: bool,
+    pub enabled: bool,
+    pub buffer: PathBuf,
+    #[serde(flatten)]
+    pub summary: Summary,
+}
+
+/// Run a telemetry subcommand
+///
+/// `config_path` is the `--config` file, if given; `on` and `off` change
+/// that file (or the default config.toml).
+pub fn run(
+    cmd: &TelemetryCommands,
+    config: &Config,
+    config_path: Option<&Path>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let enabled = match cmd {
+        TelemetryCommands::On => set_enabled(config_path, true)?,
+        TelemetryCommands::Off => {
+            set_enabled(config_path, false)?;
+            telemetry::clear()?;
+            false
+        }
+        TelemetryCommands::Show => config.telemetry.enabled,
+    };
+
+    let result = TelemetryResult {
+        success: true,
+        enabled,
+        buffer: telemetry::buffer_path(),
+        summary: telemetry::summarize(&telemetry::events()),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+        return Ok(true);
+    }
+
+    match cmd {
+        TelemetryCommands::On => {
+            println!(
+                "{} Telemetry is on. Each command records its name, result, error category, duration, vqx version and OS in:",
+                style("✓").green()
+            );
+            println!("  {}", result.buffer.display());
+            println!("  Arguments, profiles, URLs and credentials are never recorded, and nothing is sent.");
+        }
+        TelemetryCommands::Off => {
+            println!(
+                "{} Telemetry is off and the recorded statistics were deleted",
+                style("✓").green()
+            );
+        }
+        TelemetryCommands::Show => display(&result),
+    }
+    Ok(true)
+}
+
+fn set_enabled(config_path: Option<&Path>, enabled: bool) -> Result<bool> {
+    let path = match config_path {
+        Some(path) => path.to_path_buf(),
+        None => Config::config_file_path()?,
+    };
+    let mut config = Config::load_from(&path)?;
+    config.telemetry.enabled = enabled;
+    config.save_to(&path)?;
+    Ok(enabled)
+}
+
+fn display(result: &TelemetryResult) {
+    let summary = &result.summary;
+    println!();
+    println!("{}", style("Telemetry").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!(
+        "  Status: {}",
+        if result.enabled {
+            style("on").green()
+        } else {
+            style("off").dim()
+        }
+    );
+    println!("  Buffer: {}", result.buffer.display());
+    match summary.since {
+        Some(since) => println!("  Events: {} since {}", summary.events, since),
+        None => println!("  Events: none"),
+    }
+
+    if !summary.commands.is_empty() {
+        println!();
+        let mut commands: Vec<_> = summary.commands.iter().collect();
+        commands.sort_by(|a, b| b.1.runs.cmp(&a.1.runs).then(a.0.cmp(b.0)));
+        for (command, usage) in commands {
+            let failures = if usage.failures > 0 {
+                style(format!("{} failed", usage.failures))
+                    .red()
+                    .to_string()
+            } else {
+                String::new()
+            };
+            println!("  {:<24} {:>6} run(s)  {}", command, usage.runs, failures);
+        }
+    }
+    if !summary.error_categories.is_empty() {
+        println!();
+        println!("  Errors by category:");
+        for (category, count) in &summary.error_categories {
+            println!("    {:<22} {:>6}", category, count);
+        }
+    }
+    println!();
+}
@@ -0,0 +1,218 @@
+//! Template command implementation
+//!
+//! `render` writes a copy of an export with `{{vars.<name>}}` placeholders
+//! replaced by a profile's values, to inspect what `import` or `sync push`
+//! would send. `extract` does the reverse in place, turning a profile's
+//! values into placeholders after a pull. See [`crate::template`].
+
+use crate::cli::{OutputFormat, TemplateCommands, TemplateExtractArgs, TemplateRenderArgs};
+use crate::error::{Result, VqxError};
+use crate::normalizer;
+use crate::profile::ProfileManager;
+use crate::report;
+use crate::template::{self, Extraction};
+use crate::theme::style;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Result of template render
+#[derive(Debug, Serialize)]
+pub struct TemplateRenderResult {
+    pub success: bool,
+    pub profile: String,
+    pub directory: PathBuf,
+    pub out_dir: PathBuf,
+    /// Variables used by placeholders
+    pub variables: Vec<String>,
+    pub files_rendered: usize,
+}
+
+/// Result of template extract
+#[derive(Debug, Serialize)]
+pub struct TemplateExtractResult {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub directory: PathBuf,
+    pub dry_run: bool,
+    pub extractions: Vec<Extraction>,
+}
+
+/// Run template subcommand
+pub fn run(
+    cmd: &TemplateCommands,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    match cmd {
+        TemplateCommands::Render(args) => render(args, profile_name, output_format),
+        TemplateCommands::Extract(args) => extract(args, profile_name, output_format),
+    }
+}
+
+/// Name and `vars` of a profile (the default profile if none is given)
+fn profile_vars(profile_name: Option<&str>) -> Result<(String, BTreeMap<String, String>)> {
+    let manager = ProfileManager::new()?;
+    let name = profile_name.unwrap_or(&manager.store().default_profile);
+    let profile = manager.store().get(name)?;
+    Ok((name.to_string(), profile.vars.clone()))
+}
+
+fn check_directory(dir: &Path) -> Result<()> {
+    if dir.is_dir() {
+        Ok(())
+    } else {
+        Err(VqxError::Other(format!(
+            "Not a directory: {}",
+            dir.display()
+        )))
+    }
+}
+
+/// Render a copy of the export with the profile's values
+fn render(
+    args: &TemplateRenderArgs,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    check_directory(&args.directory)?;
+    if args.out_dir.exists() {
+        return Err(VqxError::Other(format!(
+            "Output directory already exists: {}",
+            args.out_dir.display()
+        )));
+    }
+    let (profile, vars) = profile_vars(profile_name)?;
+    let variables = template::used_variables(&args.directory)?;
+
+    // Check all values before writing the copy
+    let missing: Vec<String> = variables
+        .iter()
+        .filter(|v| !vars.contains_key(*v))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(VqxError::TemplateVariablesMissing {
+            profile,
+            names: missing.join(", "),
+        });
+    }
+
+    std::fs::create_dir_all(&args.out_dir).map_err(|_| VqxError::FileWriteFailed {
+        path: args.out_dir.display().to_string(),
+    })?;
+    normalizer::copy_directory(&args.directory, &args.out_dir)?;
+    let files_rendered = template::render_directory(&args.out_dir, &profile, &vars)?;
+
+    let result = TemplateRenderResult {
+        success: true,
+        profile,
+        directory: args.directory.clone(),
+        out_dir: args.out_dir.clone(),
+        variables: variables.into_iter().collect(),
+        files_rendered,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        println!();
+        println!("{}", style("Template Render").bold().cyan());
+        println!("{}", style("─".repeat(50)).dim());
+        println!("  Profile:   {}", style(&result.profile).green());
+        println!("  Directory: {}", result.directory.display());
+        println!("  Output:    {}", result.out_dir.display());
+        if !result.variables.is_empty() {
+            println!("  Variables: {}", result.variables.join(", "));
+        }
+        println!();
+        println!(
+            "{} Rendered {} file(s)",
+            style("✓").green(),
+            result.files_rendered
+        );
+        println!();
+    }
+
+    Ok(result.success)
+}
+
+/// Replace values with placeholders in place
+fn extract(
+    args: &TemplateExtractArgs,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    check_directory(&args.directory)?;
+
+    // Only --var values: the profile is optional
+    let (profile, mut vars) = if profile_name.is_none() && !args.vars.is_empty() {
+        (None, BTreeMap::new())
+    } else {
+        let (name, vars) = profile_vars(profile_name)?;
+        (Some(name), vars)
+    };
+    for assignment in &args.vars {
+        let (name, value) = template::parse_assignment(assignment)?;
+        vars.insert(name, value);
+    }
+    if vars.is_empty() {
+        return Err(VqxError::Other(
+            "No variables to extract: set profile vars with `vqx profile set <name> --var NAME=VALUE` or pass --var".to_string(),
+        ));
+    }
+
+    let extractions = template::extract_directory(&args.directory, &vars, args.dry_run)?;
+    let result = TemplateExtractResult {
+        success: true,
+        profile,
+        directory: args.directory.clone(),
+        dry_run: args.dry_run,
+        extractions,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display_extract(&result);
+    }
+
+    Ok(result.success)
+}
+
+/// Display the extract result to the terminal
+fn display_extract(result: &TemplateExtractResult) {
+    println!();
+    println!("{}", style("Template Extract").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    if let Some(ref profile) = result.profile {
+        println!("  Profile:   {}", style(profile).green());
+    }
+    println!("  Directory: {}", result.directory.display());
+    println!();
+
+    for e in &result.extractions {
+        println!(
+            "  {} {} {}",
+            e.file,
+            style(template::placeholder(&e.variable)).cyan(),
+            style(format!("({}x)", e.occurrences)).dim()
+        );
+    }
+    if !result.extractions.is_empty() {
+        println!();
+    }
+
+    let total: usize = result.extractions.iter().map(|e| e.occurrences).sum();
+    if result.dry_run {
+        println!(
+            "{} {} value(s) would be replaced (dry run)",
+            style("✓").green(),
+            total
+        );
+    } else {
+        println!("{} Replaced {} value(s)", style("✓").green(), total);
+    }
+    println!();
+}
@@ -0,0 +1,313 @@
+//! Trash command implementation
+//!
+//! Lists, restores and empties the entries `safe-delete --trash` leaves
+//! behind. `restore` imports the kept resource definitions and inserts the
+//! kept records into the profile they were deleted from (or `--profile`),
+//! then removes the entry. See [`crate::trash`].
+
+use crate::backend::Executor;
+use crate::cache::ExportCache;
+use crate::cli::{OutputFormat, TrashCommands, TrashEmptyArgs, TrashRestoreArgs};
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::lock::OperationLock;
+use crate::profile::ProfileManager;
+use crate::prompt;
+use crate::report;
+use crate::theme::style;
+use crate::trash::{TrashEntry, TrashStore};
+use crate::underlying::{CliOptions, ExecResult, UnderlyingCli};
+use chrono::Utc;
+use serde::Serialize;
+
+/// Result of trash list
+#[derive(Debug, Serialize)]
+pub struct TrashListResult {
+    pub success: bool,
+    pub entries: Vec<TrashEntry>,
+}
+
+/// Result of trash restore
+#[derive(Debug, Serialize)]
+pub struct TrashRestoreResult {
+    pub success: bool,
+    pub profile: String,
+    pub entry: TrashEntry,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of trash empty
+#[derive(Debug, Serialize)]
+pub struct TrashEmptyResult {
+    pub success: bool,
+    /// IDs of the removed entries
+    pub removed: Vec<String>,
+    /// Entries still in the trash
+    pub remaining: usize,
+}
+
+/// Run trash subcommand
+pub async fn run(
+    cmd: &TrashCommands,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    match cmd {
+        TrashCommands::List => list(config, output_format),
+        TrashCommands::Restore(args) => restore(args, config, profile_name, output_format).await,
+        TrashCommands::Empty(args) => empty(args, config, output_format),
+    }
+}
+
+fn list(config: &Config, output_format: OutputFormat) -> Result<bool> {
+    let result = TrashListResult {
+        success: true,
+        entries: TrashStore::new(config).list(),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+        return Ok(true);
+    }
+
+    println!();
+    println!("{}", style("Trash").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    if result.entries.is_empty() {
+        println!("  The trash is empty");
+    }
+    let now = Utc::now();
+    for e in &result.entries {
+        let expiry = if e.is_expired(now) {
+            style("expired".to_string()).red()
+        } else {
+            style(format!("until {}", e.expires_at.format("%Y-%m-%d"))).dim()
+        };
+        println!(
+            "  {}  {}/{} ({} item(s)) from {} by {} {}",
+            style(&e.id).bold(),
+            e.resource_type,
+            e.target,
+            e.items.len(),
+            style(e.profile.as_deref().unwrap_or("(default)")).green(),
+            e.deleted_by,
+            expiry
+        );
+    }
+    println!();
+    Ok(true)
+}
+
+async fn restore(
+    args: &TrashRestoreArgs,
+    config: &Config,
+    profile_name: Option<&str>,
+    output_format: OutputFormat,
+) -> Result<bool> {
+    let store = TrashStore::new(config);
+    let entry = store.find(&args.id)?;
+
+    // Back to where the items came from, unless --profile says otherwise
+    let manager = ProfileManager::new()?;
+    let profile_name = profile_name
+        .or(entry.profile.as_deref())
+        .unwrap_or(&manager.store().default_profile)
+        .to_string();
+    let profile = manager.get_resolved(&profile_name)?;
+
+    if !output_format.is_machine_readable() {
+        display_entry(&entry, &profile_name);
+    }
+
+    if !args.yes {
+        if output_format.is_machine_readable() {
+            return Err(VqxError::DestructiveOperationNotConfirmed {
+                operation: "trash restore".to_string(),
+            });
+        }
+        let confirmed = prompt::confirm(
+            format!(
+                "Restore {} item(s) to {} ({})?",
+                entry.items.len(),
+                profile.url,
+                profile_name
+            ),
+            "Trash restore",
+            "--yes",
+        )?;
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Trash restore".to_string(),
+            });
+        }
+    }
+
+    let _lock = OperationLock::acquire(
+        "trash restore",
+        &profile_name,
+        &profile.url,
+        profile.namespace.as_deref(),
+        &args.lock,
+    )
+    .await?;
+
+    let cli = Executor::new(
+        UnderlyingCli::new(config.cli_path.clone())
+            .with_timeout(config.timeout_for("trash"))
+            .with_retry_policy(config.retry_policy()),
+        Some(&profile),
+    )?;
+    let options = CliOptions::from_profile(&profile);
+
+    let mut error = None;
+    if let Some(dir) = store.metadata_dir(&entry.id) {
+        ExportCache::new(config).invalidate(&profile_name, profile.namespace.as_deref());
+        let result = cli
+            .cli()
+            .import(
+                &options,
+                Some("metadata"),
+                Some(&dir),
+                Some(config.default_chunk_size),
+                None,
+                None,
+                None,
+            )
+            .await?;
+        error = failure("import", &result);
+    }
+    if let (None, Some(file)) = (&error, store.records_file(&entry.id)) {
+        let result = cli
+            .insert(
+                &options,
+                &entry.resource_type,
+                &file.to_string_lossy(),
+                false,
+            )
+            .await?;
+        error = failure("insert", &result);
+    }
+
+    // A failed restore keeps the entry for another attempt
+    if error.is_none() {
+        store.remove(&entry.id)?;
+    }
+
+    let result = TrashRestoreResult {
+        success: error.is_none(),
+        profile: profile_name,
+        entry,
+        error,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else if let Some(ref error) = result.error {
+        println!("{} Restore failed", style("✗").red().bold());
+        eprintln!("{}", style(error).red());
+    } else {
+        println!(
+            "{} Restored {} item(s) to {}",
+            style("✓").green().bold(),
+            result.entry.items.len(),
+            style(&result.profile).green()
+        );
+        println!();
+    }
+
+    Ok(result.success)
+}
+
+/// Error message of a failed CLI call
+fn failure(command: &str, result: &ExecResult) -> Option<String> {
+    (!result.success()).then(|| {
+        format!(
+            "{} failed with exit code {}: {}",
+            command,
+            result.code(),
+            result.stderr.trim()
+        )
+    })
+}
+
+fn empty(args: &TrashEmptyArgs, config: &Config, output_format: OutputFormat) -> Result<bool> {
+    let store = TrashStore::new(config);
+    let now = Utc::now();
+    let (remove, keep): (Vec<TrashEntry>, Vec<TrashEntry>) = store
+        .list()
+        .into_iter()
+        .partition(|e| args.all || e.is_expired(now));
+
+    // Entries within their grace period are only removed on request
+    if args.all && !remove.is_empty() && !args.yes {
+        if output_format.is_machine_readable() {
+            return Err(VqxError::DestructiveOperationNotConfirmed {
+                operation: "trash empty --all".to_string(),
+            });
+        }
+        let confirmed = prompt::confirm(
+            format!(
+                "Remove all {} trash entries? Their items can no longer be restored.",
+                remove.len()
+            ),
+            "Trash empty",
+            "--yes",
+        )?;
+        if !confirmed {
+            return Err(VqxError::Cancelled {
+                operation: "Trash empty".to_string(),
+            });
+        }
+    }
+
+    for e in &remove {
+        store.remove(&e.id)?;
+    }
+
+    let result = TrashEmptyResult {
+        success: true,
+        removed: remove.into_iter().map(|e| e.id).collect(),
+        remaining: keep.len(),
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        println!(
+            "{} Removed {} trash entr{} ({} remaining)",
+            style("✓").green(),
+            result.removed.len(),
+            if result.removed.len() == 1 {
+                "y"
+            } else {
+                "ies"
+            },
+            result.remaining
+        );
+    }
+
+    Ok(result.success)
+}
+
+/// Display an entry before restoring it
+fn display_entry(entry: &TrashEntry, profile_name: &str) {
+    println!();
+    println!("{}", style("Trash Restore").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Entry:   {}", style(&entry.id).bold());
+    println!("  Profile: {}", style(profile_name).green());
+    println!(
+        "  Deleted: {}/{} by {} at {}",
+        entry.resource_type,
+        entry.target,
+        entry.deleted_by,
+        entry.deleted_at.format("%Y-%m-%d %H:%M:%S UTC")
+    );
+    println!();
+    for item in &entry.items {
+        println!("  {}", item);
+    }
+    println!();
+}
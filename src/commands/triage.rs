@@ -0,0 +1,461 @@
+//! Triage command implementation
+//!
+//! Collects a support bundle that can be attached to an issue:
+//! - doctor results
+//! - the effective configuration and profiles (secrets masked)
+//! - vqx, CLI and Java versions
+//! - OS and environment details
+//! - the tail of the configured log file
+//! - the most recent approval requests and decisions (the push audit trail)
+//! - with `--operation <id>`, every log line of that run
+//!
+//! Everything is written into a single `.tar.gz` archive. Text that may
+//! contain credentials is passed through [`sanitize`] before it is bundled,
+//! and notification webhook URLs are masked wherever they appear.
+
+use crate::approval::ApprovalStore;
+use crate::cli::{DoctorArgs, OutputFormat, TriageArgs};
+use crate::commands::doctor;
+use crate::config::{Config, LoggingConfig};
+use crate::error::{Result, VqxError};
+use crate::profile::ProfileStore;
+use crate::report;
+use crate::theme::style;
+use crate::underlying::{CliOptions, UnderlyingCli};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::Regex;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use tracing::info;
+
+/// Replacement for masked secret values
+const MASK: &str = "********";
+
+/// Number of approval requests to include
+const RECENT_APPROVALS: usize = 20;
+
+/// Result of the triage command
+#[derive(Debug, Serialize)]
+pub struct TriageResult {
+    pub success: bool,
+    pub archive: PathBuf,
+    pub files: Vec<String>,
+}
+
+/// One file of the bundle
+struct BundleFile {
+    name: &'static str,
+    content: String,
+}
+
+/// Run the triage command
+pub async fn run(
+    args: &TriageArgs,
+    config: &Config,
+    output_format: OutputFormat,
+) -> Result<TriageResult> {
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let archive = args
+        .out
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(format!("vqx-triage-{}.tar.gz", stamp)));
+    info!(archive = %archive.display(), "Collecting support bundle");
+
+    let mut files = Vec::new();
+
+    // Doctor results
+    let checks = doctor::run(
+        &DoctorArgs {
+            test_connection: args.test_connection,
+            ..Default::default()
+        },
+        config,
+        None,
+    )
+    .await?;
+    files.push(BundleFile {
+        name: "doctor.json",
+        content: serde_json::to_string_pretty(&checks)?,
+    });
+
+    // Effective configuration, webhook URLs masked
+    let mut masked_config = config.clone();
+    for channel in &mut masked_config.notify.channels {
+        if channel.url.is_some() {
+            channel.url = Some(MASK.to_string());
+        }
+    }
+    files.push(BundleFile {
+        name: "config.toml",
+        content: toml::to_string_pretty(&masked_config)
+            .map_err(|e| VqxError::Other(format!("Failed to serialize config: {}", e)))?,
+    });
+
+    // Profiles, secrets masked
+    files.push(BundleFile {
+        name: "profiles.toml",
+        content: masked_profiles(),
+    });
+
+    // Versions
+    files.push(BundleFile {
+        name: "versions.txt",
+        content: collect_versions(config).await,
+    });
+
+    // Environment
+    files.push(BundleFile {
+        name: "environment.txt",
+        content: collect_environment(),
+    });
+
+    // Log file tail
+    if let Some(log_file) = config.logging.active_file(chrono::Utc::now()) {
+        files.push(BundleFile {
+            name: "vqx.log",
+            content: tail_file(&log_file, args.log_lines),
+        });
+    }
+
+    // Recent approvals
+    let approvals: Vec<_> = ApprovalStore::new(config)?
+        .recent(RECENT_APPROVALS)
+        .into_iter()
+        .map(|(request, decision)| serde_json::json!({"request": request, "decision": decision}))
+        .collect();
+    files.push(BundleFile {
+        name: "approvals.json",
+        content: serde_json::to_string_pretty(&approvals)?,
+    });
+
+    // Transcript of a failed run
+    if let Some(ref id) = args.operation {
+        files.push(BundleFile {
+            name: "transcript.log",
+            content: transcript(&config.logging, id),
+        });
+    }
+
+    let webhook_urls: Vec<&str> = config
+        .notify
+        .channels
+        .iter()
+        .filter_map(|c| c.url.as_deref())
+        .collect();
+    for file in &mut files {
+        file.content = sanitize(&file.content);
+        for url in &webhook_urls {
+            file.content = file.content.replace(url, MASK);
+        }
+    }
+
+    write_archive(&archive, &format!("vqx-triage-{}", stamp), &files)?;
+
+    let result = TriageResult {
+        success: true,
+        archive,
+        files: files.iter().map(|f| f.name.to_string()).collect(),
+    };
+
+    match output_format {
+        OutputFormat::Json
+        | OutputFormat::Yaml
+        | OutputFormat::Ndjson
+        | OutputFormat::Porcelain => {
+            println!("{}", report::render(&result, output_format)?);
+        }
+        OutputFormat::Text | OutputFormat::Csv => {
+            println!(
+                "{} Support bundle written to {}",
+                style("✓").green().bold(),
+                style(result.archive.display()).cyan()
+            );
+            for name in &result.files {
+                println!("  {} {}", style("→").dim(), name);
+            }
+            println!();
+            println!(
+                "{}",
+                style("Secrets are masked, but please review the bundle before sharing it.").dim()
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+/// Serialize all profiles with passwords and tokens masked
+fn masked_profiles() -> String {
+    let store = match ProfileStore::load() {
+        Ok(store) => store,
+        Err(e) => return format!("# Could not load profiles: {}\n", e),
+    };
+
+    store
+        .masked()
+        .to_toml()
+        .unwrap_or_else(|e| format!("# Could not serialize profiles: {}\n", e))
+}
+
+/// vqx, Vantiq CLI and Java versions
+async fn collect_versions(config: &Config) -> String {
+    let mut out = format!("vqx: {}\n", env!("CARGO_PKG_VERSION"));
+
+    let cli =
+        UnderlyingCli::new(config.cli_path.clone()).with_timeout(config.timeout_for("triage"));
+    let cli_version = match cli.version(&CliOptions::default()).await {
+        Ok(r) => format!("{}{}", r.stdout, r.stderr),
+        Err(e) => format!("unavailable ({})", e),
+    };
+    out.push_str(&format!(
+        "\nVantiq CLI ({}):\n{}\n",
+        config.cli_path,
+        cli_version.trim()
+    ));
+
+    // Java prints its version to stderr
+    let java_version = match Command::new("java").arg("-version").output() {
+        Ok(o) => String::from_utf8_lossy(&o.stderr).trim().to_string(),
+        Err(e) => format!("unavailable ({})", e),
+    };
+    out.push_str(&format!("\nJava:\n{}\n", java_version));
+
+    out
+}
+
+/// OS details and relevant environment variables
+fn collect_environment() -> String {
+    let mut out = format!(
+        "os: {}\narch: {}\nfamily: {}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        std::env::consts::FAMILY
+    );
+    if let Ok(dir) = std::env::current_dir() {
+        out.push_str(&format!("cwd: {}\n", dir.display()));
+    }
+
+    out.push_str("\n[environment]\n");
+    let mut vars: Vec<(String, String)> = std::env::vars()
+        .filter(|(k, _)| {
+            k.starts_with("VQX_")
+                || k.starts_with("VANTIQ_")
+                || matches!(k.as_str(), "JAVA_HOME" | "PATH" | "SHELL" | "TERM" | "LANG")
+        })
+        .collect();
+    vars.sort();
+    for (key, value) in vars {
+        out.push_str(&format!("{}={}\n", key, mask_env_value(&key, &value)));
+    }
+
+    out
+}
+
+/// Mask the value of environment variables that look like credentials
+fn mask_env_value(key: &str, value: &str) -> String {
+    let key = key.to_uppercase();
+    if ["TOKEN", "PASSWORD", "SECRET", "KEY"]
+        .iter()
+        .any(|s| key.contains(s))
+    {
+        MASK.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Last `lines` lines of a file, or a note when it cannot be read
+fn tail_file(path: &Path, lines: usize) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            let all: Vec<&str> = content.lines().collect();
+            let start = all.len().saturating_sub(lines);
+            all[start..].join("\n")
+        }
+        Err(e) => format!("# Could not read {}: {}\n", path.display(), e),
+    }
+}
+
+/// Log lines tagged with an operation ID, from the log file and its rotations
+fn transcript(logging: &LoggingConfig, id: &str) -> String {
+    let Some(ref file) = logging.file else {
+        return "# No log file is configured ([logging] file), so no transcript was kept\n"
+            .to_string();
+    };
+    let path = Path::new(file);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let rotated = format!("{}.", name);
+
+    let mut logs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy())
+                .is_some_and(|n| n == name || n.starts_with(&rotated))
+        })
+        .collect();
+    logs.sort();
+
+    let lines: Vec<String> = logs
+        .iter()
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .filter(|line| line.contains(id))
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    if lines.is_empty() {
+        format!("# No log lines for operation {}\n", id)
+    } else {
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Mask credentials in free-form text
+///
+/// Covers `key = value` / `"key": "value"` pairs, CLI `-p`/`-t` arguments,
+/// bearer tokens and Slack webhook URLs.
+pub(crate) fn sanitize(text: &str) -> String {
+    static PATTERNS: OnceLock<Vec<Regex>> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        vec![
+            Regex::new(
+                r#"(?i)((?:password|passwd|token|secret|authorization)"?\s*[:=]\s*"?(?:bearer\s+)?)[^\s",]+"#,
+            )
+            .unwrap(),
+            Regex::new(r"(\s-[pt]\s+)\S+").unwrap(),
+            Regex::new(r"(?i)(bearer\s+)\S+").unwrap(),
+            Regex::new(r#"(https://hooks\.slack\.com/)[^\s"',]+"#).unwrap(),
+        ]
+    });
+
+    let mut text = text.to_string();
+    for pattern in patterns {
+        text = pattern
+            .replace_all(&text, format!("${{1}}{}", MASK))
+            .into_owned();
+    }
+    text
+}
+
+/// Write the bundle files into a gzipped tarball under `root/`
+fn write_archive(path: &Path, root: &str, files: &[BundleFile]) -> Result<()> {
+    let file = std::fs::File::create(path).map_err(|_| VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    })?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    for bundle_file in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(bundle_file.content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(chrono::Utc::now().timestamp() as u64);
+        header.set_cksum();
+        builder.append_data(
+            &mut header,
+            format!("{}/{}", root, bundle_file.name),
+            bundle_file.content.as_bytes(),
+        )?;
+    }
+
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_masks_secrets() {
+        assert_eq!(sanitize("token = \"abc123\""), "token = \"********\"");
+        assert_eq!(
+            sanitize(r#"{"password": "hunter2", "url": "https://x"}"#),
+            r#"{"password": "********", "url": "https://x"}"#
+        );
+        assert_eq!(
+            sanitize("vantiq -b https://dev.vantiq.com -t abc.def -s x"),
+            "vantiq -b https://dev.vantiq.com -t ******** -s x"
+        );
+        assert_eq!(
+            sanitize("Authorization: Bearer abc"),
+            "Authorization: Bearer ********"
+        );
+        assert_eq!(sanitize("curl -H 'bearer xyz'"), "curl -H 'bearer ********");
+        assert_eq!(
+            sanitize("POST https://hooks.slack.com/services/T0/B0/xyz failed"),
+            "POST https://hooks.slack.com/******** failed"
+        );
+        assert_eq!(sanitize("nothing secret here"), "nothing secret here");
+    }
+
+    #[test]
+    fn test_transcript() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("vqx.log");
+        std::fs::write(
+            dir.path().join("vqx.log.2026-10-15"),
+            "INFO operation{id=a-1}: Exporting\nINFO operation{id=b-2}: Exporting\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("vqx.log.2026-10-16"),
+            "WARN operation{id=a-1}: Retrying\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("other.log"), "operation{id=a-1}\n").unwrap();
+
+        let logging = LoggingConfig {
+            file: Some(file.display().to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            transcript(&logging, "a-1"),
+            "INFO operation{id=a-1}: Exporting\nWARN operation{id=a-1}: Retrying\n"
+        );
+        assert!(transcript(&logging, "c-3").starts_with("# No log lines"));
+        assert!(transcript(&LoggingConfig::default(), "a-1").starts_with("# No log file"));
+    }
+
+    #[test]
+    fn test_mask_env_value() {
+        assert_eq!(mask_env_value("VQX_TOKEN", "abc"), MASK);
+        assert_eq!(mask_env_value("VQX_PROFILE", "dev"), "dev");
+    }
+
+    #[test]
+    fn test_write_archive() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("bundle.tar.gz");
+        let files = vec![BundleFile {
+            name: "doctor.json",
+            content: "[]".to_string(),
+        }];
+        write_archive(&path, "vqx-triage-test", &files).unwrap();
+
+        let decoder = flate2::read::GzDecoder::new(std::fs::File::open(&path).unwrap());
+        let mut archive = tar::Archive::new(decoder);
+        let names: Vec<String> = archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().display().to_string())
+            .collect();
+        assert_eq!(names, vec!["vqx-triage-test/doctor.json"]);
+    }
+}
@@ -0,0 +1,329 @@
+//! Validate command implementation
+//!
+//! Checks every resource JSON of an export directory against the built-in
+//! schemas in [`crate::validation`] and reports violations with file and
+//! line, before an import has a chance to fail halfway through.
+//!
+//! `--layout` also checks the structure of the directory, which the import
+//! relies on: only known subdirectories, definition files named after the
+//! `name` inside them, and `.vail` files that a definition refers to. Hand
+//! edits that break these produce confusing import errors, or resources
+//! that are silently skipped.
+
+use crate::cli::{OutputFormat, ValidateArgs};
+use crate::commands::diff::RESOURCE_TYPES;
+use crate::error::{Result, VqxError};
+use crate::manifest;
+use crate::masking;
+use crate::normalizer;
+use crate::report;
+use crate::theme::style;
+use crate::validation::{self, Violation};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Violations found in one file
+#[derive(Debug, Serialize)]
+pub struct FileViolations {
+    /// Path relative to the validated directory
+    pub file: String,
+    pub violations: Vec<Violation>,
+}
+
+/// A file or directory that does not fit the export layout
+#[derive(Debug, Serialize)]
+pub struct LayoutIssue {
+    /// Path relative to the validated directory
+    pub path: String,
+    pub message: String,
+}
+
+/// Validate operation result
+#[derive(Debug, Serialize)]
+pub struct ValidateResult {
+    pub success: bool,
+    pub directory: PathBuf,
+    pub files_checked: usize,
+    pub invalid_files: Vec<FileViolations>,
+    /// Layout issues, with `--layout`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<Vec<LayoutIssue>>,
+}
+
+/// Top-level directories of an export besides the resource types
+const OTHER_DIRS: &[&str] = &[masking::DATA_DIR, "documents", manifest::HIDDEN_DIR];
+
+/// Run validate command
+pub fn run(args: &ValidateArgs, output_format: OutputFormat) -> Result<ValidateResult> {
+    let dir = &args.directory;
+    if !dir.is_dir() {
+        return Err(VqxError::Other(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+
+    let mut files_checked = 0;
+    let mut invalid_files = Vec::new();
+
+    for resource_type in RESOURCE_TYPES {
+        for path in json_files(&dir.join(resource_type)) {
+            let violations = validation::validate_file(&path, resource_type).map_err(|_| {
+                VqxError::FileReadFailed {
+                    path: path.display().to_string(),
+                }
+            })?;
+            files_checked += 1;
+
+            if !violations.is_empty() {
+                invalid_files.push(FileViolations {
+                    file: path
+                        .strip_prefix(dir)
+                        .unwrap_or(&path)
+                        .display()
+                        .to_string(),
+                    violations,
+                });
+            }
+        }
+    }
+
+    let layout = args.layout.then(|| check_layout(dir));
+    let result = ValidateResult {
+        success: invalid_files.is_empty() && layout.as_ref().is_none_or(Vec::is_empty),
+        directory: dir.clone(),
+        files_checked,
+        invalid_files,
+        layout,
+    };
+
+    if output_format.is_machine_readable() {
+        println!("{}", report::render(&result, output_format)?);
+    } else {
+        display_result(&result);
+    }
+
+    Ok(result)
+}
+
+/// JSON files below a resource directory, in a stable order
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Check the structure of an export directory
+fn check_layout(dir: &Path) -> Vec<LayoutIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |path: &Path, message: String| {
+        issues.push(LayoutIssue {
+            path: path
+                .strip_prefix(dir)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/"),
+            message,
+        })
+    };
+
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let known = RESOURCE_TYPES.contains(&name.as_str()) || OTHER_DIRS.contains(&name.as_str());
+        if entry.path().is_dir() && !known && !name.starts_with('.') {
+            issue(
+                &entry.path(),
+                "Unknown directory; it is not a resource type and is not imported".to_string(),
+            );
+        }
+    }
+
+    for resource_type in RESOURCE_TYPES {
+        let mut referenced = BTreeSet::new();
+        let mut code_files = Vec::new();
+        for path in WalkDir::new(dir.join(resource_type))
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+        {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            match path.extension().and_then(|e| e.to_str()) {
+                _ if file_name.starts_with('.') => {}
+                Some("json") => {
+                    // Unreadable definitions are reported by the schema check
+                    let Some(value) = std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+                    else {
+                        continue;
+                    };
+                    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                    // Names with a slash (topics) cannot be file names
+                    if let Some(name) = value.get("name").and_then(Value::as_str) {
+                        if name != stem && !name.contains('/') {
+                            issue(
+                                &path,
+                                format!(
+                                    "File name does not match the name '{}' inside; expected {}.json",
+                                    name, name
+                                ),
+                            );
+                        }
+                    }
+                    let parent = path.parent().unwrap_or(dir);
+                    for reference in normalizer::code_references(&value) {
+                        let code_path = parent.join(Path::new(reference).file_name().unwrap_or_default());
+                        if !code_path.is_file() {
+                            issue(&path, format!("Refers to missing code file {}", reference));
+                        }
+                        referenced.insert(code_path);
+                    }
+                }
+                Some("vail") => code_files.push(path),
+                _ => issue(
+                    &path,
+                    "Unexpected file; resource directories hold .json definitions and their .vail code"
+                        .to_string(),
+                ),
+            }
+        }
+        for path in code_files.iter().filter(|p| !referenced.contains(*p)) {
+            issue(
+                path,
+                "Orphaned code file; no definition refers to it".to_string(),
+            );
+        }
+    }
+
+    issues.sort_by(|a, b| a.path.cmp(&b.path));
+    issues
+}
+
+/// Display the validation result to the terminal
+fn display_result(result: &ValidateResult) {
+    println!();
+    println!("{}", style("Validate").bold().cyan());
+    println!("{}", style("─".repeat(50)).dim());
+    println!("  Directory: {}", result.directory.display());
+    println!();
+
+    for file in &result.invalid_files {
+        println!("{} {}", style("✗").red(), file.file);
+        for violation in &file.violations {
+            println!(
+                "    {}:{} {} {}",
+                file.file,
+                violation.line,
+                style(&violation.path).dim(),
+                violation.message
+            );
+        }
+    }
+    if !result.invalid_files.is_empty() {
+        println!();
+    }
+
+    let layout = result.layout.as_deref().unwrap_or_default();
+    for issue in layout {
+        println!("{} {}: {}", style("✗").red(), issue.path, issue.message);
+    }
+    if !layout.is_empty() {
+        println!();
+    }
+
+    let violations: usize = result
+        .invalid_files
+        .iter()
+        .map(|f| f.violations.len())
+        .sum();
+    if result.success {
+        println!(
+            "{} {} files valid{}",
+            style("✓").green(),
+            result.files_checked,
+            if result.layout.is_some() {
+                ", layout OK"
+            } else {
+                ""
+            }
+        );
+    } else if violations > 0 {
+        println!(
+            "{} {} violation(s) in {} of {} files",
+            style("✗").red().bold(),
+            violations,
+            result.invalid_files.len(),
+            result.files_checked
+        );
+    }
+    if !layout.is_empty() {
+        println!(
+            "{} {} layout issue(s)",
+            style("✗").red().bold(),
+            layout.len()
+        );
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_layout() {
+        let dir = TempDir::new().unwrap();
+        for (rel, content) in [
+            ("types/Order.json", r#"{"name": "Order"}"#),
+            ("types/Customer.json", r#"{"name": "Client"}"#),
+            (
+                "procedures/Orders.total.json",
+                r#"{"name": "Orders.total", "ruleText": {"$vail": "Orders.total.vail"}}"#,
+            ),
+            ("procedures/Orders.total.vail", "PROCEDURE Orders.total()"),
+            ("procedures/Old.vail", "PROCEDURE Old()"),
+            (
+                "rules/OnOrder.json",
+                r#"{"name": "OnOrder", "ruleText": {"$vail": "OnOrder.vail"}}"#,
+            ),
+            ("rules/notes.txt", ""),
+            ("topics/orders.json", r#"{"name": "/orders"}"#),
+            ("backup/types/Order.json", r#"{"name": "Order"}"#),
+            ("data/Order.json", "[]"),
+            ("_hidden/types/ArsType.json", r#"{"name": "Other"}"#),
+            (".git/HEAD", ""),
+        ] {
+            let path = dir.path().join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let issues: Vec<(String, String)> = check_layout(dir.path())
+            .into_iter()
+            .map(|i| (i.path, i.message))
+            .collect();
+        let paths: Vec<&str> = issues.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "backup",
+                "procedures/Old.vail",
+                "rules/OnOrder.json",
+                "rules/notes.txt",
+                "types/Customer.json",
+            ]
+        );
+        assert!(issues[2].1.contains("missing code file OnOrder.vail"));
+        assert!(issues[4].1.contains("expected Client.json"));
+    }
+}
@@ -0,0 +1,228 @@
+//! Breaking-change detection between type definitions
+//!
+//! Compares two versions of a type semantically rather than line by line:
+//! removed properties, changed data types, new required fields and removed
+//! indexes. Each finding carries a severity so CI can block risky promotes.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// How risky a change is for existing data and clients
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// May affect performance or constraints; review before promoting
+    Warning,
+    /// Breaks existing data, queries or clients
+    Breaking,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+/// One compatibility finding for a resource
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CompatIssue {
+    /// Resource identifier (`<resource_type>/<name>`)
+    pub id: String,
+    pub severity: Severity,
+    /// Affected property, if any
+    pub property: Option<String>,
+    pub message: String,
+}
+
+impl CompatIssue {
+    fn new(id: &str, severity: Severity, property: Option<&str>, message: String) -> Self {
+        Self {
+            id: id.to_string(),
+            severity,
+            property: property.map(str::to_string),
+            message,
+        }
+    }
+}
+
+/// Properties of a type keyed by name
+///
+/// Accepts both the object form (`{"total": {...}}`) and an array of
+/// definitions with a `name`.
+fn properties(definition: &Value) -> BTreeMap<&str, &Value> {
+    match definition.get("properties") {
+        Some(Value::Object(map)) => map.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| Some((v.get("name")?.as_str()?, v)))
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+fn is_required(property: &Value) -> bool {
+    property.get("required").and_then(Value::as_bool) == Some(true)
+}
+
+fn is_multi(property: &Value) -> bool {
+    property.get("multi").and_then(Value::as_bool) == Some(true)
+}
+
+/// Indexes of a type keyed by their canonical JSON
+fn indexes(definition: &Value) -> BTreeMap<String, &Value> {
+    definition
+        .get("indexes")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().map(|v| (v.to_string(), v)).collect())
+        .unwrap_or_default()
+}
+
+/// Compare two versions of a type definition
+///
+/// `id` identifies the type in the returned issues.
+pub fn compare_types(id: &str, source: &Value, target: &Value) -> Vec<CompatIssue> {
+    let mut issues = Vec::new();
+    let source_props = properties(source);
+    let target_props = properties(target);
+
+    for (&name, old) in &source_props {
+        let Some(new) = target_props.get(name) else {
+            issues.push(CompatIssue::new(
+                id,
+                Severity::Breaking,
+                Some(name),
+                format!("Property '{}' removed", name),
+            ));
+            continue;
+        };
+
+        let old_type = old.get("type").and_then(Value::as_str);
+        let new_type = new.get("type").and_then(Value::as_str);
+        if old_type != new_type {
+            issues.push(CompatIssue::new(
+                id,
+                Severity::Breaking,
+                Some(name),
+                format!(
+                    "Property '{}' changed type from {} to {}",
+                    name,
+                    old_type.unwrap_or("(none)"),
+                    new_type.unwrap_or("(none)")
+                ),
+            ));
+        }
+        if is_multi(old) != is_multi(new) {
+            issues.push(CompatIssue::new(
+                id,
+                Severity::Breaking,
+                Some(name),
+                format!("Property '{}' changed between single and multi value", name),
+            ));
+        }
+        if !is_required(old) && is_required(new) {
+            issues.push(CompatIssue::new(
+                id,
+                Severity::Breaking,
+                Some(name),
+                format!("Property '{}' is now required", name),
+            ));
+        }
+    }
+
+    for (&name, new) in &target_props {
+        if !source_props.contains_key(name) && is_required(new) {
+            issues.push(CompatIssue::new(
+                id,
+                Severity::Breaking,
+                Some(name),
+                format!("Required property '{}' added", name),
+            ));
+        }
+    }
+
+    let target_indexes = indexes(target);
+    for (key, index) in indexes(source) {
+        if target_indexes.contains_key(&key) {
+            continue;
+        }
+        let unique = index
+            .pointer("/options/unique")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let message = if unique {
+            format!("Unique index {} removed", key)
+        } else {
+            format!("Index {} removed", key)
+        };
+        issues.push(CompatIssue::new(id, Severity::Warning, None, message));
+    }
+
+    issues
+}
+
+/// Issue for a type that no longer exists in the target
+pub fn removed_type(id: &str) -> CompatIssue {
+    CompatIssue::new(id, Severity::Breaking, None, "Type removed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compatible_changes() {
+        let source = json!({"name": "Order", "properties": {"id": {"type": "String"}}});
+        let target = json!({
+            "name": "Order",
+            "properties": {"id": {"type": "String"}, "note": {"type": "String"}}
+        });
+        assert!(compare_types("types/Order", &source, &target).is_empty());
+    }
+
+    #[test]
+    fn test_breaking_changes() {
+        let source = json!({
+            "name": "Order",
+            "properties": {
+                "id": {"type": "String"},
+                "total": {"type": "Real"},
+                "paid": {"type": "Boolean"}
+            },
+            "indexes": [{"keys": {"id": 1}, "options": {"unique": true}}]
+        });
+        let target = json!({
+            "name": "Order",
+            "properties": {
+                "id": {"type": "String", "required": true},
+                "total": {"type": "Integer"},
+                "customer": {"type": "String", "required": true}
+            },
+            "indexes": []
+        });
+
+        let issues = compare_types("types/Order", &source, &target);
+        let summary: Vec<_> = issues
+            .iter()
+            .map(|i| (i.severity, i.property.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Severity::Breaking, Some("id")),
+                (Severity::Breaking, Some("paid")),
+                (Severity::Breaking, Some("total")),
+                (Severity::Breaking, Some("customer")),
+                (Severity::Warning, None),
+            ]
+        );
+        assert!(issues[4].message.starts_with("Unique index"));
+    }
+}
@@ -4,9 +4,13 @@
 //! the underlying CLI's profile system.
 
 use crate::error::{Result, VqxError};
+use crate::policy::PolicyRules;
+use crate::underlying::{Backoff, RetryPolicy, StallPolicy};
+use chrono::{DateTime, Utc};
 #[cfg(windows)]
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
@@ -27,11 +31,12 @@ pub struct Config {
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u64,
 
-    /// Maximum number of retries for transient failures
+    /// Maximum number of retries for transient failures of read commands
+    /// (see `retry` for other command classes)
     #[serde(default = "default_max_retries")]
     pub max_retries: u32,
 
-    /// Base delay for exponential backoff (milliseconds)
+    /// Base delay for exponential backoff of read commands (milliseconds)
     #[serde(default = "default_retry_delay")]
     pub retry_delay_ms: u64,
 
@@ -40,6 +45,28 @@ pub struct Config {
     #[serde(default = "default_chunk_size")]
     pub default_chunk_size: u32,
 
+    /// Log a heartbeat when a long-running command has been silent this long (seconds)
+    #[serde(default = "default_heartbeat")]
+    pub heartbeat_seconds: u64,
+
+    /// Warn (and offer to abort) when a long-running command has been silent this long (seconds)
+    #[serde(default = "default_stall_threshold")]
+    pub stall_threshold_seconds: u64,
+
+    /// Share a class data sharing archive between CLI calls to cut JVM startup
+    /// (see [`crate::underlying::share_classes`])
+    #[serde(default)]
+    pub warm_jvm: bool,
+
+    /// Per-command timeouts in seconds, keyed by vqx command (e.g. `export = 3600`)
+    /// Commands without an entry use `timeout_seconds`
+    #[serde(default)]
+    pub timeouts: BTreeMap<String, u64>,
+
+    /// Retry behavior per command class
+    #[serde(default)]
+    pub retry: RetryConfig,
+
     /// Logging configuration
     #[serde(default)]
     pub logging: LoggingConfig,
@@ -55,6 +82,63 @@ pub struct Config {
     /// Normalization settings for JSON output
     #[serde(default)]
     pub normalization: NormalizationConfig,
+
+    /// Masking of exported type data
+    #[serde(default)]
+    pub masking: MaskingConfig,
+
+    /// Export cache used by diff and sync push
+    #[serde(default)]
+    pub cache: CacheConfig,
+
+    /// Diff settings
+    #[serde(default)]
+    pub diff: DiffConfig,
+
+    /// Approval gates for `sync push --require-approval`
+    #[serde(default)]
+    pub approval: ApprovalConfig,
+
+    /// Resource names that import and sync push only send with --force
+    #[serde(default)]
+    pub import: ImportConfig,
+
+    /// Trash for `safe-delete --trash`
+    #[serde(default)]
+    pub trash: TrashConfig,
+
+    /// Snapshots of protected profiles before imports (`vqx rollback`)
+    #[serde(default)]
+    pub snapshots: SnapshotConfig,
+
+    /// Limits for `--chunk auto` (see [`crate::chunking`])
+    #[serde(default)]
+    pub chunking: ChunkingConfig,
+
+    /// Web IDE links for `vqx open`
+    #[serde(default)]
+    pub ide: IdeConfig,
+
+    /// Allowed and denied operations per profile glob (`[policy.<profile>]`)
+    /// See [`crate::policy`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub policy: BTreeMap<String, PolicyRules>,
+
+    /// Jobs run by `vqx schedule run` (`[[schedule]]`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule: Vec<ScheduledJob>,
+
+    /// HTTP trigger server (`vqx serve`)
+    #[serde(default)]
+    pub serve: ServeConfig,
+
+    /// Notifications when long operations finish
+    #[serde(default)]
+    pub notify: NotifyConfig,
+
+    /// Opt-in usage statistics (`vqx telemetry`)
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
 }
 
 fn default_cli_path() -> String {
@@ -81,6 +165,14 @@ fn default_chunk_size() -> u32 {
     5000
 }
 
+fn default_heartbeat() -> u64 {
+    30
+}
+
+fn default_stall_threshold() -> u64 {
+    300
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -89,10 +181,29 @@ impl Default for Config {
             max_retries: default_max_retries(),
             retry_delay_ms: default_retry_delay(),
             default_chunk_size: default_chunk_size(),
+            heartbeat_seconds: default_heartbeat(),
+            stall_threshold_seconds: default_stall_threshold(),
+            warm_jvm: false,
+            timeouts: BTreeMap::new(),
+            retry: RetryConfig::default(),
             logging: LoggingConfig::default(),
             output: OutputConfig::default(),
             safe_delete: SafeDeleteConfig::default(),
             normalization: NormalizationConfig::default(),
+            masking: MaskingConfig::default(),
+            cache: CacheConfig::default(),
+            diff: DiffConfig::default(),
+            approval: ApprovalConfig::default(),
+            import: ImportConfig::default(),
+            trash: TrashConfig::default(),
+            snapshots: SnapshotConfig::default(),
+            chunking: ChunkingConfig::default(),
+            ide: IdeConfig::default(),
+            policy: BTreeMap::new(),
+            schedule: Vec::new(),
+            serve: ServeConfig::default(),
+            notify: NotifyConfig::default(),
+            telemetry: TelemetryConfig::default(),
         }
     }
 }
@@ -152,12 +263,6 @@ impl Config {
         Ok(config)
     }
 
-    /// Save config to the default location
-    pub fn save(&self) -> Result<()> {
-        let path = Self::config_file_path()?;
-        self.save_to(&path)
-    }
-
     /// Save config to a specific file
     pub fn save_to(&self, path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
@@ -178,14 +283,93 @@ impl Config {
         Ok(())
     }
 
-    /// Get timeout as Duration
-    pub fn timeout(&self) -> Duration {
-        Duration::from_secs(self.timeout_seconds)
+    /// Get the timeout for a vqx command, honoring `[timeouts]`
+    pub fn timeout_for(&self, command: &str) -> Duration {
+        Duration::from_secs(
+            self.timeouts
+                .get(command)
+                .copied()
+                .unwrap_or(self.timeout_seconds),
+        )
+    }
+
+    /// Get retry behavior per command class
+    ///
+    /// Read commands fall back to `max_retries`/`retry_delay_ms`; other
+    /// mutating commands are not retried unless `[retry.mutate]` is set.
+    pub fn retry_policy(&self) -> RetryPolicy {
+        let backoff = |class: Option<RetryClassConfig>, fallback: Backoff| {
+            class.map_or(fallback, |c| Backoff {
+                max_retries: c.max_retries,
+                base_delay_ms: c.delay_ms,
+            })
+        };
+        RetryPolicy {
+            read: backoff(
+                self.retry.read,
+                Backoff {
+                    max_retries: self.max_retries,
+                    base_delay_ms: self.retry_delay_ms,
+                },
+            ),
+            mutate: backoff(self.retry.mutate, Backoff::NONE),
+            jitter: self.retry.jitter,
+        }
     }
 
-    /// Get retry delay as Duration
-    pub fn retry_delay(&self) -> Duration {
-        Duration::from_millis(self.retry_delay_ms)
+    /// Get heartbeat/stall thresholds for streaming commands
+    pub fn stall_policy(&self) -> StallPolicy {
+        StallPolicy {
+            heartbeat: Duration::from_secs(self.heartbeat_seconds),
+            stall_after: Duration::from_secs(self.stall_threshold_seconds),
+        }
+    }
+}
+
+/// Retry settings for one class of commands
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RetryClassConfig {
+    /// Retries after the first attempt
+    #[serde(default)]
+    pub max_retries: u32,
+
+    /// Base delay for exponential backoff (milliseconds)
+    #[serde(default = "default_retry_delay")]
+    pub delay_ms: u64,
+}
+
+/// Retry configuration per command class
+///
+/// Destructive commands (delete, deleteMatching, import) are never retried
+/// automatically.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Read commands (list, find, select, export)
+    /// Default: top-level `max_retries` and `retry_delay_ms`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read: Option<RetryClassConfig>,
+
+    /// Other commands that change server state (insert, upsert, deploy, run, ...)
+    /// Default: no retries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mutate: Option<RetryClassConfig>,
+
+    /// Fraction of each backoff delay that is randomized (0.0-1.0)
+    #[serde(default = "default_jitter")]
+    pub jitter: f64,
+}
+
+fn default_jitter() -> f64 {
+    0.5
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            read: None,
+            mutate: None,
+            jitter: default_jitter(),
+        }
     }
 }
 
@@ -197,8 +381,8 @@ pub struct LoggingConfig {
     pub level: String,
 
     /// Log format: text, json
-    #[serde(default = "default_log_format")]
-    pub format: String,
+    #[serde(default)]
+    pub format: LogFormat,
 
     /// Include timestamps in logs
     #[serde(default = "default_true")]
@@ -207,14 +391,39 @@ pub struct LoggingConfig {
     /// Log file path (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub file: Option<String>,
+
+    /// Log file rotation: never, hourly, daily
+    #[serde(default)]
+    pub rotation: LogRotation,
 }
 
-fn default_log_level() -> String {
-    "info".to_string()
+/// Format of log events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per event
+    Json,
 }
 
-fn default_log_format() -> String {
-    "text".to_string()
+/// When the log file is rotated
+///
+/// Rotated files get a date suffix, e.g. `vqx.log.2024-01-15` (daily) or
+/// `vqx.log.2024-01-15-09` (hourly).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    /// Always append to the configured file
+    #[default]
+    Never,
+    Hourly,
+    Daily,
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
 }
 
 fn default_true() -> bool {
@@ -225,13 +434,27 @@ impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
             level: default_log_level(),
-            format: default_log_format(),
+            format: LogFormat::default(),
             timestamps: true,
             file: None,
+            rotation: LogRotation::default(),
         }
     }
 }
 
+impl LoggingConfig {
+    /// The file currently written to, including the rotation suffix
+    pub fn active_file(&self, now: DateTime<Utc>) -> Option<PathBuf> {
+        let file = self.file.as_ref()?;
+        let suffix = match self.rotation {
+            LogRotation::Never => return Some(PathBuf::from(file)),
+            LogRotation::Hourly => now.format("%Y-%m-%d-%H"),
+            LogRotation::Daily => now.format("%Y-%m-%d"),
+        };
+        Some(PathBuf::from(format!("{}.{}", file, suffix)))
+    }
+}
+
 /// Output format configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutputConfig {
@@ -243,13 +466,50 @@ pub struct OutputConfig {
     #[serde(default = "default_true")]
     pub pretty_json: bool,
 
-    /// Use colors in output
+    /// Use colors in output (`--color` and `NO_COLOR` take precedence)
     #[serde(default = "default_true")]
     pub colors: bool,
 
+    /// Color theme: default, high-contrast
+    #[serde(default)]
+    pub theme: ColorTheme,
+
+    /// Language of messages: auto (from LANG), en, ja
+    #[serde(default)]
+    pub language: Language,
+
     /// Show progress bars for long operations
     #[serde(default = "default_true")]
     pub progress: bool,
+
+    /// Pager for long output (default: `$PAGER`, else `less`); "" or "cat"
+    /// disables paging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pager: Option<String>,
+}
+
+/// Color theme of terminal output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorTheme {
+    /// Standard terminal colors
+    #[default]
+    Default,
+    /// Bright, bold colors without faint text
+    HighContrast,
+}
+
+/// Language of user-facing messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    /// From `LC_ALL`, `LC_MESSAGES` or `LANG`
+    #[default]
+    Auto,
+    /// English
+    En,
+    /// Japanese
+    Ja,
 }
 
 fn default_output_format() -> String {
@@ -262,7 +522,10 @@ impl Default for OutputConfig {
             default_format: default_output_format(),
             pretty_json: true,
             colors: true,
+            theme: ColorTheme::default(),
+            language: Language::default(),
             progress: true,
+            pager: None,
         }
     }
 }
@@ -326,12 +589,32 @@ pub struct NormalizationConfig {
     pub sort_arrays: bool,
 
     /// Fields to exclude from normalized output (timestamps, etc.)
+    /// Key globs (`ars_*`) match at any depth; paths (`properties[*].ars_createdAt`,
+    /// `/config/lastRun`) select nested fields
     #[serde(default = "default_excluded_fields")]
     pub excluded_fields: Vec<String>,
 
     /// Field to use for array sorting (fallback order: name, id, _id)
     #[serde(default = "default_sort_fields")]
     pub array_sort_fields: Vec<String>,
+
+    /// Move procedure/rule code into sibling `.vail` files
+    /// The JSON keeps a `{"$vail": "<file>"}` reference; code is re-embedded before import
+    #[serde(default)]
+    pub extract_code: bool,
+
+    /// Convert CRLF line endings in string values to LF
+    #[serde(default = "default_true")]
+    pub normalize_line_endings: bool,
+
+    /// Convert string values to Unicode Normalization Form C
+    #[serde(default = "default_true")]
+    pub unicode_nfc: bool,
+
+    /// Write RFC 8785 (JCS) canonical JSON instead of pretty-printed JSON
+    /// Byte-stable output for hashing; not meant for reading
+    #[serde(default)]
+    pub canonical_json: bool,
 }
 
 fn default_excluded_fields() -> Vec<String> {
@@ -356,10 +639,506 @@ impl Default for NormalizationConfig {
             sort_arrays: true,
             excluded_fields: default_excluded_fields(),
             array_sort_fields: default_sort_fields(),
+            extract_code: false,
+            normalize_line_endings: true,
+            unicode_nfc: true,
+            canonical_json: false,
         }
     }
 }
 
+/// How a masked field is anonymized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaskAction {
+    /// Replace with `"[REDACTED]"` (`null` for non-string values)
+    Redact,
+    /// Salted SHA-256 of the value
+    Hash,
+    /// Deterministic fake value of the same shape
+    Fake,
+}
+
+/// Masking applied to `export data` before files reach the output directory
+///
+/// ```toml
+/// [masking]
+/// salt = "..."
+///
+/// [masking.types.Customer]
+/// email = "fake"
+/// "address.street" = "redact"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaskingConfig {
+    /// Salt for `hash` and `fake`; without it, hashes of guessable values can be reversed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+
+    /// Actions per type name and field (dotted paths for nested fields)
+    #[serde(default)]
+    pub types: BTreeMap<String, BTreeMap<String, MaskAction>>,
+}
+
+impl MaskingConfig {
+    /// Whether any field is configured for masking
+    pub fn is_enabled(&self) -> bool {
+        self.types.values().any(|fields| !fields.is_empty())
+    }
+}
+
+/// Export cache settings
+///
+/// ```toml
+/// [cache]
+/// ttl_seconds = 600
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheConfig {
+    /// Reuse a profile's export for this long (seconds); 0 disables the cache
+    #[serde(default = "default_cache_ttl")]
+    pub ttl_seconds: u64,
+}
+
+fn default_cache_ttl() -> u64 {
+    300
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_seconds: default_cache_ttl(),
+        }
+    }
+}
+
+impl CacheConfig {
+    /// Get the TTL as Duration
+    pub fn ttl(&self) -> Duration {
+        Duration::from_secs(self.ttl_seconds)
+    }
+}
+
+/// Diff settings
+///
+/// ```toml
+/// [diff]
+/// ignore = ["sources/*.config.password", "scheduledevents/* /interval"]
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiffConfig {
+    /// Fields left out of every comparison, in addition to `--ignore-path`
+    /// (see `vqx diff --ignore-path`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+}
+
+/// Approval gate settings
+///
+/// ```toml
+/// [approval]
+/// dir = "/mnt/shared/vqx-approvals"
+/// timeout_seconds = 1800
+///
+/// # Pushing side: public keys of the approvers
+/// [approval.trusted_keys]
+/// alice = "3b6a27bc..."
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalConfig {
+    /// Directory for approval requests and decisions
+    /// Default: `approvals/` in the config directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// How long a push waits for `vqx approve` (seconds)
+    #[serde(default = "default_approval_timeout")]
+    pub timeout_seconds: u64,
+
+    /// Approver side: private key file that signs decisions
+    /// Default: `VQX_APPROVAL_KEY`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+
+    /// Pushing side: public keys whose signatures approve a push, by approver
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub trusted_keys: BTreeMap<String, String>,
+}
+
+fn default_approval_timeout() -> u64 {
+    3600
+}
+
+impl Default for ApprovalConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            timeout_seconds: default_approval_timeout(),
+            signing_key: None,
+            trusted_keys: BTreeMap::new(),
+        }
+    }
+}
+
+/// Resource name rules for import and sync push
+///
+/// Like the `[safe_delete]` prefixes, but for resources about to be
+/// imported. See [`crate::blocklist`].
+///
+/// ```toml
+/// [import]
+/// blocked_prefixes = ["System", "ARS", "Shared"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportConfig {
+    /// Allowlist of resource name prefixes that can be imported (empty: all)
+    #[serde(default)]
+    pub allowed_prefixes: Vec<String>,
+
+    /// Blocklist of resource name prefixes that need --force to be imported
+    #[serde(default = "default_import_blocked_prefixes")]
+    pub blocked_prefixes: Vec<String>,
+}
+
+fn default_import_blocked_prefixes() -> Vec<String> {
+    vec!["System".to_string(), "ARS".to_string()]
+}
+
+impl Default for ImportConfig {
+    fn default() -> Self {
+        Self {
+            allowed_prefixes: vec![],
+            blocked_prefixes: default_import_blocked_prefixes(),
+        }
+    }
+}
+
+impl ImportConfig {
+    /// Whether a resource name needs --force to be imported
+    pub fn is_blocked(&self, name: &str) -> bool {
+        self.blocked_prefixes
+            .iter()
+            .any(|p| name.starts_with(p.as_str()))
+            || (!self.allowed_prefixes.is_empty()
+                && !self
+                    .allowed_prefixes
+                    .iter()
+                    .any(|p| name.starts_with(p.as_str())))
+    }
+}
+
+/// Trash settings for `safe-delete --trash`
+///
+/// ```toml
+/// [trash]
+/// retention_days = 14
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashConfig {
+    /// Directory for trash entries
+    /// Default: `vqx/trash` in the local data directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Days an entry is kept before `vqx trash empty` removes it
+    #[serde(default = "default_trash_retention")]
+    pub retention_days: u32,
+}
+
+fn default_trash_retention() -> u32 {
+    7
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            retention_days: default_trash_retention(),
+        }
+    }
+}
+
+/// Snapshots taken before `import`, `sync push`, `promote` and `rollback`
+/// change a protected profile
+///
+/// ```toml
+/// [snapshots]
+/// keep = 5
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    /// Export the metadata of protected profiles before changing them
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Directory for snapshots
+    /// Default: `vqx/snapshots` in the local data directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Snapshots kept per profile; older ones are removed
+    #[serde(default = "default_snapshot_keep")]
+    pub keep: usize,
+}
+
+fn default_snapshot_keep() -> usize {
+    10
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+            keep: default_snapshot_keep(),
+        }
+    }
+}
+
+/// Chunk size tuning for `export`/`import --chunk auto`
+///
+/// ```toml
+/// [chunking]
+/// target_seconds = 30
+/// max_size = 20000
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkingConfig {
+    /// Time one chunk should take; slower chunks shrink, faster ones grow
+    #[serde(default = "default_chunk_target")]
+    pub target_seconds: u64,
+
+    /// Smallest chunk size tried
+    #[serde(default = "default_chunk_min")]
+    pub min_size: u32,
+
+    /// Largest chunk size tried
+    #[serde(default = "default_chunk_max")]
+    pub max_size: u32,
+}
+
+fn default_chunk_target() -> u64 {
+    20
+}
+
+fn default_chunk_min() -> u32 {
+    100
+}
+
+fn default_chunk_max() -> u32 {
+    50000
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            target_seconds: default_chunk_target(),
+            min_size: default_chunk_min(),
+            max_size: default_chunk_max(),
+        }
+    }
+}
+
+/// Web IDE links for `vqx open`
+///
+/// `url_template` may use `{url}` (the profile's base URL without a trailing
+/// slash), `{namespace}`, `{resource}` and `{name}`.
+///
+/// ```toml
+/// [ide]
+/// url_template = "{url}/ui/ide/index.html?namespace={namespace}#/{resource}/{name}"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdeConfig {
+    /// URL of a resource in the web IDE
+    #[serde(default = "default_ide_url_template")]
+    pub url_template: String,
+}
+
+fn default_ide_url_template() -> String {
+    "{url}/ui/ide/index.html#/{resource}/{name}".to_string()
+}
+
+impl Default for IdeConfig {
+    fn default() -> Self {
+        Self {
+            url_template: default_ide_url_template(),
+        }
+    }
+}
+
+/// A vqx command run on a cron schedule
+///
+/// ```toml
+/// [[schedule]]
+/// id = "job-1"
+/// cron = "0 2 * * *"
+/// command = ["sync", "pull", "-d", "./repo", "--profile", "prod"]
+/// dir = "/home/me/backups"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: String,
+
+    /// Cron expression, see [`crate::schedule`]
+    pub cron: String,
+
+    /// vqx arguments, without the program name
+    pub command: Vec<String>,
+
+    /// Working directory for relative paths in the command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+}
+
+/// Settings of `vqx serve`
+///
+/// ```toml
+/// [serve]
+/// listen = "127.0.0.1:8787"
+/// token_env = "VQX_SERVE_TOKEN"
+///
+/// [serve.operations.pull-prod]
+/// description = "Back up prod"
+/// command = ["sync", "pull", "-d", "/srv/prod", "--profile", "prod", "--force"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServeConfig {
+    /// Address to listen on
+    #[serde(default = "default_serve_listen")]
+    pub listen: String,
+
+    /// Environment variable holding the bearer token clients must send
+    #[serde(default = "default_serve_token_env")]
+    pub token_env: String,
+
+    /// Operations clients can run, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub operations: BTreeMap<String, ServeOperation>,
+}
+
+fn default_serve_listen() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+fn default_serve_token_env() -> String {
+    "VQX_SERVE_TOKEN".to_string()
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        Self {
+            listen: default_serve_listen(),
+            token_env: default_serve_token_env(),
+            operations: BTreeMap::new(),
+        }
+    }
+}
+
+/// A vqx command `vqx serve` runs on request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServeOperation {
+    /// vqx arguments, without the program name
+    pub command: Vec<String>,
+
+    /// Working directory for relative paths in the command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Notifications when long operations finish, see [`crate::notify`]
+///
+/// ```toml
+/// [notify]
+/// commands = ["export", "promote", "sync pull"]
+/// min_duration_secs = 60
+///
+/// [[notify.channels]]
+/// type = "slack"
+/// url = "https://hooks.slack.com/services/..."
+/// on = "failure"
+///
+/// [[notify.channels]]
+/// type = "desktop"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    /// Commands that notify when they finish
+    #[serde(default = "default_notify_commands")]
+    pub commands: Vec<String>,
+
+    /// Only notify about runs that took at least this long
+    #[serde(default)]
+    pub min_duration_secs: u64,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<NotifyChannel>,
+}
+
+fn default_notify_commands() -> Vec<String> {
+    ["export", "import", "sync pull", "sync push", "promote"]
+        .map(String::from)
+        .to_vec()
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            commands: default_notify_commands(),
+            min_duration_secs: 0,
+            channels: Vec::new(),
+        }
+    }
+}
+
+/// Where a notification goes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyChannel {
+    #[serde(rename = "type")]
+    pub kind: NotifyKind,
+
+    /// Webhook URL (`slack` and `webhook`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    #[serde(default)]
+    pub on: NotifyOn,
+}
+
+/// Kind of notification channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyKind {
+    /// Slack incoming webhook
+    Slack,
+    /// JSON summary POSTed to a URL
+    Webhook,
+    /// Desktop notification
+    Desktop,
+}
+
+/// Which runs a channel hears about
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyOn {
+    /// Every run
+    #[default]
+    Always,
+    /// Failed runs only
+    Failure,
+}
+
+/// Opt-in usage statistics, see [`crate::telemetry`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Record usage in the local buffer (`vqx telemetry on`)
+    #[serde(default)]
+    pub enabled: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -370,6 +1149,7 @@ mod tests {
         assert_eq!(config.timeout_seconds, 120);
         assert_eq!(config.max_retries, 3);
         assert!(config.safe_delete.require_confirm);
+        assert_eq!(config.cache.ttl(), Duration::from_secs(300));
     }
 
     #[test]
@@ -390,4 +1170,77 @@ mod tests {
             .excluded_fields
             .contains(&"ars_modifiedAt".to_string()));
     }
+
+    #[test]
+    fn test_masking_config() {
+        let config: Config = toml::from_str(
+            "[masking]\nsalt = \"s\"\n[masking.types.Customer]\nemail = \"fake\"\n\"address.zip\" = \"redact\"\n",
+        )
+        .unwrap();
+        assert!(config.masking.is_enabled());
+        assert_eq!(
+            config.masking.types["Customer"]["address.zip"],
+            MaskAction::Redact
+        );
+        assert!(!Config::default().masking.is_enabled());
+    }
+
+    #[test]
+    fn test_timeout_for() {
+        let config: Config =
+            toml::from_str("timeout_seconds = 60\n[timeouts]\nexport = 3600\n").unwrap();
+        assert_eq!(config.timeout_for("export"), Duration::from_secs(3600));
+        assert_eq!(config.timeout_for("import"), Duration::from_secs(60));
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let loaded: Config = toml::from_str(&saved).unwrap();
+        assert_eq!(loaded.timeouts["export"], 3600);
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = Config::default().retry_policy();
+        assert_eq!(policy.read.max_retries, 3);
+        assert_eq!(policy.read.base_delay_ms, 1000);
+        assert_eq!(policy.mutate, Backoff::NONE);
+
+        let config: Config = toml::from_str(
+            "[retry]\njitter = 0.2\n[retry.read]\nmax_retries = 5\n[retry.mutate]\nmax_retries = 1\ndelay_ms = 250\n",
+        )
+        .unwrap();
+        let policy = config.retry_policy();
+        assert_eq!(policy.read.max_retries, 5);
+        assert_eq!(policy.read.base_delay_ms, 1000);
+        assert_eq!(policy.mutate.max_retries, 1);
+        assert_eq!(policy.mutate.base_delay_ms, 250);
+        assert_eq!(policy.jitter, 0.2);
+    }
+
+    #[test]
+    fn test_logging_config() {
+        let config: Config = toml::from_str(
+            "[logging]\nformat = \"json\"\nfile = \"/var/log/vqx.log\"\nrotation = \"daily\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.logging.format, LogFormat::Json);
+        assert_eq!(config.logging.rotation, LogRotation::Daily);
+
+        let now = DateTime::parse_from_rfc3339("2024-01-15T09:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            config.logging.active_file(now),
+            Some(PathBuf::from("/var/log/vqx.log.2024-01-15"))
+        );
+
+        let logging = LoggingConfig {
+            rotation: LogRotation::Never,
+            ..config.logging
+        };
+        assert_eq!(
+            logging.active_file(now),
+            Some(PathBuf::from("/var/log/vqx.log"))
+        );
+        assert!(toml::from_str::<Config>("[logging]\nformat = \"xml\"\n").is_err());
+    }
 }
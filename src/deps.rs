@@ -0,0 +1,225 @@
+//! Resource dependency graph
+//!
+//! Built from a metadata export: a resource depends on another when one of
+//! its string values (names, type references, VAIL code) mentions the other
+//! resource's name. Used by safe-delete to plan cascades.
+
+use crate::commands::diff::RESOURCE_TYPES;
+use crate::error::Result;
+use crate::normalizer;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::Path;
+
+/// A resource in the graph, e.g. `types/Order`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ResourceRef {
+    pub resource_type: String,
+    pub name: String,
+}
+
+impl ResourceRef {
+    pub fn new(resource_type: &str, name: &str) -> Self {
+        Self {
+            resource_type: resource_type.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ResourceRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.resource_type, self.name)
+    }
+}
+
+/// Resource definitions of an export, keyed by resource
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    resources: BTreeMap<ResourceRef, Value>,
+}
+
+impl DependencyGraph {
+    /// Load all resource definitions from an export directory
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut graph = Self::default();
+
+        for resource_type in RESOURCE_TYPES {
+            let Ok(entries) = std::fs::read_dir(dir.join(resource_type)) else {
+                continue;
+            };
+            for path in entries.flatten().map(|e| e.path()) {
+                if path.extension().is_none_or(|e| e != "json") {
+                    continue;
+                }
+                let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                    continue;
+                };
+                let value: Value = serde_json::from_str(&normalizer::read_embedded(&path)?)?;
+                let name = value
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or(stem)
+                    .to_string();
+                graph.insert(ResourceRef::new(resource_type, &name), value);
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Add a resource definition
+    pub fn insert(&mut self, resource: ResourceRef, definition: Value) {
+        self.resources.insert(resource, definition);
+    }
+
+    /// Get a resource definition
+    pub fn get(&self, resource: &ResourceRef) -> Option<&Value> {
+        self.resources.get(resource)
+    }
+
+    /// Resources that reference `resource` directly
+    pub fn direct_dependents(&self, resource: &ResourceRef) -> BTreeSet<ResourceRef> {
+        let pattern = format!(r"(?:^|[^\w.]){}(?:\W|$)", regex::escape(&resource.name));
+        let Ok(re) = Regex::new(&pattern) else {
+            return BTreeSet::new();
+        };
+
+        self.resources
+            .iter()
+            .filter(|(other, _)| *other != resource)
+            .filter(|(_, definition)| references(definition, &re))
+            .map(|(other, _)| other.clone())
+            .collect()
+    }
+
+    /// Transitive dependents of `resource` in deletion order
+    ///
+    /// Every resource comes before the resources it depends on, so deleting
+    /// in this order never leaves a dangling reference. `resource` itself is
+    /// not included; it goes last.
+    pub fn cascade(&self, resource: &ResourceRef) -> Vec<ResourceRef> {
+        // Collect the closure and each member's direct dependents
+        let mut edges: BTreeMap<ResourceRef, BTreeSet<ResourceRef>> = BTreeMap::new();
+        let mut pending = vec![resource.clone()];
+        while let Some(current) = pending.pop() {
+            if edges.contains_key(&current) {
+                continue;
+            }
+            let dependents = self.direct_dependents(&current);
+            pending.extend(dependents.iter().cloned());
+            edges.insert(current, dependents);
+        }
+        edges.remove(resource);
+        for dependents in edges.values_mut() {
+            dependents.remove(resource);
+        }
+
+        // Delete a resource once everything depending on it is gone
+        let mut order = Vec::with_capacity(edges.len());
+        while !edges.is_empty() {
+            let ready = edges
+                .iter()
+                .find(|(_, dependents)| dependents.is_empty())
+                .or_else(|| edges.iter().next()) // cycle: break it by name
+                .map(|(r, _)| r.clone())
+                .unwrap();
+            edges.remove(&ready);
+            for dependents in edges.values_mut() {
+                dependents.remove(&ready);
+            }
+            order.push(ready);
+        }
+
+        order
+    }
+}
+
+/// Whether any string value in a definition matches the reference pattern
+fn references(value: &Value, re: &Regex) -> bool {
+    match value {
+        Value::String(s) => re.is_match(s),
+        Value::Array(items) => items.iter().any(|v| references(v, re)),
+        Value::Object(map) => map.values().any(|v| references(v, re)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        graph.insert(
+            ResourceRef::new("types", "Order"),
+            json!({"name": "Order", "properties": {"total": {"type": "Real"}}}),
+        );
+        graph.insert(
+            ResourceRef::new("procedures", "Orders.total"),
+            json!({"name": "Orders.total", "ruleText": "PROCEDURE Orders.total()\nSELECT FROM Order"}),
+        );
+        graph.insert(
+            ResourceRef::new("rules", "OnOrder"),
+            json!({"name": "OnOrder", "ruleText": "RULE OnOrder\nWHEN INSERT OCCURS ON Order\nOrders.total()"}),
+        );
+        graph.insert(
+            ResourceRef::new("types", "OrderLine"),
+            json!({"name": "OrderLine"}),
+        );
+        graph
+    }
+
+    #[test]
+    fn test_direct_dependents_match_whole_names() {
+        let graph = sample_graph();
+        let dependents = graph.direct_dependents(&ResourceRef::new("types", "Order"));
+
+        assert!(dependents.contains(&ResourceRef::new("procedures", "Orders.total")));
+        assert!(dependents.contains(&ResourceRef::new("rules", "OnOrder")));
+        // "OrderLine" only shares a prefix
+        assert!(!dependents.contains(&ResourceRef::new("types", "OrderLine")));
+    }
+
+    #[test]
+    fn test_cascade_deletes_dependents_first() {
+        let graph = sample_graph();
+        let order = graph.cascade(&ResourceRef::new("types", "Order"));
+
+        let position = |r: &ResourceRef| order.iter().position(|o| o == r).unwrap();
+        let rule = ResourceRef::new("rules", "OnOrder");
+        let procedure = ResourceRef::new("procedures", "Orders.total");
+
+        assert_eq!(order.len(), 2);
+        // The rule calls the procedure, so it must go first
+        assert!(position(&rule) < position(&procedure));
+    }
+
+    #[test]
+    fn test_cascade_without_dependents() {
+        let graph = sample_graph();
+        assert!(graph
+            .cascade(&ResourceRef::new("types", "OrderLine"))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_load_uses_resource_directories() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("types")).unwrap();
+        std::fs::create_dir_all(dir.path().join("_hidden/types")).unwrap();
+        std::fs::write(dir.path().join("types/Order.json"), r#"{"name": "Order"}"#).unwrap();
+        std::fs::write(
+            dir.path().join("_hidden/types/ArsType.json"),
+            r#"{"name": "ArsType"}"#,
+        )
+        .unwrap();
+
+        let graph = DependencyGraph::load(dir.path()).unwrap();
+        assert!(graph.get(&ResourceRef::new("types", "Order")).is_some());
+        assert!(graph.get(&ResourceRef::new("types", "ArsType")).is_none());
+    }
+}
@@ -3,6 +3,12 @@
 //! Based on: CLI Reference Guide - Installation section (Java 11 requirement)
 //! and Command Line Options section (connection errors)
 
+use crate::cli::OutputFormat;
+use crate::exit;
+use crate::i18n;
+use crate::report;
+use serde::Serialize;
+use std::fmt::Display;
 use thiserror::Error;
 
 /// Main error type for vqx operations
@@ -31,12 +37,25 @@ pub enum VqxError {
     #[error("Profile '{name}' not found")]
     ProfileNotFound { name: String },
 
+    #[error("Credential '{name}' not found")]
+    CredentialNotFound { name: String },
+
+    #[error("Credentials for profile '{profile}' are {age_days} days old (limit: {max_days} days). Rotate them before running destructive operations.")]
+    StaleCredential {
+        profile: String,
+        age_days: i64,
+        max_days: u32,
+    },
+
     #[error("Profile file not found: {path}")]
     ProfileFileNotFound { path: String },
 
     #[error("Invalid profile configuration: {message}")]
     ProfileInvalid { message: String },
 
+    #[error("Profile '{profile}' has no value for template variable(s): {names}")]
+    TemplateVariablesMissing { profile: String, names: String },
+
     #[error("Cannot use namespace option with access token. Use username/password instead. (PDF: Profile section notes)")]
     NamespaceWithToken,
 
@@ -52,6 +71,16 @@ pub enum VqxError {
     #[error("Failed to spawn CLI process: {message}")]
     CliSpawnFailed { message: String },
 
+    #[error("CLI command aborted after producing no output for {seconds} seconds")]
+    CliStalled { seconds: u64 },
+
+    #[error("Authentication failed: {message}")]
+    AuthenticationFailed { message: String },
+
+    #[error("REST request failed: {message}")]
+    #[cfg_attr(not(feature = "rest-backend"), allow(dead_code))]
+    RestRequestFailed { message: String },
+
     // ===========================================
     // Destructive operation safeguards
     // Based on: PDF "Delete" and "DeleteMatching" sections
@@ -62,6 +91,44 @@ pub enum VqxError {
     #[error("Backup required before destructive operation but failed: {message}")]
     BackupFailed { message: String },
 
+    #[error(
+        "{target} is locked by {holder}. Use --wait to wait for it or --force-lock to break it"
+    )]
+    LockHeld { target: String, holder: String },
+
+    #[error("{operation} cancelled by user")]
+    Cancelled { operation: String },
+
+    #[error("Profile '{profile}' is read-only: {operation} is not allowed")]
+    ProfileProtected { profile: String, operation: String },
+
+    #[error("{count} resource(s) match the [import] block list: {names}")]
+    ResourcesBlocked { count: usize, names: String },
+
+    #[error("Policy for profile '{profile}' does not allow {operation}: {reason}")]
+    PolicyDenied {
+        profile: String,
+        operation: String,
+        reason: String,
+    },
+
+    #[error(
+        "{operation} needs input, but stdin is not a terminal or --no-input is set; {alternative}"
+    )]
+    InputRequired {
+        operation: String,
+        alternative: String,
+    },
+
+    #[error("{operation} needs the server, but --offline is set")]
+    Offline { operation: String },
+
+    #[error("No approval for plan {hash} within {seconds} seconds")]
+    ApprovalTimeout { hash: String, seconds: u64 },
+
+    #[error("Approval refused: {message}")]
+    ApprovalRejected { message: String },
+
     // ===========================================
     // I/O and configuration errors
     // ===========================================
@@ -77,6 +144,12 @@ pub enum VqxError {
     #[error("Invalid TOML configuration: {message}")]
     InvalidToml { message: String },
 
+    #[error("Invalid cron expression '{expression}': {message}")]
+    InvalidSchedule { expression: String, message: String },
+
+    #[error("Invalid config key '{key}': {message}")]
+    InvalidConfigKey { key: String, message: String },
+
     // ===========================================
     // Secret storage errors
     // ===========================================
@@ -107,6 +180,12 @@ impl From<serde_json::Error> for VqxError {
     }
 }
 
+impl From<serde_yaml::Error> for VqxError {
+    fn from(err: serde_yaml::Error) -> Self {
+        VqxError::Other(format!("Failed to serialize YAML: {}", err))
+    }
+}
+
 impl From<toml::de::Error> for VqxError {
     fn from(err: toml::de::Error) -> Self {
         VqxError::InvalidToml {
@@ -116,3 +195,412 @@ impl From<toml::de::Error> for VqxError {
 }
 
 pub type Result<T> = std::result::Result<T, VqxError>;
+
+impl VqxError {
+    /// Stable identifier of the error kind, e.g. `profile_not_found`
+    ///
+    /// Codes are part of the machine-readable output and never change once
+    /// released; new variants get new codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            VqxError::JavaNotFound => "java_not_found",
+            VqxError::JavaVersionUnsupported { .. } => "java_version_unsupported",
+            VqxError::CliNotFound { .. } => "cli_not_found",
+            VqxError::CliNotExecutable { .. } => "cli_not_executable",
+            VqxError::ProfileNotFound { .. } => "profile_not_found",
+            VqxError::CredentialNotFound { .. } => "credential_not_found",
+            VqxError::StaleCredential { .. } => "stale_credential",
+            VqxError::ProfileFileNotFound { .. } => "profile_file_not_found",
+            VqxError::ProfileInvalid { .. } => "profile_invalid",
+            VqxError::TemplateVariablesMissing { .. } => "template_variables_missing",
+            VqxError::NamespaceWithToken => "namespace_with_token",
+            VqxError::CliExecutionFailed { .. } => "cli_execution_failed",
+            VqxError::CliTimeout { .. } => "cli_timeout",
+            VqxError::CliSpawnFailed { .. } => "cli_spawn_failed",
+            VqxError::CliStalled { .. } => "cli_stalled",
+            VqxError::AuthenticationFailed { .. } => "authentication_failed",
+            VqxError::RestRequestFailed { .. } => "rest_request_failed",
+            VqxError::DestructiveOperationNotConfirmed { .. } => "not_confirmed",
+            VqxError::BackupFailed { .. } => "backup_failed",
+            VqxError::LockHeld { .. } => "lock_held",
+            VqxError::Cancelled { .. } => "cancelled",
+            VqxError::ProfileProtected { .. } => "profile_protected",
+            VqxError::PolicyDenied { .. } => "policy_denied",
+            VqxError::ResourcesBlocked { .. } => "resources_blocked",
+            VqxError::InputRequired { .. } => "input_required",
+            VqxError::Offline { .. } => "offline",
+            VqxError::ApprovalTimeout { .. } => "approval_timeout",
+            VqxError::ApprovalRejected { .. } => "approval_rejected",
+            VqxError::FileReadFailed { .. } => "file_read_failed",
+            VqxError::FileWriteFailed { .. } => "file_write_failed",
+            VqxError::InvalidJson { .. } => "invalid_json",
+            VqxError::InvalidToml { .. } => "invalid_toml",
+            VqxError::InvalidSchedule { .. } => "invalid_schedule",
+            VqxError::InvalidConfigKey { .. } => "invalid_config_key",
+            VqxError::SecretStorageFailed { .. } => "secret_storage_failed",
+            VqxError::EncryptionFailed { .. } => "encryption_failed",
+            VqxError::Other(_) => "other",
+        }
+    }
+
+    /// Broad group of the error, following the sections of [`VqxError`]
+    pub fn category(&self) -> &'static str {
+        match self {
+            VqxError::JavaNotFound
+            | VqxError::JavaVersionUnsupported { .. }
+            | VqxError::CliNotFound { .. }
+            | VqxError::CliNotExecutable { .. } => "environment",
+            VqxError::ProfileNotFound { .. }
+            | VqxError::CredentialNotFound { .. }
+            | VqxError::StaleCredential { .. }
+            | VqxError::ProfileFileNotFound { .. }
+            | VqxError::ProfileInvalid { .. }
+            | VqxError::TemplateVariablesMissing { .. }
+            | VqxError::NamespaceWithToken => "profile",
+            VqxError::CliExecutionFailed { .. }
+            | VqxError::CliTimeout { .. }
+            | VqxError::CliSpawnFailed { .. }
+            | VqxError::CliStalled { .. }
+            | VqxError::AuthenticationFailed { .. }
+            | VqxError::RestRequestFailed { .. } => "cli",
+            VqxError::DestructiveOperationNotConfirmed { .. }
+            | VqxError::BackupFailed { .. }
+            | VqxError::LockHeld { .. }
+            | VqxError::Cancelled { .. }
+            | VqxError::ProfileProtected { .. }
+            | VqxError::PolicyDenied { .. }
+            | VqxError::ResourcesBlocked { .. }
+            | VqxError::InputRequired { .. }
+            | VqxError::Offline { .. }
+            | VqxError::ApprovalTimeout { .. }
+            | VqxError::ApprovalRejected { .. } => "safety",
+            VqxError::FileReadFailed { .. }
+            | VqxError::FileWriteFailed { .. }
+            | VqxError::InvalidJson { .. }
+            | VqxError::InvalidToml { .. }
+            | VqxError::InvalidSchedule { .. }
+            | VqxError::InvalidConfigKey { .. } => "io",
+            VqxError::SecretStorageFailed { .. } | VqxError::EncryptionFailed { .. } => "secrets",
+            VqxError::Other(_) => "other",
+        }
+    }
+
+    /// What the user can do about the error, if there is a common fix
+    pub fn hint(&self) -> Option<&'static str> {
+        Some(match self {
+            VqxError::JavaNotFound | VqxError::JavaVersionUnsupported { .. } => {
+                "Install Java 11 or later and make sure it is on PATH; run `vqx doctor` to check"
+            }
+            VqxError::CliNotFound { .. }
+            | VqxError::CliNotExecutable { .. }
+            | VqxError::CliSpawnFailed { .. } => {
+                "Set `cli_path` in config.toml, --cli or VQX_CLI_PATH; run `vqx doctor` to check"
+            }
+            VqxError::ProfileNotFound { .. } => {
+                "Run `vqx profile list` to see profiles or `vqx profile init` to create one"
+            }
+            VqxError::CredentialNotFound { .. } => {
+                "Run `vqx credential list` to see credentials or `vqx credential set` to create one"
+            }
+            VqxError::StaleCredential { .. } => {
+                "Rotate the credentials with `vqx profile set` or `vqx credential set`"
+            }
+            VqxError::ProfileInvalid { .. } | VqxError::NamespaceWithToken => {
+                "Check the profile with `vqx profile show <name>` and fix it with `vqx profile set`"
+            }
+            VqxError::TemplateVariablesMissing { .. } => {
+                "Set the values with `vqx profile set <name> --var <name>=<value>`"
+            }
+            VqxError::CliExecutionFailed { .. } => {
+                "Re-run with --verbose to see the underlying CLI command and its output"
+            }
+            VqxError::RestRequestFailed { .. } => {
+                "Check the profile's URL and network access, or set `backend = \"cli\"` on the profile"
+            }
+            VqxError::CliTimeout { .. } => {
+                "Raise `timeout_seconds` or `[timeouts]` in config.toml, or pass --timeout"
+            }
+            VqxError::CliStalled { .. } => {
+                "Raise `stall_threshold_seconds` in config.toml if the server is just slow"
+            }
+            VqxError::AuthenticationFailed { .. } => {
+                "Check the profile's credentials with `vqx profile show <name>` or `vqx doctor --test-connection`"
+            }
+            VqxError::DestructiveOperationNotConfirmed { .. } => "Re-run with --yes to confirm",
+            VqxError::LockHeld { .. } => {
+                "Wait for the other vqx process, pass --wait, or --force-lock if it hangs"
+            }
+            VqxError::ProfileProtected { .. } => {
+                "Use another profile, or change it with `vqx profile set <name> --protection confirm`"
+            }
+            VqxError::ResourcesBlocked { .. } => {
+                "Pass --force to import them anyway, or adjust the `[import]` prefixes in config.toml"
+            }
+            VqxError::PolicyDenied { .. } => {
+                "Check the `[policy]` sections in config.toml, or use another profile"
+            }
+            VqxError::Offline { .. } => {
+                "Drop --offline (and VQX_OFFLINE) to reach the server, or work on an export directory"
+            }
+            VqxError::ApprovalTimeout { .. } => {
+                "Ask a second person to run `vqx approve <hash>`, or raise `[approval] timeout_seconds`"
+            }
+            VqxError::InvalidToml { .. } => {
+                "Fix config.toml; see examples/config.toml for the available settings"
+            }
+            VqxError::InvalidSchedule { .. } => {
+                "Give five fields (minute hour day month weekday), e.g. \"0 2 * * *\", or @daily"
+            }
+            VqxError::InvalidConfigKey { .. } => {
+                "Run `vqx config get` to see the settings and their current values"
+            }
+            VqxError::SecretStorageFailed { .. } => {
+                "Check that the OS keychain is available, or disable `use_secure_storage`"
+            }
+            _ => return None,
+        })
+    }
+
+    /// Message in the configured language, see [`crate::i18n`]
+    pub fn localized(&self) -> String {
+        let args = self.message_args();
+        let args: Vec<(&str, &dyn Display)> = args
+            .iter()
+            .map(|(name, value)| (*name, value as &dyn Display))
+            .collect();
+        i18n::error_message(self.code(), &args).unwrap_or_else(|| self.to_string())
+    }
+
+    /// Fields of the variant, named as in its message
+    fn message_args(&self) -> Vec<(&'static str, String)> {
+        match self {
+            VqxError::JavaVersionUnsupported { found } => vec![("found", found.clone())],
+            VqxError::CliNotFound { path }
+            | VqxError::CliNotExecutable { path }
+            | VqxError::ProfileFileNotFound { path }
+            | VqxError::FileReadFailed { path }
+            | VqxError::FileWriteFailed { path } => vec![("path", path.clone())],
+            VqxError::ProfileNotFound { name } | VqxError::CredentialNotFound { name } => {
+                vec![("name", name.clone())]
+            }
+            VqxError::StaleCredential {
+                profile,
+                age_days,
+                max_days,
+            } => vec![
+                ("profile", profile.clone()),
+                ("age_days", age_days.to_string()),
+                ("max_days", max_days.to_string()),
+            ],
+            VqxError::ProfileInvalid { message }
+            | VqxError::CliSpawnFailed { message }
+            | VqxError::AuthenticationFailed { message }
+            | VqxError::RestRequestFailed { message }
+            | VqxError::BackupFailed { message }
+            | VqxError::ApprovalRejected { message }
+            | VqxError::InvalidJson { message }
+            | VqxError::InvalidToml { message }
+            | VqxError::SecretStorageFailed { message }
+            | VqxError::EncryptionFailed { message } => vec![("message", message.clone())],
+            VqxError::TemplateVariablesMissing { profile, names } => {
+                vec![("profile", profile.clone()), ("names", names.clone())]
+            }
+            VqxError::CliExecutionFailed { code, message } => {
+                vec![("code", code.to_string()), ("message", message.clone())]
+            }
+            VqxError::CliTimeout { seconds } | VqxError::CliStalled { seconds } => {
+                vec![("seconds", seconds.to_string())]
+            }
+            VqxError::DestructiveOperationNotConfirmed { operation }
+            | VqxError::Cancelled { operation }
+            | VqxError::Offline { operation } => vec![("operation", operation.clone())],
+            VqxError::LockHeld { target, holder } => {
+                vec![("target", target.clone()), ("holder", holder.clone())]
+            }
+            VqxError::ProfileProtected { profile, operation } => {
+                vec![
+                    ("profile", profile.clone()),
+                    ("operation", operation.clone()),
+                ]
+            }
+            VqxError::ResourcesBlocked { count, names } => {
+                vec![("count", count.to_string()), ("names", names.clone())]
+            }
+            VqxError::PolicyDenied {
+                profile,
+                operation,
+                reason,
+            } => vec![
+                ("profile", profile.clone()),
+                ("operation", operation.clone()),
+                ("reason", reason.clone()),
+            ],
+            VqxError::InputRequired {
+                operation,
+                alternative,
+            } => vec![
+                ("operation", operation.clone()),
+                ("alternative", alternative.clone()),
+            ],
+            VqxError::ApprovalTimeout { hash, seconds } => {
+                vec![("hash", hash.clone()), ("seconds", seconds.to_string())]
+            }
+            VqxError::InvalidSchedule {
+                expression,
+                message,
+            } => vec![
+                ("expression", expression.clone()),
+                ("message", message.clone()),
+            ],
+            VqxError::InvalidConfigKey { key, message } => {
+                vec![("key", key.clone()), ("message", message.clone())]
+            }
+            VqxError::JavaNotFound | VqxError::NamespaceWithToken | VqxError::Other(_) => {
+                Vec::new()
+            }
+        }
+    }
+
+    /// Process exit code for the error, see [`crate::exit`]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            VqxError::AuthenticationFailed { .. } | VqxError::StaleCredential { .. } => exit::AUTH,
+            VqxError::JavaNotFound
+            | VqxError::JavaVersionUnsupported { .. }
+            | VqxError::CliNotFound { .. }
+            | VqxError::CliNotExecutable { .. }
+            | VqxError::CliSpawnFailed { .. } => exit::CLI_MISSING,
+            VqxError::Cancelled { .. } | VqxError::ApprovalRejected { .. } => exit::CANCELLED,
+            VqxError::CliTimeout { .. }
+            | VqxError::CliStalled { .. }
+            | VqxError::ApprovalTimeout { .. } => exit::TIMEOUT,
+            VqxError::LockHeld { .. } => exit::LOCKED,
+            _ => exit::FAILURE,
+        }
+    }
+}
+
+/// Error as printed on stderr with a machine-readable `--output`
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    /// Stable code, see [`VqxError::code`]
+    pub code: String,
+    pub category: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+    /// Underlying causes, outermost first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub causes: Vec<String>,
+}
+
+impl ErrorReport {
+    pub fn new(err: &anyhow::Error) -> Self {
+        let vqx = err.chain().find_map(|e| e.downcast_ref::<VqxError>());
+        Self {
+            code: vqx.map_or("other", VqxError::code).to_string(),
+            category: vqx.map_or("other", VqxError::category).to_string(),
+            message: err.to_string(),
+            hint: vqx.and_then(VqxError::hint).map(str::to_string),
+            causes: err.chain().skip(1).map(|e| e.to_string()).collect(),
+        }
+    }
+}
+
+/// Print a command error on stderr
+///
+/// Machine-readable formats get an [`ErrorReport`] in the same format, so
+/// wrappers can branch on `code` instead of parsing text.
+pub fn render_error(err: &anyhow::Error, output_format: OutputFormat) {
+    if output_format.is_machine_readable() {
+        if let Ok(out) = report::render(&ErrorReport::new(err), output_format) {
+            eprintln!("{}", out);
+            return;
+        }
+    }
+    // A vqx error without context is printed in the configured language
+    match err.downcast_ref::<VqxError>() {
+        Some(vqx) if err.chain().count() == 1 => {
+            eprintln!("{}: {}", i18n::t("error"), vqx.localized())
+        }
+        _ => eprintln!("{}: {:?}", i18n::t("error"), err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_report() {
+        let err = anyhow::Error::new(VqxError::ProfileNotFound {
+            name: "prod".to_string(),
+        });
+        let report = ErrorReport::new(&err);
+        assert_eq!(report.code, "profile_not_found");
+        assert_eq!(report.category, "profile");
+        assert_eq!(report.message, "Profile 'prod' not found");
+        assert!(report.hint.unwrap().contains("vqx profile init"));
+        assert!(report.causes.is_empty());
+
+        let json = report::render(&ErrorReport::new(&err), OutputFormat::Ndjson).unwrap();
+        assert!(json.starts_with(r#"{"report_version":1,"code":"profile_not_found","#));
+    }
+
+    #[test]
+    fn test_localized_messages_fill_all_fields() {
+        let errors = [
+            VqxError::StaleCredential {
+                profile: "prod".to_string(),
+                age_days: 120,
+                max_days: 90,
+            },
+            VqxError::PolicyDenied {
+                profile: "prod".to_string(),
+                operation: "import".to_string(),
+                reason: "read-only".to_string(),
+            },
+            VqxError::InputRequired {
+                operation: "Import".to_string(),
+                alternative: "pass --yes to confirm".to_string(),
+            },
+            VqxError::LockHeld {
+                target: "prod".to_string(),
+                holder: "pid 1".to_string(),
+            },
+            VqxError::Offline {
+                operation: "export".to_string(),
+            },
+        ];
+        for err in &errors {
+            // English is the thiserror message
+            assert_eq!(err.localized(), err.to_string());
+
+            let args = err.message_args();
+            let args: Vec<(&str, &dyn Display)> = args
+                .iter()
+                .map(|(name, value)| (*name, value as &dyn Display))
+                .collect();
+            let ja =
+                i18n::error_message_in(crate::config::Language::Ja, err.code(), &args).unwrap();
+            assert!(!ja.contains('{'), "{}", ja);
+        }
+    }
+
+    #[test]
+    fn test_error_report_context() {
+        let err =
+            anyhow::Error::new(VqxError::CliTimeout { seconds: 120 }).context("Export failed");
+        let report = ErrorReport::new(&err);
+        assert_eq!(report.code, "cli_timeout");
+        assert_eq!(report.message, "Export failed");
+        assert_eq!(
+            report.causes,
+            vec!["CLI command timed out after 120 seconds"]
+        );
+
+        let report = ErrorReport::new(&anyhow::anyhow!("boom"));
+        assert_eq!(report.code, "other");
+        assert_eq!(report.hint, None);
+    }
+}
@@ -0,0 +1,64 @@
+//! Process exit codes
+//!
+//! Every command exits with one of these codes so scripts and CI can tell
+//! failures apart without parsing output. Errors map to a code with
+//! [`VqxError::exit_code`](crate::error::VqxError::exit_code); command
+//! results use [`status`]. `run` keeps its own code 2 for suites that could
+//! not be run, and invalid arguments exit with 2 as well.
+
+/// The command succeeded
+pub const SUCCESS: i32 = 0;
+/// The operation failed
+pub const FAILURE: i32 = 1;
+/// `diff --strict` found changes
+pub const CHANGES: i32 = 2;
+/// Authentication against the server failed, or the credentials are overdue
+pub const AUTH: i32 = 3;
+/// Java or the Vantiq CLI is missing or cannot be started
+pub const CLI_MISSING: i32 = 4;
+/// The user declined a confirmation prompt, or an approval was refused
+pub const CANCELLED: i32 = 5;
+/// The underlying CLI timed out or stalled, or no approval arrived in time
+pub const TIMEOUT: i32 = 6;
+/// Another vqx process holds the operation lock
+pub const LOCKED: i32 = 7;
+
+/// Exit code for a command result
+pub fn status(success: bool) -> i32 {
+    if success {
+        SUCCESS
+    } else {
+        FAILURE
+    }
+}
+
+/// Exit code for a command error
+pub fn for_error(err: &anyhow::Error) -> i32 {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<crate::error::VqxError>())
+        .map_or(FAILURE, |e| e.exit_code())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::VqxError;
+
+    #[test]
+    fn test_for_error() {
+        let err = anyhow::Error::new(VqxError::Cancelled {
+            operation: "Import".to_string(),
+        });
+        assert_eq!(for_error(&err), CANCELLED);
+
+        let err = anyhow::Error::new(VqxError::CliNotFound {
+            path: "vantiq".to_string(),
+        })
+        .context("Export failed");
+        assert_eq!(for_error(&err), CLI_MISSING);
+
+        assert_eq!(for_error(&anyhow::anyhow!("boom")), FAILURE);
+        assert_eq!(status(true), SUCCESS);
+        assert_eq!(status(false), FAILURE);
+    }
+}
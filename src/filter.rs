@@ -0,0 +1,361 @@
+//! Resource filters (`--include` / `--exclude`)
+//!
+//! Every command that selects resources uses the same patterns:
+//! - `types`: every resource of a type
+//! - `procedures/Orders.total`: one resource
+//! - globs in either part, where `*` matches any run of characters and `?`
+//!   one character: `procedures/Order*`, `*/Order*`, `source*`
+//!
+//! A resource is selected when it matches an include pattern (or there are
+//! none) and no exclude pattern. In an export directory a resource is the
+//! file `<type>/<name>.<ext>`, so `Foo.json` and its extracted `Foo.vail`
+//! are selected together. Files outside the resource type directories are
+//! not resources; they are only kept when nothing is included explicitly.
+//!
+//! Plain type names are what the underlying CLI's `-include`/`-exclude`
+//! understand (PDF: "-include <typeName(s)>"). `export` and `import` pass
+//! filters made of plain names on as before; anything else is applied by
+//! vqx to the files.
+
+use crate::cli::FilterArgs;
+use crate::commands::list::glob_to_regex;
+use crate::error::{Result, VqxError};
+use regex::Regex;
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+/// One `<type>[/<name>]` pattern
+#[derive(Debug)]
+struct Pattern {
+    text: String,
+    resource_type: Regex,
+    name: Option<Regex>,
+}
+
+impl Pattern {
+    fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (resource_type, name) = match text.split_once('/') {
+            Some((resource_type, name)) => (resource_type, Some(name)),
+            None => (text, None),
+        };
+        if resource_type.is_empty() || name == Some("") {
+            return Err(VqxError::Other(format!(
+                "Invalid resource pattern '{}': expected <type> or <type>/<name>",
+                text
+            )));
+        }
+        Ok(Self {
+            text: text.to_string(),
+            resource_type: glob_to_regex(resource_type)?,
+            name: name.map(glob_to_regex).transpose()?,
+        })
+    }
+
+    fn matches(&self, resource_type: &str, name: &str) -> bool {
+        self.resource_type.is_match(resource_type)
+            && self.name.as_ref().is_none_or(|re| re.is_match(name))
+    }
+
+    /// A type name without glob, as the underlying CLI takes it
+    fn is_plain(&self) -> bool {
+        !self.text.contains(['/', '*', '?'])
+    }
+}
+
+/// Compiled include and exclude patterns
+#[derive(Debug, Default)]
+pub struct ResourceFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+}
+
+impl ResourceFilter {
+    /// Compile patterns; empty patterns are skipped
+    pub fn new<S: AsRef<str>>(include: &[S], exclude: &[S]) -> Result<Self> {
+        let compile = |patterns: &[S]| {
+            patterns
+                .iter()
+                .map(|p| p.as_ref())
+                .filter(|p| !p.trim().is_empty())
+                .map(Pattern::parse)
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(Self {
+            include: compile(include)?,
+            exclude: compile(exclude)?,
+        })
+    }
+
+    /// Filter from `--include`/`--exclude`
+    ///
+    /// Resource types (`--resource`) and a name glob (`--name`) are
+    /// shorthands for more include patterns: `<type>`, `<type>/<name>` or
+    /// `*/<name>`.
+    pub fn from_args(args: &FilterArgs, types: &[String], name: Option<&str>) -> Result<Self> {
+        let mut include = args.include.clone();
+        match (types, name) {
+            ([], Some(name)) => include.push(format!("*/{}", name)),
+            (types, name) => include.extend(types.iter().map(|t| match name {
+                Some(name) => format!("{}/{}", t, name),
+                None => t.clone(),
+            })),
+        }
+        Self::new(&include, &args.exclude)
+    }
+
+    /// Whether every resource is selected
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether the resource `<resource_type>/<name>` is selected
+    pub fn matches(&self, resource_type: &str, name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|p| p.matches(resource_type, name)))
+            && !self.exclude.iter().any(|p| p.matches(resource_type, name))
+    }
+
+    /// Whether any resource of the type can be selected
+    pub fn matches_type(&self, resource_type: &str) -> bool {
+        let type_matches = |p: &Pattern| p.resource_type.is_match(resource_type);
+        (self.include.is_empty() || self.include.iter().any(type_matches))
+            && !self
+                .exclude
+                .iter()
+                .any(|p| p.name.is_none() && type_matches(p))
+    }
+
+    /// Whether a file of an export directory is selected, by its path
+    /// relative to the directory
+    pub fn matches_path(&self, rel: &Path) -> bool {
+        let parts: Vec<&str> = rel
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+        let stem = rel.file_stem().and_then(|s| s.to_str());
+        match (parts.as_slice(), stem) {
+            ([resource_type, _, ..], Some(name)) => self.matches(resource_type, name),
+            _ => self.include.is_empty(),
+        }
+    }
+
+    /// Include and exclude patterns that select none of `resources`
+    ///
+    /// A resource without a name stands for every resource of its type, as
+    /// when only the types are known.
+    pub fn unmatched(&self, resources: &[(&str, Option<&str>)]) -> (Vec<&str>, Vec<&str>) {
+        fn unmatched<'a>(
+            patterns: &'a [Pattern],
+            resources: &[(&str, Option<&str>)],
+        ) -> Vec<&'a str> {
+            patterns
+                .iter()
+                .filter(|p| {
+                    !resources.iter().any(|(resource_type, name)| match name {
+                        Some(name) => p.matches(resource_type, name),
+                        None => p.resource_type.is_match(resource_type),
+                    })
+                })
+                .map(|p| p.text.as_str())
+                .collect()
+        }
+        (
+            unmatched(&self.include, resources),
+            unmatched(&self.exclude, resources),
+        )
+    }
+
+    /// Type names for the underlying CLI's `-include` and `-exclude`, when
+    /// the filter consists of plain type names only
+    pub fn cli_types(&self) -> Option<(Vec<&str>, Vec<&str>)> {
+        fn names(patterns: &[Pattern]) -> Vec<&str> {
+            patterns.iter().map(|p| p.text.as_str()).collect()
+        }
+        let all_plain = self
+            .include
+            .iter()
+            .chain(&self.exclude)
+            .all(Pattern::is_plain);
+        all_plain.then(|| (names(&self.include), names(&self.exclude)))
+    }
+
+    /// Copy the selected files of `from` into `to`, returning how many
+    pub fn copy_selected(&self, from: &Path, to: &Path) -> Result<usize> {
+        let mut copied = 0;
+        for entry in WalkDir::new(from).into_iter().filter_map(|e| e.ok()) {
+            let Ok(rel) = entry.path().strip_prefix(from) else {
+                continue;
+            };
+            if !entry.file_type().is_file() || !self.matches_path(rel) {
+                continue;
+            }
+            let dest = to.join(rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent).map_err(|_| VqxError::FileWriteFailed {
+                    path: parent.display().to_string(),
+                })?;
+            }
+            fs::copy(entry.path(), &dest).map_err(|_| VqxError::FileWriteFailed {
+                path: dest.display().to_string(),
+            })?;
+            copied += 1;
+        }
+        Ok(copied)
+    }
+
+    /// Copy of `dir` with only the selected files, or `None` when every
+    /// file is selected
+    pub fn stage(&self, dir: &Path) -> Result<Option<TempDir>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let staging = TempDir::new()?;
+        self.copy_selected(dir, staging.path())?;
+        Ok(Some(staging))
+    }
+
+    /// Remove the files of `dir` that are not selected, returning how many
+    pub fn prune(&self, dir: &Path) -> Result<usize> {
+        if self.is_empty() {
+            return Ok(0);
+        }
+        let unselected: Vec<_> = WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| {
+                e.path()
+                    .strip_prefix(dir)
+                    .is_ok_and(|rel| !self.matches_path(rel))
+            })
+            .map(|e| e.into_path())
+            .collect();
+        for path in &unselected {
+            fs::remove_file(path)?;
+        }
+        Ok(unselected.len())
+    }
+}
+
+impl fmt::Display for ResourceFilter {
+    /// The patterns, excluded ones with a leading `!`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let patterns: Vec<String> = self
+            .include
+            .iter()
+            .map(|p| p.text.clone())
+            .chain(self.exclude.iter().map(|p| format!("!{}", p.text)))
+            .collect();
+        f.write_str(&patterns.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patterns() {
+        let filter =
+            ResourceFilter::new(&["procedures/Order*", "types"], &["types/Audit?"]).unwrap();
+        assert!(filter.matches("procedures", "Orders.total"));
+        assert!(!filter.matches("procedures", "Billing.run"));
+        assert!(filter.matches("types", "Order"));
+        assert!(!filter.matches("types", "Audit1"));
+        assert!(!filter.matches("rules", "Order"));
+        assert!(filter.matches_type("types"));
+        assert!(!filter.matches_type("rules"));
+
+        // Whole types excluded
+        let filter = ResourceFilter::new(&[] as &[&str], &["source*"]).unwrap();
+        assert!(!filter.matches_type("sources"));
+        assert!(filter.matches("types", "Order"));
+
+        assert_eq!(filter.to_string(), "!source*");
+
+        assert!(ResourceFilter::new(&["types/"], &[]).is_err());
+        assert!(ResourceFilter::new(&["/Order"], &[]).is_err());
+    }
+
+    #[test]
+    fn test_from_args() {
+        let args = FilterArgs {
+            include: vec![],
+            exclude: vec!["*/Test*".to_string()],
+        };
+        let types = ["types".to_string(), "procedures".to_string()];
+        let filter = ResourceFilter::from_args(&args, &types, Some("Order*")).unwrap();
+        assert!(filter.matches("procedures", "Orders.total"));
+        assert!(!filter.matches("procedures", "Billing"));
+        assert!(!filter.matches("rules", "Order"));
+        assert!(!filter.matches("types", "TestOrder"));
+
+        let filter = ResourceFilter::from_args(&args, &[], Some("Order*")).unwrap();
+        assert!(filter.matches("rules", "OrderRule"));
+        assert!(filter.cli_types().is_none());
+    }
+
+    #[test]
+    fn test_paths_and_cli_types() {
+        let filter = ResourceFilter::new(&["procedures/Orders.*"], &[]).unwrap();
+        assert!(filter.matches_path(Path::new("procedures/Orders.total.json")));
+        assert!(filter.matches_path(Path::new("procedures/Orders.total.vail")));
+        assert!(!filter.matches_path(Path::new("types/Order.json")));
+        assert!(!filter.matches_path(Path::new("export.json")));
+        assert!(ResourceFilter::default().matches_path(Path::new("export.json")));
+
+        let plain = ResourceFilter::new(&["TypeA"], &["TypeB"]).unwrap();
+        assert_eq!(plain.cli_types(), Some((vec!["TypeA"], vec!["TypeB"])));
+        assert!(filter.cli_types().is_none());
+    }
+
+    #[test]
+    fn test_unmatched() {
+        let filter =
+            ResourceFilter::new(&["procedures/Order*", "rule*"], &["types/Audit", "topics"])
+                .unwrap();
+        let resources = [
+            ("procedures", Some("Orders.total")),
+            ("types", Some("Order")),
+            ("topics", None),
+        ];
+        assert_eq!(
+            filter.unmatched(&resources),
+            (vec!["rule*"], vec!["types/Audit"])
+        );
+        assert_eq!(
+            filter.unmatched(&[("rules", None), ("types", None)]),
+            (vec!["procedures/Order*"], vec!["topics"])
+        );
+    }
+
+    #[test]
+    fn test_stage_and_prune() {
+        let dir = TempDir::new().unwrap();
+        for rel in ["types/Order.json", "types/Audit.json", "rules/OnOrder.json"] {
+            let path = dir.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "{}").unwrap();
+        }
+        let filter = ResourceFilter::new(&["types"], &["*/Audit"]).unwrap();
+
+        let staged = filter.stage(dir.path()).unwrap().unwrap();
+        assert!(staged.path().join("types/Order.json").is_file());
+        assert!(!staged.path().join("types/Audit.json").exists());
+        assert!(!staged.path().join("rules").exists());
+        assert!(ResourceFilter::default()
+            .stage(dir.path())
+            .unwrap()
+            .is_none());
+
+        assert_eq!(filter.prune(dir.path()).unwrap(), 2);
+        assert!(dir.path().join("types/Order.json").is_file());
+        assert!(!dir.path().join("rules/OnOrder.json").exists());
+    }
+}
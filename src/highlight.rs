@@ -0,0 +1,234 @@
+//! Syntax highlighting for `vqx show`
+//!
+//! Colors JSON and VAIL code with [`style`], so highlighting follows
+//! `--color`, `NO_COLOR` and the theme. The tokenizers are deliberately
+//! simple: they only need to tell keywords, strings, numbers and comments
+//! apart, and every input character ends up in exactly one token.
+
+use crate::theme::style;
+use serde_json::Value;
+
+/// VAIL keywords (matched case-insensitively, as VAIL's SQL-like statements are)
+const VAIL_KEYWORDS: &[&str] = &[
+    "and",
+    "as",
+    "break",
+    "catch",
+    "continue",
+    "delete",
+    "else",
+    "event",
+    "execute",
+    "false",
+    "finally",
+    "for",
+    "from",
+    "if",
+    "import",
+    "in",
+    "insert",
+    "null",
+    "or",
+    "private",
+    "procedure",
+    "publish",
+    "return",
+    "rule",
+    "select",
+    "source",
+    "stateful",
+    "throw",
+    "to",
+    "topic",
+    "true",
+    "try",
+    "until",
+    "update",
+    "upsert",
+    "var",
+    "when",
+    "where",
+    "while",
+    "with",
+];
+
+/// Kind of a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Keyword,
+    Key,
+    String,
+    Number,
+    Comment,
+    Plain,
+}
+
+/// Pretty-printed JSON with colored keys and values
+pub fn json(value: &Value) -> String {
+    let text = serde_json::to_string_pretty(value).unwrap_or_default();
+    paint(&tokenize_json(&text))
+}
+
+/// VAIL code with colored keywords, strings, numbers and comments
+pub fn vail(code: &str) -> String {
+    paint(&tokenize_vail(code))
+}
+
+fn paint(tokens: &[(Token, &str)]) -> String {
+    tokens
+        .iter()
+        .map(|(kind, text)| match kind {
+            Token::Keyword => style(text).magenta().bold().to_string(),
+            Token::Key => style(text).cyan().to_string(),
+            Token::String => style(text).green().to_string(),
+            Token::Number => style(text).yellow().to_string(),
+            Token::Comment => style(text).dim().to_string(),
+            Token::Plain => text.to_string(),
+        })
+        .collect()
+}
+
+/// Split pretty-printed JSON into tokens
+pub fn tokenize_json(text: &str) -> Vec<(Token, &str)> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while let Some(c) = text[pos..].chars().next() {
+        let rest = &text[pos..];
+        let len = match c {
+            '"' => string_len(rest, '"'),
+            '-' | '0'..='9' => rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.')))
+                .unwrap_or(rest.len()),
+            c if c.is_ascii_alphabetic() => rest
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(rest.len()),
+            c => c.len_utf8(),
+        };
+        let tail = &rest[len..];
+        let kind = match c {
+            // A string followed by a colon is an object key
+            '"' if tail.trim_start().starts_with(':') => Token::Key,
+            '"' => Token::String,
+            '-' | '0'..='9' => Token::Number,
+            c if c.is_ascii_alphabetic() => Token::Keyword,
+            _ => Token::Plain,
+        };
+        push(&mut tokens, text, kind, pos, pos + len);
+        pos += len;
+    }
+    tokens
+}
+
+/// Split VAIL code into tokens
+pub fn tokenize_vail(code: &str) -> Vec<(Token, &str)> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while let Some(c) = code[pos..].chars().next() {
+        let rest = &code[pos..];
+        let (kind, len) = if rest.starts_with("//") {
+            (Token::Comment, rest.find('\n').unwrap_or(rest.len()))
+        } else if rest.starts_with("/*") {
+            (
+                Token::Comment,
+                rest.find("*/").map_or(rest.len(), |i| i + 2),
+            )
+        } else {
+            match c {
+                '"' | '\'' | '`' => (Token::String, string_len(rest, c)),
+                '0'..='9' => (
+                    Token::Number,
+                    rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                        .unwrap_or(rest.len()),
+                ),
+                c if c.is_alphabetic() || c == '_' => {
+                    let len = rest
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .unwrap_or(rest.len());
+                    let word = rest[..len].to_ascii_lowercase();
+                    let kind = if VAIL_KEYWORDS.contains(&word.as_str()) {
+                        Token::Keyword
+                    } else {
+                        Token::Plain
+                    };
+                    (kind, len)
+                }
+                c => (Token::Plain, c.len_utf8()),
+            }
+        };
+        push(&mut tokens, code, kind, pos, pos + len);
+        pos += len;
+    }
+    tokens
+}
+
+/// Length of the string literal at the start of `text`, quotes included
+fn string_len(text: &str, quote: char) -> usize {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            c if c == quote => return i + c.len_utf8(),
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Append the token at `start..end` of `source`, merging runs of plain text
+fn push<'a>(
+    tokens: &mut Vec<(Token, &'a str)>,
+    source: &'a str,
+    kind: Token,
+    start: usize,
+    end: usize,
+) {
+    if kind == Token::Plain {
+        if let Some((Token::Plain, last)) = tokens.last_mut() {
+            *last = &source[start - last.len()..end];
+            return;
+        }
+    }
+    tokens.push((kind, &source[start..end]));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds<'a>(tokens: &[(Token, &'a str)], kind: Token) -> Vec<&'a str> {
+        tokens
+            .iter()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, t)| *t)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_json() {
+        let text = "{\n  \"name\": \"a \\\"b\\\"\",\n  \"n\": -1.5e3,\n  \"ok\": true\n}";
+        let tokens = tokenize_json(text);
+        assert_eq!(tokens.iter().map(|(_, t)| *t).collect::<String>(), text);
+        assert_eq!(
+            kinds(&tokens, Token::Key),
+            vec!["\"name\"", "\"n\"", "\"ok\""]
+        );
+        assert_eq!(kinds(&tokens, Token::String), vec!["\"a \\\"b\\\"\""]);
+        assert_eq!(kinds(&tokens, Token::Number), vec!["-1.5e3"]);
+        assert_eq!(kinds(&tokens, Token::Keyword), vec!["true"]);
+    }
+
+    #[test]
+    fn test_tokenize_vail() {
+        let code = "PROCEDURE Foo(x Integer)\n// note\nvar s = 'it''s' /* c */\nSELECT * FROM Order WHERE n == 42";
+        let tokens = tokenize_vail(code);
+        assert_eq!(tokens.iter().map(|(_, t)| *t).collect::<String>(), code);
+        assert_eq!(
+            kinds(&tokens, Token::Keyword),
+            vec!["PROCEDURE", "var", "SELECT", "FROM", "WHERE"]
+        );
+        assert_eq!(kinds(&tokens, Token::Comment), vec!["// note", "/* c */"]);
+        assert_eq!(kinds(&tokens, Token::String), vec!["'it'", "'s'"]);
+        assert_eq!(kinds(&tokens, Token::Number), vec!["42"]);
+    }
+}
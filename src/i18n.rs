@@ -0,0 +1,461 @@
+//! Localized messages (vqx extension)
+//!
+//! User-facing text is looked up by key in a message catalog per language,
+//! starting with English (`en`) and Japanese (`ja`). The language is
+//! `[output] language` in config.toml; with `auto` (the default) it follows
+//! `LC_ALL`, `LC_MESSAGES` or `LANG`, so `LANG=ja_JP.UTF-8` selects
+//! Japanese.
+//!
+//! Messages take `{name}` placeholders filled by [`tf`]. A key missing from
+//! a catalog falls back to English. Machine-readable output (reports, error
+//! codes) is never localized.
+
+use crate::config::Language;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+static LANGUAGE: OnceLock<Language> = OnceLock::new();
+
+/// Pick the language from config (`auto` detects it from the environment)
+pub fn configure(configured: Language) {
+    let _ = LANGUAGE.set(match configured {
+        Language::Auto => detect(),
+        language => language,
+    });
+}
+
+/// Language of messages; detected from the environment until configured
+pub fn language() -> Language {
+    *LANGUAGE.get_or_init(detect)
+}
+
+fn detect() -> Language {
+    // Tests compare English output whatever the developer's locale
+    if cfg!(test) {
+        return Language::En;
+    }
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty());
+    from_locale(locale.as_deref())
+}
+
+/// Language of a POSIX locale such as `ja_JP.UTF-8`
+fn from_locale(locale: Option<&str>) -> Language {
+    match locale {
+        Some(locale) if locale.starts_with("ja") => Language::Ja,
+        _ => Language::En,
+    }
+}
+
+/// Message for `key` in the current language
+pub fn t(key: &'static str) -> &'static str {
+    lookup(language(), key)
+}
+
+/// Message for `key` with its `{name}` placeholders filled in
+pub fn tf(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    fill(t(key), args)
+}
+
+/// Field label padded for aligned `Label: value` lines
+pub fn label(key: &'static str) -> String {
+    // Japanese labels are double-width
+    let width = match language() {
+        Language::Ja => 13,
+        Language::En | Language::Auto => 10,
+    };
+    console::pad_str(
+        &format!("{}:", t(key)),
+        width,
+        console::Alignment::Left,
+        None,
+    )
+    .into_owned()
+}
+
+fn lookup(language: Language, key: &'static str) -> &'static str {
+    let find = |catalog: &[(&'static str, &'static str)]| {
+        catalog.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+    };
+    let localized = match language {
+        Language::Ja => find(JA),
+        Language::En | Language::Auto => None,
+    };
+    localized.or_else(|| find(EN)).unwrap_or(key)
+}
+
+fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = template.to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    out
+}
+
+/// Translated message for an error code, see [`crate::error::VqxError`]
+pub(crate) fn error_message(code: &str, args: &[(&str, &dyn Display)]) -> Option<String> {
+    error_message_in(language(), code, args)
+}
+
+/// [`error_message`] in `language`
+pub(crate) fn error_message_in(
+    language: Language,
+    code: &str,
+    args: &[(&str, &dyn Display)],
+) -> Option<String> {
+    let catalog = match language {
+        Language::Ja => JA_ERRORS,
+        Language::En | Language::Auto => return None,
+    };
+    catalog
+        .iter()
+        .find(|(k, _)| *k == code)
+        .map(|(_, template)| fill(template, args))
+}
+
+// =============================================================================
+// en
+// =============================================================================
+
+const EN: &[(&str, &str)] = &[
+    ("error", "Error"),
+    (
+        "error.operation",
+        "Operation ID: {id} (attach `vqx triage --operation {id}` to a support ticket)",
+    ),
+    ("enabled", "enabled"),
+    ("label.profile", "Profile"),
+    ("label.server", "Server"),
+    ("label.type", "Type"),
+    ("label.directory", "Directory"),
+    ("label.files", "Files"),
+    ("label.chunk", "Chunk"),
+    ("label.normalize", "Normalize"),
+    ("label.masking", "Masking"),
+    ("label.hidden", "Hidden"),
+    ("label.removed", "Removed"),
+    ("label.source", "Source"),
+    ("label.target", "Target"),
+    ("label.filter", "Filter"),
+    ("label.mode", "Mode"),
+    ("label.ignore", "Ignore"),
+    ("hidden.not_imported", "{dir}/ is not imported"),
+    // export
+    ("export.title", "Export"),
+    ("export.progress", "Exporting from Vantiq..."),
+    ("export.failed", "Export failed with exit code {code}"),
+    ("export.exported", "Exported {count} files to {dir}"),
+    ("export.normalizing", "Normalizing..."),
+    ("export.normalizing_progress", "Normalizing JSON files..."),
+    ("export.normalized", "Normalized {count} files"),
+    (
+        "export.normalize_errors",
+        "{count} files had errors during normalization",
+    ),
+    ("export.complete", "Export complete"),
+    // import
+    ("import.title", "Import"),
+    (
+        "import.warning",
+        "⚠  Warning: Import may overwrite existing resources!",
+    ),
+    (
+        "import.confirm",
+        "Import ~{count} files to {url} ({profile})?",
+    ),
+    ("import.progress", "Importing to Vantiq..."),
+    ("import.failed", "Import failed with exit code {code}"),
+    ("import.complete", "Import complete"),
+    // sync
+    ("sync.pull.title", "Sync Pull"),
+    (
+        "sync.pull.not_empty",
+        "⚠  Directory already contains files. They may be overwritten.",
+    ),
+    ("sync.pull.confirm", "Continue with sync pull?"),
+    ("sync.pull.progress", "Pulling from Vantiq..."),
+    ("sync.pull.failed", "Sync pull failed with exit code {code}"),
+    ("sync.pull.complete", "Sync pull complete"),
+    ("sync.pull.removed", "{count} stale file(s)"),
+    ("sync.push.title", "Sync Push"),
+    ("sync.push.comparing", "Comparing changes..."),
+    ("sync.push.changes", "Changes to push:"),
+    (
+        "sync.push.summary",
+        "{added} added, {removed} removed, {modified} modified",
+    ),
+    ("sync.push.dry_run", "Dry run - no changes made"),
+    ("sync.push.nothing", "Nothing to push"),
+    (
+        "sync.push.warning",
+        "⚠  Warning: This will modify resources on the server!",
+    ),
+    ("sync.push.confirm", "Push changes to {url} ({profile})?"),
+    ("sync.push.progress", "Pushing to Vantiq..."),
+    ("sync.push.failed", "Sync push failed with exit code {code}"),
+    ("sync.push.complete", "Sync push complete"),
+    // diff
+    ("diff.title", "Diff"),
+    ("diff.code_only", "code only (procedures, rules)"),
+    ("diff.rules", "{count} rule(s)"),
+    ("diff.no_differences", "No differences found"),
+    ("diff.found", "Found {count} change(s)"),
+    ("diff.added", "{count} added:"),
+    ("diff.removed", "{count} removed:"),
+    ("diff.modified", "{count} modified:"),
+    ("diff.no_breaking", "No breaking changes"),
+    ("diff.issues", "{count} compatibility issue(s):"),
+    ("diff.errors", "{count} error(s):"),
+];
+
+// =============================================================================
+// ja
+// =============================================================================
+
+const JA: &[(&str, &str)] = &[
+    ("error", "エラー"),
+    (
+        "error.operation",
+        "操作 ID: {id}（サポートへの問い合わせには `vqx triage --operation {id}` のバンドルを添付してください）",
+    ),
+    ("enabled", "有効"),
+    ("label.profile", "プロファイル"),
+    ("label.server", "サーバー"),
+    ("label.type", "種類"),
+    ("label.directory", "ディレクトリ"),
+    ("label.files", "ファイル"),
+    ("label.chunk", "チャンク"),
+    ("label.normalize", "正規化"),
+    ("label.masking", "マスキング"),
+    ("label.hidden", "非表示"),
+    ("label.removed", "削除"),
+    ("label.source", "比較元"),
+    ("label.target", "比較先"),
+    ("label.filter", "絞り込み"),
+    ("label.mode", "モード"),
+    ("label.ignore", "無視"),
+    ("hidden.not_imported", "{dir}/ はインポートされません"),
+    // export
+    ("export.title", "エクスポート"),
+    ("export.progress", "Vantiq からエクスポート中..."),
+    (
+        "export.failed",
+        "エクスポートが終了コード {code} で失敗しました",
+    ),
+    (
+        "export.exported",
+        "{count} 個のファイルを {dir} にエクスポートしました",
+    ),
+    ("export.normalizing", "正規化中..."),
+    ("export.normalizing_progress", "JSON ファイルを正規化中..."),
+    ("export.normalized", "{count} 個のファイルを正規化しました"),
+    (
+        "export.normalize_errors",
+        "{count} 個のファイルで正規化中にエラーが発生しました",
+    ),
+    ("export.complete", "エクスポート完了"),
+    // import
+    ("import.title", "インポート"),
+    (
+        "import.warning",
+        "⚠  警告: インポートは既存のリソースを上書きする可能性があります",
+    ),
+    (
+        "import.confirm",
+        "約 {count} 個のファイルを {url} ({profile}) にインポートしますか?",
+    ),
+    ("import.progress", "Vantiq にインポート中..."),
+    (
+        "import.failed",
+        "インポートが終了コード {code} で失敗しました",
+    ),
+    ("import.complete", "インポート完了"),
+    // sync
+    ("sync.pull.title", "同期 (pull)"),
+    (
+        "sync.pull.not_empty",
+        "⚠  ディレクトリにはすでにファイルがあります。上書きされる可能性があります",
+    ),
+    ("sync.pull.confirm", "sync pull を続行しますか?"),
+    ("sync.pull.progress", "Vantiq から取得中..."),
+    (
+        "sync.pull.failed",
+        "sync pull が終了コード {code} で失敗しました",
+    ),
+    ("sync.pull.complete", "sync pull 完了"),
+    ("sync.pull.removed", "古いファイル {count} 個"),
+    ("sync.push.title", "同期 (push)"),
+    ("sync.push.comparing", "変更を比較中..."),
+    ("sync.push.changes", "プッシュする変更:"),
+    (
+        "sync.push.summary",
+        "追加 {added}、削除 {removed}、変更 {modified}",
+    ),
+    ("sync.push.dry_run", "ドライラン - 変更は行っていません"),
+    ("sync.push.nothing", "プッシュする変更はありません"),
+    (
+        "sync.push.warning",
+        "⚠  警告: サーバー上のリソースを変更します",
+    ),
+    (
+        "sync.push.confirm",
+        "{url} ({profile}) に変更をプッシュしますか?",
+    ),
+    ("sync.push.progress", "Vantiq にプッシュ中..."),
+    (
+        "sync.push.failed",
+        "sync push が終了コード {code} で失敗しました",
+    ),
+    ("sync.push.complete", "sync push 完了"),
+    // diff
+    ("diff.title", "差分"),
+    ("diff.code_only", "コードのみ (procedures, rules)"),
+    ("diff.rules", "{count} 個のルール"),
+    ("diff.no_differences", "差分はありません"),
+    ("diff.found", "{count} 件の変更があります"),
+    ("diff.added", "追加 {count} 件:"),
+    ("diff.removed", "削除 {count} 件:"),
+    ("diff.modified", "変更 {count} 件:"),
+    ("diff.no_breaking", "互換性を壊す変更はありません"),
+    ("diff.issues", "互換性の問題 {count} 件:"),
+    ("diff.errors", "エラー {count} 件:"),
+];
+
+/// Japanese error messages by error code, with the fields of the variant
+const JA_ERRORS: &[(&str, &str)] = &[
+    (
+        "java_not_found",
+        "Java がインストールされていないか、PATH に見つかりません。Vantiq CLI には Java 11 が必要です。",
+    ),
+    (
+        "java_version_unsupported",
+        "Java {found} はサポートされていません。Vantiq CLI には Java 11 以降が必要です。",
+    ),
+    ("cli_not_found", "Vantiq CLI の実行ファイルが見つかりません: {path}"),
+    ("cli_not_executable", "Vantiq CLI を実行できません: {path}"),
+    ("profile_not_found", "プロファイル '{name}' が見つかりません"),
+    ("credential_not_found", "認証情報 '{name}' が見つかりません"),
+    (
+        "stale_credential",
+        "プロファイル '{profile}' の認証情報は {age_days} 日前のものです（上限: {max_days} 日）。破壊的な操作の前に更新してください。",
+    ),
+    ("profile_file_not_found", "プロファイルファイルが見つかりません: {path}"),
+    ("profile_invalid", "プロファイルの設定が不正です: {message}"),
+    (
+        "template_variables_missing",
+        "プロファイル '{profile}' にテンプレート変数の値がありません: {names}",
+    ),
+    (
+        "namespace_with_token",
+        "アクセストークンでは namespace オプションを使えません。ユーザー名とパスワードを使ってください。",
+    ),
+    (
+        "cli_execution_failed",
+        "CLI コマンドが終了コード {code} で失敗しました: {message}",
+    ),
+    ("cli_timeout", "CLI コマンドが {seconds} 秒でタイムアウトしました"),
+    ("cli_spawn_failed", "CLI プロセスを起動できませんでした: {message}"),
+    (
+        "cli_stalled",
+        "CLI コマンドが {seconds} 秒間出力しなかったため中断しました",
+    ),
+    ("authentication_failed", "認証に失敗しました: {message}"),
+    ("rest_request_failed", "REST リクエストに失敗しました: {message}"),
+    (
+        "not_confirmed",
+        "破壊的な操作 '{operation}' には明示的な確認が必要です",
+    ),
+    (
+        "backup_failed",
+        "破壊的な操作の前のバックアップに失敗しました: {message}",
+    ),
+    (
+        "lock_held",
+        "{target} は {holder} がロックしています。--wait で待つか --force-lock でロックを解除してください",
+    ),
+    ("cancelled", "{operation} はキャンセルされました"),
+    (
+        "profile_protected",
+        "プロファイル '{profile}' は読み取り専用です: {operation} は許可されていません",
+    ),
+    (
+        "resources_blocked",
+        "{count} 個のリソースが [import] のブロックリストに一致します: {names}",
+    ),
+    (
+        "policy_denied",
+        "プロファイル '{profile}' のポリシーは {operation} を許可していません: {reason}",
+    ),
+    (
+        "input_required",
+        "{operation} には入力が必要ですが、標準入力が端末でないか --no-input が指定されています。{alternative}",
+    ),
+    (
+        "offline",
+        "{operation} にはサーバーが必要ですが、--offline が指定されています",
+    ),
+    (
+        "approval_timeout",
+        "プラン {hash} は {seconds} 秒以内に承認されませんでした",
+    ),
+    ("approval_rejected", "承認が拒否されました: {message}"),
+    ("file_read_failed", "ファイルを読み込めませんでした: {path}"),
+    ("file_write_failed", "ファイルに書き込めませんでした: {path}"),
+    ("invalid_json", "不正な JSON: {message}"),
+    ("invalid_toml", "不正な TOML 設定: {message}"),
+    (
+        "invalid_schedule",
+        "不正な cron 式 '{expression}': {message}",
+    ),
+    ("invalid_config_key", "不正な設定キー '{key}': {message}"),
+    (
+        "secret_storage_failed",
+        "セキュアストレージにアクセスできませんでした: {message}",
+    ),
+    (
+        "encryption_failed",
+        "認証情報の暗号化・復号に失敗しました: {message}",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn placeholders(text: &str) -> Vec<String> {
+        let re = Regex::new(r"\{(\w+)\}").unwrap();
+        let mut names: Vec<String> = re.captures_iter(text).map(|c| c[1].to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_catalogs_match() {
+        for (key, text) in JA {
+            let en = EN.iter().find(|(k, _)| k == key);
+            assert!(en.is_some(), "ja key '{}' missing in en", key);
+            assert_eq!(placeholders(text), placeholders(en.unwrap().1), "{}", key);
+        }
+        assert_eq!(JA.len(), EN.len());
+    }
+
+    #[test]
+    fn test_lookup_and_fill() {
+        assert_eq!(lookup(Language::Ja, "export.complete"), "エクスポート完了");
+        assert_eq!(lookup(Language::En, "export.complete"), "Export complete");
+        assert_eq!(lookup(Language::Ja, "no.such.key"), "no.such.key");
+        assert_eq!(
+            fill(
+                lookup(Language::En, "export.exported"),
+                &[("count", &3), ("dir", &"out")]
+            ),
+            "Exported 3 files to out"
+        );
+        assert_eq!(from_locale(Some("ja_JP.UTF-8")), Language::Ja);
+        assert_eq!(from_locale(Some("C")), Language::En);
+        assert_eq!(from_locale(None), Language::En);
+    }
+}
@@ -0,0 +1,196 @@
+//! Diff ignore rules
+//!
+//! Fields that differ by design between environments (server URLs,
+//! credential references, schedule offsets) would show up as modifications
+//! in every diff. Ignore rules remove them from both sides before resources
+//! are compared. A rule is either:
+//! - a glob over `<resource type>/<name>.<dotted field path>`, such as
+//!   `sources/*.config.password`; arrays along the path are matched element
+//!   by element, as in `[masking]`
+//! - a resource glob and a JSON pointer glob separated by whitespace, such as
+//!   `sources/* /config/password`, for keys that contain dots or for single
+//!   array elements
+//!
+//! `*` matches any run of characters, including `.` and `/`. Rules come from
+//! `--ignore-path` and the `[diff] ignore` config list.
+
+use crate::commands::list::glob_to_regex;
+use crate::error::Result;
+use regex::Regex;
+use serde_json::Value;
+
+/// One ignore rule
+#[derive(Debug)]
+enum Rule {
+    /// Matches `<resource id>.<dotted path>`
+    Dotted(Regex),
+    /// Matches the resource id, then the JSON pointer of a field
+    Pointer { resource: Regex, pointer: Regex },
+}
+
+/// Compiled ignore rules
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    /// Compile rules
+    pub fn parse<S: AsRef<str>>(patterns: &[S]) -> Result<Self> {
+        let rules = patterns
+            .iter()
+            .map(|p| p.as_ref().trim())
+            .filter(|p| !p.is_empty())
+            .map(|p| match p.split_once(char::is_whitespace) {
+                Some((resource, pointer)) => Ok(Rule::Pointer {
+                    resource: glob_to_regex(resource)?,
+                    pointer: glob_to_regex(pointer.trim())?,
+                }),
+                None => Ok(Rule::Dotted(glob_to_regex(p)?)),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Remove the ignored fields of a resource definition
+    ///
+    /// Returns whether anything was removed.
+    pub fn strip(&self, resource: &str, value: &mut Value) -> bool {
+        let pointer_rules: Vec<&Regex> = self
+            .rules
+            .iter()
+            .filter_map(|r| match r {
+                Rule::Pointer {
+                    resource: re,
+                    pointer,
+                } if re.is_match(resource) => Some(pointer),
+                _ => None,
+            })
+            .collect();
+        let dotted_rules: Vec<&Regex> = self
+            .rules
+            .iter()
+            .filter_map(|r| match r {
+                Rule::Dotted(re) => Some(re),
+                _ => None,
+            })
+            .collect();
+        if pointer_rules.is_empty() && dotted_rules.is_empty() {
+            return false;
+        }
+
+        let ignored = |pointer: &str, dotted: &str| {
+            pointer_rules.iter().any(|re| re.is_match(pointer))
+                || dotted_rules.iter().any(|re| re.is_match(dotted))
+        };
+        strip_value(value, "", resource, &ignored)
+    }
+}
+
+/// Remove matching fields below `value`
+///
+/// `pointer` is the JSON pointer of `value`, `dotted` its dotted path with
+/// the resource id in front.
+fn strip_value(
+    value: &mut Value,
+    pointer: &str,
+    dotted: &str,
+    ignored: &dyn Fn(&str, &str) -> bool,
+) -> bool {
+    let mut removed = false;
+    match value {
+        Value::Object(map) => {
+            let keys: Vec<String> = map.keys().cloned().collect();
+            for key in keys {
+                let child_pointer =
+                    format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+                let child_dotted = format!("{}.{}", dotted, key);
+                if ignored(&child_pointer, &child_dotted) {
+                    map.remove(&key);
+                    removed = true;
+                } else if let Some(child) = map.get_mut(&key) {
+                    removed |= strip_value(child, &child_pointer, &child_dotted, ignored);
+                }
+            }
+        }
+        Value::Array(items) => {
+            let mut index = 0;
+            items.retain_mut(|item| {
+                let child_pointer = format!("{}/{}", pointer, index);
+                index += 1;
+                if ignored(&child_pointer, dotted) {
+                    removed = true;
+                    return false;
+                }
+                // Array elements share the dotted path of the array
+                removed |= strip_value(item, &child_pointer, dotted, ignored);
+                true
+            });
+        }
+        _ => {}
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_dotted_rules() {
+        let rules = IgnoreRules::parse(&["sources/*.config.password", "*.servers.url"]).unwrap();
+        let mut source = json!({
+            "name": "Crm",
+            "config": {"password": "@secrets(crm)", "pollingInterval": 60},
+            "servers": [{"url": "https://a", "port": 1}, {"url": "https://b"}]
+        });
+        assert!(rules.strip("sources/Crm", &mut source));
+        assert_eq!(
+            source,
+            json!({
+                "name": "Crm",
+                "config": {"pollingInterval": 60},
+                "servers": [{"port": 1}, {}]
+            })
+        );
+
+        // Other resource types are not affected by the sources rule
+        let mut topic = json!({"config": {"password": "x"}});
+        assert!(!rules.strip("topics/Crm", &mut topic));
+    }
+
+    #[test]
+    fn test_pointer_rules() {
+        let rules = IgnoreRules::parse(&[
+            "scheduledevents/* /interval",
+            "sources/Crm /config/headers/x.api-key",
+            "sources/Crm /tags/0",
+        ])
+        .unwrap();
+        assert_eq!(rules.len(), 3);
+
+        let mut event = json!({"name": "Nightly", "interval": "1 day"});
+        assert!(rules.strip("scheduledevents/Nightly", &mut event));
+        assert_eq!(event, json!({"name": "Nightly"}));
+
+        let mut source = json!({
+            "config": {"headers": {"x.api-key": "k", "accept": "json"}},
+            "tags": ["dev", "crm"]
+        });
+        assert!(rules.strip("sources/Crm", &mut source));
+        assert_eq!(
+            source,
+            json!({"config": {"headers": {"accept": "json"}}, "tags": ["crm"]})
+        );
+        assert!(!rules.strip("sources/Other", &mut json!({"tags": ["dev"]})));
+    }
+}
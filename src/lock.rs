@@ -0,0 +1,419 @@
+//! Advisory operation locks
+//!
+//! Commands that write to a namespace (import, sync push, promote,
+//! safe-delete) hold a lock per server URL and namespace in
+//! `<config dir>/locks/`, so two vqx invocations cannot interleave their
+//! changes, even through different profiles for the same namespace. The
+//! lock is advisory: it only coordinates vqx processes.
+//!
+//! The lock is an OS file lock on the lock file, which also records who
+//! holds it. The OS releases it when the holder exits, even after a crash,
+//! so there are no stale locks to clean up. `--force-lock` replaces the lock
+//! file instead, leaving the old holder locking a file nobody else opens.
+
+use crate::cli::LockArgs;
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Subdirectory of the config directory holding lock files
+pub const LOCK_DIR: &str = "locks";
+
+/// Interval between attempts while waiting for a lock
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Contents of a lock file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    /// vqx command holding the lock (e.g. "import")
+    pub command: String,
+    pub profile: String,
+    #[serde(default)]
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    pub acquired_at: DateTime<Utc>,
+}
+
+impl LockInfo {
+    fn describe(&self) -> String {
+        format!(
+            "'{}' on profile '{}' (pid {}) since {}",
+            self.command,
+            self.profile,
+            self.pid,
+            self.acquired_at.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+
+    /// Placeholder for a holder that has not written the lock file yet
+    fn unknown() -> Self {
+        Self {
+            pid: 0,
+            command: "unknown".to_string(),
+            profile: String::new(),
+            url: String::new(),
+            namespace: None,
+            acquired_at: Utc::now(),
+        }
+    }
+}
+
+/// A held lock; the OS releases it when the file is closed on drop
+#[derive(Debug)]
+pub struct OperationLock {
+    path: PathBuf,
+    file: File,
+}
+
+/// Outcome of one attempt to take the lock
+enum Attempt {
+    Acquired(File),
+    Held(LockInfo),
+    /// The lock file was replaced while it was being locked
+    Replaced,
+}
+
+impl OperationLock {
+    /// Acquire the lock for a namespace on the server at `url` in the default location
+    ///
+    /// `profile` is only recorded for other invocations to report.
+    pub async fn acquire(
+        command: &str,
+        profile: &str,
+        url: &str,
+        namespace: Option<&str>,
+        args: &LockArgs,
+    ) -> Result<Self> {
+        let dir = Config::config_dir()?.join(LOCK_DIR);
+        Self::acquire_in(&dir, command, profile, url, namespace, args).await
+    }
+
+    /// Acquire the lock in `dir`, waiting or breaking it as `args` allow
+    pub async fn acquire_in(
+        dir: &Path,
+        command: &str,
+        profile: &str,
+        url: &str,
+        namespace: Option<&str>,
+        args: &LockArgs,
+    ) -> Result<Self> {
+        fs::create_dir_all(dir).map_err(|_| VqxError::FileWriteFailed {
+            path: dir.display().to_string(),
+        })?;
+        let url = normalize_url(url);
+        let path = dir.join(lock_file_name(&url, namespace));
+        let info = LockInfo {
+            pid: std::process::id(),
+            command: command.to_string(),
+            profile: profile.to_string(),
+            url: url.clone(),
+            namespace: namespace.map(str::to_string),
+            acquired_at: Utc::now(),
+        };
+
+        // None: fail immediately; Some(None): wait indefinitely
+        let deadline = args
+            .wait
+            .map(|secs| secs.map(|s| Instant::now() + Duration::from_secs(s)));
+        let mut force = args.force_lock;
+        let mut announced = false;
+
+        loop {
+            let holder = match try_lock(&path, &info)? {
+                Attempt::Acquired(file) => return Ok(Self { path, file }),
+                Attempt::Replaced => continue,
+                Attempt::Held(holder) => holder,
+            };
+
+            if force {
+                warn!(holder = %holder.describe(), "Breaking operation lock (--force-lock)");
+                fs::remove_file(&path).map_err(|_| VqxError::FileWriteFailed {
+                    path: path.display().to_string(),
+                })?;
+                force = false;
+                continue;
+            }
+
+            let expired = match deadline {
+                None => true,
+                Some(None) => false,
+                Some(Some(deadline)) => Instant::now() >= deadline,
+            };
+            if expired {
+                return Err(VqxError::LockHeld {
+                    target: lock_target(&url, namespace),
+                    holder: holder.describe(),
+                });
+            }
+
+            if !announced {
+                eprintln!(
+                    "Waiting for {} held by {}...",
+                    lock_target(&url, namespace),
+                    holder.describe()
+                );
+                announced = true;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for OperationLock {
+    fn drop(&mut self) {
+        // Clear the holder unless the lock was broken and the file replaced
+        // (--force-lock); closing the file releases the lock itself
+        if is_same_file(&self.file, &self.path) {
+            if let Err(e) = self.file.set_len(0) {
+                debug!(path = %self.path.display(), error = %e, "Could not clear lock file");
+            }
+        }
+    }
+}
+
+/// Take the OS lock on the lock file at `path` and record `info` in it
+fn try_lock(path: &Path, info: &LockInfo) -> Result<Attempt> {
+    let write_failed = || VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    };
+    let mut file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|_| write_failed())?;
+
+    match file.try_lock() {
+        Ok(()) => {}
+        // Not written yet (or being rewritten) by the holder
+        Err(TryLockError::WouldBlock) => {
+            return Ok(Attempt::Held(
+                read_lock(path).unwrap_or_else(LockInfo::unknown),
+            ))
+        }
+        Err(TryLockError::Error(_)) => return Err(write_failed()),
+    }
+
+    // --force-lock elsewhere may have replaced the file since it was opened
+    if !is_same_file(&file, path) {
+        return Ok(Attempt::Replaced);
+    }
+    file.set_len(0).map_err(|_| write_failed())?;
+    file.write_all(serde_json::to_string_pretty(info)?.as_bytes())
+        .map_err(|_| write_failed())?;
+    Ok(Attempt::Acquired(file))
+}
+
+/// Whether `path` still names the open `file`
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// Whether `path` still names the open `file`
+///
+/// Windows has no stable file identity in std; only the path's existence
+/// is checked.
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+fn read_lock(path: &Path) -> Option<LockInfo> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Server URL as a lock key: without a trailing slash, case-insensitive
+fn normalize_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+/// Human-readable name of what is locked
+fn lock_target(url: &str, namespace: Option<&str>) -> String {
+    match namespace {
+        Some(ns) => format!("namespace '{}' on {}", ns, url),
+        None => format!("the default namespace on {}", url),
+    }
+}
+
+/// Lock file name for a server and namespace, e.g. `dev.vantiq.com@orders.lock`
+fn lock_file_name(url: &str, namespace: Option<&str>) -> String {
+    let host = url.split_once("://").map_or(url, |(_, rest)| rest);
+    format!("{}.lock", target_key(host, namespace))
+}
+
+/// File-name-safe key for a profile and namespace, e.g. `prod@orders`
+pub(crate) fn target_key(profile: &str, namespace: Option<&str>) -> String {
+    let sanitize = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    };
+    match namespace {
+        Some(ns) => format!("{}@{}", sanitize(profile), sanitize(ns)),
+        None => sanitize(profile),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const URL: &str = "https://prod.vantiq.com";
+
+    fn lock_args(wait: Option<Option<u64>>, force_lock: bool) -> LockArgs {
+        LockArgs { wait, force_lock }
+    }
+
+    #[test]
+    fn test_lock_file_name() {
+        assert_eq!(
+            lock_file_name(&normalize_url("https://Prod.vantiq.com/"), Some("orders")),
+            "prod.vantiq.com@orders.lock"
+        );
+        assert_eq!(
+            lock_file_name("http://localhost:8080", None),
+            "localhost_8080.lock"
+        );
+        assert_eq!(lock_file_name("a/b", Some("c:d")), "a_b@c_d.lock");
+    }
+
+    #[tokio::test]
+    async fn test_lock_held_and_released() {
+        let dir = TempDir::new().unwrap();
+        let args = lock_args(None, false);
+
+        let lock = OperationLock::acquire_in(dir.path(), "import", "prod", URL, Some("ns"), &args)
+            .await
+            .unwrap();
+        assert_eq!(read_lock(&lock.path).unwrap().command, "import");
+
+        // Held by this process, also through another profile for the same namespace
+        let err =
+            OperationLock::acquire_in(dir.path(), "promote", "prod-admin", URL, Some("ns"), &args)
+                .await
+                .unwrap_err();
+        assert!(matches!(err, VqxError::LockHeld { .. }));
+        assert!(err.to_string().contains("'import' on profile 'prod'"));
+
+        // Other namespaces and servers are independent
+        OperationLock::acquire_in(dir.path(), "import", "prod", URL, Some("other"), &args)
+            .await
+            .unwrap();
+        OperationLock::acquire_in(
+            dir.path(),
+            "import",
+            "dev",
+            "https://dev.vantiq.com",
+            Some("ns"),
+            &args,
+        )
+        .await
+        .unwrap();
+
+        // A bounded wait gives up
+        let err = OperationLock::acquire_in(
+            dir.path(),
+            "promote",
+            "prod",
+            URL,
+            Some("ns"),
+            &lock_args(Some(Some(0)), false),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, VqxError::LockHeld { .. }));
+
+        let path = lock.path.clone();
+        drop(lock);
+        assert!(read_lock(&path).is_none());
+        OperationLock::acquire_in(dir.path(), "promote", "prod", URL, Some("ns"), &args)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_force_lock() {
+        let dir = TempDir::new().unwrap();
+        let held = OperationLock::acquire_in(
+            dir.path(),
+            "import",
+            "prod",
+            URL,
+            None,
+            &lock_args(None, false),
+        )
+        .await
+        .unwrap();
+
+        let forced = OperationLock::acquire_in(
+            dir.path(),
+            "import",
+            "prod",
+            URL,
+            None,
+            &lock_args(None, true),
+        )
+        .await
+        .unwrap();
+
+        // The broken lock does not clear the new holder
+        drop(held);
+        assert_eq!(read_lock(&forced.path).unwrap().pid, std::process::id());
+        assert!(OperationLock::acquire_in(
+            dir.path(),
+            "import",
+            "prod",
+            URL,
+            None,
+            &lock_args(None, false)
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_leftover_lock_file_is_free() {
+        let dir = TempDir::new().unwrap();
+        // Written by a process that crashed: nobody holds the OS lock
+        let leftover = LockInfo {
+            command: "import".to_string(),
+            ..LockInfo::unknown()
+        };
+        let path = dir.path().join(lock_file_name(URL, None));
+        fs::write(&path, serde_json::to_string(&leftover).unwrap()).unwrap();
+
+        let lock = OperationLock::acquire_in(
+            dir.path(),
+            "sync push",
+            "prod",
+            URL,
+            None,
+            &lock_args(None, false),
+        )
+        .await
+        .unwrap();
+        assert_eq!(read_lock(&lock.path).unwrap().command, "sync push");
+    }
+}
@@ -12,38 +12,142 @@
 //! ## Phase 1 Implementation
 //! - `doctor`: Check environment prerequisites
 //! - `profile`: Manage connection profiles
+//! - `credential`: Manage named credentials shared by profiles
+//! - `list`: Structured resource listings
+//! - `select`: Query records with inline qualifiers
+//! - `insert`/`upsert`: Bulk-load records in batches
 //! - `passthrough`: Direct CLI access
 //!
 //! ## Phase 2 Implementation
 //! - `export`: Export with JSON normalization
 //! - `import`: Import with safety confirmations
 
+mod approval;
+mod backend;
+mod blocklist;
+mod cache;
+mod chunking;
 mod cli;
 mod commands;
+mod compat;
 mod config;
+#[cfg(unix)]
+mod deps;
 mod error;
+mod exit;
+mod filter;
+mod highlight;
+mod i18n;
+mod ignore;
+mod lock;
+mod manifest;
+mod masking;
+mod metrics;
 mod normalizer;
+mod notify;
+mod offline;
+mod pager;
+mod patch;
+mod policy;
 mod profile;
+mod progress;
+mod project;
+mod prompt;
+mod render;
+mod report;
+#[cfg(feature = "rest-backend")]
+mod rest;
+mod schedule;
+mod secrets;
+mod secure_file;
+mod serve;
+mod snapshot;
+mod suites;
+mod summary;
+mod telemetry;
+mod template;
+mod testreport;
+mod theme;
+mod timing;
+mod trash;
 mod underlying;
+mod validation;
+mod vantiq_profile;
 
 use anyhow::Result;
 use clap::Parser;
-use cli::{Cli, Commands};
-use config::Config;
-use console::style;
-use tracing::info;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use cli::{
+    CiCommands, Cli, Commands, DeployCommand, DeployCommands, OutputFormat, RollbackArgs,
+    SyncCommands, TrashCommands,
+};
+use config::{Config, LogFormat, LogRotation, LoggingConfig};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use suites::SuiteStatus;
+use tracing::{info, info_span, Instrument, Subscriber};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    fmt::{self, MakeWriter},
+    prelude::*,
+    registry::LookupSpan,
+    EnvFilter, Layer,
+};
+
+/// Whether log lines (and so operation transcripts) are written to a file
+static LOGGING_TO_FILE: AtomicBool = AtomicBool::new(false);
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     // Parse CLI arguments
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    let operation_id = new_operation_id();
+
+    let exit_code = match run(&mut cli, &operation_id).await {
+        Ok(code) => code,
+        Err(err) => {
+            error::render_error(&err, cli.output);
+            if !cli.output.is_machine_readable() && LOGGING_TO_FILE.load(Ordering::Relaxed) {
+                eprintln!(
+                    "{}",
+                    theme::style(i18n::tf("error.operation", &[("id", &operation_id)])).dim()
+                );
+            }
+            exit::for_error(&err)
+        }
+    };
 
-    // Initialize logging
-    init_logging(&cli)?;
+    std::process::exit(exit_code);
+}
+
+/// ID of this invocation, tagged on every log line it writes
+///
+/// `vqx triage --operation <id>` bundles the log lines of a failed run.
+fn new_operation_id() -> String {
+    format!(
+        "{}-{:x}",
+        chrono::Local::now().format("%Y%m%dT%H%M%S"),
+        std::process::id()
+    )
+}
+
+/// Set up config and logging, then run the command; returns the exit code
+async fn run(cli: &mut Cli, operation_id: &str) -> Result<i32> {
+    // Project defaults (.vqx.toml) for what the command line leaves open
+    project::apply(cli)?;
+    if cli.porcelain {
+        cli.output = OutputFormat::Porcelain;
+    }
+    let cli = &*cli;
 
     // Load configuration
-    let config = load_config(&cli)?;
+    let config = load_config(cli)?;
+    i18n::configure(config.output.language);
+
+    // Initialize colors and logging
+    theme::configure(cli.color, &config.output);
+    init_logging(cli, &config)?;
 
     info!(
         cli_path = %config.cli_path,
@@ -51,176 +155,652 @@ async fn main() -> Result<()> {
         "Starting vqx"
     );
 
-    // Execute command
+    prompt::set_no_input(cli.no_input);
+    pager::configure(cli.no_pager, config.output.pager.as_deref());
+    progress::configure(cli.no_progress, config.output.progress);
+    offline::configure(cli.offline);
+    if config.warm_jvm {
+        underlying::share_classes(Config::config_dir()?.join(underlying::CLASS_ARCHIVE_FILE));
+    }
+    summary::configure(cli.summary);
+    policy::Policy::compile(&config.policy)?.install();
+
+    // Fail fast instead of waiting for network timeouts
+    if cli.command.needs_server() {
+        offline::check(cli.command.operation())?;
+    }
+
+    // Credential age policy
+    check_credentials(cli)?;
+
+    // Production guardrails
+    check_protection(cli, &config)?;
+
+    // Execute command, then report how it went
+    let started = Instant::now();
+    let mut counts = BTreeMap::new();
+    let span = info_span!("operation", id = %operation_id);
+    let result = execute(cli, &config, &mut counts)
+        .instrument(span.clone())
+        .await;
+    summary::set_counts(&counts);
+    summary::finish(!cli.output.is_machine_readable());
+    notify::send(
+        &config.notify,
+        &cli.command,
+        cli.profile.as_deref(),
+        &result,
+        started.elapsed(),
+        counts,
+    )
+    .instrument(span)
+    .await;
+    telemetry::record(&config.telemetry, &cli.command, &result, started.elapsed());
+    result
+}
+
+/// Run the command; commands that notify put their counts in `counts`
+async fn execute(cli: &Cli, config: &Config, counts: &mut BTreeMap<String, usize>) -> Result<i32> {
     let exit_code = match &cli.command {
         // Phase 1: Core utilities
-        Commands::Doctor(args) => {
-            let results = commands::doctor::run(args, &config).await?;
-            commands::doctor::display_results(&results, cli.verbose);
+        Commands::Init(args) => {
+            let success = commands::init::run(
+                args,
+                config,
+                cli.config.as_deref(),
+                cli.profile.as_deref(),
+                cli.output,
+            )
+            .await?;
+
+            exit::status(success)
+        }
 
-            if results.iter().all(|r| r.passed) {
-                0
+        Commands::Doctor(args) => {
+            let mut results = commands::doctor::run(args, config, cli.profile.as_deref()).await?;
+            if args.fix {
+                commands::doctor::fix(
+                    &mut results,
+                    args,
+                    config,
+                    !cli.output.is_machine_readable(),
+                )
+                .await?;
+            }
+            if cli.output.is_machine_readable() {
+                commands::doctor::print_report(&results, cli.output)?;
             } else {
-                1
+                commands::doctor::display_results(&results, cli.verbose);
             }
+
+            exit::status(results.iter().all(|r| r.passed))
         }
 
         Commands::Profile(cmd) => {
-            commands::profile::run(cmd, cli.output).await?;
-            0
+            commands::profile::run(cmd, config, cli.output).await?;
+            exit::SUCCESS
+        }
+
+        Commands::Credential(cmd) => {
+            commands::credential::run(cmd, cli.output).await?;
+            exit::SUCCESS
+        }
+
+        Commands::Login(args) => {
+            let result =
+                commands::login::login(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Logout(args) => {
+            let result = commands::login::logout(args, cli.profile.as_deref(), cli.output)?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Config(cmd) => {
+            let success = commands::config::run(cmd, config, cli.config.as_deref(), cli.output)?;
+
+            exit::status(success)
+        }
+
+        Commands::List(args) => {
+            let result = commands::list::run(
+                args,
+                config,
+                cli.profile.as_deref(),
+                cli.output,
+                cli.verbose,
+            )
+            .await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Select(args) => {
+            let result = commands::select::run(
+                args,
+                config,
+                cli.profile.as_deref(),
+                cli.output,
+                cli.verbose,
+            )
+            .await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Dump(args) => {
+            let result =
+                commands::dump::dump(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Load(args) => {
+            let result =
+                commands::dump::load(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Insert(args) | Commands::Upsert(args) => {
+            let mode = if matches!(cli.command, Commands::Insert(_)) {
+                commands::insert::WriteMode::Insert
+            } else {
+                commands::insert::WriteMode::Upsert
+            };
+            let result = commands::insert::run(
+                mode,
+                args,
+                config,
+                cli.profile.as_deref(),
+                cli.output,
+                cli.verbose,
+            )
+            .await?;
+
+            exit::status(result.success)
         }
 
         Commands::External(args) => {
-            // Direct CLI access: `vqx list types` -> `vantiq list types`
-            commands::external::run(&args, &config, cli.profile.as_deref(), cli.verbose).await?
+            // Direct CLI access: `vqx find procedures X` -> `vantiq find procedures X`
+            commands::external::run(args, config, cli.profile.as_deref(), cli.verbose).await?
         }
 
         // Phase 2: Export/Import
         Commands::Export(args) => {
             let result = commands::export::run(
                 args,
-                &config,
+                config,
                 cli.profile.as_deref(),
                 cli.output,
                 cli.verbose,
             )
             .await?;
 
-            if result.success {
-                0
-            } else {
-                1
-            }
+            counts.extend(
+                [
+                    ("files_exported", result.files_exported),
+                    ("files_normalized", result.files_normalized),
+                ]
+                .into_iter()
+                .filter_map(|(name, n)| Some((name.to_string(), n?))),
+            );
+            counts.insert("errors".to_string(), result.errors.len());
+
+            exit::status(result.success)
         }
 
         Commands::Import(args) => {
             let result = commands::import::run(
                 args,
-                &config,
+                config,
                 cli.profile.as_deref(),
                 cli.output,
                 cli.verbose,
             )
             .await?;
 
-            if result.success {
-                0
-            } else {
-                1
+            if let Some(n) = result.resources_imported {
+                counts.insert("resources_imported".to_string(), n);
             }
+            counts.insert("errors".to_string(), result.errors.len());
+
+            exit::status(result.success)
         }
 
         // Phase 3: Diff/Sync
         Commands::Diff(args) => {
-            let result = commands::diff::run(args, &config, cli.output, cli.verbose).await?;
+            let result = commands::diff::run(args, config, cli.output, cli.verbose).await?;
 
-            if result.success && !result.has_changes() {
-                0
-            } else if result.success {
-                // Changes found, but operation succeeded
-                0
+            if args.breaking && result.has_breaking_changes() {
+                exit::FAILURE
+            } else if result.success && cli.strict && result.has_changes() {
+                exit::CHANGES
             } else {
-                1
+                exit::status(result.success)
             }
         }
 
         Commands::Sync(cmd) => {
-            let result = commands::sync::run(
-                cmd,
-                &config,
-                cli.profile.as_deref(),
-                cli.output,
-                cli.verbose,
-            )
-            .await?;
+            let result =
+                commands::sync::run(cmd, config, cli.profile.as_deref(), cli.output, cli.verbose)
+                    .await?;
 
-            if result.success {
-                0
-            } else {
-                1
+            if let Some(n) = result.files_processed {
+                counts.insert("files_processed".to_string(), n);
+            }
+            if let Some(ref changes) = result.changes {
+                counts.insert("added".to_string(), changes.added);
+                counts.insert("removed".to_string(), changes.removed);
+                counts.insert("modified".to_string(), changes.modified);
             }
+            counts.insert("errors".to_string(), result.errors.len());
+
+            exit::status(result.success)
         }
 
         Commands::SafeDelete(args) => {
             let result = commands::safe_delete::run(
                 args,
-                &config,
+                config,
                 cli.profile.as_deref(),
                 cli.output,
                 cli.verbose,
             )
             .await?;
 
-            if result.success {
-                0
-            } else {
-                1
-            }
+            exit::status(result.success)
+        }
+
+        Commands::Trash(cmd) => {
+            let success =
+                commands::trash::run(cmd, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(success)
+        }
+
+        Commands::Rollback(args) => {
+            let success =
+                commands::rollback::run(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(success)
         }
 
         Commands::Promote(args) => {
             let result = commands::promote::run(
                 args,
-                &config,
+                config,
                 cli.profile.as_deref(),
                 cli.output,
                 cli.verbose,
             )
             .await?;
 
-            if result.success {
-                0
-            } else {
-                1
+            if let Some(suites) = result.test_result.as_ref().and_then(|t| t.suites.as_ref()) {
+                let failed = suites
+                    .iter()
+                    .filter(|s| matches!(s.status, SuiteStatus::Failed | SuiteStatus::Error))
+                    .count();
+                counts.insert("test_suites".to_string(), suites.len());
+                counts.insert("failed_suites".to_string(), failed);
             }
+
+            exit::status(result.success)
         }
 
         Commands::Run(cmd) => {
-            let result = commands::run::run(
-                cmd,
-                &config,
+            let result =
+                commands::run::run(cmd, config, cli.profile.as_deref(), cli.output, cli.verbose)
+                    .await?;
+
+            result.exit_code()
+        }
+
+        Commands::Ci(CiCommands::Sandbox(args)) => {
+            let result =
+                commands::ci::sandbox(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Deploy(DeployCommand {
+            command: Some(DeployCommands::Plan(args)),
+            ..
+        }) => {
+            let plan =
+                commands::deploy::plan(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(plan.success)
+        }
+
+        Commands::Deploy(DeployCommand { args, .. }) | Commands::Undeploy(args) => {
+            let action = if matches!(cli.command, Commands::Deploy(_)) {
+                commands::deploy::DeployAction::Deploy
+            } else {
+                commands::deploy::DeployAction::Undeploy
+            };
+            let result = commands::deploy::run(
+                action,
+                args,
+                config,
                 cli.profile.as_deref(),
                 cli.output,
                 cli.verbose,
             )
             .await?;
 
-            if result.success {
-                0
-            } else {
-                1
-            }
+            exit::status(result.success)
+        }
+
+        // Tooling
+        Commands::Schema(cmd) => {
+            commands::schema::run(cmd)?;
+            exit::SUCCESS
+        }
+
+        Commands::Docs(cmd) => {
+            commands::docs::run(cmd)?;
+            exit::SUCCESS
+        }
+
+        Commands::Explain(args) => {
+            commands::explain::run(args);
+            exit::SUCCESS
+        }
+
+        Commands::Serve(args) => {
+            let success =
+                commands::serve::run(args, config, cli.config.as_deref(), cli.output).await?;
+
+            exit::status(success)
+        }
+
+        Commands::Schedule(cmd) => {
+            let success =
+                commands::schedule::run(cmd, config, cli.config.as_deref(), cli.output).await?;
+
+            exit::status(success)
+        }
+
+        Commands::Telemetry(cmd) => {
+            let success = commands::telemetry::run(cmd, config, cli.config.as_deref(), cli.output)?;
+
+            exit::status(success)
+        }
+
+        Commands::Triage(args) => {
+            let result = commands::triage::run(args, config, cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Normalize(args) => {
+            let result = commands::normalize::run(args, config, cli.output)?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Validate(args) => {
+            let result = commands::validate::run(args, cli.output)?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Scan(args) => {
+            let result = commands::scan::run(args, cli.output)?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Grep(args) => {
+            let result =
+                commands::grep::run(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Stats(args) => {
+            let result =
+                commands::stats::run(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Show(args) => {
+            let result =
+                commands::show::run(args, config, cli.profile.as_deref(), cli.output).await?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Open(args) => {
+            let result = commands::open::run(args, config, cli.profile.as_deref(), cli.output)?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Patch(cmd) => {
+            let result = commands::patch::run(cmd, cli.output)?;
+
+            exit::status(result.success)
+        }
+
+        Commands::Template(cmd) => {
+            let success = commands::template::run(cmd, cli.profile.as_deref(), cli.output)?;
+
+            exit::status(success)
+        }
+
+        Commands::New(args) => {
+            commands::new::run(args, config, cli.output)?;
+            exit::SUCCESS
+        }
+
+        Commands::Rename(args) => {
+            commands::rename::run(args, cli.output)?;
+            exit::SUCCESS
+        }
+
+        Commands::Approve(args) => {
+            let success = commands::approve::run(args, config, cli.output)?;
+
+            exit::status(success)
         }
     };
 
-    std::process::exit(exit_code);
+    Ok(exit_code)
+}
+
+/// Warn about overdue credentials, refusing destructive commands when the
+/// profile requires it
+fn check_credentials(cli: &Cli) -> Result<()> {
+    match &cli.command {
+        Commands::Init(_)
+        | Commands::Doctor(_)
+        | Commands::Profile(_)
+        | Commands::Credential(_)
+        | Commands::Login(_)
+        | Commands::Logout(_)
+        | Commands::Config(_)
+        | Commands::Schema(_)
+        | Commands::Docs(_)
+        | Commands::Explain(_)
+        | Commands::Serve(_)
+        | Commands::Schedule(_)
+        | Commands::Telemetry(_)
+        | Commands::Triage(_)
+        | Commands::Normalize(_)
+        | Commands::Validate(_)
+        | Commands::Scan(_)
+        | Commands::Patch(_)
+        | Commands::Template(_)
+        | Commands::New(_)
+        | Commands::Rename(_)
+        | Commands::Approve(_)
+        | Commands::Open(_)
+        | Commands::Trash(TrashCommands::List | TrashCommands::Empty(_))
+        | Commands::Rollback(RollbackArgs { to: None, .. }) => {}
+        Commands::Promote(args) => {
+            commands::credential::check_credential_age(Some(&args.from), false)?;
+            commands::credential::check_credential_age(Some(&args.to), true)?;
+        }
+        Commands::Deploy(DeployCommand {
+            command: Some(DeployCommands::Plan(args)),
+            ..
+        }) => {
+            commands::credential::check_credential_age(cli.profile.as_deref(), false)?;
+            commands::credential::check_credential_age(Some(&args.to), false)?;
+        }
+        command => {
+            commands::credential::check_credential_age(
+                cli.profile.as_deref(),
+                command.is_destructive(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Enforce the `protection` of the profile a command changes
+fn check_protection(cli: &Cli, config: &Config) -> Result<()> {
+    if !cli.command.is_destructive() || cli.command.is_dry_run() {
+        return Ok(());
+    }
+    let profile = match &cli.command {
+        // Refused on protected profiles by the command itself, without a prompt first
+        Commands::Sync(SyncCommands::Watch(_)) => return Ok(()),
+        Commands::Promote(args) => Some(args.to.clone()),
+        Commands::Ci(CiCommands::Sandbox(args)) if args.sandbox_profile.is_some() => {
+            args.sandbox_profile.clone()
+        }
+        // A rollback goes to the profile of the snapshot by default
+        Commands::Rollback(RollbackArgs { to: Some(id), .. }) if cli.profile.is_none() => {
+            snapshot::SnapshotStore::new(config)
+                .find(id)
+                .ok()
+                .map(|s| s.profile)
+        }
+        _ => cli.profile.clone(),
+    };
+    commands::profile::check_protection(
+        profile.as_deref(),
+        &cli.command.operation(),
+        cli.confirm_profile.as_deref(),
+    )?;
+    Ok(())
 }
 
 /// Initialize logging based on CLI options and config
-fn init_logging(cli: &Cli) -> Result<()> {
+///
+/// With `logging.file` (or `--log-file`) set, events are also appended to
+/// that file (without colors, rotated per `logging.rotation`) so that
+/// retries and failures of past runs can be reviewed. With
+/// `logging.format = "json"` every event is one JSON object; console events
+/// then go to stderr so they never mix with `--output json` reports.
+fn init_logging(cli: &Cli, config: &Config) -> Result<()> {
+    let logging = &config.logging;
     let level = if cli.verbose {
         "debug"
     } else if cli.quiet {
         "error"
     } else {
-        "info"
+        logging.level.as_str()
     };
 
     let filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(format!("vqx={}", level)));
 
+    let console = match logging.format {
+        LogFormat::Text => log_layer(
+            LogFormat::Text,
+            false,
+            theme::colors_enabled(),
+            std::io::stdout,
+        ),
+        LogFormat::Json => log_layer(LogFormat::Json, logging.timestamps, false, std::io::stderr),
+    };
+
+    let file_layer = match logging.file {
+        Some(ref path) => {
+            LOGGING_TO_FILE.store(true, Ordering::Relaxed);
+            let appender = log_file_appender(Path::new(path), logging.rotation)?;
+            Some(log_layer(
+                logging.format,
+                logging.timestamps,
+                false,
+                appender,
+            ))
+        }
+        None => None,
+    };
+
     tracing_subscriber::registry()
-        .with(fmt::layer().with_target(false).without_time())
+        .with(console)
+        .with(file_layer)
         .with(filter)
         .init();
 
     Ok(())
 }
 
+/// A formatting layer for one log destination
+fn log_layer<S, W>(
+    format: LogFormat,
+    timestamps: bool,
+    colors: bool,
+    writer: W,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer()
+        .with_target(false)
+        .with_ansi(colors)
+        .with_writer(writer);
+    match (format, timestamps) {
+        (LogFormat::Text, true) => layer.boxed(),
+        (LogFormat::Text, false) => layer.without_time().boxed(),
+        (LogFormat::Json, true) => layer.json().boxed(),
+        (LogFormat::Json, false) => layer.json().without_time().boxed(),
+    }
+}
+
+/// Appender for the log file; rotated files get a date suffix
+fn log_file_appender(path: &Path, rotation: LogRotation) -> Result<RollingFileAppender> {
+    let write_failed = || error::VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = path.file_name().ok_or_else(write_failed)?;
+    let rotation = match rotation {
+        LogRotation::Never => Rotation::NEVER,
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+    };
+
+    let appender = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(prefix.to_string_lossy())
+        .build(dir)
+        .map_err(|_| write_failed())?;
+    Ok(appender)
+}
+
 /// Load configuration from file or defaults
 fn load_config(cli: &Cli) -> Result<Config> {
     let config = if let Some(ref path) = cli.config {
-        Config::load_from(path)?
+        match Config::load_from(path) {
+            // `vqx config validate/edit` report (and fix) a broken file
+            Err(_) if matches!(cli.command, Commands::Config(_)) => Config::default(),
+            result => result?,
+        }
     } else {
         Config::load().unwrap_or_default()
     };
@@ -235,5 +815,29 @@ fn load_config(cli: &Cli) -> Result<Config> {
         config
     };
 
+    // A timeout given on the command line applies to every command
+    let config = if let Some(seconds) = cli.timeout {
+        Config {
+            timeout_seconds: seconds,
+            timeouts: Default::default(),
+            ..config
+        }
+    } else {
+        config
+    };
+
+    // Override log file if specified on command line
+    let config = if let Some(ref log_file) = cli.log_file {
+        Config {
+            logging: LoggingConfig {
+                file: Some(log_file.display().to_string()),
+                ..config.logging
+            },
+            ..config
+        }
+    } else {
+        config
+    };
+
     Ok(config)
 }
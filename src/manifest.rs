@@ -0,0 +1,146 @@
+//! Export manifest
+//!
+//! `vqx-manifest.json` at the root of an export directory records content
+//! that vqx placed there outside the regular resource directories, so other
+//! commands and reviewers can tell it apart.
+//!
+//! Hidden resources (PDF: "export hidden") are written to [`HIDDEN_DIR`].
+//! That directory is not a resource type directory, so it is skipped by
+//! import, diff, sync and promote unless explicitly requested.
+//!
+//! While an export runs, and after it failed, the manifest also holds its
+//! [`ExportCheckpoint`], so `export --resume` can skip what is complete.
+
+use crate::error::{Result, VqxError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Manifest file name
+pub const MANIFEST_FILE: &str = "vqx-manifest.json";
+
+/// Subdirectory holding hidden resources
+pub const HIDDEN_DIR: &str = "_hidden";
+
+/// Contents of `vqx-manifest.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<HiddenResources>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<ExportCheckpoint>,
+}
+
+/// Record of a hidden resource export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HiddenResources {
+    /// Directory relative to the export root
+    pub directory: String,
+    pub exported_at: DateTime<Utc>,
+    pub resource_types: Vec<String>,
+    pub files: usize,
+}
+
+/// State of an export that has not finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportCheckpoint {
+    /// Export type and the options it was started with; a resumed export
+    /// must use the same ones
+    pub export_type: String,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+    /// Start of the first attempt
+    pub started_at: DateTime<Utc>,
+    pub attempts: u32,
+    /// Types (data exports) or resource types exported completely
+    pub completed: Vec<String>,
+    /// Type being exported when the last attempt failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_progress: Option<String>,
+    /// Chunk files written for `in_progress` before the failure
+    #[serde(default)]
+    pub chunks_written: usize,
+}
+
+impl Manifest {
+    /// Load the manifest of an export directory (empty if there is none)
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&path).map_err(|_| VqxError::FileReadFailed {
+            path: path.display().to_string(),
+        })?;
+        serde_json::from_str(&content).map_err(|e| VqxError::InvalidJson {
+            message: format!("{}: {}", path.display(), e),
+        })
+    }
+
+    /// Write the manifest to an export directory; an empty manifest is
+    /// removed
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        let path = dir.join(MANIFEST_FILE);
+        if self.hidden.is_none() && self.checkpoint.is_none() {
+            if path.exists() {
+                std::fs::remove_file(&path).map_err(|_| VqxError::FileWriteFailed {
+                    path: path.display().to_string(),
+                })?;
+            }
+            return Ok(());
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, content + "\n").map_err(|_| VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        })
+    }
+}
+
+/// Whether an export directory contains hidden resources
+pub fn has_hidden(dir: &Path) -> bool {
+    dir.join(HIDDEN_DIR).is_dir()
+}
+
+/// Whether a path relative to an export root lies in the hidden directory
+pub fn is_hidden_path(rel: &Path) -> bool {
+    rel.components()
+        .next()
+        .is_some_and(|c| c.as_os_str() == HIDDEN_DIR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(Manifest::load(dir.path()).unwrap().hidden.is_none());
+
+        let manifest = Manifest {
+            hidden: Some(HiddenResources {
+                directory: HIDDEN_DIR.to_string(),
+                exported_at: Utc::now(),
+                resource_types: vec!["types".to_string()],
+                files: 3,
+            }),
+            checkpoint: None,
+        };
+        manifest.save(dir.path()).unwrap();
+
+        let loaded = Manifest::load(dir.path()).unwrap();
+        assert_eq!(loaded.hidden.unwrap().files, 3);
+
+        Manifest::default().save(dir.path()).unwrap();
+        assert!(!dir.path().join(MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn test_is_hidden_path() {
+        assert!(is_hidden_path(Path::new("_hidden/types/ArsType.json")));
+        assert!(!is_hidden_path(Path::new("types/Order.json")));
+    }
+}
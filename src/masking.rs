@@ -0,0 +1,453 @@
+//! Masking of exported type data
+//!
+//! Data exported for lower environments often contains personal data. The
+//! `[masking]` config lists, per type and per field, how to anonymize it:
+//! - `redact`: replace with `"[REDACTED]"` (`null` for non-string values)
+//! - `hash`: salted SHA-256 of the value, so equal values stay equal and
+//!   joins across types keep working
+//! - `fake`: a deterministic value of the same shape (letters, digits and
+//!   separators kept in place; emails keep a valid form)
+//!
+//! Fields may be dotted paths into nested objects (`address.street`); arrays
+//! along the path are masked element by element.
+
+use crate::config::{MaskAction, MaskingConfig};
+use crate::error::{Result, VqxError};
+use serde::Serialize;
+use serde_json::{Number, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Replacement for redacted string values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Directory of type data in an export
+pub const DATA_DIR: &str = "data";
+
+/// Masked value counts of one export
+#[derive(Debug, Default, Serialize)]
+pub struct MaskingReport {
+    /// Data files with at least one masked value
+    pub files_masked: usize,
+    /// Records checked in configured types
+    pub records: usize,
+    /// Masked values per type and field
+    pub fields: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl MaskingReport {
+    /// Total number of masked values
+    pub fn total(&self) -> usize {
+        self.fields.values().flat_map(BTreeMap::values).sum()
+    }
+}
+
+/// Stream of bytes derived from a seed, for fake values
+struct FakeBytes {
+    seed: Vec<u8>,
+    block: Vec<u8>,
+    counter: u32,
+}
+
+impl FakeBytes {
+    fn new(seed: &[u8]) -> Self {
+        Self {
+            seed: seed.to_vec(),
+            block: Vec::new(),
+            counter: 0,
+        }
+    }
+
+    fn next_byte(&mut self) -> u8 {
+        if self.block.is_empty() {
+            self.block = Sha256::new()
+                .chain_update(&self.seed)
+                .chain_update(self.counter.to_be_bytes())
+                .finalize()
+                .to_vec();
+            self.counter += 1;
+        }
+        self.block.pop().unwrap_or_default()
+    }
+}
+
+/// Text a value is hashed from: the string itself, or its JSON
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn digest(salt: &str, value: &Value) -> Vec<u8> {
+    Sha256::new()
+        .chain_update(salt.as_bytes())
+        .chain_update([0u8])
+        .chain_update(value_text(value).as_bytes())
+        .finalize()
+        .to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Fake string keeping the shape of the original
+fn fake_string(value: &str, bytes: &mut FakeBytes) -> String {
+    let mut fake_chars = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_digit() {
+                    char::from(b'0' + bytes.next_byte() % 10)
+                } else if c.is_uppercase() {
+                    char::from(b'A' + bytes.next_byte() % 26)
+                } else if c.is_alphabetic() {
+                    char::from(b'a' + bytes.next_byte() % 26)
+                } else {
+                    c
+                }
+            })
+            .collect()
+    };
+
+    match value.split_once('@') {
+        Some((local, domain)) if !local.is_empty() && domain.contains('.') => {
+            format!("{}@example.com", fake_chars(local))
+        }
+        _ => fake_chars(value),
+    }
+}
+
+/// Fake number with the same sign and number of integer digits
+fn fake_number(n: &Number, bytes: &mut FakeBytes) -> Value {
+    if let Some(i) = n.as_i64() {
+        let digits = i.unsigned_abs().to_string().len();
+        let mut fake: i64 = 0;
+        for d in 0..digits {
+            let digit = bytes.next_byte() % 10;
+            // Keep the leading digit non-zero so the magnitude is unchanged
+            let digit = if d == 0 && digits > 1 {
+                digit % 9 + 1
+            } else {
+                digit
+            };
+            fake = fake.saturating_mul(10).saturating_add(i64::from(digit));
+        }
+        return Value::from(if i < 0 { -fake } else { fake });
+    }
+
+    let f = n.as_f64().unwrap_or_default();
+    let factor = 0.5 + f64::from(bytes.next_byte()) / 255.0;
+    let fake = (f * factor * 100.0).round() / 100.0;
+    Number::from_f64(fake).map_or(Value::Null, Value::Number)
+}
+
+/// Mask one value; `None` if there is nothing to mask
+fn mask_value(action: MaskAction, salt: &str, value: &Value) -> Option<Value> {
+    if value.is_null() {
+        return None;
+    }
+    let masked = match action {
+        MaskAction::Redact => match value {
+            Value::String(_) => Value::String(REDACTED.to_string()),
+            _ => Value::Null,
+        },
+        MaskAction::Hash => Value::String(hex(&digest(salt, value))),
+        MaskAction::Fake => {
+            let mut bytes = FakeBytes::new(&digest(salt, value));
+            match value {
+                Value::String(s) => Value::String(fake_string(s, &mut bytes)),
+                Value::Number(n) => fake_number(n, &mut bytes),
+                Value::Array(items) => Value::Array(
+                    items
+                        .iter()
+                        .map(|v| mask_value(action, salt, v).unwrap_or(Value::Null))
+                        .collect(),
+                ),
+                Value::Bool(_) => value.clone(),
+                _ => Value::Null,
+            }
+        }
+    };
+    Some(masked)
+}
+
+/// Mask the field at `path` in a record, returning the number of masked values
+fn mask_path(record: &mut Value, path: &[&str], action: MaskAction, salt: &str) -> usize {
+    match record {
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| mask_path(item, path, action, salt))
+            .sum(),
+        Value::Object(map) => {
+            let Some((key, rest)) = path.split_first() else {
+                return 0;
+            };
+            let Some(field) = map.get_mut(*key) else {
+                return 0;
+            };
+            if !rest.is_empty() {
+                return mask_path(field, rest, action, salt);
+            }
+            match mask_value(action, salt, field) {
+                Some(masked) => {
+                    *field = masked;
+                    1
+                }
+                None => 0,
+            }
+        }
+        _ => 0,
+    }
+}
+
+/// Mask the records of one type in place
+///
+/// `data` is the content of a data file: an array of records or a single
+/// record. Counts are added to `report`.
+pub fn mask_records(
+    config: &MaskingConfig,
+    type_name: &str,
+    data: &mut Value,
+    report: &mut MaskingReport,
+) -> usize {
+    let Some(fields) = config.types.get(type_name) else {
+        return 0;
+    };
+    let salt = config.salt.as_deref().unwrap_or_default();
+
+    let records: Vec<&mut Value> = match data {
+        Value::Array(items) => items.iter_mut().collect(),
+        other => vec![other],
+    };
+    report.records += records.len();
+
+    let mut masked = 0;
+    for record in records {
+        for (field, &action) in fields {
+            let path: Vec<&str> = field.split('.').collect();
+            let count = mask_path(record, &path, action, salt);
+            if count > 0 {
+                *report
+                    .fields
+                    .entry(type_name.to_string())
+                    .or_default()
+                    .entry(field.clone())
+                    .or_default() += count;
+                masked += count;
+            }
+        }
+    }
+    masked
+}
+
+/// Type of a data file: `data/<Type>.json` or `data/<Type>/<chunk>.json`
+fn data_type(relative: &Path) -> Option<String> {
+    let mut components = relative.components();
+    if components.next()?.as_os_str() != DATA_DIR {
+        return None;
+    }
+    let first = Path::new(components.next()?.as_os_str());
+    if relative.extension().is_none_or(|e| e != "json") {
+        return None;
+    }
+    let name = match components.next() {
+        Some(_) => first.as_os_str(),
+        None => first.file_stem()?,
+    };
+    name.to_str().map(str::to_string)
+}
+
+/// Fail unless fields masked with `hash` or `fake` have a salt
+///
+/// Without a secret salt, anyone can hash candidate values (emails, phone
+/// numbers) and match them against the export.
+pub fn check(config: &MaskingConfig) -> Result<()> {
+    let salted = config
+        .types
+        .values()
+        .flat_map(BTreeMap::values)
+        .any(|action| matches!(action, MaskAction::Hash | MaskAction::Fake));
+    if salted && config.salt.as_deref().is_none_or(|s| s.trim().is_empty()) {
+        return Err(VqxError::Other(
+            "[masking] uses `hash` or `fake` without a salt; set [masking] salt to a secret value"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Copy an export from `source` to `target`, masking type data on the way
+///
+/// Only masked content is ever written to `target`; files that cannot be
+/// parsed are an error rather than copied unmasked.
+pub fn mask_directory(
+    config: &MaskingConfig,
+    source: &Path,
+    target: &Path,
+) -> Result<MaskingReport> {
+    check(config)?;
+    let mut report = MaskingReport::default();
+
+    for entry in WalkDir::new(source).min_depth(1) {
+        let entry = entry.map_err(|e| VqxError::Other(e.to_string()))?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        let dest = target.join(relative);
+
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&dest).map_err(|_| VqxError::FileWriteFailed {
+                path: dest.display().to_string(),
+            })?;
+            continue;
+        }
+
+        let masked = match data_type(relative).filter(|t| config.types.contains_key(t)) {
+            Some(type_name) => {
+                let content =
+                    fs::read_to_string(entry.path()).map_err(|_| VqxError::FileReadFailed {
+                        path: entry.path().display().to_string(),
+                    })?;
+                let mut data: Value = serde_json::from_str(&content).map_err(|e| {
+                    VqxError::Other(format!("Cannot mask {}: {}", relative.display(), e))
+                })?;
+                if mask_records(config, &type_name, &mut data, &mut report) > 0 {
+                    report.files_masked += 1;
+                    Some(serde_json::to_string_pretty(&data)? + "\n")
+                } else {
+                    None
+                }
+            }
+            None => None,
+        };
+
+        let written = match masked {
+            Some(content) => fs::write(&dest, content),
+            None => fs::copy(entry.path(), &dest).map(|_| ()),
+        };
+        written.map_err(|_| VqxError::FileWriteFailed {
+            path: dest.display().to_string(),
+        })?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> MaskingConfig {
+        toml::from_str(
+            r#"
+            salt = "s3"
+            [types.Customer]
+            name = "redact"
+            email = "fake"
+            ssn = "hash"
+            "address.zip" = "fake"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mask_records() {
+        let mut data = json!([
+            {
+                "name": "Taro Yamada",
+                "email": "taro@corp.jp",
+                "ssn": "123-45-6789",
+                "address": {"zip": "100-0001", "city": "Tokyo"},
+                "age": 42
+            },
+            {"name": null, "email": "hanako@corp.jp"}
+        ]);
+        let mut report = MaskingReport::default();
+        assert_eq!(
+            mask_records(&config(), "Customer", &mut data, &mut report),
+            5
+        );
+
+        let first = &data[0];
+        assert_eq!(first["name"], REDACTED);
+        assert_eq!(first["age"], 42);
+        assert_eq!(first["address"]["city"], "Tokyo");
+
+        let email = first["email"].as_str().unwrap();
+        assert!(email.ends_with("@example.com"));
+        assert_eq!(email.len(), "taro@example.com".len());
+
+        let zip = first["address"]["zip"].as_str().unwrap();
+        assert_ne!(zip, "100-0001");
+        assert!(zip.chars().nth(3) == Some('-') && zip.len() == 8);
+
+        let ssn = first["ssn"].as_str().unwrap();
+        assert_eq!(ssn.len(), 64);
+
+        assert_eq!(report.records, 2);
+        assert_eq!(report.fields["Customer"]["email"], 2);
+        assert_eq!(report.fields["Customer"]["name"], 1);
+        assert_eq!(report.total(), 5);
+    }
+
+    #[test]
+    fn test_check_requires_salt() {
+        assert!(check(&config()).is_ok());
+
+        let mut config = config();
+        config.salt = Some(" ".to_string());
+        assert!(check(&config).is_err());
+        config.salt = None;
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(mask_directory(&config, dir.path(), &dir.path().join("out")).is_err());
+
+        // Redaction needs no salt
+        config
+            .types
+            .get_mut("Customer")
+            .unwrap()
+            .retain(|_, a| *a == MaskAction::Redact);
+        assert!(check(&config).is_ok());
+    }
+
+    #[test]
+    fn test_masking_is_deterministic() {
+        let config = config();
+        let mut a = json!({"email": "taro@corp.jp", "ssn": "123"});
+        let mut b = a.clone();
+        let mut report = MaskingReport::default();
+        mask_records(&config, "Customer", &mut a, &mut report);
+        mask_records(&config, "Customer", &mut b, &mut report);
+        assert_eq!(a, b);
+
+        let mut other = json!({"email": "taro@corp.jp"});
+        mask_records(&config, "Order", &mut other, &mut report);
+        assert_eq!(other["email"], "taro@corp.jp");
+    }
+
+    #[test]
+    fn test_fake_number_keeps_digits() {
+        let mut bytes = FakeBytes::new(b"seed");
+        let fake = fake_number(&Number::from(-4821), &mut bytes);
+        let fake = fake.as_i64().unwrap();
+        assert!((-9999..=-1000).contains(&fake));
+    }
+
+    #[test]
+    fn test_data_type() {
+        assert_eq!(
+            data_type(Path::new("data/Customer.json")).as_deref(),
+            Some("Customer")
+        );
+        assert_eq!(
+            data_type(Path::new("data/Customer/Customer_1.json")).as_deref(),
+            Some("Customer")
+        );
+        assert_eq!(data_type(Path::new("types/Customer.json")), None);
+        assert_eq!(data_type(Path::new("data/notes.txt")), None);
+    }
+}
@@ -0,0 +1,242 @@
+//! Prometheus metrics for long-running modes (vqx extension)
+//!
+//! `vqx serve` answers `GET /metrics` and `vqx schedule run --metrics <addr>`
+//! starts a listener for it. Metrics are kept per operation (a serve
+//! operation or a scheduled job) in the Prometheus text format:
+//!
+//! - `vqx_runs_total{operation,result}`: finished runs by `success`/`failure`
+//! - `vqx_run_duration_seconds{operation}`: summary of run durations
+//! - `vqx_last_run_timestamp_seconds`, `vqx_last_success_timestamp_seconds`
+//! - `vqx_last_run_success{operation}`: 1 if the last run succeeded
+//! - `vqx_drift_resources{operation}`: resources that differed in the last
+//!   diff report of the operation
+//!
+//! Operations start at zero so that a job which never ran still shows up.
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Metrics of one operation
+#[derive(Debug, Default, Clone)]
+struct Stats {
+    successes: u64,
+    failures: u64,
+    duration_seconds: f64,
+    last_run: Option<DateTime<Utc>>,
+    last_success: Option<DateTime<Utc>>,
+    last_succeeded: Option<bool>,
+    drift: Option<usize>,
+}
+
+/// Name, help and value of a gauge per operation
+type Gauge = (&'static str, &'static str, fn(&Stats) -> Option<String>);
+
+/// Metrics of a serve or schedule process
+#[derive(Debug)]
+pub struct Metrics {
+    started_at: DateTime<Utc>,
+    operations: Mutex<BTreeMap<String, Stats>>,
+}
+
+impl Metrics {
+    /// Metrics with `operations` at zero
+    pub fn new<'a>(operations: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            started_at: Utc::now(),
+            operations: Mutex::new(
+                operations
+                    .into_iter()
+                    .map(|name| (name.to_string(), Stats::default()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Record a finished run
+    pub fn record(
+        &self,
+        operation: &str,
+        success: bool,
+        duration: Duration,
+        finished_at: DateTime<Utc>,
+    ) {
+        let mut operations = self.operations.lock().unwrap();
+        let stats = operations.entry(operation.to_string()).or_default();
+        if success {
+            stats.successes += 1;
+            stats.last_success = Some(finished_at);
+        } else {
+            stats.failures += 1;
+        }
+        stats.duration_seconds += duration.as_secs_f64();
+        stats.last_run = Some(finished_at);
+        stats.last_succeeded = Some(success);
+    }
+
+    /// Record the drift found by a run
+    pub fn record_drift(&self, operation: &str, resources: usize) {
+        let mut operations = self.operations.lock().unwrap();
+        operations.entry(operation.to_string()).or_default().drift = Some(resources);
+    }
+
+    /// Metrics in the Prometheus text format
+    pub fn render(&self) -> String {
+        let operations = self.operations.lock().unwrap().clone();
+        let mut out = String::new();
+
+        family(
+            &mut out,
+            "vqx_start_time_seconds",
+            "gauge",
+            "Time the process started",
+        );
+        let _ = writeln!(out, "vqx_start_time_seconds {}", seconds(self.started_at));
+
+        family(
+            &mut out,
+            "vqx_runs_total",
+            "counter",
+            "Finished runs by operation and result",
+        );
+        for (name, stats) in &operations {
+            for (result, count) in [("success", stats.successes), ("failure", stats.failures)] {
+                let _ = writeln!(
+                    out,
+                    "vqx_runs_total{{operation=\"{}\",result=\"{}\"}} {}",
+                    escape(name),
+                    result,
+                    count
+                );
+            }
+        }
+
+        family(
+            &mut out,
+            "vqx_run_duration_seconds",
+            "summary",
+            "Duration of finished runs",
+        );
+        for (name, stats) in &operations {
+            let label = escape(name);
+            let _ = writeln!(
+                out,
+                "vqx_run_duration_seconds_sum{{operation=\"{}\"}} {}",
+                label, stats.duration_seconds
+            );
+            let _ = writeln!(
+                out,
+                "vqx_run_duration_seconds_count{{operation=\"{}\"}} {}",
+                label,
+                stats.successes + stats.failures
+            );
+        }
+
+        let gauges: [Gauge; 4] = [
+            (
+                "vqx_last_run_timestamp_seconds",
+                "Time the last run finished",
+                |s| s.last_run.map(seconds),
+            ),
+            (
+                "vqx_last_success_timestamp_seconds",
+                "Time the last successful run finished",
+                |s| s.last_success.map(seconds),
+            ),
+            (
+                "vqx_last_run_success",
+                "Whether the last run succeeded (1) or failed (0)",
+                |s| s.last_succeeded.map(|ok| u8::from(ok).to_string()),
+            ),
+            (
+                "vqx_drift_resources",
+                "Resources that differed in the last diff report",
+                |s| s.drift.map(|d| d.to_string()),
+            ),
+        ];
+        for (metric, help, value) in gauges {
+            family(&mut out, metric, "gauge", help);
+            for (name, stats) in &operations {
+                if let Some(value) = value(stats) {
+                    let _ = writeln!(
+                        out,
+                        "{}{{operation=\"{}\"}} {}",
+                        metric,
+                        escape(name),
+                        value
+                    );
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Resources added, removed or modified in a `diff` report
+///
+/// Reports of other commands have no drift.
+pub fn drift(report: &Value) -> Option<usize> {
+    ["added", "removed", "modified"]
+        .iter()
+        .map(|key| report.get(key).and_then(Value::as_array).map(Vec::len))
+        .sum()
+}
+
+fn family(out: &mut String, metric: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", metric, help);
+    let _ = writeln!(out, "# TYPE {} {}", metric, kind);
+}
+
+fn seconds(time: DateTime<Utc>) -> String {
+    format!("{:.3}", time.timestamp_millis() as f64 / 1000.0)
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::new(["nightly", "idle"]);
+        let at = DateTime::parse_from_rfc3339("2026-03-10T02:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        metrics.record("nightly", true, Duration::from_millis(1500), at);
+        metrics.record("nightly", false, Duration::from_millis(500), at);
+        metrics.record_drift("nightly", 3);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE vqx_runs_total counter\n"));
+        assert!(text.contains("vqx_runs_total{operation=\"nightly\",result=\"success\"} 1\n"));
+        assert!(text.contains("vqx_runs_total{operation=\"idle\",result=\"failure\"} 0\n"));
+        assert!(text.contains("vqx_run_duration_seconds_sum{operation=\"nightly\"} 2\n"));
+        assert!(text.contains("vqx_run_duration_seconds_count{operation=\"nightly\"} 2\n"));
+        assert!(text.contains(
+            "vqx_last_success_timestamp_seconds{operation=\"nightly\"} 1773108000.000\n"
+        ));
+        assert!(text.contains("vqx_last_run_success{operation=\"nightly\"} 0\n"));
+        assert!(text.contains("vqx_drift_resources{operation=\"nightly\"} 3\n"));
+        // Never ran, so no timestamps
+        assert!(!text.contains("vqx_last_run_timestamp_seconds{operation=\"idle\"}"));
+        assert_eq!(escape("a\"b\\c"), "a\\\"b\\\\c");
+    }
+
+    #[test]
+    fn test_drift() {
+        let report = json!({ "added": [1], "removed": [], "modified": [1, 2] });
+        assert_eq!(drift(&report), Some(3));
+        assert_eq!(drift(&json!({ "success": true })), None);
+    }
+}
@@ -6,140 +6,222 @@
 //! Features:
 //! - Alphabetically sorted object keys
 //! - Stable array ordering (by name/id fields)
-//! - Removal of volatile fields (timestamps, versions)
+//! - Removal of volatile fields (timestamps, versions), by key glob or path
+//! - Line-ending (CRLF -> LF) and Unicode NFC normalization of string values
+//! - Optional extraction of procedure/rule code into `.vail` files
+//! - Optional RFC 8785 (JCS) canonical output for stable hashing
 //!
 //! Based on: CLI Reference Guide PDF - Export section
 //! The export command produces JSON files that this module normalizes.
 
+use crate::commands::list::glob_to_regex;
 use crate::config::NormalizationConfig;
 use crate::error::{Result, VqxError};
+use regex::Regex;
 use serde_json::{Map, Value};
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
-use tracing::{debug, info};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tracing::{debug, warn};
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+use walkdir::WalkDir;
+
+/// Fields holding VAIL source in procedure and rule definitions
+const CODE_FIELDS: &[&str] = &["ruleText", "text"];
+
+/// Key of an extracted code reference: `"ruleText": {"$vail": "Name.vail"}`
+pub const CODE_REF_KEY: &str = "$vail";
+
+/// Resource directories whose definitions carry VAIL code
+const CODE_RESOURCE_TYPES: &[&str] = &["procedures", "rules"];
+
+/// One `excluded_fields` rule
+///
+/// - `ars_*`: key glob, matched at any depth
+/// - `/config/lastRun`: JSON pointer, matched from the document root
+/// - `properties[*].ars_createdAt`: dotted path, matched at any depth
+///   (a leading `$.` anchors it at the root)
+///
+/// Every segment is a glob; `[*]` matches any array element or object key.
+#[derive(Debug)]
+enum FieldRule {
+    Key(Regex),
+    Path {
+        segments: Vec<Regex>,
+        anchored: bool,
+    },
+}
+
+impl FieldRule {
+    fn parse(rule: &str) -> Result<Self> {
+        if let Some(pointer) = rule.strip_prefix('/') {
+            let segments = pointer
+                .split('/')
+                .map(|s| glob_to_regex(&s.replace("~1", "/").replace("~0", "~")))
+                .collect::<Result<_>>()?;
+            return Ok(FieldRule::Path {
+                segments,
+                anchored: true,
+            });
+        }
+
+        let (path, anchored) = match rule.strip_prefix("$.") {
+            Some(rest) => (rest, true),
+            None => (rule, false),
+        };
+        let mut segments = split_dotted_path(path);
+        if segments.len() == 1 && !anchored {
+            return Ok(FieldRule::Key(glob_to_regex(&segments.remove(0))?));
+        }
+        Ok(FieldRule::Path {
+            segments: segments
+                .iter()
+                .map(|s| glob_to_regex(s))
+                .collect::<Result<_>>()?,
+            anchored,
+        })
+    }
+
+    /// Whether the field at `path` (keys and array indexes from the root)
+    /// is excluded by this rule
+    fn matches(&self, path: &[String]) -> bool {
+        match self {
+            FieldRule::Key(re) => path.last().is_some_and(|key| re.is_match(key)),
+            FieldRule::Path { segments, anchored } => {
+                if path.len() < segments.len() || (*anchored && path.len() != segments.len()) {
+                    return false;
+                }
+                let tail = &path[path.len() - segments.len()..];
+                segments.iter().zip(tail).all(|(re, seg)| re.is_match(seg))
+            }
+        }
+    }
+}
+
+/// Split `a.b[*].c` into `["a", "b", "*", "c"]`
+fn split_dotted_path(path: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    for c in path.chars() {
+        match c {
+            '.' | '[' | ']' => {
+                if !current.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
 
 /// JSON Normalizer for producing stable, diff-friendly output
 pub struct Normalizer {
     config: NormalizationConfig,
+    excluded: Vec<FieldRule>,
 }
 
 impl Normalizer {
     /// Create a new normalizer with default configuration
     pub fn new() -> Self {
-        Self {
-            config: NormalizationConfig::default(),
-        }
+        Self::with_config(NormalizationConfig::default())
     }
 
     /// Create a normalizer with custom configuration
+    /// Invalid `excluded_fields` rules are skipped with a warning
     pub fn with_config(config: NormalizationConfig) -> Self {
-        Self { config }
+        let excluded = config
+            .excluded_fields
+            .iter()
+            .filter_map(|rule| match FieldRule::parse(rule) {
+                Ok(parsed) => Some(parsed),
+                Err(e) => {
+                    warn!(rule = %rule, error = %e, "Ignoring invalid excluded_fields rule");
+                    None
+                }
+            })
+            .collect();
+        Self { config, excluded }
     }
 
     /// Normalize a JSON value
     pub fn normalize(&self, value: &Value) -> Value {
-        self.normalize_value(value)
-    }
-
-    /// Normalize a JSON string
-    pub fn normalize_str(&self, json_str: &str) -> Result<String> {
-        let value: Value = serde_json::from_str(json_str)?;
-        let normalized = self.normalize(&value);
-        Ok(serde_json::to_string_pretty(&normalized)?)
+        self.normalize_value(value, &mut Vec::new())
     }
 
-    /// Normalize a JSON file in place
-    pub fn normalize_file(&self, path: &Path) -> Result<()> {
-        let content = fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
-            path: path.display().to_string(),
-        })?;
-
-        let normalized = self.normalize_str(&content)?;
-
-        fs::write(path, normalized).map_err(|_| VqxError::FileWriteFailed {
-            path: path.display().to_string(),
-        })?;
-
-        debug!(path = %path.display(), "Normalized JSON file");
-        Ok(())
+    /// Serialize a normalized value: pretty-printed, or JCS when `canonical_json` is set
+    pub fn serialize(&self, value: &Value) -> Result<String> {
+        if self.config.canonical_json {
+            Ok(to_canonical_json(value))
+        } else {
+            Ok(serde_json::to_string_pretty(value)?)
+        }
     }
 
-    /// Normalize all JSON files in a directory recursively
-    pub fn normalize_directory(&self, dir: &Path) -> Result<NormalizationStats> {
-        let mut stats = NormalizationStats::default();
-
-        if !dir.is_dir() {
-            return Err(VqxError::Other(format!(
-                "Not a directory: {}",
-                dir.display()
-            )));
+    /// Recursively normalize a JSON value found at `path`
+    fn normalize_value(&self, value: &Value, path: &mut Vec<String>) -> Value {
+        match value {
+            Value::Object(map) => self.normalize_object(map, path),
+            Value::Array(arr) => self.normalize_array(arr, path),
+            Value::String(s) => Value::String(self.normalize_string(s)),
+            _ => value.clone(),
         }
-
-        self.normalize_directory_recursive(dir, &mut stats)?;
-
-        info!(
-            files = stats.files_processed,
-            errors = stats.errors,
-            "Normalization complete"
-        );
-
-        Ok(stats)
     }
 
-    fn normalize_directory_recursive(
-        &self,
-        dir: &Path,
-        stats: &mut NormalizationStats,
-    ) -> Result<()> {
-        for entry in fs::read_dir(dir).map_err(|e| VqxError::Other(e.to_string()))? {
-            let entry = entry.map_err(|e| VqxError::Other(e.to_string()))?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                self.normalize_directory_recursive(&path, stats)?;
-            } else if path.extension().map(|e| e == "json").unwrap_or(false) {
-                match self.normalize_file(&path) {
-                    Ok(()) => stats.files_processed += 1,
-                    Err(e) => {
-                        stats.errors += 1;
-                        stats
-                            .error_files
-                            .push((path.display().to_string(), e.to_string()));
-                    }
-                }
+    /// Normalize only the string values of a JSON value, keeping its layout
+    /// Used for code resources whose structure is left untouched
+    pub fn normalize_strings(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| (k.clone(), self.normalize_strings(v)))
+                    .collect(),
+            ),
+            Value::Array(arr) => {
+                Value::Array(arr.iter().map(|v| self.normalize_strings(v)).collect())
             }
+            Value::String(s) => Value::String(self.normalize_string(s)),
+            _ => value.clone(),
         }
-
-        Ok(())
     }
 
-    /// Recursively normalize a JSON value
-    fn normalize_value(&self, value: &Value) -> Value {
-        match value {
-            Value::Object(map) => self.normalize_object(map),
-            Value::Array(arr) => self.normalize_array(arr),
-            _ => value.clone(),
+    /// Normalize line endings and Unicode form of a string
+    /// Windows exports use CRLF; macOS tools may produce decomposed (NFD) text
+    pub fn normalize_string(&self, s: &str) -> String {
+        let mut result = if self.config.normalize_line_endings && s.contains('\r') {
+            s.replace("\r\n", "\n")
+        } else {
+            s.to_string()
+        };
+        if self.config.unicode_nfc && !is_nfc(&result) {
+            result = result.nfc().collect();
         }
+        result
     }
 
     /// Normalize a JSON object
     /// - Sort keys alphabetically (using BTreeMap)
     /// - Remove excluded fields
     /// - Recursively normalize nested values
-    fn normalize_object(&self, map: &Map<String, Value>) -> Value {
+    fn normalize_object(&self, map: &Map<String, Value>, path: &mut Vec<String>) -> Value {
         let mut sorted: BTreeMap<String, Value> = BTreeMap::new();
 
         for (key, value) in map {
+            path.push(key.clone());
+
             // Skip excluded fields
-            if self.config.excluded_fields.contains(key) {
-                continue;
+            if !self.excluded.iter().any(|rule| rule.matches(path)) {
+                // Recursively normalize the value
+                let normalized_value = self.normalize_value(value, path);
+                sorted.insert(key.clone(), normalized_value);
             }
 
-            // Recursively normalize the value
-            let normalized_value = self.normalize_value(value);
-            sorted.insert(key.clone(), normalized_value);
+            path.pop();
         }
 
         // Convert BTreeMap back to serde_json Map (which preserves insertion order)
@@ -150,15 +232,25 @@ impl Normalizer {
     /// Normalize a JSON array
     /// - Sort by configured sort fields (name, id, _id)
     /// - Recursively normalize elements
-    fn normalize_array(&self, arr: &[Value]) -> Value {
+    fn normalize_array(&self, arr: &[Value], path: &mut Vec<String>) -> Value {
         // First, normalize all elements
-        let mut normalized: Vec<Value> = arr.iter().map(|v| self.normalize_value(v)).collect();
+        let mut normalized: Vec<Value> = arr
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                path.push(i.to_string());
+                let normalized = self.normalize_value(v, path);
+                path.pop();
+                normalized
+            })
+            .collect();
 
         // Sort if enabled and array contains objects
-        if self.config.sort_arrays && !normalized.is_empty() {
-            if normalized.iter().all(|v| v.is_object()) {
-                normalized.sort_by(|a, b| self.compare_objects(a, b));
-            }
+        if self.config.sort_arrays
+            && !normalized.is_empty()
+            && normalized.iter().all(|v| v.is_object())
+        {
+            normalized.sort_by(|a, b| self.compare_objects(a, b));
         }
 
         Value::Array(normalized)
@@ -216,6 +308,36 @@ pub struct NormalizationStats {
     pub error_files: Vec<(String, String)>,
 }
 
+/// A file write computed by the normalizer
+#[derive(Debug)]
+pub struct PlannedWrite {
+    pub path: PathBuf,
+    /// Current content, or `None` if the file does not exist yet
+    pub before: Option<String>,
+    pub after: String,
+}
+
+impl PlannedWrite {
+    fn new(path: &Path, after: String) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            before: fs::read_to_string(path).ok(),
+            after,
+        }
+    }
+
+    /// Whether writing would change the file
+    pub fn is_change(&self) -> bool {
+        self.before.as_deref() != Some(self.after.as_str())
+    }
+
+    pub fn apply(&self) -> Result<()> {
+        fs::write(&self.path, &self.after).map_err(|_| VqxError::FileWriteFailed {
+            path: self.path.display().to_string(),
+        })
+    }
+}
+
 /// Resource-specific normalizer that understands Vantiq resource types
 /// Based on PDF export directory structure:
 /// - types/
@@ -241,7 +363,7 @@ impl ResourceNormalizer {
             // Types may have schema definitions that need special handling
             "types" => self.normalize_type(value),
             // Procedures are VAIL code, stored differently
-            "procedures" | "rules" => value.clone(), // Don't modify code files
+            "procedures" | "rules" => self.base_normalizer.normalize_strings(value), // Keep code layout
             // Default normalization for other resources
             _ => self.base_normalizer.normalize(value),
         }
@@ -281,6 +403,27 @@ impl ResourceNormalizer {
     /// Normalize an export directory
     /// PDF: Export creates directories like types/, procedures/, rules/, etc.
     pub fn normalize_export_directory(&self, dir: &Path) -> Result<NormalizationStats> {
+        let _step = crate::summary::step("normalize");
+        self.process_export_directory(dir, None)
+    }
+
+    /// Compute the writes `normalize_export_directory` would make, without writing
+    /// Only writes that change a file are returned
+    pub fn preview_export_directory(
+        &self,
+        dir: &Path,
+    ) -> Result<(NormalizationStats, Vec<PlannedWrite>)> {
+        let mut planned = Vec::new();
+        let stats = self.process_export_directory(dir, Some(&mut planned))?;
+        Ok((stats, planned))
+    }
+
+    /// Normalize an export directory, or only collect its writes into `planned`
+    fn process_export_directory(
+        &self,
+        dir: &Path,
+        mut planned: Option<&mut Vec<PlannedWrite>>,
+    ) -> Result<NormalizationStats> {
         let mut stats = NormalizationStats::default();
 
         // Known resource directories from PDF Export section
@@ -311,7 +454,12 @@ impl ResourceNormalizer {
             let resource_dir = dir.join(resource_type);
             if resource_dir.is_dir() {
                 debug!(resource_type, "Normalizing resource directory");
-                self.normalize_resource_directory(&resource_dir, resource_type, &mut stats)?;
+                self.normalize_resource_directory(
+                    &resource_dir,
+                    resource_type,
+                    &mut stats,
+                    planned.as_deref_mut(),
+                )?;
             }
         }
 
@@ -323,13 +471,23 @@ impl ResourceNormalizer {
         dir: &Path,
         resource_type: &str,
         stats: &mut NormalizationStats,
+        mut planned: Option<&mut Vec<PlannedWrite>>,
     ) -> Result<()> {
         for entry in fs::read_dir(dir).map_err(|e| VqxError::Other(e.to_string()))? {
             let entry = entry.map_err(|e| VqxError::Other(e.to_string()))?;
             let path = entry.path();
 
             if path.is_file() && path.extension().map(|e| e == "json").unwrap_or(false) {
-                match self.normalize_resource_file(&path, resource_type) {
+                let result = self
+                    .plan_resource_file(&path, resource_type)
+                    .and_then(|writes| match planned.as_deref_mut() {
+                        Some(planned) => {
+                            planned.extend(writes.into_iter().filter(PlannedWrite::is_change));
+                            Ok(())
+                        }
+                        None => writes.iter().try_for_each(PlannedWrite::apply),
+                    });
+                match result {
                     Ok(()) => stats.files_processed += 1,
                     Err(e) => {
                         stats.errors += 1;
@@ -340,29 +498,344 @@ impl ResourceNormalizer {
                 }
             } else if path.is_dir() {
                 // Recurse into subdirectories
-                self.normalize_resource_directory(&path, resource_type, stats)?;
+                self.normalize_resource_directory(
+                    &path,
+                    resource_type,
+                    stats,
+                    planned.as_deref_mut(),
+                )?;
             }
         }
 
         Ok(())
     }
 
-    fn normalize_resource_file(&self, path: &Path, resource_type: &str) -> Result<()> {
+    /// Compute the normalized content of a resource file (and its `.vail` file)
+    fn plan_resource_file(&self, path: &Path, resource_type: &str) -> Result<Vec<PlannedWrite>> {
         let content = fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
             path: path.display().to_string(),
         })?;
 
         let value: Value = serde_json::from_str(&content)?;
-        let normalized = self.normalize_resource(resource_type, &value);
-        let output = serde_json::to_string_pretty(&normalized)?;
+        self.plan_resource(path, resource_type, &value)
+    }
+
+    /// Compute the files of resource `value` stored at `path`: the normalized
+    /// definition and, with `extract_code`, its `.vail` file
+    pub fn plan_resource(
+        &self,
+        path: &Path,
+        resource_type: &str,
+        value: &Value,
+    ) -> Result<Vec<PlannedWrite>> {
+        let mut normalized = self.normalize_resource(resource_type, value);
+        let mut writes = Vec::new();
+
+        if self.base_normalizer.config.extract_code && CODE_RESOURCE_TYPES.contains(&resource_type)
+        {
+            writes.extend(extract_code(path, &mut normalized));
+        }
+
+        let output = self.base_normalizer.serialize(&normalized)?;
+        writes.push(PlannedWrite::new(path, output));
+
+        debug!(path = %path.display(), resource_type, "Normalized resource file");
+        Ok(writes)
+    }
+}
+
+/// Serialize a value as RFC 8785 (JCS) canonical JSON
+///
+/// No whitespace, object keys sorted by UTF-16 code units and numbers in
+/// ECMAScript form, so equal values always produce identical bytes.
+pub fn to_canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(&mut out, value);
+    out
+}
+
+fn write_canonical(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => out.push_str(&canonical_number(n)),
+        Value::String(s) => write_canonical_string(out, s),
+        Value::Array(arr) => {
+            out.push('[');
+            for (i, item) in arr.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical_string(out, key);
+                out.push(':');
+                write_canonical(out, item);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_canonical_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
 
-        fs::write(path, output).map_err(|_| VqxError::FileWriteFailed {
+/// Format a number like ECMAScript `Number.prototype.toString`
+fn canonical_number(n: &serde_json::Number) -> String {
+    let f = n.as_f64().unwrap_or(0.0);
+    if f == 0.0 {
+        return "0".to_string();
+    }
+
+    // `{:e}` yields the shortest round-trip digits, e.g. "-1.2345e-7"
+    let sci = format!("{:e}", f);
+    let (mantissa, exponent) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    // Position of the decimal point relative to the digits
+    let point = exponent.parse::<i32>().unwrap_or(0) + 1;
+
+    let body = if k <= point && point <= 21 {
+        format!("{}{}", digits, "0".repeat((point - k) as usize))
+    } else if 0 < point && point <= 21 {
+        format!(
+            "{}.{}",
+            &digits[..point as usize],
+            &digits[point as usize..]
+        )
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        let fraction = if k > 1 {
+            format!(".{}", &digits[1..])
+        } else {
+            String::new()
+        };
+        let exp = point - 1;
+        let exp_sign = if exp < 0 { "-" } else { "+" };
+        format!("{}{}e{}{}", &digits[..1], fraction, exp_sign, exp.abs())
+    };
+    format!("{}{}", sign, body)
+}
+
+/// Move the code of a procedure/rule definition into `<stem>.vail` next to `path`
+///
+/// Returns the write of the `.vail` file; `value` gets the reference.
+fn extract_code(path: &Path, value: &mut Value) -> Option<PlannedWrite> {
+    let Value::Object(map) = value else {
+        return None;
+    };
+    let field = CODE_FIELDS
+        .iter()
+        .find(|f| map.get(**f).is_some_and(|v| v.is_string()))?;
+    let stem = path.file_stem().and_then(|s| s.to_str())?;
+
+    let file_name = format!("{}.vail", stem);
+    let code_path = path.with_file_name(&file_name);
+    let code = map[*field].as_str().unwrap_or_default().to_string();
+
+    map.insert(
+        field.to_string(),
+        serde_json::json!({ CODE_REF_KEY: file_name }),
+    );
+    debug!(path = %code_path.display(), "Extracted VAIL code");
+    Some(PlannedWrite::new(&code_path, code))
+}
+
+/// Get the field holding the (embedded) VAIL code of a definition
+pub fn code_field(value: &Value) -> Option<&'static str> {
+    CODE_FIELDS
+        .iter()
+        .find(|f| value.get(**f).is_some_and(|v| v.is_string()))
+        .copied()
+}
+
+/// `.vail` files referenced by the extracted code fields of a definition
+pub fn code_references(value: &Value) -> Vec<&str> {
+    CODE_FIELDS
+        .iter()
+        .filter_map(|f| value.get(*f).and_then(code_reference))
+        .collect()
+}
+
+/// Get the `.vail` file name referenced by an extracted code field
+fn code_reference(value: &Value) -> Option<&str> {
+    value.get(CODE_REF_KEY).and_then(|v| v.as_str())
+}
+
+/// Replace code references in a definition with the contents of their `.vail` files
+///
+/// Returns the names of the embedded files.
+pub fn embed_code(value: &mut Value, dir: &Path) -> Result<Vec<String>> {
+    let mut embedded = Vec::new();
+    let Value::Object(map) = value else {
+        return Ok(embedded);
+    };
+
+    for field in CODE_FIELDS {
+        let Some(reference) = map.get(*field).and_then(code_reference) else {
+            continue;
+        };
+        // Only sibling files are referenced; never follow paths out of the directory
+        let Some(file_name) = Path::new(reference).file_name() else {
+            continue;
+        };
+        let code_path = dir.join(file_name);
+        let code = fs::read_to_string(&code_path).map_err(|_| VqxError::FileReadFailed {
+            path: code_path.display().to_string(),
+        })?;
+        embedded.push(file_name.to_string_lossy().to_string());
+        map.insert(field.to_string(), Value::String(code));
+    }
+
+    Ok(embedded)
+}
+
+/// Read a resource file with any extracted code embedded again
+///
+/// Used for comparisons so code-only changes are not hidden behind references.
+pub fn read_embedded(path: &Path) -> Result<String> {
+    let content = fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+        path: path.display().to_string(),
+    })?;
+    if !content.contains(CODE_REF_KEY) {
+        return Ok(content);
+    }
+
+    let mut value: Value = serde_json::from_str(&content)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    if embed_code(&mut value, dir)?.is_empty() {
+        return Ok(content);
+    }
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Definition files in the code-carrying resource directories of an export
+fn code_definition_files(dir: &Path) -> impl Iterator<Item = std::path::PathBuf> + '_ {
+    CODE_RESOURCE_TYPES.iter().flat_map(move |resource_type| {
+        WalkDir::new(dir.join(resource_type))
+            .into_iter()
+            .flatten()
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+    })
+}
+
+/// Whether an export directory contains extracted code references
+pub fn has_extracted_code(dir: &Path) -> bool {
+    code_definition_files(dir).any(|path| {
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+            .is_some_and(|v| {
+                CODE_FIELDS
+                    .iter()
+                    .any(|f| v.get(*f).and_then(code_reference).is_some())
+            })
+    })
+}
+
+/// Re-embed extracted code in place and remove the consumed `.vail` files
+///
+/// Only run this on a staging copy; returns the number of definitions updated.
+pub fn embed_code_directory(dir: &Path) -> Result<usize> {
+    let mut updated = 0;
+
+    for path in code_definition_files(dir).collect::<Vec<_>>() {
+        let content = fs::read_to_string(&path).map_err(|_| VqxError::FileReadFailed {
             path: path.display().to_string(),
         })?;
+        if !content.contains(CODE_REF_KEY) {
+            continue;
+        }
 
-        debug!(path = %path.display(), resource_type, "Normalized resource file");
-        Ok(())
+        let mut value: Value = serde_json::from_str(&content)?;
+        let parent = path.parent().unwrap_or(dir);
+        let embedded = embed_code(&mut value, parent)?;
+        if embedded.is_empty() {
+            continue;
+        }
+
+        fs::write(&path, serde_json::to_string_pretty(&value)?).map_err(|_| {
+            VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            }
+        })?;
+        // The code now lives in the definition; don't import it twice
+        for file_name in embedded {
+            let _ = fs::remove_file(parent.join(file_name));
+        }
+        updated += 1;
+    }
+
+    if updated > 0 {
+        debug!(definitions = updated, "Re-embedded VAIL code");
+    }
+    Ok(updated)
+}
+
+/// Prepare a directory for import
+///
+/// Returns a staging copy with code re-embedded when the directory contains
+/// extracted code, or `None` when it can be imported as is.
+pub fn stage_for_import(dir: &Path) -> Result<Option<TempDir>> {
+    if !has_extracted_code(dir) {
+        return Ok(None);
+    }
+
+    let staging = TempDir::new()?;
+    copy_directory(dir, staging.path())?;
+    embed_code_directory(staging.path())?;
+    Ok(Some(staging))
+}
+
+/// Copy the files and directories below `from` into `to`
+pub fn copy_directory(from: &Path, to: &Path) -> Result<()> {
+    for entry in WalkDir::new(from).into_iter().flatten() {
+        let Ok(rel) = entry.path().strip_prefix(from) else {
+            continue;
+        };
+        let dest = to.join(rel);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&dest)?;
+        } else if entry.file_type().is_file() {
+            fs::copy(entry.path(), &dest).map_err(|_| VqxError::FileReadFailed {
+                path: entry.path().display().to_string(),
+            })?;
+        }
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -456,4 +929,197 @@ mod tests {
         assert!(output.get("name").is_some());
         assert!(output.get("custom_field").is_none());
     }
+
+    #[test]
+    fn test_excluded_field_patterns() {
+        let config = NormalizationConfig {
+            excluded_fields: vec![
+                "ars_*".to_string(),
+                "properties[*].createdAt".to_string(),
+                "/config/lastRun".to_string(),
+            ],
+            ..Default::default()
+        };
+        let normalizer = Normalizer::with_config(config);
+
+        let input: Value = serde_json::json!({
+            "name": "test",
+            "ars_version": 3,
+            "createdAt": "kept",
+            "config": {"lastRun": "2024-01-01", "nested": {"lastRun": "kept"}},
+            "properties": [
+                {"name": "a", "createdAt": "2024-01-01", "ars_properties": {}},
+                {"name": "b", "createdAt": "2024-01-02"}
+            ]
+        });
+
+        let output = normalizer.normalize(&input);
+
+        assert!(output.get("ars_version").is_none());
+        assert_eq!(output["createdAt"], "kept");
+        assert!(output["config"].get("lastRun").is_none());
+        assert_eq!(output["config"]["nested"]["lastRun"], "kept");
+        for prop in output["properties"].as_array().unwrap() {
+            assert!(prop.get("createdAt").is_none());
+            assert!(prop.get("ars_properties").is_none());
+            assert!(prop.get("name").is_some());
+        }
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let input: Value = serde_json::from_str(
+            r#"{"b": [1.0, 1e21, 1e-7, 0.000001, -0.5, 100], "a": "line\n\u0001\u00e9", "\u20ac": null, "\ud83d\ude00": true}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_canonical_json(&input),
+            "{\"a\":\"line\\n\\u0001é\",\"b\":[1,1e+21,1e-7,0.000001,-0.5,100],\"€\":null,\"😀\":true}"
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_config() {
+        let config = NormalizationConfig {
+            canonical_json: true,
+            ..Default::default()
+        };
+        let normalizer = Normalizer::with_config(config);
+
+        let value = serde_json::from_str("{\n  \"z\": 1,\n  \"a\": 2.50\n}").unwrap();
+        let output = normalizer.serialize(&normalizer.normalize(&value)).unwrap();
+        assert_eq!(output, "{\"a\":2.5,\"z\":1}");
+    }
+
+    #[test]
+    fn test_split_dotted_path() {
+        assert_eq!(
+            split_dotted_path("properties[*].ars_createdAt"),
+            vec!["properties", "*", "ars_createdAt"]
+        );
+        assert_eq!(split_dotted_path("a.b[0]"), vec!["a", "b", "0"]);
+    }
+
+    #[test]
+    fn test_normalize_line_endings_and_nfc() {
+        let normalizer = Normalizer::new();
+        // "e" + combining acute accent (NFD) vs precomposed "é" (NFC)
+        let input = serde_json::json!({"description": "Cafe\u{0301}\r\nline two"});
+
+        let output = normalizer.normalize(&input);
+        assert_eq!(output["description"], "Caf\u{00e9}\nline two");
+
+        let config = NormalizationConfig {
+            normalize_line_endings: false,
+            unicode_nfc: false,
+            ..Default::default()
+        };
+        let output = Normalizer::with_config(config).normalize(&input);
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_code_resources_normalize_strings_only() {
+        let normalizer = ResourceNormalizer::new(NormalizationConfig::default());
+        let input = serde_json::json!({"ruleText": "RULE OnOrder\r\nlog.info(\"Cafe\u{0301}\")"});
+
+        let output = normalizer.normalize_resource("rules", &input);
+        assert_eq!(
+            output["ruleText"],
+            "RULE OnOrder\nlog.info(\"Caf\u{00e9}\")"
+        );
+    }
+
+    #[test]
+    fn test_extract_and_embed_code() {
+        let dir = TempDir::new().unwrap();
+        let procedures = dir.path().join("procedures");
+        fs::create_dir_all(&procedures).unwrap();
+        let code = "PROCEDURE Orders.total(id)\nreturn 1\n";
+        fs::write(
+            procedures.join("Orders.total.json"),
+            serde_json::json!({"name": "Orders.total", "ruleText": code}).to_string(),
+        )
+        .unwrap();
+
+        let config = NormalizationConfig {
+            extract_code: true,
+            ..Default::default()
+        };
+        ResourceNormalizer::new(config)
+            .normalize_export_directory(dir.path())
+            .unwrap();
+
+        let vail = procedures.join("Orders.total.vail");
+        assert_eq!(fs::read_to_string(&vail).unwrap(), code);
+        let json = fs::read_to_string(procedures.join("Orders.total.json")).unwrap();
+        assert!(!json.contains("PROCEDURE"));
+        assert!(has_extracted_code(dir.path()));
+
+        // Comparisons see the code again
+        let embedded = read_embedded(&procedures.join("Orders.total.json")).unwrap();
+        assert!(embedded.contains("PROCEDURE Orders.total(id)"));
+
+        let staging = stage_for_import(dir.path()).unwrap().unwrap();
+        let staged: Value = serde_json::from_str(
+            &fs::read_to_string(staging.path().join("procedures/Orders.total.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(staged["ruleText"], code);
+        assert!(!staging.path().join("procedures/Orders.total.vail").exists());
+
+        // The working tree is left untouched
+        assert!(vail.exists());
+    }
+
+    #[test]
+    fn test_preview_export_directory_writes_nothing() {
+        let dir = TempDir::new().unwrap();
+        let types = dir.path().join("types");
+        fs::create_dir_all(&types).unwrap();
+        let original = r#"{"name":"Order","ars_version":2}"#;
+        fs::write(types.join("Order.json"), original).unwrap();
+
+        let normalizer = ResourceNormalizer::new(NormalizationConfig::default());
+        let (stats, planned) = normalizer.preview_export_directory(dir.path()).unwrap();
+
+        assert_eq!(stats.files_processed, 1);
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].before.as_deref(), Some(original));
+        assert!(!planned[0].after.contains("ars_version"));
+        assert_eq!(
+            fs::read_to_string(types.join("Order.json")).unwrap(),
+            original
+        );
+
+        // Once normalized, there is nothing left to preview
+        normalizer.normalize_export_directory(dir.path()).unwrap();
+        let (_, planned) = normalizer.preview_export_directory(dir.path()).unwrap();
+        assert!(planned.is_empty());
+    }
+
+    #[test]
+    fn test_stage_for_import_without_references() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("rules")).unwrap();
+        fs::write(
+            dir.path().join("rules/OnOrder.json"),
+            r#"{"name": "OnOrder", "ruleText": "RULE OnOrder"}"#,
+        )
+        .unwrap();
+
+        assert!(stage_for_import(dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_embed_code_ignores_paths_outside_directory() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("passwd"), "secret").unwrap();
+        let nested = dir.path().join("rules");
+        fs::create_dir_all(&nested).unwrap();
+
+        let mut value = serde_json::json!({"ruleText": {"$vail": "../passwd"}});
+        assert!(embed_code(&mut value, &nested).is_err());
+    }
 }
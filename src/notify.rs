@@ -0,0 +1,285 @@
+//! Notifications when long operations finish (vqx extension)
+//!
+//! Commands listed in `[notify] commands` (export, import, sync and promote
+//! by default) send a summary to each `[[notify.channels]]` entry when they
+//! finish: a Slack incoming webhook, a generic webhook that receives the
+//! [`Notification`] as JSON, or a desktop notification. Scheduled jobs and
+//! `vqx serve` operations run vqx as a child process, so they notify too.
+//!
+//! Webhooks are sent with `curl` and desktop notifications with
+//! `notify-send` (Linux), `osascript` (macOS) or PowerShell (Windows). A
+//! notification that cannot be sent is logged as a warning and never changes
+//! the outcome of the command.
+
+use crate::cli::{Commands, SyncCommands};
+use crate::config::{NotifyChannel, NotifyConfig, NotifyKind, NotifyOn};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{debug, warn};
+
+/// How long one channel may take
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Summary of a finished run
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub command: String,
+    pub success: bool,
+    /// Profile the command ran against; `None` for the default profile
+    pub profile: Option<String>,
+    pub duration_seconds: f64,
+    /// Command-specific counts, e.g. `files_exported`
+    pub counts: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub finished_at: DateTime<Utc>,
+}
+
+impl Notification {
+    /// One line for chat and desktop notifications
+    pub fn summary(&self) -> String {
+        let mut line = format!(
+            "vqx {} {} on {} in {:.1}s",
+            self.command,
+            if self.success { "succeeded" } else { "failed" },
+            self.profile.as_deref().unwrap_or("the default profile"),
+            self.duration_seconds
+        );
+        if !self.counts.is_empty() {
+            let counts: Vec<String> = self
+                .counts
+                .iter()
+                .map(|(name, n)| format!("{} {}", name.replace('_', " "), n))
+                .collect();
+            line.push_str(&format!(" ({})", counts.join(", ")));
+        }
+        if let Some(ref error) = self.error {
+            line.push_str(&format!(": {}", error));
+        }
+        line
+    }
+}
+
+/// Name of a command that can notify, as used in `[notify] commands`
+pub fn command_name(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Export(_) => Some("export"),
+        Commands::Import(_) => Some("import"),
+        Commands::Sync(SyncCommands::Pull(_)) => Some("sync pull"),
+        Commands::Sync(SyncCommands::Push(_)) => Some("sync push"),
+        Commands::Promote(_) => Some("promote"),
+        _ => None,
+    }
+}
+
+/// Send a finished run to the configured channels
+///
+/// Dry runs, commands not in `[notify] commands` and runs shorter than
+/// `min_duration_secs` are not reported.
+pub async fn send(
+    config: &NotifyConfig,
+    command: &Commands,
+    profile: Option<&str>,
+    result: &anyhow::Result<i32>,
+    elapsed: Duration,
+    counts: BTreeMap<String, usize>,
+) {
+    if config.channels.is_empty() || command.is_dry_run() {
+        return;
+    }
+    let Some(name) = command_name(command) else {
+        return;
+    };
+    if !config.commands.iter().any(|c| c == name)
+        || elapsed < Duration::from_secs(config.min_duration_secs)
+    {
+        return;
+    }
+
+    let profile = match command {
+        Commands::Promote(args) => Some(args.to.as_str()),
+        _ => profile,
+    };
+    let notification = Notification {
+        command: name.to_string(),
+        success: matches!(result, Ok(code) if *code == crate::exit::SUCCESS),
+        profile: profile.map(String::from),
+        duration_seconds: elapsed.as_secs_f64(),
+        counts,
+        error: result.as_ref().err().map(|e| e.to_string()),
+        finished_at: Utc::now(),
+    };
+
+    for channel in &config.channels {
+        if channel.on == NotifyOn::Failure && notification.success {
+            continue;
+        }
+        match tokio::time::timeout(SEND_TIMEOUT, deliver(channel, &notification)).await {
+            Ok(Ok(())) => debug!(channel = ?channel.kind, "Notification sent"),
+            Ok(Err(e)) => warn!(channel = ?channel.kind, error = %e, "Notification failed"),
+            Err(_) => warn!(channel = ?channel.kind, "Notification timed out"),
+        }
+    }
+}
+
+async fn deliver(channel: &NotifyChannel, notification: &Notification) -> Result<(), String> {
+    match channel.kind {
+        NotifyKind::Slack | NotifyKind::Webhook => {
+            let url = channel
+                .url
+                .as_deref()
+                .ok_or_else(|| format!("{:?} channel has no url", channel.kind))?;
+            post_json(url, &payload(channel.kind, notification)).await
+        }
+        NotifyKind::Desktop => desktop(notification).await,
+    }
+}
+
+/// Body of a webhook notification
+fn payload(kind: NotifyKind, notification: &Notification) -> serde_json::Value {
+    match kind {
+        NotifyKind::Slack => {
+            let icon = if notification.success { "✅" } else { "❌" };
+            serde_json::json!({ "text": format!("{} {}", icon, notification.summary()) })
+        }
+        _ => serde_json::json!(notification),
+    }
+}
+
+/// POST JSON to `url` with curl, passing the body on stdin
+async fn post_json(url: &str, body: &serde_json::Value) -> Result<(), String> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--max-time",
+            "10",
+            "--header",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("could not run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(body.to_string().as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().await.map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+async fn desktop(notification: &Notification) -> Result<(), String> {
+    let title = format!(
+        "vqx {} {}",
+        notification.command,
+        if notification.success {
+            "succeeded"
+        } else {
+            "failed"
+        }
+    );
+    let body = notification.summary();
+
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("osascript");
+        command.arg("-e").arg(format!(
+            "display notification {} with title {}",
+            applescript_string(&body),
+            applescript_string(&title)
+        ));
+        command
+    } else if cfg!(windows) {
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-Command"]).arg(format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $n = New-Object System.Windows.Forms.NotifyIcon; \
+             $n.Icon = [System.Drawing.SystemIcons]::Information; \
+             $n.Visible = $true; \
+             $n.ShowBalloonTip(10000, {}, {}, 'Info'); \
+             Start-Sleep -Seconds 5; $n.Dispose()",
+            powershell_string(&title),
+            powershell_string(&body)
+        ));
+        command
+    } else {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name", "vqx"]).arg(&title).arg(&body);
+        command
+    };
+
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| format!("could not show a desktop notification: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn powershell_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut notification = Notification {
+            command: "export".to_string(),
+            success: true,
+            profile: Some("prod".to_string()),
+            duration_seconds: 12.34,
+            counts: BTreeMap::from([("files_exported".to_string(), 120)]),
+            error: None,
+            finished_at: Utc::now(),
+        };
+        assert_eq!(
+            notification.summary(),
+            "vqx export succeeded on prod in 12.3s (files exported 120)"
+        );
+        assert_eq!(
+            payload(NotifyKind::Slack, &notification)["text"],
+            "✅ vqx export succeeded on prod in 12.3s (files exported 120)"
+        );
+
+        notification.success = false;
+        notification.profile = None;
+        notification.counts.clear();
+        notification.error = Some("Connection refused".to_string());
+        assert_eq!(
+            notification.summary(),
+            "vqx export failed on the default profile in 12.3s: Connection refused"
+        );
+        assert_eq!(
+            payload(NotifyKind::Webhook, &notification)["success"],
+            false
+        );
+        assert_eq!(powershell_string("it's"), "'it''s'");
+    }
+}
@@ -0,0 +1,37 @@
+//! Offline mode (`--offline`)
+//!
+//! With `--offline` (or `VQX_OFFLINE`), vqx never runs the underlying CLI
+//! or talks to a server. Commands that work on local files run as usual:
+//! profiles, credentials and config, `normalize`, `validate`, `scan`,
+//! `rename`, and `diff`, `grep` and `stats` on export directories. Commands
+//! that need the server fail at once with [`VqxError::Offline`] instead of
+//! waiting for network timeouts.
+//!
+//! [`check`] is called by [`UnderlyingCli`](crate::underlying::UnderlyingCli)
+//! and the REST client before every call, and before a profile is
+//! exported for `diff`, `grep`, `stats` or `sync`, so nothing slips through.
+
+use crate::error::{Result, VqxError};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turn offline mode on for this run (`--offline`)
+pub fn configure(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether remote calls are forbidden
+pub fn enabled() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Refuse `operation` in offline mode
+pub fn check(operation: impl Into<String>) -> Result<()> {
+    if enabled() {
+        return Err(VqxError::Offline {
+            operation: operation.into(),
+        });
+    }
+    Ok(())
+}
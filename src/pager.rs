@@ -0,0 +1,95 @@
+//! Pager for long output
+//!
+//! Like git, text output of `diff`, `grep` and passthrough commands that
+//! does not fit on the screen is piped through a pager: `[output] pager` in
+//! config.toml, else `$PAGER`, else `less`. `less` runs with `LESS=FRX`
+//! unless `LESS` is set, so colors are kept and output that fits on one
+//! screen is printed as is.
+//!
+//! Nothing is paged with `--no-pager`, when stdout is not a terminal, or
+//! when the pager is empty or `cat`. A pager that cannot be started is
+//! skipped.
+
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use tracing::debug;
+
+static PAGER: OnceLock<Option<Vec<String>>> = OnceLock::new();
+
+/// Choose the pager (`--no-pager` disables it)
+pub fn configure(no_pager: bool, configured: Option<&str>) {
+    let pager = if no_pager {
+        None
+    } else {
+        resolve(configured, std::env::var("PAGER").ok().as_deref())
+    };
+    let _ = PAGER.set(pager);
+}
+
+/// Pager command line from `[output] pager` and `$PAGER`
+fn resolve(configured: Option<&str>, env: Option<&str>) -> Option<Vec<String>> {
+    let command = configured
+        .or(env)
+        .unwrap_or(if cfg!(windows) { "more" } else { "less" });
+    let words: Vec<String> = command.split_whitespace().map(String::from).collect();
+    match words.first().map(String::as_str) {
+        None | Some("cat") => None,
+        Some(_) => Some(words),
+    }
+}
+
+/// Print `text`, through the pager when it does not fit on the screen
+pub fn page(text: &str) {
+    if let Some(Some(pager)) = PAGER.get() {
+        if std::io::stdout().is_terminal() && exceeds_screen(text) && run(pager, text) {
+            return;
+        }
+    }
+    print!("{}", text);
+    let _ = std::io::stdout().flush();
+}
+
+fn exceeds_screen(text: &str) -> bool {
+    let (rows, _) = console::Term::stdout().size();
+    text.lines().count() >= rows as usize
+}
+
+/// Whether the pager showed `text`
+fn run(pager: &[String], text: &str) -> bool {
+    let mut command = Command::new(&pager[0]);
+    command.args(&pager[1..]).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            debug!(pager = %pager[0], error = %e, "Could not start pager");
+            return false;
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes the pipe
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve(Some("less -S"), Some("more")),
+            Some(vec!["less".to_string(), "-S".to_string()])
+        );
+        assert_eq!(resolve(None, Some("most")), Some(vec!["most".to_string()]));
+        assert_eq!(resolve(Some(""), Some("less")), None);
+        assert_eq!(resolve(None, Some("cat")), None);
+        assert!(resolve(None, None).is_some());
+    }
+}
@@ -0,0 +1,502 @@
+//! Unified diff patches of export trees (vqx extension)
+//!
+//! [`tree_patch`] writes a standard unified diff (`--- a/<path>`,
+//! `+++ b/<path>`, `/dev/null` for added and removed files) between two
+//! export directories, so it also works with `git apply` or `patch -p1`.
+//! [`parse`] and [`apply`] read such a patch back and apply it to a
+//! directory, refusing to change anything when a hunk does not match.
+//!
+//! Hunks are located like `patch` does without fuzz: at the line the patch
+//! expects, or else at the nearest place where the context and removed
+//! lines match exactly.
+
+use crate::error::{Result, VqxError};
+use crate::filter::ResourceFilter;
+use similar::TextDiff;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+const DEV_NULL: &str = "/dev/null";
+const NO_NEWLINE: &str = "\\ No newline at end of file";
+
+/// Unified diff of all files that differ between two directories
+///
+/// Only files of resources selected by `filter` are compared. Files
+/// that are not UTF-8 are listed as differing but have no hunks.
+pub fn tree_patch(source: &Path, target: &Path, filter: &ResourceFilter) -> Result<String> {
+    let source_files = tree_files(source, filter);
+    let target_files = tree_files(target, filter);
+    let paths: BTreeSet<&String> = source_files.iter().chain(&target_files).collect();
+
+    let mut out = String::new();
+    for rel in paths {
+        let old = source_files
+            .contains(rel)
+            .then(|| fs::read(source.join(rel)))
+            .transpose()?;
+        let new = target_files
+            .contains(rel)
+            .then(|| fs::read(target.join(rel)))
+            .transpose()?;
+        if old == new {
+            continue;
+        }
+
+        let old_name = old
+            .as_ref()
+            .map_or(DEV_NULL.to_string(), |_| format!("a/{}", rel));
+        let new_name = new
+            .as_ref()
+            .map_or(DEV_NULL.to_string(), |_| format!("b/{}", rel));
+        let as_text = |bytes: &Option<Vec<u8>>| match bytes {
+            Some(b) => String::from_utf8(b.clone()).ok(),
+            None => Some(String::new()),
+        };
+        match (as_text(&old), as_text(&new)) {
+            (Some(old), Some(new)) => {
+                let diff = TextDiff::from_lines(&old, &new);
+                out.push_str(
+                    &diff
+                        .unified_diff()
+                        .context_radius(3)
+                        .header(&old_name, &new_name)
+                        .to_string(),
+                );
+            }
+            _ => out.push_str(&format!(
+                "Binary files {} and {} differ\n",
+                old_name, new_name
+            )),
+        }
+    }
+    Ok(out)
+}
+
+/// Relative paths (with `/`) of the files below a directory
+fn tree_files(dir: &Path, filter: &ResourceFilter) -> BTreeSet<String> {
+    WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let rel = e.path().strip_prefix(dir).ok()?;
+            let parts: Vec<_> = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            filter.matches_path(rel).then(|| parts.join("/"))
+        })
+        .collect()
+}
+
+/// One line of a hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HunkLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+/// One `@@ -l,s +l,s @@` hunk; line texts include their line ending
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hunk {
+    /// First line of the hunk in the original file (1-based; 0 for empty files)
+    old_start: usize,
+    lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Remove(s) => Some(s.as_str()),
+                HunkLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn new_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|l| match l {
+                HunkLine::Context(s) | HunkLine::Add(s) => Some(s.as_str()),
+                HunkLine::Remove(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Changes to one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilePatch {
+    /// Path relative to the patched directory
+    pub path: String,
+    /// The file is created (`--- /dev/null`)
+    pub created: bool,
+    /// The file is removed (`+++ /dev/null`)
+    pub deleted: bool,
+    hunks: Vec<Hunk>,
+}
+
+/// Parse a unified diff
+///
+/// Lines outside file sections (`diff --git`, `index`, comments) are
+/// skipped; `a/` and `b/` prefixes are stripped from paths.
+pub fn parse(patch: &str) -> Result<Vec<FilePatch>> {
+    let invalid = |line: usize, message: &str| {
+        VqxError::Other(format!("Invalid patch at line {}: {}", line, message))
+    };
+
+    let lines: Vec<&str> = patch.split_inclusive('\n').collect();
+    let mut files = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(old) = lines[i].strip_prefix("--- ") else {
+            i += 1;
+            continue;
+        };
+        let new = lines
+            .get(i + 1)
+            .and_then(|l| l.strip_prefix("+++ "))
+            .ok_or_else(|| invalid(i + 2, "expected '+++' after '---'"))?;
+        let old = header_path(old);
+        let new = header_path(new);
+        let created = old == DEV_NULL;
+        let deleted = new == DEV_NULL;
+        let path = if deleted { old } else { new };
+        let path = strip_prefix(path, if deleted { "a/" } else { "b/" });
+        if created && deleted {
+            return Err(invalid(i + 1, "both sides are /dev/null"));
+        }
+        safe_relative(&path).ok_or_else(|| invalid(i + 1, &format!("unsafe path '{}'", path)))?;
+        i += 2;
+
+        let mut hunks = Vec::new();
+        while let Some(header) = lines.get(i).filter(|l| l.starts_with("@@")) {
+            let (old_start, old_count, new_count) =
+                parse_hunk_header(header).ok_or_else(|| invalid(i + 1, "bad hunk header"))?;
+            i += 1;
+
+            let (mut old_seen, mut new_seen) = (0, 0);
+            let mut hunk_lines = Vec::new();
+            while old_seen < old_count || new_seen < new_count {
+                let line = lines
+                    .get(i)
+                    .ok_or_else(|| invalid(i + 1, "hunk ends early"))?;
+                let (tag, text) = line.split_at(line.len().min(1));
+                // Some tools drop the space of empty context lines
+                let (tag, text) = if *line == "\n" || *line == "\r\n" {
+                    (" ", *line)
+                } else {
+                    (tag, text)
+                };
+                let text = text.to_string();
+                match tag {
+                    " " => {
+                        old_seen += 1;
+                        new_seen += 1;
+                        hunk_lines.push(HunkLine::Context(text));
+                    }
+                    "-" => {
+                        old_seen += 1;
+                        hunk_lines.push(HunkLine::Remove(text));
+                    }
+                    "+" => {
+                        new_seen += 1;
+                        hunk_lines.push(HunkLine::Add(text));
+                    }
+                    _ => return Err(invalid(i + 1, "unexpected line in hunk")),
+                }
+                i += 1;
+                if lines.get(i).is_some_and(|l| l.trim_end() == NO_NEWLINE) {
+                    strip_newline(hunk_lines.last_mut());
+                    i += 1;
+                }
+            }
+            hunks.push(Hunk {
+                old_start,
+                lines: hunk_lines,
+            });
+        }
+
+        files.push(FilePatch {
+            path,
+            created,
+            deleted,
+            hunks,
+        });
+    }
+    Ok(files)
+}
+
+/// Path of a `---`/`+++` header, without a trailing timestamp
+fn header_path(header: &str) -> &str {
+    let header = header.trim_end_matches(['\n', '\r']);
+    header.split('\t').next().unwrap_or(header).trim_end()
+}
+
+fn strip_prefix(path: &str, prefix: &str) -> String {
+    path.strip_prefix(prefix).unwrap_or(path).to_string()
+}
+
+/// The path as a relative path that stays inside the patched directory
+fn safe_relative(path: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(path);
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then_some(path)
+}
+
+/// `@@ -l[,s] +l[,s] @@` → (old start, old count, new count)
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = header.split_whitespace().skip(1);
+    let range = |part: &str, sign: char| -> Option<(usize, usize)> {
+        let part = part.strip_prefix(sign)?;
+        match part.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((part.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = range(parts.next()?, '-')?;
+    let (_, new_count) = range(parts.next()?, '+')?;
+    Some((old_start, old_count, new_count))
+}
+
+fn strip_newline(line: Option<&mut HunkLine>) {
+    if let Some(HunkLine::Context(s) | HunkLine::Remove(s) | HunkLine::Add(s)) = line {
+        let trimmed = s.trim_end_matches('\n').trim_end_matches('\r').len();
+        s.truncate(trimmed);
+    }
+}
+
+/// Why a file patch cannot be applied
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub path: String,
+    pub message: String,
+}
+
+/// New contents per file: `None` removes the file
+pub type Changes = BTreeMap<String, Option<String>>;
+
+/// Work out the new file contents for a patch against `dir`
+///
+/// Nothing is written: either every file applies and all changes are
+/// returned, or the conflicts are.
+pub fn apply(dir: &Path, files: &[FilePatch]) -> std::result::Result<Changes, Vec<Conflict>> {
+    let mut changes = Changes::new();
+    let mut conflicts = Vec::new();
+
+    for file in files {
+        let conflict = |message: String| Conflict {
+            path: file.path.clone(),
+            message,
+        };
+        let current = fs::read_to_string(dir.join(&file.path)).ok();
+
+        match (file.created, file.deleted, current) {
+            (true, _, Some(current)) => {
+                let new = file
+                    .hunks
+                    .iter()
+                    .flat_map(Hunk::new_lines)
+                    .collect::<String>();
+                if current != new {
+                    conflicts.push(conflict("already exists with other content".to_string()));
+                }
+            }
+            (true, _, None) => {
+                let new = file.hunks.iter().flat_map(Hunk::new_lines).collect();
+                changes.insert(file.path.clone(), Some(new));
+            }
+            // Removed before: nothing left to do
+            (false, true, None) => {}
+            (false, false, None) => conflicts.push(conflict("does not exist".to_string())),
+            (false, deleted, Some(current)) => match apply_hunks(&current, &file.hunks) {
+                Ok(new) if deleted && !new.is_empty() => conflicts.push(conflict(
+                    "has content the patch does not remove".to_string(),
+                )),
+                Ok(_) if deleted => {
+                    changes.insert(file.path.clone(), None);
+                }
+                Ok(new) => {
+                    changes.insert(file.path.clone(), Some(new));
+                }
+                Err(message) => conflicts.push(conflict(message)),
+            },
+        }
+    }
+
+    if conflicts.is_empty() {
+        Ok(changes)
+    } else {
+        Err(conflicts)
+    }
+}
+
+/// Apply hunks to a file's content
+fn apply_hunks(content: &str, hunks: &[Hunk]) -> std::result::Result<String, String> {
+    let mut lines: Vec<&str> = content.split_inclusive('\n').collect();
+    // Lines added or removed by earlier hunks shift later ones
+    let mut delta: isize = 0;
+
+    for (n, hunk) in hunks.iter().enumerate() {
+        let old = hunk.old_lines();
+        let new = hunk.new_lines();
+        let expected = (hunk.old_start.saturating_sub(1) as isize + delta).max(0) as usize;
+        // An empty hunk at 0 inserts at the start
+        let expected = if old.is_empty() && hunk.old_start > 0 {
+            (expected + 1).min(lines.len())
+        } else {
+            expected
+        };
+
+        let position = find_block(&lines, &old, expected).ok_or_else(|| {
+            format!(
+                "hunk {} (line {}) does not match the file",
+                n + 1,
+                hunk.old_start
+            )
+        })?;
+        lines.splice(position..position + old.len(), new.iter().copied());
+        delta += new.len() as isize - old.len() as isize;
+    }
+    Ok(lines.concat())
+}
+
+/// Position of `block` in `lines` nearest to `expected`
+fn find_block(lines: &[&str], block: &[&str], expected: usize) -> Option<usize> {
+    let matches_at =
+        |p: usize| p + block.len() <= lines.len() && lines[p..p + block.len()] == *block;
+    let last = lines.len().saturating_sub(block.len());
+    (0..=last.max(expected))
+        .flat_map(|offset| {
+            [expected.checked_sub(offset), expected.checked_add(offset)]
+                .into_iter()
+                .flatten()
+        })
+        .find(|&p| p <= last && matches_at(p))
+}
+
+/// Write computed changes to `dir`
+pub fn write_changes(dir: &Path, changes: &Changes) -> Result<()> {
+    for (rel, content) in changes {
+        let path = dir.join(rel);
+        match content {
+            Some(content) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent).map_err(|_| VqxError::FileWriteFailed {
+                        path: parent.display().to_string(),
+                    })?;
+                }
+                fs::write(&path, content).map_err(|_| VqxError::FileWriteFailed {
+                    path: path.display().to_string(),
+                })?;
+            }
+            None => fs::remove_file(&path).map_err(|_| VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            })?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn numbered(n: usize, changed: Option<usize>) -> String {
+        (1..=n)
+            .map(|i| {
+                if Some(i) == changed {
+                    format!("line {} changed\n", i)
+                } else {
+                    format!("line {}\n", i)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        write(source.path(), "types/Order.json", &numbered(20, None));
+        write(target.path(), "types/Order.json", &numbered(20, Some(10)));
+        write(source.path(), "types/Old.json", "{}\n");
+        write(target.path(), "procedures/New.vail", "PROCEDURE New()");
+        write(source.path(), "export.json", "{}\n");
+        write(target.path(), "export.json", "{}\n");
+
+        let patch = tree_patch(source.path(), target.path(), &ResourceFilter::default()).unwrap();
+        assert!(patch.contains("--- /dev/null\n+++ b/procedures/New.vail\n"));
+        assert!(patch.contains("--- a/types/Old.json\n+++ /dev/null\n"));
+        assert!(patch.contains("-line 10\n+line 10 changed\n"));
+        assert!(!patch.contains("export.json"));
+
+        let files = parse(&patch).unwrap();
+        assert_eq!(files.len(), 3);
+        let changes = apply(source.path(), &files).unwrap();
+        write_changes(source.path(), &changes).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(source.path().join("types/Order.json")).unwrap(),
+            numbered(20, Some(10))
+        );
+        assert_eq!(
+            fs::read_to_string(source.path().join("procedures/New.vail")).unwrap(),
+            "PROCEDURE New()"
+        );
+        assert!(!source.path().join("types/Old.json").exists());
+
+        // Filtered by resource type
+        let filter = ResourceFilter::new(&["types"], &[]).unwrap();
+        let patch = tree_patch(source.path(), target.path(), &filter).unwrap();
+        assert!(patch.is_empty());
+    }
+
+    #[test]
+    fn test_offset_and_conflict() {
+        let source = TempDir::new().unwrap();
+        let target = TempDir::new().unwrap();
+        write(source.path(), "types/Order.json", &numbered(20, None));
+        write(target.path(), "types/Order.json", &numbered(20, Some(10)));
+        let files =
+            parse(&tree_patch(source.path(), target.path(), &ResourceFilter::default()).unwrap())
+                .unwrap();
+
+        // Two lines inserted above the hunk: applies at an offset
+        let shifted = format!("extra\nextra\n{}", numbered(20, None));
+        write(source.path(), "types/Order.json", &shifted);
+        let changes = apply(source.path(), &files).unwrap();
+        assert!(changes["types/Order.json"]
+            .as_deref()
+            .unwrap()
+            .contains("extra\nextra\nline 1\n"));
+
+        // The changed line itself differs: conflict, nothing applied
+        write(source.path(), "types/Order.json", &numbered(20, Some(10)));
+        let conflicts = apply(source.path(), &files).unwrap_err();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].path, "types/Order.json");
+        assert!(conflicts[0].message.contains("hunk 1"));
+    }
+
+    #[test]
+    fn test_parse_rejects_unsafe_paths() {
+        let patch = "--- a/../etc/passwd\n+++ b/../etc/passwd\n@@ -1 +1 @@\n-a\n+b\n";
+        assert!(parse(patch).is_err());
+        assert_eq!(parse_hunk_header("@@ -3,7 +3,8 @@ fn x"), Some((3, 7, 8)));
+        assert_eq!(parse_hunk_header("@@ -0,0 +1 @@"), Some((0, 0, 1)));
+    }
+}
@@ -0,0 +1,205 @@
+//! Operation policies per profile (vqx extension)
+//!
+//! `[policy.<profile glob>]` sections in config.toml restrict which
+//! underlying operations may run against matching profiles:
+//!
+//! ```toml
+//! [policy.prod]
+//! deny = ["deleteMatching", "import data"]
+//!
+//! [policy."dev-*"]
+//! allow = ["list", "find", "select", "export", "import", "run"]
+//! ```
+//!
+//! A rule is a command glob, optionally followed by a glob over the
+//! command's target: the resource type for list, find, select, insert,
+//! upsert, delete and deleteMatching, the type (`metadata`, `data`, ...) for
+//! export and import. A call is refused when any deny rule of a matching
+//! section matches it, or when a matching section has an allow list and none
+//! of its rules match.
+//!
+//! The policy is installed once at startup and checked by
+//! [`UnderlyingCli`](crate::underlying::UnderlyingCli) before every CLI
+//! call, for REST calls and for passthrough commands, so every vqx command
+//! is covered. Calls without a vqx profile are not checked.
+
+use crate::commands::list::glob_to_regex;
+use crate::error::{Result, VqxError};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Rules of one `[policy.<profile glob>]` section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PolicyRules {
+    /// Only these operations are allowed (empty: all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+
+    /// These operations are refused
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+/// One compiled rule
+#[derive(Debug)]
+struct Rule {
+    text: String,
+    command: Regex,
+    target: Option<Regex>,
+}
+
+impl Rule {
+    fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (command, target) = match text.split_once(char::is_whitespace) {
+            Some((command, target)) => (command, Some(glob_to_regex(target.trim())?)),
+            None => (text, None),
+        };
+        Ok(Self {
+            text: text.to_string(),
+            command: glob_to_regex(command)?,
+            target,
+        })
+    }
+
+    fn matches(&self, command: &str, target: &str) -> bool {
+        self.command.is_match(command) && self.target.as_ref().is_none_or(|t| t.is_match(target))
+    }
+}
+
+#[derive(Debug)]
+struct Section {
+    profiles: Regex,
+    allow: Vec<Rule>,
+    deny: Vec<Rule>,
+}
+
+/// Compiled `[policy]` sections
+#[derive(Debug, Default)]
+pub struct Policy {
+    sections: Vec<Section>,
+}
+
+static INSTALLED: OnceLock<Policy> = OnceLock::new();
+
+impl Policy {
+    /// Compile the `[policy]` table of config.toml
+    pub fn compile(config: &BTreeMap<String, PolicyRules>) -> Result<Self> {
+        let rules = |list: &[String]| list.iter().map(|r| Rule::parse(r)).collect::<Result<_>>();
+        let sections = config
+            .iter()
+            .map(|(profiles, section)| {
+                Ok(Section {
+                    profiles: glob_to_regex(profiles)?,
+                    allow: rules(&section.allow)?,
+                    deny: rules(&section.deny)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { sections })
+    }
+
+    /// Make this the policy checked by [`check`]
+    ///
+    /// Only the first call has an effect.
+    pub fn install(self) {
+        let _ = INSTALLED.set(self);
+    }
+
+    /// Check an operation against a profile's rules
+    pub fn evaluate(&self, profile: &str, command: &str, args: &[String]) -> Result<()> {
+        let target = target(command, args);
+        for section in self
+            .sections
+            .iter()
+            .filter(|s| s.profiles.is_match(profile))
+        {
+            let reason =
+                if let Some(rule) = section.deny.iter().find(|r| r.matches(command, &target)) {
+                    format!("denied by '{}'", rule.text)
+                } else if !section.allow.is_empty()
+                    && !section.allow.iter().any(|r| r.matches(command, &target))
+                {
+                    "not in the allow list".to_string()
+                } else {
+                    continue;
+                };
+            return Err(VqxError::PolicyDenied {
+                profile: profile.to_string(),
+                operation: format!("{} {}", command, target).trim_end().to_string(),
+                reason,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// What a command acts on: its first positional argument
+///
+/// export and import default to `metadata`, as in the CLI.
+fn target(command: &str, args: &[String]) -> String {
+    match args.first() {
+        Some(arg) if !arg.starts_with('-') => arg.clone(),
+        _ if matches!(command, "export" | "import") => "metadata".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Check an operation against the installed policy
+pub fn check(profile: Option<&str>, command: &str, args: &[String]) -> Result<()> {
+    match (INSTALLED.get(), profile) {
+        (Some(policy), Some(profile)) => policy.evaluate(profile, command, args),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(toml: &str) -> Policy {
+        let config: BTreeMap<String, PolicyRules> = toml::from_str(toml).unwrap();
+        Policy::compile(&config).unwrap()
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_deny_rules() {
+        let policy =
+            policy("[prod]\ndeny = [\"deleteMatching\", \"import data\", \"delete procedures\"]\n");
+        assert!(policy
+            .evaluate("prod", "deleteMatching", &args(&["types", "{}"]))
+            .is_err());
+        let err = policy
+            .evaluate("prod", "import", &args(&["data", "-d", "./data"]))
+            .unwrap_err();
+        assert!(err.to_string().contains("import data"));
+        assert!(policy
+            .evaluate("prod", "import", &args(&["-d", "./src"]))
+            .is_ok());
+        assert!(policy
+            .evaluate("prod", "delete", &args(&["types", "Order"]))
+            .is_ok());
+        assert!(policy
+            .evaluate("dev", "deleteMatching", &args(&["types", "{}"]))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let policy = policy("[\"dev-*\"]\nallow = [\"list\", \"select\", \"export\"]\n");
+        assert!(policy.evaluate("dev-eu", "list", &args(&["types"])).is_ok());
+        let err = policy
+            .evaluate("dev-eu", "delete", &args(&["types", "Order"]))
+            .unwrap_err();
+        assert!(
+            matches!(err, VqxError::PolicyDenied { ref reason, .. } if reason == "not in the allow list")
+        );
+        assert!(policy.evaluate("dev", "delete", &args(&["types"])).is_ok());
+    }
+}
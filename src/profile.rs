@@ -8,14 +8,17 @@
 //! vqx extends this with:
 //! - TOML-based configuration for better tooling support
 //! - Secure credential storage via keyring or encrypted files
+//! - Named credentials shared by several profiles
 //! - Environment variable integration
 //! - Interactive profile creation
 
 use crate::error::{Result, VqxError};
+use crate::secure_file;
+use chrono::{DateTime, Utc};
 #[cfg(windows)]
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
@@ -32,6 +35,10 @@ const CONFIG_DIR_NAME: &str = "vqx";
 /// Profile file name
 const PROFILES_FILE: &str = "profiles.toml";
 
+/// Sessions this close to expiry are no longer used, so a command does not
+/// start with a token that runs out midway
+const SESSION_MARGIN_SECS: i64 = 60;
+
 /// A single profile configuration
 ///
 /// Maps to the profile entries in PDF "Profile" section:
@@ -97,6 +104,146 @@ pub struct Profile {
     /// If true, password/token should be retrieved from keyring
     #[serde(default)]
     pub use_secure_storage: bool,
+
+    /// Named credential shared with other profiles (vqx extension)
+    /// When set, the credential's username/password/token replace the profile's own
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential: Option<String>,
+
+    /// When the password/token was first set (vqx extension)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_created_at: Option<DateTime<Utc>>,
+
+    /// When the password/token was last set (vqx extension)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_rotated_at: Option<DateTime<Utc>>,
+
+    /// Warn when credentials are older than this many days (vqx extension)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_credential_age_days: Option<u32>,
+
+    /// Refuse destructive operations while credentials are overdue (vqx extension)
+    #[serde(default)]
+    pub block_stale_credentials: bool,
+
+    /// How vqx talks to the server (vqx extension)
+    #[serde(default, skip_serializing_if = "Backend::is_cli")]
+    pub backend: Backend,
+
+    /// Values for `{{vars.<name>}}` placeholders in exports (vqx extension)
+    /// See [`crate::template`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub vars: BTreeMap<String, String>,
+
+    /// Guardrail for commands that change the server (vqx extension)
+    #[serde(default, skip_serializing_if = "Protection::is_open")]
+    pub protection: Protection,
+
+    /// How the password/token reach the underlying CLI (vqx extension)
+    #[serde(default, skip_serializing_if = "CredentialPassing::is_auto")]
+    pub credential_passing: CredentialPassing,
+
+    /// Profile in the underlying CLI's profile file to use instead of own
+    /// credentials, passed as `-s` (vqx extension)
+    /// See [`crate::vantiq_profile`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vantiq_profile: Option<String>,
+
+    /// Profile file of `vantiq_profile` when not `~/.vantiq/profile`, passed as `-f`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vantiq_profile_file: Option<String>,
+
+    /// Short-lived access token from `vqx login`, used instead of the
+    /// username/password until it expires (vqx extension)
+    /// Kept in the keyring instead when `use_secure_storage` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_token: Option<String>,
+
+    /// When `session_token` expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_expires_at: Option<DateTime<Utc>>,
+
+    /// Name in the profile store, set when loaded by name
+    /// Used to match `[policy]` sections, see [`crate::policy`]
+    #[serde(skip)]
+    pub name: Option<String>,
+}
+
+/// Guardrail for commands that change the server (vqx extension)
+///
+/// Applies to import, sync push, safe-delete, deploy/undeploy, insert/upsert,
+/// promote (on the target), run, ci sandbox, serve, schedule run and
+/// mutating passthrough commands; sync watch refuses protected profiles.
+/// Dry runs are always allowed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Protection {
+    /// No extra checks
+    #[default]
+    Open,
+    /// Type the profile name (or pass --confirm-profile) to proceed
+    Confirm,
+    /// Refuse such commands
+    ReadOnly,
+}
+
+impl Protection {
+    pub fn is_open(&self) -> bool {
+        *self == Protection::Open
+    }
+}
+
+/// How the password/token reach the underlying CLI (vqx extension)
+///
+/// On the command line (`-p`/`-t`) they show up in `ps` output to other
+/// users. `file` writes them to a temporary profile file readable by the
+/// owner only and passes `-s`/`-f` instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialPassing {
+    /// `file` for protected profiles, `args` otherwise
+    #[default]
+    Auto,
+    /// Command line options
+    Args,
+    /// Temporary profile file
+    File,
+}
+
+impl CredentialPassing {
+    pub fn is_auto(&self) -> bool {
+        *self == CredentialPassing::Auto
+    }
+
+    /// Whether credentials go through a profile file for a profile with `protection`
+    pub fn uses_file(&self, protection: Protection) -> bool {
+        match self {
+            CredentialPassing::Auto => !protection.is_open(),
+            CredentialPassing::Args => false,
+            CredentialPassing::File => true,
+        }
+    }
+}
+
+/// Transport used for a profile's operations (vqx extension)
+///
+/// `rest` calls the Vantiq REST API directly for list, find, select,
+/// insert, upsert and delete, avoiding the JVM startup of the CLI. Other
+/// operations (export, import, run, ...) always use the CLI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// The Vantiq CLI
+    #[default]
+    Cli,
+    /// The Vantiq REST API (requires the `rest-backend` feature)
+    Rest,
+}
+
+impl Backend {
+    pub fn is_cli(&self) -> bool {
+        *self == Backend::Cli
+    }
 }
 
 fn default_url() -> String {
@@ -115,6 +262,20 @@ impl Default for Profile {
             client_options: None,
             description: None,
             use_secure_storage: false,
+            credential: None,
+            credential_created_at: None,
+            credential_rotated_at: None,
+            max_credential_age_days: None,
+            block_stale_credentials: false,
+            backend: Backend::Cli,
+            vars: BTreeMap::new(),
+            protection: Protection::Open,
+            credential_passing: CredentialPassing::Auto,
+            vantiq_profile: None,
+            vantiq_profile_file: None,
+            session_token: None,
+            session_expires_at: None,
+            name: None,
         }
     }
 }
@@ -130,6 +291,7 @@ impl Profile {
 
     /// Set username/password authentication
     /// PDF Note: "username/password can only be used for Edge servers"
+    #[cfg(test)]
     pub fn with_credentials(
         mut self,
         username: impl Into<String>,
@@ -142,6 +304,7 @@ impl Profile {
 
     /// Set token authentication
     /// PDF Note: "public clouds and any server using keycloak access require use of the token option"
+    #[cfg(test)]
     pub fn with_token(mut self, token: impl Into<String>) -> Self {
         self.token = Some(token.into());
         self
@@ -149,20 +312,30 @@ impl Profile {
 
     /// Set target namespace
     /// PDF Note: "the namespace option can only be used with username/password"
+    #[cfg(test)]
     pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
         self.namespace = Some(namespace.into());
         self
     }
 
-    /// Enable SSL trust
-    pub fn with_trust_ssl(mut self) -> Self {
-        self.trust_ssl = true;
-        self
+    /// Record that the password/token was set at `now`
+    pub fn touch_credential(&mut self, now: DateTime<Utc>) {
+        self.credential_created_at.get_or_insert(now);
+        self.credential_rotated_at = Some(now);
+    }
+
+    /// Whether a `vqx login` session is still usable at `now`
+    pub fn has_session(&self, now: DateTime<Utc>) -> bool {
+        self.session_expires_at.is_some_and(|expires_at| {
+            expires_at > now + chrono::Duration::seconds(SESSION_MARGIN_SECS)
+        })
     }
 
     /// Check if profile has valid authentication
     pub fn has_auth(&self) -> bool {
-        self.token.is_some() || (self.username.is_some() && self.password.is_some())
+        self.token.is_some()
+            || (self.username.is_some() && self.password.is_some())
+            || self.vantiq_profile.is_some()
     }
 
     /// Get authentication type description
@@ -171,6 +344,8 @@ impl Profile {
             "username/password"
         } else if self.token.is_some() {
             "access token"
+        } else if self.vantiq_profile.is_some() {
+            "CLI profile"
         } else {
             "none"
         }
@@ -186,6 +361,77 @@ impl Profile {
         Ok(())
     }
 
+    /// Mask sensitive fields for display
+    pub fn masked(&self) -> Self {
+        Self {
+            password: self.password.as_ref().map(|_| "********".to_string()),
+            token: self.token.as_ref().map(|_| "********".to_string()),
+            session_token: self.session_token.as_ref().map(|_| "********".to_string()),
+            client_options: self.client_options.as_ref().map(|c| ClientOptions {
+                proxy: c.proxy.as_ref().map(|p| ProxyOptions {
+                    password: p.password.as_ref().map(|_| "********".to_string()),
+                    ..p.clone()
+                }),
+                ..c.clone()
+            }),
+            ..self.clone()
+        }
+    }
+}
+
+/// A named credential that several profiles can reference
+///
+/// Rotating the credential updates every profile that uses it, e.g. one CI
+/// token shared by the dev, test and prod profiles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Credential {
+    /// Role label describing what the credential is for (e.g. "ci", "deploy")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Password/token are stored in the keyring rather than in the file
+    #[serde(default)]
+    pub use_secure_storage: bool,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rotated_at: Option<DateTime<Utc>>,
+}
+
+impl Credential {
+    /// Record that the password/token was set at `now`
+    pub fn touch(&mut self, now: DateTime<Utc>) {
+        self.created_at.get_or_insert(now);
+        self.rotated_at = Some(now);
+    }
+
+    /// Get authentication type description
+    pub fn auth_type(&self) -> &'static str {
+        if self.use_secure_storage && self.password.is_none() && self.token.is_none() {
+            "secure storage"
+        } else if self.password.is_some() && self.username.is_some() {
+            "username/password"
+        } else if self.token.is_some() {
+            "access token"
+        } else {
+            "none"
+        }
+    }
+
     /// Mask sensitive fields for display
     pub fn masked(&self) -> Self {
         Self {
@@ -194,6 +440,47 @@ impl Profile {
             ..self.clone()
         }
     }
+
+    /// Apply this credential's authentication to a profile
+    ///
+    /// The credential replaces the profile's own authentication: a password
+    /// credential drops the profile's token, a token credential its username
+    /// and password (which the CLI would use instead of the token).
+    fn apply_to(&self, profile: &mut Profile) {
+        if self.password.is_some() {
+            if self.username.is_some() {
+                profile.username = self.username.clone();
+            }
+            profile.password = self.password.clone();
+            profile.token = None;
+        } else if self.token.is_some() {
+            profile.username = None;
+            profile.password = None;
+            profile.token = self.token.clone();
+        } else if self.username.is_some() {
+            profile.username = self.username.clone();
+        }
+    }
+}
+
+/// Age of a profile's credentials against its policy
+#[derive(Debug, Clone, PartialEq)]
+pub struct CredentialAge {
+    pub age_days: i64,
+    pub max_days: u32,
+    /// Destructive operations are refused while overdue
+    pub blocking: bool,
+}
+
+impl CredentialAge {
+    pub fn is_overdue(&self) -> bool {
+        self.age_days > i64::from(self.max_days)
+    }
+}
+
+/// Secure storage owner for a named credential's secrets
+fn credential_secret_owner(name: &str) -> String {
+    format!("credential-{}", name)
 }
 
 /// HTTP client options
@@ -206,7 +493,7 @@ impl Profile {
 ///     proxyOptions { ... }
 /// }
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ClientOptions {
     #[serde(default)]
     pub trust_all: bool,
@@ -238,7 +525,7 @@ impl Default for ClientOptions {
 
 /// Proxy configuration
 /// Based on PDF "proxyOptions" section
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ProxyOptions {
     pub host: String,
     pub port: u16,
@@ -258,6 +545,10 @@ pub struct ProfileStore {
     /// All profiles
     #[serde(default)]
     pub profiles: HashMap<String, Profile>,
+
+    /// Named credentials shared by profiles
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub credentials: BTreeMap<String, Credential>,
 }
 
 fn default_profile_name() -> String {
@@ -265,11 +556,6 @@ fn default_profile_name() -> String {
 }
 
 impl ProfileStore {
-    /// Create a new empty profile store
-    pub fn new() -> Self {
-        Self::default()
-    }
-
     /// Get the config directory path
     /// Uses ~/.config/vqx on Unix (macOS/Linux) for consistency with documentation
     /// Uses %APPDATA%\vqx on Windows
@@ -307,15 +593,30 @@ impl ProfileStore {
     }
 
     /// Load profiles from a specific file
+    ///
+    /// `<path>.enc`, when present, is decrypted and used instead.
     pub fn load_from(path: &Path) -> Result<Self> {
-        if !path.exists() {
+        let encrypted = secure_file::encrypted_path(path);
+        let content = if encrypted.exists() {
+            if path.exists() {
+                warn!(
+                    path = %path.display(),
+                    "Ignoring plaintext profiles file; the encrypted one is used"
+                );
+            }
+            let bytes = fs::read(&encrypted).map_err(|_e| VqxError::ProfileFileNotFound {
+                path: encrypted.display().to_string(),
+            })?;
+            secure_file::decrypt(&bytes, &secure_file::passphrase(&encrypted)?)?
+        } else if path.exists() {
+            secure_file::warn_if_shared(path);
+            fs::read_to_string(path).map_err(|_e| VqxError::ProfileFileNotFound {
+                path: path.display().to_string(),
+            })?
+        } else {
             debug!(path = %path.display(), "Profile file not found, using defaults");
             return Ok(Self::default());
-        }
-
-        let content = fs::read_to_string(path).map_err(|_e| VqxError::ProfileFileNotFound {
-            path: path.display().to_string(),
-        })?;
+        };
 
         let store: Self = toml::from_str(&content)?;
         info!(
@@ -326,13 +627,9 @@ impl ProfileStore {
         Ok(store)
     }
 
-    /// Save profiles to the default location
-    pub fn save(&self) -> Result<()> {
-        let path = Self::profiles_file_path()?;
-        self.save_to(&path)
-    }
-
-    /// Save profiles to a specific file
+    /// Save profiles to a specific file, readable by the owner only
+    ///
+    /// An encrypted store (`<path>.enc`) stays encrypted.
     pub fn save_to(&self, path: &Path) -> Result<()> {
         // Ensure parent directory exists
         if let Some(parent) = path.parent() {
@@ -345,7 +642,17 @@ impl ProfileStore {
             message: e.to_string(),
         })?;
 
-        fs::write(path, content).map_err(|_| VqxError::FileWriteFailed {
+        let encrypted = secure_file::encrypted_path(path);
+        let (path, content) = if encrypted.exists() {
+            let passphrase = secure_file::passphrase(&encrypted)?;
+            (
+                encrypted.as_path(),
+                secure_file::encrypt(&content, &passphrase)?,
+            )
+        } else {
+            (path, content.into_bytes())
+        };
+        secure_file::write_private(path, content).map_err(|_| VqxError::FileWriteFailed {
             path: path.display().to_string(),
         })?;
 
@@ -360,19 +667,6 @@ impl ProfileStore {
         })
     }
 
-    /// Get the default profile
-    pub fn get_default(&self) -> Result<&Profile> {
-        self.get(&self.default_profile)
-    }
-
-    /// Get a profile, falling back to default if name is None
-    pub fn get_or_default(&self, name: Option<&str>) -> Result<&Profile> {
-        match name {
-            Some(n) => self.get(n),
-            None => self.get_default(),
-        }
-    }
-
     /// Add or update a profile
     pub fn set(&mut self, name: impl Into<String>, profile: Profile) {
         let name = name.into();
@@ -406,6 +700,85 @@ impl ProfileStore {
         self.profiles.contains_key(name)
     }
 
+    /// Get a named credential
+    pub fn get_credential(&self, name: &str) -> Result<&Credential> {
+        self.credentials
+            .get(name)
+            .ok_or(VqxError::CredentialNotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Add or update a named credential
+    pub fn set_credential(&mut self, name: impl Into<String>, credential: Credential) {
+        let name = name.into();
+        info!(name = %name, "Setting credential");
+        self.credentials.insert(name, credential);
+    }
+
+    /// Remove a named credential
+    pub fn remove_credential(&mut self, name: &str) -> Option<Credential> {
+        info!(name = %name, "Removing credential");
+        self.credentials.remove(name)
+    }
+
+    /// Names of the profiles that reference a credential, sorted
+    pub fn profiles_using(&self, credential: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .profiles
+            .iter()
+            .filter(|(_, p)| p.credential.as_deref() == Some(credential))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Age of a profile's credentials at `now`
+    ///
+    /// Uses the named credential's dates when the profile references one.
+    /// Returns None when the profile has no age limit or the dates are unknown
+    /// (e.g. profiles created before dates were tracked).
+    pub fn credential_age(&self, name: &str, now: DateTime<Utc>) -> Result<Option<CredentialAge>> {
+        let profile = self.get(name)?;
+        let Some(max_days) = profile.max_credential_age_days else {
+            return Ok(None);
+        };
+
+        let rotated = match profile.credential {
+            Some(ref credential) => {
+                let credential = self.get_credential(credential)?;
+                credential.rotated_at.or(credential.created_at)
+            }
+            None => profile
+                .credential_rotated_at
+                .or(profile.credential_created_at),
+        };
+
+        Ok(rotated.map(|rotated| CredentialAge {
+            age_days: (now - rotated).num_days(),
+            max_days,
+            blocking: profile.block_stale_credentials,
+        }))
+    }
+
+    /// Copy of the store with passwords and tokens masked
+    pub fn masked(&self) -> Self {
+        Self {
+            default_profile: self.default_profile.clone(),
+            profiles: self
+                .profiles
+                .iter()
+                .map(|(name, p)| (name.clone(), p.masked()))
+                .collect(),
+            credentials: self
+                .credentials
+                .iter()
+                .map(|(name, c)| (name.clone(), c.masked()))
+                .collect(),
+        }
+    }
+
     /// Export to TOML string
     pub fn to_toml(&self) -> Result<String> {
         toml::to_string_pretty(self).map_err(|e| VqxError::InvalidToml {
@@ -442,6 +815,7 @@ impl ProfileManager {
     }
 
     /// Create with a specific path
+    #[cfg(test)]
     pub fn with_path(path: PathBuf) -> Result<Self> {
         let store = ProfileStore::load_from(&path)?;
         Ok(Self {
@@ -466,7 +840,17 @@ impl ProfileManager {
     }
 
     /// Get a profile with credentials resolved (from secure storage if needed)
+    ///
+    /// While a `vqx login` session is valid, its token replaces the
+    /// username/password.
     pub fn get_resolved(&self, name: &str) -> Result<Profile> {
+        let mut profile = self.get_resolved_stored(name)?;
+        self.apply_session(name, &mut profile)?;
+        Ok(profile)
+    }
+
+    /// Get a profile with its stored credentials resolved, ignoring a session
+    pub fn get_resolved_stored(&self, name: &str) -> Result<Profile> {
         let profile = self.store.get(name)?;
         self.resolve_credentials(name, profile.clone())
     }
@@ -478,6 +862,9 @@ impl ProfileManager {
     }
 
     /// Resolve credentials from secure storage if needed
+    ///
+    /// A named credential, when referenced, is applied after the profile's own
+    /// secrets so that it takes precedence.
     fn resolve_credentials(&self, name: &str, mut profile: Profile) -> Result<Profile> {
         if profile.use_secure_storage {
             // Try to get credentials from keyring
@@ -489,11 +876,77 @@ impl ProfileManager {
                 if let Some(token) = self.get_secret(name, "token")? {
                     profile.token = Some(token);
                 }
+                if let Some(proxy) = profile
+                    .client_options
+                    .as_mut()
+                    .and_then(|c| c.proxy.as_mut())
+                {
+                    if let Some(password) = self.get_secret(name, "proxy_password")? {
+                        proxy.password = Some(password);
+                    }
+                }
             }
         }
+
+        if let Some(ref credential_name) = profile.credential {
+            let credential = self.get_resolved_credential(credential_name)?;
+            credential.apply_to(&mut profile);
+        }
+
+        profile.name = Some(name.to_string());
         Ok(profile)
     }
 
+    /// Authenticate with the session token while it is valid
+    fn apply_session(&self, name: &str, profile: &mut Profile) -> Result<()> {
+        if profile.session_expires_at.is_none() {
+            return Ok(());
+        }
+        if !profile.has_session(Utc::now()) {
+            debug!(
+                profile = name,
+                "Session expired, using the stored credentials"
+            );
+            return Ok(());
+        }
+        let token = if profile.use_secure_storage {
+            self.get_secret(name, "session_token")?
+        } else {
+            profile.session_token.clone()
+        };
+        if let Some(token) = token {
+            profile.token = Some(token);
+            profile.username = None;
+            profile.password = None;
+        }
+        Ok(())
+    }
+
+    /// Get a named credential with secrets resolved from secure storage
+    pub fn get_resolved_credential(&self, name: &str) -> Result<Credential> {
+        let mut credential = self.store.get_credential(name)?.clone();
+        if credential.use_secure_storage {
+            let owner = credential_secret_owner(name);
+            if let Some(password) = self.get_secret(&owner, "password")? {
+                credential.password = Some(password);
+            }
+            if let Some(token) = self.get_secret(&owner, "token")? {
+                credential.token = Some(token);
+            }
+        }
+        Ok(credential)
+    }
+
+    /// Store a named credential's secret in secure storage
+    pub fn set_credential_secret(&self, name: &str, key: &str, value: &str) -> Result<()> {
+        self.set_secret(&credential_secret_owner(name), key, value)
+    }
+
+    /// Delete a named credential's secret from secure storage
+    pub fn delete_credential_secret(&self, name: &str, key: &str) -> Result<()> {
+        self.delete_secret(&credential_secret_owner(name), key)
+    }
+
     /// Store a secret in secure storage
     #[cfg(feature = "keyring-storage")]
     pub fn set_secret(&self, profile_name: &str, key: &str, value: &str) -> Result<()> {
@@ -618,7 +1071,7 @@ mod tests {
 
     #[test]
     fn test_profile_store_roundtrip() {
-        let mut store = ProfileStore::new();
+        let mut store = ProfileStore::default();
         store.set(
             "test",
             Profile::new("https://test.vantiq.com").with_token("token"),
@@ -631,6 +1084,22 @@ mod tests {
         assert_eq!(loaded.get("test").unwrap().url, "https://test.vantiq.com");
     }
 
+    #[test]
+    fn test_profile_protection() {
+        let profile: Profile =
+            toml::from_str("url = \"https://prod.vantiq.com\"\nprotection = \"read-only\"\n")
+                .unwrap();
+        assert_eq!(profile.protection, Protection::ReadOnly);
+
+        // Open profiles keep their files unchanged
+        let toml = toml::to_string(&Profile::default()).unwrap();
+        assert!(!toml.contains("protection"));
+        assert_eq!(
+            toml::from_str::<Profile>(&toml).unwrap().protection,
+            Protection::Open
+        );
+    }
+
     #[test]
     fn test_profile_masked() {
         let profile = Profile::new("https://test.vantiq.com")
@@ -642,4 +1111,108 @@ mod tests {
         assert_eq!(masked.password, Some("********".to_string()));
         assert_eq!(masked.token, Some("********".to_string()));
     }
+
+    #[test]
+    fn test_shared_credential_resolution() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("profiles.toml");
+
+        let mut store = ProfileStore::default();
+        store.set_credential(
+            "svc-ci-token",
+            Credential {
+                role: Some("ci".to_string()),
+                token: Some("rotated".to_string()),
+                ..Default::default()
+            },
+        );
+        for name in ["dev", "prod"] {
+            let mut profile = Profile::new("https://test.vantiq.com").with_token("stale");
+            profile.credential = Some("svc-ci-token".to_string());
+            store.set(name, profile);
+        }
+        let mut orphan = Profile::new("https://test.vantiq.com");
+        orphan.credential = Some("missing".to_string());
+        store.set("orphan", orphan);
+        store.save_to(&path).unwrap();
+
+        let manager = ProfileManager::with_path(path).unwrap();
+        assert_eq!(
+            manager.get_resolved("prod").unwrap().token.as_deref(),
+            Some("rotated")
+        );
+        assert_eq!(
+            manager.store().profiles_using("svc-ci-token"),
+            vec!["dev", "prod"]
+        );
+        assert!(matches!(
+            manager.get_resolved("orphan"),
+            Err(VqxError::CredentialNotFound { .. })
+        ));
+
+        let masked = manager.store().masked();
+        assert_eq!(
+            masked.credentials["svc-ci-token"].token.as_deref(),
+            Some("********")
+        );
+    }
+
+    #[test]
+    fn test_credential_replaces_profile_auth() {
+        let token = Credential {
+            token: Some("svc".to_string()),
+            ..Default::default()
+        };
+        let mut profile =
+            Profile::new("https://test.vantiq.com").with_credentials("user", "own-password");
+        token.apply_to(&mut profile);
+        assert_eq!(profile.token.as_deref(), Some("svc"));
+        assert!(profile.username.is_none() && profile.password.is_none());
+
+        let password = Credential {
+            username: Some("svc-user".to_string()),
+            password: Some("svc-password".to_string()),
+            ..Default::default()
+        };
+        let mut profile = Profile::new("https://test.vantiq.com").with_token("own-token");
+        password.apply_to(&mut profile);
+        assert_eq!(profile.username.as_deref(), Some("svc-user"));
+        assert_eq!(profile.password.as_deref(), Some("svc-password"));
+        assert!(profile.token.is_none());
+    }
+
+    #[test]
+    fn test_credential_age() {
+        let now = Utc::now();
+        let mut store = ProfileStore::default();
+
+        let mut profile = Profile::new("https://test.vantiq.com").with_token("t");
+        profile.touch_credential(now - chrono::Duration::days(100));
+        profile.max_credential_age_days = Some(90);
+        profile.block_stale_credentials = true;
+        store.set("prod", profile);
+
+        let age = store.credential_age("prod", now).unwrap().unwrap();
+        assert_eq!(age.age_days, 100);
+        assert!(age.is_overdue());
+        assert!(age.blocking);
+
+        // Rotating the shared credential resets the age of profiles using it
+        let mut credential = Credential::default();
+        credential.touch(now - chrono::Duration::days(200));
+        credential.touch(now - chrono::Duration::days(5));
+        store.set_credential("svc-ci-token", credential);
+        let mut shared = Profile::new("https://test.vantiq.com");
+        shared.credential = Some("svc-ci-token".to_string());
+        shared.max_credential_age_days = Some(30);
+        store.set("dev", shared);
+
+        let age = store.credential_age("dev", now).unwrap().unwrap();
+        assert_eq!(age.age_days, 5);
+        assert!(!age.is_overdue());
+
+        // No limit configured
+        store.set("plain", Profile::new("https://test.vantiq.com"));
+        assert_eq!(store.credential_age("plain", now).unwrap(), None);
+    }
 }
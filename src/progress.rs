@@ -0,0 +1,290 @@
+//! Export/import progress parsed from the underlying CLI's output
+//!
+//! The underlying CLI works through resources in chunks (`-chunk <size>`)
+//! and reports as it goes, e.g. "Processing chunk 3 of 12" or "Exported 500
+//! records". Once a chunk total is seen the spinner becomes a bar with
+//! counts and an ETA; until then it counts the resources processed so far.
+//!
+//! Spinners and bars are only drawn when stderr is a terminal, so CI logs
+//! stay clean; `--no-progress` or `[output] progress = false` turn them off
+//! everywhere.
+
+use crate::error::Result;
+use crate::underlying::{
+    CliOptions, ExecResult, StallPolicy, StreamControl, StreamEvent, UnderlyingCli,
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn progress display off for this run (`--no-progress`, `[output] progress`)
+pub fn configure(no_progress: bool, configured: bool) {
+    DISABLED.store(no_progress || !configured, Ordering::Relaxed);
+}
+
+/// Whether spinners and bars may be drawn
+pub fn enabled() -> bool {
+    !DISABLED.load(Ordering::Relaxed) && std::io::stderr().is_terminal()
+}
+
+/// Progress information found in one line of CLI output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressLine {
+    /// "chunk N of M": N chunks processed out of M
+    Chunk { current: u64, total: u64 },
+    /// "N of M records": absolute position within a known total
+    Position { current: u64, total: u64 },
+    /// "Exported N records": N more resources processed
+    Processed { count: u64 },
+}
+
+/// Parse a line of export/import output
+pub fn parse_line(line: &str) -> Option<ProgressLine> {
+    static CHUNK: OnceLock<Regex> = OnceLock::new();
+    static POSITION: OnceLock<Regex> = OnceLock::new();
+    static PROCESSED: OnceLock<Regex> = OnceLock::new();
+
+    const UNITS: &str = r"(?:resources?|records?|instances?|items?|files?|objects?)";
+
+    let chunk =
+        CHUNK.get_or_init(|| Regex::new(r"(?i)\bchunk\s+(\d+)\s*(?:of|/)\s*(\d+)").unwrap());
+    let position = POSITION.get_or_init(|| {
+        Regex::new(&format!(r"(?i)\b(\d+)\s*(?:of|/)\s*(\d+)\s+{}\b", UNITS)).unwrap()
+    });
+    let processed = PROCESSED.get_or_init(|| {
+        Regex::new(&format!(
+            r"(?i)\b(?:exported|imported|processed|wrote|loaded)\s+(\d+)\s+(?:\w+\s+)?{}\b",
+            UNITS
+        ))
+        .unwrap()
+    });
+
+    let number = |caps: &regex::Captures, i: usize| caps[i].parse::<u64>().ok();
+
+    if let Some(caps) = chunk.captures(line) {
+        let (current, total) = (number(&caps, 1)?, number(&caps, 2)?);
+        return (total > 0).then_some(ProgressLine::Chunk { current, total });
+    }
+    if let Some(caps) = position.captures(line) {
+        let (current, total) = (number(&caps, 1)?, number(&caps, 2)?);
+        return (total > 0).then_some(ProgressLine::Position { current, total });
+    }
+    let caps = processed.captures(line)?;
+    Some(ProgressLine::Processed {
+        count: number(&caps, 1)?,
+    })
+}
+
+/// Progress display for a streamed export or import
+///
+/// With `enabled` false nothing is drawn, but lines are still parsed so the
+/// counts are available.
+pub struct TransferProgress {
+    bar: Option<ProgressBar>,
+    label: String,
+    /// Resources processed so far
+    resources: u64,
+    /// Latest (processed, total) chunk counts
+    chunks: Option<(u64, u64)>,
+    /// Unit shown by the determinate bar, once switched from the spinner
+    unit: Option<&'static str>,
+}
+
+impl TransferProgress {
+    /// Start a spinner labelled e.g. "Exporting from Vantiq..."
+    ///
+    /// Nothing is drawn unless `enabled` and progress display is [`enabled`].
+    pub fn new(label: impl Into<String>, enabled: bool) -> Self {
+        let label = label.into();
+        let bar = (enabled && self::enabled()).then(|| {
+            let pb = ProgressBar::new_spinner();
+            pb.set_style(spinner_style());
+            pb.set_message(label.clone());
+            pb.enable_steady_tick(Duration::from_millis(100));
+            pb
+        });
+        Self {
+            bar,
+            label,
+            resources: 0,
+            chunks: None,
+            unit: None,
+        }
+    }
+
+    /// The underlying bar, if drawing
+    pub fn bar(&self) -> Option<&ProgressBar> {
+        self.bar.as_ref()
+    }
+
+    /// Update from a line of CLI output
+    pub fn observe(&mut self, line: &str) {
+        match parse_line(line) {
+            Some(ProgressLine::Chunk { current, total }) => {
+                self.chunks = Some((current.min(total), total));
+            }
+            Some(ProgressLine::Position { current, total }) => {
+                self.resources = current;
+                if self.chunks.is_none() {
+                    self.set_bar(current.min(total), total, "resources");
+                }
+            }
+            Some(ProgressLine::Processed { count }) => {
+                self.resources += count;
+            }
+            None => {}
+        }
+
+        if let Some((current, total)) = self.chunks {
+            self.set_bar(current, total, "chunks");
+        }
+        if let Some(ref pb) = self.bar {
+            pb.set_message(self.message());
+        }
+    }
+
+    /// Run a CLI command (export or import), updating progress from its output
+    pub async fn run(
+        &mut self,
+        cli: &UnderlyingCli,
+        options: &CliOptions,
+        command: &str,
+        args: Vec<OsString>,
+        policy: StallPolicy,
+    ) -> Result<ExecResult> {
+        cli.execute_streaming(options, command, args, policy, |event| {
+            if let StreamEvent::Line { text, .. } = event {
+                self.observe(text);
+            }
+            StreamControl::Continue
+        })
+        .await
+    }
+
+    /// Show a transient status (e.g. a heartbeat) until the next output line
+    pub fn set_status(&self, status: impl Into<String>) {
+        if let Some(ref pb) = self.bar {
+            pb.set_message(status.into());
+        }
+    }
+
+    /// Switch back to a spinner for a follow-up step such as normalization
+    pub fn set_phase(&mut self, label: impl Into<String>) {
+        self.label = label.into();
+        self.chunks = None;
+        self.resources = 0;
+        self.unit = None;
+        if let Some(ref pb) = self.bar {
+            pb.reset();
+            pb.set_style(spinner_style());
+            pb.set_message(self.label.clone());
+            pb.enable_steady_tick(Duration::from_millis(100));
+        }
+    }
+
+    pub fn finish_and_clear(&self) {
+        if let Some(ref pb) = self.bar {
+            pb.finish_and_clear();
+        }
+    }
+
+    fn set_bar(&mut self, current: u64, total: u64, unit: &'static str) {
+        if let Some(ref pb) = self.bar {
+            if self.unit != Some(unit) {
+                pb.set_style(bar_style(unit));
+                self.unit = Some(unit);
+            }
+            pb.set_length(total);
+            pb.set_position(current);
+        }
+    }
+
+    fn message(&self) -> String {
+        match (self.resources, self.chunks) {
+            (0, _) => self.label.clone(),
+            (n, Some(_)) => format!("{} ({} resources)", self.label, n),
+            (n, None) => format!("{} ({} resources processed)", self.label, n),
+        }
+    }
+}
+
+fn spinner_style() -> ProgressStyle {
+    ProgressStyle::default_spinner()
+        .template("{spinner:.green} {msg}")
+        .unwrap()
+}
+
+fn bar_style(unit: &str) -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template(&format!(
+            "{{spinner:.green}} [{{bar:40.cyan/blue}}] {{pos}}/{{len}} {} (ETA {{eta}}) {{msg}}",
+            unit
+        ))
+        .unwrap()
+        .progress_chars("#>-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("Processing chunk 3 of 12"),
+            Some(ProgressLine::Chunk {
+                current: 3,
+                total: 12
+            })
+        );
+        assert_eq!(
+            parse_line("Exporting types (chunk 2/5)"),
+            Some(ProgressLine::Chunk {
+                current: 2,
+                total: 5
+            })
+        );
+        assert_eq!(
+            parse_line("Imported 250 of 1000 records"),
+            Some(ProgressLine::Position {
+                current: 250,
+                total: 1000
+            })
+        );
+        assert_eq!(
+            parse_line("Exported 500 records"),
+            Some(ProgressLine::Processed { count: 500 })
+        );
+        assert_eq!(
+            parse_line("Processed 12 procedure resources"),
+            Some(ProgressLine::Processed { count: 12 })
+        );
+        assert_eq!(parse_line("Exporting procedures..."), None);
+        assert_eq!(parse_line("chunk 1 of 0"), None);
+    }
+
+    #[test]
+    fn test_transfer_progress_counts() {
+        let mut progress = TransferProgress::new("Exporting", false);
+        progress.observe("Connecting to server");
+        progress.observe("Exported 100 records");
+        progress.observe("Exported 50 records");
+        assert_eq!(progress.resources, 150);
+        assert_eq!(progress.chunks, None);
+
+        progress.observe("Processing chunk 2 of 4");
+        progress.observe("chunk 9 of 4");
+        assert_eq!(progress.chunks, Some((4, 4)));
+        assert_eq!(progress.message(), "Exporting (150 resources)");
+
+        progress.set_phase("Normalizing JSON files...");
+        assert_eq!(progress.resources, 0);
+        assert_eq!(progress.chunks, None);
+    }
+}
@@ -0,0 +1,176 @@
+//! Project file `.vqx.toml` (vqx extension)
+//!
+//! A `.vqx.toml` in the current directory or one of its parents marks a vqx
+//! project (`vqx init` writes one). It supplies defaults for what the
+//! command line leaves open:
+//!
+//! - `profile`: profile used when neither `--profile` nor `VQX_PROFILE` is
+//!   given (instead of the default profile in profiles.toml)
+//! - `directory`: directory `export`, `import`, `new` and `rename` use
+//!   without `-d`, relative to the project root
+//!
+//! ```toml
+//! profile = "dev"
+//! directory = "export"
+//! ```
+
+use crate::cli::{Cli, Commands};
+use crate::error::{Result, VqxError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Name of the project file
+pub const PROJECT_FILE: &str = ".vqx.toml";
+
+/// Settings in `.vqx.toml`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub profile: Option<String>,
+    pub directory: Option<PathBuf>,
+}
+
+/// A project file and the directory it was found in
+#[derive(Debug)]
+pub struct Project {
+    pub root: PathBuf,
+    pub config: ProjectConfig,
+}
+
+impl Project {
+    /// Find the nearest `.vqx.toml` from `start` upwards
+    pub fn discover(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let path = dir.join(PROJECT_FILE);
+            if path.is_file() {
+                return Self::load(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+            path: path.display().to_string(),
+        })?;
+        let config: ProjectConfig = toml::from_str(&content)?;
+        Ok(Self {
+            root: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            config,
+        })
+    }
+
+    /// Export directory, resolved against the project root
+    pub fn directory(&self) -> Option<PathBuf> {
+        self.config
+            .directory
+            .as_ref()
+            .map(|dir| self.root.join(dir))
+    }
+
+    /// Fill in what `cli` leaves open from the project file
+    pub fn apply(&self, cli: &mut Cli) {
+        if cli.profile.is_none() {
+            cli.profile = self.config.profile.clone();
+        }
+        let directory = match &mut cli.command {
+            Commands::Export(args) => &mut args.directory,
+            Commands::Import(args) => &mut args.directory,
+            Commands::New(args) => &mut args.directory,
+            Commands::Rename(args) => &mut args.directory,
+            _ => return,
+        };
+        if directory.is_none() {
+            *directory = self.directory();
+        }
+    }
+}
+
+/// Apply the project file found from the current directory, if any
+pub fn apply(cli: &mut Cli) -> Result<()> {
+    let Ok(cwd) = std::env::current_dir() else {
+        return Ok(());
+    };
+    if let Some(project) = Project::discover(&cwd)? {
+        debug!(root = %project.root.display(), "Using project file");
+        project.apply(cli);
+    }
+    Ok(())
+}
+
+/// Content of a new project file, with comments
+pub fn render(config: &ProjectConfig) -> String {
+    let mut out = String::from("# vqx project settings\n\n");
+    out.push_str("# Profile used when --profile / VQX_PROFILE is not given\n");
+    match config.profile {
+        Some(ref profile) => out.push_str(&format!("profile = {}\n", toml_string(profile))),
+        None => out.push_str("# profile = \"dev\"\n"),
+    }
+    out.push_str("\n# Export directory used when -d is not given\n");
+    match config.directory {
+        Some(ref dir) => out.push_str(&format!(
+            "directory = {}\n",
+            toml_string(&dir.to_string_lossy().replace('\\', "/"))
+        )),
+        None => out.push_str("# directory = \"export\"\n"),
+    }
+    out
+}
+
+fn toml_string(s: &str) -> String {
+    toml::Value::String(s.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_discover_and_apply() {
+        let dir = TempDir::new().unwrap();
+        let config = ProjectConfig {
+            profile: Some("dev".to_string()),
+            directory: Some(PathBuf::from("export")),
+        };
+        fs::write(dir.path().join(PROJECT_FILE), render(&config)).unwrap();
+        let nested = dir.path().join("export/types");
+        fs::create_dir_all(&nested).unwrap();
+
+        let project = Project::discover(&nested).unwrap().unwrap();
+        assert_eq!(project.config, config);
+        assert_eq!(project.root, dir.path());
+
+        let mut cli = Cli::parse_from(["vqx", "export"]);
+        project.apply(&mut cli);
+        assert_eq!(cli.profile.as_deref(), Some("dev"));
+        match cli.command {
+            Commands::Export(ref args) => {
+                assert_eq!(args.directory, Some(dir.path().join("export")))
+            }
+            _ => unreachable!(),
+        }
+
+        // The command line wins
+        let mut cli = Cli::parse_from(["vqx", "-s", "prod", "import", "-d", "in"]);
+        project.apply(&mut cli);
+        assert_eq!(cli.profile.as_deref(), Some("prod"));
+        match cli.command {
+            Commands::Import(ref args) => assert_eq!(args.directory, Some(PathBuf::from("in"))),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_render_commented_defaults() {
+        let text = render(&ProjectConfig::default());
+        assert_eq!(
+            toml::from_str::<ProjectConfig>(&text).unwrap(),
+            ProjectConfig::default()
+        );
+        assert!(text.contains("# profile = \"dev\""));
+    }
+}
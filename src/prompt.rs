@@ -0,0 +1,151 @@
+//! Interactive prompts
+//!
+//! Prompts need a terminal on stdin. In CI, cron jobs or pipes, or with the
+//! global `--no-input` flag, a prompt would hang or fail inside dialoguer;
+//! [`confirm`] and [`require_input`] fail with
+//! [`VqxError::InputRequired`] instead, naming the flag that answers the
+//! question up front.
+//!
+//! Commands never call dialoguer directly; every prompt goes through this
+//! module.
+
+use crate::error::{Result, VqxError};
+use dialoguer::{Confirm, Input, Password, Select};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static NO_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// Disable all prompts (`--no-input`)
+pub fn set_no_input(no_input: bool) {
+    NO_INPUT.store(no_input, Ordering::Relaxed);
+}
+
+/// Whether prompts can be shown
+pub fn is_interactive() -> bool {
+    !NO_INPUT.load(Ordering::Relaxed) && std::io::stdin().is_terminal()
+}
+
+/// Fail unless prompts can be shown
+///
+/// `alternative` tells the user how to run `operation` without input,
+/// e.g. "pass --yes to confirm".
+pub fn require_input(operation: &str, alternative: &str) -> Result<()> {
+    if is_interactive() {
+        Ok(())
+    } else {
+        Err(VqxError::InputRequired {
+            operation: operation.to_string(),
+            alternative: alternative.to_string(),
+        })
+    }
+}
+
+/// Ask a yes/no question (default: no)
+///
+/// `skip_flag` is the flag that answers yes without asking.
+pub fn confirm(prompt: impl Into<String>, operation: &str, skip_flag: &str) -> Result<bool> {
+    ask_yes_no(
+        prompt,
+        false,
+        operation,
+        &format!("pass {} to confirm", skip_flag),
+    )
+}
+
+/// Ask a yes/no question with the given default answer
+pub fn ask_yes_no(
+    prompt: impl Into<String>,
+    default: bool,
+    operation: &str,
+    alternative: &str,
+) -> Result<bool> {
+    require_input(operation, alternative)?;
+    Confirm::new()
+        .with_prompt(prompt)
+        .default(default)
+        .interact()
+        .map_err(|e| VqxError::Other(format!("Confirmation failed: {}", e)))
+}
+
+/// Let the user pick one of `items` (default: the first); returns its index
+pub fn select(
+    prompt: impl Into<String>,
+    items: &[&str],
+    operation: &str,
+    alternative: &str,
+) -> Result<usize> {
+    require_input(operation, alternative)?;
+    Select::new()
+        .with_prompt(prompt)
+        .items(items)
+        .default(0)
+        .interact()
+        .map_err(|e| VqxError::Other(format!("Selection failed: {}", e)))
+}
+
+/// Read a line of text
+///
+/// An empty answer gives `default` when there is one; otherwise it is
+/// accepted only with `allow_empty`.
+pub fn input(
+    prompt: impl Into<String>,
+    default: Option<String>,
+    allow_empty: bool,
+    operation: &str,
+    alternative: &str,
+) -> Result<String> {
+    require_input(operation, alternative)?;
+    let mut input = Input::<String>::new()
+        .with_prompt(prompt)
+        .allow_empty(allow_empty);
+    if let Some(default) = default {
+        input = input.default(default);
+    }
+    input
+        .interact_text()
+        .map_err(|e| VqxError::Other(format!("Input failed: {}", e)))
+}
+
+/// Read a secret without echoing it
+pub fn password(prompt: impl Into<String>, operation: &str, alternative: &str) -> Result<String> {
+    require_input(operation, alternative)?;
+    Password::new()
+        .with_prompt(prompt)
+        .interact()
+        .map_err(|e| VqxError::Other(format!("Input failed: {}", e)))
+}
+
+/// Read a new secret, typed twice
+pub fn new_password(
+    prompt: impl Into<String>,
+    repeat: impl Into<String>,
+    operation: &str,
+    alternative: &str,
+) -> Result<String> {
+    require_input(operation, alternative)?;
+    Password::new()
+        .with_prompt(prompt)
+        .with_confirmation(repeat, "Entries do not match")
+        .interact()
+        .map_err(|e| VqxError::Other(format!("Input failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_input() {
+        set_no_input(true);
+        assert!(!is_interactive());
+        let err = confirm("Delete?", "Profile deletion", "--force").unwrap_err();
+        assert!(matches!(err, VqxError::InputRequired { .. }));
+        assert!(err.to_string().contains("pass --force to confirm"));
+
+        let err = select("Push?", &["yes", "no"], "Sync push", "drop --interactive").unwrap_err();
+        assert!(err.to_string().contains("drop --interactive"));
+        assert!(password("Password", "Login", "use --password-stdin").is_err());
+        set_no_input(false);
+    }
+}
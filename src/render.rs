@@ -0,0 +1,1130 @@
+//! Diff renderers
+//!
+//! A [`DiffRenderer`] turns a [`DiffResult`] into one output format. All
+//! renderers are registered in [`diff_renderers`]; commands look them up by
+//! name (`vqx diff --format <name>`) instead of matching on formats
+//! themselves, so a new format only needs an implementation and one line in
+//! the registry.
+
+use crate::cli::OutputFormat;
+use crate::commands::diff::{ChangeKind, DiffResult, ResourceDiff};
+use crate::compat::Severity;
+use crate::error::{Result, VqxError};
+use crate::i18n;
+use crate::report;
+use crate::theme::style;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Options shared by all renderers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RenderOptions {
+    /// Whether `diff_text` holds full diffs rather than `+N -M` summaries
+    pub full: bool,
+}
+
+/// Renders a diff result in one output format
+pub trait DiffRenderer {
+    /// Name used with `--format`
+    fn name(&self) -> &'static str;
+
+    /// One-line description for help and error messages
+    fn description(&self) -> &'static str;
+
+    /// Whether the renderer needs full diffs regardless of `--full`
+    fn requires_full_diff(&self) -> bool {
+        false
+    }
+
+    /// Whether the output is meant for a terminal
+    ///
+    /// Terminal formats get the progress spinner and header; all others
+    /// print nothing but the rendered output so it can be redirected.
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    /// Render the result; the returned text ends with a newline
+    fn render(&self, result: &DiffResult, options: RenderOptions) -> Result<String>;
+}
+
+/// All diff renderers, keyed by name
+pub fn diff_renderers() -> Vec<Box<dyn DiffRenderer>> {
+    vec![
+        Box::new(TextRenderer),
+        Box::new(JsonRenderer),
+        Box::new(YamlRenderer),
+        Box::new(NdjsonRenderer),
+        Box::new(PorcelainRenderer),
+        Box::new(MarkdownRenderer),
+        Box::new(HtmlRenderer),
+        Box::new(PullRequestRenderer::GITHUB),
+        Box::new(PullRequestRenderer::GITLAB),
+        Box::new(SideBySideRenderer),
+        Box::new(PatchRenderer),
+    ]
+}
+
+/// Look up a diff renderer by name
+pub fn diff_renderer(name: &str) -> Result<Box<dyn DiffRenderer>> {
+    let renderers = diff_renderers();
+    let available: Vec<&str> = renderers.iter().map(|r| r.name()).collect();
+    let available = available.join(", ");
+
+    renderers
+        .into_iter()
+        .find(|r| r.name() == name)
+        .ok_or_else(|| {
+            VqxError::Other(format!(
+                "Unknown diff format '{}'. Available formats: {}",
+                name, available
+            ))
+        })
+}
+
+fn fmt_error(e: std::fmt::Error) -> VqxError {
+    VqxError::Other(format!("Failed to render diff: {}", e))
+}
+
+/// Look up the renderer for a `--report` file by its extension
+pub fn report_renderer(path: &Path) -> Result<Box<dyn DiffRenderer>> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("html" | "htm") => Ok(Box::new(HtmlRenderer)),
+        Some("md" | "markdown") => Ok(Box::new(MarkdownRenderer)),
+        _ => Err(VqxError::Other(format!(
+            "Cannot tell the report format of '{}': use a .html or .md file",
+            path.display()
+        ))),
+    }
+}
+
+/// Changes per resource type: (type, added, removed, modified)
+fn type_summary(result: &DiffResult) -> Vec<(&str, usize, usize, usize)> {
+    let mut counts: BTreeMap<&str, (usize, usize, usize)> = BTreeMap::new();
+    for diff in &result.added {
+        counts.entry(&diff.resource_type).or_default().0 += 1;
+    }
+    for diff in &result.removed {
+        counts.entry(&diff.resource_type).or_default().1 += 1;
+    }
+    for diff in &result.modified {
+        counts.entry(&diff.resource_type).or_default().2 += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(t, (added, removed, modified))| (t, added, removed, modified))
+        .collect()
+}
+
+/// `+N -M` line counts of a full diff
+fn line_counts(text: &str) -> String {
+    let count = |sign: char| {
+        text.lines()
+            .filter(|l| l.starts_with(sign) && !l.starts_with("+++") && !l.starts_with("---"))
+            .count()
+    };
+    format!("+{} -{}", count('+'), count('-'))
+}
+
+/// Modified resources with their diff lines, in report order
+fn modified_with_lines(result: &DiffResult) -> impl Iterator<Item = (&ResourceDiff, &str)> {
+    result
+        .modified
+        .iter()
+        .map(|d| (d, d.diff_text.as_deref().unwrap_or("")))
+}
+
+// =============================================================================
+// text
+// =============================================================================
+
+/// Colored terminal output (default)
+pub struct TextRenderer;
+
+impl DiffRenderer for TextRenderer {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn description(&self) -> &'static str {
+        "Colored summary for the terminal (default)"
+    }
+
+    fn is_terminal(&self) -> bool {
+        true
+    }
+
+    fn render(&self, result: &DiffResult, options: RenderOptions) -> Result<String> {
+        let mut out = String::new();
+        write_text(&mut out, result, options.full).map_err(fmt_error)?;
+        Ok(out)
+    }
+}
+
+fn write_text(out: &mut String, result: &DiffResult, full_diff: bool) -> std::fmt::Result {
+    writeln!(out)?;
+    writeln!(out, "{}", style("─".repeat(50)).dim())?;
+
+    if !result.has_changes() {
+        writeln!(
+            out,
+            "{} {}",
+            style("✓").green().bold(),
+            i18n::t("diff.no_differences")
+        )?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    writeln!(
+        out,
+        "{} {}",
+        style("!").yellow().bold(),
+        i18n::tf("diff.found", &[("count", &result.total_changes())])
+    )?;
+    writeln!(out)?;
+
+    // Added
+    if !result.added.is_empty() {
+        writeln!(
+            out,
+            "{} {}",
+            style("+").green().bold(),
+            i18n::tf("diff.added", &[("count", &result.added.len())])
+        )?;
+        for diff in &result.added {
+            writeln!(
+                out,
+                "    {} {}/{}",
+                style("+").green(),
+                diff.resource_type,
+                diff.name
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    // Removed
+    if !result.removed.is_empty() {
+        writeln!(
+            out,
+            "{} {}",
+            style("-").red().bold(),
+            i18n::tf("diff.removed", &[("count", &result.removed.len())])
+        )?;
+        for diff in &result.removed {
+            writeln!(
+                out,
+                "    {} {}/{}",
+                style("-").red(),
+                diff.resource_type,
+                diff.name
+            )?;
+        }
+        writeln!(out)?;
+    }
+
+    // Modified
+    if !result.modified.is_empty() {
+        writeln!(
+            out,
+            "{} {}",
+            style("~").yellow().bold(),
+            i18n::tf("diff.modified", &[("count", &result.modified.len())])
+        )?;
+        for diff in &result.modified {
+            writeln!(
+                out,
+                "    {} {}/{}",
+                style("~").yellow(),
+                diff.resource_type,
+                diff.name
+            )?;
+            if full_diff {
+                if let Some(ref text) = diff.diff_text {
+                    for line in text.lines() {
+                        let colored_line = if line.starts_with('+') {
+                            style(line).green().to_string()
+                        } else if line.starts_with('-') {
+                            style(line).red().to_string()
+                        } else {
+                            line.to_string()
+                        };
+                        writeln!(out, "        {}", colored_line)?;
+                    }
+                }
+            } else if let Some(ref text) = diff.diff_text {
+                writeln!(out, "        {}", style(text).dim())?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    // Breaking changes (--breaking)
+    if let Some(ref issues) = result.breaking {
+        if issues.is_empty() {
+            writeln!(
+                out,
+                "{} {}",
+                style("✓").green().bold(),
+                i18n::t("diff.no_breaking")
+            )?;
+        } else {
+            writeln!(
+                out,
+                "{} {}",
+                style("⚠").yellow().bold(),
+                i18n::tf("diff.issues", &[("count", &issues.len())])
+            )?;
+            for issue in issues {
+                let severity = match issue.severity {
+                    Severity::Breaking => style(issue.severity).red().bold(),
+                    Severity::Warning => style(issue.severity).yellow(),
+                };
+                writeln!(out, "    [{}] {}: {}", severity, issue.id, issue.message)?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    // Errors
+    if !result.errors.is_empty() {
+        writeln!(
+            out,
+            "{} {}",
+            style("⚠").red().bold(),
+            i18n::tf("diff.errors", &[("count", &result.errors.len())])
+        )?;
+        for error in &result.errors {
+            writeln!(out, "    {}", style(error).red())?;
+        }
+        writeln!(out)?;
+    }
+
+    writeln!(out, "{}", style("─".repeat(50)).dim())?;
+    writeln!(out)
+}
+
+// =============================================================================
+// json
+// =============================================================================
+
+/// Versioned JSON report (same as `--output json`)
+pub struct JsonRenderer;
+
+impl DiffRenderer for JsonRenderer {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn description(&self) -> &'static str {
+        "Versioned JSON report"
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        Ok(report::to_json(result)? + "\n")
+    }
+}
+
+// =============================================================================
+// yaml
+// =============================================================================
+
+/// Versioned report as YAML (same as `--output yaml`)
+pub struct YamlRenderer;
+
+impl DiffRenderer for YamlRenderer {
+    fn name(&self) -> &'static str {
+        "yaml"
+    }
+
+    fn description(&self) -> &'static str {
+        "Versioned report as YAML"
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        Ok(report::render(result, OutputFormat::Yaml)? + "\n")
+    }
+}
+
+// =============================================================================
+// ndjson
+// =============================================================================
+
+/// One JSON object per changed resource (same as `--output ndjson`)
+///
+/// Entries carry their `change` kind, so a stream can be filtered with e.g.
+/// `jq 'select(.change == "Removed")'`.
+pub struct NdjsonRenderer;
+
+impl DiffRenderer for NdjsonRenderer {
+    fn name(&self) -> &'static str {
+        "ndjson"
+    }
+
+    fn description(&self) -> &'static str {
+        "One JSON object per added, removed or modified resource"
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        let entries: Vec<&ResourceDiff> = result
+            .added
+            .iter()
+            .chain(&result.removed)
+            .chain(&result.modified)
+            .collect();
+        report::to_ndjson(&entries)
+    }
+}
+
+// =============================================================================
+// porcelain
+// =============================================================================
+
+/// One `<status> <type>/<name>` line per changed resource (same as
+/// `--output porcelain`)
+///
+/// The format never changes between versions: `A` added, `D` removed, `M`
+/// modified, sorted by resource ID.
+pub struct PorcelainRenderer;
+
+impl DiffRenderer for PorcelainRenderer {
+    fn name(&self) -> &'static str {
+        "porcelain"
+    }
+
+    fn description(&self) -> &'static str {
+        "Stable `<status> <type>/<name>` lines for scripts"
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        Ok(porcelain(
+            result
+                .added
+                .iter()
+                .chain(&result.removed)
+                .chain(&result.modified)
+                .map(|d| (d.change, d.id.as_str())),
+        ))
+    }
+}
+
+/// Porcelain lines for `(change, resource ID)` pairs, sorted by ID
+pub fn porcelain<'a>(changes: impl IntoIterator<Item = (ChangeKind, &'a str)>) -> String {
+    let mut changes: Vec<_> = changes.into_iter().collect();
+    changes.sort_by(|a, b| a.1.cmp(b.1));
+    changes
+        .into_iter()
+        .map(|(change, id)| format!("{} {}\n", change.code(), id))
+        .collect()
+}
+
+// =============================================================================
+// markdown
+// =============================================================================
+
+/// Markdown table, e.g. for pull request comments
+pub struct MarkdownRenderer;
+
+impl DiffRenderer for MarkdownRenderer {
+    fn name(&self) -> &'static str {
+        "markdown"
+    }
+
+    fn description(&self) -> &'static str {
+        "Markdown tables with optional collapsible diffs"
+    }
+
+    fn render(&self, result: &DiffResult, options: RenderOptions) -> Result<String> {
+        let mut out = String::new();
+        write_markdown(&mut out, result, options.full).map_err(fmt_error)?;
+        Ok(out)
+    }
+}
+
+fn write_markdown(out: &mut String, result: &DiffResult, full_diff: bool) -> std::fmt::Result {
+    writeln!(out, "## Diff: `{}` → `{}`", result.source, result.target)?;
+    writeln!(out)?;
+
+    if !result.has_changes() {
+        writeln!(out, "No differences found.")?;
+    } else {
+        writeln!(out, "**{} change(s)**", result.total_changes())?;
+        writeln!(out)?;
+        writeln!(out, "| Resource type | Added | Removed | Modified |")?;
+        writeln!(out, "|---------------|------:|--------:|---------:|")?;
+        for (resource_type, added, removed, modified) in type_summary(result) {
+            writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                resource_type, added, removed, modified
+            )?;
+        }
+        writeln!(out)?;
+        writeln!(out, "| Change | Resource | Details |")?;
+        writeln!(out, "|--------|----------|---------|")?;
+        for diff in result.added.iter().chain(&result.removed) {
+            writeln!(out, "| {} | `{}` | |", diff.change, diff.id)?;
+        }
+        for (diff, text) in modified_with_lines(result) {
+            let details = if full_diff { "" } else { text };
+            writeln!(out, "| {} | `{}` | {} |", diff.change, diff.id, details)?;
+        }
+
+        if full_diff {
+            // Collapsible per resource, so long reports stay readable
+            for (diff, text) in modified_with_lines(result) {
+                writeln!(out)?;
+                writeln!(out, "<details>")?;
+                writeln!(
+                    out,
+                    "<summary><code>{}</code> ({})</summary>",
+                    escape_html(&diff.id),
+                    line_counts(text)
+                )?;
+                writeln!(out)?;
+                writeln!(out, "```diff")?;
+                write!(out, "{}", text)?;
+                if !text.ends_with('\n') {
+                    writeln!(out)?;
+                }
+                writeln!(out, "```")?;
+                writeln!(out)?;
+                writeln!(out, "</details>")?;
+            }
+        }
+    }
+
+    if let Some(ref issues) = result.breaking {
+        writeln!(out)?;
+        writeln!(out, "**Compatibility:**")?;
+        writeln!(out)?;
+        if issues.is_empty() {
+            writeln!(out, "No breaking changes.")?;
+        }
+        for issue in issues {
+            writeln!(
+                out,
+                "- **{}** `{}`: {}",
+                issue.severity, issue.id, issue.message
+            )?;
+        }
+    }
+
+    if !result.errors.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "**Errors:**")?;
+        writeln!(out)?;
+        for error in &result.errors {
+            writeln!(out, "- {}", error)?;
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// html
+// =============================================================================
+
+/// Standalone HTML page
+pub struct HtmlRenderer;
+
+impl DiffRenderer for HtmlRenderer {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn description(&self) -> &'static str {
+        "Standalone HTML page"
+    }
+
+    fn requires_full_diff(&self) -> bool {
+        true
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        let mut out = String::new();
+        write_html(&mut out, result).map_err(fmt_error)?;
+        Ok(out)
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_html(out: &mut String, result: &DiffResult) -> std::fmt::Result {
+    let title = format!(
+        "Diff: {} → {}",
+        escape_html(&result.source),
+        escape_html(&result.target)
+    );
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html>")?;
+    writeln!(out, "<head>")?;
+    writeln!(out, "<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>{}</title>", title)?;
+    writeln!(
+        out,
+        "<style>body{{font-family:sans-serif}}pre{{background:#f6f8fa;padding:8px}}\
+         table{{border-collapse:collapse;margin-bottom:1em}}\
+         th,td{{border:1px solid #d0d7de;padding:4px 8px;text-align:left}}\
+         summary{{cursor:pointer;margin:4px 0}}\
+         .add{{color:#22863a}}.del{{color:#b31d28}}.hunk{{color:#6f42c1}}\
+         .breaking{{color:#b31d28;font-weight:bold}}.warning{{color:#9a6700}}</style>"
+    )?;
+    writeln!(out, "</head>")?;
+    writeln!(out, "<body>")?;
+    writeln!(out, "<h1>{}</h1>", title)?;
+    writeln!(out, "<p>{} change(s)</p>", result.total_changes())?;
+
+    if result.has_changes() {
+        writeln!(out, "<h2>Summary</h2>")?;
+        writeln!(out, "<table>")?;
+        writeln!(
+            out,
+            "<tr><th>Resource type</th><th>Added</th><th>Removed</th><th>Modified</th></tr>"
+        )?;
+        for (resource_type, added, removed, modified) in type_summary(result) {
+            writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(resource_type),
+                added,
+                removed,
+                modified
+            )?;
+        }
+        writeln!(out, "</table>")?;
+
+        writeln!(out, "<h2>Changes</h2>")?;
+        writeln!(out, "<table>")?;
+        writeln!(out, "<tr><th>Change</th><th>Resource</th></tr>")?;
+        for diff in result
+            .added
+            .iter()
+            .chain(&result.removed)
+            .chain(&result.modified)
+        {
+            writeln!(
+                out,
+                "<tr><td>{}</td><td><code>{}</code></td></tr>",
+                diff.change,
+                escape_html(&diff.id)
+            )?;
+        }
+        writeln!(out, "</table>")?;
+    }
+
+    if let Some(ref issues) = result.breaking {
+        writeln!(out, "<h2>Compatibility</h2>")?;
+        if issues.is_empty() {
+            writeln!(out, "<p>No breaking changes.</p>")?;
+        } else {
+            writeln!(out, "<ul>")?;
+            for issue in issues {
+                writeln!(
+                    out,
+                    "<li><span class=\"{}\">{}</span> <code>{}</code>: {}</li>",
+                    issue.severity,
+                    issue.severity,
+                    escape_html(&issue.id),
+                    escape_html(&issue.message)
+                )?;
+            }
+            writeln!(out, "</ul>")?;
+        }
+    }
+
+    if !result.modified.is_empty() {
+        writeln!(out, "<h2>Modified resources</h2>")?;
+    }
+    for (diff, text) in modified_with_lines(result) {
+        writeln!(out, "<details>")?;
+        writeln!(
+            out,
+            "<summary><code>{}</code> ({})</summary>",
+            escape_html(&diff.id),
+            line_counts(text)
+        )?;
+        writeln!(out, "<pre>")?;
+        for line in text.lines() {
+            let class = if line.starts_with('+') {
+                "add"
+            } else if line.starts_with('-') {
+                "del"
+            } else if line.starts_with("@@") || line.starts_with('#') {
+                "hunk"
+            } else {
+                ""
+            };
+            writeln!(
+                out,
+                "<span class=\"{}\">{}</span>",
+                class,
+                escape_html(line)
+            )?;
+        }
+        writeln!(out, "</pre>")?;
+        writeln!(out, "</details>")?;
+    }
+
+    if !result.errors.is_empty() {
+        writeln!(out, "<h2>Errors</h2>")?;
+        writeln!(out, "<ul>")?;
+        for error in &result.errors {
+            writeln!(out, "<li>{}</li>", escape_html(error))?;
+        }
+        writeln!(out, "</ul>")?;
+    }
+
+    writeln!(out, "</body>")?;
+    writeln!(out, "</html>")
+}
+
+// =============================================================================
+// github / gitlab
+// =============================================================================
+
+/// Marker opening every pull request comment, so CI can find and update
+/// the previous one instead of adding a new comment per run
+pub const COMMENT_MARKER: &str = "<!-- vqx-diff -->";
+
+/// Longest comment body; GitHub rejects comments over 65536 characters
+const COMMENT_LIMIT: usize = 60_000;
+
+/// Markdown comment body for a pull/merge request
+///
+/// Same content as `markdown` with collapsible full diffs. When the diffs
+/// would make the comment too long, only the summary is kept.
+pub struct PullRequestRenderer {
+    name: &'static str,
+    description: &'static str,
+}
+
+impl PullRequestRenderer {
+    pub const GITHUB: Self = Self {
+        name: "github",
+        description: "Comment body for a GitHub pull request",
+    };
+    pub const GITLAB: Self = Self {
+        name: "gitlab",
+        description: "Comment body for a GitLab merge request",
+    };
+}
+
+impl DiffRenderer for PullRequestRenderer {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn requires_full_diff(&self) -> bool {
+        true
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "{}", COMMENT_MARKER).map_err(fmt_error)?;
+        write_markdown(&mut out, result, true).map_err(fmt_error)?;
+
+        if out.len() > COMMENT_LIMIT {
+            // The summary table shows `+N -M` per resource instead
+            let mut summary = result.clone();
+            for diff in &mut summary.modified {
+                diff.diff_text = diff.diff_text.as_deref().map(line_counts);
+            }
+            out.clear();
+            writeln!(out, "{}", COMMENT_MARKER).map_err(fmt_error)?;
+            write_markdown(&mut out, &summary, false).map_err(fmt_error)?;
+            writeln!(out).map_err(fmt_error)?;
+            writeln!(
+                out,
+                "_Diffs omitted: too long for a comment. Use `vqx diff --report` for the full diff._"
+            )
+            .map_err(fmt_error)?;
+        }
+        Ok(out)
+    }
+}
+
+/// GitHub Actions workflow commands flagging removed resources and
+/// breaking changes in the job summary and pull request
+pub fn github_annotations(result: &DiffResult) -> String {
+    // Workflow command data must not contain raw `%`, CR or LF
+    let escape = |s: &str| {
+        s.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+    let mut out = String::new();
+    for diff in &result.removed {
+        out.push_str(&format!(
+            "::warning title=Resource removed::{} exists in {} but not in {}\n",
+            escape(&diff.id),
+            escape(&result.source),
+            escape(&result.target)
+        ));
+    }
+    for issue in result.breaking.iter().flatten() {
+        let level = match issue.severity {
+            Severity::Breaking => "error",
+            Severity::Warning => "warning",
+        };
+        out.push_str(&format!(
+            "::{} title=Compatibility ({})::{}: {}\n",
+            level,
+            issue.severity,
+            escape(&issue.id),
+            escape(&issue.message)
+        ));
+    }
+    out
+}
+
+// =============================================================================
+// side-by-side
+// =============================================================================
+
+/// Width of each column in side-by-side output
+const SIDE_BY_SIDE_WIDTH: usize = 60;
+
+/// Two-column terminal view of modified resources
+pub struct SideBySideRenderer;
+
+impl DiffRenderer for SideBySideRenderer {
+    fn name(&self) -> &'static str {
+        "side-by-side"
+    }
+
+    fn description(&self) -> &'static str {
+        "Two-column view of modified resources"
+    }
+
+    fn requires_full_diff(&self) -> bool {
+        true
+    }
+
+    fn is_terminal(&self) -> bool {
+        true
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        let mut out = String::new();
+        write_side_by_side(&mut out, result).map_err(fmt_error)?;
+        Ok(out)
+    }
+}
+
+fn fit_column(text: &str) -> String {
+    let count = text.chars().count();
+    if count > SIDE_BY_SIDE_WIDTH {
+        let truncated: String = text.chars().take(SIDE_BY_SIDE_WIDTH - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        format!("{}{}", text, " ".repeat(SIDE_BY_SIDE_WIDTH - count))
+    }
+}
+
+/// Pair up removed and added lines of one change block
+fn flush_block(
+    out: &mut String,
+    left: &mut Vec<String>,
+    right: &mut Vec<String>,
+) -> std::fmt::Result {
+    for i in 0..left.len().max(right.len()) {
+        let (l, r) = (left.get(i), right.get(i));
+        let marker = match (l, r) {
+            (Some(_), Some(_)) => style("|").yellow(),
+            (Some(_), None) => style("<").red(),
+            _ => style(">").green(),
+        };
+        writeln!(
+            out,
+            "{} {} {}",
+            style(fit_column(l.map(String::as_str).unwrap_or(""))).red(),
+            marker,
+            style(r.map(String::as_str).unwrap_or("")).green()
+        )?;
+    }
+    left.clear();
+    right.clear();
+    Ok(())
+}
+
+fn write_side_by_side(out: &mut String, result: &DiffResult) -> std::fmt::Result {
+    writeln!(
+        out,
+        "{} {}",
+        style(fit_column(&result.source)).bold(),
+        style(&result.target).bold()
+    )?;
+
+    for diff in result.added.iter().chain(&result.removed) {
+        writeln!(out, "{} {}", style(&diff.change).dim(), diff.id)?;
+    }
+
+    for (diff, text) in modified_with_lines(result) {
+        writeln!(out)?;
+        writeln!(out, "{}", style(&diff.id).yellow().bold())?;
+        writeln!(
+            out,
+            "{}",
+            style("─".repeat(SIDE_BY_SIDE_WIDTH * 2 + 3)).dim()
+        )?;
+
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        for line in text.lines() {
+            if let Some(rest) = line.strip_prefix('-') {
+                left.push(rest.to_string());
+            } else if let Some(rest) = line.strip_prefix('+') {
+                right.push(rest.to_string());
+            } else {
+                flush_block(out, &mut left, &mut right)?;
+                if line.starts_with("@@") || line.starts_with('#') {
+                    writeln!(out, "{}", style(line).cyan())?;
+                } else {
+                    let content = line.strip_prefix(' ').unwrap_or(line);
+                    writeln!(out, "{}   {}", fit_column(content), content)?;
+                }
+            }
+        }
+        flush_block(out, &mut left, &mut right)?;
+    }
+
+    if !result.has_changes() {
+        writeln!(
+            out,
+            "{} {}",
+            style("✓").green().bold(),
+            i18n::t("diff.no_differences")
+        )?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// patch
+// =============================================================================
+
+/// Unified diff of modified resources
+pub struct PatchRenderer;
+
+impl DiffRenderer for PatchRenderer {
+    fn name(&self) -> &'static str {
+        "patch"
+    }
+
+    fn description(&self) -> &'static str {
+        "Unified diff of modified resources (source as a/, target as b/)"
+    }
+
+    fn requires_full_diff(&self) -> bool {
+        true
+    }
+
+    fn render(&self, result: &DiffResult, _options: RenderOptions) -> Result<String> {
+        let mut out = String::new();
+        write_patch(&mut out, result).map_err(fmt_error)?;
+        Ok(out)
+    }
+}
+
+/// Write one file section; `lines` is a whole-file listing or unified hunks
+fn write_patch_file(out: &mut String, path: &str, lines: &[&str]) -> std::fmt::Result {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "--- a/{}", path)?;
+    writeln!(out, "+++ b/{}", path)?;
+
+    if !lines.iter().any(|l| l.starts_with("@@")) {
+        // Whole-file listing: wrap it in a single hunk
+        let old = lines.iter().filter(|l| !l.starts_with('+')).count();
+        let new = lines.iter().filter(|l| !l.starts_with('-')).count();
+        writeln!(out, "@@ -1,{} +1,{} @@", old, new)?;
+    }
+    for line in lines {
+        writeln!(out, "{}", line)?;
+    }
+    Ok(())
+}
+
+fn write_patch(out: &mut String, result: &DiffResult) -> std::fmt::Result {
+    for diff in result.added.iter().chain(&result.removed) {
+        writeln!(out, "# {}: {}", diff.change, diff.id)?;
+    }
+
+    for (diff, text) in modified_with_lines(result) {
+        // Code resources have separate "# code" and "# metadata" sections;
+        // code maps onto the `.vail` file written by `extract_code`
+        let mut code = Vec::new();
+        let mut json = Vec::new();
+        let mut in_code = false;
+        for line in text.lines() {
+            match line {
+                "# code" => in_code = true,
+                "# metadata" => in_code = false,
+                _ if line.starts_with("---") || line.starts_with("+++") => {}
+                _ if in_code => code.push(line),
+                _ => json.push(line),
+            }
+        }
+
+        write_patch_file(out, &format!("{}.vail", diff.id), &code)?;
+        write_patch_file(out, &format!("{}.json", diff.id), &json)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::diff::{ChangeKind, ResourceDiff};
+
+    fn sample_diff() -> DiffResult {
+        DiffResult {
+            success: true,
+            source: "dev".to_string(),
+            target: "prod".to_string(),
+            added: vec![ResourceDiff::new("types", "Order", ChangeKind::Added, None)],
+            removed: vec![],
+            modified: vec![ResourceDiff::new(
+                "types",
+                "Customer",
+                ChangeKind::Modified,
+                Some(" {\n-  \"a\": 1\n+  \"a\": 2\n }\n".to_string()),
+            )],
+            errors: vec![],
+            breaking: None,
+        }
+    }
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        let renderers = diff_renderers();
+        let mut names: Vec<_> = renderers.iter().map(|r| r.name()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), renderers.len());
+
+        assert!(diff_renderer("markdown").is_ok());
+        assert!(diff_renderer("nope").is_err());
+    }
+
+    #[test]
+    fn test_ndjson_one_entry_per_line() {
+        let ndjson = NdjsonRenderer
+            .render(&sample_diff(), RenderOptions::default())
+            .unwrap();
+        let lines: Vec<&str> = ndjson.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains(r#""change":"Added""#));
+        assert!(lines[1].contains(r#""id":"types/Customer""#));
+    }
+
+    #[test]
+    fn test_porcelain_sorted_status_lines() {
+        let porcelain = PorcelainRenderer
+            .render(&sample_diff(), RenderOptions::default())
+            .unwrap();
+        assert_eq!(porcelain, "M types/Customer\nA types/Order\n");
+    }
+
+    #[test]
+    fn test_patch_wraps_listing_in_hunk() {
+        let patch = PatchRenderer
+            .render(&sample_diff(), RenderOptions { full: true })
+            .unwrap();
+
+        assert!(patch.contains("# added: types/Order"));
+        assert!(patch.contains("--- a/types/Customer.json\n+++ b/types/Customer.json\n"));
+        assert!(patch.contains("@@ -1,3 +1,3 @@\n {\n-  \"a\": 1\n+  \"a\": 2\n }\n"));
+    }
+
+    #[test]
+    fn test_markdown_and_html() {
+        let markdown = MarkdownRenderer
+            .render(&sample_diff(), RenderOptions { full: true })
+            .unwrap();
+        assert!(markdown.contains("| added | `types/Order` | |"));
+        assert!(markdown.contains("```diff\n {\n-  \"a\": 1"));
+        assert!(markdown.contains("| types | 1 | 0 | 1 |"));
+        assert!(markdown.contains("<summary><code>types/Customer</code> (+1 -1)</summary>"));
+
+        let html = HtmlRenderer
+            .render(&sample_diff(), RenderOptions { full: true })
+            .unwrap();
+        assert!(html.contains("<span class=\"del\">-  &quot;a&quot;: 1</span>"));
+        assert!(html.contains("<tr><td>types</td><td>1</td><td>0</td><td>1</td></tr>"));
+        assert!(html.contains("<details>\n<summary><code>types/Customer</code> (+1 -1)</summary>"));
+    }
+
+    #[test]
+    fn test_pull_request_comment() {
+        let mut result = sample_diff();
+        result.removed = vec![ResourceDiff::new(
+            "procedures",
+            "Old",
+            ChangeKind::Removed,
+            None,
+        )];
+
+        let comment = PullRequestRenderer::GITHUB
+            .render(&result, RenderOptions { full: true })
+            .unwrap();
+        assert!(comment.starts_with(COMMENT_MARKER));
+        assert!(comment.contains("<summary><code>types/Customer</code> (+1 -1)</summary>"));
+
+        result.modified[0].diff_text = Some("+x\n".repeat(COMMENT_LIMIT));
+        let comment = PullRequestRenderer::GITLAB
+            .render(&result, RenderOptions { full: true })
+            .unwrap();
+        assert!(comment.len() < COMMENT_LIMIT);
+        assert!(comment.contains("| modified | `types/Customer` | +60000 -0 |"));
+
+        assert_eq!(
+            github_annotations(&result),
+            "::warning title=Resource removed::procedures/Old exists in dev but not in prod\n"
+        );
+    }
+
+    #[test]
+    fn test_report_renderer_by_extension() {
+        assert_eq!(
+            report_renderer(Path::new("out.HTML")).unwrap().name(),
+            "html"
+        );
+        assert_eq!(
+            report_renderer(Path::new("review/diff.md")).unwrap().name(),
+            "markdown"
+        );
+        assert!(report_renderer(Path::new("diff.txt")).is_err());
+    }
+
+    #[test]
+    fn test_side_by_side_pairs_changes() {
+        console::set_colors_enabled(false);
+        let output = SideBySideRenderer
+            .render(&sample_diff(), RenderOptions { full: true })
+            .unwrap();
+
+        let changed = output.lines().find(|l| l.contains(" | ")).unwrap();
+        assert!(changed.starts_with("  \"a\": 1"));
+        assert!(changed.ends_with("  \"a\": 2"));
+    }
+}
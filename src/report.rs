@@ -0,0 +1,292 @@
+//! Versioned JSON reports
+//!
+//! Every machine-readable report vqx prints goes through this module so
+//! downstream parsers see the same shape between releases:
+//! - `report_version` is always the first field
+//! - fields follow struct declaration order (never hash order)
+//! - collections are sorted by a stable ID before serialization
+//!
+//! Bump `REPORT_VERSION` only for incompatible changes (renamed/removed
+//! fields or changed semantics). Adding fields is backwards compatible.
+//!
+//! `--output yaml` prints the same document as YAML and `--output ndjson`
+//! prints it on a single line; commands with rows (lists, diff entries,
+//! doctor checks) print one row per line instead with [`to_ndjson`].
+
+use crate::approval::{ApprovalRequest, Decision};
+use crate::cli::OutputFormat;
+use crate::commands::deploy::DeployPlan;
+use crate::commands::diff::DiffResult;
+use crate::commands::promote::PromoteResult;
+use crate::commands::sync::SyncResult;
+use crate::error::Result;
+use crate::summary::{self, ExecutionReport};
+use schemars::schema::RootSchema;
+use schemars::{schema_for, JsonSchema};
+use serde::{Serialize, Serializer};
+
+/// Current schema version of all JSON reports
+pub const REPORT_VERSION: u32 = 1;
+
+/// Wrapper that prepends `report_version` to a serialized report
+///
+/// Struct and map reports are flattened next to `report_version`; any other
+/// value (a list, a string) is nested under a `report` key instead, since
+/// flattening only works for maps.
+#[derive(Debug, JsonSchema)]
+pub struct Versioned<'a, T: Serialize> {
+    pub report_version: u32,
+    #[serde(flatten)]
+    pub report: &'a T,
+    /// Step timing with `--summary`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ExecutionReport>,
+}
+
+#[derive(Serialize)]
+struct Flattened<'a, T: Serialize> {
+    report_version: u32,
+    #[serde(flatten)]
+    report: &'a T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: &'a Option<ExecutionReport>,
+}
+
+#[derive(Serialize)]
+struct Nested<'a, T: Serialize> {
+    report_version: u32,
+    report: &'a T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    summary: &'a Option<ExecutionReport>,
+}
+
+impl<T: Serialize> Serialize for Versioned<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let is_map = matches!(
+            serde_json::to_value(self.report),
+            Ok(serde_json::Value::Object(_))
+        );
+        if is_map {
+            Flattened {
+                report_version: self.report_version,
+                report: self.report,
+                summary: &self.summary,
+            }
+            .serialize(serializer)
+        } else {
+            Nested {
+                report_version: self.report_version,
+                report: self.report,
+                summary: &self.summary,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'a, T: Serialize> Versioned<'a, T> {
+    pub fn new(report: &'a T) -> Self {
+        Self {
+            report_version: REPORT_VERSION,
+            report,
+            summary: summary::current(),
+        }
+    }
+}
+
+/// Serialize a report as pretty-printed, versioned JSON
+pub fn to_json<T: Serialize>(report: &T) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&Versioned::new(report))?)
+}
+
+/// Serialize a report in a machine-readable output format
+///
+/// Text, CSV and porcelain have no generic form and fall back to JSON.
+pub fn render<T: Serialize>(report: &T, format: OutputFormat) -> Result<String> {
+    serialize(&Versioned::new(report), format)
+}
+
+/// Serialize an unversioned value (e.g. a profile) in a machine-readable format
+pub fn serialize<T: Serialize + ?Sized>(value: &T, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Yaml => Ok(serde_yaml::to_string(value)?.trim_end().to_string()),
+        OutputFormat::Ndjson => Ok(serde_json::to_string(value)?),
+        OutputFormat::Json | OutputFormat::Text | OutputFormat::Csv | OutputFormat::Porcelain => {
+            Ok(serde_json::to_string_pretty(value)?)
+        }
+    }
+}
+
+/// Serialize rows as NDJSON: one compact object per line, each ending in a newline
+pub fn to_ndjson<T: Serialize>(rows: &[T]) -> Result<String> {
+    let mut out = String::new();
+    for row in rows {
+        out.push_str(&serde_json::to_string(row)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Report formats with a published JSON Schema, keyed by name
+///
+/// The approval records are the audit trail written to the approvals
+/// directory; they are stored as-is, without `report_version`.
+pub fn report_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("approval-decision", schema_for!(Decision)),
+        ("approval-request", schema_for!(ApprovalRequest)),
+        ("deploy-plan", schema_for!(Versioned<'static, DeployPlan>)),
+        ("diff", schema_for!(Versioned<'static, DiffResult>)),
+        ("promote", schema_for!(Versioned<'static, PromoteResult>)),
+        ("sync", schema_for!(Versioned<'static, SyncResult>)),
+    ]
+}
+
+/// Stable identifier for a resource: `<resource_type>/<name>`
+pub fn resource_id(resource_type: &str, name: &str) -> String {
+    format!("{}/{}", resource_type, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::diff::{ChangeKind, DiffResult, ResourceDiff};
+
+    fn sample_diff() -> DiffResult {
+        DiffResult {
+            success: true,
+            source: "dev".to_string(),
+            target: "prod".to_string(),
+            added: vec![ResourceDiff::new("types", "Order", ChangeKind::Added, None)],
+            removed: vec![],
+            modified: vec![ResourceDiff::new(
+                "procedures",
+                "calc",
+                ChangeKind::Modified,
+                Some("+1 -1".to_string()),
+            )],
+            errors: vec![],
+            breaking: None,
+        }
+    }
+
+    #[test]
+    fn test_report_version_first() {
+        let json = to_json(&serde_json::json!({"b": 1, "a": 2})).unwrap();
+        assert!(json.starts_with("{\n  \"report_version\": 1,"));
+    }
+
+    #[test]
+    fn test_diff_report_golden() {
+        let json = serde_json::to_string(&Versioned::new(&sample_diff())).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"report_version":1,"success":true,"source":"dev","target":"prod","#,
+                r#""added":[{"id":"types/Order","resource_type":"types","name":"Order","change":"Added","diff_text":null}],"#,
+                r#""removed":[],"#,
+                r#""modified":[{"id":"procedures/calc","resource_type":"procedures","name":"calc","change":"Modified","diff_text":"+1 -1"}],"#,
+                r#""errors":[],"breaking":null}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_report_schemas() {
+        let schemas = report_schemas();
+        let names: Vec<_> = schemas.iter().map(|(n, _)| *n).collect();
+        assert_eq!(
+            names,
+            vec![
+                "approval-decision",
+                "approval-request",
+                "deploy-plan",
+                "diff",
+                "promote",
+                "sync"
+            ]
+        );
+
+        let diff = serde_json::to_value(&schemas[3].1).unwrap();
+        assert!(diff["required"]
+            .as_array()
+            .unwrap()
+            .contains(&"report_version".into()));
+        assert!(diff["properties"]["added"].is_object());
+
+        let plan = serde_json::to_value(&schemas[2].1).unwrap();
+        assert!(plan["properties"]["changes"].is_object());
+
+        let request = serde_json::to_value(&schemas[1].1).unwrap();
+        assert!(request["properties"].get("report_version").is_none());
+        assert_eq!(request["properties"]["requested_at"]["format"], "date-time");
+    }
+
+    #[test]
+    fn test_diff_report_pretty_golden() {
+        let expected = r#"{
+  "report_version": 1,
+  "success": true,
+  "source": "dev",
+  "target": "prod",
+  "added": [
+    {
+      "id": "types/Order",
+      "resource_type": "types",
+      "name": "Order",
+      "change": "Added",
+      "diff_text": null
+    }
+  ],
+  "removed": [],
+  "modified": [
+    {
+      "id": "procedures/calc",
+      "resource_type": "procedures",
+      "name": "calc",
+      "change": "Modified",
+      "diff_text": "+1 -1"
+    }
+  ],
+  "errors": [],
+  "breaking": null
+}"#;
+        assert_eq!(to_json(&sample_diff()).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_non_map_report_is_nested() {
+        let json = serde_json::to_string(&Versioned::new(&vec!["a", "b"])).unwrap();
+        assert_eq!(json, r#"{"report_version":1,"report":["a","b"]}"#);
+
+        let json = serde_json::to_string(&Versioned::new(&"done")).unwrap();
+        assert_eq!(json, r#"{"report_version":1,"report":"done"}"#);
+    }
+
+    #[test]
+    fn test_render_formats() {
+        let diff = sample_diff();
+        assert_eq!(
+            render(&diff, OutputFormat::Json).unwrap(),
+            to_json(&diff).unwrap()
+        );
+
+        let yaml = render(&diff, OutputFormat::Yaml).unwrap();
+        assert!(yaml.starts_with("report_version: 1\nsuccess: true\n"));
+
+        let line = render(&diff, OutputFormat::Ndjson).unwrap();
+        assert!(!line.contains('\n'));
+        assert!(line.starts_with(r#"{"report_version":1,"#));
+    }
+
+    #[test]
+    fn test_to_ndjson() {
+        let diff = sample_diff();
+        let ndjson = to_ndjson(&diff.added).unwrap();
+        assert_eq!(
+            ndjson,
+            "{\"id\":\"types/Order\",\"resource_type\":\"types\",\"name\":\"Order\",\"change\":\"Added\",\"diff_text\":null}\n"
+        );
+        assert_eq!(to_ndjson::<ResourceDiff>(&[]).unwrap(), "");
+    }
+}
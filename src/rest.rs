@@ -0,0 +1,407 @@
+//! Vantiq REST API client (vqx extension)
+//!
+//! Talks to the server directly instead of starting the Java CLI, which
+//! saves the JVM startup on every call. Results are returned as
+//! [`ExecResult`]s shaped like the CLI's output (JSON on stdout, the error
+//! on stderr), so commands handle both backends alike.
+//!
+//! Only compiled with the `rest-backend` feature. Profiles opt in with
+//! `backend = "rest"`; see [`crate::backend::Executor`] for which
+//! operations go through here.
+
+use crate::error::{Result, VqxError};
+use crate::profile::Profile;
+use crate::underlying::{exit_status, ExecResult};
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Method, RequestBuilder, StatusCode, Url};
+use serde_json::Value;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Resource types served under `/api/v1/resources/<type>`
+///
+/// Anything else is taken to be a user-defined type, served under
+/// `/api/v1/resources/custom/<type>`.
+const SYSTEM_RESOURCES: &[&str] = &[
+    "aipatterns",
+    "catalogs",
+    "clients",
+    "collaborationtypes",
+    "configurations",
+    "debugconfigs",
+    "deployconfigs",
+    "documents",
+    "environments",
+    "images",
+    "namespaces",
+    "nodes",
+    "procedures",
+    "profiles",
+    "projects",
+    "rules",
+    "scheduledevents",
+    "secrets",
+    "services",
+    "situations",
+    "sources",
+    "subscriptions",
+    "systemmodels",
+    "tensorflowmodels",
+    "topics",
+    "types",
+    "users",
+    "videos",
+];
+
+/// How requests authenticate
+#[derive(Debug, Clone)]
+enum Auth {
+    Token(String),
+    Basic { username: String, password: String },
+}
+
+/// Client for the Vantiq REST API
+pub struct RestClient {
+    client: Client,
+    base_url: Url,
+    auth: Auth,
+    timeout: Duration,
+}
+
+impl RestClient {
+    /// Create a client for a profile
+    ///
+    /// Returns `None` when the profile cannot be served over REST: without
+    /// credentials, or with a namespace (selecting a namespace needs the
+    /// CLI's login flow).
+    pub fn from_profile(profile: &Profile, timeout: Duration) -> Result<Option<Self>> {
+        if profile.namespace.is_some() {
+            info!("Profile selects a namespace, using the CLI instead of REST");
+            return Ok(None);
+        }
+        // Like the CLI, a password takes precedence over a token
+        let auth = match (&profile.username, &profile.password, &profile.token) {
+            (Some(username), Some(password), _) => Auth::Basic {
+                username: username.clone(),
+                password: password.clone(),
+            },
+            (_, _, Some(token)) => Auth::Token(token.clone()),
+            _ => return Ok(None),
+        };
+
+        let base_url = Url::parse(&profile.url).map_err(|e| VqxError::ProfileInvalid {
+            message: format!("Invalid URL '{}': {}", profile.url, e),
+        })?;
+
+        let trust_all = profile.trust_ssl
+            || profile
+                .client_options
+                .as_ref()
+                .is_some_and(|c| c.trust_all || !c.verify_host);
+        let mut builder = Client::builder()
+            .timeout(timeout)
+            .danger_accept_invalid_certs(trust_all);
+        if let Some(proxy) = profile
+            .client_options
+            .as_ref()
+            .and_then(|c| c.proxy.as_ref())
+        {
+            let mut p = reqwest::Proxy::all(format!("http://{}:{}", proxy.host, proxy.port))
+                .map_err(|e| VqxError::ProfileInvalid {
+                    message: format!("Invalid proxy: {}", e),
+                })?;
+            if let (Some(username), Some(password)) = (&proxy.username, &proxy.password) {
+                p = p.basic_auth(username, password);
+            }
+            builder = builder.proxy(p);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| VqxError::Other(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Some(Self {
+            client,
+            base_url,
+            auth,
+            timeout,
+        }))
+    }
+
+    /// Exchange the profile's username/password for an access token
+    ///
+    /// Vantiq's `GET /authenticate` answers with the token and, depending on
+    /// the version, its lifetime in seconds (`expiresIn`) or its expiry in
+    /// milliseconds since the epoch (`expiresAt`).
+    pub async fn authenticate(&self) -> Result<Session> {
+        let Auth::Basic {
+            ref username,
+            ref password,
+        } = self.auth
+        else {
+            return Err(VqxError::Other(
+                "Logging in needs a username and password".to_string(),
+            ));
+        };
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| VqxError::ProfileInvalid {
+                message: format!("Invalid URL: {}", self.base_url),
+            })?
+            .pop_if_empty()
+            .push("authenticate");
+        debug!(%url, "REST request");
+
+        let request = self.client.get(url).basic_auth(username, Some(password));
+        let result = self.send(request).await?;
+        if !result.success() {
+            return Err(VqxError::AuthenticationFailed {
+                message: result.stderr,
+            });
+        }
+        parse_session(&result.stdout, Utc::now())
+    }
+
+    /// `list <resource>`: all instances of a resource
+    pub async fn list(&self, resource: &str) -> Result<ExecResult> {
+        self.send(self.request(Method::GET, resource, None)?).await
+    }
+
+    /// `find <resource> <id>`: one instance by name or id
+    pub async fn find(&self, resource: &str, resource_id: &str) -> Result<ExecResult> {
+        self.send(self.request(Method::GET, resource, Some(resource_id))?)
+            .await
+    }
+
+    /// `select <resource>` with an optional qualification and property list
+    ///
+    /// `qual` is the JSON query; `props` is a JSON array of property names;
+    /// `limit` caps the number of records the server returns.
+    pub async fn select(
+        &self,
+        resource: &str,
+        qual: Option<&str>,
+        props: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<ExecResult> {
+        let mut request = self.request(Method::GET, resource, None)?;
+        if let Some(qual) = qual {
+            request = request.query(&[("where", qual)]);
+        }
+        if let Some(props) = props {
+            request = request.query(&[("props", props)]);
+        }
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)]);
+        }
+        self.send(request).await
+    }
+
+    /// `insert`/`upsert <resource>`: `records` is a JSON array of records
+    pub async fn write(&self, resource: &str, records: &str, upsert: bool) -> Result<ExecResult> {
+        let mut request = self
+            .request(Method::POST, resource, None)?
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(records.to_string());
+        if upsert {
+            request = request.query(&[("upsert", "true")]);
+        }
+        self.send(request).await
+    }
+
+    /// `delete <resource> <id>`
+    pub async fn delete(&self, resource: &str, resource_id: &str) -> Result<ExecResult> {
+        self.send(self.request(Method::DELETE, resource, Some(resource_id))?)
+            .await
+    }
+
+    /// `deleteMatching <resource> <query>`
+    pub async fn delete_matching(&self, resource: &str, query: &str) -> Result<ExecResult> {
+        let request = self
+            .request(Method::DELETE, resource, None)?
+            .query(&[("where", query)]);
+        self.send(request).await
+    }
+
+    /// Build an authenticated request for a resource (and instance)
+    fn request(
+        &self,
+        method: Method,
+        resource: &str,
+        resource_id: Option<&str>,
+    ) -> Result<RequestBuilder> {
+        let url = resource_url(&self.base_url, resource, resource_id)?;
+        debug!(%method, %url, "REST request");
+
+        let request = self.client.request(method, url);
+        Ok(match self.auth {
+            Auth::Token(ref token) => request.bearer_auth(token),
+            Auth::Basic {
+                ref username,
+                ref password,
+            } => request.basic_auth(username, Some(password)),
+        })
+    }
+
+    /// Send a request and shape the response like CLI output
+    async fn send(&self, request: RequestBuilder) -> Result<ExecResult> {
+        crate::offline::check("REST request")?;
+        let response = request.send().await.map_err(|e| self.request_error(e))?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| self.request_error(e))?;
+        debug!(%status, body_len = body.len(), "REST response");
+
+        if matches!(status, StatusCode::UNAUTHORIZED) {
+            return Err(VqxError::AuthenticationFailed {
+                message: format!("{}: {}", status, body.trim()),
+            });
+        }
+        if !status.is_success() {
+            warn!(%status, body = %body, "REST request failed");
+            return Ok(ExecResult {
+                status: exit_status(1),
+                stdout: String::new(),
+                stderr: format!("HTTP {}: {}", status, body.trim()),
+                retries: Vec::new(),
+            });
+        }
+
+        Ok(ExecResult {
+            status: exit_status(0),
+            stdout: body,
+            stderr: String::new(),
+            retries: Vec::new(),
+        })
+    }
+
+    fn request_error(&self, e: reqwest::Error) -> VqxError {
+        if e.is_timeout() {
+            VqxError::CliTimeout {
+                seconds: self.timeout.as_secs(),
+            }
+        } else {
+            VqxError::RestRequestFailed {
+                message: e.to_string(),
+            }
+        }
+    }
+}
+
+/// Access token from [`RestClient::authenticate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Lifetime assumed when the server does not say
+const DEFAULT_SESSION_SECS: i64 = 3600;
+
+/// Read the `/authenticate` response received at `now`
+fn parse_session(body: &str, now: DateTime<Utc>) -> Result<Session> {
+    let value: Value = serde_json::from_str(body).map_err(|e| VqxError::InvalidJson {
+        message: format!("Unexpected /authenticate response: {}", e),
+    })?;
+    let token = value["accessToken"]
+        .as_str()
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| {
+            VqxError::Other("No accessToken in the /authenticate response".to_string())
+        })?;
+    let expires_at = match (value["expiresAt"].as_i64(), value["expiresIn"].as_i64()) {
+        (Some(millis), _) => DateTime::from_timestamp_millis(millis),
+        (None, Some(secs)) => Some(now + chrono::Duration::seconds(secs)),
+        (None, None) => None,
+    }
+    .unwrap_or(now + chrono::Duration::seconds(DEFAULT_SESSION_SECS));
+    Ok(Session {
+        token: token.to_string(),
+        expires_at,
+    })
+}
+
+/// URL of a resource collection, or of one instance
+fn resource_url(base: &Url, resource: &str, resource_id: Option<&str>) -> Result<Url> {
+    let mut url = base.clone();
+    {
+        let mut segments = url
+            .path_segments_mut()
+            .map_err(|_| VqxError::ProfileInvalid {
+                message: format!("Invalid URL: {}", base),
+            })?;
+        segments.pop_if_empty().extend(["api", "v1", "resources"]);
+        if !SYSTEM_RESOURCES.contains(&resource) {
+            segments.push("custom");
+        }
+        segments.push(resource);
+        if let Some(id) = resource_id {
+            segments.push(id);
+        }
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_url() {
+        let base = Url::parse("https://dev.vantiq.com").unwrap();
+        assert_eq!(
+            resource_url(&base, "procedures", None).unwrap().as_str(),
+            "https://dev.vantiq.com/api/v1/resources/procedures"
+        );
+        assert_eq!(
+            resource_url(&base, "Orders", Some("a/b c"))
+                .unwrap()
+                .as_str(),
+            "https://dev.vantiq.com/api/v1/resources/custom/Orders/a%2Fb%20c"
+        );
+
+        let base = Url::parse("https://edge.local:8080/vantiq/").unwrap();
+        assert_eq!(
+            resource_url(&base, "types", Some("Orders"))
+                .unwrap()
+                .as_str(),
+            "https://edge.local:8080/vantiq/api/v1/resources/types/Orders"
+        );
+    }
+
+    #[test]
+    fn test_parse_session() {
+        let now = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let session = parse_session(r#"{"accessToken": "abc", "expiresIn": 600}"#, now).unwrap();
+        assert_eq!(session.token, "abc");
+        assert_eq!(session.expires_at, now + chrono::Duration::seconds(600));
+
+        let session =
+            parse_session(r#"{"accessToken": "abc", "expiresAt": 1700000300000}"#, now).unwrap();
+        assert_eq!(session.expires_at, now + chrono::Duration::seconds(300));
+
+        let session = parse_session(r#"{"accessToken": "abc"}"#, now).unwrap();
+        assert_eq!(session.expires_at, now + chrono::Duration::hours(1));
+
+        assert!(parse_session(r#"{"error": "no"}"#, now).is_err());
+    }
+
+    #[test]
+    fn test_from_profile() {
+        let timeout = Duration::from_secs(10);
+        let profile = Profile::new("https://dev.vantiq.com").with_token("t");
+        assert!(RestClient::from_profile(&profile, timeout)
+            .unwrap()
+            .is_some());
+
+        let profile = Profile::new("https://dev.vantiq.com");
+        assert!(RestClient::from_profile(&profile, timeout)
+            .unwrap()
+            .is_none());
+
+        let profile = Profile::new("https://dev.vantiq.com")
+            .with_credentials("u", "p")
+            .with_namespace("ns");
+        assert!(RestClient::from_profile(&profile, timeout)
+            .unwrap()
+            .is_none());
+    }
+}
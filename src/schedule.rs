@@ -0,0 +1,242 @@
+//! Cron schedules for `vqx schedule` (vqx extension)
+//!
+//! Jobs are vqx command lines stored as `[[schedule]]` entries in
+//! config.toml; `vqx schedule run` starts each one as a child vqx process
+//! when its cron expression fires.
+//!
+//! Expressions have the five standard fields (minute, hour, day of month,
+//! month, day of week) with `*`, lists, ranges, steps and month/day names,
+//! or one of `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly`. As in
+//! cron, a job whose day of month and day of week are both restricted runs
+//! when either matches. Times are local.
+
+use crate::error::{Result, VqxError};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike};
+use std::fmt;
+use std::str::FromStr;
+
+const MONTHS: &[&str] = &[
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAYS: &[&str] = &["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+/// How far ahead to look for the next run (covers Feb 29 on a given weekday)
+const SEARCH_DAYS: i64 = 366 * 28;
+
+/// A parsed cron expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day of month and day of week were both restricted
+    either_day: bool,
+}
+
+impl CronSchedule {
+    /// First time after `after` (at minute resolution) the schedule fires
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let first_day = start.date_naive();
+        for offset in 0..SEARCH_DAYS {
+            let date = first_day + Duration::days(offset);
+            if !self.matches_date(date) {
+                continue;
+            }
+            for hour in bits(self.hours) {
+                for minute in bits(self.minutes) {
+                    let Some(naive) = date.and_hms_opt(hour, minute, 0) else {
+                        continue;
+                    };
+                    // Times skipped by a DST change never fire
+                    let Some(time) = Local.from_local_datetime(&naive).earliest() else {
+                        continue;
+                    };
+                    if time >= start {
+                        return Some(time);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day = has(self.days, date.day());
+        let weekday = has(self.weekdays, date.weekday().num_days_from_sunday());
+        if self.either_day {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = VqxError;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        let invalid = |message: String| VqxError::InvalidSchedule {
+            expression: expression.to_string(),
+            message,
+        };
+        let expanded = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            other => other,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid(format!(
+                "expected 5 fields (minute hour day month weekday), found {}",
+                fields.len()
+            )));
+        };
+
+        let mut weekdays = parse_field(weekday, 0, 7, WEEKDAYS).map_err(&invalid)?;
+        // 7 is Sunday too
+        if has(weekdays, 7) {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            expression: expression.trim().to_string(),
+            minutes: parse_field(minute, 0, 59, &[]).map_err(&invalid)?,
+            hours: parse_field(hour, 0, 23, &[]).map_err(&invalid)?,
+            days: parse_field(day, 1, 31, &[]).map_err(&invalid)?,
+            months: parse_field(month, 1, 12, MONTHS).map_err(&invalid)?,
+            weekdays,
+            either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+        })
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+/// One field as a bit set of the values it allows
+///
+/// `names` are the values from `min` on (months from 1, weekdays from 0).
+fn parse_field(
+    field: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> std::result::Result<u64, String> {
+    let value = |s: &str| -> std::result::Result<u32, String> {
+        let lower = s.to_ascii_lowercase();
+        let n = match names.iter().position(|n| *n == lower) {
+            Some(i) => i as u32 + min,
+            None => s.parse().map_err(|_| format!("'{}' is not a number", s))?,
+        };
+        if (min..=max).contains(&n) {
+            Ok(n)
+        } else {
+            Err(format!("{} is outside {}-{}", n, min, max))
+        }
+    };
+
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .ok()
+                    .filter(|s| *s > 0)
+                    .ok_or_else(|| format!("invalid step in '{}'", part))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (from, to) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((from, to)) => (value(from)?, value(to)?),
+                // `5/15` runs from 5 to the end of the range
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let n = value(range)?;
+                    (n, n)
+                }
+            },
+        };
+        if from > to {
+            return Err(format!("range '{}' runs backwards", range));
+        }
+        for n in (from..=to).step_by(step as usize) {
+            set |= 1 << n;
+        }
+    }
+    Ok(set)
+}
+
+fn has(set: u64, n: u32) -> bool {
+    set & (1 << n) != 0
+}
+
+fn bits(set: u64) -> impl Iterator<Item = u32> {
+    (0..64).filter(move |n| has(set, *n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    #[test]
+    fn test_next_after() {
+        let nightly: CronSchedule = "0 2 * * *".parse().unwrap();
+        assert_eq!(
+            nightly.next_after(at(2026, 3, 10, 1, 30)),
+            Some(at(2026, 3, 10, 2, 0))
+        );
+        assert_eq!(
+            nightly.next_after(at(2026, 3, 10, 2, 0)),
+            Some(at(2026, 3, 11, 2, 0))
+        );
+
+        let weekdays: CronSchedule = "*/15 9-17 * * mon-fri".parse().unwrap();
+        // 2026-03-14 is a Saturday
+        assert_eq!(
+            weekdays.next_after(at(2026, 3, 14, 12, 0)),
+            Some(at(2026, 3, 16, 9, 0))
+        );
+        assert_eq!(
+            weekdays.next_after(at(2026, 3, 16, 9, 7)),
+            Some(at(2026, 3, 16, 9, 15))
+        );
+
+        // Day of month or Sunday (7)
+        let either: CronSchedule = "0 0 1 * 7".parse().unwrap();
+        assert_eq!(
+            either.next_after(at(2026, 3, 10, 0, 0)),
+            Some(at(2026, 3, 15, 0, 0))
+        );
+
+        let leap: CronSchedule = "@yearly".parse().unwrap();
+        assert_eq!(
+            leap.next_after(at(2026, 3, 10, 0, 0)),
+            Some(at(2027, 1, 1, 0, 0))
+        );
+
+        assert!("0 2 * *".parse::<CronSchedule>().is_err());
+        assert!("61 * * * *".parse::<CronSchedule>().is_err());
+        assert!("0 5-2 * * *".parse::<CronSchedule>().is_err());
+        assert!("*/0 * * * *".parse::<CronSchedule>().is_err());
+    }
+}
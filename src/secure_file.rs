@@ -0,0 +1,308 @@
+//! Protection of files holding credentials (vqx extension)
+//!
+//! `profiles.toml` can contain plaintext passwords and tokens, so it is
+//! written readable by the owner only, and loading it warns when other users
+//! can read it. Optionally the whole store is kept passphrase-encrypted as
+//! `profiles.toml.enc` (age format, `age-encryption` feature). The passphrase
+//! comes from `VQX_PROFILES_PASSPHRASE`, the OS keyring (`vqx profile encrypt
+//! --remember`) or a prompt, and is asked for at most once per run.
+
+use crate::error::{Result, VqxError};
+use crate::prompt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Environment variable holding the passphrase of the encrypted store
+pub const PASSPHRASE_ENV: &str = "VQX_PROFILES_PASSPHRASE";
+
+/// Keyring entry of a remembered passphrase
+#[cfg(feature = "keyring-storage")]
+const KEYRING_SERVICE: &str = "vqx";
+#[cfg(feature = "keyring-storage")]
+const KEYRING_KEY: &str = "profiles-passphrase";
+
+/// Passphrase of this run, once known
+static PASSPHRASE: Mutex<Option<String>> = Mutex::new(None);
+
+static WARNED_SHARED: AtomicBool = AtomicBool::new(false);
+
+/// `<file>.enc`, the encrypted form of a file
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".enc");
+    PathBuf::from(name)
+}
+
+/// Write a file that only its owner can read
+///
+/// The content goes to a temporary file that replaces `path`, so readers
+/// see either the old or the new file, never a partial one.
+pub fn write_private(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    // Temporary files are created readable by the owner only
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    file.write_all(contents.as_ref())?;
+    file.as_file().sync_all()?;
+    file.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Whether users other than the owner can read the file
+pub fn is_shared(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o077 != 0)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Warn (once per run) when a credentials file is readable by others
+pub fn warn_if_shared(path: &Path) {
+    if is_shared(path) && !WARNED_SHARED.swap(true, Ordering::Relaxed) {
+        warn!(
+            path = %path.display(),
+            "Profiles file is readable by other users; run `chmod 600` on it"
+        );
+    }
+}
+
+/// Passphrase to unlock the encrypted store
+pub fn passphrase(path: &Path) -> Result<String> {
+    if let Some(passphrase) = cached() {
+        return Ok(passphrase);
+    }
+    let passphrase = match from_env_or_keyring()? {
+        Some(passphrase) => passphrase,
+        None => {
+            if !prompt::is_interactive() {
+                return Err(VqxError::EncryptionFailed {
+                    message: format!(
+                        "{} is locked; set {} or remember the passphrase with `vqx profile encrypt --remember`",
+                        path.display(),
+                        PASSPHRASE_ENV
+                    ),
+                });
+            }
+            prompt::password(
+                format!("Passphrase for {}", path.display()),
+                "Unlocking the profile store",
+                &format!("set {}", PASSPHRASE_ENV),
+            )?
+        }
+    };
+    cache(&passphrase);
+    Ok(passphrase)
+}
+
+/// Passphrase for a store being encrypted, confirmed when prompted
+pub fn new_passphrase() -> Result<String> {
+    let passphrase = match std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty()) {
+        Some(passphrase) => passphrase,
+        None => prompt::new_password(
+            "New passphrase",
+            "Repeat passphrase",
+            "Choosing a passphrase",
+            &format!("set {}", PASSPHRASE_ENV),
+        )?,
+    };
+    if passphrase.is_empty() {
+        return Err(VqxError::EncryptionFailed {
+            message: "The passphrase must not be empty".to_string(),
+        });
+    }
+    cache(&passphrase);
+    Ok(passphrase)
+}
+
+fn cached() -> Option<String> {
+    PASSPHRASE.lock().ok().and_then(|p| p.clone())
+}
+
+fn cache(passphrase: &str) {
+    if let Ok(mut cached) = PASSPHRASE.lock() {
+        *cached = Some(passphrase.to_string());
+    }
+}
+
+fn from_env_or_keyring() -> Result<Option<String>> {
+    if let Some(passphrase) = std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty()) {
+        return Ok(Some(passphrase));
+    }
+
+    #[cfg(feature = "keyring-storage")]
+    {
+        let entry = keyring_entry()?;
+        match entry.get_password() {
+            Ok(passphrase) => Ok(Some(passphrase)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => {
+                // A broken keyring falls back to the prompt
+                warn!(error = %e, "Failed to get the profiles passphrase from keyring");
+                Ok(None)
+            }
+        }
+    }
+
+    #[cfg(not(feature = "keyring-storage"))]
+    Ok(None)
+}
+
+#[cfg(feature = "keyring-storage")]
+fn keyring_entry() -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_KEY).map_err(|e| VqxError::SecretStorageFailed {
+        message: e.to_string(),
+    })
+}
+
+/// Store the passphrase in the OS keyring for transparent unlocking
+#[cfg(feature = "keyring-storage")]
+pub fn remember(passphrase: &str) -> Result<()> {
+    keyring_entry()?
+        .set_password(passphrase)
+        .map_err(|e| VqxError::SecretStorageFailed {
+            message: e.to_string(),
+        })
+}
+
+/// Remove a remembered passphrase
+#[cfg(feature = "keyring-storage")]
+pub fn forget() -> Result<()> {
+    match keyring_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(VqxError::SecretStorageFailed {
+            message: e.to_string(),
+        }),
+    }
+}
+
+#[cfg(not(feature = "keyring-storage"))]
+pub fn remember(_passphrase: &str) -> Result<()> {
+    Err(VqxError::SecretStorageFailed {
+        message: format!(
+            "vqx was built without keyring support; set {} instead",
+            PASSPHRASE_ENV
+        ),
+    })
+}
+
+#[cfg(not(feature = "keyring-storage"))]
+pub fn forget() -> Result<()> {
+    Ok(())
+}
+
+/// Encrypt `plaintext` with a passphrase
+#[cfg(feature = "age-encryption")]
+pub fn encrypt(plaintext: &str, passphrase: &str) -> Result<Vec<u8>> {
+    use age::secrecy::Secret;
+    use std::io::Write;
+
+    let error = |e: &dyn std::fmt::Display| VqxError::EncryptionFailed {
+        message: e.to_string(),
+    };
+    let encryptor = age::Encryptor::with_user_passphrase(Secret::new(passphrase.to_owned()));
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|e| error(&e))?;
+    writer
+        .write_all(plaintext.as_bytes())
+        .map_err(|e| error(&e))?;
+    writer.finish().map_err(|e| error(&e))?;
+    Ok(encrypted)
+}
+
+/// Decrypt what [`encrypt`] wrote
+#[cfg(feature = "age-encryption")]
+pub fn decrypt(encrypted: &[u8], passphrase: &str) -> Result<String> {
+    use age::secrecy::Secret;
+    use std::io::Read;
+
+    let error = |e: &dyn std::fmt::Display| VqxError::EncryptionFailed {
+        message: e.to_string(),
+    };
+    let decryptor = match age::Decryptor::new(encrypted).map_err(|e| error(&e))? {
+        age::Decryptor::Passphrase(decryptor) => decryptor,
+        _ => return Err(error(&"not a passphrase-encrypted file")),
+    };
+    let mut reader = decryptor
+        .decrypt(&Secret::new(passphrase.to_owned()), None)
+        .map_err(|e| error(&e))?;
+    let mut plaintext = String::new();
+    reader
+        .read_to_string(&mut plaintext)
+        .map_err(|e| error(&e))?;
+    Ok(plaintext)
+}
+
+#[cfg(not(feature = "age-encryption"))]
+pub fn encrypt(_plaintext: &str, _passphrase: &str) -> Result<Vec<u8>> {
+    ensure_available().map(|_| Vec::new())
+}
+
+#[cfg(not(feature = "age-encryption"))]
+pub fn decrypt(_encrypted: &[u8], _passphrase: &str) -> Result<String> {
+    ensure_available().map(|_| String::new())
+}
+
+/// Fail early when vqx was built without encryption
+pub fn ensure_available() -> Result<()> {
+    if cfg!(feature = "age-encryption") {
+        return Ok(());
+    }
+    Err(VqxError::EncryptionFailed {
+        message: "vqx was built without the age-encryption feature \
+                  (cargo install --path . --features age-encryption)"
+            .to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_path() {
+        assert_eq!(
+            encrypted_path(Path::new("/home/me/.config/vqx/profiles.toml")),
+            PathBuf::from("/home/me/.config/vqx/profiles.toml.enc")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.toml");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(is_shared(&path));
+
+        write_private(&path, "new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!is_shared(&path));
+    }
+
+    #[cfg(feature = "age-encryption")]
+    #[test]
+    fn test_encrypt_round_trip() {
+        let encrypted = encrypt("token = \"t\"", "pass").unwrap();
+        assert_eq!(decrypt(&encrypted, "pass").unwrap(), "token = \"t\"");
+        assert!(decrypt(&encrypted, "wrong").is_err());
+    }
+}
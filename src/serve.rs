@@ -0,0 +1,668 @@
+//! HTTP trigger server (vqx extension)
+//!
+//! `vqx serve` lets chat bots and CI systems run the operations defined in
+//! `[serve.operations]` and fetch their reports over HTTP, without shell
+//! access. An operation is a fixed vqx command line; callers can only pick
+//! one by name. Every request except `GET /health` needs
+//! `Authorization: Bearer <token>`, with the token taken from the
+//! environment variable named by `[serve] token_env`.
+//!
+//! - `GET /health`
+//! - `GET /operations`: the defined operations
+//! - `POST /operations/<name>`: start a run (`?wait=true` answers when it
+//!   has finished)
+//! - `GET /runs`, `GET /runs/<id>`: runs with their status, exit code and
+//!   report
+//! - `GET /metrics`: Prometheus metrics of the runs (see [`crate::metrics`])
+//!
+//! A run is a child vqx process with `--quiet --output json --no-input`; the report
+//! it prints is returned as `report`. An operation runs once at a time, and
+//! the last [`MAX_RUNS`] runs are kept in memory; runs in progress are never
+//! dropped. Responses are JSON and every connection carries one request.
+
+use crate::config::ServeOperation;
+use crate::error::{Result, VqxError};
+use crate::metrics::{self, Metrics};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn};
+
+/// Runs kept for `GET /runs`
+pub const MAX_RUNS: usize = 100;
+
+/// Largest request head (request line and headers) accepted
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Largest request body read (and ignored)
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Time a client has to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Stderr kept per run
+const STDERR_TAIL_BYTES: usize = 4096;
+
+/// State of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// One run of an operation
+#[derive(Debug, Clone, Serialize)]
+pub struct Run {
+    pub id: String,
+    pub operation: String,
+    pub status: RunStatus,
+    pub started_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Report the command printed with `--output json`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<Value>,
+    /// Output that is not a JSON report
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// End of the command's stderr
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+}
+
+/// Operations, credentials and runs of a server
+pub struct Server {
+    token: String,
+    operations: BTreeMap<String, ServeOperation>,
+    /// vqx executable and global arguments for runs
+    exe: PathBuf,
+    global_args: Vec<String>,
+    runs: Mutex<VecDeque<Run>>,
+    next_run: AtomicU64,
+    metrics: Metrics,
+}
+
+#[derive(Debug)]
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    authorization: Option<String>,
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn new(status: u16, body: Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: serde_json::to_string_pretty(&body).unwrap_or_default() + "\n",
+        }
+    }
+
+    /// Metrics in the Prometheus text format
+    fn metrics(metrics: &Metrics) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/plain; version=0.0.4",
+            body: metrics.render(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::new(status, json!({ "error": message.into() }))
+    }
+}
+
+impl Server {
+    /// `global_args` go before each operation's command (e.g. `--config`)
+    pub fn new(
+        token: String,
+        operations: BTreeMap<String, ServeOperation>,
+        exe: PathBuf,
+        global_args: Vec<String>,
+    ) -> Self {
+        Self {
+            token,
+            metrics: Metrics::new(operations.keys().map(String::as_str)),
+            operations,
+            exe,
+            global_args,
+            runs: Mutex::new(VecDeque::new()),
+            next_run: AtomicU64::new(1),
+        }
+    }
+
+    async fn handle(self: Arc<Self>, mut stream: TcpStream) -> Result<()> {
+        let response = match read_request(&mut stream, REQUEST_TIMEOUT).await {
+            Ok(Some(request)) => self.respond(&request).await,
+            Ok(None) => return Ok(()),
+            Err(e) => Response::error(400, e.to_string()),
+        };
+        write_response(&mut stream, &response).await
+    }
+
+    async fn respond(self: &Arc<Self>, request: &Request) -> Response {
+        let segments: Vec<&str> = request
+            .path
+            .trim_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        if request.method == "GET" && segments == ["health"] {
+            return Response::new(200, json!({ "status": "ok" }));
+        }
+        if !self.authorized(request.authorization.as_deref()) {
+            return Response::error(401, "missing or invalid bearer token");
+        }
+
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["operations"]) => {
+                let operations: Vec<Value> = self
+                    .operations
+                    .iter()
+                    .map(|(name, op)| {
+                        json!({
+                            "name": name,
+                            "description": op.description,
+                            "command": op.command,
+                        })
+                    })
+                    .collect();
+                Response::new(200, json!({ "operations": operations }))
+            }
+            ("POST", ["operations", name]) => {
+                let wait = request
+                    .query
+                    .split('&')
+                    .any(|p| p == "wait" || p == "wait=true" || p == "wait=1");
+                self.start(name, wait).await
+            }
+            ("GET", ["runs"]) => {
+                let runs: Vec<Run> = self.runs.lock().unwrap().iter().cloned().collect();
+                Response::new(200, json!({ "runs": runs }))
+            }
+            ("GET", ["runs", id]) => match self.run(id) {
+                Some(run) => Response::new(200, json!(run)),
+                None => Response::error(404, format!("no run {}", id)),
+            },
+            ("GET", ["metrics"]) => Response::metrics(&self.metrics),
+            (_, ["operations"] | ["operations", _] | ["runs"] | ["runs", _] | ["metrics"]) => {
+                Response::error(405, format!("{} is not supported here", request.method))
+            }
+            _ => Response::error(404, format!("no such endpoint {}", request.path)),
+        }
+    }
+
+    /// Bearer token check that does not stop at the first differing byte
+    fn authorized(&self, authorization: Option<&str>) -> bool {
+        let Some(token) = authorization.and_then(|a| a.strip_prefix("Bearer ")) else {
+            return false;
+        };
+        let (a, b) = (token.trim().as_bytes(), self.token.as_bytes());
+        a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+
+    fn run(&self, id: &str) -> Option<Run> {
+        self.runs
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.id == id)
+            .cloned()
+    }
+
+    async fn start(self: &Arc<Self>, name: &str, wait: bool) -> Response {
+        let Some(operation) = self.operations.get(name).cloned() else {
+            return Response::error(404, format!("no operation '{}'", name));
+        };
+
+        let run = {
+            let mut runs = self.runs.lock().unwrap();
+            if let Some(running) = runs
+                .iter()
+                .find(|r| r.operation == name && r.status == RunStatus::Running)
+            {
+                return Response::new(
+                    409,
+                    json!({
+                        "error": format!("operation '{}' is already running", name),
+                        "run": running,
+                    }),
+                );
+            }
+            let run = Run {
+                id: format!(
+                    "{}-{}",
+                    Utc::now().format("%Y%m%d%H%M%S"),
+                    self.next_run.fetch_add(1, Ordering::SeqCst)
+                ),
+                operation: name.to_string(),
+                status: RunStatus::Running,
+                started_at: Utc::now(),
+                finished_at: None,
+                exit_code: None,
+                report: None,
+                output: None,
+                stderr: None,
+            };
+            runs.push_back(run.clone());
+            evict_finished(&mut runs);
+            run
+        };
+        info!(run = %run.id, operation = %name, "Starting operation");
+
+        let server = Arc::clone(self);
+        let id = run.id.clone();
+        let task = tokio::spawn(async move { server.execute(&id, &operation).await });
+        if !wait {
+            return Response::new(202, json!(run));
+        }
+        match task.await {
+            Ok(run) => Response::new(200, json!(run)),
+            Err(e) => Response::error(500, format!("run failed: {}", e)),
+        }
+    }
+
+    /// Run an operation and record the outcome
+    async fn execute(&self, id: &str, operation: &ServeOperation) -> Run {
+        let mut command = tokio::process::Command::new(&self.exe);
+        command
+            .args(&self.global_args)
+            .args(["--quiet", "--output", "json", "--no-input"])
+            .args(&operation.command)
+            .stdin(Stdio::null());
+        if let Some(ref dir) = operation.dir {
+            command.current_dir(dir);
+        }
+        let output = command.output().await;
+
+        let mut runs = self.runs.lock().unwrap();
+        let run = runs
+            .iter_mut()
+            .find(|r| r.id == id)
+            .expect("running runs are never evicted");
+        run.finished_at = Some(Utc::now());
+        match output {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                run.status = if output.status.success() {
+                    RunStatus::Succeeded
+                } else {
+                    RunStatus::Failed
+                };
+                run.exit_code = output.status.code();
+                match serde_json::from_str::<Value>(stdout.trim()) {
+                    Ok(report) => {
+                        if let Some(drift) = metrics::drift(&report) {
+                            self.metrics.record_drift(&run.operation, drift);
+                        }
+                        run.report = Some(report);
+                    }
+                    Err(_) if stdout.trim().is_empty() => {}
+                    Err(_) => run.output = Some(stdout.to_string()),
+                }
+                run.stderr = Some(tail(stderr.trim(), STDERR_TAIL_BYTES)).filter(|s| !s.is_empty());
+            }
+            Err(e) => {
+                run.status = RunStatus::Failed;
+                run.stderr = Some(format!("could not start {}: {}", self.exe.display(), e));
+            }
+        }
+        let finished_at = run.finished_at.unwrap_or_else(Utc::now);
+        self.metrics.record(
+            &run.operation,
+            run.status == RunStatus::Succeeded,
+            (finished_at - run.started_at).to_std().unwrap_or_default(),
+            finished_at,
+        );
+        if run.status == RunStatus::Succeeded {
+            info!(run = %run.id, operation = %run.operation, "Operation finished");
+        } else {
+            warn!(run = %run.id, operation = %run.operation, exit_code = ?run.exit_code, "Operation failed");
+        }
+        run.clone()
+    }
+}
+
+/// Drop the oldest finished runs beyond [`MAX_RUNS`]
+///
+/// Running runs are kept regardless: they guard against starting their
+/// operation twice and record their outcome when they finish.
+fn evict_finished(runs: &mut VecDeque<Run>) {
+    let mut excess = runs.len().saturating_sub(MAX_RUNS);
+    runs.retain(|run| {
+        if excess > 0 && run.status != RunStatus::Running {
+            excess -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+/// Accept connections until Ctrl-C
+pub async fn serve(listener: TcpListener, server: Arc<Server>) -> Result<()> {
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                let server = Arc::clone(&server);
+                tokio::spawn(async move {
+                    if let Err(e) = server.handle(stream).await {
+                        warn!(peer = %peer, error = %e, "vqx serve connection failed");
+                    }
+                });
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    info!("vqx serve stopping");
+    Ok(())
+}
+
+/// Answer `GET /metrics` (and `GET /health`) until the task is dropped
+///
+/// Used by `schedule run --metrics`; the metrics carry no secrets, so no
+/// token is needed.
+pub async fn serve_metrics(listener: TcpListener, metrics: Arc<Metrics>) -> Result<()> {
+    loop {
+        let (mut stream, peer) = listener.accept().await?;
+        let metrics = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            let response = match read_request(&mut stream, REQUEST_TIMEOUT).await {
+                Ok(Some(request)) => match (request.method.as_str(), request.path.as_str()) {
+                    ("GET", "/metrics") => Response::metrics(&metrics),
+                    ("GET", "/health") => Response::new(200, json!({ "status": "ok" })),
+                    _ => Response::error(404, format!("no such endpoint {}", request.path)),
+                },
+                Ok(None) => return,
+                Err(e) => Response::error(400, e.to_string()),
+            };
+            if let Err(e) = write_response(&mut stream, &response).await {
+                warn!(peer = %peer, error = %e, "Metrics connection failed");
+            }
+        });
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, response: &Response) -> Result<()> {
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason(response.status),
+        response.content_type,
+        response.body.len()
+    );
+    if response.status == 401 {
+        head.push_str("WWW-Authenticate: Bearer\r\n");
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(response.body.as_bytes()).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// Read the request line and headers, and skip the body
+///
+/// The head is read through a [`MAX_HEAD_BYTES`] limit and the whole request
+/// must arrive within `timeout`, so a client cannot hold a connection open or
+/// make the server buffer an endless line.
+async fn read_request<S: AsyncRead + Unpin>(
+    stream: &mut S,
+    timeout: Duration,
+) -> Result<Option<Request>> {
+    tokio::time::timeout(timeout, read_request_inner(stream))
+        .await
+        .map_err(|_| VqxError::Other("timed out reading the request".to_string()))?
+}
+
+async fn read_request_inner<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Option<Request>> {
+    let mut reader = BufReader::new(stream).take(MAX_HEAD_BYTES as u64);
+    let mut head = Vec::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            if head.is_empty() {
+                return Ok(None);
+            }
+            break;
+        }
+        if !line.ends_with('\n') && reader.limit() == 0 {
+            return Err(VqxError::Other("request head too large".to_string()));
+        }
+        if line.trim_end().is_empty() {
+            break;
+        }
+        head.push(line);
+    }
+
+    let request_line = head.first().map(|l| l.trim()).unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(VqxError::Other(format!(
+            "invalid request line '{}'",
+            request_line
+        )));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    for line in &head[1..] {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "authorization" => authorization = Some(value.trim().to_string()),
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    if content_length > 0 {
+        let mut body = vec![0; content_length.min(MAX_BODY_BYTES)];
+        reader.set_limit(body.len() as u64);
+        reader.read_exact(&mut body).await?;
+    }
+
+    Ok(Some(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query.to_string(),
+        authorization,
+    }))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Last `max` bytes of `s`, on a character boundary
+fn tail(s: &str, max: usize) -> String {
+    let mut start = s.len().saturating_sub(max);
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    s[start..].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(id: usize, status: RunStatus) -> Run {
+        Run {
+            id: id.to_string(),
+            operation: "backup".to_string(),
+            status,
+            started_at: Utc::now(),
+            finished_at: None,
+            exit_code: None,
+            report: None,
+            output: None,
+            stderr: None,
+        }
+    }
+
+    #[test]
+    fn test_evict_finished() {
+        let mut runs: VecDeque<Run> = (0..MAX_RUNS)
+            .map(|i| run(i, RunStatus::Succeeded))
+            .collect();
+        runs[0].status = RunStatus::Running;
+        runs.push_back(run(MAX_RUNS, RunStatus::Running));
+        evict_finished(&mut runs);
+
+        // The oldest finished run goes; the older running one stays
+        assert_eq!(runs.len(), MAX_RUNS);
+        assert_eq!(runs[0].id, "0");
+        assert_eq!(runs[1].id, "2");
+
+        // More running runs than MAX_RUNS are all kept
+        let mut runs: VecDeque<Run> = (0..=MAX_RUNS).map(|i| run(i, RunStatus::Running)).collect();
+        evict_finished(&mut runs);
+        assert_eq!(runs.len(), MAX_RUNS + 1);
+    }
+
+    async fn request(addr: std::net::SocketAddr, raw: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(raw.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_read_request_limits() {
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+        client
+            .write_all(b"GET /health HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        let request = read_request(&mut server, REQUEST_TIMEOUT)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (request.method.as_str(), request.path.as_str()),
+            ("GET", "/health")
+        );
+
+        // A line without end is cut off at the head limit
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+        client.write_all(&[b'a'; MAX_HEAD_BYTES + 1]).await.unwrap();
+        let err = read_request(&mut server, REQUEST_TIMEOUT)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("too large"));
+
+        // A client that stops sending is dropped after the timeout
+        let (mut client, mut server) = tokio::io::duplex(64 * 1024);
+        client.write_all(b"GET /health HTTP/1.1\r\n").await.unwrap();
+        let err = read_request(&mut server, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let operations = BTreeMap::from([(
+            "pull".to_string(),
+            ServeOperation {
+                command: vec!["sync".to_string(), "pull".to_string()],
+                dir: None,
+                description: None,
+            },
+        )]);
+        let server = Arc::new(Server::new(
+            "s3cret".to_string(),
+            operations,
+            PathBuf::from("vqx-test-missing-exe"),
+            vec![],
+        ));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(listener, server));
+
+        let (status, body) = request(addr, "GET /health HTTP/1.1\r\n\r\n").await;
+        assert_eq!((status, body["status"].as_str()), (200, Some("ok")));
+
+        let (status, _) = request(addr, "GET /operations HTTP/1.1\r\n\r\n").await;
+        assert_eq!(status, 401);
+        let (status, _) = request(
+            addr,
+            "GET /operations HTTP/1.1\r\nAuthorization: Bearer wrong\r\n\r\n",
+        )
+        .await;
+        assert_eq!(status, 401);
+
+        let auth = "Authorization: Bearer s3cret\r\n";
+        let (status, body) =
+            request(addr, &format!("GET /operations HTTP/1.1\r\n{}\r\n", auth)).await;
+        assert_eq!(status, 200);
+        assert_eq!(body["operations"][0]["name"], "pull");
+
+        let (status, _) = request(
+            addr,
+            &format!("POST /operations/push HTTP/1.1\r\n{}\r\n", auth),
+        )
+        .await;
+        assert_eq!(status, 404);
+
+        // The executable does not exist, so the run fails right away
+        let (status, body) = request(
+            addr,
+            &format!(
+                "POST /operations/pull?wait=true HTTP/1.1\r\n{}Content-Length: 2\r\n\r\n{{}}",
+                auth
+            ),
+        )
+        .await;
+        assert_eq!(status, 200);
+        assert_eq!(body["status"], "failed");
+
+        let id = body["id"].as_str().unwrap();
+        let (status, body) =
+            request(addr, &format!("GET /runs/{} HTTP/1.1\r\n{}\r\n", id, auth)).await;
+        assert_eq!((status, body["operation"].as_str()), (200, Some("pull")));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let raw = format!("GET /metrics HTTP/1.1\r\n{}\r\n", auth);
+        stream.write_all(raw.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.contains("vqx_runs_total{operation=\"pull\",result=\"failure\"} 1\n"));
+    }
+}
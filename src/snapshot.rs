@@ -0,0 +1,289 @@
+//! Server snapshots before imports (vqx extension)
+//!
+//! Before `import`, `sync push`, `promote` and `rollback` change a protected
+//! profile (`protection = "confirm"`), the metadata of the target namespace
+//! is exported into a snapshot. `vqx rollback --to <id>` imports it again.
+//!
+//! Each snapshot is a directory with the export (`metadata/`) and
+//! `snapshot.json` naming the profile, the operation, the user and the time.
+//! Only the newest `[snapshots] keep` snapshots of a profile are kept.
+//! Snapshots live in `~/.local/share/vqx/snapshots/` unless
+//! `[snapshots] dir` points elsewhere.
+
+use crate::approval::current_user;
+use crate::config::Config;
+use crate::error::{Result, VqxError};
+use crate::profile::Profile;
+use crate::progress::TransferProgress;
+use crate::theme::style;
+use crate::underlying::{CliOptions, UnderlyingCli};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const METADATA_DIR: &str = "metadata";
+
+/// What a snapshot holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    /// vqx profile the snapshot was taken of
+    pub profile: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    pub url: String,
+    /// Command that was about to change the profile
+    pub operation: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    /// Exported resource files
+    pub files: usize,
+}
+
+/// Snapshot directory
+pub struct SnapshotStore {
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl SnapshotStore {
+    /// Store in the configured (or default) directory
+    pub fn new(config: &Config) -> Self {
+        let dir = match config.snapshots.dir {
+            Some(ref dir) => PathBuf::from(dir),
+            None => dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("vqx")
+                .join("snapshots"),
+        };
+        Self::in_dir(dir, config.snapshots.keep)
+    }
+
+    pub fn in_dir(dir: impl Into<PathBuf>, keep: usize) -> Self {
+        Self {
+            dir: dir.into(),
+            keep,
+        }
+    }
+
+    /// Directory of a snapshot
+    pub fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    /// Exported metadata of a snapshot
+    pub fn metadata_dir(&self, id: &str) -> PathBuf {
+        self.path(id).join(METADATA_DIR)
+    }
+
+    /// A new, unused snapshot ID for a profile
+    fn new_id(&self, profile: &str, now: DateTime<Utc>) -> String {
+        let base = format!("{}_{}", now.format("%Y%m%d_%H%M%S"), profile);
+        let mut id = base.clone();
+        let mut n = 1;
+        while self.path(&id).exists() {
+            n += 1;
+            id = format!("{}_{}", base, n);
+        }
+        id
+    }
+
+    /// Record a snapshot whose metadata has been exported
+    fn save(&self, snapshot: &Snapshot) -> Result<()> {
+        let path = self.path(&snapshot.id).join(SNAPSHOT_FILE);
+        fs::write(&path, serde_json::to_string_pretty(snapshot)? + "\n").map_err(|_| {
+            VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            }
+        })
+    }
+
+    /// All snapshots, oldest first
+    pub fn list(&self) -> Vec<Snapshot> {
+        let Ok(dirs) = fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+        let mut snapshots: Vec<Snapshot> = dirs
+            .flatten()
+            .filter_map(|d| fs::read_to_string(d.path().join(SNAPSHOT_FILE)).ok())
+            .filter_map(|s| serde_json::from_str(&s).ok())
+            .collect();
+        snapshots.sort_by_key(|s| s.created_at);
+        snapshots
+    }
+
+    /// The snapshot with this ID (or unique ID prefix)
+    pub fn find(&self, id: &str) -> Result<Snapshot> {
+        let matches: Vec<Snapshot> = self
+            .list()
+            .into_iter()
+            .filter(|s| s.id.starts_with(id))
+            .collect();
+        match <[Snapshot; 1]>::try_from(matches) {
+            Ok([snapshot]) => Ok(snapshot),
+            Err(matches) if matches.is_empty() => {
+                Err(VqxError::Other(format!("No snapshot {}", id)))
+            }
+            Err(_) => Err(VqxError::Other(format!(
+                "Snapshot {} is ambiguous; give more characters",
+                id
+            ))),
+        }
+    }
+
+    /// Remove the oldest snapshots of a profile beyond `keep`
+    fn prune(&self, profile: &str) -> Result<()> {
+        let snapshots: Vec<Snapshot> = self
+            .list()
+            .into_iter()
+            .filter(|s| s.profile == profile)
+            .collect();
+        let excess = snapshots.len().saturating_sub(self.keep.max(1));
+        for snapshot in &snapshots[..excess] {
+            let path = self.path(&snapshot.id);
+            fs::remove_dir_all(&path).map_err(|_| VqxError::FileWriteFailed {
+                path: path.display().to_string(),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Snapshot a protected profile before `operation` changes it
+///
+/// Returns `None` for open profiles or with `[snapshots] enabled = false`.
+/// When the export fails the operation must not go on, so that is an error.
+pub async fn take(
+    config: &Config,
+    cli: &UnderlyingCli,
+    profile_name: &str,
+    profile: &Profile,
+    operation: &str,
+    interactive: bool,
+) -> Result<Option<Snapshot>> {
+    if !config.snapshots.enabled || profile.protection.is_open() {
+        return Ok(None);
+    }
+
+    let store = SnapshotStore::new(config);
+    let now = Utc::now();
+    let id = store.new_id(profile_name, now);
+    let dir = store.metadata_dir(&id);
+    fs::create_dir_all(&dir).map_err(|_| VqxError::FileWriteFailed {
+        path: dir.display().to_string(),
+    })?;
+
+    let mut progress = TransferProgress::new(
+        format!("Taking a snapshot of '{}'...", profile_name),
+        interactive,
+    );
+    let export_args = UnderlyingCli::export_args(
+        Some("metadata"),
+        Some(&dir),
+        Some(config.default_chunk_size),
+        None,
+        None,
+        None,
+        false,
+    );
+    let result = progress
+        .run(
+            cli,
+            &CliOptions::from_profile(profile),
+            "export",
+            export_args,
+            config.stall_policy(),
+        )
+        .await;
+    progress.finish_and_clear();
+
+    let failure = match result {
+        Ok(result) if result.success() => None,
+        Ok(result) => Some(result.stderr.trim().to_string()),
+        Err(e) => Some(e.to_string()),
+    };
+    if let Some(message) = failure {
+        let _ = fs::remove_dir_all(store.path(&id));
+        return Err(VqxError::Other(format!(
+            "Could not take a snapshot of '{}' before {}: {}. \
+             Set [snapshots] enabled = false to go on without one",
+            profile_name, operation, message
+        )));
+    }
+
+    let snapshot = Snapshot {
+        id,
+        profile: profile_name.to_string(),
+        namespace: profile.namespace.clone(),
+        url: profile.url.clone(),
+        operation: operation.to_string(),
+        created_by: current_user(),
+        created_at: now,
+        files: count_files(&dir),
+    };
+    store.save(&snapshot)?;
+    store.prune(profile_name)?;
+
+    if interactive {
+        println!(
+            "{} Snapshot {} taken ({} files); undo with 'vqx rollback --to {}'",
+            style("✓").green(),
+            style(&snapshot.id).bold(),
+            snapshot.files,
+            snapshot.id
+        );
+    }
+    Ok(Some(snapshot))
+}
+
+fn count_files(dir: &Path) -> usize {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put(store: &SnapshotStore, profile: &str, minutes_ago: i64) -> Snapshot {
+        let created_at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+        let id = store.new_id(profile, created_at);
+        fs::create_dir_all(store.metadata_dir(&id)).unwrap();
+        let snapshot = Snapshot {
+            id,
+            profile: profile.to_string(),
+            namespace: None,
+            url: "https://dev.vantiq.com".to_string(),
+            operation: "import".to_string(),
+            created_by: "alice".to_string(),
+            created_at,
+            files: 0,
+        };
+        store.save(&snapshot).unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn test_find_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::in_dir(dir.path(), 2);
+
+        let oldest = put(&store, "prod", 30);
+        let middle = put(&store, "prod", 20);
+        let newest = put(&store, "prod", 10);
+        let other = put(&store, "staging", 40);
+        assert_eq!(store.list().len(), 4);
+        assert_eq!(store.find(&middle.id).unwrap().profile, "prod");
+        assert!(store.find("nothing").is_err());
+
+        store.prune("prod").unwrap();
+        let ids: Vec<String> = store.list().into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![other.id, middle.id, newest.id]);
+        assert!(!store.path(&oldest.id).exists());
+    }
+}
@@ -0,0 +1,451 @@
+//! Orchestration of multiple test suites
+//!
+//! A suites file lists named test suites, optionally per profile:
+//!
+//! ```toml
+//! concurrency = 4          # suites running at once (default: 2)
+//! timeout_seconds = 600    # per suite attempt (default: global timeout)
+//! retries = 1              # re-runs of a suite with failed tests (default: 0)
+//! profiles = ["dev"]       # default profiles (default: the -s profile)
+//!
+//! [[suite]]
+//! name = "OrderSuite"
+//! profiles = ["dev", "staging"]
+//!
+//! [[suite]]
+//! name = "BillingSuite"
+//! timeout_seconds = 1200
+//! retries = 2
+//! ```
+//!
+//! Every suite/profile pair is one job. Jobs run with bounded concurrency;
+//! a job whose tests fail is re-run up to `retries` times, and tests that
+//! failed before passing are reported as flaky. Execution errors (CLI
+//! failures, timeouts) are not retried here, since the CLI layer already
+//! retries transient errors.
+
+use crate::error::{Result, VqxError};
+use crate::testreport::{self, TestCase, TestStatus, TestSummary};
+use crate::underlying::{CliOptions, RetryPolicy, UnderlyingCli};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Default number of suites running at once
+pub const DEFAULT_CONCURRENCY: usize = 2;
+
+/// Contents of a suites file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuitesFile {
+    pub concurrency: Option<usize>,
+    pub timeout_seconds: Option<u64>,
+    pub retries: Option<u32>,
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    #[serde(default)]
+    pub suite: Vec<SuiteSpec>,
+}
+
+/// One `[[suite]]` entry
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuiteSpec {
+    pub name: String,
+    #[serde(default)]
+    pub profiles: Vec<String>,
+    pub timeout_seconds: Option<u64>,
+    pub retries: Option<u32>,
+    pub start_from: Option<String>,
+}
+
+impl SuitesFile {
+    /// Load a suites file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+            path: path.display().to_string(),
+        })?;
+        let file: SuitesFile = toml::from_str(&content).map_err(|e| {
+            VqxError::Other(format!("Invalid suites file {}: {}", path.display(), e))
+        })?;
+        if file.suite.is_empty() {
+            return Err(VqxError::Other(format!(
+                "No [[suite]] entries in {}",
+                path.display()
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Suites file for a plain list of suite names
+    pub fn from_names(names: &[String]) -> Self {
+        SuitesFile {
+            suite: names
+                .iter()
+                .map(|name| SuiteSpec {
+                    name: name.clone(),
+                    profiles: Vec::new(),
+                    timeout_seconds: None,
+                    retries: None,
+                    start_from: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Expand suites into one job per suite and profile
+    ///
+    /// `profile` is used for suites without profiles; `None` runs with the
+    /// CLI's default connection.
+    pub fn jobs(&self, profile: Option<&str>, default_timeout: Duration) -> Vec<SuiteJob> {
+        let fallback: Vec<Option<String>> = if self.profiles.is_empty() {
+            vec![profile.map(str::to_string)]
+        } else {
+            self.profiles.iter().cloned().map(Some).collect()
+        };
+
+        self.suite
+            .iter()
+            .flat_map(|spec| {
+                let profiles = if spec.profiles.is_empty() {
+                    fallback.clone()
+                } else {
+                    spec.profiles.iter().cloned().map(Some).collect()
+                };
+                profiles.into_iter().map(move |profile| SuiteJob {
+                    suite: spec.name.clone(),
+                    profile,
+                    timeout: spec
+                        .timeout_seconds
+                        .or(self.timeout_seconds)
+                        .map_or(default_timeout, Duration::from_secs),
+                    retries: spec.retries.or(self.retries).unwrap_or(0),
+                    start_from: spec.start_from.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// One suite to run against one profile
+#[derive(Debug, Clone)]
+pub struct SuiteJob {
+    pub suite: String,
+    pub profile: Option<String>,
+    pub timeout: Duration,
+    pub retries: u32,
+    pub start_from: Option<String>,
+}
+
+/// Outcome of a suite run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SuiteStatus {
+    Passed,
+    /// Passed after one or more retries
+    Flaky,
+    Failed,
+    /// The suite could not be run
+    Error,
+}
+
+impl std::fmt::Display for SuiteStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SuiteStatus::Passed => write!(f, "passed"),
+            SuiteStatus::Flaky => write!(f, "flaky"),
+            SuiteStatus::Failed => write!(f, "failed"),
+            SuiteStatus::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Result of one job, from its last attempt
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SuiteRun {
+    pub suite: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub status: SuiteStatus,
+    pub attempts: u32,
+    pub duration_ms: u64,
+    pub summary: TestSummary,
+    pub tests: Vec<TestCase>,
+    /// Tests that failed in an earlier attempt and passed in the last one
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub flaky_tests: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SuiteRun {
+    /// Whether the suite passed, possibly after retries
+    pub fn passed(&self) -> bool {
+        matches!(self.status, SuiteStatus::Passed | SuiteStatus::Flaky)
+    }
+
+    /// Suite name qualified with its profile, e.g. `OrderSuite@dev`
+    pub fn label(&self) -> String {
+        match self.profile {
+            Some(ref profile) => format!("{}@{}", self.suite, profile),
+            None => self.suite.clone(),
+        }
+    }
+}
+
+/// How to invoke the CLI for each job
+#[derive(Debug, Clone)]
+pub struct Runner {
+    pub cli_path: String,
+    pub retry: RetryPolicy,
+}
+
+impl Runner {
+    /// Run one job, retrying attempts with failed tests
+    pub async fn run_job(&self, job: &SuiteJob, options: &CliOptions) -> SuiteRun {
+        let cli = UnderlyingCli::new(self.cli_path.clone())
+            .with_timeout(job.timeout)
+            .with_retry_policy(self.retry);
+        let started = Instant::now();
+        let mut failed_before = BTreeSet::new();
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let (tests, error) = match cli
+                .run_testsuite(options, &job.suite, job.start_from.as_deref())
+                .await
+            {
+                Ok(result) => {
+                    let tests = testreport::parse_output(&result.stdout);
+                    let error = (!result.success()).then(|| {
+                        if result.stderr.trim().is_empty() {
+                            format!("CLI exited with code {}", result.code())
+                        } else {
+                            result.stderr.trim().to_string()
+                        }
+                    });
+                    (tests, error)
+                }
+                Err(e) => (Vec::new(), Some(e.to_string())),
+            };
+            let summary = TestSummary::of(&tests);
+
+            let status = if summary.has_failures() {
+                SuiteStatus::Failed
+            } else if error.is_some() {
+                SuiteStatus::Error
+            } else if attempts > 1 {
+                SuiteStatus::Flaky
+            } else {
+                SuiteStatus::Passed
+            };
+
+            if status == SuiteStatus::Failed && attempts <= job.retries {
+                failed_before.extend(
+                    tests
+                        .iter()
+                        .filter(|t| matches!(t.status, TestStatus::Failed | TestStatus::Error))
+                        .map(|t| t.name.clone()),
+                );
+                continue;
+            }
+
+            let flaky_tests = if status == SuiteStatus::Flaky {
+                tests
+                    .iter()
+                    .filter(|t| t.status == TestStatus::Passed && failed_before.contains(&t.name))
+                    .map(|t| t.name.clone())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            return SuiteRun {
+                suite: job.suite.clone(),
+                profile: job.profile.clone(),
+                status,
+                attempts,
+                duration_ms: started.elapsed().as_millis() as u64,
+                summary,
+                tests,
+                flaky_tests,
+                // Failed tests explain themselves; keep the error for suites that did not run
+                error: error.filter(|_| status == SuiteStatus::Error),
+            };
+        }
+    }
+
+    /// Run jobs with at most `concurrency` at once
+    ///
+    /// `options` holds the connection options of each job, in job order.
+    /// `on_done` is called as each job finishes. Results are sorted by
+    /// suite and profile.
+    pub async fn run_all(
+        &self,
+        jobs: Vec<(SuiteJob, CliOptions)>,
+        concurrency: usize,
+        mut on_done: impl FnMut(&SuiteRun),
+    ) -> Vec<SuiteRun> {
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mut set = JoinSet::new();
+
+        for (job, options) in jobs {
+            let runner = self.clone();
+            let semaphore = Arc::clone(&semaphore);
+            set.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                runner.run_job(&job, &options).await
+            });
+        }
+
+        let mut runs = Vec::new();
+        while let Some(joined) = set.join_next().await {
+            let run = joined.unwrap_or_else(|e| SuiteRun {
+                suite: String::new(),
+                profile: None,
+                status: SuiteStatus::Error,
+                attempts: 0,
+                duration_ms: 0,
+                summary: TestSummary::default(),
+                tests: Vec::new(),
+                flaky_tests: Vec::new(),
+                error: Some(format!("Suite task failed: {}", e)),
+            });
+            on_done(&run);
+            runs.push(run);
+        }
+
+        runs.sort_by(|a, b| (&a.suite, &a.profile).cmp(&(&b.suite, &b.profile)));
+        runs
+    }
+}
+
+/// Totals across suite runs
+#[derive(Debug, Default, Clone, Copy, Serialize, JsonSchema)]
+pub struct SuitesSummary {
+    pub passed: usize,
+    pub flaky: usize,
+    pub failed: usize,
+    pub errors: usize,
+    /// Test counts summed over all suites
+    pub tests: TestSummary,
+}
+
+impl SuitesSummary {
+    pub fn of(runs: &[SuiteRun]) -> Self {
+        let mut summary = SuitesSummary::default();
+        for run in runs {
+            match run.status {
+                SuiteStatus::Passed => summary.passed += 1,
+                SuiteStatus::Flaky => summary.flaky += 1,
+                SuiteStatus::Failed => summary.failed += 1,
+                SuiteStatus::Error => summary.errors += 1,
+            }
+            summary.tests.passed += run.summary.passed;
+            summary.tests.failed += run.summary.failed;
+            summary.tests.errors += run.summary.errors;
+            summary.tests.skipped += run.summary.skipped;
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jobs() {
+        let file: SuitesFile = toml::from_str(
+            r#"
+            timeout_seconds = 600
+            retries = 1
+
+            [[suite]]
+            name = "OrderSuite"
+            profiles = ["dev", "staging"]
+
+            [[suite]]
+            name = "BillingSuite"
+            timeout_seconds = 30
+            retries = 3
+            "#,
+        )
+        .unwrap();
+
+        let jobs = file.jobs(Some("qa"), Duration::from_secs(120));
+        let summary: Vec<_> = jobs
+            .iter()
+            .map(|j| {
+                (
+                    j.suite.as_str(),
+                    j.profile.as_deref(),
+                    j.timeout.as_secs(),
+                    j.retries,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("OrderSuite", Some("dev"), 600, 1),
+                ("OrderSuite", Some("staging"), 600, 1),
+                ("BillingSuite", Some("qa"), 30, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_names_and_unknown_fields() {
+        let file = SuitesFile::from_names(&["A".to_string(), "B".to_string()]);
+        let jobs = file.jobs(None, Duration::from_secs(120));
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(jobs[0].profile, None);
+        assert_eq!(jobs[1].retries, 0);
+
+        assert!(toml::from_str::<SuitesFile>("[[suite]]\nname = \"A\"\nretry = 2\n").is_err());
+    }
+
+    #[test]
+    fn test_suites_summary() {
+        let run = |status, summary| SuiteRun {
+            suite: "S".to_string(),
+            profile: None,
+            status,
+            attempts: 1,
+            duration_ms: 0,
+            summary,
+            tests: Vec::new(),
+            flaky_tests: Vec::new(),
+            error: None,
+        };
+        let runs = vec![
+            run(
+                SuiteStatus::Flaky,
+                TestSummary {
+                    passed: 3,
+                    ..Default::default()
+                },
+            ),
+            run(
+                SuiteStatus::Failed,
+                TestSummary {
+                    passed: 1,
+                    failed: 2,
+                    ..Default::default()
+                },
+            ),
+        ];
+        let summary = SuitesSummary::of(&runs);
+        assert_eq!((summary.flaky, summary.failed), (1, 1));
+        assert_eq!(summary.tests.passed, 4);
+        assert!(runs[0].passed() && !runs[1].passed());
+    }
+}
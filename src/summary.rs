@@ -0,0 +1,232 @@
+//! Execution summary (`--summary`)
+//!
+//! With `--summary`, vqx times the steps of a command and prints (and logs)
+//! where the time went once it finishes, e.g. `export 42s, normalize 3s,
+//! diff 1s, import 88s` for a promote, together with the total duration and
+//! the command's resource counts. JSON reports carry the same data in a
+//! `summary` field, see [`crate::report::Versioned`].
+//!
+//! Steps are recorded where the work happens, so every command is covered:
+//! each underlying CLI call is a step named after its command
+//! ([`UnderlyingCli`](crate::underlying::UnderlyingCli)), and normalization
+//! and comparison of export directories are steps of their own. Steps with
+//! the same name are added up, in the order they first ran.
+
+use crate::theme::style;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// Steps of this run, once `--summary` is on
+static STATE: Mutex<Option<Steps>> = Mutex::new(None);
+
+/// Where the time of a command went
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct ExecutionReport {
+    pub total_seconds: f64,
+    pub steps: Vec<StepTiming>,
+    /// Resource counts of the command (files exported, resources imported, ...)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub counts: BTreeMap<String, usize>,
+}
+
+/// Time spent on one kind of step
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct StepTiming {
+    pub name: String,
+    pub seconds: f64,
+    /// How often the step ran, e.g. one export per resource type
+    pub runs: usize,
+}
+
+#[derive(Debug)]
+struct Steps {
+    started: Instant,
+    steps: Vec<(String, Duration, usize)>,
+    counts: BTreeMap<String, usize>,
+}
+
+impl Steps {
+    fn new(started: Instant) -> Self {
+        Self {
+            started,
+            steps: Vec::new(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    fn record(&mut self, name: &str, elapsed: Duration) {
+        match self.steps.iter_mut().find(|(n, _, _)| n == name) {
+            Some((_, total, runs)) => {
+                *total += elapsed;
+                *runs += 1;
+            }
+            None => self.steps.push((name.to_string(), elapsed, 1)),
+        }
+    }
+
+    fn report(&self, now: Instant) -> ExecutionReport {
+        ExecutionReport {
+            total_seconds: seconds(now.saturating_duration_since(self.started)),
+            steps: self
+                .steps
+                .iter()
+                .map(|(name, elapsed, runs)| StepTiming {
+                    name: name.clone(),
+                    seconds: seconds(*elapsed),
+                    runs: *runs,
+                })
+                .collect(),
+            counts: self.counts.clone(),
+        }
+    }
+}
+
+fn seconds(d: Duration) -> f64 {
+    (d.as_secs_f64() * 1000.0).round() / 1000.0
+}
+
+/// Start timing this run (`--summary`)
+pub fn configure(enabled: bool) {
+    if let Ok(mut state) = STATE.lock() {
+        *state = enabled.then(|| Steps::new(Instant::now()));
+    }
+}
+
+/// Add `elapsed` to the step `name`
+pub fn record(name: &str, elapsed: Duration) {
+    if let Ok(mut state) = STATE.lock() {
+        if let Some(ref mut steps) = *state {
+            steps.record(name, elapsed);
+        }
+    }
+}
+
+/// Time a step until the returned guard is dropped
+pub fn step(name: impl Into<String>) -> StepGuard {
+    StepGuard {
+        name: name.into(),
+        started: Instant::now(),
+    }
+}
+
+/// Records its step when dropped, see [`step`]
+pub struct StepGuard {
+    name: String,
+    started: Instant,
+}
+
+impl Drop for StepGuard {
+    fn drop(&mut self) {
+        record(&self.name, self.started.elapsed());
+    }
+}
+
+/// Set the resource counts reported with the summary
+pub fn set_counts(counts: &BTreeMap<String, usize>) {
+    if let Ok(mut state) = STATE.lock() {
+        if let Some(ref mut steps) = *state {
+            steps
+                .counts
+                .extend(counts.iter().map(|(k, v)| (k.clone(), *v)));
+        }
+    }
+}
+
+/// The summary so far, if `--summary` is on
+pub fn current() -> Option<ExecutionReport> {
+    let state = STATE.lock().ok()?;
+    state.as_ref().map(|steps| steps.report(Instant::now()))
+}
+
+/// Log the summary and, for text output, print it
+pub fn finish(text: bool) {
+    let Some(report) = current() else {
+        return;
+    };
+    let steps: Vec<String> = report
+        .steps
+        .iter()
+        .map(|s| format!("{} {:.1}s", s.name, s.seconds))
+        .collect();
+    info!(
+        total_seconds = report.total_seconds,
+        steps = %steps.join(", "),
+        counts = ?report.counts,
+        "Execution summary"
+    );
+    if text {
+        display(&report);
+    }
+}
+
+fn display(report: &ExecutionReport) {
+    println!();
+    println!("{}", style("Summary:").bold());
+    let width = report
+        .steps
+        .iter()
+        .map(|s| s.name.len())
+        .chain(["total".len()])
+        .max()
+        .unwrap_or(0);
+    for step in &report.steps {
+        let runs = if step.runs > 1 {
+            style(format!("  ({} runs)", step.runs)).dim().to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "  {:<width$}  {:>9.1}s{}",
+            step.name,
+            step.seconds,
+            runs,
+            width = width
+        );
+    }
+    println!(
+        "  {}  {:>9.1}s",
+        style(format!("{:<width$}", "total", width = width)).bold(),
+        report.total_seconds
+    );
+    for (name, count) in &report.counts {
+        println!("  {}: {}", name.replace('_', " "), count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_report() {
+        let started = Instant::now();
+        let mut steps = Steps::new(started);
+        steps.record("export", Duration::from_secs(40));
+        steps.record("normalize", Duration::from_millis(3200));
+        steps.record("export", Duration::from_secs(2));
+        steps.counts.insert("files_exported".to_string(), 12);
+
+        let report = steps.report(started + Duration::from_secs(50));
+        assert_eq!(report.total_seconds, 50.0);
+        assert_eq!(
+            report.steps,
+            vec![
+                StepTiming {
+                    name: "export".to_string(),
+                    seconds: 42.0,
+                    runs: 2,
+                },
+                StepTiming {
+                    name: "normalize".to_string(),
+                    seconds: 3.2,
+                    runs: 1,
+                },
+            ]
+        );
+        assert_eq!(report.counts["files_exported"], 12);
+    }
+}
@@ -0,0 +1,220 @@
+//! Opt-in usage statistics (vqx extension)
+//!
+//! With `[telemetry] enabled = true` (`vqx telemetry on`), every command
+//! appends one [`Event`] to a local buffer
+//! (`~/.local/share/vqx/telemetry/events.jsonl`): the subcommand path (e.g.
+//! `sync pull`), whether it succeeded, the error category and code, the
+//! duration, and the vqx version and OS. Argument values, profile names,
+//! URLs and credentials are never recorded, and nothing is sent anywhere;
+//! `vqx telemetry show` summarizes the buffer so it can be shared to guide
+//! which wrappers to work on next.
+
+use crate::cli::{Cli, Commands};
+use crate::config::TelemetryConfig;
+use crate::error::{ErrorReport, Result, VqxError};
+use chrono::{NaiveDate, Utc};
+use clap::{ArgMatches, CommandFactory};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::debug;
+
+/// Buffer size at which the older half of the events is dropped
+const MAX_BUFFER_BYTES: u64 = 1024 * 1024;
+
+/// One recorded command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    /// Day only, no time of day
+    pub date: NaiveDate,
+    /// Subcommand path, e.g. `sync pull`
+    pub command: String,
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    pub duration_ms: u64,
+    pub version: String,
+    pub os: String,
+}
+
+/// Runs and failures of one command
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct CommandUsage {
+    pub runs: usize,
+    pub failures: usize,
+}
+
+/// What the buffer holds
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub events: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<NaiveDate>,
+    pub commands: BTreeMap<String, CommandUsage>,
+    pub error_categories: BTreeMap<String, usize>,
+}
+
+/// Path of the event buffer
+pub fn buffer_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("vqx")
+        .join("telemetry")
+        .join("events.jsonl")
+}
+
+/// Record a finished command, if telemetry is on
+///
+/// Failing to record is only logged.
+pub fn record(
+    config: &TelemetryConfig,
+    command: &Commands,
+    result: &anyhow::Result<i32>,
+    elapsed: Duration,
+) {
+    // `telemetry off` must not leave an event behind
+    if !config.enabled || matches!(command, Commands::Telemetry(_)) {
+        return;
+    }
+    let error = result.as_ref().err().map(ErrorReport::new);
+    let event = Event {
+        date: Utc::now().date_naive(),
+        command: command_path(command),
+        success: matches!(result, Ok(code) if *code == crate::exit::SUCCESS),
+        error_category: error.as_ref().map(|e| e.category.clone()),
+        error_code: error.map(|e| e.code),
+        duration_ms: elapsed.as_millis() as u64,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+    };
+    if let Err(e) = append(&buffer_path(), &event) {
+        debug!(error = %e, "Could not record telemetry");
+    }
+}
+
+fn append(path: &PathBuf, event: &Event) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_BUFFER_BYTES) {
+        let content = fs::read_to_string(path)?;
+        let lines: Vec<&str> = content.lines().collect();
+        fs::write(path, lines[lines.len() / 2..].join("\n") + "\n")?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(event)?)?;
+    Ok(())
+}
+
+/// Events in the buffer; unreadable lines are skipped
+pub fn events() -> Vec<Event> {
+    fs::read_to_string(buffer_path())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Delete the buffer
+pub fn clear() -> Result<()> {
+    let path = buffer_path();
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(_) => Err(VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        }),
+    }
+}
+
+pub fn summarize(events: &[Event]) -> Summary {
+    let mut summary = Summary {
+        events: events.len(),
+        since: events.iter().map(|e| e.date).min(),
+        commands: BTreeMap::new(),
+        error_categories: BTreeMap::new(),
+    };
+    for event in events {
+        let usage = summary.commands.entry(event.command.clone()).or_default();
+        usage.runs += 1;
+        if !event.success {
+            usage.failures += 1;
+        }
+        if let Some(ref category) = event.error_category {
+            *summary
+                .error_categories
+                .entry(category.clone())
+                .or_default() += 1;
+        }
+    }
+    summary
+}
+
+/// Subcommand names as typed, without any argument values
+fn command_path(command: &Commands) -> String {
+    // The first word of a passthrough is whatever the user typed
+    if matches!(command, Commands::External(_)) {
+        return "external".to_string();
+    }
+    match Cli::command().try_get_matches_from(std::env::args_os()) {
+        Ok(matches) => subcommand_path(&matches),
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+fn subcommand_path(matches: &ArgMatches) -> String {
+    let mut path = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        path.push(name);
+        current = sub;
+    }
+    path.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subcommand_path() {
+        let matches = Cli::command().get_matches_from([
+            "vqx",
+            "--profile",
+            "prod",
+            "sync",
+            "pull",
+            "-d",
+            "./secret-dir",
+        ]);
+        assert_eq!(subcommand_path(&matches), "sync pull");
+    }
+
+    #[test]
+    fn test_summarize() {
+        let event = |command: &str, category: Option<&str>| Event {
+            date: NaiveDate::from_ymd_opt(2026, 3, 10).unwrap(),
+            command: command.to_string(),
+            success: category.is_none(),
+            error_category: category.map(String::from),
+            error_code: None,
+            duration_ms: 10,
+            version: "0.1.3".to_string(),
+            os: "linux".to_string(),
+        };
+        let summary = summarize(&[
+            event("export", None),
+            event("export", Some("network")),
+            event("diff", None),
+        ]);
+        assert_eq!(summary.events, 3);
+        assert_eq!(summary.commands["export"].runs, 2);
+        assert_eq!(summary.commands["export"].failures, 1);
+        assert_eq!(summary.error_categories["network"], 1);
+    }
+}
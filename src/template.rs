@@ -0,0 +1,300 @@
+//! Templates for environment-specific values (vqx extension)
+//!
+//! Exported definitions may contain `{{vars.<name>}}` placeholders instead
+//! of values that differ per environment, such as server URLs. Each profile
+//! has a `vars` table with its values:
+//!
+//! ```toml
+//! [profiles.prod.vars]
+//! db_url = "https://db.example.com"
+//! ```
+//!
+//! `import` and `sync push` render the placeholders with the target
+//! profile's values in a temporary copy, so the export directory itself
+//! stays environment-neutral. [`extract_directory`] goes the other way and
+//! replaces a profile's values with placeholders.
+//!
+//! Placeholders are replaced in JSON files (with JSON string escaping) and
+//! in extracted `.vail` code.
+
+use crate::error::{Result, VqxError};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+/// `{{vars.<name>}}`, with optional spaces inside the braces
+fn pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\{\{\s*vars\.([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap())
+}
+
+/// Placeholder text for a variable
+pub fn placeholder(name: &str) -> String {
+    format!("{{{{vars.{}}}}}", name)
+}
+
+/// Whether `name` can be used in a placeholder
+pub fn is_valid_name(name: &str) -> bool {
+    pattern().is_match(&placeholder(name))
+}
+
+/// Parse a `NAME=VALUE` assignment
+pub fn parse_assignment(assignment: &str) -> Result<(String, String)> {
+    match assignment.split_once('=') {
+        Some((name, value)) if is_valid_name(name) => Ok((name.to_string(), value.to_string())),
+        _ => Err(VqxError::Other(format!(
+            "Invalid variable '{}': expected NAME=VALUE with a name of letters, digits and '_'",
+            assignment
+        ))),
+    }
+}
+
+/// JSON and VAIL files below a directory, in a stable order
+fn template_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = WalkDir::new(dir)
+        .into_iter()
+        .flatten()
+        .map(|e| e.into_path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "json" || e == "vail"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|e| e == "json")
+}
+
+/// `value` as it appears inside a JSON string literal
+fn json_escaped(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|_| VqxError::FileReadFailed {
+        path: path.display().to_string(),
+    })
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    fs::write(path, content).map_err(|_| VqxError::FileWriteFailed {
+        path: path.display().to_string(),
+    })
+}
+
+/// Variable names used by placeholders below a directory
+pub fn used_variables(dir: &Path) -> Result<BTreeSet<String>> {
+    let mut names = BTreeSet::new();
+    for path in template_files(dir) {
+        let content = read(&path)?;
+        names.extend(pattern().captures_iter(&content).map(|c| c[1].to_string()));
+    }
+    Ok(names)
+}
+
+/// Replace placeholders in one file's content
+fn render_text(content: &str, vars: &BTreeMap<String, String>, json: bool) -> String {
+    pattern()
+        .replace_all(content, |c: &regex::Captures| match vars.get(&c[1]) {
+            Some(value) if json => json_escaped(value),
+            Some(value) => value.clone(),
+            None => c[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Render all placeholders below a directory in place
+///
+/// Fails without changing anything when the profile has no value for a
+/// variable in use. Returns the number of files changed.
+pub fn render_directory(
+    dir: &Path,
+    profile: &str,
+    vars: &BTreeMap<String, String>,
+) -> Result<usize> {
+    let missing: Vec<String> = used_variables(dir)?
+        .into_iter()
+        .filter(|name| !vars.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(VqxError::TemplateVariablesMissing {
+            profile: profile.to_string(),
+            names: missing.join(", "),
+        });
+    }
+
+    let mut rendered = 0;
+    for path in template_files(dir) {
+        let content = read(&path)?;
+        let output = render_text(&content, vars, is_json(&path));
+        if output != content {
+            write(&path, &output)?;
+            rendered += 1;
+        }
+    }
+    Ok(rendered)
+}
+
+/// Rendered copy of a directory for import, or `None` without placeholders
+pub fn render_copy(
+    dir: &Path,
+    profile: &str,
+    vars: &BTreeMap<String, String>,
+) -> Result<Option<TempDir>> {
+    if used_variables(dir)?.is_empty() {
+        return Ok(None);
+    }
+    let copy = TempDir::new()?;
+    crate::normalizer::copy_directory(dir, copy.path())?;
+    render_directory(copy.path(), profile, vars)?;
+    Ok(Some(copy))
+}
+
+/// Values replaced by a placeholder in one file
+#[derive(Debug, Clone, Serialize)]
+pub struct Extraction {
+    /// Path relative to the directory
+    pub file: String,
+    pub variable: String,
+    pub occurrences: usize,
+}
+
+/// Replace the values of `vars` with placeholders below a directory
+///
+/// Values are replaced wherever they occur, longest first so a value that
+/// contains another one is kept whole. Empty values are skipped. With
+/// `dry_run`, files are left unchanged.
+pub fn extract_directory(
+    dir: &Path,
+    vars: &BTreeMap<String, String>,
+    dry_run: bool,
+) -> Result<Vec<Extraction>> {
+    let mut ordered: Vec<(&String, &String)> =
+        vars.iter().filter(|(_, value)| !value.is_empty()).collect();
+    ordered.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+
+    let mut extractions = Vec::new();
+    for path in template_files(dir) {
+        let mut content = read(&path)?;
+        let file = path
+            .strip_prefix(dir)
+            .unwrap_or(&path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let mut changed = false;
+        for (name, value) in &ordered {
+            let needle = if is_json(&path) {
+                json_escaped(value)
+            } else {
+                value.to_string()
+            };
+            let occurrences = content.matches(&needle).count();
+            if occurrences > 0 {
+                content = content.replace(&needle, &placeholder(name));
+                changed = true;
+                extractions.push(Extraction {
+                    file: file.clone(),
+                    variable: name.to_string(),
+                    occurrences,
+                });
+            }
+        }
+        if changed && !dry_run {
+            write(&path, &content)?;
+        }
+    }
+    Ok(extractions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn write_file(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_render() {
+        let dir = TempDir::new().unwrap();
+        write_file(
+            dir.path(),
+            "sources/Crm.json",
+            r#"{"config": {"uri": "{{vars.crm_url}}/api", "note": "{{ vars.quote }}"}}"#,
+        );
+        write_file(
+            dir.path(),
+            "procedures/Ping.vail",
+            "PROCEDURE Ping()\nreturn \"{{vars.crm_url}}\"\n",
+        );
+        let values = vars(&[("crm_url", "https://crm.example.com"), ("quote", "a\"b")]);
+
+        let copy = render_copy(dir.path(), "prod", &values).unwrap().unwrap();
+        let json = fs::read_to_string(copy.path().join("sources/Crm.json")).unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()["config"],
+            serde_json::json!({"uri": "https://crm.example.com/api", "note": "a\"b"})
+        );
+        let code = fs::read_to_string(copy.path().join("procedures/Ping.vail")).unwrap();
+        assert!(code.contains("return \"https://crm.example.com\""));
+
+        // The original stays a template
+        let original = fs::read_to_string(dir.path().join("sources/Crm.json")).unwrap();
+        assert!(original.contains("{{vars.crm_url}}"));
+
+        let err = render_copy(dir.path(), "dev", &vars(&[("crm_url", "x")])).unwrap_err();
+        assert!(err.to_string().contains("quote"));
+    }
+
+    #[test]
+    fn test_extract_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let original =
+            r#"{"uri": "https://crm.example.com/api", "backup": "https://crm.example.com.bak"}"#;
+        write_file(dir.path(), "sources/Crm.json", original);
+        let values = vars(&[
+            ("crm_url", "https://crm.example.com"),
+            ("backup_url", "https://crm.example.com.bak"),
+        ]);
+
+        let found = extract_directory(dir.path(), &values, true).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("sources/Crm.json")).unwrap(),
+            original
+        );
+
+        extract_directory(dir.path(), &values, false).unwrap();
+        let content = fs::read_to_string(dir.path().join("sources/Crm.json")).unwrap();
+        assert_eq!(
+            content,
+            r#"{"uri": "{{vars.crm_url}}/api", "backup": "{{vars.backup_url}}"}"#
+        );
+
+        render_directory(dir.path(), "dev", &values).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("sources/Crm.json")).unwrap(),
+            original
+        );
+        assert!(is_valid_name("db_url"));
+        assert!(!is_valid_name("db-url"));
+    }
+}
@@ -0,0 +1,380 @@
+//! Structured results of `run testsuite`
+//!
+//! The underlying CLI prints test suite results either as a JSON report or
+//! as one line per test (`Test <name> passed`, `FAIL <name>: <message>`).
+//! Both are parsed into [`TestCase`]s, which can be written as JUnit XML
+//! for CI systems.
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::sync::OnceLock;
+
+/// Outcome of one test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    /// The test could not be run to completion
+    Error,
+    Skipped,
+}
+
+impl TestStatus {
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "passed" | "pass" | "success" | "succeeded" | "ok" => Some(TestStatus::Passed),
+            "failed" | "fail" | "failure" => Some(TestStatus::Failed),
+            "error" | "errored" => Some(TestStatus::Error),
+            "skipped" | "skip" => Some(TestStatus::Skipped),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TestStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TestStatus::Passed => write!(f, "passed"),
+            TestStatus::Failed => write!(f, "failed"),
+            TestStatus::Error => write!(f, "error"),
+            TestStatus::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Result of one test in a suite
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TestCase {
+    pub name: String,
+    pub status: TestStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+/// Counts of test outcomes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub skipped: usize,
+}
+
+impl TestSummary {
+    pub fn of(tests: &[TestCase]) -> Self {
+        let mut summary = TestSummary::default();
+        for test in tests {
+            match test.status {
+                TestStatus::Passed => summary.passed += 1,
+                TestStatus::Failed => summary.failed += 1,
+                TestStatus::Error => summary.errors += 1,
+                TestStatus::Skipped => summary.skipped += 1,
+            }
+        }
+        summary
+    }
+
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.errors + self.skipped
+    }
+
+    /// Whether any test failed or errored
+    pub fn has_failures(&self) -> bool {
+        self.failed + self.errors > 0
+    }
+}
+
+/// Parse the output of `run testsuite` into test cases
+///
+/// Returns an empty list if no per-test results are recognized.
+pub fn parse_output(output: &str) -> Vec<TestCase> {
+    // The report may be preceded and followed by progress messages
+    let json = output.find(['{', '[']).and_then(|i| {
+        serde_json::Deserializer::from_str(&output[i..])
+            .into_iter::<Value>()
+            .next()?
+            .ok()
+    });
+    if let Some(value) = json {
+        let tests = parse_json(&value);
+        if !tests.is_empty() {
+            return tests;
+        }
+    }
+    output.lines().filter_map(parse_line).collect()
+}
+
+/// Per-test entries of a JSON report
+fn parse_json(value: &Value) -> Vec<TestCase> {
+    let entries = match value {
+        Value::Array(items) => items.as_slice(),
+        Value::Object(map) => match ["tests", "testResults", "results"]
+            .iter()
+            .find_map(|key| map.get(*key).and_then(Value::as_array))
+        {
+            Some(items) => items.as_slice(),
+            None => std::slice::from_ref(value),
+        },
+        _ => &[],
+    };
+    entries.iter().filter_map(parse_entry).collect()
+}
+
+fn parse_entry(entry: &Value) -> Option<TestCase> {
+    let field = |keys: &[&str]| keys.iter().find_map(|k| entry.get(*k));
+
+    let name = field(&["name", "testName", "test"])?.as_str()?.to_string();
+    let status = match field(&["status", "state", "result", "passed", "success"])? {
+        Value::String(s) => TestStatus::parse(s)?,
+        Value::Bool(true) => TestStatus::Passed,
+        Value::Bool(false) => TestStatus::Failed,
+        _ => return None,
+    };
+    let message = field(&["message", "error", "errors", "failureMessage"]).and_then(|m| match m {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Array(items) if !items.is_empty() => Some(
+            items
+                .iter()
+                .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+        Value::Object(_) => Some(m.to_string()),
+        _ => None,
+    });
+    let duration_ms = field(&["durationMs", "duration", "elapsed"]).and_then(Value::as_u64);
+
+    Some(TestCase {
+        name,
+        status,
+        message,
+        duration_ms,
+    })
+}
+
+fn line_patterns() -> &'static [Regex; 2] {
+    static PATTERNS: OnceLock<[Regex; 2]> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            // PASS MyTest / FAIL MyTest: message
+            Regex::new(r"^\s*(?P<status>PASS|PASSED|FAIL|FAILED|OK|ERROR|SKIP|SKIPPED)\b[\s:-]+'?(?P<name>[\w./-]+)'?(?:\s*[:-]\s*(?P<message>.+))?$")
+                .unwrap(),
+            // Test MyTest passed / Test 'MyTest' failed: message
+            Regex::new(r"^\s*(?i:test)\s+'?(?P<name>[\w./-]+)'?\s*(?::\s*)?(?i:(?P<status>passed|failed|succeeded|success|failure|error|errored|skipped))\b(?:\s*[:-]\s*(?P<message>.+))?$")
+                .unwrap(),
+        ]
+    })
+}
+
+fn parse_line(line: &str) -> Option<TestCase> {
+    let caps = line_patterns().iter().find_map(|re| re.captures(line))?;
+    Some(TestCase {
+        name: caps["name"].to_string(),
+        status: TestStatus::parse(&caps["status"])?,
+        message: caps.name("message").map(|m| m.as_str().trim().to_string()),
+        duration_ms: None,
+    })
+}
+
+/// Escape text for an XML attribute or element
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            c if c.is_control() && c != '\t' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn seconds(ms: u64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+/// One suite in a JUnit report
+#[derive(Debug)]
+pub struct JunitSuite<'a> {
+    pub name: &'a str,
+    pub tests: &'a [TestCase],
+    /// Execution error of the suite, if it could not be run
+    pub error: Option<&'a str>,
+    pub elapsed_ms: u64,
+}
+
+impl JunitSuite<'_> {
+    /// Test cases, with the execution error as an errored case named after
+    /// the suite so CI shows it even when no test results were printed
+    fn cases(&self) -> Vec<TestCase> {
+        let mut cases = self.tests.to_vec();
+        if let Some(error) = self.error {
+            cases.push(TestCase {
+                name: self.name.to_string(),
+                status: TestStatus::Error,
+                message: Some(error.to_string()),
+                duration_ms: None,
+            });
+        }
+        cases
+    }
+}
+
+fn junit_counts(summary: &TestSummary, elapsed_ms: u64) -> String {
+    format!(
+        "tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\"",
+        summary.total(),
+        summary.failed,
+        summary.errors,
+        summary.skipped,
+        seconds(elapsed_ms)
+    )
+}
+
+/// Render suite runs as JUnit XML
+pub fn to_junit_xml(suites: &[JunitSuite<'_>]) -> String {
+    let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S");
+    let suite_cases: Vec<Vec<TestCase>> = suites.iter().map(JunitSuite::cases).collect();
+    let all_cases: Vec<TestCase> = suite_cases.iter().flatten().cloned().collect();
+    let elapsed_ms = suites.iter().map(|s| s.elapsed_ms).max().unwrap_or(0);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"vqx\" {}>",
+        junit_counts(&TestSummary::of(&all_cases), elapsed_ms)
+    );
+    for (suite, cases) in suites.iter().zip(&suite_cases) {
+        let name = xml_escape(suite.name);
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" {} timestamp=\"{}\">",
+            name,
+            junit_counts(&TestSummary::of(cases), suite.elapsed_ms),
+            timestamp
+        );
+        for case in cases {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                xml_escape(&case.name),
+                name
+            );
+            if let Some(ms) = case.duration_ms {
+                let _ = write!(xml, " time=\"{}\"", seconds(ms));
+            }
+            let message = xml_escape(case.message.as_deref().unwrap_or_default());
+            let _ = match case.status {
+                TestStatus::Passed => writeln!(xml, "/>"),
+                TestStatus::Failed => writeln!(
+                    xml,
+                    ">\n      <failure message=\"{}\"/>\n    </testcase>",
+                    message
+                ),
+                TestStatus::Error => writeln!(
+                    xml,
+                    ">\n      <error message=\"{}\"/>\n    </testcase>",
+                    message
+                ),
+                TestStatus::Skipped => writeln!(xml, ">\n      <skipped/>\n    </testcase>"),
+            };
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_report() {
+        let output = r#"Running test suite OrderSuite
+{"name": "OrderSuite", "status": "failure", "tests": [
+  {"name": "createOrder", "status": "success", "duration": 120},
+  {"name": "cancelOrder", "status": "failure", "errors": ["expected 1", "got 0"]},
+  {"name": "refund", "passed": true}
+]}
+Done"#;
+        let tests = parse_output(output);
+        assert_eq!(tests.len(), 3);
+        assert_eq!(tests[0].status, TestStatus::Passed);
+        assert_eq!(tests[0].duration_ms, Some(120));
+        assert_eq!(tests[1].status, TestStatus::Failed);
+        assert_eq!(tests[1].message.as_deref(), Some("expected 1; got 0"));
+        assert_eq!(tests[2].status, TestStatus::Passed);
+    }
+
+    #[test]
+    fn test_parse_text_output() {
+        let output = "Running test suite OrderSuite\n\
+                      Test createOrder passed\n\
+                      Test 'cancelOrder' failed: expected 1 but got 0\n\
+                      SKIP refund\n\
+                      Done.\n";
+        let tests = parse_output(output);
+        let summary: Vec<_> = tests.iter().map(|t| (t.name.as_str(), t.status)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("createOrder", TestStatus::Passed),
+                ("cancelOrder", TestStatus::Failed),
+                ("refund", TestStatus::Skipped),
+            ]
+        );
+        assert_eq!(tests[1].message.as_deref(), Some("expected 1 but got 0"));
+        assert!(parse_output("Nothing to see").is_empty());
+    }
+
+    #[test]
+    fn test_junit_xml() {
+        let tests = vec![
+            TestCase {
+                name: "createOrder".to_string(),
+                status: TestStatus::Passed,
+                message: None,
+                duration_ms: Some(1500),
+            },
+            TestCase {
+                name: "cancelOrder".to_string(),
+                status: TestStatus::Failed,
+                message: Some("expected <1> & got \"0\"".to_string()),
+                duration_ms: None,
+            },
+        ];
+        let xml = to_junit_xml(&[JunitSuite {
+            name: "OrderSuite",
+            tests: &tests,
+            error: None,
+            elapsed_ms: 2000,
+        }]);
+        assert!(xml.contains(
+            "<testsuites name=\"vqx\" tests=\"2\" failures=\"1\" errors=\"0\" skipped=\"0\" time=\"2.000\">"
+        ));
+        assert!(xml
+            .contains("<testcase name=\"createOrder\" classname=\"OrderSuite\" time=\"1.500\"/>"));
+        assert!(xml.contains("<failure message=\"expected &lt;1&gt; &amp; got &quot;0&quot;\"/>"));
+
+        let xml = to_junit_xml(&[JunitSuite {
+            name: "OrderSuite@dev",
+            tests: &[],
+            error: Some("Connection refused"),
+            elapsed_ms: 0,
+        }]);
+        assert!(xml.contains("errors=\"1\""));
+        assert!(xml.contains("<error message=\"Connection refused\"/>"));
+    }
+}
@@ -0,0 +1,175 @@
+//! Colors and themes for terminal output
+//!
+//! All human-readable output is styled through [`style`], which works like
+//! `console::style` but resolves colors through the configured theme
+//! (`[output] theme`) when it is displayed:
+//!
+//! - `default`: the colors as written
+//! - `high-contrast`: bright, bold colors and no faint (dim) text
+//!
+//! Whether colors are used at all is decided once by [`configure`]:
+//! `--color always|never` wins; with `auto` (the default), `NO_COLOR`
+//! (see <https://no-color.org>) or `[output] colors = false` turn them off,
+//! and otherwise they are used when the output is a terminal.
+
+use crate::cli::ColorWhen;
+use crate::config::{ColorTheme, OutputConfig};
+use console::{Color, Style};
+use std::fmt;
+use std::sync::OnceLock;
+
+static THEME: OnceLock<ColorTheme> = OnceLock::new();
+
+/// Decide whether to use colors and pick the theme
+pub fn configure(when: ColorWhen, output: &OutputConfig) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if let Some(enabled) = colors_wanted(when, output.colors, no_color) {
+        console::set_colors_enabled(enabled);
+        console::set_colors_enabled_stderr(enabled);
+    }
+    let _ = THEME.set(output.theme);
+}
+
+/// Forced color setting, or `None` to detect the terminal
+fn colors_wanted(when: ColorWhen, configured: bool, no_color: bool) -> Option<bool> {
+    match when {
+        ColorWhen::Always => Some(true),
+        ColorWhen::Never => Some(false),
+        ColorWhen::Auto if no_color || !configured => Some(false),
+        ColorWhen::Auto => None,
+    }
+}
+
+/// Whether stdout output is colored
+pub fn colors_enabled() -> bool {
+    console::colors_enabled()
+}
+
+/// Wrap a value for styled display
+pub fn style<D>(val: D) -> Styled<D> {
+    Styled {
+        val,
+        color: None,
+        bold: false,
+        dim: false,
+    }
+}
+
+/// A value with a color and attributes, displayed in the current theme
+pub struct Styled<D> {
+    val: D,
+    color: Option<Color>,
+    bold: bool,
+    dim: bool,
+}
+
+impl<D> Styled<D> {
+    fn fg(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn red(self) -> Self {
+        self.fg(Color::Red)
+    }
+
+    pub fn green(self) -> Self {
+        self.fg(Color::Green)
+    }
+
+    pub fn yellow(self) -> Self {
+        self.fg(Color::Yellow)
+    }
+
+    pub fn cyan(self) -> Self {
+        self.fg(Color::Cyan)
+    }
+
+    pub fn magenta(self) -> Self {
+        self.fg(Color::Magenta)
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+
+    /// Console style for the attributes in `theme`
+    fn resolve(&self, theme: ColorTheme) -> Style {
+        let mut style = Style::new();
+        match theme {
+            ColorTheme::Default => {
+                if let Some(color) = self.color {
+                    style = style.fg(color);
+                }
+                if self.bold {
+                    style = style.bold();
+                }
+                if self.dim {
+                    style = style.dim();
+                }
+            }
+            ColorTheme::HighContrast => {
+                // Faint text is hard to read; keep it in the normal color
+                if let Some(color) = self.color {
+                    style = style.fg(color).bright().bold();
+                } else if self.bold {
+                    style = style.bold();
+                }
+            }
+        }
+        style
+    }
+}
+
+impl<D: fmt::Display> fmt::Display for Styled<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let theme = THEME.get().copied().unwrap_or_default();
+        fmt::Display::fmt(&self.resolve(theme).apply_to(&self.val), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colors_wanted() {
+        assert_eq!(colors_wanted(ColorWhen::Auto, true, false), None);
+        assert_eq!(colors_wanted(ColorWhen::Auto, true, true), Some(false));
+        assert_eq!(colors_wanted(ColorWhen::Auto, false, false), Some(false));
+        assert_eq!(colors_wanted(ColorWhen::Always, false, true), Some(true));
+        assert_eq!(colors_wanted(ColorWhen::Never, true, false), Some(false));
+    }
+
+    #[test]
+    fn test_themes() {
+        let styled = style("x").green().dim();
+        let text = |theme| {
+            styled
+                .resolve(theme)
+                .force_styling(true)
+                .apply_to("x")
+                .to_string()
+        };
+        assert_eq!(text(ColorTheme::Default), "\u{1b}[32m\u{1b}[2mx\u{1b}[0m");
+        assert_eq!(
+            text(ColorTheme::HighContrast),
+            "\u{1b}[38;5;10m\u{1b}[1mx\u{1b}[0m"
+        );
+        assert_eq!(
+            style("x")
+                .dim()
+                .resolve(ColorTheme::HighContrast)
+                .force_styling(true)
+                .apply_to("x")
+                .to_string(),
+            "x"
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Per-resource-type timing for export/import
+//!
+//! The underlying CLI reports progress as free-form lines that mention the
+//! resource type being processed (e.g. "Exporting procedures..."). Time is
+//! attributed to the most recently mentioned type until another type is
+//! mentioned or the command finishes.
+
+use crate::theme::style;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Resource type directories produced by export (PDF "Export" section)
+pub const RESOURCE_TYPES: &[&str] = &[
+    "types",
+    "procedures",
+    "rules",
+    "sources",
+    "services",
+    "topics",
+    "collaborationtypes",
+    "aicomponents",
+    "catalogs",
+    "clients",
+    "configurations",
+    "debugconfigs",
+    "deployconfigs",
+    "environments",
+    "projects",
+    "scheduledevents",
+    "subscriptions",
+    "systemmodels",
+    "documents",
+    "data",
+];
+
+/// A type is an outlier when it takes at least this multiple of the median...
+const OUTLIER_FACTOR: f64 = 3.0;
+/// ...and at least this long
+const OUTLIER_MIN: Duration = Duration::from_secs(5);
+
+/// Time spent on one resource type
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ResourceTiming {
+    pub resource_type: String,
+    pub seconds: f64,
+    /// Significantly slower than the other types
+    pub outlier: bool,
+}
+
+/// Attributes elapsed time to resource types from streamed output lines
+#[derive(Debug)]
+pub struct TimingTracker {
+    current: Option<(String, Instant)>,
+    totals: BTreeMap<String, Duration>,
+}
+
+impl Default for TimingTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimingTracker {
+    pub fn new() -> Self {
+        Self {
+            current: None,
+            totals: BTreeMap::new(),
+        }
+    }
+
+    /// Observe an output line received at `now`
+    pub fn observe(&mut self, line: &str, now: Instant) {
+        let Some(resource_type) = detect_resource_type(line) else {
+            return;
+        };
+
+        if let Some((ref current, _)) = self.current {
+            if *current == resource_type {
+                return;
+            }
+        }
+
+        self.close(now);
+        self.current = Some((resource_type, now));
+    }
+
+    /// Attribute a measured duration to a resource type, e.g. a CLI call of its own
+    pub fn add(&mut self, resource_type: &str, elapsed: Duration) {
+        *self.totals.entry(resource_type.to_string()).or_default() += elapsed;
+    }
+
+    /// Stop the clock and return the breakdown, sorted by resource type
+    pub fn finish(mut self, now: Instant) -> Vec<ResourceTiming> {
+        self.close(now);
+
+        let mut durations: Vec<Duration> = self.totals.values().copied().collect();
+        durations.sort();
+        let median = durations
+            .get(durations.len() / 2)
+            .copied()
+            .unwrap_or_default();
+
+        self.totals
+            .into_iter()
+            .map(|(resource_type, d)| ResourceTiming {
+                resource_type,
+                seconds: (d.as_secs_f64() * 1000.0).round() / 1000.0,
+                outlier: durations.len() > 1
+                    && d >= OUTLIER_MIN
+                    && d.as_secs_f64() >= median.as_secs_f64() * OUTLIER_FACTOR,
+            })
+            .collect()
+    }
+
+    fn close(&mut self, now: Instant) {
+        if let Some((resource_type, started)) = self.current.take() {
+            *self.totals.entry(resource_type).or_default() +=
+                now.saturating_duration_since(started);
+        }
+    }
+}
+
+/// Print the breakdown, highlighting outliers
+pub fn display_breakdown(timings: &[ResourceTiming]) {
+    if timings.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", style("Time by resource type:").bold());
+    let width = timings
+        .iter()
+        .map(|t| t.resource_type.len())
+        .max()
+        .unwrap_or(0);
+    for timing in timings {
+        let line = format!(
+            "  {:<width$}  {:>9.1}s",
+            timing.resource_type,
+            timing.seconds,
+            width = width
+        );
+        if timing.outlier {
+            println!("{}  {}", style(line).yellow(), style("⚠ slow").yellow());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Find the resource type mentioned in a CLI output line
+///
+/// Singular forms ("type", "procedure") are accepted; the plural directory
+/// name is returned.
+fn detect_resource_type(line: &str) -> Option<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        let alternatives: Vec<String> = RESOURCE_TYPES
+            .iter()
+            .map(|t| regex::escape(t.strip_suffix('s').unwrap_or(t)))
+            .collect();
+        Regex::new(&format!(r"(?i)\b({})s?\b", alternatives.join("|"))).unwrap()
+    });
+
+    let found = pattern.captures(line)?.get(0)?.as_str().to_lowercase();
+    RESOURCE_TYPES
+        .iter()
+        .find(|t| **t == found || t.strip_suffix('s') == Some(found.as_str()))
+        .map(|t| t.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_resource_type() {
+        assert_eq!(
+            detect_resource_type("Exporting procedures...").as_deref(),
+            Some("procedures")
+        );
+        assert_eq!(
+            detect_resource_type("Importing type Order").as_deref(),
+            Some("types")
+        );
+        assert_eq!(
+            detect_resource_type("Exported 3 collaborationtypes").as_deref(),
+            Some("collaborationtypes")
+        );
+        assert_eq!(detect_resource_type("Connecting to server"), None);
+    }
+
+    #[test]
+    fn test_tracker_breakdown() {
+        let start = Instant::now();
+        let mut tracker = TimingTracker::new();
+        tracker.observe("Exporting types", start);
+        tracker.observe("type A", start + Duration::from_secs(1));
+        tracker.observe("Exporting rules", start + Duration::from_secs(2));
+        tracker.observe("Exporting procedures", start + Duration::from_secs(3));
+        let timings = tracker.finish(start + Duration::from_secs(30));
+
+        let names: Vec<_> = timings.iter().map(|t| t.resource_type.as_str()).collect();
+        assert_eq!(names, vec!["procedures", "rules", "types"]);
+        assert_eq!(timings[0].seconds, 27.0);
+        assert!(timings[0].outlier);
+        assert_eq!(timings[2].seconds, 2.0);
+        assert!(!timings[2].outlier);
+    }
+}
@@ -0,0 +1,258 @@
+//! Trash for safe-delete (vqx extension)
+//!
+//! `safe-delete --trash` keeps what it deletes in a local trash entry
+//! instead of a plain backup, so a delete can be undone during a grace
+//! period:
+//! - resource definitions are stored in the export layout
+//!   (`metadata/<type>/<name>.json`) and restored with `import metadata`
+//! - type data records are stored in `records.json` and restored with
+//!   `insert`
+//!
+//! Each entry is tagged with the profile, the delete target, the user and the
+//! time (`entry.json`). `vqx trash empty` removes entries older than
+//! `[trash] retention_days`. Entries live in `~/.local/share/vqx/trash/`
+//! unless `[trash] dir` points elsewhere.
+
+use crate::approval::current_user;
+use crate::config::{Config, TrashConfig};
+use crate::error::{Result, VqxError};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const ENTRY_FILE: &str = "entry.json";
+const METADATA_DIR: &str = "metadata";
+const RECORDS_FILE: &str = "records.json";
+
+/// What a trash entry holds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashEntry {
+    pub id: String,
+    /// vqx profile the items were deleted from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    pub resource_type: String,
+    /// Name or query given to safe-delete
+    pub target: String,
+    /// Resources as `<type>/<name>` and records by `_id` (or name)
+    pub items: Vec<String>,
+    pub deleted_by: String,
+    pub deleted_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl TrashEntry {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// A resource definition to keep, in the export layout
+pub struct TrashedDefinition<'a> {
+    pub resource_type: &'a str,
+    pub name: &'a str,
+    pub definition: &'a Value,
+}
+
+/// Trash directory
+pub struct TrashStore {
+    dir: PathBuf,
+    retention: Duration,
+}
+
+impl TrashStore {
+    /// Store in the configured (or default) directory
+    pub fn new(config: &Config) -> Self {
+        let TrashConfig {
+            dir,
+            retention_days,
+        } = &config.trash;
+        let dir = match dir {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("vqx")
+                .join("trash"),
+        };
+        Self::in_dir(dir, *retention_days)
+    }
+
+    pub fn in_dir(dir: impl Into<PathBuf>, retention_days: u32) -> Self {
+        Self {
+            dir: dir.into(),
+            retention: Duration::days(retention_days.into()),
+        }
+    }
+
+    /// Directory of an entry
+    pub fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(id)
+    }
+
+    /// Resource definitions of an entry, in the export layout
+    pub fn metadata_dir(&self, id: &str) -> Option<PathBuf> {
+        Some(self.path(id).join(METADATA_DIR)).filter(|p| p.is_dir())
+    }
+
+    /// Type data records of an entry (a JSON array)
+    pub fn records_file(&self, id: &str) -> Option<PathBuf> {
+        Some(self.path(id).join(RECORDS_FILE)).filter(|p| p.is_file())
+    }
+
+    /// Create an entry for the given definitions and records
+    pub fn put(
+        &self,
+        profile: Option<&str>,
+        resource_type: &str,
+        target: &str,
+        definitions: &[TrashedDefinition<'_>],
+        records: &[Value],
+    ) -> Result<TrashEntry> {
+        let now = Utc::now();
+        let base = format!("{}_{}", now.format("%Y%m%d_%H%M%S"), resource_type);
+        let mut id = base.clone();
+        let mut n = 1;
+        while self.path(&id).exists() {
+            n += 1;
+            id = format!("{}_{}", base, n);
+        }
+        let entry_dir = self.path(&id);
+
+        let mut items = Vec::new();
+        for d in definitions {
+            let file = entry_dir
+                .join(METADATA_DIR)
+                .join(d.resource_type)
+                .join(format!("{}.json", d.name));
+            write_json(&file, d.definition)?;
+            items.push(crate::report::resource_id(d.resource_type, d.name));
+        }
+        if !records.is_empty() {
+            write_json(&entry_dir.join(RECORDS_FILE), &records)?;
+            items.extend(records.iter().map(record_label));
+        }
+
+        let entry = TrashEntry {
+            id,
+            profile: profile.map(str::to_string),
+            resource_type: resource_type.to_string(),
+            target: target.to_string(),
+            items,
+            deleted_by: current_user(),
+            deleted_at: now,
+            expires_at: now + self.retention,
+        };
+        write_json(&entry_dir.join(ENTRY_FILE), &entry)?;
+        Ok(entry)
+    }
+
+    /// All entries, oldest first
+    pub fn list(&self) -> Vec<TrashEntry> {
+        let Ok(dirs) = fs::read_dir(&self.dir) else {
+            return vec![];
+        };
+        let mut entries: Vec<TrashEntry> = dirs
+            .flatten()
+            .filter_map(|d| fs::read_to_string(d.path().join(ENTRY_FILE)).ok())
+            .filter_map(|s| serde_json::from_str(&s).ok())
+            .collect();
+        entries.sort_by_key(|e| e.deleted_at);
+        entries
+    }
+
+    /// The entry with this ID (or unique ID prefix)
+    pub fn find(&self, id: &str) -> Result<TrashEntry> {
+        let matches: Vec<TrashEntry> = self
+            .list()
+            .into_iter()
+            .filter(|e| e.id.starts_with(id))
+            .collect();
+        match <[TrashEntry; 1]>::try_from(matches) {
+            Ok([entry]) => Ok(entry),
+            Err(matches) if matches.is_empty() => {
+                Err(VqxError::Other(format!("No trash entry {}", id)))
+            }
+            Err(_) => Err(VqxError::Other(format!(
+                "Trash entry {} is ambiguous; give more characters",
+                id
+            ))),
+        }
+    }
+
+    /// Delete an entry for good
+    pub fn remove(&self, id: &str) -> Result<()> {
+        let path = self.path(id);
+        fs::remove_dir_all(&path).map_err(|_| VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        })
+    }
+}
+
+/// How a record is listed in an entry
+fn record_label(record: &Value) -> String {
+    ["_id", "name"]
+        .iter()
+        .find_map(|key| record.get(key))
+        .map(|v| v.as_str().map_or_else(|| v.to_string(), str::to_string))
+        .unwrap_or_else(|| "(record)".to_string())
+}
+
+fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|_| VqxError::FileWriteFailed {
+            path: parent.display().to_string(),
+        })?;
+    }
+    fs::write(path, serde_json::to_string_pretty(value)? + "\n").map_err(|_| {
+        VqxError::FileWriteFailed {
+            path: path.display().to_string(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_put_and_find() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = TrashStore::in_dir(dir.path(), 7);
+
+        let definition = json!({"name": "Order"});
+        let entry = store
+            .put(
+                Some("dev"),
+                "types",
+                "Order",
+                &[TrashedDefinition {
+                    resource_type: "types",
+                    name: "Order",
+                    definition: &definition,
+                }],
+                &[],
+            )
+            .unwrap();
+        assert_eq!(entry.items, vec!["types/Order"]);
+        assert!(store.metadata_dir(&entry.id).is_some());
+        assert!(store.records_file(&entry.id).is_none());
+        assert!(!entry.is_expired(Utc::now()));
+        assert!(entry.is_expired(Utc::now() + Duration::days(8)));
+
+        let records = store
+            .put(None, "Orders", "{}", &[], &[json!({"_id": "a1"})])
+            .unwrap();
+        assert_eq!(records.items, vec!["a1"]);
+        assert_ne!(records.id, entry.id);
+
+        assert_eq!(store.list().len(), 2);
+        assert_eq!(store.find(&entry.id).unwrap().target, "Order");
+        assert!(store.find("2").is_err());
+
+        store.remove(&entry.id).unwrap();
+        assert_eq!(store.list().len(), 1);
+    }
+}
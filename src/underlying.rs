@@ -8,10 +8,18 @@
 //! - "Installation" section (page 2)
 
 use crate::error::{Result, VqxError};
-use crate::profile::Profile;
-use std::ffi::OsStr;
+use crate::profile::{ClientOptions, Profile};
+use crate::vantiq_profile::{self, VantiqProfile};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::hash_map::RandomState;
+use std::ffi::{OsStr, OsString};
+use std::hash::{BuildHasher, Hasher};
+use std::path::{Path, PathBuf};
 use std::process::{ExitStatus, Stdio};
-use std::time::Duration;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
@@ -19,12 +27,171 @@ use tracing::{debug, info, warn};
 /// Default timeout for CLI operations (2 minutes)
 const DEFAULT_TIMEOUT_SECS: u64 = 120;
 
+/// Class data sharing archive in the config directory (`warm_jvm`)
+pub const CLASS_ARCHIVE_FILE: &str = "cli-classes.jsa";
+
+/// Archive shared by the CLI processes of this run, if `warm_jvm` is on
+static CLASS_ARCHIVE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Logged when a command is killed for exceeding its timeout
+const TIMEOUT_WARNING: &str =
+    "CLI command timed out and was terminated; raise the limit with --timeout or [timeouts] in config.toml";
+
+/// Heartbeat and stall thresholds for streaming execution
+#[derive(Debug, Clone, Copy)]
+pub struct StallPolicy {
+    /// Emit a heartbeat after this long without output
+    pub heartbeat: Duration,
+    /// Report a stall after this long without output
+    pub stall_after: Duration,
+}
+
+/// Event delivered to the handler of `execute_streaming`
+#[derive(Debug)]
+pub enum StreamEvent<'a> {
+    /// A line of stdout or stderr output
+    Line { text: &'a str, stderr: bool },
+    /// No output for `idle`; the command is still running
+    Heartbeat { elapsed: Duration, idle: Duration },
+    /// No output for at least the stall threshold
+    Stall { elapsed: Duration, idle: Duration },
+}
+
+/// Handler decision after a stream event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamControl {
+    Continue,
+    /// Kill the CLI process
+    Abort,
+    /// Enough output: end the CLI process and succeed with the output so far
+    Stop,
+}
+
+/// Class of an underlying CLI command, which decides how it is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandClass {
+    /// Reads server state only (list, find, select, export)
+    Read,
+    /// Changes server state (insert, upsert, deploy, run, ...)
+    Mutate,
+    /// Deletes or overwrites server state (delete, deleteMatching, import);
+    /// never retried automatically
+    Destructive,
+}
+
+impl CommandClass {
+    /// Classify an underlying CLI command by name
+    pub fn of(command: &str) -> Self {
+        match command {
+            "list" | "find" | "select" | "export" | "dump" | "help" => CommandClass::Read,
+            "delete" | "deleteMatching" | "import" | "load" => CommandClass::Destructive,
+            _ => CommandClass::Mutate,
+        }
+    }
+}
+
+/// Retry limits for one command class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub base_delay_ms: u64,
+}
+
+impl Backoff {
+    pub const NONE: Backoff = Backoff {
+        max_retries: 0,
+        base_delay_ms: 0,
+    };
+}
+
+/// Retry behavior per command class
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub read: Backoff,
+    pub mutate: Backoff,
+    /// Fraction of each delay that is randomized away (0.0-1.0), so that
+    /// parallel invocations do not retry in lockstep
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            read: Backoff {
+                max_retries: 3,
+                base_delay_ms: 1000,
+            },
+            mutate: Backoff::NONE,
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff for a command class; destructive commands are never retried
+    pub fn backoff(&self, class: CommandClass) -> Backoff {
+        match class {
+            CommandClass::Read => self.read,
+            CommandClass::Mutate => self.mutate,
+            CommandClass::Destructive => Backoff::NONE,
+        }
+    }
+
+    /// Delay before retry `attempt` (1-based), where `random` is in [0, 1)
+    pub fn delay(&self, backoff: Backoff, attempt: u32, random: f64) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = backoff.base_delay_ms.saturating_mul(1 << exponent) as f64;
+        let jitter = self.jitter.clamp(0.0, 1.0) * random;
+        Duration::from_millis((delay * (1.0 - jitter)) as u64)
+    }
+}
+
+/// A failed attempt that was retried
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RetryAttempt {
+    /// 1-based number of the retry that followed
+    pub attempt: u32,
+    pub error: String,
+    /// Backoff before the retry, in milliseconds
+    #[serde(rename = "delay_ms", serialize_with = "serialize_millis")]
+    #[schemars(with = "u64")]
+    pub delay: Duration,
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    delay: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(delay.as_millis() as u64)
+}
+
+/// Number of records in the complete JSON documents at the start of `output`
+///
+/// A chunk is an array of records or a single record.
+fn count_records(output: &str) -> usize {
+    serde_json::Deserializer::from_str(output)
+        .into_iter::<serde_json::Value>()
+        .map_while(|value| value.ok())
+        .map(|value| value.as_array().map_or(1, Vec::len))
+        .sum()
+}
+
+/// Uniform random number in [0, 1) for backoff jitter
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
 /// Result of a CLI execution
 #[derive(Debug, Clone)]
 pub struct ExecResult {
     pub status: ExitStatus,
     pub stdout: String,
     pub stderr: String,
+    /// Failed attempts before this result, oldest first
+    pub retries: Vec<RetryAttempt>,
 }
 
 impl ExecResult {
@@ -37,8 +204,69 @@ impl ExecResult {
     pub fn code(&self) -> i32 {
         self.status.code().unwrap_or(-1)
     }
+
+    /// Check if the command failed because the server rejected the credentials
+    pub fn is_auth_failure(&self) -> bool {
+        if self.success() {
+            return false;
+        }
+        let output = format!("{}\n{}", self.stderr, self.stdout).to_lowercase();
+        AUTH_FAILURE_PATTERNS.iter().any(|p| output.contains(p))
+    }
+}
+
+/// Start every CLI process with a class data sharing archive at `archive`
+///
+/// The first call dumps the classes the CLI loads; later calls, in this and
+/// later runs, map them instead of loading and verifying them again, which
+/// removes most of the JVM startup. This needs Java 19 or later; older JVMs
+/// ignore the flags.
+pub fn share_classes(archive: PathBuf) {
+    let _ = CLASS_ARCHIVE.set(archive);
+}
+
+/// `JAVA_OPTS` that keep a class data sharing archive at `archive`
+///
+/// Existing `JAVA_OPTS` are kept; the CLI's start script passes them on to
+/// the JVM.
+fn java_opts(archive: &Path) -> String {
+    let cds = format!(
+        "-XX:+IgnoreUnrecognizedVMOptions -XX:+AutoCreateSharedArchive -XX:SharedArchiveFile={}",
+        archive.display()
+    );
+    match std::env::var("JAVA_OPTS") {
+        Ok(existing) if !existing.trim().is_empty() => format!("{} {}", existing, cds),
+        _ => cds,
+    }
+}
+
+/// Exit status for a result that did not come from a local process
+pub fn exit_status(code: i32) -> ExitStatus {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatus::from_raw(code << 8)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatus::from_raw(code as u32)
+    }
 }
 
+/// Output of the CLI when the server rejects the credentials
+const AUTH_FAILURE_PATTERNS: &[&str] = &[
+    "unauthorized",
+    "authentication failed",
+    "invalid credentials",
+    "invalid username or password",
+    "invalid token",
+    "token has expired",
+];
+
+/// Profile name inside the temporary profile file
+const TEMP_PROFILE_NAME: &str = "vqx";
+
 /// CLI command line options as defined in PDF "Command Line Options" section
 ///
 /// PDF Reference:
@@ -51,6 +279,11 @@ impl ExecResult {
 /// - `-trust` : Trust SSL certificates
 /// - `-f <profileFile>` : Profile file path
 /// - `-v` : Print version
+///
+/// The CLI has no command line options for `clientOptions` (proxy, TLS), so
+/// when a profile sets them the options are passed through a temporary
+/// profile file instead, see [`CliOptions::with_profile_file`]. The same file
+/// keeps the password/token out of `ps` output when `credentials_file` is set.
 #[derive(Debug, Clone, Default)]
 pub struct CliOptions {
     /// -s <profileName> : Profile name from underlying CLI's profile file
@@ -86,11 +319,32 @@ pub struct CliOptions {
 
     /// -v : Verbose/version flag
     pub verbose: bool,
+
+    /// HTTP client options (proxy, TLS), only expressible in a profile file
+    pub client_options: Option<ClientOptions>,
+
+    /// Pass the password/token in a temporary profile file instead of `-p`/`-t`
+    pub credentials_file: bool,
+
+    /// vqx profile the options come from (not passed to the CLI)
+    /// Used to check `[policy]`, see [`crate::policy`]
+    pub profile: Option<String>,
 }
 
 impl CliOptions {
     /// Create CliOptions from a vqx Profile
     pub fn from_profile(profile: &Profile) -> Self {
+        // A profile linked to the CLI's profile file takes URL and credentials from there
+        if let Some(ref name) = profile.vantiq_profile {
+            return Self {
+                underlying_profile: Some(name.clone()),
+                profile_file: profile.vantiq_profile_file.clone(),
+                namespace: profile.namespace.clone(),
+                trust_ssl: profile.trust_ssl,
+                profile: profile.name.clone(),
+                ..Self::default()
+            };
+        }
         Self {
             underlying_profile: None, // We don't use underlying profile when we have credentials
             base_url: Some(profile.url.clone()),
@@ -101,7 +355,62 @@ impl CliOptions {
             trust_ssl: profile.trust_ssl,
             profile_file: None,
             verbose: false,
+            client_options: profile.client_options.clone(),
+            credentials_file: profile.credential_passing.uses_file(profile.protection),
+            profile: profile.name.clone(),
+        }
+    }
+
+    /// Options that carry the credentials and client options in a temporary
+    /// profile file
+    ///
+    /// The file holds the URL, credentials and `clientOptions` under the
+    /// profile `vqx` and is passed as `-s vqx -f <file>`. It is readable by
+    /// the owner only and removed when the returned guard is dropped, also
+    /// when a timeout or panic unwinds the caller, so keep the guard alive
+    /// until the CLI exits. Options that need neither, or that are linked to
+    /// a CLI profile, are returned unchanged.
+    pub fn with_profile_file(&self) -> Result<(CliOptions, Option<tempfile::NamedTempFile>)> {
+        let has_secret = self.password.is_some() || self.token.is_some();
+        let needs_file = self.client_options.is_some() || (self.credentials_file && has_secret);
+        if !needs_file || self.underlying_profile.is_some() {
+            return Ok((self.clone(), None));
         }
+
+        let profile = VantiqProfile {
+            name: TEMP_PROFILE_NAME.to_string(),
+            url: self.base_url.clone(),
+            username: self.username.clone(),
+            password: self.password.clone(),
+            // Password takes precedence, as with -p/-t
+            token: self
+                .password
+                .is_none()
+                .then(|| self.token.clone())
+                .flatten(),
+            client_options: self.client_options.clone(),
+            ..Default::default()
+        };
+        let write_failed = |e: std::io::Error| {
+            VqxError::Other(format!("Cannot write temporary profile file: {}", e))
+        };
+        let mut file = tempfile::Builder::new()
+            .prefix("vqx-profile-")
+            .tempfile()
+            .map_err(write_failed)?;
+        std::io::Write::write_all(&mut file, vantiq_profile::render(&[profile]).as_bytes())
+            .map_err(write_failed)?;
+
+        let options = CliOptions {
+            underlying_profile: Some(TEMP_PROFILE_NAME.to_string()),
+            profile_file: Some(file.path().to_string_lossy().to_string()),
+            namespace: self.namespace.clone(),
+            trust_ssl: self.trust_ssl,
+            verbose: self.verbose,
+            profile: self.profile.clone(),
+            ..Default::default()
+        };
+        Ok((options, Some(file)))
     }
 
     /// Validate options according to PDF constraints
@@ -116,7 +425,7 @@ impl CliOptions {
 
     /// Convert to command line arguments
     /// Based on PDF "Command Line Options" section
-    fn to_args(&self) -> Vec<String> {
+    pub(crate) fn to_args(&self) -> Vec<String> {
         let mut args = Vec::new();
 
         // -s <profileName>
@@ -243,11 +552,8 @@ pub struct UnderlyingCli {
     /// Default timeout for operations
     timeout: Duration,
 
-    /// Retry configuration
-    max_retries: u32,
-
-    /// Base delay for exponential backoff (milliseconds)
-    retry_base_delay_ms: u64,
+    /// Retry behavior per command class
+    retry: RetryPolicy,
 }
 
 impl UnderlyingCli {
@@ -256,38 +562,26 @@ impl UnderlyingCli {
         Self {
             cli_path,
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
-            max_retries: 3,
-            retry_base_delay_ms: 1000,
+            retry: RetryPolicy::default(),
         }
     }
 
-    /// Create with default CLI name based on platform
-    /// PDF: "vantiq" for Mac/Linux, "vantiq.bat" for Windows
-    pub fn with_default_path() -> Self {
-        let cli_name = if cfg!(windows) {
-            "vantiq.bat"
-        } else {
-            "vantiq"
-        };
-        Self::new(cli_name.to_string())
-    }
-
     /// Set timeout
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         self
     }
 
-    /// Set retry configuration
-    pub fn with_retries(mut self, max_retries: u32, base_delay_ms: u64) -> Self {
-        self.max_retries = max_retries;
-        self.retry_base_delay_ms = base_delay_ms;
+    /// Set retry behavior
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
         self
     }
 
-    /// Get the CLI path
-    pub fn cli_path(&self) -> &str {
-        &self.cli_path
+    /// Get the timeout
+    #[cfg_attr(not(feature = "rest-backend"), allow(dead_code))]
+    pub fn timeout(&self) -> Duration {
+        self.timeout
     }
 
     /// Check if CLI exists and is executable
@@ -307,6 +601,7 @@ impl UnderlyingCli {
     /// - Option validation
     /// - Argument construction (based on PDF "Command Line Options")
     /// - Timeout handling
+    /// - Retries of transient failures, per command class
     /// - Logging with masked secrets
     pub async fn execute<I, S>(
         &self,
@@ -321,15 +616,14 @@ impl UnderlyingCli {
         // Validate options according to PDF constraints
         options.validate()?;
 
-        let command_args: Vec<String> = args
-            .into_iter()
-            .map(|s| s.as_ref().to_string_lossy().to_string())
-            .collect();
+        let (raw_args, command_args) = Self::collect_args(args);
+        crate::offline::check(command)?;
+        crate::policy::check(options.profile.as_deref(), command, &command_args)?;
 
         // Build full argument list: [options] [command] [command_args]
-        let mut full_args = options.to_args();
-        full_args.push(command.to_string());
-        full_args.extend(command_args.clone());
+        // The temporary profile file, if any, lives until the CLI is done
+        let (options, _profile_file) = options.with_profile_file()?;
+        let full_args = Self::full_args(&options, command, raw_args);
 
         // Log with masked secrets
         let masked_args = options.to_masked_args();
@@ -341,7 +635,194 @@ impl UnderlyingCli {
             "Executing CLI command"
         );
 
-        self.execute_raw(&full_args).await
+        let _step = crate::summary::step(command);
+        let mut retries = Vec::new();
+        loop {
+            let outcome = self.execute_raw(&full_args).await;
+            match self.retry_delay(command, &outcome, &mut retries) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Self::with_history(command, outcome, retries),
+            }
+        }
+    }
+
+    /// Delay before retrying a transient failure, recorded in `retries`, or
+    /// `None` when the outcome is final
+    fn retry_delay(
+        &self,
+        command: &str,
+        outcome: &Result<ExecResult>,
+        retries: &mut Vec<RetryAttempt>,
+    ) -> Option<Duration> {
+        let error = match outcome {
+            Ok(result) if !result.success() && Self::is_transient_error(result) => {
+                format!("exit code {}: {}", result.code(), result.stderr.trim())
+            }
+            Err(e) if Self::is_retryable_error(e) => e.to_string(),
+            _ => return None,
+        };
+
+        let backoff = self.retry.backoff(CommandClass::of(command));
+        let attempt = retries.len() as u32 + 1;
+        if attempt > backoff.max_retries {
+            return None;
+        }
+
+        let delay = self.retry.delay(backoff, attempt, random_unit());
+        warn!(
+            command = %command,
+            attempt,
+            max_retries = backoff.max_retries,
+            delay_ms = delay.as_millis() as u64,
+            error = %error,
+            "Retrying CLI command"
+        );
+        retries.push(RetryAttempt {
+            attempt,
+            error,
+            delay,
+        });
+        Some(delay)
+    }
+
+    /// Attach the retry history to the final outcome and log it
+    fn with_history(
+        command: &str,
+        outcome: Result<ExecResult>,
+        retries: Vec<RetryAttempt>,
+    ) -> Result<ExecResult> {
+        if !retries.is_empty() {
+            let succeeded = matches!(outcome, Ok(ref r) if r.success());
+            info!(
+                command = %command,
+                retries = retries.len(),
+                succeeded,
+                "CLI command finished after retries"
+            );
+            for retry in &retries {
+                debug!(
+                    command = %command,
+                    attempt = retry.attempt,
+                    delay_ms = retry.delay.as_millis() as u64,
+                    error = %retry.error,
+                    "Retry history"
+                );
+            }
+        }
+
+        outcome
+            .map(|result| ExecResult { retries, ..result })
+            .and_then(Self::check_java)
+            .and_then(Self::check_auth)
+    }
+
+    /// Error for a CLI process that could not be started
+    fn spawn_error(&self, e: std::io::Error) -> VqxError {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => VqxError::CliNotFound {
+                path: self.cli_path.clone(),
+            },
+            std::io::ErrorKind::PermissionDenied => VqxError::CliNotExecutable {
+                path: self.cli_path.clone(),
+            },
+            _ => VqxError::CliSpawnFailed {
+                message: e.to_string(),
+            },
+        }
+    }
+
+    /// Turn the CLI's start script failing to find or run Java into an
+    /// environment error instead of a generic command failure
+    fn check_java(result: ExecResult) -> Result<ExecResult> {
+        if result.success() {
+            return Ok(result);
+        }
+        if result.stderr.contains("no 'java' command could be found")
+            || result
+                .stderr
+                .contains("JAVA_HOME is set to an invalid directory")
+        {
+            return Err(VqxError::JavaNotFound);
+        }
+        // "... this version of the Java Runtime only recognizes class file
+        // versions up to 52.0": class file version 52 is Java 8
+        if result.stderr.contains("UnsupportedClassVersionError") {
+            let found = result
+                .stderr
+                .split("class file versions up to ")
+                .nth(1)
+                .and_then(|rest| rest.split('.').next())
+                .and_then(|major| major.trim().parse::<u32>().ok())
+                .map_or_else(
+                    || "older than 11".to_string(),
+                    |major| major.saturating_sub(44).to_string(),
+                );
+            return Err(VqxError::JavaVersionUnsupported { found });
+        }
+        Ok(result)
+    }
+
+    /// Turn a rejected login into an error, so commands stop with the
+    /// authentication exit code instead of reporting a generic failure
+    fn check_auth(result: ExecResult) -> Result<ExecResult> {
+        if !result.is_auth_failure() {
+            return Ok(result);
+        }
+        let message = result
+            .stderr
+            .lines()
+            .chain(result.stdout.lines())
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("server rejected the credentials")
+            .to_string();
+        Err(VqxError::AuthenticationFailed { message })
+    }
+
+    /// Arguments as given, plus a lossy UTF-8 copy for policy checks and logs
+    ///
+    /// Paths are passed on as they are, so directories with non-UTF-8 names
+    /// reach the CLI intact.
+    fn collect_args<I, S>(args: I) -> (Vec<OsString>, Vec<String>)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let raw: Vec<OsString> = args.into_iter().map(|s| s.as_ref().to_owned()).collect();
+        let lossy = raw
+            .iter()
+            .map(|s| s.to_string_lossy().to_string())
+            .collect();
+        (raw, lossy)
+    }
+
+    /// `[options] [command] [command_args]`
+    fn full_args(
+        options: &CliOptions,
+        command: &str,
+        command_args: Vec<OsString>,
+    ) -> Vec<OsString> {
+        let mut full_args: Vec<OsString> =
+            options.to_args().into_iter().map(OsString::from).collect();
+        full_args.push(command.into());
+        full_args.extend(command_args);
+        full_args
+    }
+
+    /// Command that starts the CLI
+    ///
+    /// Batch files such as `vantiq.bat` on Windows are started directly as
+    /// well: std runs them through `cmd.exe` itself and quotes the arguments
+    /// for it, so passwords, tokens and paths with `&`, `|`, `^`, `%` or `"`
+    /// reach the CLI unchanged instead of being interpreted by `cmd.exe`.
+    ///
+    /// With `warm_jvm`, the JVM shares the class data sharing archive.
+    fn command(&self) -> Command {
+        let mut cmd = Command::new(&self.cli_path);
+        if let Some(archive) = CLASS_ARCHIVE.get() {
+            cmd.env("JAVA_OPTS", java_opts(archive));
+        }
+        cmd
     }
 
     /// Execute CLI with raw arguments (no option processing)
@@ -351,17 +832,23 @@ impl UnderlyingCli {
         I: IntoIterator<Item = S>,
         S: AsRef<OsStr>,
     {
-        let args: Vec<String> = args
-            .into_iter()
-            .map(|s| s.as_ref().to_string_lossy().to_string())
-            .collect();
+        let args: Vec<OsString> = args.into_iter().map(|s| s.as_ref().to_owned()).collect();
+        let command = args.first().map(|a| a.to_string_lossy());
+        crate::offline::check(command.as_deref().unwrap_or("vantiq"))?;
+
+        self.spawn_raw(&args).await
+    }
 
+    /// Spawn the CLI with raw arguments
+    async fn spawn_raw<S: AsRef<OsStr> + std::fmt::Debug>(&self, args: &[S]) -> Result<ExecResult> {
         debug!(cli = %self.cli_path, args = ?args, "Executing raw CLI command");
 
-        let mut cmd = Command::new(&self.cli_path);
-        cmd.args(&args)
+        let mut cmd = self.command();
+        cmd.args(args)
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .stderr(Stdio::piped())
+            // Terminate the CLI if the timeout below drops the future
+            .kill_on_drop(true);
 
         let result = timeout(self.timeout, cmd.output()).await;
 
@@ -389,19 +876,15 @@ impl UnderlyingCli {
                     status: output.status,
                     stdout,
                     stderr,
+                    retries: Vec::new(),
                 })
             }
             Ok(Err(e)) => {
                 warn!(error = %e, "Failed to spawn CLI process");
-                Err(VqxError::CliSpawnFailed {
-                    message: e.to_string(),
-                })
+                Err(self.spawn_error(e))
             }
             Err(_) => {
-                warn!(
-                    timeout_secs = self.timeout.as_secs(),
-                    "CLI command timed out"
-                );
+                warn!(timeout_secs = self.timeout.as_secs(), "{}", TIMEOUT_WARNING);
                 Err(VqxError::CliTimeout {
                     seconds: self.timeout.as_secs(),
                 })
@@ -409,52 +892,192 @@ impl UnderlyingCli {
         }
     }
 
-    /// Execute with retry and exponential backoff
-    pub async fn execute_with_retry<I, S>(
+    /// Execute a CLI command while streaming its output
+    ///
+    /// Unlike `execute`, output is delivered line by line as it arrives, and
+    /// periods of silence are reported via heartbeat and stall events. The
+    /// handler may abort the process, in which case `CliStalled` is returned.
+    /// The overall timeout still applies to each attempt. Transient failures
+    /// are retried like in `execute`; the handler then sees the output of
+    /// the next attempt after that of the failed one.
+    pub async fn execute_streaming<I, S, F>(
         &self,
         options: &CliOptions,
         command: &str,
         args: I,
+        policy: StallPolicy,
+        mut handler: F,
     ) -> Result<ExecResult>
     where
-        I: IntoIterator<Item = S> + Clone,
-        S: AsRef<OsStr> + Clone,
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+        F: FnMut(StreamEvent<'_>) -> StreamControl,
     {
-        let mut last_error = None;
-        let args_vec: Vec<S> = args.into_iter().collect();
-
-        for attempt in 0..=self.max_retries {
-            if attempt > 0 {
-                let delay = self.retry_base_delay_ms * 2u64.pow(attempt - 1);
-                info!(attempt, delay_ms = delay, "Retrying CLI command");
-                tokio::time::sleep(Duration::from_millis(delay)).await;
+        options.validate()?;
+
+        let (raw_args, command_args) = Self::collect_args(args);
+        crate::offline::check(command)?;
+        crate::policy::check(options.profile.as_deref(), command, &command_args)?;
+
+        let (options, _profile_file) = options.with_profile_file()?;
+        let full_args = Self::full_args(&options, command, raw_args);
+
+        info!(
+            cli = %self.cli_path,
+            command = %command,
+            options = ?options.to_masked_args(),
+            args = ?command_args,
+            "Executing CLI command (streaming)"
+        );
+        let _step = crate::summary::step(command);
+
+        let mut retries = Vec::new();
+        loop {
+            let outcome = self.stream_once(&full_args, policy, &mut handler).await;
+            match self.retry_delay(command, &outcome, &mut retries) {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Self::with_history(command, outcome, retries),
             }
+        }
+    }
 
-            match self.execute(options, command, args_vec.clone()).await {
-                Ok(result) if result.success() => return Ok(result),
-                Ok(result) => {
-                    // Command executed but returned non-zero
-                    // Don't retry for logical errors, only for transient failures
-                    if Self::is_transient_error(&result) {
-                        last_error = Some(VqxError::CliExecutionFailed {
-                            code: result.code(),
-                            message: result.stderr.clone(),
-                        });
-                        continue;
+    /// Run the CLI once, streaming its output to `handler`
+    async fn stream_once<F>(
+        &self,
+        full_args: &[OsString],
+        policy: StallPolicy,
+        handler: &mut F,
+    ) -> Result<ExecResult>
+    where
+        F: FnMut(StreamEvent<'_>) -> StreamControl,
+    {
+        let mut child = self
+            .command()
+            .args(full_args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| self.spawn_error(e))?;
+
+        let mut stdout_lines = BufReader::new(child.stdout.take().expect("piped stdout")).lines();
+        let mut stderr_lines = BufReader::new(child.stderr.take().expect("piped stderr")).lines();
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        let mut stdout_open = true;
+        let mut stderr_open = true;
+
+        let started = Instant::now();
+        let mut last_output = started;
+        let mut last_event = started;
+        // Idle duration at which the next stall is reported; re-armed on output
+        let mut next_stall = policy.stall_after;
+        let mut ticker = tokio::time::interval(
+            policy
+                .heartbeat
+                .min(policy.stall_after)
+                .min(Duration::from_secs(1))
+                .max(Duration::from_millis(100)),
+        );
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let status = loop {
+            tokio::select! {
+                line = stdout_lines.next_line(), if stdout_open => match line {
+                    Ok(Some(line)) => {
+                        last_output = Instant::now();
+                        last_event = last_output;
+                        next_stall = policy.stall_after;
+                        let control = handler(StreamEvent::Line { text: &line, stderr: false });
+                        stdout.push_str(&line);
+                        stdout.push('\n');
+                        if control != StreamControl::Continue {
+                            break Err(control);
+                        }
+                    }
+                    _ => stdout_open = false,
+                },
+                line = stderr_lines.next_line(), if stderr_open => match line {
+                    Ok(Some(line)) => {
+                        last_output = Instant::now();
+                        last_event = last_output;
+                        next_stall = policy.stall_after;
+                        let control = handler(StreamEvent::Line { text: &line, stderr: true });
+                        stderr.push_str(&line);
+                        stderr.push('\n');
+                        if control != StreamControl::Continue {
+                            break Err(control);
+                        }
                     }
-                    return Ok(result);
+                    _ => stderr_open = false,
+                },
+                status = child.wait(), if !stdout_open && !stderr_open => {
+                    break Ok(status.map_err(|e| VqxError::CliSpawnFailed {
+                        message: e.to_string(),
+                    })?);
                 }
-                Err(e) => {
-                    if Self::is_retryable_error(&e) {
-                        last_error = Some(e);
-                        continue;
+                _ = ticker.tick() => {
+                    let now = Instant::now();
+                    let elapsed = now - started;
+                    let idle = now - last_output;
+
+                    if elapsed >= self.timeout {
+                        let _ = child.kill().await;
+                        warn!(timeout_secs = self.timeout.as_secs(), "{}", TIMEOUT_WARNING);
+                        return Err(VqxError::CliTimeout {
+                            seconds: self.timeout.as_secs(),
+                        });
+                    }
+
+                    let control = if idle >= next_stall {
+                        next_stall += policy.stall_after;
+                        last_event = now;
+                        warn!(elapsed_secs = elapsed.as_secs(), idle_secs = idle.as_secs(), "CLI command appears stalled");
+                        handler(StreamEvent::Stall { elapsed, idle })
+                    } else if now - last_event >= policy.heartbeat {
+                        last_event = now;
+                        info!(elapsed_secs = elapsed.as_secs(), idle_secs = idle.as_secs(), "CLI command still running");
+                        handler(StreamEvent::Heartbeat { elapsed, idle })
+                    } else {
+                        StreamControl::Continue
+                    };
+
+                    if control != StreamControl::Continue {
+                        break Err(control);
                     }
-                    return Err(e);
                 }
             }
-        }
+        };
 
-        Err(last_error.unwrap_or_else(|| VqxError::Other("Max retries exceeded".to_string())))
+        match status {
+            Ok(status) => {
+                if !status.success() {
+                    warn!(code = status.code(), stderr = %stderr, "CLI command failed");
+                }
+                Ok(ExecResult {
+                    status,
+                    stdout,
+                    stderr,
+                    retries: Vec::new(),
+                })
+            }
+            Err(StreamControl::Stop) => {
+                let _ = child.kill().await;
+                debug!("CLI command stopped after enough output");
+                Ok(ExecResult {
+                    status: exit_status(0),
+                    stdout,
+                    stderr,
+                    retries: Vec::new(),
+                })
+            }
+            Err(_) => {
+                let _ = child.kill().await;
+                let idle = last_output.elapsed().as_secs();
+                warn!(idle_secs = idle, "CLI command aborted");
+                Err(VqxError::CliStalled { seconds: idle })
+            }
+        }
     }
 
     /// Check if an error is retryable
@@ -508,8 +1131,36 @@ impl UnderlyingCli {
         self.execute(options, "find", [resource, resource_id]).await
     }
 
+    /// Execute `dump` command
+    /// PDF: "dump <resource> <resourceId> <fileName>"
+    pub async fn dump(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        resource_id: &str,
+        file: &str,
+    ) -> Result<ExecResult> {
+        self.execute(options, "dump", [resource, resource_id, file])
+            .await
+    }
+
+    /// Execute `load` command
+    /// PDF: "load <resource> <fileName>"
+    pub async fn load(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        file: &str,
+    ) -> Result<ExecResult> {
+        self.execute(options, "load", [resource, file]).await
+    }
+
     /// Execute `select` command
     /// PDF: "The select command is a convenience to allow you to retrieve data from the Vantiq database"
+    ///
+    /// The CLI has no limit option, so with `limit` the chunk size is capped
+    /// at it and the CLI is stopped once the chunks read hold enough records.
+    #[allow(clippy::too_many_arguments)]
     pub async fn select(
         &self,
         options: &CliOptions,
@@ -518,7 +1169,12 @@ impl UnderlyingCli {
         qual_file: Option<&str>,
         props: Option<&str>,
         chunk_size: Option<u32>,
+        limit: Option<usize>,
     ) -> Result<ExecResult> {
+        let chunk_size = match (chunk_size, limit) {
+            (Some(size), Some(limit)) => Some(size.min(u32::try_from(limit).unwrap_or(u32::MAX))),
+            (size, _) => size,
+        };
         let mut args = vec![resource.to_string()];
 
         if let Some(id) = resource_id {
@@ -543,127 +1199,217 @@ impl UnderlyingCli {
             args.push(size.to_string());
         }
 
-        self.execute(options, "select", args).await
+        let Some(limit) = limit else {
+            return self.execute(options, "select", args).await;
+        };
+        // Only the closing line of a chunk (or a one-line chunk) starts at
+        // the first column, so the output read so far is parsed rarely
+        let mut output = String::new();
+        let policy = StallPolicy {
+            heartbeat: self.timeout,
+            stall_after: self.timeout,
+        };
+        self.execute_streaming(options, "select", args, policy, |event| match event {
+            StreamEvent::Line {
+                text,
+                stderr: false,
+            } => {
+                output.push_str(text);
+                output.push('\n');
+                if !text.starts_with(char::is_whitespace) && count_records(&output) >= limit {
+                    StreamControl::Stop
+                } else {
+                    StreamControl::Continue
+                }
+            }
+            _ => StreamControl::Continue,
+        })
+        .await
+    }
+
+    /// Execute `insert` or `checkedInsert` command
+    /// PDF: "insert <resource> <fileName>"
+    pub async fn insert(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        file: &str,
+        checked: bool,
+    ) -> Result<ExecResult> {
+        let command = if checked { "checkedInsert" } else { "insert" };
+        self.execute(options, command, [resource, file]).await
+    }
+
+    /// Execute `upsert` or `checkedUpsert` command
+    /// PDF: "upsert <resource> <fileName>"
+    pub async fn upsert(
+        &self,
+        options: &CliOptions,
+        resource: &str,
+        file: &str,
+        checked: bool,
+    ) -> Result<ExecResult> {
+        let command = if checked { "checkedUpsert" } else { "upsert" };
+        self.execute(options, command, [resource, file]).await
     }
 
     /// Execute `export` command
     /// PDF: "The export command writes either the resource meta-data or data stored in user defined types"
+    #[allow(clippy::too_many_arguments)]
     pub async fn export(
         &self,
         options: &CliOptions,
         export_type: Option<&str>, // "data", "metadata", "project <name>", "projectdata <name>", "hidden"
-        directory: Option<&str>,
+        directory: Option<&Path>,
         chunk_size: Option<u32>,
         include: Option<&[&str]>,
         exclude: Option<&[&str]>,
         until: Option<&str>,
         ignore_errors: bool,
     ) -> Result<ExecResult> {
-        let mut args: Vec<String> = Vec::new();
+        let args = Self::export_args(
+            export_type,
+            directory,
+            chunk_size,
+            include,
+            exclude,
+            until,
+            ignore_errors,
+        );
+        self.execute(options, "export", args).await
+    }
+
+    /// Build the argument list for `export`
+    /// PDF: "export [type] [-d <directory>] [-chunk <size>] [-include <type>] [-exclude <type>] [-until <DateTime>] [-ignoreErrors]"
+    pub fn export_args(
+        export_type: Option<&str>,
+        directory: Option<&Path>,
+        chunk_size: Option<u32>,
+        include: Option<&[&str]>,
+        exclude: Option<&[&str]>,
+        until: Option<&str>,
+        ignore_errors: bool,
+    ) -> Vec<OsString> {
+        let mut args: Vec<OsString> = Vec::new();
 
         // Export type
         if let Some(t) = export_type {
-            args.extend(t.split_whitespace().map(String::from));
+            args.extend(t.split_whitespace().map(OsString::from));
         }
 
         // -d <directory>
         if let Some(dir) = directory {
-            args.push("-d".to_string());
-            args.push(dir.to_string());
+            args.push("-d".into());
+            args.push(dir.into());
         }
 
         // -chunk <size>
         if let Some(size) = chunk_size {
-            args.push("-chunk".to_string());
-            args.push(size.to_string());
+            args.push("-chunk".into());
+            args.push(size.to_string().into());
         }
 
         // -include <typeName>
         if let Some(includes) = include {
             for inc in includes {
-                args.push("-include".to_string());
-                args.push(inc.to_string());
+                args.push("-include".into());
+                args.push(inc.into());
             }
         }
 
         // -exclude <typeName>
         if let Some(excludes) = exclude {
             for exc in excludes {
-                args.push("-exclude".to_string());
-                args.push(exc.to_string());
+                args.push("-exclude".into());
+                args.push(exc.into());
             }
         }
 
         // -until <DateTime>
         if let Some(u) = until {
-            args.push("-until".to_string());
-            args.push(u.to_string());
+            args.push("-until".into());
+            args.push(u.into());
         }
 
         // -ignoreErrors
         if ignore_errors {
-            args.push("-ignoreErrors".to_string());
+            args.push("-ignoreErrors".into());
         }
 
-        self.execute(options, "export", args).await
+        args
     }
 
     /// Execute `import` command
     /// PDF: "The import command reads all artifact definitions stored in a directory"
+    #[allow(clippy::too_many_arguments)]
     pub async fn import(
         &self,
         options: &CliOptions,
         import_type: Option<&str>, // "data" or "metadata"
-        directory: Option<&str>,
+        directory: Option<&Path>,
         chunk_size: Option<u32>,
         include: Option<&[&str]>,
         exclude: Option<&[&str]>,
         ignore: Option<&[&str]>,
     ) -> Result<ExecResult> {
-        let mut args: Vec<String> = Vec::new();
+        let args = Self::import_args(import_type, directory, chunk_size, include, exclude, ignore);
+        self.execute(options, "import", args).await
+    }
+
+    /// Build the argument list for `import`
+    /// PDF: "import [data | metadata] [-d <directory>] [-chunk <size>] [-include <type>] [-exclude <type>] [-ignore <resourceType>]"
+    pub fn import_args(
+        import_type: Option<&str>,
+        directory: Option<&Path>,
+        chunk_size: Option<u32>,
+        include: Option<&[&str]>,
+        exclude: Option<&[&str]>,
+        ignore: Option<&[&str]>,
+    ) -> Vec<OsString> {
+        let mut args: Vec<OsString> = Vec::new();
 
         // Import type
         if let Some(t) = import_type {
-            args.push(t.to_string());
+            args.push(t.into());
         }
 
         // -d <directory>
         if let Some(dir) = directory {
-            args.push("-d".to_string());
-            args.push(dir.to_string());
+            args.push("-d".into());
+            args.push(dir.into());
         }
 
         // -chunk <size>
         if let Some(size) = chunk_size {
-            args.push("-chunk".to_string());
-            args.push(size.to_string());
+            args.push("-chunk".into());
+            args.push(size.to_string().into());
         }
 
         // -include <typeName>
         if let Some(includes) = include {
             for inc in includes {
-                args.push("-include".to_string());
-                args.push(inc.to_string());
+                args.push("-include".into());
+                args.push(inc.into());
             }
         }
 
         // -exclude <typeName>
         if let Some(excludes) = exclude {
             for exc in excludes {
-                args.push("-exclude".to_string());
-                args.push(exc.to_string());
+                args.push("-exclude".into());
+                args.push(exc.into());
             }
         }
 
         // -ignore <resourceType>
         if let Some(ignores) = ignore {
             for ig in ignores {
-                args.push("-ignore".to_string());
-                args.push(ig.to_string());
+                args.push("-ignore".into());
+                args.push(ig.into());
             }
         }
 
-        self.execute(options, "import", args).await
+        args
     }
 
     /// Execute `delete` command
@@ -749,6 +1495,19 @@ impl UnderlyingCli {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_java_opts() {
+        let opts = java_opts(Path::new("/tmp/cli.jsa"));
+        assert!(opts.ends_with("-XX:SharedArchiveFile=/tmp/cli.jsa"));
+        assert!(opts.contains("-XX:+IgnoreUnrecognizedVMOptions"));
+    }
+
+    #[test]
+    fn test_exit_status() {
+        assert!(exit_status(0).success());
+        assert_eq!(exit_status(1).code(), Some(1));
+    }
+
     #[test]
     fn test_cli_options_to_args() {
         let opts = CliOptions {
@@ -796,6 +1555,82 @@ mod tests {
         assert!(matches!(opts.validate(), Err(VqxError::NamespaceWithToken)));
     }
 
+    #[test]
+    fn test_linked_profile_args() {
+        let profile = Profile {
+            url: "https://prod.vantiq.com".to_string(),
+            vantiq_profile: Some("prod".to_string()),
+            vantiq_profile_file: Some("/etc/vantiq/profile".to_string()),
+            ..Default::default()
+        };
+
+        let args = CliOptions::from_profile(&profile).to_args();
+        assert_eq!(args, vec!["-s", "prod", "-f", "/etc/vantiq/profile"]);
+    }
+
+    #[test]
+    fn test_client_options_profile_file() {
+        let mut profile = Profile::new("https://dev.vantiq.com").with_token("secret_token");
+        profile.client_options = Some(ClientOptions {
+            proxy: Some(crate::profile::ProxyOptions {
+                host: "proxy.corp".to_string(),
+                port: 8080,
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let (options, file) = CliOptions::from_profile(&profile)
+            .with_profile_file()
+            .unwrap();
+        let file = file.unwrap();
+        let args = options.to_args();
+        assert_eq!(args, vec!["-s", "vqx", "-f", file.path().to_str().unwrap()]);
+
+        let written =
+            vantiq_profile::parse(&std::fs::read_to_string(file.path()).unwrap()).unwrap();
+        assert_eq!(written[0].token.as_deref(), Some("secret_token"));
+        assert_eq!(written[0].client_options, profile.client_options);
+
+        // Nothing to pass without client options
+        let (_, file) = CliOptions::from_profile(&Profile::new("https://dev.vantiq.com"))
+            .with_profile_file()
+            .unwrap();
+        assert!(file.is_none());
+    }
+
+    #[test]
+    fn test_protected_profile_credentials_file() {
+        let mut profile = Profile::new("https://prod.vantiq.com").with_token("secret_token");
+        assert!(CliOptions::from_profile(&profile)
+            .with_profile_file()
+            .unwrap()
+            .1
+            .is_none());
+
+        profile.protection = crate::profile::Protection::Confirm;
+        let (options, file) = CliOptions::from_profile(&profile)
+            .with_profile_file()
+            .unwrap();
+        let args = options.to_args();
+        assert!(!args.contains(&"-t".to_string()));
+        assert!(!args.contains(&"secret_token".to_string()));
+
+        // The file goes away even when the caller panics
+        let path = file.as_ref().unwrap().path().to_path_buf();
+        assert!(path.exists());
+        let result = std::panic::catch_unwind(move || {
+            let _file = file;
+            panic!("CLI call failed");
+        });
+        assert!(result.is_err());
+        assert!(!path.exists());
+
+        profile.credential_passing = crate::profile::CredentialPassing::Args;
+        let args = CliOptions::from_profile(&profile).to_args();
+        assert!(args.contains(&"secret_token".to_string()));
+    }
+
     #[test]
     fn test_masked_args() {
         let opts = CliOptions {
@@ -811,4 +1646,362 @@ mod tests {
         assert!(!masked.contains(&"secret_password".to_string()));
         assert!(!masked.contains(&"secret_token".to_string()));
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_streaming_stall_abort() {
+        let cli = UnderlyingCli::new("sh".to_string());
+        let policy = StallPolicy {
+            heartbeat: Duration::from_millis(100),
+            stall_after: Duration::from_millis(300),
+        };
+
+        let mut lines = Vec::new();
+        let result = cli
+            .execute_streaming(
+                &CliOptions::default(),
+                "-c",
+                ["echo started; sleep 5"],
+                policy,
+                |event| match event {
+                    StreamEvent::Line { text, .. } => {
+                        lines.push(text.to_string());
+                        StreamControl::Continue
+                    }
+                    StreamEvent::Heartbeat { .. } => StreamControl::Continue,
+                    StreamEvent::Stall { .. } => StreamControl::Abort,
+                },
+            )
+            .await;
+
+        assert_eq!(lines, vec!["started"]);
+        assert!(matches!(result, Err(VqxError::CliStalled { .. })));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_streaming_collects_output() {
+        let cli = UnderlyingCli::new("sh".to_string());
+        let policy = StallPolicy {
+            heartbeat: Duration::from_secs(30),
+            stall_after: Duration::from_secs(60),
+        };
+
+        let result = cli
+            .execute_streaming(
+                &CliOptions::default(),
+                "-c",
+                ["echo out; echo err >&2"],
+                policy,
+                |_| StreamControl::Continue,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success());
+        assert_eq!(result.stdout, "out\n");
+        assert_eq!(result.stderr, "err\n");
+    }
+
+    #[test]
+    fn test_count_records() {
+        assert_eq!(count_records("[{\"a\":1},{\"a\":2}]\n{\"a\":3}\n"), 3);
+        // An incomplete chunk is not counted yet
+        assert_eq!(count_records("[{\"a\":1}]\n[\n  {\"a\":2},\n"), 1);
+        assert_eq!(count_records(""), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_select_limit_stops_the_cli() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+
+        // A CLI that prints two chunks of two records, then hangs
+        let script = dir.path().join("vantiq");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nprintf '[\\n  {\"a\": 1},\\n  {\"a\": 2}\\n]\\n'\n\
+             printf '[\\n  {\"a\": 3},\\n  {\"a\": 4}\\n]\\n'\nexec sleep 30\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let cli =
+            UnderlyingCli::new(script.display().to_string()).with_timeout(Duration::from_secs(20));
+        let started = Instant::now();
+        let result = cli
+            .select(
+                &CliOptions::default(),
+                "Order",
+                None,
+                None,
+                None,
+                None,
+                Some(3),
+            )
+            .await
+            .unwrap();
+        assert!(result.success());
+        assert_eq!(count_records(&result.stdout), 4);
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_metacharacters_reach_the_cli_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let args = [
+            r#"C:\exports\a&b|c^d%PATH%"e"#,
+            "pa$$ & echo INJECTED",
+            "<tok>%%",
+        ];
+
+        // A CLI that prints each argument on a line of its own
+        #[cfg(unix)]
+        let script = {
+            use std::os::unix::fs::PermissionsExt;
+            let script = dir.path().join("vantiq");
+            std::fs::write(
+                &script,
+                "#!/bin/sh\nfor a in \"$@\"; do printf '%s\\n' \"$a\"; done\n",
+            )
+            .unwrap();
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+            script
+        };
+        #[cfg(windows)]
+        let script = {
+            let script = dir.path().join("vantiq.bat");
+            std::fs::write(&script, "@echo off\r\necho %*\r\n").unwrap();
+            script
+        };
+
+        let cli = UnderlyingCli::new(script.display().to_string());
+        let result = cli
+            .execute_streaming(
+                &CliOptions::default(),
+                "export",
+                args,
+                StallPolicy {
+                    heartbeat: Duration::from_secs(30),
+                    stall_after: Duration::from_secs(60),
+                },
+                |_| StreamControl::Continue,
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success());
+        // cmd.exe must not run the text after `&` as a command of its own
+        assert!(!result.stdout.lines().any(|l| l.trim() == "INJECTED"));
+        #[cfg(unix)]
+        assert_eq!(
+            result.stdout.lines().collect::<Vec<_>>(),
+            [&["export"][..], &args[..]].concat()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_export_args_keep_non_utf8_paths() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = Path::new(OsStr::from_bytes(b"/tmp/my export/\xff"));
+        let args =
+            UnderlyingCli::export_args(Some("metadata"), Some(dir), None, None, None, None, false);
+        assert_eq!(
+            args,
+            vec![OsStr::new("metadata"), OsStr::new("-d"), dir.as_os_str()]
+        );
+    }
+
+    #[test]
+    fn test_command_class() {
+        assert_eq!(CommandClass::of("select"), CommandClass::Read);
+        assert_eq!(CommandClass::of("export"), CommandClass::Read);
+        assert_eq!(CommandClass::of("upsert"), CommandClass::Mutate);
+        assert_eq!(CommandClass::of("import"), CommandClass::Destructive);
+        assert_eq!(CommandClass::of("dump"), CommandClass::Read);
+        assert_eq!(CommandClass::of("load"), CommandClass::Destructive);
+        assert_eq!(
+            CommandClass::of("deleteMatching"),
+            CommandClass::Destructive
+        );
+
+        let policy = RetryPolicy {
+            mutate: Backoff {
+                max_retries: 2,
+                base_delay_ms: 100,
+            },
+            ..Default::default()
+        };
+        assert_eq!(policy.backoff(CommandClass::Destructive), Backoff::NONE);
+        assert_eq!(policy.backoff(CommandClass::Mutate).max_retries, 2);
+    }
+
+    #[test]
+    fn test_retry_delay_jitter() {
+        let policy = RetryPolicy::default();
+        let backoff = policy.read;
+        assert_eq!(policy.delay(backoff, 1, 0.0), Duration::from_millis(1000));
+        assert_eq!(policy.delay(backoff, 3, 0.0), Duration::from_millis(4000));
+        assert_eq!(policy.delay(backoff, 3, 0.5), Duration::from_millis(3000));
+
+        let random = random_unit();
+        assert!((0.0..1.0).contains(&random));
+    }
+
+    #[test]
+    fn test_check_java() {
+        let failed = |stderr: &str| ExecResult {
+            status: exit_status(1),
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+            retries: vec![],
+        };
+        let err = UnderlyingCli::check_java(failed(
+            "ERROR: JAVA_HOME is not set and no 'java' command could be found in your PATH.",
+        ))
+        .unwrap_err();
+        assert!(matches!(err, VqxError::JavaNotFound));
+
+        let err = UnderlyingCli::check_java(failed(
+            "Exception in thread \"main\" java.lang.UnsupportedClassVersionError: ... this version \
+             of the Java Runtime only recognizes class file versions up to 52.0",
+        ))
+        .unwrap_err();
+        assert!(matches!(err, VqxError::JavaVersionUnsupported { ref found } if found == "8"));
+
+        assert!(UnderlyingCli::check_java(failed("no such type")).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_spawn_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("vantiq");
+        let cli = UnderlyingCli::new(missing.display().to_string());
+        let err = cli
+            .execute(&CliOptions::default(), "help", Vec::<String>::new())
+            .await;
+        assert!(matches!(err, Err(VqxError::CliNotFound { .. })));
+
+        std::fs::write(&missing, "#!/bin/sh\n").unwrap();
+        let err = cli
+            .execute(&CliOptions::default(), "help", Vec::<String>::new())
+            .await;
+        assert!(matches!(err, Err(VqxError::CliNotExecutable { .. })));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_retries_transient_failures() {
+        let fast = Backoff {
+            max_retries: 2,
+            base_delay_ms: 1,
+        };
+        let cli = UnderlyingCli::new("sh".to_string()).with_retry_policy(RetryPolicy {
+            read: fast,
+            mutate: fast,
+            jitter: 0.5,
+        });
+
+        // "sh -c" classifies as a mutating command
+        let result = cli
+            .execute(
+                &CliOptions::default(),
+                "-c",
+                ["echo 'connection refused' >&2; exit 1"],
+            )
+            .await
+            .unwrap();
+        assert!(!result.success());
+        assert_eq!(result.retries.len(), 2);
+        assert_eq!(result.retries[1].attempt, 2);
+        assert!(result.retries[0].error.contains("connection refused"));
+
+        // Reports carry the history with the delay in milliseconds
+        let json = serde_json::to_value(&result.retries[0]).unwrap();
+        assert_eq!(json["attempt"], 1);
+        assert!(json["delay_ms"].is_u64());
+
+        // Logical errors are not retried
+        let result = cli
+            .execute(
+                &CliOptions::default(),
+                "-c",
+                ["echo 'no such type' >&2; exit 1"],
+            )
+            .await
+            .unwrap();
+        assert!(result.retries.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_streaming_retries_transient_failures() {
+        let fast = Backoff {
+            max_retries: 2,
+            base_delay_ms: 1,
+        };
+        let cli = UnderlyingCli::new("sh".to_string()).with_retry_policy(RetryPolicy {
+            read: fast,
+            mutate: fast,
+            jitter: 0.5,
+        });
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("failed-once");
+        // Fails with a network error the first time, then succeeds
+        let script = format!(
+            "if [ -e '{0}' ]; then echo exported; else touch '{0}'; echo 'connection reset' >&2; exit 1; fi",
+            marker.display()
+        );
+
+        let mut lines = Vec::new();
+        let result = cli
+            .execute_streaming(
+                &CliOptions::default(),
+                "-c",
+                [script],
+                StallPolicy {
+                    heartbeat: Duration::from_secs(30),
+                    stall_after: Duration::from_secs(60),
+                },
+                |event| {
+                    if let StreamEvent::Line { text, .. } = event {
+                        lines.push(text.to_string());
+                    }
+                    StreamControl::Continue
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(result.success());
+        assert_eq!(result.stdout, "exported\n");
+        assert_eq!(result.retries.len(), 1);
+        assert_eq!(lines, ["connection reset", "exported"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_auth_failure() {
+        let cli = UnderlyingCli::new("sh".to_string()).with_retry_policy(RetryPolicy {
+            read: Backoff::NONE,
+            mutate: Backoff::NONE,
+            jitter: 0.0,
+        });
+
+        let err = cli
+            .execute(
+                &CliOptions::default(),
+                "-c",
+                ["echo 'HTTP 401: Unauthorized' >&2; exit 1"],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            VqxError::AuthenticationFailed { ref message } if message == "HTTP 401: Unauthorized"
+        ));
+    }
 }
@@ -0,0 +1,316 @@
+//! Schema validation of exported resources
+//!
+//! Checks resource definitions against built-in schemas for the known
+//! resource types (required fields, field types, enum values) so broken
+//! hand-edits are caught before an import fails halfway through.
+//!
+//! The schemas only describe fields vqx knows about; unknown fields are
+//! allowed, since Vantiq adds fields between releases.
+
+use crate::commands::diff::RESOURCE_TYPES;
+use crate::normalizer::CODE_REF_KEY;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+
+/// JSON type expected for a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    String,
+    Bool,
+    Object,
+    Array,
+    /// VAIL code: a string, or a `{"$vail": "<file>"}` reference
+    Code,
+}
+
+impl FieldKind {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            FieldKind::String => value.is_string(),
+            FieldKind::Bool => value.is_boolean(),
+            FieldKind::Object => value.is_object(),
+            FieldKind::Array => value.is_array(),
+            FieldKind::Code => {
+                value.is_string() || value.get(CODE_REF_KEY).is_some_and(|v| v.is_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for FieldKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldKind::String => write!(f, "string"),
+            FieldKind::Bool => write!(f, "boolean"),
+            FieldKind::Object => write!(f, "object"),
+            FieldKind::Array => write!(f, "array"),
+            FieldKind::Code => write!(f, "VAIL code"),
+        }
+    }
+}
+
+/// Expected shape of one field
+#[derive(Debug)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub required: bool,
+    /// Allowed values of a string field; empty allows any
+    pub allowed: &'static [&'static str],
+}
+
+const fn field(name: &'static str, kind: FieldKind, required: bool) -> FieldSpec {
+    FieldSpec {
+        name,
+        kind,
+        required,
+        allowed: &[],
+    }
+}
+
+const NAME: FieldSpec = field("name", FieldKind::String, true);
+const DESCRIPTION: FieldSpec = field("description", FieldKind::String, false);
+const ACTIVE: FieldSpec = field("active", FieldKind::Bool, false);
+
+/// Data types of type properties
+pub const PROPERTY_TYPES: &[&str] = &[
+    "Any",
+    "Boolean",
+    "Currency",
+    "DateTime",
+    "Decimal",
+    "GeoJSON",
+    "Integer",
+    "Map",
+    "Object",
+    "Real",
+    "ResourceReference",
+    "String",
+    "Value",
+];
+
+/// Fields of each entry in a type's `properties`
+const TYPE_PROPERTY_FIELDS: &[FieldSpec] = &[
+    FieldSpec {
+        name: "type",
+        kind: FieldKind::String,
+        required: true,
+        allowed: PROPERTY_TYPES,
+    },
+    field("required", FieldKind::Bool, false),
+    field("multi", FieldKind::Bool, false),
+    field("indexed", FieldKind::Bool, false),
+    field("unique", FieldKind::Bool, false),
+    DESCRIPTION,
+];
+
+const TYPE_FIELDS: &[FieldSpec] = &[
+    NAME,
+    field("properties", FieldKind::Object, false),
+    field("indexes", FieldKind::Array, false),
+    field("naturalKey", FieldKind::Array, false),
+    DESCRIPTION,
+];
+
+const CODE_FIELDS: &[FieldSpec] = &[NAME, field("ruleText", FieldKind::Code, true), ACTIVE];
+
+const SOURCE_FIELDS: &[FieldSpec] = &[
+    NAME,
+    field("type", FieldKind::String, true),
+    field("config", FieldKind::Object, false),
+    ACTIVE,
+];
+
+const SERVICE_FIELDS: &[FieldSpec] = &[
+    NAME,
+    field("interface", FieldKind::Array, false),
+    DESCRIPTION,
+];
+
+const PROJECT_FIELDS: &[FieldSpec] = &[NAME, field("resources", FieldKind::Array, false)];
+
+const DEFAULT_FIELDS: &[FieldSpec] = &[NAME];
+
+/// Built-in schema of a resource type
+fn resource_fields(resource_type: &str) -> &'static [FieldSpec] {
+    match resource_type {
+        "types" => TYPE_FIELDS,
+        "procedures" | "rules" => CODE_FIELDS,
+        "sources" => SOURCE_FIELDS,
+        "services" => SERVICE_FIELDS,
+        "projects" => PROJECT_FIELDS,
+        _ => DEFAULT_FIELDS,
+    }
+}
+
+/// A schema violation in one resource file
+#[derive(Debug, Clone, Serialize)]
+pub struct Violation {
+    /// JSON path of the offending field, e.g. `/properties/total/type`
+    pub path: String,
+    /// 1-based line in the file (best effort for fields, exact for parse errors)
+    pub line: usize,
+    pub message: String,
+}
+
+/// Validate a resource file
+///
+/// Returns no violations for files of unknown resource types beyond
+/// checking that they parse.
+pub fn validate_file(path: &Path, resource_type: &str) -> std::io::Result<Vec<Violation>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(validate_str(&content, resource_type))
+}
+
+/// Validate the content of a resource file
+pub fn validate_str(content: &str, resource_type: &str) -> Vec<Violation> {
+    let value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => {
+            return vec![Violation {
+                path: String::new(),
+                line: e.line(),
+                message: format!("Invalid JSON: {}", e),
+            }]
+        }
+    };
+
+    if !RESOURCE_TYPES.contains(&resource_type) {
+        return Vec::new();
+    }
+
+    let mut violations = Vec::new();
+    let mut check = |path: &[&str], message: String| {
+        violations.push(Violation {
+            path: format!("/{}", path.join("/")),
+            line: locate(content, path),
+            message,
+        });
+    };
+
+    if !value.is_object() {
+        check(&[], "Resource definition must be a JSON object".to_string());
+        return violations;
+    }
+    check_fields(&value, resource_fields(resource_type), &[], &mut check);
+
+    // Type properties are keyed by property name
+    if resource_type == "types" {
+        if let Some(Value::Object(properties)) = value.get("properties") {
+            for (name, property) in properties {
+                let path = ["properties", name.as_str()];
+                if property.is_object() {
+                    check_fields(property, TYPE_PROPERTY_FIELDS, &path, &mut check);
+                } else {
+                    check(&path, "Property definition must be an object".to_string());
+                }
+            }
+        }
+    }
+
+    violations
+}
+
+/// Check the fields of one object against their specs
+fn check_fields(
+    value: &Value,
+    specs: &[FieldSpec],
+    parent: &[&str],
+    check: &mut impl FnMut(&[&str], String),
+) {
+    for spec in specs {
+        let path: Vec<&str> = parent.iter().copied().chain([spec.name]).collect();
+        match value.get(spec.name) {
+            None | Some(Value::Null) if spec.required => {
+                check(parent, format!("Missing required field '{}'", spec.name));
+            }
+            None | Some(Value::Null) => {}
+            Some(v) if !spec.kind.matches(v) => {
+                check(&path, format!("'{}' must be {}", spec.name, spec.kind));
+            }
+            Some(Value::String(s))
+                if !spec.allowed.is_empty() && !spec.allowed.contains(&s.as_str()) =>
+            {
+                check(
+                    &path,
+                    format!(
+                        "Invalid value '{}' for '{}'; expected one of: {}",
+                        s,
+                        spec.name,
+                        spec.allowed.join(", ")
+                    ),
+                );
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// Find the line of the field at `path` in pretty-printed or compact JSON
+///
+/// Searches for each key in turn after the previous one, which is exact for
+/// the usual one-key-per-line layout; falls back to the closest parent found.
+/// Array indexes in `path` are skipped.
+pub(crate) fn locate(content: &str, path: &[&str]) -> usize {
+    let mut offset = 0;
+    for key in path {
+        if key.parse::<usize>().is_ok() {
+            continue;
+        }
+        let needle = format!("\"{}\"", key);
+        match content[offset..].find(&needle) {
+            Some(pos) => offset += pos,
+            None => break,
+        }
+    }
+    content[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_type() {
+        let content = r#"{"name": "Order", "properties": {"total": {"type": "Real"}}}"#;
+        assert!(validate_str(content, "types").is_empty());
+    }
+
+    #[test]
+    fn test_type_violations_with_lines() {
+        let content = "{\n  \"properties\": {\n    \"total\": {\n      \"type\": \"Float\"\n    },\n    \"paid\": {\n      \"required\": \"yes\"\n    }\n  }\n}";
+        let violations = validate_str(content, "types");
+        let messages: Vec<_> = violations
+            .iter()
+            .map(|v| (v.path.as_str(), v.line))
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                ("/", 1),
+                ("/properties/paid", 6),
+                ("/properties/paid/required", 7),
+                ("/properties/total/type", 4),
+            ]
+        );
+        assert!(violations[0].message.contains("'name'"));
+        assert!(violations[3].message.contains("expected one of"));
+    }
+
+    #[test]
+    fn test_parse_error_and_code_reference() {
+        let violations = validate_str("{\n  \"name\": \"A\",\n}", "procedures");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].line, 3);
+
+        let extracted = r#"{"name": "A", "ruleText": {"$vail": "A.vail"}}"#;
+        assert!(validate_str(extracted, "procedures").is_empty());
+        assert_eq!(validate_str(r#"{"name": "A"}"#, "rules").len(), 1);
+
+        // Unknown directories only need to parse
+        assert!(validate_str(r#"[1, 2]"#, "data").is_empty());
+    }
+}
@@ -0,0 +1,481 @@
+//! The underlying CLI's profile file (vqx extension)
+//!
+//! The Vantiq CLI reads named profiles from `~/.vantiq/profile`, a Groovy
+//! ConfigSlurper file (see [`crate::profile::Profile`]):
+//!
+//! ```text
+//! base {
+//!     url = 'https://dev.vantiq.com'
+//!     username = 'myUsername'
+//!     password = 'myPassword'
+//! }
+//! prod.url = 'https://prod.vantiq.com'   // dotted keys work too
+//! prod.token = 'rTTbtHd8...'
+//! corp {
+//!     url = 'https://dev.vantiq.com'
+//!     clientOptions {                     // proxy and TLS settings
+//!         proxyOptions { host = 'proxy.corp'; port = 8080 }
+//!     }
+//! }
+//! ```
+//!
+//! `vqx profile import-vantiq` reads it into vqx profiles, either copying
+//! the credentials or linking to the CLI profile (`-s <name>`);
+//! `vqx profile export --format vantiq` writes vqx profiles in this format.
+
+use crate::error::{Result, VqxError};
+use crate::profile::{ClientOptions, Profile, ProxyOptions};
+use std::path::PathBuf;
+
+/// One profile of the CLI's profile file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VantiqProfile {
+    pub name: String,
+    pub url: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub token: Option<String>,
+    pub namespace: Option<String>,
+    pub client_options: Option<ClientOptions>,
+    /// Keys the CLI does not know, e.g. typos
+    pub unknown_keys: Vec<String>,
+}
+
+impl VantiqProfile {
+    /// The CLI profile for a vqx profile with resolved credentials
+    pub fn from_profile(name: &str, profile: &Profile) -> Self {
+        Self {
+            name: name.to_string(),
+            url: Some(profile.url.clone()),
+            username: profile.username.clone(),
+            password: profile.password.clone(),
+            token: profile.token.clone(),
+            namespace: profile.namespace.clone(),
+            client_options: profile.client_options.clone(),
+            unknown_keys: Vec::new(),
+        }
+    }
+
+    /// A vqx profile with the same URL, credentials, namespace and client options
+    pub fn to_profile(&self) -> Profile {
+        let mut profile = Profile::default();
+        if let Some(ref url) = self.url {
+            profile.url = url.clone();
+        }
+        profile.username = self.username.clone();
+        profile.password = self.password.clone();
+        profile.token = self.token.clone();
+        profile.namespace = self.namespace.clone();
+        profile.client_options = self.client_options.clone();
+        profile
+    }
+
+    fn set(&mut self, key: &str, value: String) {
+        if let Some(option) = key.strip_prefix("clientOptions.") {
+            let options = self.client_options.get_or_insert_with(Default::default);
+            if !set_client_option(options, option, &value) {
+                self.unknown_keys.push(key.to_string());
+            }
+            return;
+        }
+        let slot = match key {
+            "url" => &mut self.url,
+            "username" => &mut self.username,
+            "password" => &mut self.password,
+            "token" => &mut self.token,
+            "namespace" => &mut self.namespace,
+            _ => {
+                self.unknown_keys.push(key.to_string());
+                return;
+            }
+        };
+        *slot = Some(value);
+    }
+}
+
+/// Set a key of a `clientOptions` block; false for unknown keys and bad values
+fn set_client_option(options: &mut ClientOptions, key: &str, value: &str) -> bool {
+    if let Some(key) = key.strip_prefix("proxyOptions.") {
+        let proxy = options.proxy.get_or_insert_with(ProxyOptions::default);
+        match key {
+            "host" => proxy.host = value.to_string(),
+            "port" => match value.parse() {
+                Ok(port) => proxy.port = port,
+                Err(_) => return false,
+            },
+            "username" => proxy.username = Some(value.to_string()),
+            "password" => proxy.password = Some(value.to_string()),
+            _ => return false,
+        }
+        return true;
+    }
+    let slot = match key {
+        "trustAll" => &mut options.trust_all,
+        "verifyHost" => &mut options.verify_host,
+        "forceSni" => &mut options.force_sni,
+        _ => return false,
+    };
+    match value.parse() {
+        Ok(flag) => *slot = flag,
+        Err(_) => return false,
+    }
+    true
+}
+
+/// `~/.vantiq/profile`
+pub fn default_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vantiq").join("profile"))
+}
+
+/// Whether the CLI can read `name` as a profile block name
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Write profiles as a profile file, one block per profile
+///
+/// A profile without a secret gets a commented-out placeholder for it.
+pub fn render(profiles: &[VantiqProfile]) -> String {
+    let mut out = String::from("// Vantiq CLI profiles exported by vqx\n");
+    for profile in profiles {
+        out.push('\n');
+        out.push_str(&format!("{} {{\n", profile.name));
+        let fields = [
+            ("url", &profile.url),
+            ("username", &profile.username),
+            ("password", &profile.password),
+            ("token", &profile.token),
+            ("namespace", &profile.namespace),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                out.push_str(&format!("    {} = {}\n", key, quote(value)));
+            }
+        }
+        if let Some(ref options) = profile.client_options {
+            render_client_options(&mut out, options);
+        }
+        if profile.token.is_none() && profile.password.is_none() {
+            let key = if profile.username.is_some() {
+                "password"
+            } else {
+                "token"
+            };
+            out.push_str(&format!("    // {} = ''\n", key));
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+/// `clientOptions` block with the settings that differ from the defaults
+fn render_client_options(out: &mut String, options: &ClientOptions) {
+    out.push_str("    clientOptions {\n");
+    if options.trust_all {
+        out.push_str("        trustAll = true\n");
+    }
+    if !options.verify_host {
+        out.push_str("        verifyHost = false\n");
+    }
+    if options.force_sni {
+        out.push_str("        forceSni = true\n");
+    }
+    if let Some(ref proxy) = options.proxy {
+        out.push_str("        proxyOptions {\n");
+        out.push_str(&format!("            host = {}\n", quote(&proxy.host)));
+        out.push_str(&format!("            port = {}\n", proxy.port));
+        if let Some(ref username) = proxy.username {
+            out.push_str(&format!("            username = {}\n", quote(username)));
+        }
+        if let Some(ref password) = proxy.password {
+            out.push_str(&format!("            password = {}\n", quote(password)));
+        }
+        out.push_str("        }\n");
+    }
+    out.push_str("    }\n");
+}
+
+/// Single-quoted Groovy string
+fn quote(value: &str) -> String {
+    let mut quoted = String::from("'");
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '\'' => quoted.push_str("\\'"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Name, dotted key or bare value (`true`, `42`)
+    Word(String),
+    /// Quoted string, unescaped
+    Text(String),
+    Open,
+    Close,
+    Assign,
+}
+
+/// Parse the profile file; profiles keep the order of their first mention
+///
+/// Keys in nested blocks are joined with dots, e.g. `clientOptions.trustAll`.
+pub fn parse(content: &str) -> Result<Vec<VantiqProfile>> {
+    let tokens = tokenize(content)?;
+    let mut profiles: Vec<VantiqProfile> = Vec::new();
+    // Open blocks: the profile name, then nested blocks such as `clientOptions`
+    let mut blocks: Vec<&str> = Vec::new();
+    let mut i = 0;
+
+    let error = |line: usize, message: &str| VqxError::Other(format!("line {}: {}", line, message));
+
+    while i < tokens.len() {
+        let (ref token, line) = tokens[i];
+        match token {
+            Token::Close if !blocks.is_empty() => {
+                blocks.pop();
+                i += 1;
+            }
+            Token::Word(word) => {
+                let next = tokens.get(i + 1).map(|(t, _)| t);
+                match next {
+                    Some(Token::Open) => {
+                        if blocks.is_empty() {
+                            profile_index(&mut profiles, word);
+                        }
+                        blocks.push(word);
+                        i += 2;
+                    }
+                    Some(Token::Assign) => {
+                        let value = match tokens.get(i + 2) {
+                            Some((Token::Word(v) | Token::Text(v), _)) => v.clone(),
+                            _ => return Err(error(line, &format!("missing value for '{}'", word))),
+                        };
+                        let mut path = blocks.join(".");
+                        if !path.is_empty() {
+                            path.push('.');
+                        }
+                        path.push_str(word);
+                        let Some((name, key)) = path.split_once('.') else {
+                            return Err(error(
+                                line,
+                                &format!("'{}' is outside of a profile", word),
+                            ));
+                        };
+                        let index = profile_index(&mut profiles, name);
+                        profiles[index].set(key, value);
+                        i += 3;
+                    }
+                    _ => {
+                        return Err(error(
+                            line,
+                            &format!("expected '=' or '{{' after '{}'", word),
+                        ))
+                    }
+                }
+            }
+            _ => return Err(error(line, "unexpected token")),
+        }
+    }
+    if !blocks.is_empty() {
+        return Err(VqxError::Other("missing '}' at end of file".to_string()));
+    }
+    Ok(profiles)
+}
+
+fn profile_index(profiles: &mut Vec<VantiqProfile>, name: &str) -> usize {
+    match profiles.iter().position(|p| p.name == name) {
+        Some(index) => index,
+        None => {
+            profiles.push(VantiqProfile {
+                name: name.to_string(),
+                ..Default::default()
+            });
+            profiles.len() - 1
+        }
+    }
+}
+
+/// Split the file into tokens with their line numbers
+fn tokenize(content: &str) -> Result<Vec<(Token, usize)>> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() || c == ';' => {}
+            '#' => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            last = c;
+                        }
+                        None => {
+                            return Err(VqxError::Other(format!(
+                                "line {}: unterminated comment",
+                                line
+                            )))
+                        }
+                    }
+                }
+            }
+            '{' => tokens.push((Token::Open, line)),
+            '}' => tokens.push((Token::Close, line)),
+            '=' => tokens.push((Token::Assign, line)),
+            '\'' | '"' => {
+                let start = line;
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(c) => text.push(c),
+                            None => break,
+                        },
+                        Some(q) if q == c => break,
+                        Some(ch) => {
+                            if ch == '\n' {
+                                line += 1;
+                            }
+                            text.push(ch);
+                        }
+                        None => {
+                            return Err(VqxError::Other(format!(
+                                "line {}: unterminated string",
+                                start
+                            )))
+                        }
+                    }
+                }
+                tokens.push((Token::Text(text), start));
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '-' | '.') => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push((Token::Word(word), line));
+            }
+            c => {
+                return Err(VqxError::Other(format!(
+                    "line {}: unexpected '{}'",
+                    line, c
+                )))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() {
+        let content = r#"
+// Default profile
+base {
+    url = 'https://dev.vantiq.com'
+    username = 'me'
+    password = "it's \"secret\""
+}
+/* Production,
+   token only */
+prod {
+    url = 'https://prod.vantiq.com'; token = 'abc='
+    tokn = 'typo'
+}
+edge.url = 'https://edge.local'
+edge.namespace = 'plant1'
+"#;
+        let profiles = parse(content).unwrap();
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["base", "prod", "edge"]);
+
+        assert_eq!(profiles[0].username.as_deref(), Some("me"));
+        assert_eq!(profiles[0].password.as_deref(), Some("it's \"secret\""));
+        assert_eq!(profiles[1].token.as_deref(), Some("abc="));
+        assert_eq!(profiles[1].unknown_keys, vec!["tokn"]);
+        assert_eq!(profiles[2].namespace.as_deref(), Some("plant1"));
+
+        let profile = profiles[1].to_profile();
+        assert_eq!(profile.url, "https://prod.vantiq.com");
+        assert!(profile.has_auth());
+    }
+
+    #[test]
+    fn test_render_round_trip() {
+        let mut profile = Profile::new("https://dev.vantiq.com");
+        profile.username = Some("me".to_string());
+        profile.password = Some("it's a \\ secret".to_string());
+        profile.namespace = Some("team".to_string());
+        let prod = Profile::new("https://prod.vantiq.com");
+
+        let mut corp = Profile::new("https://dev.vantiq.com").with_token("t");
+        corp.client_options = Some(ClientOptions {
+            verify_host: false,
+            proxy: Some(ProxyOptions {
+                host: "proxy.corp".to_string(),
+                port: 8080,
+                username: Some("me".to_string()),
+                password: None,
+            }),
+            ..Default::default()
+        });
+
+        let profiles = vec![
+            VantiqProfile::from_profile("dev", &profile),
+            VantiqProfile::from_profile("prod", &prod),
+            VantiqProfile::from_profile("corp", &corp),
+        ];
+        let content = render(&profiles);
+        assert!(content.contains("    // token = ''\n"), "{}", content);
+        assert!(content.contains("            port = 8080\n"), "{}", content);
+        assert_eq!(parse(&content).unwrap(), profiles);
+
+        assert!(is_valid_name("dev_2"));
+        assert!(!is_valid_name("dev-eu"));
+        assert!(!is_valid_name("2dev"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("base {\n url = 'x'\n").is_err());
+        assert!(parse("url = 'x'").is_err());
+        let error = parse("base {\n url 'x'\n}").unwrap_err().to_string();
+        assert!(error.contains("line 2"), "{}", error);
+    }
+}